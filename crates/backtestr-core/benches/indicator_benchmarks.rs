@@ -163,6 +163,43 @@ fn bench_pipeline_update_all(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_warm_up(c: &mut Criterion) {
+    let bars = generate_bar_data(10_000);
+    let mut group = c.benchmark_group("warm_up_10k_bars_20_indicators");
+    group.sample_size(10);
+
+    let build = || {
+        let pipeline = IndicatorPipeline::new(1000);
+        for i in 0..10 {
+            pipeline.register_indicator(format!("SMA_{}", i), Box::new(SMA::new(10 + i)));
+            pipeline.register_indicator(format!("EMA_{}", i), Box::new(EMA::new(10 + i)));
+        }
+        pipeline
+    };
+
+    group.bench_function("update_all_per_bar", |b| {
+        b.iter(|| {
+            let pipeline = build();
+            for bar in &bars {
+                pipeline.update_all(bar, Timeframe::M1).unwrap();
+            }
+            black_box(pipeline.get_value("SMA_0", Timeframe::M1))
+        });
+    });
+
+    group.bench_function("warm_up_batch", |b| {
+        b.iter(|| {
+            let pipeline = build();
+            for name in pipeline.get_indicator_names() {
+                pipeline.warm_up(&name, Timeframe::M1, &bars).unwrap();
+            }
+            black_box(pipeline.get_value("SMA_0", Timeframe::M1))
+        });
+    });
+
+    group.finish();
+}
+
 fn bench_cache_retrieval(c: &mut Criterion) {
     let mut group = c.benchmark_group("cache_retrieval");
 
@@ -226,6 +263,7 @@ criterion_group!(
     benches,
     bench_individual_indicators,
     bench_pipeline_update_all,
+    bench_warm_up,
     bench_cache_retrieval,
     bench_memory_usage
 );
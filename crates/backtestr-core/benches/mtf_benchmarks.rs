@@ -96,6 +96,31 @@ fn bench_throughput(c: &mut Criterion) {
     group.finish();
 }
 
+/// A single-symbol stream through only M1 against every timeframe
+fn bench_active_timeframes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("active_timeframes");
+    group.measurement_time(Duration::from_secs(10));
+
+    let ticks = generate_ticks("EURUSD", 10_000);
+    group.throughput(Throughput::Elements(ticks.len() as u64));
+    for (name, timeframes) in [("m1_only", vec![Timeframe::M1]), ("all", Timeframe::all())] {
+        let config = MTFConfig {
+            enabled_timeframes: timeframes,
+            ..Default::default()
+        };
+        group.bench_with_input(BenchmarkId::new("ticks", name), &config, |b, config| {
+            b.iter(|| {
+                let manager = MTFStateManager::new(config.clone());
+                for tick in &ticks {
+                    manager.process_tick(black_box(tick)).unwrap();
+                }
+            });
+        });
+    }
+
+    group.finish();
+}
+
 fn bench_memory_usage(c: &mut Criterion) {
     let mut group = c.benchmark_group("memory");
 
@@ -164,6 +189,7 @@ criterion_group!(
     bench_tick_processing,
     bench_state_query,
     bench_throughput,
+    bench_active_timeframes,
     bench_memory_usage,
     bench_multi_symbol
 );
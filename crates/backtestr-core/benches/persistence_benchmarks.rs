@@ -88,6 +88,12 @@ fn bench_serialization(c: &mut Criterion) {
             engine_version: "1.0.0".to_string(),
         },
         checksum: 0,
+        checkpoint_id: "bench-checkpoint".to_string(),
+        base_checkpoint_id: None,
+        parent_checkpoint_id: None,
+        changed_states: Default::default(),
+        removed_symbols: Vec::new(),
+        positions: None,
     };
 
     c.bench_function("checkpoint_serialization", |b| {
@@ -1,9 +1,11 @@
 use crate::events::{BarCompletionEvent, EventBus};
+use crate::indicators::IndicatorPipeline;
 use backtestr_data::models::Bar;
-use backtestr_data::timeframe::Timeframe;
+use backtestr_data::timeframe::{Timeframe, WeekStart};
 use std::collections::HashMap;
+use std::sync::Arc;
 
-use super::{GapDetector, SessionManager, VolumeAggregator};
+use super::{GapDetector, GapFillPolicy, SessionManager, VolumeAggregator};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum AggregationMethod {
@@ -46,6 +48,8 @@ pub struct BarAggregator {
     volume_aggregator: VolumeAggregator,
     event_bus: EventBus,
     pending_bars: HashMap<Timeframe, Vec<Bar>>,
+    /// Notified when a daily session boundary passes
+    indicator_pipeline: Option<Arc<IndicatorPipeline>>,
 }
 
 impl BarAggregator {
@@ -77,6 +81,16 @@ impl BarAggregator {
             Timeframe::D1,
             AggregationRule::new(Timeframe::H4, Timeframe::D1, 6),
         );
+        // Calendar timeframes also close when a bar opens a new period,
+        // so these counts are only upper bounds
+        aggregation_rules.insert(
+            Timeframe::W1,
+            AggregationRule::new(Timeframe::D1, Timeframe::W1, 7),
+        );
+        aggregation_rules.insert(
+            Timeframe::MN1,
+            AggregationRule::new(Timeframe::W1, Timeframe::MN1, 5),
+        );
 
         Self {
             aggregation_rules,
@@ -85,9 +99,24 @@ impl BarAggregator {
             volume_aggregator: VolumeAggregator::new(),
             event_bus,
             pending_bars: HashMap::new(),
+            indicator_pipeline: None,
         }
     }
 
+    /// Open `W1` bars on `week_start` instead of Monday, for both period
+    /// rollover and session boundaries
+    pub fn with_week_start(mut self, week_start: WeekStart) -> Self {
+        self.session_manager.set_week_start(week_start);
+        self
+    }
+
+    /// Reset session-anchored indicators in `pipeline` at each daily
+    /// session boundary
+    pub fn with_indicator_pipeline(mut self, pipeline: Arc<IndicatorPipeline>) -> Self {
+        self.indicator_pipeline = Some(pipeline);
+        self
+    }
+
     pub fn add_rule(&mut self, timeframe: Timeframe, rule: AggregationRule) {
         self.aggregation_rules.insert(timeframe, rule);
     }
@@ -105,6 +134,17 @@ impl BarAggregator {
             .collect();
 
         for target_tf in target_timeframes {
+            if self.is_excluded_holiday(&bar, target_tf) {
+                continue;
+            }
+
+            // Weeks and months hold a varying number of source bars; a bar
+            // from the next period completes whatever is pending
+            if let Some(previous) = self.close_previous_period(&bar, target_tf) {
+                completed_bars.push(previous.clone());
+                events_to_publish.push(BarCompletionEvent::for_timeframe(target_tf, previous));
+            }
+
             // Add bar to pending bars for this timeframe
             self.pending_bars
                 .entry(target_tf)
@@ -116,16 +156,7 @@ impl BarAggregator {
             if let Some(aggregated) = self.try_aggregate_bars(pending, target_tf) {
                 completed_bars.push(aggregated.clone());
 
-                // Prepare completion event
-                let event = match target_tf {
-                    Timeframe::M5 => BarCompletionEvent::FiveMinuteBar(aggregated),
-                    Timeframe::M15 => BarCompletionEvent::FifteenMinuteBar(aggregated),
-                    Timeframe::H1 => BarCompletionEvent::HourBar(aggregated),
-                    Timeframe::H4 => BarCompletionEvent::FourHourBar(aggregated),
-                    Timeframe::D1 => BarCompletionEvent::DailyBar(aggregated),
-                    _ => BarCompletionEvent::MinuteBar(aggregated),
-                };
-                events_to_publish.push(event);
+                events_to_publish.push(BarCompletionEvent::for_timeframe(target_tf, aggregated));
 
                 // Clear pending bars after successful aggregation
                 self.pending_bars.get_mut(&target_tf).unwrap().clear();
@@ -137,14 +168,102 @@ impl BarAggregator {
             self.event_bus.publish(event);
         }
 
+        self.notify_session_boundary(&bar.symbol, bar.timestamp_end);
+
         completed_bars
     }
 
+    /// Daily and weekly bars leave out source bars from holiday sessions,
+    /// so the bar after a Monday holiday is Tuesday's and a week ends on
+    /// its last trading day. Bars are dated by the session they trade in,
+    /// not the local calendar date they open on.
+    fn is_excluded_holiday(&self, bar: &Bar, target_tf: Timeframe) -> bool {
+        matches!(target_tf, Timeframe::D1 | Timeframe::W1)
+            && self
+                .session_manager
+                .is_holiday_session(&bar.symbol, bar.timestamp_start)
+    }
+
+    /// Aggregate and clear pending bars of a calendar timeframe when `bar`
+    /// opens a later period
+    fn close_previous_period(&mut self, bar: &Bar, target_tf: Timeframe) -> Option<Bar> {
+        if !matches!(target_tf, Timeframe::W1 | Timeframe::MN1) {
+            return None;
+        }
+
+        let first = self.pending_bars.get(&target_tf)?.first()?;
+        if self.period_start(first, target_tf) == self.period_start(bar, target_tf) {
+            return None;
+        }
+
+        let pending = self.pending_bars.remove(&target_tf)?;
+        self.aggregate_standard(&pending, target_tf)
+    }
+
+    /// Start of the `target_tf` bar `bar` belongs to. Calendar timeframes
+    /// start at the week or month boundary of the bar's session date, even
+    /// when the first source bar opens later, so Monday's session counts
+    /// toward its week although it opens on Sunday evening; other
+    /// timeframes start with their first source bar.
+    fn period_start(&self, bar: &Bar, target_tf: Timeframe) -> i64 {
+        match target_tf {
+            Timeframe::W1 | Timeframe::MN1 => {
+                let session_start = self
+                    .session_manager
+                    .session_date(&bar.symbol, bar.timestamp_start)
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+                    .map_or(bar.timestamp_start, |start| {
+                        start.and_utc().timestamp_millis()
+                    });
+                target_tf.bar_start_timestamp_with(session_start, self.session_manager.week_start())
+            }
+            _ => bar.timestamp_start,
+        }
+    }
+
+    /// Whether `last_bar` ends at the close of a `target_tf` bar. Weeks
+    /// close with Friday's session and months with their last day's, in
+    /// the symbol's timezone and at any early close.
+    fn closes_period(&self, last_bar: &Bar, target_tf: Timeframe) -> bool {
+        let (symbol, timestamp) = (&last_bar.symbol, last_bar.timestamp_end);
+        match target_tf {
+            Timeframe::W1 => self.session_manager.is_weekly_boundary(symbol, timestamp),
+            Timeframe::MN1 => self.session_manager.is_monthly_boundary(symbol, timestamp),
+            _ => self
+                .session_manager
+                .is_session_boundary(symbol, target_tf, timestamp),
+        }
+    }
+
+    /// Tell the attached pipeline a new session opens after `timestamp`
+    fn notify_session_boundary(&self, symbol: &str, timestamp: i64) {
+        if let Some(pipeline) = &self.indicator_pipeline {
+            if self
+                .session_manager
+                .is_session_boundary(symbol, Timeframe::D1, timestamp)
+            {
+                pipeline.notify_session_boundary();
+            }
+        }
+    }
+
     pub fn aggregate_bars(
         &mut self,
         source_bars: &[Bar],
         target_timeframe: Timeframe,
     ) -> Option<Bar> {
+        let trading_bars: Vec<Bar>;
+        let source_bars = if matches!(target_timeframe, Timeframe::D1 | Timeframe::W1) {
+            trading_bars = source_bars
+                .iter()
+                .filter(|bar| !self.is_excluded_holiday(bar, target_timeframe))
+                .cloned()
+                .collect();
+            &trading_bars[..]
+        } else {
+            source_bars
+        };
+
         let rule = self.aggregation_rules.get(&target_timeframe)?;
 
         if source_bars.is_empty() {
@@ -155,10 +274,7 @@ impl BarAggregator {
         if source_bars.len() < rule.bars_per_aggregation {
             // Check if we hit a session boundary
             if let Some(last_bar) = source_bars.last() {
-                if self
-                    .session_manager
-                    .is_session_boundary(target_timeframe, last_bar.timestamp_end)
-                {
+                if self.closes_period(last_bar, target_timeframe) {
                     return Some(self.create_session_bar(source_bars, target_timeframe));
                 }
             }
@@ -188,10 +304,7 @@ impl BarAggregator {
 
         // Check for session boundary
         if let Some(last_bar) = pending_bars.last() {
-            if self
-                .session_manager
-                .is_session_boundary(target_timeframe, last_bar.timestamp_end)
-            {
+            if self.closes_period(last_bar, target_timeframe) {
                 return Some(self.create_session_bar(pending_bars, target_timeframe));
             }
         }
@@ -228,7 +341,7 @@ impl BarAggregator {
         let mut bar = Bar::new(
             first_bar.symbol.clone(),
             target_timeframe,
-            first_bar.timestamp_start,
+            self.period_start(first_bar, target_timeframe),
             last_bar.timestamp_end,
             open,
             high,
@@ -274,7 +387,7 @@ impl BarAggregator {
         let mut bar = Bar::new(
             first_bar.symbol.clone(),
             target_timeframe,
-            first_bar.timestamp_start,
+            self.period_start(first_bar, target_timeframe),
             last_bar.timestamp_end,
             first_bar.open,
             high,
@@ -298,9 +411,26 @@ impl BarAggregator {
         source_bars: &[Bar],
         target_timeframe: Timeframe,
     ) -> Option<Bar> {
-        // Handle aggregation when there's a gap in the data
-        // For now, we'll aggregate what we have
-        self.aggregate_standard(source_bars, target_timeframe)
+        if self.gap_detector.fill_policy() == GapFillPolicy::None {
+            return self.aggregate_standard(source_bars, target_timeframe);
+        }
+
+        // Inject synthetic bars for each gap before aggregating
+        let mut filled = Vec::with_capacity(source_bars.len());
+        for (i, bar) in source_bars.iter().enumerate() {
+            if i > 0 {
+                let prev_bar = &source_bars[i - 1];
+                if self.gap_detector.is_gap(prev_bar, bar) {
+                    filled.extend(
+                        self.gap_detector
+                            .fill_gap(prev_bar, bar, prev_bar.timeframe),
+                    );
+                }
+            }
+            filled.push(bar.clone());
+        }
+
+        self.aggregate_standard(&filled, target_timeframe)
     }
 
     pub fn force_close_bars(&mut self, timestamp: i64) -> Vec<Bar> {
@@ -311,24 +441,14 @@ impl BarAggregator {
 
         for timeframe in timeframes {
             let pending = self.pending_bars.get(&timeframe).unwrap();
-            if !pending.is_empty()
-                && self
-                    .session_manager
-                    .is_session_boundary(timeframe, timestamp)
-            {
+            if pending.first().is_some_and(|first| {
+                self.session_manager
+                    .is_session_boundary(&first.symbol, timeframe, timestamp)
+            }) {
                 if let Some(bar) = self.aggregate_standard(pending, timeframe) {
                     closed_bars.push(bar.clone());
 
-                    // Prepare completion event
-                    let event = match timeframe {
-                        Timeframe::M5 => BarCompletionEvent::FiveMinuteBar(bar),
-                        Timeframe::M15 => BarCompletionEvent::FifteenMinuteBar(bar),
-                        Timeframe::H1 => BarCompletionEvent::HourBar(bar),
-                        Timeframe::H4 => BarCompletionEvent::FourHourBar(bar),
-                        Timeframe::D1 => BarCompletionEvent::DailyBar(bar),
-                        _ => BarCompletionEvent::MinuteBar(bar),
-                    };
-                    events_to_publish.push(event);
+                    events_to_publish.push(BarCompletionEvent::for_timeframe(timeframe, bar));
 
                     self.pending_bars.get_mut(&timeframe).unwrap().clear();
                 }
@@ -347,6 +467,7 @@ impl BarAggregator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aggregation::{MarketHours, MarketSchedule};
     use chrono::Duration;
 
     fn create_test_bar(
@@ -418,6 +539,117 @@ mod tests {
         assert_eq!(bar.low, 1.0915);
     }
 
+    #[test]
+    fn test_daily_cascades_to_weekly_and_monthly() {
+        let session_manager = SessionManager::new();
+        let gap_detector = GapDetector::new(Duration::days(3));
+        let mut aggregator = BarAggregator::new(session_manager, gap_detector, EventBus::new());
+        let day = 86_400_000;
+        let monday = 1704067200000; // 2024-01-01
+
+        // Monday to Friday; the weekend has no daily bars
+        for i in 0..5 {
+            let bar = create_test_bar(
+                "EURUSD",
+                Timeframe::D1,
+                monday + i * day,
+                1.0900 + i as f64 * 0.001,
+                1.0950 + i as f64 * 0.001,
+                1.0850,
+                1.0910 + i as f64 * 0.001,
+            );
+            assert!(aggregator.process_bar(bar, Timeframe::D1).is_empty());
+        }
+
+        // The next Monday's bar completes the five-day week
+        let next_monday = create_test_bar(
+            "EURUSD",
+            Timeframe::D1,
+            monday + 7 * day,
+            1.1,
+            1.1,
+            1.1,
+            1.1,
+        );
+        let completed = aggregator.process_bar(next_monday, Timeframe::D1);
+        assert_eq!(completed.len(), 1);
+        let week = &completed[0];
+        assert_eq!(week.timeframe, Timeframe::W1);
+        assert_eq!(week.timestamp_start, monday);
+        assert_eq!(week.close, 1.0950);
+        assert_eq!(week.high, 1.0990);
+
+        // Four weeks opening in February 2024, then the first March week
+        let feb_5 = 1707091200000;
+        for i in 0..4 {
+            let bar = create_test_bar(
+                "EURUSD",
+                Timeframe::W1,
+                feb_5 + i * 7 * day,
+                1.08,
+                1.09 + i as f64 * 0.01,
+                1.07,
+                1.08,
+            );
+            assert!(aggregator.process_bar(bar, Timeframe::W1).is_empty());
+        }
+        let march = create_test_bar(
+            "EURUSD",
+            Timeframe::W1,
+            feb_5 + 28 * day,
+            1.1,
+            1.1,
+            1.1,
+            1.1,
+        );
+        let completed = aggregator.process_bar(march, Timeframe::W1);
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].timeframe, Timeframe::MN1);
+        // The month opens on the 1st, not with its first weekly bar
+        assert_eq!(completed[0].timestamp_start, 1706745600000);
+        assert_eq!(completed[0].high, 1.12);
+    }
+
+    #[test]
+    fn test_weeks_open_on_configured_day() {
+        let mut aggregator = BarAggregator::new(
+            SessionManager::new(),
+            GapDetector::new(Duration::days(3)),
+            EventBus::new(),
+        )
+        .with_week_start(WeekStart::Sunday);
+        let day = 86_400_000;
+        let sunday = 1703980800000; // 2023-12-31
+
+        // Sunday's bar opens the week instead of closing the previous one
+        for i in 0..6 {
+            let bar = create_test_bar(
+                "EURUSD",
+                Timeframe::D1,
+                sunday + i * day,
+                1.0,
+                1.1,
+                0.9,
+                1.0,
+            );
+            assert!(aggregator.process_bar(bar, Timeframe::D1).is_empty());
+        }
+
+        let next_sunday = create_test_bar(
+            "EURUSD",
+            Timeframe::D1,
+            sunday + 7 * day,
+            1.0,
+            1.0,
+            1.0,
+            1.0,
+        );
+        let completed = aggregator.process_bar(next_sunday, Timeframe::D1);
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].timestamp_start, sunday);
+        assert_eq!(completed[0].timestamp_end, sunday + 6 * day);
+    }
+
     #[test]
     fn test_insufficient_bars() {
         let session_manager = SessionManager::new();
@@ -446,4 +678,439 @@ mod tests {
         let aggregated = aggregator.aggregate_bars(&source_bars, Timeframe::M5);
         assert!(aggregated.is_none());
     }
+
+    #[test]
+    fn test_gap_aggregation_with_fill_policies() {
+        let base_timestamp = 1704067200000;
+        let mut source_bars = Vec::new();
+        for i in [0, 1, 2, 9, 10] {
+            source_bars.push(create_test_bar(
+                "EURUSD",
+                Timeframe::M1,
+                base_timestamp + i * 60_000,
+                1.0920,
+                1.0925,
+                1.0915,
+                1.0922,
+            ));
+        }
+        // Gap reopens well below the prior range
+        source_bars[3].open = 1.0900;
+        source_bars[3].low = 1.0898;
+
+        let aggregate = |policy: GapFillPolicy| {
+            let gap_detector = GapDetector::new(Duration::minutes(5)).with_fill_policy(policy);
+            let mut aggregator =
+                BarAggregator::new(SessionManager::new(), gap_detector, EventBus::new());
+            aggregator
+                .aggregate_bars(&source_bars, Timeframe::M5)
+                .unwrap()
+        };
+
+        // Synthetic bars lie between the previous close and the next open,
+        // so they never extend the aggregated range under any policy
+        let unfilled = aggregate(GapFillPolicy::None);
+        for policy in [GapFillPolicy::ForwardFill, GapFillPolicy::Interpolate] {
+            let filled = aggregate(policy);
+            assert_eq!(filled.open, 1.0920);
+            assert_eq!(filled.low, 1.0898);
+            assert_eq!(filled.close, unfilled.close);
+            assert_eq!(filled.timestamp_start, unfilled.timestamp_start);
+            assert_eq!(filled.timestamp_end, unfilled.timestamp_end);
+        }
+    }
+
+    #[test]
+    fn test_session_boundary_resets_vwap() {
+        use crate::indicators::{BarData, VWAP};
+
+        let pipeline = Arc::new(IndicatorPipeline::new(100));
+        pipeline.register_indicator("VWAP".to_string(), Box::new(VWAP::new(true)));
+        let mut aggregator = BarAggregator::new(
+            SessionManager::new(),
+            GapDetector::new(Duration::minutes(5)),
+            EventBus::new(),
+        )
+        .with_indicator_pipeline(pipeline.clone());
+
+        let bar_data = |bar: &Bar| BarData {
+            open: bar.open,
+            high: bar.high,
+            low: bar.low,
+            close: bar.close,
+            volume: 1000.0,
+            timestamp: bar.timestamp_start,
+        };
+
+        // 2024-01-01 16:58 and 16:59 Eastern; the second bar closes at the
+        // 17:00 session boundary, 22:00 UTC
+        let before_close = 1704146280000;
+        for (i, price) in [1.10, 1.20].iter().enumerate() {
+            let bar = create_test_bar(
+                "EURUSD",
+                Timeframe::M1,
+                before_close + i as i64 * 60_000,
+                *price,
+                *price,
+                *price,
+                *price,
+            );
+            pipeline.update_all(&bar_data(&bar), Timeframe::M1).unwrap();
+            aggregator.process_bar(bar, Timeframe::M1);
+        }
+
+        assert_eq!(pipeline.get_value("VWAP", Timeframe::M1), None);
+
+        let next = create_test_bar(
+            "EURUSD",
+            Timeframe::M1,
+            before_close + 120_000,
+            1.30,
+            1.30,
+            1.30,
+            1.30,
+        );
+        pipeline
+            .update_all(&bar_data(&next), Timeframe::M1)
+            .unwrap();
+        let value = pipeline.get_value("VWAP", Timeframe::M1).unwrap();
+        assert!((value - 1.30).abs() < 1e-9);
+    }
+
+    fn with_holiday(date: (i32, u32, u32)) -> SessionManager {
+        let mut schedule = MarketSchedule::new();
+        schedule.add_holiday(chrono::NaiveDate::from_ymd_opt(date.0, date.1, date.2).unwrap());
+        let mut session_manager = SessionManager::new();
+        session_manager.set_market_schedule(schedule);
+        // The daily bars here run from one UTC midnight to the next, so
+        // sessions do too
+        session_manager.add_market_hours(
+            "EURUSD".to_string(),
+            MarketHours {
+                timezone: chrono_tz::UTC,
+                open_time: chrono::NaiveTime::MIN,
+                close_time: chrono::NaiveTime::MIN,
+                ..MarketHours::forex("EURUSD")
+            },
+        );
+        session_manager
+    }
+
+    #[test]
+    fn test_holiday_excluded_from_daily_bars() {
+        // Monday 2024-01-15 is a holiday
+        let mut aggregator = BarAggregator::new(
+            with_holiday((2024, 1, 15)),
+            GapDetector::new(Duration::days(4)),
+            EventBus::new(),
+        );
+        let h4 = Timeframe::H4.duration_ms();
+        let friday = 1705017600000; // 2024-01-12
+        let monday = friday + 3 * 86_400_000;
+        let tuesday = monday + 86_400_000;
+
+        let mut daily = Vec::new();
+        for day in [friday, monday, tuesday] {
+            for i in 0..6 {
+                let price = if day == monday { 2.0 } else { 1.0 };
+                let bar = create_test_bar(
+                    "EURUSD",
+                    Timeframe::H4,
+                    day + i * h4,
+                    price,
+                    price,
+                    price,
+                    price,
+                );
+                daily.extend(
+                    aggregator
+                        .process_bar(bar, Timeframe::H4)
+                        .into_iter()
+                        .filter(|bar| bar.timeframe == Timeframe::D1),
+                );
+            }
+        }
+
+        // Friday's bar is followed directly by Tuesday's, untouched by Monday
+        assert_eq!(daily.len(), 2);
+        assert_eq!(daily[0].timestamp_start, friday);
+        assert_eq!(daily[1].timestamp_start, tuesday);
+        assert_eq!(daily[1].timestamp_end, tuesday + 86_400_000);
+        assert_eq!(daily[1].high, 1.0);
+    }
+
+    #[test]
+    fn test_weekly_bar_ends_on_last_trading_day() {
+        // Friday 2024-01-05 is a holiday
+        let mut aggregator = BarAggregator::new(
+            with_holiday((2024, 1, 5)),
+            GapDetector::new(Duration::days(4)),
+            EventBus::new(),
+        );
+        let day = 86_400_000;
+        let monday = 1704067200000; // 2024-01-01
+
+        let mut weekly = Vec::new();
+        for i in 0..5 {
+            let bar = create_test_bar(
+                "EURUSD",
+                Timeframe::D1,
+                monday + i * day,
+                1.09,
+                1.10 + i as f64 * 0.01,
+                1.08,
+                1.09 + i as f64 * 0.01,
+            );
+            let completed = aggregator.process_bar(bar, Timeframe::D1);
+            // Thursday's bar closes the week; the holiday adds nothing
+            assert_eq!(completed.is_empty(), i != 3);
+            weekly.extend(completed);
+        }
+
+        let week = &weekly[0];
+        assert_eq!(week.timestamp_start, monday);
+        assert_eq!(week.timestamp_end, monday + 4 * day);
+        assert!((week.close - 1.12).abs() < 1e-9);
+        assert!((week.high - 1.13).abs() < 1e-9);
+
+        let next_monday = create_test_bar(
+            "EURUSD",
+            Timeframe::D1,
+            monday + 7 * day,
+            1.1,
+            1.1,
+            1.1,
+            1.1,
+        );
+        assert!(aggregator
+            .process_bar(next_monday, Timeframe::D1)
+            .is_empty());
+
+        // Direct aggregation leaves the holiday out too, and closes the
+        // week at Thursday's close
+        let days: Vec<Bar> = (0..5)
+            .map(|i| {
+                let price = if i == 4 { 2.0 } else { 1.0 };
+                create_test_bar(
+                    "EURUSD",
+                    Timeframe::D1,
+                    monday + i * day,
+                    price,
+                    price,
+                    price,
+                    price,
+                )
+            })
+            .collect();
+        let week = aggregator.aggregate_bars(&days, Timeframe::W1).unwrap();
+        assert_eq!(week.high, 1.0);
+        assert_eq!(week.timestamp_end, monday + 4 * day);
+    }
+
+    #[test]
+    fn test_holiday_dated_by_session_in_symbol_timezone() {
+        // Monday 2024-01-15 is a holiday; EURUSD keeps its Eastern sessions,
+        // which open at 17:00, 22:00 UTC, the evening before
+        let mut schedule = MarketSchedule::new();
+        schedule.add_holiday(chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+        let mut session_manager = SessionManager::new();
+        session_manager.set_market_schedule(schedule);
+        let mut aggregator = BarAggregator::new(
+            session_manager,
+            GapDetector::new(Duration::days(4)),
+            EventBus::new(),
+        );
+        let h4 = Timeframe::H4.duration_ms();
+        let monday = 1705276800000; // 2024-01-15 00:00 UTC, Sunday 19:00 Eastern
+        let tuesday = monday + 86_400_000; // Monday 19:00 Eastern
+
+        let mut daily = Vec::new();
+        for day in [monday, tuesday] {
+            for i in 0..6 {
+                let price = if day == monday { 2.0 } else { 1.0 };
+                let bar = create_test_bar(
+                    "EURUSD",
+                    Timeframe::H4,
+                    day + i * h4,
+                    price,
+                    price,
+                    price,
+                    price,
+                );
+                daily.extend(
+                    aggregator
+                        .process_bar(bar, Timeframe::H4)
+                        .into_iter()
+                        .filter(|bar| bar.timeframe == Timeframe::D1),
+                );
+            }
+        }
+
+        // The bars opening Monday at UTC midnight trade in the holiday
+        // session and are left out; those opening Tuesday belong to
+        // Tuesday's session and are kept
+        assert_eq!(daily.len(), 1);
+        assert_eq!(daily[0].timestamp_start, tuesday);
+        assert_eq!(daily[0].high, 1.0);
+    }
+
+    #[test]
+    fn test_weekly_bar_closes_on_last_trading_day_session() {
+        // Friday 2024-01-05 is a holiday, so the week's last session is
+        // Thursday's, closing at 17:00 Eastern
+        let mut schedule = MarketSchedule::new();
+        schedule.add_holiday(chrono::NaiveDate::from_ymd_opt(2024, 1, 5).unwrap());
+        let mut session_manager = SessionManager::new();
+        session_manager.set_market_schedule(schedule);
+        let mut aggregator = BarAggregator::new(
+            session_manager,
+            GapDetector::new(Duration::days(4)),
+            EventBus::new(),
+        );
+        let day = 86_400_000;
+        let monday = 1704067200000; // 2024-01-01
+        let session_open = monday - 2 * 3_600_000; // Sunday 17:00 Eastern
+
+        let mut weekly = Vec::new();
+        for i in 0..4 {
+            let bar = Bar::new(
+                "EURUSD".to_string(),
+                Timeframe::D1,
+                session_open + i * day,
+                session_open + (i + 1) * day,
+                1.09,
+                1.10 + i as f64 * 0.01,
+                1.08,
+                1.09 + i as f64 * 0.01,
+            );
+            weekly.extend(aggregator.process_bar(bar, Timeframe::D1));
+        }
+
+        // Thursday's bar closes the week without waiting for next Monday
+        assert_eq!(weekly.len(), 1);
+        let week = &weekly[0];
+        assert_eq!(week.timeframe, Timeframe::W1);
+        assert_eq!(week.timestamp_start, monday);
+        assert_eq!(week.timestamp_end, session_open + 4 * day);
+        assert!((week.high - 1.13).abs() < 1e-9);
+        assert!((week.close - 1.12).abs() < 1e-9);
+
+        // The holiday's bar starts nothing new
+        let friday = Bar::new(
+            "EURUSD".to_string(),
+            Timeframe::D1,
+            session_open + 4 * day,
+            session_open + 5 * day,
+            2.0,
+            2.0,
+            2.0,
+            2.0,
+        );
+        assert!(aggregator.process_bar(friday, Timeframe::D1).is_empty());
+        let next_monday = Bar::new(
+            "EURUSD".to_string(),
+            Timeframe::D1,
+            session_open + 7 * day,
+            session_open + 8 * day,
+            1.1,
+            1.1,
+            1.1,
+            1.1,
+        );
+        assert!(aggregator
+            .process_bar(next_monday, Timeframe::D1)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_daily_bar_ends_at_early_close() {
+        // Wednesday 2024-12-24 closes at 15:00 Eastern, 20:00 UTC
+        let mut schedule = MarketSchedule::new();
+        schedule.add_early_close(
+            chrono::NaiveDate::from_ymd_opt(2024, 12, 24).unwrap(),
+            chrono::NaiveTime::from_hms_opt(15, 0, 0).unwrap(),
+        );
+        let mut session_manager = SessionManager::new();
+        session_manager.set_market_schedule(schedule);
+        let mut aggregator = BarAggregator::new(
+            session_manager,
+            GapDetector::new(Duration::days(2)),
+            EventBus::new(),
+        );
+
+        let h4 = Timeframe::H4.duration_ms();
+        let christmas_eve = 1734998400000; // 2024-12-24
+        let mut daily = Vec::new();
+        for i in 0..5 {
+            let bar = create_test_bar(
+                "EURUSD",
+                Timeframe::H4,
+                christmas_eve + i * h4,
+                1.04,
+                1.05,
+                1.03,
+                1.04,
+            );
+            daily.extend(
+                aggregator
+                    .process_bar(bar, Timeframe::H4)
+                    .into_iter()
+                    .filter(|bar| bar.timeframe == Timeframe::D1),
+            );
+        }
+
+        // Five four-hour bars, closed at the early close instead of waiting
+        // for a sixth
+        assert_eq!(daily.len(), 1);
+        assert_eq!(daily[0].timestamp_start, christmas_eve);
+        assert_eq!(daily[0].timestamp_end, christmas_eve + 20 * 3_600_000);
+    }
+
+    #[test]
+    fn test_weekly_bar_closes_at_friday_early_close() {
+        // Friday 2024-11-29 closes at 13:00 Eastern, 18:00 UTC
+        let mut schedule = MarketSchedule::new();
+        schedule.add_early_close(
+            chrono::NaiveDate::from_ymd_opt(2024, 11, 29).unwrap(),
+            chrono::NaiveTime::from_hms_opt(13, 0, 0).unwrap(),
+        );
+        let mut session_manager = SessionManager::new();
+        session_manager.set_market_schedule(schedule);
+        let mut aggregator = BarAggregator::new(
+            session_manager,
+            GapDetector::new(Duration::days(2)),
+            EventBus::new(),
+        );
+
+        let day = 86_400_000;
+        let monday = 1732492800000; // 2024-11-25
+        let friday_close = 1732903200000;
+        let mut weekly = Vec::new();
+        for i in 0..5 {
+            // Days run to the 17:00 Eastern close, Friday to the early close
+            let end = if i == 4 {
+                friday_close
+            } else {
+                monday + i * day + 22 * 3_600_000
+            };
+            let bar = Bar::new(
+                "EURUSD".to_string(),
+                Timeframe::D1,
+                monday + i * day,
+                end,
+                1.05,
+                1.06 + i as f64 * 0.01,
+                1.04,
+                1.05,
+            );
+            weekly.extend(aggregator.process_bar(bar, Timeframe::D1));
+        }
+
+        // The week closes with Friday's bar instead of waiting for Monday
+        assert_eq!(weekly.len(), 1);
+        assert_eq!(weekly[0].timeframe, Timeframe::W1);
+        assert_eq!(weekly[0].timestamp_start, monday);
+        assert_eq!(weekly[0].timestamp_end, friday_close);
+        assert!((weekly[0].high - 1.10).abs() < 1e-9);
+    }
 }
@@ -5,9 +5,22 @@ use chrono::{DateTime, Datelike, Duration};
 
 use super::MarketSchedule;
 
+/// How `GapDetector::fill_gap` synthesizes bars for a gap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GapFillPolicy {
+    /// Leave gaps empty
+    None,
+    /// Flat bars at the previous close
+    #[default]
+    ForwardFill,
+    /// Walk linearly from the previous close to the next open
+    Interpolate,
+}
+
 pub struct GapDetector {
     max_gap_duration: Duration,
     market_schedule: MarketSchedule,
+    fill_policy: GapFillPolicy,
 }
 
 impl GapDetector {
@@ -15,9 +28,19 @@ impl GapDetector {
         Self {
             max_gap_duration,
             market_schedule: MarketSchedule::new(),
+            fill_policy: GapFillPolicy::default(),
         }
     }
 
+    pub fn with_fill_policy(mut self, policy: GapFillPolicy) -> Self {
+        self.fill_policy = policy;
+        self
+    }
+
+    pub fn fill_policy(&self) -> GapFillPolicy {
+        self.fill_policy
+    }
+
     pub fn with_schedule(mut self, schedule: MarketSchedule) -> Self {
         self.market_schedule = schedule;
         self
@@ -148,6 +171,9 @@ impl GapDetector {
         GapType::Data
     }
 
+    /// Synthesize bars covering the gap between `prev_bar` and `next_bar`
+    /// according to the fill policy. Weekend gaps are never filled since
+    /// the market is closed.
     pub fn fill_gap(
         &self,
         prev_bar: &Bar,
@@ -155,6 +181,13 @@ impl GapDetector {
         timeframe: backtestr_data::timeframe::Timeframe,
     ) -> Vec<Bar> {
         let mut filled_bars = Vec::new();
+
+        if self.fill_policy == GapFillPolicy::None
+            || self.classify_gap(prev_bar, next_bar) == GapType::Weekend
+        {
+            return filled_bars;
+        }
+
         let gap_duration_ms = next_bar.timestamp_start - prev_bar.timestamp_end;
         let bar_duration_ms = timeframe.duration_ms();
 
@@ -164,18 +197,27 @@ impl GapDetector {
 
         // Create synthetic bars to fill the gap
         let num_bars = (gap_duration_ms / bar_duration_ms) as usize;
+        let step = (next_bar.open - prev_bar.close) / num_bars as f64;
         let mut current_timestamp = prev_bar.timestamp_end;
 
-        for _ in 0..num_bars {
+        for i in 0..num_bars {
+            let (open, close) = match self.fill_policy {
+                GapFillPolicy::Interpolate => (
+                    prev_bar.close + step * i as f64,
+                    prev_bar.close + step * (i + 1) as f64,
+                ),
+                _ => (prev_bar.close, prev_bar.close),
+            };
+
             let bar = Bar::new(
                 prev_bar.symbol.clone(),
                 timeframe,
                 current_timestamp,
                 current_timestamp + bar_duration_ms,
-                prev_bar.close, // Use previous close as OHLC
-                prev_bar.close,
-                prev_bar.close,
-                prev_bar.close,
+                open,
+                open.max(close),
+                open.min(close),
+                close,
             );
 
             filled_bars.push(bar);
@@ -316,4 +358,129 @@ mod tests {
         assert_eq!(filled[3].timestamp_start, 1704067440000);
         assert_eq!(filled[3].timestamp_end, 1704067500000);
     }
+
+    #[test]
+    fn test_fill_policy_none() {
+        let detector = GapDetector::new(Duration::minutes(5)).with_fill_policy(GapFillPolicy::None);
+
+        let bar1 = create_test_bar("EURUSD", 1704067200000, 1704067260000);
+        let bar2 = create_test_bar("EURUSD", 1704067500000, 1704067560000);
+
+        assert!(detector.fill_gap(&bar1, &bar2, Timeframe::M1).is_empty());
+    }
+
+    #[test]
+    fn test_interpolated_fill() {
+        let detector =
+            GapDetector::new(Duration::minutes(5)).with_fill_policy(GapFillPolicy::Interpolate);
+
+        let bar1 = create_test_bar("EURUSD", 1704067200000, 1704067260000); // close 1.0922
+        let mut bar2 = create_test_bar("EURUSD", 1704067500000, 1704067560000);
+        bar2.open = 1.0930;
+
+        let filled = detector.fill_gap(&bar1, &bar2, Timeframe::M1);
+        assert_eq!(filled.len(), 4);
+
+        assert!((filled[0].open - 1.0922).abs() < 1e-9);
+        assert!((filled[0].close - 1.0924).abs() < 1e-9);
+        assert!((filled[3].close - 1.0930).abs() < 1e-9);
+        for pair in filled.windows(2) {
+            assert_eq!(pair[0].close, pair[1].open);
+        }
+        assert!(filled.iter().all(|b| b.high == b.close && b.low == b.open));
+    }
+
+    #[test]
+    fn test_forward_fill_bar_contents() {
+        let detector = GapDetector::new(Duration::minutes(5));
+
+        let bar1 = create_test_bar("EURUSD", 1704067200000, 1704067260000); // close 1.0922
+        let bar2 = create_test_bar("EURUSD", 1704067500000, 1704067560000);
+
+        // One flat bar at the previous close for each missing minute
+        let filled = detector.fill_gap(&bar1, &bar2, Timeframe::M1);
+        for (i, bar) in filled.iter().enumerate() {
+            let start = 1704067260000 + i as i64 * 60_000;
+            assert_eq!(bar.symbol, "EURUSD");
+            assert_eq!(bar.timeframe, Timeframe::M1);
+            assert_eq!(bar.timestamp_start, start);
+            assert_eq!(bar.timestamp_end, start + 60_000);
+            assert_eq!(
+                (bar.open, bar.high, bar.low, bar.close),
+                (1.0922, 1.0922, 1.0922, 1.0922)
+            );
+        }
+        assert_eq!(filled.last().unwrap().timestamp_end, bar2.timestamp_start);
+    }
+
+    #[test]
+    fn test_fill_policy_none_skips_a_fillable_gap() {
+        let bar1 = create_test_bar("EURUSD", 1704067200000, 1704067260000);
+        let bar2 = create_test_bar("EURUSD", 1704067500000, 1704067560000);
+
+        // The default policy fills this gap, so the empty result is down to
+        // the policy
+        let forward = GapDetector::new(Duration::minutes(5));
+        assert_eq!(forward.fill_gap(&bar1, &bar2, Timeframe::M1).len(), 4);
+        let none = GapDetector::new(Duration::minutes(5)).with_fill_policy(GapFillPolicy::None);
+        assert!(none.fill_gap(&bar1, &bar2, Timeframe::M1).is_empty());
+    }
+
+    #[test]
+    fn test_interpolated_fill_steps_evenly() {
+        let detector =
+            GapDetector::new(Duration::minutes(5)).with_fill_policy(GapFillPolicy::Interpolate);
+
+        let bar1 = create_test_bar("EURUSD", 1704067200000, 1704067260000); // close 1.0922
+        let mut bar2 = create_test_bar("EURUSD", 1704067500000, 1704067560000);
+        bar2.open = 1.0930;
+
+        // Rising by 2 points a bar from the previous close to the next open
+        let filled = detector.fill_gap(&bar1, &bar2, Timeframe::M1);
+        let expected = [
+            (1.0922, 1.0924),
+            (1.0924, 1.0926),
+            (1.0926, 1.0928),
+            (1.0928, 1.0930),
+        ];
+        assert_eq!(filled.len(), expected.len());
+        for (i, (bar, (open, close))) in filled.iter().zip(expected).enumerate() {
+            let start = 1704067260000 + i as i64 * 60_000;
+            assert_eq!(bar.timestamp_start, start);
+            assert_eq!(bar.timestamp_end, start + 60_000);
+            assert!((bar.open - open).abs() < 1e-9);
+            assert!((bar.close - close).abs() < 1e-9);
+        }
+
+        // A gap reopening lower walks down, with high and low swapped
+        bar2.open = 1.0914;
+        let filled = detector.fill_gap(&bar1, &bar2, Timeframe::M1);
+        assert!((filled[0].close - 1.0920).abs() < 1e-9);
+        assert!((filled[3].close - 1.0914).abs() < 1e-9);
+        for pair in filled.windows(2) {
+            assert_eq!(pair[0].close, pair[1].open);
+        }
+        assert!(filled.iter().all(|b| b.high == b.open && b.low == b.close));
+    }
+
+    #[test]
+    fn test_weekend_gap_never_filled() {
+        let friday_close =
+            NaiveDateTime::parse_from_str("2024-01-05 22:00:00", "%Y-%m-%d %H:%M:%S")
+                .unwrap()
+                .and_utc()
+                .timestamp_millis();
+        let sunday_open = NaiveDateTime::parse_from_str("2024-01-07 22:00:00", "%Y-%m-%d %H:%M:%S")
+            .unwrap()
+            .and_utc()
+            .timestamp_millis();
+
+        let bar1 = create_test_bar("EURUSD", friday_close - 3_600_000, friday_close);
+        let bar2 = create_test_bar("EURUSD", sunday_open, sunday_open + 3_600_000);
+
+        for policy in [GapFillPolicy::ForwardFill, GapFillPolicy::Interpolate] {
+            let detector = GapDetector::new(Duration::hours(1)).with_fill_policy(policy);
+            assert!(detector.fill_gap(&bar1, &bar2, Timeframe::H1).is_empty());
+        }
+    }
 }
@@ -4,6 +4,6 @@ pub mod session_manager;
 pub mod volume_aggregator;
 
 pub use bar_aggregator::{AggregationMethod, AggregationRule, BarAggregator};
-pub use gap_detector::GapDetector;
+pub use gap_detector::{GapDetector, GapFillPolicy};
 pub use session_manager::{MarketHours, MarketSchedule, SessionManager};
-pub use volume_aggregator::VolumeAggregator;
+pub use volume_aggregator::{VolumeAggregator, VolumeLevel, VolumeProfile};
@@ -1,8 +1,14 @@
-use backtestr_data::timeframe::Timeframe;
-use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday};
+use backtestr_data::timeframe::{Timeframe, WeekStart};
+use chrono::{
+    DateTime, Datelike, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone,
+    Timelike, Weekday,
+};
 use chrono_tz::Tz;
 use std::collections::{HashMap, HashSet};
 
+/// Timezone of symbols without their own `MarketHours`
+const DEFAULT_TIMEZONE: Tz = chrono_tz::US::Eastern;
+
 #[derive(Debug, Clone)]
 pub struct MarketHours {
     pub symbol: String,
@@ -18,7 +24,7 @@ impl Default for MarketHours {
         // Forex default (24/5) - Sunday 5pm ET to Friday 5pm ET
         MarketHours {
             symbol: "DEFAULT".to_string(),
-            timezone: chrono_tz::US::Eastern,
+            timezone: DEFAULT_TIMEZONE,
             open_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
             close_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
             trading_days: vec![
@@ -149,14 +155,15 @@ pub struct SessionManager {
     market_hours: HashMap<String, MarketHours>,
     market_schedule: MarketSchedule,
     session_close_times: HashMap<Timeframe, NaiveTime>,
+    week_start: WeekStart,
 }
 
 impl Default for SessionManager {
     fn default() -> Self {
         let mut session_close_times = HashMap::new();
 
-        // Default session close times (Eastern Time)
-        session_close_times.insert(Timeframe::D1, NaiveTime::from_hms_opt(17, 0, 0).unwrap());
+        // Daily bars close at each symbol's market-hours close unless a D1
+        // time is set
         session_close_times.insert(Timeframe::H4, NaiveTime::from_hms_opt(0, 0, 0).unwrap());
         session_close_times.insert(Timeframe::H1, NaiveTime::from_hms_opt(0, 0, 0).unwrap());
 
@@ -164,6 +171,7 @@ impl Default for SessionManager {
             market_hours: HashMap::new(),
             market_schedule: MarketSchedule::new(),
             session_close_times,
+            week_start: WeekStart::default(),
         }
     }
 }
@@ -184,11 +192,68 @@ impl SessionManager {
             .unwrap_or_else(|| MarketHours::forex(symbol))
     }
 
+    pub fn set_market_schedule(&mut self, schedule: MarketSchedule) {
+        self.market_schedule = schedule;
+    }
+
+    pub fn market_schedule(&self) -> &MarketSchedule {
+        &self.market_schedule
+    }
+
+    /// Whether `timestamp_ms` falls on a holiday, by the date in the
+    /// symbol's timezone
+    pub fn is_holiday(&self, symbol: &str, timestamp_ms: i64) -> bool {
+        self.local_datetime(symbol, timestamp_ms)
+            .is_some_and(|local| self.market_schedule.is_holiday(local.date()))
+    }
+
+    /// Date of the session trading at `timestamp_ms`: the symbol's local
+    /// date until that day's close, the next date after it. Forex opens at
+    /// the previous evening's close, so Monday 19:00 Eastern already
+    /// trades in Tuesday's session. A midnight close keeps calendar days.
+    pub fn session_date(&self, symbol: &str, timestamp_ms: i64) -> Option<NaiveDate> {
+        let local = self.local_datetime(symbol, timestamp_ms)?;
+        let close = self.close_time(symbol, local.date());
+        if close != NaiveTime::MIN && local.time() >= close {
+            local.date().succ_opt()
+        } else {
+            Some(local.date())
+        }
+    }
+
+    /// Whether the session trading at `timestamp_ms` is a holiday's, by
+    /// `session_date`
+    pub fn is_holiday_session(&self, symbol: &str, timestamp_ms: i64) -> bool {
+        self.session_date(symbol, timestamp_ms)
+            .is_some_and(|date| self.market_schedule.is_holiday(date))
+    }
+
+    /// A `D1` close time overrides every symbol's market-hours close,
+    /// though early closes still take precedence
     pub fn set_session_close_time(&mut self, timeframe: Timeframe, close_time: NaiveTime) {
         self.session_close_times.insert(timeframe, close_time);
     }
 
-    pub fn is_session_boundary(&self, timeframe: Timeframe, timestamp_ms: i64) -> bool {
+    /// Day `W1` bars open on for `is_session_boundary`; Monday by default
+    pub fn set_week_start(&mut self, week_start: WeekStart) {
+        self.week_start = week_start;
+    }
+
+    pub fn week_start(&self) -> WeekStart {
+        self.week_start
+    }
+
+    /// Whether `timestamp_ms` closes a `timeframe` bar for `symbol`.
+    ///
+    /// Daily closes and calendar weeks and months are compared in the
+    /// symbol's timezone, so a 17:00 Eastern close is 22:00 UTC in winter
+    /// and 21:00 UTC in summer. Intraday alignment stays in UTC.
+    pub fn is_session_boundary(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        timestamp_ms: i64,
+    ) -> bool {
         let datetime = DateTime::from_timestamp_millis(timestamp_ms).map(|dt| dt.naive_utc());
         if datetime.is_none() {
             return false;
@@ -197,11 +262,10 @@ impl SessionManager {
 
         match timeframe {
             Timeframe::D1 => {
-                // Daily bars close at configured time (default 5pm ET)
-                if let Some(close_time) = self.session_close_times.get(&Timeframe::D1) {
-                    return dt.time() == *close_time;
-                }
-                false
+                // Daily bars close at the symbol's market close, or at that
+                // date's early close
+                self.local_datetime(symbol, timestamp_ms)
+                    .is_some_and(|local| self.is_daily_close(symbol, local))
             }
             Timeframe::H4 => {
                 // 4-hour bars align with specific times
@@ -224,6 +288,14 @@ impl SessionManager {
                 // 1-minute bars
                 dt.second() == 0
             }
+            // Calendar week and calendar month boundaries, by the symbol's
+            // local calendar
+            Timeframe::W1 | Timeframe::MN1 => self
+                .local_datetime(symbol, timestamp_ms)
+                .is_some_and(|local| {
+                    let local_ms = local.and_utc().timestamp_millis();
+                    timeframe.is_bar_boundary_with(local_ms, self.week_start)
+                }),
         }
     }
 
@@ -244,6 +316,125 @@ impl SessionManager {
         hours.is_trading_time(dt)
     }
 
+    /// Whether `symbol` trades at `timestamp_ms`, judged by the wall clock
+    /// in the symbol's timezone. Holidays and the hours after an early
+    /// close count as closed.
+    pub fn is_trading_at(&self, symbol: &str, timestamp_ms: i64) -> bool {
+        let Some(local) = self.local_datetime(symbol, timestamp_ms) else {
+            return false;
+        };
+
+        if self.market_schedule.is_holiday(local.date()) {
+            return false;
+        }
+
+        // Half days end at their early close
+        if let Some(early_close) = self.market_schedule.get_close_time(local.date()) {
+            if local.time() >= early_close {
+                return false;
+            }
+        }
+
+        self.get_market_hours(symbol).is_trading_time(local)
+    }
+
+    /// Milliseconds of `[from_ms, to_ms)` during which `symbol` trades, by
+    /// `is_trading_at`.
+    ///
+    /// Trading only starts or stops at a local midnight, open, close,
+    /// session break, early close or clock change, so the range is split at
+    /// those instants and each piece is checked once. Long ranges cost a
+    /// few checks per day.
+    pub fn trading_time_between(&self, symbol: &str, from_ms: i64, to_ms: i64) -> i64 {
+        if from_ms >= to_ms {
+            return 0;
+        }
+        let (Some(first), Some(last)) = (
+            self.local_datetime(symbol, from_ms),
+            self.local_datetime(symbol, to_ms),
+        ) else {
+            return 0;
+        };
+
+        let mut cuts = vec![from_ms, to_ms];
+        for date in first
+            .date()
+            .iter_days()
+            .take_while(|date| *date <= last.date())
+        {
+            cuts.extend(
+                self.trading_changes(symbol, date)
+                    .into_iter()
+                    .filter(|cut| (from_ms..to_ms).contains(cut)),
+            );
+        }
+        cuts.sort_unstable();
+        cuts.dedup();
+
+        cuts.windows(2)
+            .filter(|piece| self.is_trading_at(symbol, piece[0]))
+            .map(|piece| piece[1] - piece[0])
+            .sum()
+    }
+
+    /// Instants during the symbol's local `date` at which `is_trading_at`
+    /// may change
+    fn trading_changes(&self, symbol: &str, date: NaiveDate) -> Vec<i64> {
+        let tz = self.timezone(symbol);
+        let hours = self.get_market_hours(symbol);
+        let mut times = vec![NaiveTime::MIN, hours.open_time, hours.close_time];
+        if let Some((break_start, break_end)) = hours.session_break {
+            times.extend([break_start, break_end]);
+        }
+        times.extend(self.market_schedule.get_close_time(date));
+
+        // A time skipped by a clock change is passed at the change itself
+        let mut changes: Vec<i64> = times
+            .into_iter()
+            .flat_map(|time| match tz.from_local_datetime(&date.and_time(time)) {
+                LocalResult::Single(at) => vec![at.timestamp_millis()],
+                LocalResult::Ambiguous(early, late) => {
+                    vec![early.timestamp_millis(), late.timestamp_millis()]
+                }
+                LocalResult::None => Vec::new(),
+            })
+            .collect();
+        changes.extend(self.clock_change(tz, date));
+        changes
+    }
+
+    /// Instant the UTC offset of `tz` changes during local `date`, found to
+    /// the minute
+    fn clock_change(&self, tz: Tz, date: NaiveDate) -> Option<i64> {
+        const MINUTE_MS: i64 = 60_000;
+
+        let start_of = |date: NaiveDate| {
+            tz.from_local_datetime(&date.and_time(NaiveTime::MIN))
+                .earliest()
+                .map(|at| at.timestamp_millis())
+        };
+        let offset_at = |ms: i64| {
+            DateTime::from_timestamp_millis(ms)
+                .map(|utc| tz.offset_from_utc_datetime(&utc.naive_utc()).fix())
+        };
+        let mut before = start_of(date)?;
+        let mut after = start_of(date.succ_opt()?)?;
+        let offset = offset_at(before)?;
+        if offset_at(after)? == offset {
+            return None;
+        }
+
+        while after - before > MINUTE_MS {
+            let mid = before + (after - before) / MINUTE_MS / 2 * MINUTE_MS;
+            if offset_at(mid)? == offset {
+                before = mid;
+            } else {
+                after = mid;
+            }
+        }
+        Some(after)
+    }
+
     pub fn get_next_session_open(&self, symbol: &str, timestamp_ms: i64) -> Option<i64> {
         let datetime = DateTime::from_timestamp_millis(timestamp_ms)?.naive_utc();
         let hours = self.get_market_hours(symbol);
@@ -265,49 +456,86 @@ impl SessionManager {
         None
     }
 
+    /// Close of the session on the symbol's local date at `timestamp_ms`,
+    /// as a UTC timestamp; a 13:00 Eastern early close is 18:00 UTC
     pub fn get_session_close(&self, symbol: &str, timestamp_ms: i64) -> Option<i64> {
-        let datetime = DateTime::from_timestamp_millis(timestamp_ms)?.naive_utc();
-        let hours = self.get_market_hours(symbol);
-
-        // Check for early close
-        if let Some(early_close) = self.market_schedule.get_close_time(datetime.date()) {
-            let close_datetime = NaiveDateTime::new(datetime.date(), early_close);
-            return Some(close_datetime.and_utc().timestamp_millis());
-        }
+        let date = self.local_datetime(symbol, timestamp_ms)?.date();
+        let close = NaiveDateTime::new(date, self.close_time(symbol, date));
+        let utc = self
+            .timezone(symbol)
+            .from_local_datetime(&close)
+            .earliest()?;
+        Some(utc.timestamp_millis())
+    }
 
-        // Regular close
-        let close_datetime = NaiveDateTime::new(datetime.date(), hours.close_time);
-        Some(close_datetime.and_utc().timestamp_millis())
+    /// Weekly bars close at the daily close, or early close, of the week's
+    /// last trading day in the symbol's timezone; Friday unless it is a
+    /// holiday
+    pub fn is_weekly_boundary(&self, symbol: &str, timestamp_ms: i64) -> bool {
+        self.closing_session(symbol, timestamp_ms)
+            .is_some_and(|date| self.is_last_trading_day(symbol, date))
     }
 
-    pub fn is_weekly_boundary(&self, timestamp_ms: i64) -> bool {
-        let datetime = DateTime::from_timestamp_millis(timestamp_ms).map(|dt| dt.naive_utc());
-        if datetime.is_none() {
+    /// Whether no trading day follows `date` before the weekend
+    fn is_last_trading_day(&self, symbol: &str, date: NaiveDate) -> bool {
+        let friday = Weekday::Fri.num_days_from_monday();
+        if date.weekday().num_days_from_monday() > friday {
             return false;
         }
-        let dt = datetime.unwrap();
 
-        // Weekly bars close on Friday at market close
-        dt.weekday() == Weekday::Fri && dt.hour() == 17 && dt.minute() == 0
+        let hours = self.get_market_hours(symbol);
+        date.iter_days()
+            .skip(1)
+            .take_while(|day| day.weekday().num_days_from_monday() <= friday)
+            .all(|day| {
+                !hours.trading_days.contains(&day.weekday()) || self.market_schedule.is_holiday(day)
+            })
     }
 
-    pub fn is_monthly_boundary(&self, timestamp_ms: i64) -> bool {
-        let datetime = DateTime::from_timestamp_millis(timestamp_ms).map(|dt| dt.naive_utc());
-        if datetime.is_none() {
-            return false;
-        }
-        let dt = datetime.unwrap();
+    /// Monthly bars close on the last day of the month at the daily close,
+    /// in the symbol's timezone
+    pub fn is_monthly_boundary(&self, symbol: &str, timestamp_ms: i64) -> bool {
+        self.closing_session(symbol, timestamp_ms)
+            .and_then(|date| Some((date, date.succ_opt()?)))
+            .is_some_and(|(date, next)| next.month() != date.month())
+    }
 
-        // Check if it's the last trading day of the month
-        let next_day = dt.date().succ_opt();
-        if let Some(next) = next_day {
-            if next.month() != dt.month() {
-                // It's the last day of the month
-                return dt.hour() == 17 && dt.minute() == 0;
-            }
+    /// Whether `local`, in the symbol's timezone, is that day's close,
+    /// which is the early close on a half day
+    fn is_daily_close(&self, symbol: &str, local: NaiveDateTime) -> bool {
+        local.time() == self.close_time(symbol, local.date())
+    }
+
+    /// Date of the session `timestamp_ms` closes, if it is a daily close;
+    /// a midnight close ends the previous day's session
+    fn closing_session(&self, symbol: &str, timestamp_ms: i64) -> Option<NaiveDate> {
+        let local = self.local_datetime(symbol, timestamp_ms)?;
+        if !self.is_daily_close(symbol, local) {
+            return None;
         }
+        self.session_date(symbol, timestamp_ms - 1)
+    }
+
+    /// Local close time on `date`: the early close, else the `D1` override,
+    /// else the symbol's market-hours close
+    fn close_time(&self, symbol: &str, date: NaiveDate) -> NaiveTime {
+        self.market_schedule
+            .get_close_time(date)
+            .or_else(|| self.session_close_times.get(&Timeframe::D1).copied())
+            .unwrap_or_else(|| self.get_market_hours(symbol).close_time)
+    }
 
-        false
+    fn timezone(&self, symbol: &str) -> Tz {
+        self.market_hours
+            .get(symbol)
+            .map(|hours| hours.timezone)
+            .unwrap_or(DEFAULT_TIMEZONE)
+    }
+
+    /// Wall-clock time at `timestamp_ms` in the symbol's timezone
+    fn local_datetime(&self, symbol: &str, timestamp_ms: i64) -> Option<NaiveDateTime> {
+        let utc = DateTime::from_timestamp_millis(timestamp_ms)?;
+        Some(utc.with_timezone(&self.timezone(symbol)).naive_local())
     }
 }
 
@@ -343,26 +571,26 @@ mod tests {
     fn test_session_boundary_detection() {
         let manager = SessionManager::new();
 
-        // Test daily boundary (5pm)
-        let timestamp = NaiveDateTime::parse_from_str("2024-01-01 17:00:00", "%Y-%m-%d %H:%M:%S")
+        // Test daily boundary (5pm ET is 22:00 UTC in winter)
+        let timestamp = NaiveDateTime::parse_from_str("2024-01-01 22:00:00", "%Y-%m-%d %H:%M:%S")
             .unwrap()
             .and_utc()
             .timestamp_millis();
-        assert!(manager.is_session_boundary(Timeframe::D1, timestamp));
+        assert!(manager.is_session_boundary("EURUSD", Timeframe::D1, timestamp));
 
         // Test hourly boundary
         let timestamp = NaiveDateTime::parse_from_str("2024-01-01 14:00:00", "%Y-%m-%d %H:%M:%S")
             .unwrap()
             .and_utc()
             .timestamp_millis();
-        assert!(manager.is_session_boundary(Timeframe::H1, timestamp));
+        assert!(manager.is_session_boundary("EURUSD", Timeframe::H1, timestamp));
 
         // Test non-boundary
         let timestamp = NaiveDateTime::parse_from_str("2024-01-01 14:30:00", "%Y-%m-%d %H:%M:%S")
             .unwrap()
             .and_utc()
             .timestamp_millis();
-        assert!(!manager.is_session_boundary(Timeframe::H1, timestamp));
+        assert!(!manager.is_session_boundary("EURUSD", Timeframe::H1, timestamp));
     }
 
     #[test]
@@ -382,4 +610,358 @@ mod tests {
             Some(early_close_time)
         );
     }
+
+    fn utc_millis(datetime: &str) -> i64 {
+        NaiveDateTime::parse_from_str(datetime, "%Y-%m-%d %H:%M:%S")
+            .unwrap()
+            .and_utc()
+            .timestamp_millis()
+    }
+
+    #[test]
+    fn test_daily_boundary_follows_dst() {
+        let manager = SessionManager::new();
+
+        // 17:00 Eastern is 22:00 UTC under EST and 21:00 UTC under EDT
+        for (boundary, not_boundary) in [
+            ("2024-03-08 22:00:00", "2024-03-08 21:00:00"),
+            ("2024-03-11 21:00:00", "2024-03-11 22:00:00"),
+            ("2024-11-01 21:00:00", "2024-11-01 22:00:00"),
+            ("2024-11-04 22:00:00", "2024-11-04 21:00:00"),
+        ] {
+            assert!(
+                manager.is_session_boundary("EURUSD", Timeframe::D1, utc_millis(boundary)),
+                "{boundary} should close the day"
+            );
+            assert!(!manager.is_session_boundary(
+                "EURUSD",
+                Timeframe::D1,
+                utc_millis(not_boundary)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_boundary_uses_symbol_timezone() {
+        let mut manager = SessionManager::new();
+        manager.add_market_hours(
+            "DAX".to_string(),
+            MarketHours {
+                symbol: "DAX".to_string(),
+                timezone: chrono_tz::Europe::Berlin,
+                ..Default::default()
+            },
+        );
+
+        // 17:00 in Berlin (CEST) and New York (EDT) on the same day
+        assert!(manager.is_session_boundary(
+            "DAX",
+            Timeframe::D1,
+            utc_millis("2024-07-02 15:00:00")
+        ));
+        assert!(!manager.is_session_boundary(
+            "DAX",
+            Timeframe::D1,
+            utc_millis("2024-07-02 21:00:00")
+        ));
+        assert!(manager.is_session_boundary(
+            "EURUSD",
+            Timeframe::D1,
+            utc_millis("2024-07-02 21:00:00")
+        ));
+    }
+
+    #[test]
+    fn test_weekly_and_monthly_boundaries_in_local_time() {
+        let manager = SessionManager::new();
+
+        // Friday 2024-03-08 is EST, Friday 2024-03-15 is EDT
+        assert!(manager.is_weekly_boundary("EURUSD", utc_millis("2024-03-08 22:00:00")));
+        assert!(manager.is_weekly_boundary("EURUSD", utc_millis("2024-03-15 21:00:00")));
+        assert!(!manager.is_weekly_boundary("EURUSD", utc_millis("2024-03-15 22:00:00")));
+        assert!(!manager.is_weekly_boundary("EURUSD", utc_millis("2024-03-15 17:00:00")));
+
+        // 2024-05-31 17:00 EDT; 17:00 UTC is still mid-session in New York
+        assert!(manager.is_monthly_boundary("EURUSD", utc_millis("2024-05-31 21:00:00")));
+        assert!(!manager.is_monthly_boundary("EURUSD", utc_millis("2024-05-31 17:00:00")));
+        // 2024-01-31 17:00 EST
+        assert!(manager.is_monthly_boundary("EURUSD", utc_millis("2024-01-31 22:00:00")));
+
+        // Calendar weeks and months turn over at local midnight
+        assert!(manager.is_session_boundary(
+            "EURUSD",
+            Timeframe::W1,
+            utc_millis("2024-03-11 04:00:00")
+        ));
+        assert!(!manager.is_session_boundary(
+            "EURUSD",
+            Timeframe::W1,
+            utc_millis("2024-03-11 00:00:00")
+        ));
+        assert!(manager.is_session_boundary(
+            "EURUSD",
+            Timeframe::MN1,
+            utc_millis("2024-02-01 05:00:00")
+        ));
+        assert!(!manager.is_session_boundary(
+            "EURUSD",
+            Timeframe::MN1,
+            utc_millis("2024-02-01 00:00:00")
+        ));
+    }
+
+    #[test]
+    fn test_daily_close_follows_symbol_hours() {
+        let mut manager = SessionManager::new();
+        manager.add_market_hours("SPY".to_string(), MarketHours::stock_market("SPY"));
+        manager.add_market_hours("ES".to_string(), MarketHours::futures("ES"));
+
+        // 16:00 Eastern and 16:00 Central on 2024-01-10
+        let stock_close = utc_millis("2024-01-10 21:00:00");
+        let futures_close = utc_millis("2024-01-10 22:00:00");
+        assert!(manager.is_session_boundary("SPY", Timeframe::D1, stock_close));
+        assert!(!manager.is_session_boundary("SPY", Timeframe::D1, futures_close));
+        assert!(manager.is_session_boundary("ES", Timeframe::D1, futures_close));
+        // Forex keeps the 17:00 Eastern close
+        assert!(manager.is_session_boundary("EURUSD", Timeframe::D1, futures_close));
+        assert!(!manager.is_session_boundary("EURUSD", Timeframe::D1, stock_close));
+
+        // An explicit D1 close applies to every symbol
+        manager.set_session_close_time(Timeframe::D1, NaiveTime::from_hms_opt(16, 0, 0).unwrap());
+        assert!(manager.is_session_boundary("EURUSD", Timeframe::D1, stock_close));
+    }
+
+    #[test]
+    fn test_holiday_uses_symbol_local_date() {
+        let mut schedule = MarketSchedule::new();
+        schedule.add_holiday(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+        let mut manager = SessionManager::new();
+        manager.set_market_schedule(schedule);
+        manager.add_market_hours(
+            "DAX".to_string(),
+            MarketHours {
+                timezone: chrono_tz::Europe::Berlin,
+                ..MarketHours::stock_market("DAX")
+            },
+        );
+
+        // 2024-01-16 03:00 UTC is still the 15th in New York, but already
+        // the 16th in Berlin
+        let late = utc_millis("2024-01-16 03:00:00");
+        assert!(manager.is_holiday("EURUSD", late));
+        assert!(!manager.is_holiday("DAX", late));
+
+        // 2024-01-14 23:30 UTC is already the 15th in Berlin
+        let early = utc_millis("2024-01-14 23:30:00");
+        assert!(!manager.is_holiday("EURUSD", early));
+        assert!(manager.is_holiday("DAX", early));
+    }
+
+    #[test]
+    fn test_session_date_rolls_over_at_close() {
+        let mut schedule = MarketSchedule::new();
+        schedule.add_holiday(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+        let mut manager = SessionManager::new();
+        manager.set_market_schedule(schedule);
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d);
+
+        // Sunday 19:00 Eastern trades in Monday's session, and Monday
+        // 19:00 Eastern in Tuesday's
+        let monday_session = utc_millis("2024-01-15 00:00:00");
+        let tuesday_session = utc_millis("2024-01-16 00:00:00");
+        assert_eq!(
+            manager.session_date("EURUSD", monday_session),
+            date(2024, 1, 15)
+        );
+        assert_eq!(
+            manager.session_date("EURUSD", tuesday_session),
+            date(2024, 1, 16)
+        );
+        assert!(manager.is_holiday_session("EURUSD", monday_session));
+        assert!(!manager.is_holiday_session("EURUSD", tuesday_session));
+
+        // The close itself opens the next session
+        let close = utc_millis("2024-01-15 22:00:00");
+        assert_eq!(manager.session_date("EURUSD", close), date(2024, 1, 16));
+        assert_eq!(manager.session_date("EURUSD", close - 1), date(2024, 1, 15));
+
+        // A midnight close keeps sessions on calendar days
+        manager.set_session_close_time(Timeframe::D1, NaiveTime::MIN);
+        assert_eq!(
+            manager.session_date("EURUSD", tuesday_session),
+            date(2024, 1, 15)
+        );
+    }
+
+    #[test]
+    fn test_is_trading_at_uses_local_wall_clock() {
+        let mut manager = SessionManager::new();
+        manager.add_market_hours("SPY".to_string(), MarketHours::stock_market("SPY"));
+
+        // 09:30 Eastern is 14:30 UTC in winter and 13:30 UTC in summer
+        assert!(manager.is_trading_at("SPY", utc_millis("2024-01-16 14:30:00")));
+        assert!(!manager.is_trading_at("SPY", utc_millis("2024-01-16 13:30:00")));
+        assert!(manager.is_trading_at("SPY", utc_millis("2024-07-16 13:30:00")));
+
+        // Forex closes at 17:00 Eastern on Friday
+        assert!(manager.is_trading_at("EURUSD", utc_millis("2024-01-19 21:59:00")));
+        assert!(!manager.is_trading_at("EURUSD", utc_millis("2024-01-19 22:00:00")));
+
+        // The UTC-based check is unchanged
+        assert!(manager.is_market_open("SPY", utc_millis("2024-01-16 13:30:00")));
+    }
+
+    #[test]
+    fn test_is_trading_at_honours_holidays_and_early_closes() {
+        let mut schedule = MarketSchedule::new();
+        schedule.add_holiday(NaiveDate::from_ymd_opt(2024, 11, 28).unwrap());
+        schedule.add_early_close(
+            NaiveDate::from_ymd_opt(2024, 11, 29).unwrap(),
+            NaiveTime::from_hms_opt(13, 0, 0).unwrap(),
+        );
+        let mut manager = SessionManager::new();
+        manager.set_market_schedule(schedule);
+
+        assert!(!manager.is_trading_at("EURUSD", utc_millis("2024-11-28 15:00:00")));
+        assert!(manager.is_trading_at("EURUSD", utc_millis("2024-11-29 17:59:00")));
+        assert!(!manager.is_trading_at("EURUSD", utc_millis("2024-11-29 18:00:00")));
+    }
+
+    #[test]
+    fn test_trading_time_between_skips_only_the_closure() {
+        let manager = SessionManager::new();
+        // Friday 2024-01-19 20:00 UTC to Monday 06:00 UTC: two hours before
+        // the 22:00 UTC close and one after the Monday 05:00 UTC open
+        let open = manager.trading_time_between(
+            "EURUSD",
+            utc_millis("2024-01-19 20:00:00"),
+            utc_millis("2024-01-22 06:00:00"),
+        );
+        assert_eq!(open, 3 * 3_600_000);
+
+        // Partial minutes at either end are counted
+        let from = utc_millis("2024-01-16 10:00:00") + 15_000;
+        assert_eq!(
+            manager.trading_time_between("EURUSD", from, from + 90_000),
+            90_000
+        );
+        assert_eq!(manager.trading_time_between("EURUSD", from, from), 0);
+    }
+
+    /// `trading_time_between` by checking every minute; sessions open and
+    /// close on whole minutes
+    fn trading_minutes_between(manager: &SessionManager, symbol: &str, from: i64, to: i64) -> i64 {
+        const MINUTE_MS: i64 = 60_000;
+
+        let mut open_ms = 0;
+        let mut cursor = from;
+        while cursor < to {
+            let next = ((cursor.div_euclid(MINUTE_MS) + 1) * MINUTE_MS).min(to);
+            if manager.is_trading_at(symbol, cursor) {
+                open_ms += next - cursor;
+            }
+            cursor = next;
+        }
+        open_ms
+    }
+
+    #[test]
+    fn test_trading_time_between_matches_minute_by_minute() {
+        let mut schedule = MarketSchedule::new();
+        schedule.add_holiday(NaiveDate::from_ymd_opt(2024, 3, 6).unwrap());
+        schedule.add_early_close(
+            NaiveDate::from_ymd_opt(2024, 3, 8).unwrap(),
+            NaiveTime::from_hms_opt(13, 0, 0).unwrap(),
+        );
+        let mut manager = SessionManager::new();
+        manager.set_market_schedule(schedule);
+        manager.add_market_hours("ES".to_string(), MarketHours::futures("ES"));
+        manager.add_market_hours("AAPL".to_string(), MarketHours::stock_market("AAPL"));
+
+        // Two weeks around the US spring clock change, starting mid-minute
+        let from = utc_millis("2024-03-01 13:37:00") + 15_000;
+        let to = utc_millis("2024-03-15 20:00:00");
+        for symbol in ["EURUSD", "ES", "AAPL"] {
+            assert_eq!(
+                manager.trading_time_between(symbol, from, to),
+                trading_minutes_between(&manager, symbol, from, to),
+                "{}",
+                symbol
+            );
+        }
+
+        // Sessions opening inside the hour a clock change skips or repeats
+        let early_session = |open_time| MarketHours {
+            symbol: "DAX".to_string(),
+            timezone: chrono_tz::US::Eastern,
+            open_time,
+            close_time: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+            trading_days: vec![Weekday::Sat, Weekday::Sun, Weekday::Mon],
+            session_break: None,
+        };
+        manager.add_market_hours(
+            "DAX".to_string(),
+            early_session(NaiveTime::from_hms_opt(2, 30, 0).unwrap()),
+        );
+        let from = utc_millis("2024-03-09 00:00:00");
+        let to = utc_millis("2024-03-12 00:00:00");
+        assert_eq!(
+            manager.trading_time_between("DAX", from, to),
+            trading_minutes_between(&manager, "DAX", from, to)
+        );
+        manager.add_market_hours(
+            "DAX".to_string(),
+            early_session(NaiveTime::from_hms_opt(1, 30, 0).unwrap()),
+        );
+        let from = utc_millis("2024-11-02 00:00:00");
+        let to = utc_millis("2024-11-05 00:00:00");
+        assert_eq!(
+            manager.trading_time_between("DAX", from, to),
+            trading_minutes_between(&manager, "DAX", from, to)
+        );
+
+        // And across the autumn change, where an hour repeats
+        let from = utc_millis("2024-10-30 00:00:00");
+        let to = utc_millis("2024-11-06 00:00:00");
+        for symbol in ["EURUSD", "ES", "AAPL"] {
+            assert_eq!(
+                manager.trading_time_between(symbol, from, to),
+                trading_minutes_between(&manager, symbol, from, to),
+                "{}",
+                symbol
+            );
+        }
+    }
+
+    #[test]
+    fn test_early_close_is_session_boundary() {
+        let mut schedule = MarketSchedule::new();
+        // Friday after Thanksgiving closes at 13:00 Eastern
+        schedule.add_early_close(
+            NaiveDate::from_ymd_opt(2024, 11, 29).unwrap(),
+            NaiveTime::from_hms_opt(13, 0, 0).unwrap(),
+        );
+        let mut manager = SessionManager::new();
+        manager.set_market_schedule(schedule);
+
+        let early = utc_millis("2024-11-29 18:00:00");
+        let regular = utc_millis("2024-11-29 22:00:00");
+        assert!(manager.is_session_boundary("EURUSD", Timeframe::D1, early));
+        assert!(!manager.is_session_boundary("EURUSD", Timeframe::D1, regular));
+
+        // The early close on a Friday also ends the week
+        assert!(manager.is_weekly_boundary("EURUSD", early));
+        assert!(!manager.is_weekly_boundary("EURUSD", regular));
+        assert_eq!(
+            manager.get_session_close("EURUSD", utc_millis("2024-11-29 15:00:00")),
+            Some(early)
+        );
+
+        // Other days keep the regular close
+        assert!(manager.is_session_boundary(
+            "EURUSD",
+            Timeframe::D1,
+            utc_millis("2024-11-27 22:00:00")
+        ));
+    }
 }
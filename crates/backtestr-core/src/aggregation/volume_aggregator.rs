@@ -1,4 +1,11 @@
 use backtestr_data::models::Bar;
+use backtestr_data::timeframe::Timeframe;
+use backtestr_data::Database;
+use chrono::{DateTime, NaiveTime, Timelike, Utc};
+use chrono_tz::Tz;
+
+/// Bars read per query when building a profile from the database
+const PROFILE_CHUNK_SIZE: usize = 10_000;
 
 #[derive(Default)]
 pub struct VolumeAggregator {
@@ -118,10 +125,8 @@ impl VolumeAggregator {
     }
 
     pub fn calculate_volume_profile(&self, bars: &[Bar], num_levels: usize) -> VolumeProfile {
-        let mut profile = VolumeProfile::new(num_levels);
-
         if bars.is_empty() {
-            return profile;
+            return VolumeProfile::new(num_levels);
         }
 
         // Find price range
@@ -133,59 +138,101 @@ impl VolumeAggregator {
             max_price = max_price.max(bar.high);
         }
 
-        if min_price >= max_price {
-            return profile;
+        let mut profile = VolumeProfile::with_price_range(min_price, max_price, num_levels);
+        for bar in bars {
+            profile.add_bar(bar);
         }
+        profile.update_poc();
+        profile
+    }
 
-        profile.min_price = min_price;
-        profile.max_price = max_price;
+    /// Volume profile of the stored bars starting in `start..=end`.
+    ///
+    /// The price range comes from one SQL aggregate, then bars are read in
+    /// chunks and binned as they arrive, so the range never has to fit in
+    /// memory.
+    pub fn query_volume_profile(
+        &self,
+        database: &Database,
+        symbol: &str,
+        timeframe: Timeframe,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        num_levels: usize,
+    ) -> backtestr_data::Result<VolumeProfile> {
+        let Some((min_price, max_price)) =
+            database.bar_price_range(symbol, timeframe, start, end)?
+        else {
+            return Ok(VolumeProfile::new(num_levels));
+        };
+
+        let mut profile = VolumeProfile::with_price_range(min_price, max_price, num_levels);
+        if profile.levels.is_empty() {
+            return Ok(profile);
+        }
 
-        let price_step = (max_price - min_price) / num_levels as f64;
+        // Keys are exclusive, so begin just before the first wanted bar
+        let mut after = start.timestamp_millis() - 1;
+        loop {
+            let bars =
+                database.query_bars_after(symbol, timeframe, after, end, PROFILE_CHUNK_SIZE)?;
+            let Some(last) = bars.last() else { break };
+            after = last.timestamp_start;
 
-        // Initialize levels
-        for i in 0..num_levels {
-            let level_price = min_price + (i as f64 + 0.5) * price_step;
-            profile.levels.push(VolumeLevel {
-                price: level_price,
-                volume: 0,
-                tick_count: 0,
-            });
+            for bar in &bars {
+                profile.add_bar(bar);
+            }
         }
 
-        // Distribute volume across levels
-        for bar in bars {
-            let volume = bar.volume.unwrap_or(0);
-            let ticks = bar.tick_count.unwrap_or(0);
-
-            // Find which levels this bar touches
-            let low_level = ((bar.low - min_price) / price_step).floor() as usize;
-            let high_level = ((bar.high - min_price) / price_step).ceil() as usize;
-
-            let num_touched_levels = (high_level - low_level + 1).max(1);
-            let volume_per_level = volume / num_touched_levels as i64;
-            let ticks_per_level = ticks / num_touched_levels as i32;
-
-            for level_idx in low_level..=high_level.min(num_levels - 1) {
-                if level_idx < profile.levels.len() {
-                    profile.levels[level_idx].volume += volume_per_level;
-                    profile.levels[level_idx].tick_count += ticks_per_level;
-                }
-            }
+        profile.update_poc();
+        Ok(profile)
+    }
+
+    /// Total bar volume per `bucket_minutes` slice of the UTC day; see
+    /// `volume_by_time_of_day_in`
+    pub fn volume_by_time_of_day(
+        &self,
+        bars: &[Bar],
+        bucket_minutes: u32,
+    ) -> Vec<(NaiveTime, i64)> {
+        self.volume_by_time_of_day_in(bars, bucket_minutes, chrono_tz::UTC)
+    }
+
+    /// Total bar volume per `bucket_minutes` slice of the day in `timezone`.
+    ///
+    /// Returns every bucket from midnight in order, keyed by its start time,
+    /// with zero for buckets no bar falls in. A bar counts wholly towards
+    /// the bucket holding its `timestamp_start`, even if it runs past the
+    /// bucket's end. Returns nothing when `bucket_minutes` is zero; a size
+    /// that does not divide the day leaves a shorter last bucket.
+    pub fn volume_by_time_of_day_in(
+        &self,
+        bars: &[Bar],
+        bucket_minutes: u32,
+        timezone: Tz,
+    ) -> Vec<(NaiveTime, i64)> {
+        const MINUTES_PER_DAY: u32 = 24 * 60;
+        if bucket_minutes == 0 {
+            return Vec::new();
         }
 
-        // Find POC (Point of Control)
-        let mut max_volume = 0i64;
-        let mut poc_price = 0.0;
+        let bucket_minutes = bucket_minutes.min(MINUTES_PER_DAY);
+        let mut buckets: Vec<(NaiveTime, i64)> = (0..MINUTES_PER_DAY)
+            .step_by(bucket_minutes as usize)
+            .filter_map(|minute| NaiveTime::from_hms_opt(minute / 60, minute % 60, 0))
+            .map(|start| (start, 0))
+            .collect();
 
-        for level in &profile.levels {
-            if level.volume > max_volume {
-                max_volume = level.volume;
-                poc_price = level.price;
-            }
+        for bar in bars {
+            let Some(start) = DateTime::from_timestamp_millis(bar.timestamp_start) else {
+                continue;
+            };
+            let local = start.with_timezone(&timezone);
+            let minute = local.hour() * 60 + local.minute();
+            buckets[(minute / bucket_minutes) as usize].1 += bar.volume.unwrap_or(0);
         }
 
-        profile.poc = poc_price;
-        profile
+        buckets
     }
 
     pub fn is_high_volume_bar(&self, bar: &Bar, average_volume: f64) -> bool {
@@ -241,6 +288,75 @@ impl VolumeProfile {
         }
     }
 
+    /// Empty levels evenly spanning `min_price..max_price`; no levels when
+    /// the range is empty
+    pub fn with_price_range(min_price: f64, max_price: f64, num_levels: usize) -> Self {
+        let mut profile = Self::new(num_levels);
+        if min_price >= max_price {
+            return profile;
+        }
+
+        profile.min_price = min_price;
+        profile.max_price = max_price;
+
+        let price_step = (max_price - min_price) / num_levels as f64;
+        for i in 0..num_levels {
+            let level_price = min_price + (i as f64 + 0.5) * price_step;
+            profile.levels.push(VolumeLevel {
+                price: level_price,
+                volume: 0,
+                tick_count: 0,
+            });
+        }
+
+        profile
+    }
+
+    /// Spread a bar's volume and ticks evenly over the levels it touches
+    pub fn add_bar(&mut self, bar: &Bar) {
+        let num_levels = self.levels.len();
+        if num_levels == 0 {
+            return;
+        }
+
+        let price_step = (self.max_price - self.min_price) / num_levels as f64;
+        let volume = bar.volume.unwrap_or(0);
+        let ticks = bar.tick_count.unwrap_or(0);
+
+        // Find which levels this bar touches
+        let low_level = ((bar.low - self.min_price) / price_step).floor() as usize;
+        let high_level = ((bar.high - self.min_price) / price_step).ceil() as usize;
+
+        let num_touched_levels = (high_level - low_level + 1).max(1);
+        let volume_per_level = volume / num_touched_levels as i64;
+        let ticks_per_level = ticks / num_touched_levels as i32;
+
+        for level in self
+            .levels
+            .iter_mut()
+            .take(high_level.min(num_levels - 1) + 1)
+            .skip(low_level)
+        {
+            level.volume += volume_per_level;
+            level.tick_count += ticks_per_level;
+        }
+    }
+
+    /// Set `poc` to the level with the most volume
+    pub fn update_poc(&mut self) {
+        let mut max_volume = 0i64;
+        let mut poc_price = 0.0;
+
+        for level in &self.levels {
+            if level.volume > max_volume {
+                max_volume = level.volume;
+                poc_price = level.price;
+            }
+        }
+
+        self.poc = poc_price;
+    }
+
     pub fn get_value_area(&self, percentage: f64) -> (f64, f64) {
         if self.levels.is_empty() {
             return (0.0, 0.0);
@@ -285,7 +401,6 @@ impl VolumeProfile {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use backtestr_data::timeframe::Timeframe;
 
     fn create_test_bar_with_volume(symbol: &str, volume: i64, tick_count: i32) -> Bar {
         Bar::new(
@@ -438,4 +553,103 @@ mod tests {
         assert!(va_low >= profile.min_price);
         assert!(va_high <= profile.max_price);
     }
+
+    #[test]
+    fn test_query_volume_profile_matches_in_memory() {
+        let aggregator = VolumeAggregator::new();
+        let base = 1704067200000;
+        let bars: Vec<Bar> = (0..50)
+            .map(|i| {
+                let mid = 1.0920 + (i % 7) as f64 * 0.0003;
+                Bar::new(
+                    "EURUSD".to_string(),
+                    Timeframe::M1,
+                    base + i * 60_000,
+                    base + (i + 1) * 60_000,
+                    mid,
+                    mid + 0.0004,
+                    mid - 0.0004,
+                    mid,
+                )
+                .with_volume(1_000 + i * 10)
+            })
+            .collect();
+
+        let mut database = Database::new_memory().unwrap();
+        database.batch_insert_bars(&bars).unwrap();
+        let start = DateTime::from_timestamp_millis(base).unwrap();
+        let end = DateTime::from_timestamp_millis(base + 49 * 60_000).unwrap();
+
+        let stored = aggregator
+            .query_volume_profile(&database, "EURUSD", Timeframe::M1, start, end, 12)
+            .unwrap();
+        let expected = aggregator.calculate_volume_profile(&bars, 12);
+
+        assert_eq!(stored.poc, expected.poc);
+        assert_eq!(stored.min_price, expected.min_price);
+        assert_eq!(stored.max_price, expected.max_price);
+        let volumes = |p: &VolumeProfile| p.levels.iter().map(|l| l.volume).collect::<Vec<_>>();
+        assert_eq!(volumes(&stored), volumes(&expected));
+        assert_eq!(stored.get_value_area(70.0), expected.get_value_area(70.0));
+
+        let empty = aggregator
+            .query_volume_profile(&database, "GBPUSD", Timeframe::M1, start, end, 12)
+            .unwrap();
+        assert!(empty.levels.is_empty());
+    }
+
+    #[test]
+    fn test_volume_by_time_of_day() {
+        let aggregator = VolumeAggregator::new();
+        let bar = |start: i64, volume: i64| {
+            Bar::new(
+                "EURUSD".to_string(),
+                Timeframe::M15,
+                start,
+                start + 900_000,
+                1.09,
+                1.09,
+                1.09,
+                1.09,
+            )
+            .with_volume(volume)
+        };
+        let day = 86_400_000;
+        let base = 1704067200000; // 2024-01-01 00:00 UTC
+
+        let bars = vec![
+            // 14:00 and 14:15 on two days land in the 14:00 bucket
+            bar(base + 14 * 3_600_000, 100),
+            bar(base + 14 * 3_600_000 + 900_000, 50),
+            bar(base + day + 14 * 3_600_000, 200),
+            // 14:45 runs past 15:00 but counts towards its start
+            bar(base + 14 * 3_600_000 + 2_700_000, 25),
+            bar(base + 15 * 3_600_000, 10),
+        ];
+
+        let profile = aggregator.volume_by_time_of_day(&bars, 60);
+        assert_eq!(profile.len(), 24);
+        assert_eq!(profile[0].0, NaiveTime::from_hms_opt(0, 0, 0).unwrap());
+        assert_eq!(
+            profile[14],
+            (NaiveTime::from_hms_opt(14, 0, 0).unwrap(), 375)
+        );
+        assert_eq!(profile[15].1, 10);
+        assert_eq!(profile.iter().map(|(_, v)| v).sum::<i64>(), 385);
+
+        // 14:00 UTC is 09:00 in New York in January and 10:00 in July
+        let eastern = aggregator.volume_by_time_of_day_in(&bars, 60, chrono_tz::US::Eastern);
+        assert_eq!(eastern[9].1, 375);
+        let july = 1719792000000; // 2024-07-01 00:00 UTC
+        let summer = aggregator.volume_by_time_of_day_in(
+            &[bar(july + 14 * 3_600_000, 40)],
+            30,
+            chrono_tz::US::Eastern,
+        );
+        assert_eq!(summer.len(), 48);
+        assert_eq!(summer[20], (NaiveTime::from_hms_opt(10, 0, 0).unwrap(), 40));
+
+        assert!(aggregator.volume_by_time_of_day(&bars, 0).is_empty());
+        assert_eq!(aggregator.volume_by_time_of_day(&bars, 7 * 60).len(), 4);
+    }
 }
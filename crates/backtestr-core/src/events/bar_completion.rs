@@ -1,7 +1,9 @@
 use backtestr_data::models::Bar;
+use backtestr_data::timeframe::Timeframe;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum BarCompletionEvent {
     MinuteBar(Bar),
     FiveMinuteBar(Bar),
@@ -9,9 +11,25 @@ pub enum BarCompletionEvent {
     HourBar(Bar),
     FourHourBar(Bar),
     DailyBar(Bar),
+    WeeklyBar(Bar),
+    MonthlyBar(Bar),
 }
 
 impl BarCompletionEvent {
+    /// Completion event for a bar of `timeframe`
+    pub fn for_timeframe(timeframe: Timeframe, bar: Bar) -> Self {
+        match timeframe {
+            Timeframe::M1 => Self::MinuteBar(bar),
+            Timeframe::M5 => Self::FiveMinuteBar(bar),
+            Timeframe::M15 => Self::FifteenMinuteBar(bar),
+            Timeframe::H1 => Self::HourBar(bar),
+            Timeframe::H4 => Self::FourHourBar(bar),
+            Timeframe::D1 => Self::DailyBar(bar),
+            Timeframe::W1 => Self::WeeklyBar(bar),
+            Timeframe::MN1 => Self::MonthlyBar(bar),
+        }
+    }
+
     pub fn bar(&self) -> &Bar {
         match self {
             Self::MinuteBar(bar)
@@ -19,7 +37,9 @@ impl BarCompletionEvent {
             | Self::FifteenMinuteBar(bar)
             | Self::HourBar(bar)
             | Self::FourHourBar(bar)
-            | Self::DailyBar(bar) => bar,
+            | Self::DailyBar(bar)
+            | Self::WeeklyBar(bar)
+            | Self::MonthlyBar(bar) => bar,
         }
     }
 
@@ -31,9 +51,15 @@ impl BarCompletionEvent {
             Self::HourBar(_) => "1H",
             Self::FourHourBar(_) => "4H",
             Self::DailyBar(_) => "D1",
+            Self::WeeklyBar(_) => "W1",
+            Self::MonthlyBar(_) => "MN1",
         }
     }
 
+    pub fn symbol(&self) -> &str {
+        &self.bar().symbol
+    }
+
     pub fn timestamp(&self) -> i64 {
         self.bar().timestamp_end
     }
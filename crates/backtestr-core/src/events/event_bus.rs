@@ -6,12 +6,15 @@ type EventCallback = Arc<dyn Fn(&BarCompletionEvent) + Send + Sync>;
 
 pub struct EventBus {
     subscribers: Arc<Mutex<HashMap<String, Vec<EventCallback>>>>,
+    /// Callbacks indexed by symbol, so unsubscribed symbols cost one lookup
+    symbol_subscribers: Arc<Mutex<HashMap<String, Vec<EventCallback>>>>,
 }
 
 impl EventBus {
     pub fn new() -> Self {
         Self {
             subscribers: Arc::new(Mutex::new(HashMap::new())),
+            symbol_subscribers: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -27,6 +30,23 @@ impl EventBus {
         SubscriptionHandle {
             event_type: event_type.to_string(),
             callback_id: callbacks.len() - 1,
+            scope: SubscriptionScope::EventType,
+        }
+    }
+
+    /// Subscribe to every event for bars of `symbol`, on any timeframe
+    pub fn subscribe_symbol<F>(&self, symbol: &str, callback: F) -> SubscriptionHandle
+    where
+        F: Fn(&BarCompletionEvent) + Send + Sync + 'static,
+    {
+        let mut subs = self.symbol_subscribers.lock().unwrap();
+        let callbacks = subs.entry(symbol.to_string()).or_default();
+        callbacks.push(Arc::new(callback));
+
+        SubscriptionHandle {
+            event_type: symbol.to_string(),
+            callback_id: callbacks.len() - 1,
+            scope: SubscriptionScope::Symbol,
         }
     }
 
@@ -54,10 +74,22 @@ impl EventBus {
                 callback(&event);
             }
         }
+        drop(subs);
+
+        // Call symbol subscribers
+        let symbol_subs = self.symbol_subscribers.lock().unwrap();
+        if let Some(callbacks) = symbol_subs.get(event.symbol()) {
+            for callback in callbacks {
+                callback(&event);
+            }
+        }
     }
 
     pub fn unsubscribe(&self, handle: SubscriptionHandle) {
-        let mut subs = self.subscribers.lock().unwrap();
+        let mut subs = match handle.scope {
+            SubscriptionScope::EventType => self.subscribers.lock().unwrap(),
+            SubscriptionScope::Symbol => self.symbol_subscribers.lock().unwrap(),
+        };
         if let Some(callbacks) = subs.get_mut(&handle.event_type) {
             if handle.callback_id < callbacks.len() {
                 callbacks.remove(handle.callback_id);
@@ -73,12 +105,18 @@ impl EventBus {
     pub fn clear_all_subscribers(&self) {
         let mut subs = self.subscribers.lock().unwrap();
         subs.clear();
+        self.symbol_subscribers.lock().unwrap().clear();
     }
 
     pub fn subscriber_count(&self, event_type: &str) -> usize {
         let subs = self.subscribers.lock().unwrap();
         subs.get(event_type).map(|v| v.len()).unwrap_or(0)
     }
+
+    pub fn symbol_subscriber_count(&self, symbol: &str) -> usize {
+        let subs = self.symbol_subscribers.lock().unwrap();
+        subs.get(symbol).map(|v| v.len()).unwrap_or(0)
+    }
 }
 
 impl Default for EventBus {
@@ -91,14 +129,23 @@ impl Clone for EventBus {
     fn clone(&self) -> Self {
         Self {
             subscribers: Arc::clone(&self.subscribers),
+            symbol_subscribers: Arc::clone(&self.symbol_subscribers),
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SubscriptionScope {
+    EventType,
+    Symbol,
+}
+
 #[derive(Debug)]
 pub struct SubscriptionHandle {
+    /// Event type, or symbol for symbol subscriptions
     event_type: String,
     callback_id: usize,
+    scope: SubscriptionScope,
 }
 
 #[cfg(test)]
@@ -207,4 +254,44 @@ mod tests {
         assert_eq!(event_bus.subscriber_count("5M"), 1);
         assert_eq!(event_bus.subscriber_count("1H"), 0);
     }
+
+    #[test]
+    fn test_symbol_subscription() {
+        let event_bus = EventBus::new();
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = Arc::clone(&counter);
+
+        let handle = event_bus.subscribe_symbol("GBPUSD", move |event| {
+            assert_eq!(event.symbol(), "GBPUSD");
+            counter_clone.fetch_add(1, Ordering::SeqCst);
+        });
+        assert_eq!(event_bus.symbol_subscriber_count("GBPUSD"), 1);
+
+        let bar = |symbol: &str, timeframe: Timeframe| {
+            Bar::new(
+                symbol.to_string(),
+                timeframe,
+                1704067200000,
+                1704067200000 + timeframe.duration_ms(),
+                1.0920,
+                1.0925,
+                1.0915,
+                1.0922,
+            )
+        };
+
+        event_bus.publish(BarCompletionEvent::MinuteBar(bar("EURUSD", Timeframe::M1)));
+        event_bus.publish(BarCompletionEvent::MinuteBar(bar("GBPUSD", Timeframe::M1)));
+        event_bus.publish(BarCompletionEvent::HourBar(bar("GBPUSD", Timeframe::H1)));
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+
+        // Symbol handles only remove symbol subscriptions
+        event_bus.subscribe("1M", |_event| {});
+        event_bus.unsubscribe(handle);
+        assert_eq!(event_bus.symbol_subscriber_count("GBPUSD"), 0);
+        assert_eq!(event_bus.subscriber_count("1M"), 1);
+
+        event_bus.publish(BarCompletionEvent::MinuteBar(bar("GBPUSD", Timeframe::M1)));
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
 }
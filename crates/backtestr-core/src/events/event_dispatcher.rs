@@ -1,52 +1,272 @@
-use crate::events::{BarEvent, TickEvent};
-use std::sync::Arc;
+use crate::events::{BarEvent, IndicatorUpdateEvent, TickEvent};
+use crossbeam::channel::{self, Sender};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::thread::{JoinHandle, ThreadId};
+use tracing::error;
 
 pub trait EventHandler: Send + Sync {
     fn on_tick(&self, event: &TickEvent);
     fn on_bar(&self, event: &BarEvent);
+
+    /// Called after attached indicators were updated from a completed bar
+    fn on_indicator_update(&self, _event: &IndicatorUpdateEvent) {}
 }
 
-#[derive(Clone)]
+type HandlerList = Arc<RwLock<Vec<Arc<dyn EventHandler>>>>;
+
+enum DispatchMessage {
+    Tick(TickEvent),
+    Bar(BarEvent),
+    IndicatorUpdate(IndicatorUpdateEvent),
+    /// Acknowledged once every earlier message has been handled
+    Flush(Sender<()>),
+}
+
+/// Bounded queue drained by a worker thread that runs the handlers
+struct AsyncQueue {
+    sender: Option<Sender<DispatchMessage>>,
+    max_depth: AtomicUsize,
+    /// Handler calls that panicked on the worker and were skipped
+    panics: Arc<AtomicUsize>,
+    worker_id: ThreadId,
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl AsyncQueue {
+    fn new(capacity: usize, handlers: HandlerList) -> Self {
+        let (sender, receiver) = channel::bounded::<DispatchMessage>(capacity);
+        let panics = Arc::new(AtomicUsize::new(0));
+        let worker_panics = Arc::clone(&panics);
+
+        // A panicking handler is logged and skipped; letting it unwind
+        // would kill the worker and silently drop every later event
+        let run = move |call: &dyn Fn(&dyn EventHandler)| {
+            let snapshot = handlers.read().unwrap().clone();
+            for handler in snapshot.iter() {
+                if panic::catch_unwind(AssertUnwindSafe(|| call(handler.as_ref()))).is_err() {
+                    worker_panics.fetch_add(1, Ordering::Relaxed);
+                    error!("Event handler panicked; continuing with the next handler");
+                }
+            }
+        };
+
+        let worker = std::thread::spawn(move || {
+            for message in receiver {
+                match message {
+                    DispatchMessage::Tick(event) => run(&|handler| handler.on_tick(&event)),
+                    DispatchMessage::Bar(event) => run(&|handler| handler.on_bar(&event)),
+                    DispatchMessage::IndicatorUpdate(event) => {
+                        run(&|handler| handler.on_indicator_update(&event))
+                    }
+                    DispatchMessage::Flush(ack) => {
+                        let _ = ack.send(());
+                    }
+                }
+            }
+        });
+
+        Self {
+            sender: Some(sender),
+            max_depth: AtomicUsize::new(0),
+            panics,
+            worker_id: worker.thread().id(),
+            worker: Mutex::new(Some(worker)),
+        }
+    }
+
+    /// Enqueue a message, blocking while the queue is full so no event is dropped
+    fn send(&self, message: DispatchMessage) {
+        // The worker would wait on a full queue only it can drain
+        debug_assert_ne!(
+            std::thread::current().id(),
+            self.worker_id,
+            "event handlers must not dispatch to or flush their own async dispatcher"
+        );
+        if let Some(sender) = &self.sender {
+            if sender.send(message).is_ok() {
+                self.max_depth.fetch_max(sender.len(), Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+impl Drop for AsyncQueue {
+    fn drop(&mut self) {
+        // Closing the channel lets the worker finish queued events and exit
+        self.sender.take();
+        // A handler may drop the last upgraded handle on the worker itself,
+        // which then exits once the handler returns; it cannot join itself
+        if std::thread::current().id() == self.worker_id {
+            return;
+        }
+        if let Some(worker) = self.worker.lock().unwrap().take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Fans tick and bar events out to registered handlers.
+///
+/// `new` dispatches synchronously on the caller's thread. `new_async` hands
+/// events to a worker thread through a bounded queue so slow handlers do not
+/// stall tick processing; when the queue is full, dispatch blocks until
+/// there is room rather than dropping events. Call `flush` to wait until
+/// every queued event has been handled.
+///
+/// A handler that panics during synchronous dispatch unwinds into the
+/// caller. On the async worker the panic is caught and logged, counted in
+/// `handler_panic_count`, and the remaining handlers and events still run.
+/// Handlers of an async dispatcher must not dispatch to or flush that same
+/// dispatcher: the worker would wait on a queue only it can drain.
+///
+/// Clones share the sequence counter. Clones of an async dispatcher also
+/// share its worker queue and handler list, so a handler added through one
+/// clone receives events dispatched through any of them; clones of a
+/// synchronous dispatcher copy the handler list and stay independent.
+/// Handlers run on a snapshot of the list, so they may add handlers
+/// through a clone; those take effect from the next event. A handler that
+/// keeps a dispatcher should hold a `downgrade`d handle: a full clone of an
+/// async dispatcher in its own handler list keeps the worker alive forever.
 pub struct EventDispatcher {
-    handlers: Vec<Arc<dyn EventHandler>>,
+    handlers: HandlerList,
     sequence_counter: Arc<std::sync::atomic::AtomicU64>,
+    async_queue: Option<Arc<AsyncQueue>>,
 }
 
 impl EventDispatcher {
     pub fn new() -> Self {
         Self {
-            handlers: Vec::new(),
+            handlers: Arc::new(RwLock::new(Vec::new())),
             sequence_counter: Arc::new(std::sync::atomic::AtomicU64::new(0)),
+            async_queue: None,
         }
     }
 
+    /// Dispatch on a worker thread through a queue holding up to `capacity`
+    /// events. A zero capacity is rejected; it would make every dispatch a
+    /// rendezvous with the worker.
+    pub fn new_async(capacity: usize) -> Result<Self, String> {
+        if capacity == 0 {
+            return Err("async dispatch capacity must be at least 1".to_string());
+        }
+
+        let dispatcher = Self::new();
+        let queue = AsyncQueue::new(capacity, Arc::clone(&dispatcher.handlers));
+        Ok(Self {
+            async_queue: Some(Arc::new(queue)),
+            ..dispatcher
+        })
+    }
+
+    pub fn is_async(&self) -> bool {
+        self.async_queue.is_some()
+    }
+
+    /// Handle that does not keep this dispatcher, or its async worker,
+    /// alive; for handlers that add handlers or dispatch themselves
+    pub fn downgrade(&self) -> WeakEventDispatcher {
+        WeakEventDispatcher {
+            handlers: Arc::downgrade(&self.handlers),
+            sequence_counter: Arc::downgrade(&self.sequence_counter),
+            async_queue: self.async_queue.as_ref().map(Arc::downgrade),
+        }
+    }
+
+    /// The handlers at this moment, so none is called with the list locked
+    fn snapshot(&self) -> Vec<Arc<dyn EventHandler>> {
+        self.handlers.read().unwrap().clone()
+    }
+
     pub fn add_handler(&mut self, handler: Arc<dyn EventHandler>) {
-        self.handlers.push(handler);
+        self.handlers.write().unwrap().push(handler);
     }
 
     pub fn dispatch_tick(&self, event: &TickEvent) {
-        for handler in &self.handlers {
+        if let Some(queue) = &self.async_queue {
+            queue.send(DispatchMessage::Tick(event.clone()));
+            return;
+        }
+
+        for handler in self.snapshot() {
             handler.on_tick(event);
         }
     }
 
     pub fn dispatch_bar(&self, event: &BarEvent) {
-        for handler in &self.handlers {
+        if let Some(queue) = &self.async_queue {
+            queue.send(DispatchMessage::Bar(event.clone()));
+            return;
+        }
+
+        for handler in self.snapshot() {
             handler.on_bar(event);
         }
     }
 
+    pub fn dispatch_indicator_update(&self, event: &IndicatorUpdateEvent) {
+        if let Some(queue) = &self.async_queue {
+            queue.send(DispatchMessage::IndicatorUpdate(event.clone()));
+            return;
+        }
+
+        for handler in self.snapshot() {
+            handler.on_indicator_update(event);
+        }
+    }
+
+    /// Block until all queued events have been handled. No-op when synchronous.
+    pub fn flush(&self) {
+        if let Some(queue) = &self.async_queue {
+            let (ack_sender, ack_receiver) = channel::bounded(1);
+            queue.send(DispatchMessage::Flush(ack_sender));
+            let _ = ack_receiver.recv();
+        }
+    }
+
+    /// Handler calls that panicked on the async worker; 0 when synchronous,
+    /// where panics reach the caller instead
+    pub fn handler_panic_count(&self) -> usize {
+        self.async_queue
+            .as_ref()
+            .map(|queue| queue.panics.load(Ordering::Relaxed))
+            .unwrap_or(0)
+    }
+
+    /// Deepest the async queue has been after an enqueue; 0 when synchronous
+    pub fn max_queue_depth(&self) -> usize {
+        self.async_queue
+            .as_ref()
+            .map(|queue| queue.max_depth.load(Ordering::Relaxed))
+            .unwrap_or(0)
+    }
+
     pub fn next_sequence(&self) -> u64 {
         self.sequence_counter
             .fetch_add(1, std::sync::atomic::Ordering::SeqCst)
     }
 
     pub fn clear_handlers(&mut self) {
-        self.handlers.clear();
+        self.handlers.write().unwrap().clear();
     }
 
     pub fn handler_count(&self) -> usize {
-        self.handlers.len()
+        self.handlers.read().unwrap().len()
+    }
+}
+
+impl Clone for EventDispatcher {
+    fn clone(&self) -> Self {
+        let handlers = match self.async_queue {
+            Some(_) => Arc::clone(&self.handlers),
+            None => Arc::new(RwLock::new(self.snapshot())),
+        };
+        Self {
+            handlers,
+            sequence_counter: Arc::clone(&self.sequence_counter),
+            async_queue: self.async_queue.clone(),
+        }
     }
 }
 
@@ -56,6 +276,29 @@ impl Default for EventDispatcher {
     }
 }
 
+/// Weak counterpart of `EventDispatcher`, from `EventDispatcher::downgrade`
+#[derive(Clone)]
+pub struct WeakEventDispatcher {
+    handlers: Weak<RwLock<Vec<Arc<dyn EventHandler>>>>,
+    sequence_counter: Weak<std::sync::atomic::AtomicU64>,
+    async_queue: Option<Weak<AsyncQueue>>,
+}
+
+impl WeakEventDispatcher {
+    /// The dispatcher, unless every `EventDispatcher` sharing it is gone
+    pub fn upgrade(&self) -> Option<EventDispatcher> {
+        let async_queue = match &self.async_queue {
+            Some(queue) => Some(queue.upgrade()?),
+            None => None,
+        };
+        Some(EventDispatcher {
+            handlers: self.handlers.upgrade()?,
+            sequence_counter: self.sequence_counter.upgrade()?,
+            async_queue,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -169,4 +412,255 @@ mod tests {
         dispatcher.clear_handlers();
         assert_eq!(dispatcher.handler_count(), 0);
     }
+
+    struct SlowHandler {
+        ticks: Arc<Mutex<Vec<i64>>>,
+    }
+
+    impl EventHandler for SlowHandler {
+        fn on_tick(&self, event: &TickEvent) {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            self.ticks.lock().unwrap().push(event.tick.timestamp);
+        }
+
+        fn on_bar(&self, _event: &BarEvent) {}
+    }
+
+    #[test]
+    fn test_async_dispatch_rejects_zero_capacity() {
+        assert!(EventDispatcher::new_async(0).is_err());
+    }
+
+    #[test]
+    fn test_async_dispatch_preserves_order_without_drops() {
+        let mut dispatcher = EventDispatcher::new_async(4).unwrap();
+        assert!(dispatcher.is_async());
+
+        let ticks = Arc::new(Mutex::new(Vec::new()));
+        dispatcher.add_handler(Arc::new(SlowHandler {
+            ticks: ticks.clone(),
+        }));
+
+        // Far more events than the queue holds; dispatch blocks instead of dropping
+        for i in 0..50 {
+            let tick = Tick::new_with_millis("EURUSD".to_string(), i, 1.0920, 1.0922);
+            dispatcher.dispatch_tick(&TickEvent::from_tick(tick));
+        }
+        dispatcher.flush();
+
+        let seen = ticks.lock().unwrap();
+        assert_eq!(*seen, (0..50).collect::<Vec<_>>());
+        assert!(dispatcher.max_queue_depth() <= 4);
+        assert!(dispatcher.max_queue_depth() > 0);
+    }
+
+    #[test]
+    fn test_async_flush_runs_bar_handlers() {
+        let mut dispatcher = EventDispatcher::new_async(16).unwrap();
+        let handler = Arc::new(TestHandler::new());
+        let bar_count = handler.bar_count.clone();
+        dispatcher.add_handler(handler);
+
+        let bar = Bar::new(
+            "EURUSD".to_string(),
+            Timeframe::M1,
+            1704067200000,
+            1704067260000,
+            1.0920,
+            1.0925,
+            1.0918,
+            1.0923,
+        );
+        for sequence in 0..3 {
+            dispatcher.dispatch_bar(&BarEvent::bar_closed(bar.clone(), sequence));
+        }
+        dispatcher.flush();
+
+        assert_eq!(*bar_count.lock().unwrap(), 3);
+        assert_eq!(EventDispatcher::new().max_queue_depth(), 0);
+    }
+
+    struct PanicOnFirstTick {
+        ticks: Arc<Mutex<Vec<i64>>>,
+    }
+
+    impl EventHandler for PanicOnFirstTick {
+        fn on_tick(&self, event: &TickEvent) {
+            if event.tick.timestamp == 0 {
+                panic!("handler failure");
+            }
+            self.ticks.lock().unwrap().push(event.tick.timestamp);
+        }
+
+        fn on_bar(&self, _event: &BarEvent) {}
+    }
+
+    #[test]
+    fn test_async_worker_survives_handler_panic() {
+        let mut dispatcher = EventDispatcher::new_async(4).unwrap();
+        let ticks = Arc::new(Mutex::new(Vec::new()));
+        dispatcher.add_handler(Arc::new(PanicOnFirstTick {
+            ticks: ticks.clone(),
+        }));
+        let counter = Arc::new(TestHandler::new());
+        let tick_count = counter.tick_count.clone();
+        dispatcher.add_handler(counter);
+
+        for i in 0..3 {
+            let tick = Tick::new_with_millis("EURUSD".to_string(), i, 1.0920, 1.0922);
+            dispatcher.dispatch_tick(&TickEvent::from_tick(tick));
+        }
+        dispatcher.flush();
+
+        // Later handlers and later events still run
+        assert_eq!(*ticks.lock().unwrap(), vec![1, 2]);
+        assert_eq!(*tick_count.lock().unwrap(), 3);
+        assert_eq!(dispatcher.handler_panic_count(), 1);
+    }
+
+    #[test]
+    fn test_clones_share_handlers_only_when_async() {
+        let tick = || {
+            TickEvent::from_tick(Tick::new_with_millis(
+                "EURUSD".to_string(),
+                0,
+                1.0920,
+                1.0922,
+            ))
+        };
+
+        let mut sync = EventDispatcher::new();
+        let clone = sync.clone();
+        sync.add_handler(Arc::new(TestHandler::new()));
+        assert_eq!(clone.handler_count(), 0);
+
+        let mut dispatcher = EventDispatcher::new_async(4).unwrap();
+        let clone = dispatcher.clone();
+        let handler = Arc::new(TestHandler::new());
+        let tick_count = handler.tick_count.clone();
+        dispatcher.add_handler(handler);
+        assert_eq!(clone.handler_count(), 1);
+
+        clone.dispatch_tick(&tick());
+        clone.flush();
+        assert_eq!(*tick_count.lock().unwrap(), 1);
+    }
+
+    struct AddsHandlerOnTick {
+        dispatcher: WeakEventDispatcher,
+        added: Arc<TestHandler>,
+    }
+
+    impl EventHandler for AddsHandlerOnTick {
+        fn on_tick(&self, _event: &TickEvent) {
+            if let Some(mut dispatcher) = self.dispatcher.upgrade() {
+                dispatcher.add_handler(self.added.clone());
+            }
+        }
+
+        fn on_bar(&self, _event: &BarEvent) {}
+    }
+
+    #[test]
+    fn test_handler_can_add_handler_during_dispatch() {
+        let mut dispatcher = EventDispatcher::new_async(4).unwrap();
+        let added = Arc::new(TestHandler::new());
+        let tick_count = added.tick_count.clone();
+        dispatcher.add_handler(Arc::new(AddsHandlerOnTick {
+            dispatcher: dispatcher.downgrade(),
+            added,
+        }));
+
+        let tick = Tick::new_with_millis("EURUSD".to_string(), 0, 1.0920, 1.0922);
+        dispatcher.dispatch_tick(&TickEvent::from_tick(tick.clone()));
+        dispatcher.flush();
+        assert_eq!(dispatcher.handler_count(), 2);
+        // The new handler starts with the next event
+        assert_eq!(*tick_count.lock().unwrap(), 0);
+
+        dispatcher.dispatch_tick(&TickEvent::from_tick(tick));
+        dispatcher.flush();
+        assert_eq!(*tick_count.lock().unwrap(), 1);
+    }
+
+    /// Sends on `dropped` when the worker lets go of it
+    struct DropSignal {
+        dispatcher: WeakEventDispatcher,
+        dropped: Mutex<std::sync::mpsc::Sender<()>>,
+    }
+
+    impl EventHandler for DropSignal {
+        fn on_tick(&self, _event: &TickEvent) {
+            assert!(self.dispatcher.upgrade().is_some());
+        }
+
+        fn on_bar(&self, _event: &BarEvent) {}
+    }
+
+    impl Drop for DropSignal {
+        fn drop(&mut self) {
+            let _ = self.dropped.lock().unwrap().send(());
+        }
+    }
+
+    #[test]
+    fn test_dropping_async_dispatcher_stops_worker() {
+        let (sender, dropped) = std::sync::mpsc::channel();
+        let mut dispatcher = EventDispatcher::new_async(4).unwrap();
+        let weak = dispatcher.downgrade();
+        dispatcher.add_handler(Arc::new(DropSignal {
+            dispatcher: weak.clone(),
+            dropped: Mutex::new(sender),
+        }));
+
+        let tick = Tick::new_with_millis("EURUSD".to_string(), 0, 1.0920, 1.0922);
+        dispatcher.dispatch_tick(&TickEvent::from_tick(tick));
+        drop(dispatcher);
+
+        // The worker finished and released its handlers
+        assert!(dropped.try_recv().is_ok());
+        assert!(weak.upgrade().is_none());
+    }
+
+    /// Holds the only dispatcher handle left while its tick is handled
+    struct DropsLastHandle {
+        dispatcher: Mutex<Option<EventDispatcher>>,
+        go: Mutex<std::sync::mpsc::Receiver<()>>,
+        done: Mutex<std::sync::mpsc::Sender<()>>,
+    }
+
+    impl EventHandler for DropsLastHandle {
+        fn on_tick(&self, _event: &TickEvent) {
+            // Wait until the test has dropped its own handle
+            let _ = self.go.lock().unwrap().recv();
+            drop(self.dispatcher.lock().unwrap().take());
+            let _ = self.done.lock().unwrap().send(());
+        }
+
+        fn on_bar(&self, _event: &BarEvent) {}
+    }
+
+    #[test]
+    fn test_last_handle_dropped_on_worker_does_not_join_itself() {
+        let (go, go_receiver) = std::sync::mpsc::channel();
+        let (sender, done) = std::sync::mpsc::channel();
+        let mut dispatcher = EventDispatcher::new_async(4).unwrap();
+        let handler = Arc::new(DropsLastHandle {
+            dispatcher: Mutex::new(None),
+            go: Mutex::new(go_receiver),
+            done: Mutex::new(sender),
+        });
+        dispatcher.add_handler(handler.clone());
+
+        let tick = Tick::new_with_millis("EURUSD".to_string(), 0, 1.0920, 1.0922);
+        let weak = dispatcher.downgrade();
+        *handler.dispatcher.lock().unwrap() = Some(dispatcher.clone());
+        dispatcher.dispatch_tick(&TickEvent::from_tick(tick));
+        drop(dispatcher);
+        go.send(()).unwrap();
+
+        done.recv_timeout(std::time::Duration::from_secs(5))
+            .expect("worker dropped the last handle without finishing");
+        assert!(weak.upgrade().is_none());
+    }
 }
@@ -0,0 +1,316 @@
+//! Recording and replay of event streams for deterministic debugging.
+//!
+//! `EventRecorder` captures every `BarCompletionEvent` published on an
+//! `EventBus` and every `TickEvent` it is handed (directly or as an
+//! `EventHandler` on an `EventDispatcher`), numbering them in arrival order.
+//! Recordings are stored as bincode compressed with ZSTD, the same format
+//! used for checkpoints. `EventReplayer` loads a recording and republishes
+//! the events in their original order.
+//!
+//! bincode is not self-describing, so every recording starts with a
+//! `RecordingHeader` carrying `RECORDING_VERSION`, and a recording from
+//! another version is rejected instead of misread.
+//!
+//! Version history:
+//! - 1: first versioned layout, with `Tick::last` and `Tick::last_size`.
+//!   Older recordings have no header and must be recorded again.
+
+use super::{BarCompletionEvent, BarEvent, EventBus, EventDispatcher, EventHandler};
+use super::{SubscriptionHandle, TickEvent};
+use crate::persistence::compression::{compress_data, decompress_data};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Marks a versioned recording; headerless recordings start with the event count
+const RECORDING_MAGIC: [u8; 4] = *b"BTEV";
+
+/// Current recording layout version
+pub const RECORDING_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordingHeader {
+    magic: [u8; 4],
+    version: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RecordedEventKind {
+    Bar(BarCompletionEvent),
+    Tick(TickEvent),
+}
+
+/// An event with its position in the recorded stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// 0-based publish order across all event kinds
+    pub sequence: u64,
+    /// Market timestamp of the event (bar close or tick time)
+    pub timestamp: i64,
+    pub kind: RecordedEventKind,
+}
+
+#[derive(Clone, Default)]
+pub struct EventRecorder {
+    events: Arc<Mutex<Vec<RecordedEvent>>>,
+}
+
+impl EventRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record every bar completion event published on `bus`
+    pub fn attach(&self, bus: &EventBus) -> SubscriptionHandle {
+        let recorder = self.clone();
+        bus.subscribe_all(move |event| recorder.record_bar(event))
+    }
+
+    pub fn record_bar(&self, event: &BarCompletionEvent) {
+        self.push(event.timestamp(), RecordedEventKind::Bar(event.clone()));
+    }
+
+    pub fn record_tick(&self, event: &TickEvent) {
+        self.push(event.tick.timestamp, RecordedEventKind::Tick(event.clone()));
+    }
+
+    pub fn events(&self) -> Vec<RecordedEvent> {
+        self.events.lock().unwrap().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.events.lock().unwrap().clear();
+    }
+
+    /// Write the recording to `path`
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let header = RecordingHeader {
+            magic: RECORDING_MAGIC,
+            version: RECORDING_VERSION,
+        };
+        let mut serialized =
+            bincode::serialize(&header).context("Failed to serialize recording header")?;
+        bincode::serialize_into(&mut serialized, &self.events())
+            .context("Failed to serialize events")?;
+        let compressed = compress_data(&serialized, COMPRESSION_LEVEL)?;
+        fs::write(path, compressed)
+            .with_context(|| format!("Failed to write recording: {}", path.display()))
+    }
+
+    fn push(&self, timestamp: i64, kind: RecordedEventKind) {
+        // Sequence is assigned under the lock so it matches the stored order
+        let mut events = self.events.lock().unwrap();
+        let sequence = events.len() as u64;
+        events.push(RecordedEvent {
+            sequence,
+            timestamp,
+            kind,
+        });
+    }
+}
+
+impl EventHandler for EventRecorder {
+    fn on_tick(&self, event: &TickEvent) {
+        self.record_tick(event);
+    }
+
+    fn on_bar(&self, _event: &BarEvent) {}
+}
+
+/// Replays a recording produced by `EventRecorder::save`
+#[derive(Debug, Clone)]
+pub struct EventReplayer {
+    events: Vec<RecordedEvent>,
+}
+
+impl EventReplayer {
+    pub fn load(path: &Path) -> Result<Self> {
+        let compressed = fs::read(path)
+            .with_context(|| format!("Failed to read recording: {}", path.display()))?;
+        let decompressed = decompress_data(&compressed)?;
+        let mut reader = decompressed.as_slice();
+        let header: RecordingHeader = bincode::deserialize_from(&mut reader)
+            .context("Failed to deserialize recording header")?;
+        if header.magic != RECORDING_MAGIC {
+            bail!(
+                "Recording {} has no version header; it predates versioned recordings and must be recorded again",
+                path.display()
+            );
+        }
+        if header.version != RECORDING_VERSION {
+            bail!(
+                "Incompatible recording version in {}: expected {}, got {}",
+                path.display(),
+                RECORDING_VERSION,
+                header.version
+            );
+        }
+        let mut events: Vec<RecordedEvent> =
+            bincode::deserialize_from(reader).context("Failed to deserialize events")?;
+        events.sort_by_key(|event| event.sequence);
+        Ok(Self { events })
+    }
+
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+
+    /// Publish the recorded bar events into `bus`, returning how many were sent
+    pub fn replay(&self, bus: &EventBus) -> usize {
+        let mut published = 0;
+        for event in &self.events {
+            if let RecordedEventKind::Bar(bar_event) = &event.kind {
+                bus.publish(bar_event.clone());
+                published += 1;
+            }
+        }
+        published
+    }
+
+    /// Replay the whole stream in its original order: bar events into `bus`,
+    /// tick events into `dispatcher`
+    pub fn replay_all(&self, bus: &EventBus, dispatcher: &EventDispatcher) -> usize {
+        for event in &self.events {
+            match &event.kind {
+                RecordedEventKind::Bar(bar_event) => bus.publish(bar_event.clone()),
+                RecordedEventKind::Tick(tick_event) => dispatcher.dispatch_tick(tick_event),
+            }
+        }
+        self.events.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backtestr_data::models::{Bar, Tick};
+    use backtestr_data::timeframe::Timeframe;
+    use tempfile::TempDir;
+
+    fn bar(timeframe: Timeframe, start: i64) -> Bar {
+        Bar::new(
+            "EURUSD".to_string(),
+            timeframe,
+            start,
+            start + timeframe.duration_ms(),
+            1.0920,
+            1.0925,
+            1.0915,
+            1.0922,
+        )
+    }
+
+    fn names(bus: &EventBus) -> Arc<Mutex<Vec<String>>> {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        bus.subscribe_all(move |event| {
+            sink.lock()
+                .unwrap()
+                .push(event.timeframe_name().to_string())
+        });
+        seen
+    }
+
+    #[test]
+    fn test_record_save_and_replay_preserves_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("events.bin");
+
+        let bus = EventBus::new();
+        let recorder = EventRecorder::new();
+        recorder.attach(&bus);
+
+        let start = 1704067200000;
+        bus.publish(BarCompletionEvent::MinuteBar(bar(Timeframe::M1, start)));
+        bus.publish(BarCompletionEvent::FiveMinuteBar(bar(Timeframe::M5, start)));
+        bus.publish(BarCompletionEvent::FifteenMinuteBar(bar(
+            Timeframe::M15,
+            start,
+        )));
+        bus.publish(BarCompletionEvent::MinuteBar(bar(
+            Timeframe::M1,
+            start + 60_000,
+        )));
+        recorder.save(&path).unwrap();
+
+        let replayer = EventReplayer::load(&path).unwrap();
+        assert_eq!(replayer.events().len(), 4);
+
+        let fresh = EventBus::new();
+        let seen = names(&fresh);
+        assert_eq!(replayer.replay(&fresh), 4);
+        assert_eq!(*seen.lock().unwrap(), vec!["1M", "5M", "15M", "1M"]);
+    }
+
+    #[test]
+    fn test_ticks_interleaved_with_bars() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("events.bin");
+
+        let bus = EventBus::new();
+        let recorder = EventRecorder::new();
+        recorder.attach(&bus);
+        let mut dispatcher = EventDispatcher::new();
+        dispatcher.add_handler(Arc::new(recorder.clone()));
+
+        let start = 1704067200000;
+        let tick = Tick::new_with_millis("EURUSD".to_string(), start + 1_000, 1.0920, 1.0922);
+        dispatcher.dispatch_tick(&TickEvent::from_tick(tick));
+        bus.publish(BarCompletionEvent::MinuteBar(bar(Timeframe::M1, start)));
+        recorder.save(&path).unwrap();
+
+        let replayer = EventReplayer::load(&path).unwrap();
+        let events = replayer.events();
+        assert!(matches!(events[0].kind, RecordedEventKind::Tick(_)));
+        assert_eq!(events[0].timestamp, start + 1_000);
+        assert!(matches!(events[1].kind, RecordedEventKind::Bar(_)));
+        assert_eq!(events[1].sequence, 1);
+
+        let replay_recorder = EventRecorder::new();
+        let fresh_bus = EventBus::new();
+        replay_recorder.attach(&fresh_bus);
+        let mut fresh_dispatcher = EventDispatcher::new();
+        fresh_dispatcher.add_handler(Arc::new(replay_recorder.clone()));
+
+        assert_eq!(replayer.replay_all(&fresh_bus, &fresh_dispatcher), 2);
+        let replayed = replay_recorder.events();
+        assert!(matches!(replayed[0].kind, RecordedEventKind::Tick(_)));
+        assert!(matches!(replayed[1].kind, RecordedEventKind::Bar(_)));
+    }
+
+    #[test]
+    fn test_unversioned_and_other_version_recordings_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // The pre-header layout: just the serialized event list
+        let legacy = temp_dir.path().join("legacy.bin");
+        let events: Vec<RecordedEvent> = Vec::new();
+        let payload = bincode::serialize(&events).unwrap();
+        fs::write(&legacy, compress_data(&payload, COMPRESSION_LEVEL).unwrap()).unwrap();
+        let err = EventReplayer::load(&legacy).unwrap_err();
+        assert!(err.to_string().contains("no version header"));
+
+        let newer = temp_dir.path().join("newer.bin");
+        let header = RecordingHeader {
+            magic: RECORDING_MAGIC,
+            version: RECORDING_VERSION + 1,
+        };
+        let mut payload = bincode::serialize(&header).unwrap();
+        payload.extend_from_slice(b"newer recording layout");
+        fs::write(&newer, compress_data(&payload, COMPRESSION_LEVEL).unwrap()).unwrap();
+        let err = EventReplayer::load(&newer).unwrap_err();
+        assert!(err.to_string().contains("Incompatible recording version"));
+    }
+}
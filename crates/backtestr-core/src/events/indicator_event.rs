@@ -0,0 +1,20 @@
+use backtestr_data::Timeframe;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Indicator values recomputed after a bar completed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndicatorUpdateEvent {
+    pub symbol: String,
+    pub timeframe: Timeframe,
+    /// Start time of the completed bar the values were computed from
+    pub bar_timestamp: i64,
+    /// Latest value of every enabled, warmed-up indicator, by name
+    pub values: HashMap<String, f64>,
+}
+
+impl IndicatorUpdateEvent {
+    pub fn value(&self, name: &str) -> Option<f64> {
+        self.values.get(name).copied()
+    }
+}
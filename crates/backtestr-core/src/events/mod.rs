@@ -2,10 +2,16 @@ mod bar_completion;
 mod bar_event;
 mod event_bus;
 mod event_dispatcher;
+mod event_recorder;
+mod indicator_event;
 mod tick_event;
 
 pub use bar_completion::BarCompletionEvent;
 pub use bar_event::{BarEvent, BarEventType};
 pub use event_bus::{EventBus, SubscriptionHandle};
-pub use event_dispatcher::{EventDispatcher, EventHandler};
+pub use event_dispatcher::{EventDispatcher, EventHandler, WeakEventDispatcher};
+pub use event_recorder::{
+    EventRecorder, EventReplayer, RecordedEvent, RecordedEventKind, RECORDING_VERSION,
+};
+pub use indicator_event::IndicatorUpdateEvent;
 pub use tick_event::TickEvent;
@@ -4,6 +4,7 @@
 //! and retrieving indicator values across multiple timeframes.
 
 use super::indicator_trait::IndicatorValue;
+use super::output::IndicatorOutput;
 use backtestr_data::Timeframe;
 use dashmap::DashMap;
 use std::collections::VecDeque;
@@ -29,6 +30,7 @@ use std::sync::Arc;
 #[derive(Debug, Clone)]
 pub struct IndicatorCache {
     values: Arc<DashMap<(String, Timeframe), VecDeque<IndicatorValue>>>,
+    outputs: Arc<DashMap<(String, Timeframe), IndicatorOutput>>,
     max_history: usize,
 }
 
@@ -41,6 +43,7 @@ impl IndicatorCache {
     pub fn new(max_history: usize) -> Self {
         Self {
             values: Arc::new(DashMap::new()),
+            outputs: Arc::new(DashMap::new()),
             max_history,
         }
     }
@@ -80,6 +83,27 @@ impl IndicatorCache {
             .and_then(|values| values.back().copied())
     }
 
+    /// Stores the latest structured output of a multi-value indicator.
+    pub fn insert_output(
+        &self,
+        indicator_name: String,
+        timeframe: Timeframe,
+        output: IndicatorOutput,
+    ) {
+        self.outputs.insert((indicator_name, timeframe), output);
+    }
+
+    /// Gets the latest structured output for an indicator, if it has one.
+    pub fn get_output(
+        &self,
+        indicator_name: &str,
+        timeframe: Timeframe,
+    ) -> Option<IndicatorOutput> {
+        self.outputs
+            .get(&(indicator_name.to_string(), timeframe))
+            .map(|output| output.clone())
+    }
+
     /// Retrieves historical values for an indicator.
     ///
     /// # Arguments
@@ -110,6 +134,7 @@ impl IndicatorCache {
     /// Clears all cached values.
     pub fn clear(&self) {
         self.values.clear();
+        self.outputs.clear();
     }
 
     /// Clears all cached values for a specific indicator.
@@ -128,6 +153,14 @@ impl IndicatorCache {
         for key in keys_to_remove {
             self.values.remove(&key);
         }
+        self.outputs.retain(|key, _| key.0 != indicator_name);
+    }
+
+    /// Clears the cached values of one indicator on one timeframe.
+    pub fn clear_entry(&self, indicator_name: &str, timeframe: Timeframe) {
+        let key = (indicator_name.to_string(), timeframe);
+        self.values.remove(&key);
+        self.outputs.remove(&key);
     }
 
     pub fn clear_timeframe(&self, timeframe: Timeframe) {
@@ -141,6 +174,7 @@ impl IndicatorCache {
         for key in keys_to_remove {
             self.values.remove(&key);
         }
+        self.outputs.retain(|key, _| key.1 != timeframe);
     }
 
     /// Gets cache statistics.
@@ -0,0 +1,179 @@
+use crate::indicators::indicator_trait::{BarData, Indicator};
+
+/// Feeds the output of one indicator into another.
+///
+/// Each value produced by `source` is turned into a flat bar (open, high,
+/// low and close all equal to the value, volume and timestamp carried over)
+/// and passed to `target`. This composes any two scalar indicators, e.g. an
+/// SMA of RSI or an EMA of ATR, without a bespoke implementation.
+///
+/// # Examples
+///
+/// ```
+/// use backtestr_core::indicators::{Chained, Indicator, RSI, SMA};
+///
+/// let sma_of_rsi = Chained::new(RSI::new(14), SMA::new(5));
+/// assert_eq!(sma_of_rsi.name(), "SMA(RSI)");
+/// assert_eq!(sma_of_rsi.warm_up_period(), 15 + 5 - 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Chained<A, B> {
+    source: A,
+    target: B,
+    name: String,
+}
+
+impl<A, B> Chained<A, B>
+where
+    A: Indicator<Input = BarData, Output = f64>,
+    B: Indicator<Input = BarData, Output = f64>,
+{
+    pub fn new(source: A, target: B) -> Self {
+        let name = format!("{}({})", target.name(), source.name());
+        Self {
+            source,
+            target,
+            name,
+        }
+    }
+
+    /// The upstream indicator whose output feeds `target`
+    pub fn source(&self) -> &A {
+        &self.source
+    }
+
+    /// The downstream indicator applied to `source`'s output
+    pub fn target(&self) -> &B {
+        &self.target
+    }
+}
+
+impl<A, B> Indicator for Chained<A, B>
+where
+    A: Indicator<Input = BarData, Output = f64> + Clone + 'static,
+    B: Indicator<Input = BarData, Output = f64> + Clone + 'static,
+{
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn warm_up_period(&self) -> usize {
+        // The first source value also counts towards the target's warm-up
+        self.source.warm_up_period() + self.target.warm_up_period().saturating_sub(1)
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        let value = self.source.update(input)?;
+
+        self.target.update(BarData {
+            open: value,
+            high: value,
+            low: value,
+            close: value,
+            volume: input.volume,
+            timestamp: input.timestamp,
+        })
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.target.current()
+    }
+
+    fn reset(&mut self) {
+        self.source.reset();
+        self.target.reset();
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::{ATR, EMA, RSI, SMA};
+
+    fn create_bars(count: usize) -> Vec<BarData> {
+        (0..count)
+            .map(|i| {
+                let close = 100.0 + (i as f64 * 0.7).sin() * 5.0 + i as f64 * 0.1;
+                BarData {
+                    open: close - 0.5,
+                    high: close + 1.0,
+                    low: close - 1.0,
+                    close,
+                    volume: 1000.0 + i as f64,
+                    timestamp: i as i64,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sma_of_rsi_matches_manual_chain() {
+        let bars = create_bars(60);
+
+        let mut chained = Chained::new(RSI::new(14), SMA::new(5));
+        let mut rsi = RSI::new(14);
+        let mut sma = SMA::new(5);
+
+        for bar in &bars {
+            let chained_value = chained.update(*bar);
+
+            let manual_value = rsi.update(*bar).and_then(|value| {
+                sma.update(BarData {
+                    open: value,
+                    high: value,
+                    low: value,
+                    close: value,
+                    volume: bar.volume,
+                    timestamp: bar.timestamp,
+                })
+            });
+
+            assert_eq!(chained_value, manual_value);
+        }
+
+        assert!(chained.is_ready());
+        assert_eq!(chained.current(), sma.current());
+    }
+
+    #[test]
+    fn test_combined_warm_up() {
+        let bars = create_bars(40);
+
+        let mut chained = Chained::new(SMA::new(4), SMA::new(3));
+        assert_eq!(chained.warm_up_period(), 6);
+        let first_value_at = bars
+            .iter()
+            .position(|bar| chained.update(*bar).is_some())
+            .unwrap();
+        assert_eq!(first_value_at + 1, 6);
+
+        // Warm-up is an upper bound when the source reports a conservative period
+        let mut ema_of_atr = Chained::new(ATR::new(14), EMA::new(3));
+        let warm_up = ema_of_atr.warm_up_period();
+        let first_value_at = bars
+            .iter()
+            .position(|bar| ema_of_atr.update(*bar).is_some())
+            .unwrap();
+        assert!(first_value_at < warm_up);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut chained = Chained::new(RSI::new(3), SMA::new(2));
+        for bar in create_bars(10) {
+            chained.update(bar);
+        }
+        assert!(chained.is_ready());
+
+        chained.reset();
+        assert!(!chained.is_ready());
+        assert!(chained.source().current().is_none());
+    }
+}
@@ -0,0 +1,290 @@
+//! Indicators computed from the values of other registered indicators.
+//!
+//! A `DerivedIndicator` holds no bar state of its own. Each `update_all`
+//! feeds it the values its sources produced for the current bar, and the
+//! pipeline caches the combined result under the derived indicator's name.
+
+use anyhow::{bail, Result};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::Arc;
+
+/// Shared so a cloned pipeline reuses the same closures
+#[derive(Clone)]
+enum Combinator {
+    Unary(Arc<dyn Fn(f64) -> f64 + Send + Sync>),
+    Binary(Arc<dyn Fn(f64, f64) -> f64 + Send + Sync>),
+}
+
+/// A series built from one or two other indicators by a closure.
+///
+/// # Examples
+///
+/// ```
+/// use backtestr_core::indicators::{Chained, DerivedIndicator, IndicatorPipeline, RSI, SMA};
+///
+/// let pipeline = IndicatorPipeline::new(100);
+/// pipeline.register_indicator("RSI".to_string(), Box::new(RSI::new(14)));
+/// pipeline.register_indicator(
+///     "RSI_SMA".to_string(),
+///     Box::new(Chained::new(RSI::new(14), SMA::new(5))),
+/// );
+///
+/// // RSI minus its own moving average
+/// pipeline
+///     .register_derived(
+///         "RSI_DIFF".to_string(),
+///         DerivedIndicator::binary("RSI", "RSI_SMA", |rsi, sma| rsi - sma),
+///     )
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct DerivedIndicator {
+    sources: Vec<String>,
+    combinator: Combinator,
+}
+
+impl DerivedIndicator {
+    pub fn unary(
+        source: impl Into<String>,
+        combine: impl Fn(f64) -> f64 + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            sources: vec![source.into()],
+            combinator: Combinator::Unary(Arc::new(combine)),
+        }
+    }
+
+    pub fn binary(
+        first: impl Into<String>,
+        second: impl Into<String>,
+        combine: impl Fn(f64, f64) -> f64 + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            sources: vec![first.into(), second.into()],
+            combinator: Combinator::Binary(Arc::new(combine)),
+        }
+    }
+
+    /// Names of the indicators this one reads, in argument order
+    pub fn sources(&self) -> &[String] {
+        &self.sources
+    }
+
+    /// Combine the sources' values for this bar; `None` unless every source
+    /// produced one
+    pub fn compute(&self, lookup: impl Fn(&str) -> Option<f64>) -> Option<f64> {
+        match &self.combinator {
+            Combinator::Unary(combine) => Some(combine(lookup(&self.sources[0])?)),
+            Combinator::Binary(combine) => Some(combine(
+                lookup(&self.sources[0])?,
+                lookup(&self.sources[1])?,
+            )),
+        }
+    }
+}
+
+impl fmt::Debug for DerivedIndicator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DerivedIndicator")
+            .field("sources", &self.sources)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Registered derived indicators, kept in an order where every indicator
+/// comes after the derived indicators it reads
+#[derive(Debug, Default, Clone)]
+pub(crate) struct DerivedGraph {
+    indicators: HashMap<String, DerivedIndicator>,
+    order: Vec<String>,
+}
+
+impl DerivedGraph {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.indicators.is_empty()
+    }
+
+    pub(crate) fn contains(&self, name: &str) -> bool {
+        self.indicators.contains_key(name)
+    }
+
+    /// Add or replace `name`, refusing anything that would create a cycle.
+    ///
+    /// `is_base` tells whether a source is a regular indicator; every other
+    /// source must be a derived indicator already in the graph.
+    pub(crate) fn insert(
+        &mut self,
+        name: String,
+        derived: DerivedIndicator,
+        is_base: impl Fn(&str) -> bool,
+    ) -> Result<()> {
+        for source in derived.sources() {
+            if source == &name {
+                bail!("Derived indicator {} depends on itself", name);
+            }
+            if !is_base(source) && !self.contains(source) {
+                bail!(
+                    "Derived indicator {} depends on unregistered indicator {}",
+                    name,
+                    source
+                );
+            }
+        }
+
+        let previous = self.indicators.insert(name.clone(), derived);
+        match self.sorted() {
+            Some(order) => {
+                self.order = order;
+                Ok(())
+            }
+            None => {
+                // Put back whatever was registered under the name before
+                match previous {
+                    Some(previous) => self.indicators.insert(name.clone(), previous),
+                    None => self.indicators.remove(&name),
+                };
+                bail!("Derived indicator {} would create a dependency cycle", name)
+            }
+        }
+    }
+
+    pub(crate) fn remove(&mut self, name: &str) -> bool {
+        let removed = self.indicators.remove(name).is_some();
+        self.order.retain(|n| n != name);
+        removed
+    }
+
+    /// Derived indicators in update order
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&String, &DerivedIndicator)> {
+        self.order
+            .iter()
+            .filter_map(|name| self.indicators.get_key_value(name))
+    }
+
+    /// Kahn's algorithm over the derived-to-derived edges; `None` if a
+    /// cycle leaves some indicators unsorted
+    fn sorted(&self) -> Option<Vec<String>> {
+        let mut pending: HashMap<&str, usize> = HashMap::new();
+        let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (name, derived) in &self.indicators {
+            let derived_sources = derived
+                .sources()
+                .iter()
+                .filter(|s| self.contains(s))
+                .collect::<Vec<_>>();
+            pending.insert(name, derived_sources.len());
+            for source in derived_sources {
+                dependents.entry(source).or_default().push(name);
+            }
+        }
+
+        // Sorted seeds keep the order stable between registrations
+        let mut ready: Vec<&str> = pending
+            .iter()
+            .filter(|(_, &count)| count == 0)
+            .map(|(&name, _)| name)
+            .collect();
+        ready.sort_unstable();
+        let mut queue: VecDeque<&str> = ready.into();
+
+        let mut order = Vec::with_capacity(self.indicators.len());
+        while let Some(name) = queue.pop_front() {
+            order.push(name.to_string());
+            for &dependent in dependents.get(name).into_iter().flatten() {
+                let count = pending.get_mut(dependent).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    queue.push_back(dependent);
+                }
+            }
+        }
+
+        (order.len() == self.indicators.len()).then_some(order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_base(name: &str) -> bool {
+        name.starts_with("BASE")
+    }
+
+    #[test]
+    fn test_compute_needs_every_source() {
+        let diff = DerivedIndicator::binary("A", "B", |a, b| a - b);
+        assert_eq!(
+            diff.compute(|name| if name == "A" { Some(5.0) } else { Some(3.0) }),
+            Some(2.0)
+        );
+        assert_eq!(
+            diff.compute(|name| if name == "A" { Some(5.0) } else { None }),
+            None
+        );
+
+        let doubled = DerivedIndicator::unary("A", |a| a * 2.0);
+        assert_eq!(doubled.compute(|_| Some(4.0)), Some(8.0));
+    }
+
+    #[test]
+    fn test_order_follows_dependencies() {
+        let mut graph = DerivedGraph::default();
+        graph
+            .insert("D1".into(), DerivedIndicator::unary("BASE", |a| a), is_base)
+            .unwrap();
+        graph
+            .insert(
+                "D2".into(),
+                DerivedIndicator::binary("D1", "BASE", |a, b| a + b),
+                is_base,
+            )
+            .unwrap();
+        graph
+            .insert("A0".into(), DerivedIndicator::unary("D2", |a| a), is_base)
+            .unwrap();
+
+        let order: Vec<&String> = graph.iter().map(|(name, _)| name).collect();
+        assert_eq!(order, ["D1", "D2", "A0"]);
+    }
+
+    #[test]
+    fn test_cycles_are_rejected() {
+        let mut graph = DerivedGraph::default();
+
+        assert!(graph
+            .insert("D1".into(), DerivedIndicator::unary("D1", |a| a), is_base)
+            .is_err());
+
+        graph
+            .insert("D1".into(), DerivedIndicator::unary("BASE", |a| a), is_base)
+            .unwrap();
+        graph
+            .insert("D2".into(), DerivedIndicator::unary("D1", |a| a), is_base)
+            .unwrap();
+
+        // Re-pointing D1 at D2 closes the loop D1 -> D2 -> D1
+        let err = graph
+            .insert("D1".into(), DerivedIndicator::unary("D2", |a| a), is_base)
+            .unwrap_err();
+        assert!(err.to_string().contains("cycle"));
+
+        // The original D1 is kept
+        assert_eq!(graph.indicators["D1"].sources(), ["BASE"]);
+        assert_eq!(graph.iter().count(), 2);
+    }
+
+    #[test]
+    fn test_unknown_source_is_rejected() {
+        let mut graph = DerivedGraph::default();
+        assert!(graph
+            .insert(
+                "D1".into(),
+                DerivedIndicator::unary("MISSING", |a| a),
+                is_base
+            )
+            .is_err());
+        assert!(graph.is_empty());
+    }
+}
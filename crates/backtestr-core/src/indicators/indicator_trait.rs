@@ -1,3 +1,4 @@
+use super::output::IndicatorOutput;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
@@ -40,7 +41,7 @@ pub struct IndicatorValue {
 /// ```
 /// use backtestr_core::indicators::{Indicator, BarData};
 ///
-/// #[derive(Debug)]
+/// #[derive(Debug, Clone)]
 /// struct MyIndicator {
 ///     period: usize,
 ///     values: Vec<f64>,
@@ -66,6 +67,9 @@ pub struct IndicatorValue {
 ///         self.values.clear();
 ///         self.current_value = None;
 ///     }
+///     fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+///         Box::new(self.clone())
+///     }
 /// }
 /// ```
 pub trait Indicator: Send + Sync + Debug {
@@ -94,6 +98,23 @@ pub trait Indicator: Send + Sync + Debug {
     /// recalculating the entire history on each update.
     fn update(&mut self, input: Self::Input) -> Option<Self::Output>;
 
+    /// Feeds a batch of historical inputs in order and returns the value
+    /// after the last one.
+    ///
+    /// The default calls `update` for each input. Indicators may override it
+    /// with a bulk computation, but the resulting state and values must be
+    /// bit-identical to feeding the inputs one at a time.
+    fn warm_up_batch(&mut self, inputs: &[Self::Input]) -> Option<Self::Output>
+    where
+        Self::Input: Clone,
+    {
+        let mut last = None;
+        for input in inputs {
+            last = self.update(input.clone());
+        }
+        last
+    }
+
     /// Returns the current indicator value without updating.
     ///
     /// Returns `None` if the indicator hasn't warmed up yet.
@@ -102,12 +123,70 @@ pub trait Indicator: Send + Sync + Debug {
     /// Resets the indicator to its initial state, clearing all internal data.
     fn reset(&mut self);
 
+    /// Returns a boxed copy of this indicator, accumulated state included.
+    ///
+    /// Implementations are normally `Box::new(self.clone())`.
+    fn clone_box(&self) -> Box<dyn Indicator<Input = Self::Input, Output = Self::Output>>;
+
+    /// Returns a new instance with the same parameters and no accumulated
+    /// state.
+    ///
+    /// The pipeline keeps a separate instance per timeframe and builds each
+    /// one with this, so M1 and M5 bars never share a rolling window.
+    /// Defaults to `clone_box` followed by `reset`.
+    fn clone_fresh(&self) -> Box<dyn Indicator<Input = Self::Input, Output = Self::Output>> {
+        let mut fresh = self.clone_box();
+        fresh.reset();
+        fresh
+    }
+
     /// Checks if the indicator has enough data to produce valid values.
     ///
     /// Default implementation checks if `current()` returns `Some`.
     fn is_ready(&self) -> bool {
         self.current().is_some()
     }
+
+    /// Returns the full output of a multi-value indicator.
+    ///
+    /// Defaults to `None` for scalar indicators. The pipeline caches this
+    /// alongside the scalar value so it can be retrieved with
+    /// `IndicatorPipeline::get_output`.
+    fn structured_output(&self) -> Option<IndicatorOutput> {
+        None
+    }
+
+    /// Serializes the indicator's internal state for checkpointing.
+    ///
+    /// Defaults to an empty buffer, meaning the indicator has no persisted
+    /// state and must re-warm after recovery.
+    fn serialize_state(&self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    /// Restores state produced by `serialize_state`.
+    ///
+    /// Fails if the data belongs to a different indicator type or was saved
+    /// with different parameters (e.g. another period).
+    fn restore_state(&mut self, _data: &[u8]) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Whether the indicator needs volume and none of the bars it has seen
+    /// carried any.
+    ///
+    /// Defaults to `false`. Volume indicators such as `OBV` and `VWAP`
+    /// produce no value on a feed without volume; the pipeline reports them
+    /// as `HealthStatus::VolumeUnavailable`.
+    fn volume_unavailable(&self) -> bool {
+        false
+    }
+
+    /// Called when a new trading session opens.
+    ///
+    /// Defaults to a no-op. Session-anchored indicators such as `VWAP`
+    /// override this to start a fresh accumulation.
+    fn on_session_boundary(&mut self) {}
 }
 
 /// Default configuration parameters for all indicators.
@@ -228,3 +307,67 @@ pub struct BarData {
     /// Unix timestamp of the bar
     pub timestamp: i64,
 }
+
+impl From<&backtestr_data::Bar> for BarData {
+    /// Uses the bar's start time, matching how bars are keyed in storage
+    fn from(bar: &backtestr_data::Bar) -> Self {
+        Self {
+            open: bar.open,
+            high: bar.high,
+            low: bar.low,
+            close: bar.close,
+            volume: bar.volume.unwrap_or(0) as f64,
+            timestamp: bar.timestamp_start,
+        }
+    }
+}
+
+/// Which price of a bar feeds an indicator.
+///
+/// Mirrors `PriceType` in the volume aggregator; indicators default to
+/// `Close`.
+///
+/// # Examples
+///
+/// ```
+/// use backtestr_core::indicators::{BarData, PriceSource};
+///
+/// let bar = BarData {
+///     open: 100.0,
+///     high: 104.0,
+///     low: 98.0,
+///     close: 102.0,
+///     volume: 10000.0,
+///     timestamp: 1234567890,
+/// };
+/// assert_eq!(PriceSource::Median.price(&bar), 101.0);
+/// assert_eq!(PriceSource::Typical.price(&bar), 304.0 / 3.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PriceSource {
+    Open,
+    High,
+    Low,
+    #[default]
+    Close,
+    /// `(high + low + close) / 3`
+    Typical,
+    /// `(high + low + 2 * close) / 4`
+    Weighted,
+    /// `(high + low) / 2`
+    Median,
+}
+
+impl PriceSource {
+    pub fn price(self, bar: &BarData) -> f64 {
+        match self {
+            PriceSource::Open => bar.open,
+            PriceSource::High => bar.high,
+            PriceSource::Low => bar.low,
+            PriceSource::Close => bar.close,
+            PriceSource::Typical => (bar.high + bar.low + bar.close) / 3.0,
+            PriceSource::Weighted => (bar.high + bar.low + bar.close * 2.0) / 4.0,
+            PriceSource::Median => (bar.high + bar.low) / 2.0,
+        }
+    }
+}
@@ -11,6 +11,7 @@
 //! - **Incremental calculation**: Optimized for streaming data without full recalculation
 //! - **Caching layer**: Automatic history management with configurable depth
 //! - **Pipeline processing**: Parallel execution for multiple indicators
+//! - **Composition**: `Chained` feeds one indicator's output into another
 //!
 //! # Categories
 //!
@@ -19,6 +20,8 @@
 //! - **EMA** - Exponential Moving Average
 //! - **WMA** - Weighted Moving Average
 //! - **DEMA** - Double Exponential Moving Average
+//! - **TEMA** - Triple Exponential Moving Average
+//! - **HMA** - Hull Moving Average
 //!
 //! ## Momentum Indicators
 //! - **RSI** - Relative Strength Index
@@ -26,22 +29,40 @@
 //! - **Stochastic** - Stochastic Oscillator
 //! - **CCI** - Commodity Channel Index
 //! - **Williams %R** - Williams Percent Range
+//! - **ROC** - Rate of Change
+//! - **Momentum** - Price change over a lookback
+//! - **CMO** - Chande Momentum Oscillator
+//! - **TRIX** - Rate of change of a triple-smoothed EMA
 //!
 //! ## Volatility Indicators
 //! - **Bollinger Bands** - Price bands based on standard deviation
 //! - **ATR** - Average True Range
 //! - **Keltner Channels** - ATR-based price channels
 //! - **Donchian Channels** - High/Low price channels
+//! - **SuperTrend** - ATR-based trailing trend line
+//! - **Squeeze** - Bollinger Bands inside Keltner Channels, with momentum
+//! - **StdDev** - Rolling standard deviation of closes
+//! - **Historical Volatility** - Annualized standard deviation of log returns
 //!
 //! ## Volume Indicators
 //! - **OBV** - On-Balance Volume
+//! - **MFI** - Money Flow Index
 //! - **Volume SMA** - Simple Moving Average of Volume
 //! - **VWAP** - Volume Weighted Average Price
+//! - **Anchored VWAP** - VWAP from a chosen anchor timestamp, with deviation bands
+//! - **A/D** - Accumulation/Distribution line
+//! - **CMF** - Chaikin Money Flow
 //!
 //! ## Other Indicators
 //! - **ADX** - Average Directional Index
+//! - **Aroon** - Bars since the highest high and lowest low
 //! - **Parabolic SAR** - Stop and Reverse indicator
-//! - **Pivot Points** - Support/Resistance levels
+//! - **Pivot Points** - Support/Resistance levels (Classic, Camarilla, Woodie, Fibonacci, DeMark)
+//! - **Divergence Detector** - Price/oscillator divergence at confirmed swings
+//! - **Vortex** - Upward versus downward movement relative to true range
+//! - **Linear Regression** - Least-squares line with slope and residual bands
+//! - **Zig Zag** - Swing pivots confirmed by a percentage reversal
+//! - **Elder Ray** - Bull and Bear Power of the high and low against an EMA
 //!
 //! # Examples
 //!
@@ -75,21 +96,34 @@
 //! ```
 
 pub mod cache;
+pub mod chained;
+pub mod derived;
 pub mod indicator_trait;
 pub mod momentum;
 pub mod other;
+pub mod output;
 pub mod pipeline;
+pub(crate) mod state;
 pub mod trend;
 pub mod volatility;
 pub mod volume;
 
 pub use cache::IndicatorCache;
-pub use indicator_trait::{BarData, Indicator, IndicatorDefaults, IndicatorValue};
-pub use pipeline::IndicatorPipeline;
+pub use chained::Chained;
+pub use derived::DerivedIndicator;
+pub use indicator_trait::{BarData, Indicator, IndicatorDefaults, IndicatorValue, PriceSource};
+pub use output::{IndicatorOutput, TypedOutput};
+pub use pipeline::{HealthStatus, IndicatorHealth, IndicatorPipeline};
 
 // Re-export all indicators
-pub use momentum::{Stochastic, WilliamsR, CCI, MACD, RSI};
-pub use other::{ParabolicSAR, PivotPoints, SupportResistance, ADX};
-pub use trend::{DEMA, EMA, SMA, WMA};
-pub use volatility::{BollingerBands, DonchianChannels, KeltnerChannels, ATR};
-pub use volume::{VolumeSMA, OBV, VWAP};
+pub use momentum::{Momentum, Stochastic, WilliamsR, CCI, CMO, MACD, ROC, RSI, TRIX};
+pub use other::{
+    Aroon, Divergence, DivergenceDetector, DivergenceKind, ElderRay, LinearRegression,
+    ParabolicSAR, PivotMethod, PivotPoints, SupportResistance, Vortex, ZigZag, ADX,
+};
+pub use trend::{DEMA, EMA, HMA, SMA, TEMA, WMA};
+pub use volatility::{
+    AtrSmoothing, BollingerBands, DonchianChannels, HistoricalVolatility, KeltnerChannels,
+    SqueezeIndicator, StdDev, SuperTrend, ATR,
+};
+pub use volume::{AccDist, AnchoredVWAP, VolumeSMA, CMF, MFI, OBV, VWAP};
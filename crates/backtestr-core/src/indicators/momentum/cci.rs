@@ -1,7 +1,7 @@
 use crate::indicators::indicator_trait::{BarData, Indicator};
 use std::collections::VecDeque;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CCI {
     period: usize,
     typical_prices: VecDeque<f64>,
@@ -76,6 +76,10 @@ impl Indicator for CCI {
         self.typical_prices.clear();
         self.current_value = None;
     }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
 }
 
 #[cfg(test)]
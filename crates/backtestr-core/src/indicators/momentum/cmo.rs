@@ -0,0 +1,162 @@
+use crate::indicators::indicator_trait::{BarData, Indicator};
+use std::collections::VecDeque;
+
+/// Chande Momentum Oscillator:
+/// `100 * (sum_up - sum_down) / (sum_up + sum_down)` over the last `period`
+/// close-to-close moves, ranging from -100 to 100.
+#[derive(Debug, Clone)]
+pub struct CMO {
+    period: usize,
+    previous_close: Option<f64>,
+    /// The last `period` close-to-close changes, oldest first
+    changes: VecDeque<f64>,
+    current_value: Option<f64>,
+}
+
+impl CMO {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            previous_close: None,
+            changes: VecDeque::with_capacity(period),
+            current_value: None,
+        }
+    }
+}
+
+impl Indicator for CMO {
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        "CMO"
+    }
+
+    fn warm_up_period(&self) -> usize {
+        self.period + 1
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        let previous = self.previous_close.replace(input.close)?;
+
+        let change = input.close - previous;
+        self.changes.push_back(change);
+        if self.changes.len() > self.period {
+            self.changes.pop_front();
+        }
+
+        if self.changes.len() < self.period {
+            return None;
+        }
+
+        // Summed afresh each bar: running totals keep rounding residue after
+        // a volatile stretch leaves the window, turning a flat one into ±100
+        let (sum_up, sum_down) = self.changes.iter().fold((0.0, 0.0), |(up, down), &c| {
+            if c > 0.0 {
+                (up + c, down)
+            } else {
+                (up, down - c)
+            }
+        });
+
+        // Flat prices have no movement either way: neutral, not NaN
+        let total = sum_up + sum_down;
+        let cmo = if total > 0.0 {
+            100.0 * (sum_up - sum_down) / total
+        } else {
+            0.0
+        };
+        self.current_value = Some(cmo);
+        self.current_value
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current_value
+    }
+
+    fn reset(&mut self) {
+        self.previous_close = None;
+        self.changes.clear();
+        self.current_value = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(i: i64, close: f64) -> BarData {
+        BarData {
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 1000.0,
+            timestamp: i,
+        }
+    }
+
+    #[test]
+    fn test_cmo_calculation() {
+        let mut cmo = CMO::new(4);
+        assert_eq!(cmo.warm_up_period(), 5);
+        for (i, close) in [100.0, 102.0, 101.0, 104.0].iter().enumerate() {
+            assert!(cmo.update(bar(i as i64, *close)).is_none());
+        }
+
+        // Moves +2, -1, +3, -2: up 5, down 3
+        let value = cmo.update(bar(4, 102.0)).unwrap();
+        assert!((value - 25.0).abs() < 1e-9);
+
+        // The +2 drops out, +4 comes in: up 7, down 3
+        let value = cmo.update(bar(5, 106.0)).unwrap();
+        assert!((value - 40.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cmo_bounds() {
+        let mut rising = CMO::new(3);
+        let mut falling = CMO::new(3);
+        for i in 0..6 {
+            rising.update(bar(i, 100.0 + i as f64));
+            falling.update(bar(i, 100.0 - i as f64));
+        }
+        assert_eq!(rising.current(), Some(100.0));
+        assert_eq!(falling.current(), Some(-100.0));
+    }
+
+    #[test]
+    fn test_flat_prices_are_neutral() {
+        let mut cmo = CMO::new(3);
+        for i in 0..5 {
+            cmo.update(bar(i, 100.0));
+        }
+        assert_eq!(cmo.current(), Some(0.0));
+
+        cmo.reset();
+        assert!(cmo.current().is_none());
+    }
+
+    #[test]
+    fn test_flat_window_after_volatility_is_neutral() {
+        let mut cmo = CMO::new(5);
+        let volatile = [
+            1.0916, 1.551, 1.8513, 1.931, 1.0325, 1.9436, 1.0705, 1.8681, 1.453, 1.7541, 1.2812,
+            1.2686,
+        ];
+        for (i, close) in volatile.iter().enumerate() {
+            cmo.update(bar(i as i64, *close));
+        }
+
+        // Once the volatile moves have rolled out, nothing is left over
+        for i in 0..5 {
+            cmo.update(bar(volatile.len() as i64 + i, 1.2686));
+        }
+        assert_eq!(cmo.current(), Some(0.0));
+    }
+}
@@ -1,8 +1,10 @@
 use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::output::IndicatorOutput;
+use crate::indicators::state;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MACD {
-    #[allow(dead_code)]
     fast_period: usize,
     slow_period: usize,
     signal_period: usize,
@@ -14,7 +16,7 @@ pub struct MACD {
     current_histogram: Option<f64>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Ema {
     period: usize,
     multiplier: f64,
@@ -61,7 +63,7 @@ impl Ema {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct MACDOutput {
     pub macd: f64,
     pub signal: f64,
@@ -145,6 +147,42 @@ impl Indicator for MACD {
         self.current_signal = None;
         self.current_histogram = None;
     }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn serialize_state(&self) -> Vec<u8> {
+        state::encode(self.name(), self)
+    }
+
+    fn restore_state(&mut self, data: &[u8]) -> anyhow::Result<()> {
+        let restored: Self = state::decode(self.name(), data)?;
+        state::ensure_parameter(
+            self.name(),
+            "fast period",
+            self.fast_period,
+            restored.fast_period,
+        )?;
+        state::ensure_parameter(
+            self.name(),
+            "slow period",
+            self.slow_period,
+            restored.slow_period,
+        )?;
+        state::ensure_parameter(
+            self.name(),
+            "signal period",
+            self.signal_period,
+            restored.signal_period,
+        )?;
+        *self = restored;
+        Ok(())
+    }
+
+    fn structured_output(&self) -> Option<IndicatorOutput> {
+        self.get_output().map(IndicatorOutput::Macd)
+    }
 }
 
 #[cfg(test)]
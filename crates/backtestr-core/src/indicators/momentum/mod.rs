@@ -1,11 +1,19 @@
 pub mod cci;
+pub mod cmo;
 pub mod macd;
+pub mod mom;
+pub mod roc;
 pub mod rsi;
 pub mod stochastic;
+pub mod trix;
 pub mod williams_r;
 
 pub use cci::CCI;
+pub use cmo::CMO;
 pub use macd::{MACDOutput, MACD};
+pub use mom::Momentum;
+pub use roc::ROC;
 pub use rsi::RSI;
 pub use stochastic::{Stochastic, StochasticOutput};
+pub use trix::TRIX;
 pub use williams_r::WilliamsR;
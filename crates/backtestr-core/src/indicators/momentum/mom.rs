@@ -0,0 +1,103 @@
+use crate::indicators::indicator_trait::{BarData, Indicator};
+use std::collections::VecDeque;
+
+/// Momentum: `close - close[period]`
+#[derive(Debug, Clone)]
+pub struct Momentum {
+    period: usize,
+    /// The last `period + 1` closes, oldest first
+    closes: VecDeque<f64>,
+    current_value: Option<f64>,
+}
+
+impl Momentum {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            closes: VecDeque::with_capacity(period + 1),
+            current_value: None,
+        }
+    }
+}
+
+impl Indicator for Momentum {
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        "Momentum"
+    }
+
+    fn warm_up_period(&self) -> usize {
+        self.period + 1
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        self.closes.push_back(input.close);
+        if self.closes.len() > self.period + 1 {
+            self.closes.pop_front();
+        }
+
+        if self.closes.len() == self.period + 1 {
+            let momentum = input.close - self.closes[0];
+            self.current_value = Some(momentum);
+            Some(momentum)
+        } else {
+            None
+        }
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current_value
+    }
+
+    fn reset(&mut self) {
+        self.closes.clear();
+        self.current_value = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(i: i64, close: f64) -> BarData {
+        BarData {
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 1000.0,
+            timestamp: i,
+        }
+    }
+
+    #[test]
+    fn test_momentum_calculation() {
+        let mut momentum = Momentum::new(2);
+        assert_eq!(momentum.warm_up_period(), 3);
+        assert!(momentum.update(bar(0, 100.0)).is_none());
+        assert!(momentum.update(bar(1, 103.0)).is_none());
+
+        assert_eq!(momentum.update(bar(2, 105.0)), Some(5.0));
+        assert_eq!(momentum.update(bar(3, 101.0)), Some(-2.0));
+    }
+
+    #[test]
+    fn test_momentum_reset() {
+        let mut momentum = Momentum::new(2);
+        for i in 0..5 {
+            momentum.update(bar(i, 100.0 + i as f64));
+        }
+        assert_eq!(momentum.current(), Some(2.0));
+
+        momentum.reset();
+        assert!(momentum.current().is_none());
+        assert!(momentum.update(bar(0, 100.0)).is_none());
+    }
+}
@@ -0,0 +1,119 @@
+use crate::indicators::indicator_trait::{BarData, Indicator};
+use std::collections::VecDeque;
+
+/// Rate of Change: `100 * (close - close[period]) / close[period]`
+#[derive(Debug, Clone)]
+pub struct ROC {
+    period: usize,
+    /// The last `period + 1` closes, oldest first
+    closes: VecDeque<f64>,
+    current_value: Option<f64>,
+}
+
+impl ROC {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            closes: VecDeque::with_capacity(period + 1),
+            current_value: None,
+        }
+    }
+}
+
+impl Indicator for ROC {
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        "ROC"
+    }
+
+    fn warm_up_period(&self) -> usize {
+        self.period + 1
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        self.closes.push_back(input.close);
+        if self.closes.len() > self.period + 1 {
+            self.closes.pop_front();
+        }
+
+        // A zero prior close has no defined rate; report nothing for this
+        // bar rather than NaN or infinity
+        self.current_value = match self.closes.front() {
+            Some(&prior) if self.closes.len() == self.period + 1 && prior != 0.0 => {
+                Some(100.0 * (input.close - prior) / prior)
+            }
+            _ => None,
+        };
+        self.current_value
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current_value
+    }
+
+    fn reset(&mut self) {
+        self.closes.clear();
+        self.current_value = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(i: i64, close: f64) -> BarData {
+        BarData {
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 1000.0,
+            timestamp: i,
+        }
+    }
+
+    #[test]
+    fn test_roc_calculation() {
+        let mut roc = ROC::new(3);
+        assert!(roc.update(bar(0, 100.0)).is_none());
+        assert!(roc.update(bar(1, 101.0)).is_none());
+        assert!(roc.update(bar(2, 102.0)).is_none());
+
+        // Compared with the close three bars back
+        assert_eq!(roc.update(bar(3, 110.0)), Some(10.0));
+        let value = roc.update(bar(4, 90.9)).unwrap();
+        assert!((value + 10.0).abs() < 1e-9);
+        assert_eq!(roc.current(), Some(value));
+    }
+
+    #[test]
+    fn test_roc_zero_prior_close() {
+        let mut roc = ROC::new(1);
+        roc.update(bar(0, 0.0));
+        assert!(roc.update(bar(1, 5.0)).is_none());
+        assert!(roc.current().is_none());
+
+        // Defined again once the zero leaves the window
+        assert_eq!(roc.update(bar(2, 10.0)), Some(100.0));
+    }
+
+    #[test]
+    fn test_roc_reset() {
+        let mut roc = ROC::new(2);
+        for i in 0..5 {
+            roc.update(bar(i, 100.0 + i as f64));
+        }
+        assert!(roc.current().is_some());
+
+        roc.reset();
+        assert!(roc.current().is_none());
+        assert!(roc.update(bar(0, 100.0)).is_none());
+    }
+}
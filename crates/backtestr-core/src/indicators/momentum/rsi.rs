@@ -1,7 +1,9 @@
 use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::state;
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RSI {
     period: usize,
     gains: VecDeque<f64>,
@@ -101,6 +103,21 @@ impl Indicator for RSI {
         self.previous_close = None;
         self.current_value = None;
     }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn serialize_state(&self) -> Vec<u8> {
+        state::encode(self.name(), self)
+    }
+
+    fn restore_state(&mut self, data: &[u8]) -> anyhow::Result<()> {
+        let restored: Self = state::decode(self.name(), data)?;
+        state::ensure_parameter(self.name(), "period", self.period, restored.period)?;
+        *self = restored;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
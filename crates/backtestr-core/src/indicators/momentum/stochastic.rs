@@ -1,7 +1,8 @@
 use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::output::IndicatorOutput;
 use std::collections::VecDeque;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Stochastic {
     k_period: usize,
     d_period: usize,
@@ -12,7 +13,7 @@ pub struct Stochastic {
     current_d: Option<f64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct StochasticOutput {
     pub k: f64,
     pub d: f64,
@@ -99,6 +100,14 @@ impl Indicator for Stochastic {
         self.current_k = None;
         self.current_d = None;
     }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn structured_output(&self) -> Option<IndicatorOutput> {
+        self.get_output().map(IndicatorOutput::Stochastic)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,137 @@
+use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::trend::tema::flat_bar;
+use crate::indicators::trend::EMA;
+
+/// TRIX: the 1-bar percentage change of a triple-smoothed EMA of closes.
+///
+/// The three smoothing stages are regular `EMA`s chained the same way as in
+/// `TEMA`; the first value needs one bar past the point the third EMA seeds.
+#[derive(Debug, Clone)]
+pub struct TRIX {
+    period: usize,
+    ema1: EMA,
+    ema2: EMA,
+    ema3: EMA,
+    previous_smoothed: Option<f64>,
+    current_value: Option<f64>,
+}
+
+impl TRIX {
+    /// Standard period is 15.
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            ema1: EMA::new(period),
+            ema2: EMA::new(period),
+            ema3: EMA::new(period),
+            previous_smoothed: None,
+            current_value: None,
+        }
+    }
+}
+
+impl Indicator for TRIX {
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        "TRIX"
+    }
+
+    fn warm_up_period(&self) -> usize {
+        // Triple EMA after 3p - 2 bars, then one more for the change
+        self.period * 3 - 1
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        let ema1 = self.ema1.update(input)?;
+        let ema2 = self.ema2.update(flat_bar(ema1, &input))?;
+        let smoothed = self.ema3.update(flat_bar(ema2, &input))?;
+
+        // A zero prior value has no defined rate, as in `ROC`
+        self.current_value = match self.previous_smoothed.replace(smoothed) {
+            Some(previous) if previous != 0.0 => Some(100.0 * (smoothed - previous) / previous),
+            _ => None,
+        };
+        self.current_value
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current_value
+    }
+
+    fn reset(&mut self) {
+        self.ema1.reset();
+        self.ema2.reset();
+        self.ema3.reset();
+        self.previous_smoothed = None;
+        self.current_value = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(i: i64, close: f64) -> BarData {
+        BarData {
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 1000.0,
+            timestamp: i,
+        }
+    }
+
+    #[test]
+    fn test_warm_up() {
+        let mut trix = TRIX::new(4);
+        assert_eq!(trix.warm_up_period(), 11);
+        for i in 0..10 {
+            assert!(trix.update(bar(i, 100.0 + i as f64)).is_none());
+        }
+        assert!(trix.update(bar(10, 110.0)).is_some());
+    }
+
+    #[test]
+    fn test_matches_chained_emas() {
+        let mut trix = TRIX::new(3);
+        let (mut e1, mut e2, mut e3) = (EMA::new(3), EMA::new(3), EMA::new(3));
+        let mut previous = None;
+        for i in 0..40 {
+            let input = bar(i, 100.0 + (i as f64 * 0.4).sin() * 5.0);
+            let value = trix.update(input);
+
+            let smoothed = e1
+                .update(input)
+                .and_then(|v| e2.update(flat_bar(v, &input)))
+                .and_then(|v| e3.update(flat_bar(v, &input)));
+            if let (Some(prior), Some(now)) = (previous, smoothed) {
+                let expected: f64 = 100.0 * (now - prior) / prior;
+                assert!((value.unwrap() - expected).abs() < 1e-12);
+            }
+            previous = smoothed.or(previous);
+        }
+    }
+
+    #[test]
+    fn test_sign_follows_trend() {
+        let mut rising = TRIX::new(3);
+        let mut flat = TRIX::new(3);
+        for i in 0..20 {
+            rising.update(bar(i, 100.0 + i as f64));
+            flat.update(bar(i, 100.0));
+        }
+        assert!(rising.current().unwrap() > 0.0);
+        assert_eq!(flat.current(), Some(0.0));
+
+        rising.reset();
+        assert!(rising.current().is_none());
+    }
+}
@@ -1,7 +1,7 @@
 use crate::indicators::indicator_trait::{BarData, Indicator};
 use std::collections::VecDeque;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WilliamsR {
     period: usize,
     highs: VecDeque<f64>,
@@ -67,6 +67,10 @@ impl Indicator for WilliamsR {
         self.lows.clear();
         self.current_value = None;
     }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
 }
 
 #[cfg(test)]
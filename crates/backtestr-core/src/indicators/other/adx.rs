@@ -14,7 +14,7 @@ use std::collections::VecDeque;
 /// - 25-50: Strong trend
 /// - 50-75: Very strong trend
 /// - 75-100: Extremely strong trend
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ADX {
     period: usize,
     plus_dm_ema: Ema,
@@ -28,7 +28,7 @@ pub struct ADX {
 }
 
 /// Simple EMA implementation for internal use
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Ema {
     #[allow(dead_code)]
     period: usize,
@@ -178,6 +178,10 @@ impl Indicator for ADX {
         self.dx_values.clear();
         self.current_value = None;
     }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,229 @@
+//! Aroon indicator implementation.
+//!
+//! Aroon measures how recently the highest high and lowest low of the
+//! lookback occurred. Readings near 100 mean a fresh extreme; the
+//! oscillator is positive when highs are more recent than lows.
+
+use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::output::IndicatorOutput;
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AroonOutput {
+    pub aroon_up: f64,
+    pub aroon_down: f64,
+    /// `aroon_up - aroon_down`
+    pub oscillator: f64,
+}
+
+/// Aroon Up/Down over the last `period + 1` bars.
+///
+/// The extremes are tracked with monotonic deques of bar indices, so each
+/// update is O(1) amortized instead of rescanning the window. On ties the
+/// most recent bar counts as the extreme. The scalar output is the
+/// oscillator.
+#[derive(Debug, Clone)]
+pub struct Aroon {
+    period: usize,
+    /// Index of the next bar
+    index: usize,
+    /// (index, high), highs strictly decreasing from the front
+    highs: VecDeque<(usize, f64)>,
+    /// (index, low), lows strictly increasing from the front
+    lows: VecDeque<(usize, f64)>,
+    current: Option<AroonOutput>,
+}
+
+impl Aroon {
+    /// Standard period is 25.
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            index: 0,
+            highs: VecDeque::with_capacity(period + 1),
+            lows: VecDeque::with_capacity(period + 1),
+            current: None,
+        }
+    }
+
+    pub fn get_aroon(&self) -> Option<AroonOutput> {
+        self.current
+    }
+}
+
+impl Indicator for Aroon {
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        "Aroon"
+    }
+
+    fn warm_up_period(&self) -> usize {
+        self.period + 1
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        let index = self.index;
+        self.index += 1;
+
+        while self
+            .highs
+            .back()
+            .is_some_and(|&(_, high)| high <= input.high)
+        {
+            self.highs.pop_back();
+        }
+        self.highs.push_back((index, input.high));
+        while self.lows.back().is_some_and(|&(_, low)| low >= input.low) {
+            self.lows.pop_back();
+        }
+        self.lows.push_back((index, input.low));
+
+        // Keep the last `period + 1` bars
+        if let Some(oldest) = index.checked_sub(self.period) {
+            while self.highs.front().is_some_and(|&(i, _)| i < oldest) {
+                self.highs.pop_front();
+            }
+            while self.lows.front().is_some_and(|&(i, _)| i < oldest) {
+                self.lows.pop_front();
+            }
+        }
+
+        if self.index < self.warm_up_period() {
+            return None;
+        }
+
+        let period = self.period as f64;
+        let since_high = (index - self.highs[0].0) as f64;
+        let since_low = (index - self.lows[0].0) as f64;
+        let aroon_up = 100.0 * (period - since_high) / period;
+        let aroon_down = 100.0 * (period - since_low) / period;
+
+        let output = AroonOutput {
+            aroon_up,
+            aroon_down,
+            oscillator: aroon_up - aroon_down,
+        };
+        self.current = Some(output);
+        Some(output.oscillator)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current.map(|output| output.oscillator)
+    }
+
+    fn reset(&mut self) {
+        self.index = 0;
+        self.highs.clear();
+        self.lows.clear();
+        self.current = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn structured_output(&self) -> Option<IndicatorOutput> {
+        self.current.map(IndicatorOutput::Aroon)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(i: i64, high: f64, low: f64) -> BarData {
+        BarData {
+            open: (high + low) / 2.0,
+            high,
+            low,
+            close: (high + low) / 2.0,
+            volume: 1000.0,
+            timestamp: i,
+        }
+    }
+
+    /// Rescans the window, for checking the deque bookkeeping
+    fn naive(bars: &[BarData], period: usize) -> AroonOutput {
+        let window = &bars[bars.len() - period - 1..];
+        let mut high_at = 0;
+        let mut low_at = 0;
+        for (i, b) in window.iter().enumerate() {
+            if b.high >= window[high_at].high {
+                high_at = i;
+            }
+            if b.low <= window[low_at].low {
+                low_at = i;
+            }
+        }
+        let up = 100.0 * high_at as f64 / period as f64;
+        let down = 100.0 * low_at as f64 / period as f64;
+        AroonOutput {
+            aroon_up: up,
+            aroon_down: down,
+            oscillator: up - down,
+        }
+    }
+
+    #[test]
+    fn test_warm_up() {
+        let mut aroon = Aroon::new(5);
+        assert_eq!(aroon.warm_up_period(), 6);
+        for i in 0..5 {
+            assert!(aroon.update(bar(i, 101.0, 99.0)).is_none());
+        }
+        assert!(aroon.update(bar(5, 101.0, 99.0)).is_some());
+    }
+
+    #[test]
+    fn test_trend_extremes() {
+        let mut aroon = Aroon::new(5);
+        for i in 0..10 {
+            let price = 100.0 + i as f64;
+            aroon.update(bar(i, price + 0.5, price - 0.5));
+        }
+        // Every bar is a new high; the lowest low is the oldest in the window
+        let output = aroon.get_aroon().unwrap();
+        assert_eq!(output.aroon_up, 100.0);
+        assert_eq!(output.aroon_down, 0.0);
+        assert_eq!(aroon.current(), Some(100.0));
+    }
+
+    #[test]
+    fn test_matches_window_rescan() {
+        let period = 7;
+        let mut aroon = Aroon::new(period);
+        let mut bars = Vec::new();
+        for i in 0..200 {
+            // Rounded so ties occur
+            let mid =
+                (100.0 + (i as f64 * 0.37).sin() * 5.0 + (i as f64 * 0.11).cos() * 3.0).round();
+            bars.push(bar(i, mid + 1.0, mid - 1.0));
+            aroon.update(bars[bars.len() - 1]);
+
+            if bars.len() > period {
+                assert_eq!(
+                    aroon.get_aroon().unwrap(),
+                    naive(&bars, period),
+                    "bar {}",
+                    i
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut aroon = Aroon::new(3);
+        for i in 0..10 {
+            aroon.update(bar(i, 101.0 + i as f64, 99.0));
+        }
+        assert!(aroon.structured_output().is_some());
+
+        aroon.reset();
+        assert!(aroon.current().is_none());
+        assert!(aroon.update(bar(0, 101.0, 99.0)).is_none());
+    }
+}
@@ -0,0 +1,197 @@
+use super::swing::{find_swing_points, SwingKind, SwingPoint};
+use crate::indicators::indicator_trait::IndicatorValue;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DivergenceKind {
+    /// Price makes a lower low while the oscillator makes a higher low
+    Bullish,
+    /// Price makes a higher high while the oscillator makes a lower high
+    Bearish,
+    /// Price makes a higher low while the oscillator makes a lower low
+    HiddenBullish,
+    /// Price makes a lower high while the oscillator makes a higher high
+    HiddenBearish,
+}
+
+/// Divergence between two consecutive confirmed price swings and the
+/// oscillator values at the same timestamps
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Divergence {
+    pub kind: DivergenceKind,
+    /// The later of the two price swings; the signal time
+    pub price_pivot: SwingPoint,
+    /// Oscillator value at `price_pivot`
+    pub osc_pivot: SwingPoint,
+    pub prior_price_pivot: SwingPoint,
+    pub prior_osc_pivot: SwingPoint,
+}
+
+/// Finds regular and hidden divergences between price and an oscillator
+/// such as RSI or the MACD histogram.
+///
+/// Swings are confirmed with `strength` bars on each side, so the most
+/// recent `strength` bars never produce a signal and a reported divergence
+/// stays reported as more bars arrive.
+#[derive(Debug, Clone, Copy)]
+pub struct DivergenceDetector {
+    strength: usize,
+    lookback: usize,
+}
+
+impl DivergenceDetector {
+    /// `lookback` is the most bars allowed between the two compared swings
+    pub fn new(strength: usize, lookback: usize) -> Self {
+        Self {
+            strength: strength.max(1),
+            lookback,
+        }
+    }
+
+    /// Divergences in time order. Series are matched by timestamp; price
+    /// swings with no oscillator value at the same timestamp are skipped.
+    pub fn detect(
+        &self,
+        price: &[IndicatorValue],
+        oscillator: &[IndicatorValue],
+    ) -> Vec<Divergence> {
+        let osc_by_time: HashMap<i64, (usize, f64)> = oscillator
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (v.timestamp, (i, v.value)))
+            .collect();
+
+        let swings = find_swing_points(price, self.strength);
+        let mut divergences = Vec::new();
+
+        for kind in [SwingKind::High, SwingKind::Low] {
+            let same_kind: Vec<(SwingPoint, SwingPoint)> = swings
+                .iter()
+                .filter(|s| s.kind == kind)
+                .filter_map(|s| {
+                    let &(index, value) = osc_by_time.get(&s.timestamp)?;
+                    Some((*s, SwingPoint { index, value, ..*s }))
+                })
+                .collect();
+
+            for pair in same_kind.windows(2) {
+                let (prior_price, prior_osc) = pair[0];
+                let (price_pivot, osc_pivot) = pair[1];
+                if price_pivot.index - prior_price.index > self.lookback {
+                    continue;
+                }
+
+                let price_up = price_pivot.value > prior_price.value;
+                let price_down = price_pivot.value < prior_price.value;
+                let osc_up = osc_pivot.value > prior_osc.value;
+                let osc_down = osc_pivot.value < prior_osc.value;
+
+                let divergence_kind = match kind {
+                    SwingKind::Low if price_down && osc_up => DivergenceKind::Bullish,
+                    SwingKind::Low if price_up && osc_down => DivergenceKind::HiddenBullish,
+                    SwingKind::High if price_up && osc_down => DivergenceKind::Bearish,
+                    SwingKind::High if price_down && osc_up => DivergenceKind::HiddenBearish,
+                    _ => continue,
+                };
+
+                divergences.push(Divergence {
+                    kind: divergence_kind,
+                    price_pivot,
+                    osc_pivot,
+                    prior_price_pivot: prior_price,
+                    prior_osc_pivot: prior_osc,
+                });
+            }
+        }
+
+        divergences.sort_by_key(|d| d.price_pivot.timestamp);
+        divergences
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(values: &[f64]) -> Vec<IndicatorValue> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| IndicatorValue {
+                value,
+                timestamp: 1_000 * i as i64,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_bullish_and_hidden_bearish() {
+        // Lows at 2 and 6: price lower low, oscillator higher low.
+        // Highs at 4 and 8: price lower high, oscillator higher high.
+        let price = series(&[5.0, 4.0, 2.0, 3.0, 4.0, 3.0, 1.5, 3.0, 3.5, 3.0, 3.2]);
+        let osc = series(&[
+            50.0, 40.0, 20.0, 30.0, 45.0, 35.0, 25.0, 40.0, 55.0, 50.0, 52.0,
+        ]);
+
+        let divergences = DivergenceDetector::new(1, 10).detect(&price, &osc);
+        let kinds: Vec<DivergenceKind> = divergences.iter().map(|d| d.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![DivergenceKind::Bullish, DivergenceKind::HiddenBearish]
+        );
+
+        let bullish = &divergences[0];
+        assert_eq!(bullish.price_pivot.index, 6);
+        assert_eq!(bullish.prior_price_pivot.index, 2);
+        assert_eq!(bullish.osc_pivot.value, 25.0);
+    }
+
+    #[test]
+    fn test_bearish_and_hidden_bullish() {
+        // Highs at 2 and 6: price higher high, oscillator lower high.
+        // Lows at 4 and 8: price higher low, oscillator lower low.
+        let price = series(&[1.0, 2.0, 4.0, 3.0, 2.0, 3.0, 5.0, 4.0, 2.5, 3.0, 2.8]);
+        let osc = series(&[
+            50.0, 60.0, 80.0, 60.0, 40.0, 60.0, 70.0, 50.0, 30.0, 40.0, 38.0,
+        ]);
+
+        let kinds: Vec<DivergenceKind> = DivergenceDetector::new(1, 10)
+            .detect(&price, &osc)
+            .iter()
+            .map(|d| d.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![DivergenceKind::Bearish, DivergenceKind::HiddenBullish]
+        );
+    }
+
+    #[test]
+    fn test_lookback_limits_pivot_distance() {
+        let price = series(&[5.0, 4.0, 2.0, 3.0, 4.0, 3.0, 1.5, 3.0, 3.5]);
+        let osc = series(&[50.0, 40.0, 20.0, 30.0, 45.0, 35.0, 25.0, 40.0, 55.0]);
+        assert!(DivergenceDetector::new(1, 3)
+            .detect(&price, &osc)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_signals_never_disappear() {
+        // The dip to 1.5 at bar 6 is undercut at bar 7, so the real low is 7
+        let price = series(&[5.0, 4.0, 2.0, 3.0, 4.0, 3.0, 1.5, 1.0, 3.0, 3.5]);
+        let osc = series(&[50.0, 40.0, 20.0, 30.0, 45.0, 35.0, 25.0, 22.0, 40.0, 55.0]);
+        let detector = DivergenceDetector::new(1, 10);
+
+        // Before bar 7 arrives the dip at 6 is unconfirmed and must not signal
+        assert!(detector.detect(&price[..7], &osc[..7]).is_empty());
+
+        let mut previous = Vec::new();
+        for end in 3..=price.len() {
+            let current = detector.detect(&price[..end], &osc[..end]);
+            assert!(previous.iter().all(|d| current.contains(d)));
+            previous = current;
+        }
+        assert_eq!(previous.len(), 1);
+        assert_eq!(previous[0].price_pivot.index, 7);
+    }
+}
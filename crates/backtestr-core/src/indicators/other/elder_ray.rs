@@ -0,0 +1,142 @@
+//! Elder Ray implementation.
+//!
+//! Bull Power is how far buyers pushed the high above the consensus value
+//! (an EMA of closes), Bear Power how far sellers pushed the low below it.
+
+use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::output::IndicatorOutput;
+use crate::indicators::trend::EMA;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ElderRayOutput {
+    /// `high - EMA`
+    pub bull_power: f64,
+    /// `low - EMA`
+    pub bear_power: f64,
+}
+
+/// Elder Ray Bull and Bear Power around an EMA of `period` closes.
+///
+/// The scalar output is `bull_power + bear_power`, positive when the bar
+/// sits above the EMA overall; the full output is available from
+/// `get_elder_ray`.
+#[derive(Debug, Clone)]
+pub struct ElderRay {
+    ema: EMA,
+    current: Option<ElderRayOutput>,
+}
+
+impl ElderRay {
+    /// Standard period is 13.
+    pub fn new(period: usize) -> Self {
+        Self {
+            ema: EMA::new(period.max(1)),
+            current: None,
+        }
+    }
+
+    pub fn get_elder_ray(&self) -> Option<ElderRayOutput> {
+        self.current
+    }
+}
+
+impl Indicator for ElderRay {
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        "ElderRay"
+    }
+
+    fn warm_up_period(&self) -> usize {
+        self.ema.warm_up_period()
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        let ema = self.ema.update(input)?;
+        self.current = Some(ElderRayOutput {
+            bull_power: input.high - ema,
+            bear_power: input.low - ema,
+        });
+        self.current()
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current
+            .map(|output| output.bull_power + output.bear_power)
+    }
+
+    fn reset(&mut self) {
+        self.ema.reset();
+        self.current = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn structured_output(&self) -> Option<IndicatorOutput> {
+        self.current.map(IndicatorOutput::ElderRay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(i: i64, close: f64) -> BarData {
+        BarData {
+            open: close,
+            high: close + 2.0,
+            low: close - 1.0,
+            close,
+            volume: 1000.0,
+            timestamp: i,
+        }
+    }
+
+    #[test]
+    fn test_none_until_ema_ready() {
+        let mut elder = ElderRay::new(3);
+        assert_eq!(elder.warm_up_period(), 3);
+        assert!(elder.update(bar(0, 100.0)).is_none());
+        assert!(elder.update(bar(1, 101.0)).is_none());
+        assert!(elder.get_elder_ray().is_none());
+        assert!(elder.update(bar(2, 102.0)).is_some());
+    }
+
+    #[test]
+    fn test_powers_against_ema() {
+        let mut elder = ElderRay::new(3);
+        let mut ema = EMA::new(3);
+        let mut last = None;
+        for i in 0..10 {
+            let input = bar(i, 100.0 + 2.0 * i as f64);
+            last = elder.update(input);
+            let ema = ema.update(input);
+            if let Some(ema) = ema {
+                let output = elder.get_elder_ray().unwrap();
+                assert!((output.bull_power - (input.high - ema)).abs() < 1e-12);
+                assert!((output.bear_power - (input.low - ema)).abs() < 1e-12);
+            }
+        }
+
+        let output = elder.get_elder_ray().unwrap();
+        assert_eq!(last, Some(output.bull_power + output.bear_power));
+        // In a steady uptrend the low stays above the lagging EMA
+        assert!(output.bear_power > 0.0);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut elder = ElderRay::new(2);
+        for i in 0..5 {
+            elder.update(bar(i, 100.0));
+        }
+        assert!(elder.structured_output().is_some());
+
+        elder.reset();
+        assert!(elder.current().is_none());
+        assert!(elder.update(bar(0, 100.0)).is_none());
+    }
+}
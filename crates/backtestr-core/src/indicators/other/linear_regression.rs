@@ -0,0 +1,245 @@
+//! Linear regression channel implementation.
+//!
+//! Fits a least-squares line through the last `period` closes. The
+//! endpoint of the line is a low-lag moving average, the slope a measure of
+//! trend strength, and the bands bracket it by the spread of the residuals.
+
+use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::output::IndicatorOutput;
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinRegOutput {
+    /// The fitted line at the latest bar
+    pub value: f64,
+    /// Price change per bar along the fitted line
+    pub slope: f64,
+    pub upper: f64,
+    pub lower: f64,
+}
+
+/// Least-squares line over the last `period` closes.
+///
+/// The fit is kept incremental: x is the bar's position in the window, so
+/// `sum_x` and `sum_x2` are fixed once the window is full and `sum_xy`
+/// shifts by `sum_y` as the window slides. Each update is O(1). The scalar
+/// output is the regression endpoint.
+#[derive(Debug, Clone)]
+pub struct LinearRegression {
+    period: usize,
+    deviations: f64,
+    closes: VecDeque<f64>,
+    sum_x: f64,
+    sum_x2: f64,
+    sum_y: f64,
+    sum_y2: f64,
+    sum_xy: f64,
+    current: Option<LinRegOutput>,
+}
+
+impl LinearRegression {
+    /// Bands default to 2 standard deviations of the residuals.
+    pub fn new(period: usize) -> Self {
+        // A line needs at least two points
+        let period = period.max(2);
+        Self {
+            period,
+            deviations: 2.0,
+            closes: VecDeque::with_capacity(period + 1),
+            sum_x: 0.0,
+            sum_x2: 0.0,
+            sum_y: 0.0,
+            sum_y2: 0.0,
+            sum_xy: 0.0,
+            current: None,
+        }
+    }
+
+    pub fn with_deviations(mut self, deviations: f64) -> Self {
+        self.deviations = deviations;
+        self
+    }
+
+    pub fn get_linreg(&self) -> Option<LinRegOutput> {
+        self.current
+    }
+}
+
+impl Indicator for LinearRegression {
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        "LinReg"
+    }
+
+    fn warm_up_period(&self) -> usize {
+        self.period
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        let y = input.close;
+
+        if self.closes.len() == self.period {
+            // Every remaining bar moves one position left, so each loses
+            // its own y from sum_xy; the oldest (at x = 0) contributed none
+            let oldest = self.closes.pop_front().unwrap();
+            self.sum_y -= oldest;
+            self.sum_y2 -= oldest * oldest;
+            self.sum_xy -= self.sum_y;
+        } else {
+            let x = self.closes.len() as f64;
+            self.sum_x += x;
+            self.sum_x2 += x * x;
+        }
+
+        let x = self.closes.len() as f64;
+        self.closes.push_back(y);
+        self.sum_y += y;
+        self.sum_y2 += y * y;
+        self.sum_xy += x * y;
+
+        if self.closes.len() < self.period {
+            return None;
+        }
+
+        let n = self.period as f64;
+        let slope = (n * self.sum_xy - self.sum_x * self.sum_y)
+            / (n * self.sum_x2 - self.sum_x * self.sum_x);
+        let intercept = (self.sum_y - slope * self.sum_x) / n;
+        let value = intercept + slope * (n - 1.0);
+
+        // Sum of squared residuals of a least-squares fit; clamped because
+        // cancellation can leave it slightly negative on a perfect line
+        let ssr = (self.sum_y2 - intercept * self.sum_y - slope * self.sum_xy).max(0.0);
+        let band = self.deviations * (ssr / n).sqrt();
+
+        let output = LinRegOutput {
+            value,
+            slope,
+            upper: value + band,
+            lower: value - band,
+        };
+        self.current = Some(output);
+        Some(value)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current.map(|output| output.value)
+    }
+
+    fn reset(&mut self) {
+        self.closes.clear();
+        self.sum_x = 0.0;
+        self.sum_x2 = 0.0;
+        self.sum_y = 0.0;
+        self.sum_y2 = 0.0;
+        self.sum_xy = 0.0;
+        self.current = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn structured_output(&self) -> Option<IndicatorOutput> {
+        self.current.map(IndicatorOutput::LinReg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(i: i64, close: f64) -> BarData {
+        BarData {
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 1000.0,
+            timestamp: i,
+        }
+    }
+
+    /// Refits the window from scratch, for checking the running sums
+    fn naive(closes: &[f64], deviations: f64) -> LinRegOutput {
+        let n = closes.len() as f64;
+        let mean_x = (n - 1.0) / 2.0;
+        let mean_y = closes.iter().sum::<f64>() / n;
+        let mut sxy = 0.0;
+        let mut sxx = 0.0;
+        for (i, y) in closes.iter().enumerate() {
+            sxy += (i as f64 - mean_x) * (y - mean_y);
+            sxx += (i as f64 - mean_x).powi(2);
+        }
+        let slope = sxy / sxx;
+        let intercept = mean_y - slope * mean_x;
+        let ssr: f64 = closes
+            .iter()
+            .enumerate()
+            .map(|(i, y)| (y - (intercept + slope * i as f64)).powi(2))
+            .sum();
+        let value = intercept + slope * (n - 1.0);
+        let band = deviations * (ssr / n).sqrt();
+        LinRegOutput {
+            value,
+            slope,
+            upper: value + band,
+            lower: value - band,
+        }
+    }
+
+    #[test]
+    fn test_perfect_line() {
+        let mut linreg = LinearRegression::new(5);
+        for i in 0..4 {
+            assert!(linreg.update(bar(i, 100.0 + 2.0 * i as f64)).is_none());
+        }
+        for i in 4..20 {
+            linreg.update(bar(i, 100.0 + 2.0 * i as f64));
+        }
+
+        let output = linreg.get_linreg().unwrap();
+        assert!((output.value - 138.0).abs() < 1e-9);
+        assert!((output.slope - 2.0).abs() < 1e-9);
+        assert!((output.upper - output.value).abs() < 1e-6);
+        assert_eq!(linreg.current(), Some(output.value));
+    }
+
+    #[test]
+    fn test_matches_refit() {
+        let period = 10;
+        let mut linreg = LinearRegression::new(period).with_deviations(1.5);
+        let mut closes = Vec::new();
+        for i in 0..200 {
+            let close = 100.0 + (i as f64 * 0.3).sin() * 4.0 + i as f64 * 0.05;
+            closes.push(close);
+            linreg.update(bar(i, close));
+
+            if closes.len() >= period {
+                let expected = naive(&closes[closes.len() - period..], 1.5);
+                let output = linreg.get_linreg().unwrap();
+                assert!((output.value - expected.value).abs() < 1e-6, "bar {}", i);
+                assert!((output.slope - expected.slope).abs() < 1e-6, "bar {}", i);
+                assert!((output.upper - expected.upper).abs() < 1e-6, "bar {}", i);
+                assert!((output.lower - expected.lower).abs() < 1e-6, "bar {}", i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_linreg_reset() {
+        let mut linreg = LinearRegression::new(3);
+        for i in 0..10 {
+            linreg.update(bar(i, 100.0 + i as f64));
+        }
+        assert!(linreg.structured_output().is_some());
+
+        linreg.reset();
+        assert!(linreg.current().is_none());
+        assert!(linreg.update(bar(0, 100.0)).is_none());
+        assert!(linreg.update(bar(1, 101.0)).is_none());
+        assert!(linreg.update(bar(2, 102.0)).is_some());
+    }
+}
@@ -1,9 +1,23 @@
 pub mod adx;
+pub mod aroon;
+pub mod divergence;
+pub mod elder_ray;
+pub mod linear_regression;
 pub mod parabolic_sar;
 pub mod pivot;
 pub mod support_resistance;
+pub mod swing;
+pub mod vortex;
+pub mod zigzag;
 
 pub use adx::ADX;
+pub use aroon::{Aroon, AroonOutput};
+pub use divergence::{Divergence, DivergenceDetector, DivergenceKind};
+pub use elder_ray::{ElderRay, ElderRayOutput};
+pub use linear_regression::{LinRegOutput, LinearRegression};
 pub use parabolic_sar::ParabolicSAR;
-pub use pivot::{PivotOutput, PivotPoints};
+pub use pivot::{PivotMethod, PivotOutput, PivotPoints};
 pub use support_resistance::{SupportResistance, SupportResistanceOutput};
+pub use swing::{find_swing_points, SwingKind, SwingPoint};
+pub use vortex::{Vortex, VortexOutput};
+pub use zigzag::{ZigZag, ZigZagOutput};
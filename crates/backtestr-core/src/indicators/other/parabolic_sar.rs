@@ -14,7 +14,7 @@ use crate::indicators::indicator_trait::{BarData, Indicator};
 ///
 /// - `acceleration`: Initial acceleration factor (typically 0.02)
 /// - `max_acceleration`: Maximum acceleration factor (typically 0.2)
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ParabolicSAR {
     acceleration_factor: f64,
     acceleration_step: f64,
@@ -147,6 +147,10 @@ impl Indicator for ParabolicSAR {
         self.is_long = true;
         self.previous_bar = None;
     }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
 }
 
 /// Returns the position side indicated by the SAR
@@ -1,57 +1,169 @@
 use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::output::IndicatorOutput;
 
-#[derive(Debug)]
-pub struct PivotPoints {
-    previous_high: Option<f64>,
-    previous_low: Option<f64>,
-    previous_close: Option<f64>,
-    current_pivot: Option<f64>,
-    current_r1: Option<f64>,
-    current_r2: Option<f64>,
-    current_s1: Option<f64>,
-    current_s2: Option<f64>,
+/// How the levels are derived from the prior bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PivotMethod {
+    /// Typical-price pivot with two levels each side
+    #[default]
+    Classic,
+    /// Levels around the prior close at 1.1/12, 1.1/6, 1.1/4 and 1.1/2 of
+    /// the range, four each side
+    Camarilla,
+    /// Classic levels around a pivot that weights the close twice
+    Woodie,
+    /// Classic pivot with levels at 0.382, 0.618 and 1.0 of the range
+    Fibonacci,
+    /// Pivot weighted by whether the bar closed up or down. DeMark defines
+    /// one level each side; R2 and S2 sit a full range from the pivot as in
+    /// Classic
+    DeMark,
 }
 
 #[derive(Debug, Clone)]
+pub struct PivotPoints {
+    method: PivotMethod,
+    previous: Option<BarData>,
+    current: Option<PivotOutput>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct PivotOutput {
+    pub method: PivotMethod,
     pub pivot: f64,
     pub r1: f64,
     pub r2: f64,
     pub s1: f64,
     pub s2: f64,
+    /// Levels past R2, R3 first; only Camarilla and Fibonacci have any
+    pub extra_resistance: Vec<f64>,
+    /// Levels past S2, S3 first; only Camarilla and Fibonacci have any
+    pub extra_support: Vec<f64>,
+}
+
+impl PivotOutput {
+    /// Resistance level `n`, counting from 1 (`r(1)` is R1)
+    pub fn r(&self, n: usize) -> Option<f64> {
+        match n {
+            0 => None,
+            1 => Some(self.r1),
+            2 => Some(self.r2),
+            _ => self.extra_resistance.get(n - 3).copied(),
+        }
+    }
+
+    /// Support level `n`, counting from 1 (`s(1)` is S1)
+    pub fn s(&self, n: usize) -> Option<f64> {
+        match n {
+            0 => None,
+            1 => Some(self.s1),
+            2 => Some(self.s2),
+            _ => self.extra_support.get(n - 3).copied(),
+        }
+    }
+
+    /// Every resistance level, R1 first
+    pub fn resistance(&self) -> Vec<f64> {
+        [self.r1, self.r2]
+            .into_iter()
+            .chain(self.extra_resistance.iter().copied())
+            .collect()
+    }
+
+    /// Every support level, S1 first
+    pub fn support(&self) -> Vec<f64> {
+        [self.s1, self.s2]
+            .into_iter()
+            .chain(self.extra_support.iter().copied())
+            .collect()
+    }
 }
 
 impl PivotPoints {
     pub fn new() -> Self {
+        Self::with_method(PivotMethod::Classic)
+    }
+
+    pub fn with_method(method: PivotMethod) -> Self {
         Self {
-            previous_high: None,
-            previous_low: None,
-            previous_close: None,
-            current_pivot: None,
-            current_r1: None,
-            current_r2: None,
-            current_s1: None,
-            current_s2: None,
+            method,
+            previous: None,
+            current: None,
         }
     }
 
+    pub fn method(&self) -> PivotMethod {
+        self.method
+    }
+
     pub fn get_levels(&self) -> Option<PivotOutput> {
-        if let (Some(pivot), Some(r1), Some(r2), Some(s1), Some(s2)) = (
-            self.current_pivot,
-            self.current_r1,
-            self.current_r2,
-            self.current_s1,
-            self.current_s2,
-        ) {
-            Some(PivotOutput {
-                pivot,
-                r1,
-                r2,
-                s1,
-                s2,
-            })
-        } else {
-            None
+        self.current.clone()
+    }
+
+    fn levels(&self, prev: &BarData) -> PivotOutput {
+        let (high, low, close) = (prev.high, prev.low, prev.close);
+        let range = high - low;
+        let typical = (high + low + close) / 3.0;
+
+        // Levels moving away from the pivot, R1 and S1 first
+        let (pivot, resistance, support): (f64, Vec<f64>, Vec<f64>) = match self.method {
+            PivotMethod::Classic => (
+                typical,
+                vec![2.0 * typical - low, typical + range],
+                vec![2.0 * typical - high, typical - range],
+            ),
+            PivotMethod::Camarilla => {
+                let offsets = [12.0, 6.0, 4.0, 2.0].map(|divisor| range * 1.1 / divisor);
+                (
+                    typical,
+                    offsets.iter().map(|o| close + o).collect(),
+                    offsets.iter().map(|o| close - o).collect(),
+                )
+            }
+            PivotMethod::Woodie => {
+                let pivot = (high + low + 2.0 * close) / 4.0;
+                (
+                    pivot,
+                    vec![2.0 * pivot - low, pivot + range],
+                    vec![2.0 * pivot - high, pivot - range],
+                )
+            }
+            PivotMethod::Fibonacci => {
+                let ratios = [0.382, 0.618, 1.0];
+                (
+                    typical,
+                    ratios.iter().map(|r| typical + r * range).collect(),
+                    ratios.iter().map(|r| typical - r * range).collect(),
+                )
+            }
+            PivotMethod::DeMark => {
+                // Down bars weight the low, up bars the high, and a bar
+                // that closed where it opened weights the close
+                let x = if close < prev.open {
+                    high + 2.0 * low + close
+                } else if close > prev.open {
+                    2.0 * high + low + close
+                } else {
+                    high + low + 2.0 * close
+                };
+                let pivot = x / 4.0;
+                (
+                    pivot,
+                    vec![x / 2.0 - low, pivot + range],
+                    vec![x / 2.0 - high, pivot - range],
+                )
+            }
+        };
+
+        PivotOutput {
+            method: self.method,
+            pivot,
+            r1: resistance[0],
+            r2: resistance[1],
+            s1: support[0],
+            s2: support[1],
+            extra_resistance: resistance[2..].to_vec(),
+            extra_support: support[2..].to_vec(),
         }
     }
 }
@@ -75,41 +187,144 @@ impl Indicator for PivotPoints {
     }
 
     fn update(&mut self, input: BarData) -> Option<f64> {
-        if let (Some(prev_high), Some(prev_low), Some(prev_close)) =
-            (self.previous_high, self.previous_low, self.previous_close)
-        {
-            let pivot = (prev_high + prev_low + prev_close) / 3.0;
-            let r1 = 2.0 * pivot - prev_low;
-            let r2 = pivot + (prev_high - prev_low);
-            let s1 = 2.0 * pivot - prev_high;
-            let s2 = pivot - (prev_high - prev_low);
-
-            self.current_pivot = Some(pivot);
-            self.current_r1 = Some(r1);
-            self.current_r2 = Some(r2);
-            self.current_s1 = Some(s1);
-            self.current_s2 = Some(s2);
+        if let Some(prev) = self.previous {
+            self.current = Some(self.levels(&prev));
         }
+        self.previous = Some(input);
 
-        self.previous_high = Some(input.high);
-        self.previous_low = Some(input.low);
-        self.previous_close = Some(input.close);
-
-        self.current_pivot
+        self.current()
     }
 
     fn current(&self) -> Option<f64> {
-        self.current_pivot
+        self.current.as_ref().map(|levels| levels.pivot)
     }
 
     fn reset(&mut self) {
-        self.previous_high = None;
-        self.previous_low = None;
-        self.previous_close = None;
-        self.current_pivot = None;
-        self.current_r1 = None;
-        self.current_r2 = None;
-        self.current_s1 = None;
-        self.current_s2 = None;
+        self.previous = None;
+        self.current = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn structured_output(&self) -> Option<IndicatorOutput> {
+        self.current.clone().map(IndicatorOutput::Pivot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(open: f64, high: f64, low: f64, close: f64, timestamp: i64) -> BarData {
+        BarData {
+            open,
+            high,
+            low,
+            close,
+            volume: 1000.0,
+            timestamp,
+        }
+    }
+
+    fn levels_after(method: PivotMethod, prev: BarData) -> PivotOutput {
+        let mut pivots = PivotPoints::with_method(method);
+        assert!(pivots.update(prev).is_none());
+        pivots.update(bar(100.0, 101.0, 99.0, 100.0, 2));
+        pivots.get_levels().unwrap()
+    }
+
+    fn assert_levels(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_classic_is_default() {
+        let prev = bar(100.0, 110.0, 90.0, 105.0, 1);
+        let levels = levels_after(PivotMethod::Classic, prev);
+        assert_eq!(PivotPoints::new().method(), PivotMethod::Classic);
+
+        // P = (110 + 90 + 105) / 3
+        let p = 305.0 / 3.0;
+        assert!((levels.pivot - p).abs() < 1e-9);
+        assert!((levels.r1 - (2.0 * p - 90.0)).abs() < 1e-9);
+        assert!((levels.r2 - (p + 20.0)).abs() < 1e-9);
+        assert!((levels.s1 - (2.0 * p - 110.0)).abs() < 1e-9);
+        assert!((levels.s2 - (p - 20.0)).abs() < 1e-9);
+        assert!(levels.extra_resistance.is_empty() && levels.extra_support.is_empty());
+        assert_eq!(levels.r(1), Some(levels.r1));
+        assert_eq!(levels.s(2), Some(levels.s2));
+        assert_eq!(levels.s(3), None);
+        assert_eq!(levels.r(0), None);
+    }
+
+    #[test]
+    fn test_camarilla() {
+        let levels = levels_after(PivotMethod::Camarilla, bar(100.0, 110.0, 90.0, 105.0, 1));
+        // 1.1 * 20 = 22, split by 12, 6, 4 and 2 around the close
+        assert_levels(
+            &levels.resistance(),
+            &[105.0 + 22.0 / 12.0, 105.0 + 22.0 / 6.0, 110.5, 116.0],
+        );
+        assert_levels(
+            &levels.support(),
+            &[105.0 - 22.0 / 12.0, 105.0 - 22.0 / 6.0, 99.5, 94.0],
+        );
+    }
+
+    #[test]
+    fn test_woodie() {
+        let levels = levels_after(PivotMethod::Woodie, bar(100.0, 110.0, 90.0, 105.0, 1));
+        // P = (110 + 90 + 2 * 105) / 4
+        assert_eq!(levels.pivot, 102.5);
+        assert_levels(&levels.resistance(), &[115.0, 122.5]);
+        assert_levels(&levels.support(), &[95.0, 82.5]);
+    }
+
+    #[test]
+    fn test_fibonacci() {
+        let levels = levels_after(PivotMethod::Fibonacci, bar(100.0, 110.0, 90.0, 100.0, 1));
+        assert_eq!(levels.pivot, 100.0);
+        assert_levels(&levels.resistance(), &[107.64, 112.36, 120.0]);
+        assert_levels(&levels.support(), &[92.36, 87.64, 80.0]);
+    }
+
+    #[test]
+    fn test_demark_branches_on_open_and_close() {
+        // Up bar: X = 2H + L + C
+        let up = levels_after(PivotMethod::DeMark, bar(100.0, 110.0, 90.0, 105.0, 1));
+        let x = 2.0 * 110.0 + 90.0 + 105.0;
+        assert_eq!(up.pivot, x / 4.0);
+        assert_levels(&up.resistance(), &[x / 2.0 - 90.0, x / 4.0 + 20.0]);
+        assert_levels(&up.support(), &[x / 2.0 - 110.0, x / 4.0 - 20.0]);
+
+        // Down bar: X = H + 2L + C
+        let down = levels_after(PivotMethod::DeMark, bar(105.0, 110.0, 90.0, 100.0, 1));
+        assert_eq!(down.pivot, (110.0 + 180.0 + 100.0) / 4.0);
+
+        // Unchanged bar: X = H + L + 2C
+        let flat = levels_after(PivotMethod::DeMark, bar(100.0, 110.0, 90.0, 100.0, 1));
+        assert_eq!(flat.pivot, (110.0 + 90.0 + 200.0) / 4.0);
+        assert_eq!((flat.r1, flat.s1), (110.0, 90.0));
+    }
+
+    #[test]
+    fn test_levels_follow_prior_bar() {
+        let mut pivots = PivotPoints::new();
+        pivots.update(bar(100.0, 110.0, 90.0, 100.0, 1));
+        let first = pivots.update(bar(100.0, 130.0, 100.0, 130.0, 2));
+        assert_eq!(first, Some(100.0));
+
+        // The second bar is now the prior bar
+        let second = pivots.update(bar(100.0, 101.0, 99.0, 100.0, 3));
+        assert_eq!(second, Some(120.0));
+        assert!(pivots.structured_output().is_some());
+
+        pivots.reset();
+        assert!(pivots.current().is_none());
     }
 }
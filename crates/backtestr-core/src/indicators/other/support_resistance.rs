@@ -1,7 +1,7 @@
 use crate::indicators::indicator_trait::{BarData, Indicator};
 use std::collections::VecDeque;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SupportResistance {
     period: usize,
     highs: VecDeque<f64>,
@@ -87,4 +87,8 @@ impl Indicator for SupportResistance {
         self.current_resistance = None;
         self.current_support = None;
     }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
 }
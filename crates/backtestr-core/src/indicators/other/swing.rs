@@ -0,0 +1,97 @@
+use crate::indicators::indicator_trait::IndicatorValue;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwingKind {
+    High,
+    Low,
+}
+
+/// A confirmed local extreme in a value series
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwingPoint {
+    pub kind: SwingKind,
+    /// Position in the series the swing was found in
+    pub index: usize,
+    pub timestamp: i64,
+    pub value: f64,
+}
+
+/// Find swing highs and lows with `strength` values on each side.
+///
+/// A swing high is strictly above the `strength` values before it and at
+/// least as high as the `strength` values after it (lows mirror this), so a
+/// flat top yields one swing. The last `strength` values can never be
+/// swings: a pivot only counts once enough later values confirm it, and a
+/// confirmed pivot never disappears as more data arrives.
+pub fn find_swing_points(values: &[IndicatorValue], strength: usize) -> Vec<SwingPoint> {
+    let strength = strength.max(1);
+    if values.len() < 2 * strength + 1 {
+        return Vec::new();
+    }
+
+    let mut swings = Vec::new();
+    for index in strength..values.len() - strength {
+        let value = values[index].value;
+        let before = &values[index - strength..index];
+        let after = &values[index + 1..=index + strength];
+
+        let kind = if before.iter().all(|v| value > v.value)
+            && after.iter().all(|v| value >= v.value)
+        {
+            SwingKind::High
+        } else if before.iter().all(|v| value < v.value) && after.iter().all(|v| value <= v.value) {
+            SwingKind::Low
+        } else {
+            continue;
+        };
+
+        swings.push(SwingPoint {
+            kind,
+            index,
+            timestamp: values[index].timestamp,
+            value,
+        });
+    }
+    swings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(values: &[f64]) -> Vec<IndicatorValue> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| IndicatorValue {
+                value,
+                timestamp: i as i64,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_finds_highs_and_lows() {
+        let values = series(&[1.0, 3.0, 2.0, 0.5, 2.5, 2.5, 1.0]);
+        let swings = find_swing_points(&values, 1);
+
+        let found: Vec<(SwingKind, usize)> = swings.iter().map(|s| (s.kind, s.index)).collect();
+        // The flat top at 4..5 is one swing
+        assert_eq!(
+            found,
+            vec![
+                (SwingKind::High, 1),
+                (SwingKind::Low, 3),
+                (SwingKind::High, 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unconfirmed_tail_is_ignored() {
+        // 5.0 looks like a high but only one value follows it
+        let values = series(&[1.0, 2.0, 1.0, 3.0, 5.0, 4.0]);
+        assert!(find_swing_points(&values, 2).iter().all(|s| s.index != 4));
+        assert!(find_swing_points(&values[..2], 1).is_empty());
+    }
+}
@@ -0,0 +1,178 @@
+//! Vortex Indicator implementation.
+//!
+//! VI+ measures how far each high reaches above the previous low, VI- how
+//! far each low reaches below the previous high, both normalized by the
+//! true range over the same bars. VI+ above VI- indicates an uptrend.
+
+use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::output::IndicatorOutput;
+use crate::indicators::volatility::atr::true_range;
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VortexOutput {
+    pub vi_plus: f64,
+    pub vi_minus: f64,
+}
+
+/// Vortex Indicator over `period` bars.
+///
+/// The scalar output is `vi_plus - vi_minus`, so a crossing is a sign
+/// change; the full output is available from `get_vortex`.
+#[derive(Debug, Clone)]
+pub struct Vortex {
+    period: usize,
+    /// (VM+, VM-, TR) per bar after the first
+    movements: VecDeque<(f64, f64, f64)>,
+    previous: Option<BarData>,
+    current: Option<VortexOutput>,
+}
+
+impl Vortex {
+    /// Standard period is 14.
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            movements: VecDeque::with_capacity(period),
+            previous: None,
+            current: None,
+        }
+    }
+
+    pub fn get_vortex(&self) -> Option<VortexOutput> {
+        self.current
+    }
+}
+
+impl Indicator for Vortex {
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        "Vortex"
+    }
+
+    fn warm_up_period(&self) -> usize {
+        self.period + 1
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        // Both movements and the true range need the previous bar
+        let previous = self.previous.replace(input)?;
+
+        let vm_plus = (input.high - previous.low).abs();
+        let vm_minus = (input.low - previous.high).abs();
+        let tr = true_range(&input, Some(previous.close));
+
+        self.movements.push_back((vm_plus, vm_minus, tr));
+        if self.movements.len() > self.period {
+            self.movements.pop_front();
+        }
+
+        if self.movements.len() < self.period {
+            return None;
+        }
+
+        // A run of identical flat bars has no range to normalize by
+        let tr_sum: f64 = self.movements.iter().map(|m| m.2).sum();
+        if tr_sum <= 0.0 {
+            self.current = None;
+            return None;
+        }
+
+        let output = VortexOutput {
+            vi_plus: self.movements.iter().map(|m| m.0).sum::<f64>() / tr_sum,
+            vi_minus: self.movements.iter().map(|m| m.1).sum::<f64>() / tr_sum,
+        };
+        self.current = Some(output);
+        Some(output.vi_plus - output.vi_minus)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current.map(|output| output.vi_plus - output.vi_minus)
+    }
+
+    fn reset(&mut self) {
+        self.movements.clear();
+        self.previous = None;
+        self.current = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn structured_output(&self) -> Option<IndicatorOutput> {
+        self.current.map(IndicatorOutput::Vortex)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(i: i64, high: f64, low: f64, close: f64) -> BarData {
+        BarData {
+            open: close,
+            high,
+            low,
+            close,
+            volume: 1000.0,
+            timestamp: i,
+        }
+    }
+
+    #[test]
+    fn test_vortex_calculation() {
+        let mut vortex = Vortex::new(2);
+        assert_eq!(vortex.warm_up_period(), 3);
+
+        assert!(vortex.update(bar(0, 11.0, 9.0, 10.0)).is_none());
+        // VM+ |12 - 9| = 3, VM- |10 - 11| = 1, TR 2
+        assert!(vortex.update(bar(1, 12.0, 10.0, 11.0)).is_none());
+        // VM+ |13 - 10| = 3, VM- |11 - 12| = 1, TR 2
+        let value = vortex.update(bar(2, 13.0, 11.0, 12.0)).unwrap();
+
+        let output = vortex.get_vortex().unwrap();
+        assert_eq!(output.vi_plus, 6.0 / 4.0);
+        assert_eq!(output.vi_minus, 2.0 / 4.0);
+        assert_eq!(value, 1.0);
+        assert_eq!(vortex.current(), Some(1.0));
+    }
+
+    #[test]
+    fn test_vortex_downtrend_is_negative() {
+        let mut vortex = Vortex::new(5);
+        for i in 0..20 {
+            let price = 200.0 - i as f64;
+            vortex.update(bar(i, price + 1.0, price - 1.0, price));
+        }
+
+        let output = vortex.get_vortex().unwrap();
+        assert!(output.vi_minus > output.vi_plus);
+        assert!(vortex.current().unwrap() < 0.0);
+    }
+
+    #[test]
+    fn test_vortex_flat_bars() {
+        let mut vortex = Vortex::new(2);
+        for i in 0..5 {
+            assert!(vortex.update(bar(i, 10.0, 10.0, 10.0)).is_none());
+        }
+        assert!(vortex.structured_output().is_none());
+    }
+
+    #[test]
+    fn test_vortex_reset() {
+        let mut vortex = Vortex::new(2);
+        for i in 0..5 {
+            vortex.update(bar(i, 11.0 + i as f64, 9.0 + i as f64, 10.0 + i as f64));
+        }
+        assert!(vortex.structured_output().is_some());
+
+        vortex.reset();
+        assert!(vortex.current().is_none());
+        assert!(vortex.update(bar(0, 11.0, 9.0, 10.0)).is_none());
+    }
+}
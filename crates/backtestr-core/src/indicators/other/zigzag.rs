@@ -0,0 +1,285 @@
+//! Zig Zag implementation.
+//!
+//! Zig Zag connects swing highs and lows that are at least a given
+//! percentage apart. The most recent extreme keeps moving while price
+//! extends it, so it is only *tentative*; it becomes a *confirmed* pivot
+//! once price reverses from it by the full threshold, and never changes
+//! after that.
+
+use super::swing::{SwingKind, SwingPoint};
+use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::output::IndicatorOutput;
+
+/// The latest confirmed pivot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZigZagOutput {
+    pub last_pivot_price: f64,
+    pub last_pivot_kind: SwingKind,
+    pub bars_since_pivot: usize,
+}
+
+/// Zig Zag over bar highs and lows.
+///
+/// Only confirmed pivots are reported through the output, so consumers
+/// such as divergence detection never act on an extreme that can still
+/// repaint. The extreme being tracked is available from `tentative_pivot`.
+/// Pivot indices count bars since the indicator started or was reset.
+#[derive(Debug, Clone)]
+pub struct ZigZag {
+    /// Reversal needed to confirm a pivot, as a fraction
+    threshold: f64,
+    /// Index of the next bar
+    index: usize,
+    /// Highest high and lowest low before the first pivot
+    initial_high: Option<SwingPoint>,
+    initial_low: Option<SwingPoint>,
+    confirmed: Option<SwingPoint>,
+    tentative: Option<SwingPoint>,
+}
+
+impl ZigZag {
+    /// `deviation_pct` is in percent, so 5.0 requires a 5% reversal.
+    pub fn new(deviation_pct: f64) -> Self {
+        Self {
+            threshold: deviation_pct.abs() / 100.0,
+            index: 0,
+            initial_high: None,
+            initial_low: None,
+            confirmed: None,
+            tentative: None,
+        }
+    }
+
+    pub fn get_zigzag(&self) -> Option<ZigZagOutput> {
+        let pivot = self.confirmed?;
+        Some(ZigZagOutput {
+            last_pivot_price: pivot.value,
+            last_pivot_kind: pivot.kind,
+            bars_since_pivot: self.index.saturating_sub(pivot.index + 1),
+        })
+    }
+
+    /// The latest pivot that can no longer move
+    pub fn confirmed_pivot(&self) -> Option<SwingPoint> {
+        self.confirmed
+    }
+
+    /// The extreme since the last confirmed pivot, which moves while price
+    /// keeps extending it
+    pub fn tentative_pivot(&self) -> Option<SwingPoint> {
+        self.tentative
+    }
+
+    fn point(&self, kind: SwingKind, input: &BarData) -> SwingPoint {
+        SwingPoint {
+            kind,
+            index: self.index,
+            timestamp: input.timestamp,
+            value: match kind {
+                SwingKind::High => input.high,
+                SwingKind::Low => input.low,
+            },
+        }
+    }
+
+    /// Before the first pivot, confirm whichever initial extreme price has
+    /// moved away from by the threshold
+    fn update_initial(&mut self, input: &BarData) {
+        let high = match self.initial_high {
+            Some(high) if high.value >= input.high => high,
+            _ => self.point(SwingKind::High, input),
+        };
+        let low = match self.initial_low {
+            Some(low) if low.value <= input.low => low,
+            _ => self.point(SwingKind::Low, input),
+        };
+        self.initial_high = Some(high);
+        self.initial_low = Some(low);
+
+        if input.low <= high.value * (1.0 - self.threshold) && high.index < self.index {
+            self.confirm(high, self.point(SwingKind::Low, input));
+        } else if input.high >= low.value * (1.0 + self.threshold) && low.index < self.index {
+            self.confirm(low, self.point(SwingKind::High, input));
+        }
+    }
+
+    fn confirm(&mut self, pivot: SwingPoint, next: SwingPoint) {
+        self.confirmed = Some(pivot);
+        self.tentative = Some(next);
+        self.initial_high = None;
+        self.initial_low = None;
+    }
+}
+
+impl Indicator for ZigZag {
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        "ZigZag"
+    }
+
+    fn warm_up_period(&self) -> usize {
+        // The first pivot needs a later bar to reverse from it; beyond that
+        // it depends on the data
+        2
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        match self.tentative {
+            None => self.update_initial(&input),
+            Some(extreme) => match extreme.kind {
+                SwingKind::High if input.high > extreme.value => {
+                    self.tentative = Some(self.point(SwingKind::High, &input));
+                }
+                SwingKind::High if input.low <= extreme.value * (1.0 - self.threshold) => {
+                    self.confirm(extreme, self.point(SwingKind::Low, &input));
+                }
+                SwingKind::Low if input.low < extreme.value => {
+                    self.tentative = Some(self.point(SwingKind::Low, &input));
+                }
+                SwingKind::Low if input.high >= extreme.value * (1.0 + self.threshold) => {
+                    self.confirm(extreme, self.point(SwingKind::High, &input));
+                }
+                _ => {}
+            },
+        }
+
+        self.index += 1;
+        self.current()
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.confirmed.map(|pivot| pivot.value)
+    }
+
+    fn reset(&mut self) {
+        self.index = 0;
+        self.initial_high = None;
+        self.initial_low = None;
+        self.confirmed = None;
+        self.tentative = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn structured_output(&self) -> Option<IndicatorOutput> {
+        self.get_zigzag().map(IndicatorOutput::ZigZag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(i: i64, price: f64) -> BarData {
+        BarData {
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: 1000.0,
+            timestamp: i,
+        }
+    }
+
+    fn feed(zigzag: &mut ZigZag, prices: &[f64]) {
+        for (i, &price) in prices.iter().enumerate() {
+            zigzag.update(bar(i as i64, price));
+        }
+    }
+
+    #[test]
+    fn test_pivot_confirmed_only_after_reversal() {
+        let mut zigzag = ZigZag::new(10.0);
+
+        // Rising from 100 confirms the starting low once 110 is reached
+        feed(&mut zigzag, &[100.0, 105.0, 112.0, 120.0]);
+        let output = zigzag.get_zigzag().unwrap();
+        assert_eq!(output.last_pivot_kind, SwingKind::Low);
+        assert_eq!(output.last_pivot_price, 100.0);
+        assert_eq!(output.bars_since_pivot, 3);
+
+        // 120 is only the tentative high
+        let tentative = zigzag.tentative_pivot().unwrap();
+        assert_eq!((tentative.kind, tentative.value), (SwingKind::High, 120.0));
+
+        // A dip short of 10% leaves it tentative
+        zigzag.update(bar(4, 110.0));
+        assert_eq!(zigzag.get_zigzag().unwrap().last_pivot_kind, SwingKind::Low);
+
+        // 108 is 10% below 120: the high is confirmed
+        zigzag.update(bar(5, 108.0));
+        let output = zigzag.get_zigzag().unwrap();
+        assert_eq!(output.last_pivot_kind, SwingKind::High);
+        assert_eq!(output.last_pivot_price, 120.0);
+        assert_eq!(output.bars_since_pivot, 2);
+        assert_eq!(zigzag.current(), Some(120.0));
+    }
+
+    #[test]
+    fn test_tentative_pivot_repaints() {
+        let mut zigzag = ZigZag::new(5.0);
+        feed(&mut zigzag, &[100.0, 106.0, 108.0]);
+        assert_eq!(zigzag.tentative_pivot().unwrap().value, 108.0);
+
+        // A higher high moves the tentative pivot but not the confirmed one
+        zigzag.update(bar(3, 115.0));
+        assert_eq!(zigzag.tentative_pivot().unwrap().value, 115.0);
+        assert_eq!(zigzag.tentative_pivot().unwrap().index, 3);
+        assert_eq!(zigzag.confirmed_pivot().unwrap().value, 100.0);
+    }
+
+    #[test]
+    fn test_initial_decline_confirms_high() {
+        let mut zigzag = ZigZag::new(5.0);
+        feed(&mut zigzag, &[100.0, 102.0, 99.0, 96.0]);
+
+        let pivot = zigzag.confirmed_pivot().unwrap();
+        assert_eq!(
+            (pivot.kind, pivot.value, pivot.index),
+            (SwingKind::High, 102.0, 1)
+        );
+        assert_eq!(zigzag.tentative_pivot().unwrap().kind, SwingKind::Low);
+    }
+
+    #[test]
+    fn test_no_pivot_within_threshold() {
+        let mut zigzag = ZigZag::new(5.0);
+        feed(&mut zigzag, &[100.0, 101.0, 99.0, 102.0, 98.0]);
+        assert!(zigzag.get_zigzag().is_none());
+        assert!(zigzag.tentative_pivot().is_none());
+    }
+
+    #[test]
+    fn test_confirmed_pivots_alternate() {
+        let mut zigzag = ZigZag::new(3.0);
+        let mut pivots: Vec<SwingPoint> = Vec::new();
+        for i in 0..300 {
+            let price = 100.0 + (i as f64 * 0.1).sin() * 10.0;
+            zigzag.update(bar(i, price));
+            if let Some(pivot) = zigzag.confirmed_pivot() {
+                if pivots.last() != Some(&pivot) {
+                    pivots.push(pivot);
+                }
+            }
+        }
+
+        assert!(pivots.len() >= 4);
+        assert!(pivots.windows(2).all(|w| w[0].kind != w[1].kind));
+        assert!(pivots.windows(2).all(|w| w[0].index < w[1].index));
+    }
+
+    #[test]
+    fn test_zigzag_reset() {
+        let mut zigzag = ZigZag::new(5.0);
+        feed(&mut zigzag, &[100.0, 110.0, 100.0]);
+        assert!(zigzag.structured_output().is_some());
+
+        zigzag.reset();
+        assert!(zigzag.current().is_none());
+        assert!(zigzag.tentative_pivot().is_none());
+    }
+}
@@ -0,0 +1,72 @@
+//! Structured outputs for indicators that produce more than one value.
+//!
+//! The pipeline caches the scalar `current()` value of every indicator, which
+//! loses the extra series of multi-value indicators (MACD signal, Bollinger
+//! bands, ...). Indicators that expose `Indicator::structured_output` have
+//! their full output cached alongside the scalar so it can be read back with
+//! `IndicatorPipeline::get_output`.
+
+use super::momentum::{MACDOutput, StochasticOutput};
+use super::other::{
+    AroonOutput, ElderRayOutput, LinRegOutput, PivotOutput, VortexOutput, ZigZagOutput,
+};
+use super::volatility::{
+    BollingerOutput, DonchianOutput, KeltnerOutput, SqueezeOutput, SuperTrendOutput,
+};
+use super::volume::AnchoredVWAPOutput;
+
+/// Full output of a multi-value indicator
+#[derive(Debug, Clone, PartialEq)]
+pub enum IndicatorOutput {
+    Macd(MACDOutput),
+    Bollinger(BollingerOutput),
+    Stochastic(StochasticOutput),
+    Keltner(KeltnerOutput),
+    Donchian(DonchianOutput),
+    SuperTrend(SuperTrendOutput),
+    AnchoredVwap(AnchoredVWAPOutput),
+    Squeeze(SqueezeOutput),
+    Aroon(AroonOutput),
+    Vortex(VortexOutput),
+    LinReg(LinRegOutput),
+    ZigZag(ZigZagOutput),
+    Pivot(PivotOutput),
+    ElderRay(ElderRayOutput),
+}
+
+/// Output types that can be extracted from an `IndicatorOutput`
+pub trait TypedOutput: Sized {
+    fn from_output(output: IndicatorOutput) -> Option<Self>;
+}
+
+macro_rules! typed_output {
+    ($($variant:ident => $output:ty),* $(,)?) => {
+        $(
+            impl TypedOutput for $output {
+                fn from_output(output: IndicatorOutput) -> Option<Self> {
+                    match output {
+                        IndicatorOutput::$variant(inner) => Some(inner),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+typed_output! {
+    Macd => MACDOutput,
+    Bollinger => BollingerOutput,
+    Stochastic => StochasticOutput,
+    Keltner => KeltnerOutput,
+    Donchian => DonchianOutput,
+    SuperTrend => SuperTrendOutput,
+    AnchoredVwap => AnchoredVWAPOutput,
+    Squeeze => SqueezeOutput,
+    Aroon => AroonOutput,
+    Vortex => VortexOutput,
+    LinReg => LinRegOutput,
+    ZigZag => ZigZagOutput,
+    Pivot => PivotOutput,
+    ElderRay => ElderRayOutput,
+}
@@ -3,23 +3,97 @@
 //! This module provides a high-performance pipeline that can process multiple
 //! indicators in parallel when beneficial, with automatic caching of results.
 
-use anyhow::Result;
-use dashmap::DashMap;
+use anyhow::{anyhow, bail, Context, Result};
+use dashmap::{DashMap, DashSet};
 use rayon::prelude::*;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 use std::time::Instant;
 use tracing::debug;
 
 use backtestr_data::Timeframe;
 
 use super::cache::IndicatorCache;
+use super::derived::{DerivedGraph, DerivedIndicator};
 use super::indicator_trait::{BarData, Indicator, IndicatorDefaults, IndicatorValue};
+use super::momentum::{MACDOutput, StochasticOutput};
+use super::output::{IndicatorOutput, TypedOutput};
+use super::volatility::BollingerOutput;
+use crate::persistence::serialization::IndicatorSnapshot;
+
+/// Values larger than this in absolute terms are treated as runaway by default
+pub const DEFAULT_MAGNITUDE_BOUND: f64 = 1e12;
+
+type BoxedIndicator = Box<dyn Indicator<Input = BarData, Output = f64>>;
+
+/// A registered indicator with its own instance per timeframe, so bars of
+/// different timeframes never share a rolling window
+#[derive(Debug)]
+struct TimeframeInstances {
+    /// The instance passed to `register_indicator`, until the first
+    /// timeframe claims it
+    registered: Option<BoxedIndicator>,
+    /// Untouched copy that instances for later timeframes are built from
+    template: BoxedIndicator,
+    instances: HashMap<Timeframe, BoxedIndicator>,
+}
+
+impl TimeframeInstances {
+    fn new(indicator: BoxedIndicator) -> Self {
+        Self {
+            template: indicator.clone_fresh(),
+            registered: Some(indicator),
+            instances: HashMap::new(),
+        }
+    }
+
+    /// The instance fed by `timeframe`, created on first use
+    fn for_timeframe(&mut self, timeframe: Timeframe) -> &mut BoxedIndicator {
+        let Self {
+            registered,
+            template,
+            instances,
+        } = self;
+        instances
+            .entry(timeframe)
+            .or_insert_with(|| registered.take().unwrap_or_else(|| template.clone_fresh()))
+    }
+
+    /// A copy of the instance `for_timeframe` would return, without
+    /// creating it
+    fn copy_for_timeframe(&self, timeframe: Timeframe) -> BoxedIndicator {
+        match (self.instances.get(&timeframe), &self.registered) {
+            (Some(instance), _) | (None, Some(instance)) => instance.clone_box(),
+            (None, None) => self.template.clone_fresh(),
+        }
+    }
+
+    /// Every live instance with its timeframe; `None` marks the registered
+    /// instance before any bar reached it
+    fn iter(&self) -> impl Iterator<Item = (Option<Timeframe>, &BoxedIndicator)> {
+        self.registered.iter().map(|i| (None, i)).chain(
+            self.instances
+                .iter()
+                .map(|(timeframe, i)| (Some(*timeframe), i)),
+        )
+    }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = (Option<Timeframe>, &mut BoxedIndicator)> {
+        self.registered.iter_mut().map(|i| (None, i)).chain(
+            self.instances
+                .iter_mut()
+                .map(|(timeframe, i)| (Some(*timeframe), i)),
+        )
+    }
+}
 
 /// High-performance pipeline for managing multiple technical indicators.
 ///
 /// The pipeline automatically chooses between sequential and parallel processing
 /// based on the number of indicators, caches results per timeframe, and provides
-/// thread-safe access to all indicators.
+/// thread-safe access to all indicators. Each registered indicator keeps a
+/// separate instance per timeframe, built with `Indicator::clone_fresh` the
+/// first time that timeframe is updated.
 ///
 /// # Performance Characteristics
 ///
@@ -48,11 +122,15 @@ use super::indicator_trait::{BarData, Indicator, IndicatorDefaults, IndicatorVal
 /// let result = pipeline.update_all(&bar, Timeframe::M1).unwrap();
 /// ```
 pub struct IndicatorPipeline {
-    indicators: Arc<DashMap<String, Box<dyn Indicator<Input = BarData, Output = f64>>>>,
+    indicators: Arc<DashMap<String, TimeframeInstances>>,
     cache: IndicatorCache,
     #[allow(dead_code)]
     defaults: IndicatorDefaults,
     parallel_threshold: usize,
+    magnitude_bound: f64,
+    last_timeframe: Arc<RwLock<Option<Timeframe>>>,
+    disabled: Arc<DashSet<String>>,
+    derived: Arc<RwLock<DerivedGraph>>,
 }
 
 impl IndicatorPipeline {
@@ -62,6 +140,10 @@ impl IndicatorPipeline {
             cache: IndicatorCache::new(cache_size),
             defaults: IndicatorDefaults::default(),
             parallel_threshold: 5, // Use parallel processing if more than 5 indicators
+            magnitude_bound: DEFAULT_MAGNITUDE_BOUND,
+            last_timeframe: Arc::new(RwLock::new(None)),
+            disabled: Arc::new(DashSet::new()),
+            derived: Arc::new(RwLock::new(DerivedGraph::default())),
         }
     }
 
@@ -71,26 +153,111 @@ impl IndicatorPipeline {
             cache: IndicatorCache::new(cache_size),
             defaults,
             parallel_threshold: 5,
+            magnitude_bound: DEFAULT_MAGNITUDE_BOUND,
+            last_timeframe: Arc::new(RwLock::new(None)),
+            disabled: Arc::new(DashSet::new()),
+            derived: Arc::new(RwLock::new(DerivedGraph::default())),
         }
     }
 
-    pub fn register_indicator(
-        &self,
-        name: String,
-        indicator: Box<dyn Indicator<Input = BarData, Output = f64>>,
-    ) {
+    pub fn register_indicator(&self, name: String, indicator: BoxedIndicator) {
         debug!("Registering indicator: {}", name);
-        self.indicators.insert(name, indicator);
+        self.indicators
+            .insert(name, TimeframeInstances::new(indicator));
+    }
+
+    /// Register several indicators at once, e.g. one per parameter in a
+    /// sweep. Later entries replace earlier ones with the same name.
+    pub fn register_many(&self, specs: Vec<(String, BoxedIndicator)>) {
+        for (name, indicator) in specs {
+            self.register_indicator(name, indicator);
+        }
+    }
+
+    /// A pipeline with the same registrations and settings but no state.
+    ///
+    /// Every indicator is rebuilt from its registration template, derived
+    /// indicators and disabled flags carry over, and the cache starts empty
+    /// with the same history limit. The copy shares nothing with `self`, so
+    /// each sweep worker can own one.
+    pub fn clone_empty(&self) -> IndicatorPipeline {
+        let indicators = DashMap::new();
+        for entry in self.indicators.iter() {
+            indicators.insert(
+                entry.key().clone(),
+                TimeframeInstances::new(entry.value().template.clone_fresh()),
+            );
+        }
+        let derived = self
+            .derived
+            .read()
+            .map(|graph| graph.clone())
+            .unwrap_or_default();
+        let disabled = DashSet::new();
+        for name in self.disabled.iter() {
+            disabled.insert(name.key().clone());
+        }
+
+        Self {
+            indicators: Arc::new(indicators),
+            cache: IndicatorCache::new(self.cache.get_stats().max_history),
+            defaults: self.defaults.clone(),
+            parallel_threshold: self.parallel_threshold,
+            magnitude_bound: self.magnitude_bound,
+            last_timeframe: Arc::new(RwLock::new(None)),
+            disabled: Arc::new(disabled),
+            derived: Arc::new(RwLock::new(derived)),
+        }
+    }
+
+    /// Register an indicator computed from other registered indicators.
+    ///
+    /// Its sources may be regular or derived indicators and must already be
+    /// registered. `update_all` evaluates derived indicators after every
+    /// regular one, each after the derived indicators it reads, and caches
+    /// the result under `name`. Fails if `name` is taken by a regular
+    /// indicator or if the registration would make the indicator depend on
+    /// itself, directly or through other derived indicators.
+    pub fn register_derived(&self, name: String, derived: DerivedIndicator) -> Result<()> {
+        if self.indicators.contains_key(&name) {
+            bail!("Indicator {} is already registered", name);
+        }
+
+        debug!(
+            "Registering derived indicator: {} from {:?}",
+            name,
+            derived.sources()
+        );
+        let mut graph = self
+            .derived
+            .write()
+            .map_err(|_| anyhow!("Derived indicator registry poisoned"))?;
+        graph.insert(name.clone(), derived, |source| {
+            self.indicators.contains_key(source)
+        })?;
+        // Values cached under a replaced definition no longer apply
+        self.cache.clear_indicator(&name);
+        Ok(())
     }
 
     pub fn update_all(&self, bar: &BarData, timeframe: Timeframe) -> Result<UpdateResult> {
         let start = Instant::now();
-        let indicator_count = self.indicators.len();
+        let skipped_count = self
+            .indicators
+            .iter()
+            .filter(|entry| self.disabled.contains(entry.key()))
+            .count();
+        let indicator_count = self.indicators.len() - skipped_count;
+
+        if let Ok(mut last_timeframe) = self.last_timeframe.write() {
+            *last_timeframe = Some(timeframe);
+        }
 
         if indicator_count == 0 {
             return Ok(UpdateResult {
                 updated_count: 0,
                 failed_count: 0,
+                skipped_count,
                 duration_micros: start.elapsed().as_micros() as u64,
             });
         }
@@ -101,11 +268,15 @@ impl IndicatorPipeline {
             self.update_sequential(bar, timeframe)
         };
 
-        let (updated_count, failed_count) = results;
+        let (mut updated_count, mut failed_count) = results;
+        let (derived_updated, derived_failed) = self.update_derived(bar, timeframe);
+        updated_count += derived_updated;
+        failed_count += derived_failed;
 
         Ok(UpdateResult {
             updated_count,
             failed_count,
+            skipped_count,
             duration_micros: start.elapsed().as_micros() as u64,
         })
     }
@@ -115,7 +286,11 @@ impl IndicatorPipeline {
         let mut failed = 0;
 
         for mut entry in self.indicators.iter_mut() {
-            let (name, indicator) = entry.pair_mut();
+            let (name, instances) = entry.pair_mut();
+            if self.disabled.contains(name) {
+                continue;
+            }
+            let indicator = instances.for_timeframe(timeframe);
 
             if let Some(value) = indicator.update(*bar) {
                 let indicator_value = IndicatorValue {
@@ -123,6 +298,9 @@ impl IndicatorPipeline {
                     timestamp: bar.timestamp,
                 };
                 self.cache.insert(name.clone(), timeframe, indicator_value);
+                if let Some(output) = indicator.structured_output() {
+                    self.cache.insert_output(name.clone(), timeframe, output);
+                }
                 updated += 1;
             } else {
                 failed += 1;
@@ -133,26 +311,33 @@ impl IndicatorPipeline {
     }
 
     fn update_parallel(&self, bar: &BarData, timeframe: Timeframe) -> (usize, usize) {
-        let results: Vec<(String, Option<f64>)> = self
+        type ParallelResult = (String, Option<f64>, Option<IndicatorOutput>);
+        let results: Vec<ParallelResult> = self
             .indicators
             .iter_mut()
+            .filter(|entry| !self.disabled.contains(entry.key()))
             .par_bridge()
             .map(|mut entry| {
-                let (name, indicator) = entry.pair_mut();
+                let (name, instances) = entry.pair_mut();
+                let indicator = instances.for_timeframe(timeframe);
                 let result = indicator.update(*bar);
-                (name.clone(), result)
+                let output = result.and(indicator.structured_output());
+                (name.clone(), result, output)
             })
             .collect();
 
         let mut updated = 0;
         let mut failed = 0;
 
-        for (name, result) in results {
+        for (name, result, output) in results {
             if let Some(value) = result {
                 let indicator_value = IndicatorValue {
                     value,
                     timestamp: bar.timestamp,
                 };
+                if let Some(output) = output {
+                    self.cache.insert_output(name.clone(), timeframe, output);
+                }
                 self.cache.insert(name, timeframe, indicator_value);
                 updated += 1;
             } else {
@@ -163,6 +348,110 @@ impl IndicatorPipeline {
         (updated, failed)
     }
 
+    /// Evaluate derived indicators from the values cached for this bar.
+    ///
+    /// A source counts only if it produced a value for `bar`, so a derived
+    /// indicator never mixes a fresh value with a stale one.
+    fn update_derived(&self, bar: &BarData, timeframe: Timeframe) -> (usize, usize) {
+        let Ok(graph) = self.derived.read() else {
+            return (0, 0);
+        };
+        if graph.is_empty() {
+            return (0, 0);
+        }
+
+        let mut updated = 0;
+        let mut failed = 0;
+        for (name, derived) in graph.iter() {
+            let value = derived.compute(|source| {
+                self.cache
+                    .get(source, timeframe)
+                    .filter(|v| v.timestamp == bar.timestamp)
+                    .map(|v| v.value)
+            });
+
+            if let Some(value) = value {
+                self.cache.insert(
+                    name.clone(),
+                    timeframe,
+                    IndicatorValue {
+                        value,
+                        timestamp: bar.timestamp,
+                    },
+                );
+                updated += 1;
+            } else {
+                failed += 1;
+            }
+        }
+
+        (updated, failed)
+    }
+
+    /// Mute or unmute a registered indicator without losing its state.
+    ///
+    /// `update_all` skips disabled indicators, so their warm-up state is
+    /// exactly as it was when disabled and they resume from there once
+    /// re-enabled. While disabled, `get_value` and the other accessors keep
+    /// returning the last value computed before muting; use `is_stale` to
+    /// tell such values apart. Unknown names are ignored.
+    pub fn set_enabled(&self, name: &str, enabled: bool) {
+        if enabled {
+            self.disabled.remove(name);
+        } else if self.indicators.contains_key(name) {
+            debug!("Disabling indicator: {}", name);
+            self.disabled.insert(name.to_string());
+        }
+    }
+
+    /// Whether `update_all` feeds this indicator. False for unknown names.
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.indicators.contains_key(name) && !self.disabled.contains(name)
+    }
+
+    /// True when the indicator is disabled, so its cached values are from
+    /// before it was muted rather than from the latest bar
+    pub fn is_stale(&self, name: &str) -> bool {
+        self.disabled.contains(name)
+    }
+
+    /// Feed `history` to one registered indicator in a single batch and
+    /// cache the resulting value, so `get_value` reflects it immediately.
+    ///
+    /// Much cheaper than calling `update_all` once per bar when adding an
+    /// indicator mid-run: other indicators are untouched and only the final
+    /// value is cached. Returns the value after the last bar.
+    pub fn warm_up(
+        &self,
+        name: &str,
+        timeframe: Timeframe,
+        history: &[BarData],
+    ) -> Result<Option<f64>> {
+        let mut instances = self
+            .indicators
+            .get_mut(name)
+            .with_context(|| format!("Indicator not registered: {}", name))?;
+        let indicator = instances.for_timeframe(timeframe);
+
+        let value = indicator.warm_up_batch(history);
+        if let (Some(value), Some(last)) = (value, history.last()) {
+            self.cache.insert(
+                name.to_string(),
+                timeframe,
+                IndicatorValue {
+                    value,
+                    timestamp: last.timestamp,
+                },
+            );
+            if let Some(output) = indicator.structured_output() {
+                self.cache
+                    .insert_output(name.to_string(), timeframe, output);
+            }
+        }
+
+        Ok(value)
+    }
+
     pub fn get_value(&self, indicator_name: &str, timeframe: Timeframe) -> Option<f64> {
         self.cache.get(indicator_name, timeframe).map(|v| v.value)
     }
@@ -175,6 +464,40 @@ impl IndicatorPipeline {
         self.cache.get(indicator_name, timeframe)
     }
 
+    /// Latest full output of a multi-value indicator.
+    ///
+    /// Returns `None` if the indicator is unknown, has not warmed up, or
+    /// produces a different output type than `T`.
+    pub fn get_output<T: TypedOutput>(
+        &self,
+        indicator_name: &str,
+        timeframe: Timeframe,
+    ) -> Option<T> {
+        self.cache
+            .get_output(indicator_name, timeframe)
+            .and_then(T::from_output)
+    }
+
+    pub fn get_macd(&self, indicator_name: &str, timeframe: Timeframe) -> Option<MACDOutput> {
+        self.get_output(indicator_name, timeframe)
+    }
+
+    pub fn get_bollinger(
+        &self,
+        indicator_name: &str,
+        timeframe: Timeframe,
+    ) -> Option<BollingerOutput> {
+        self.get_output(indicator_name, timeframe)
+    }
+
+    pub fn get_stochastic(
+        &self,
+        indicator_name: &str,
+        timeframe: Timeframe,
+    ) -> Option<StochasticOutput> {
+        self.get_output(indicator_name, timeframe)
+    }
+
     pub fn get_history(
         &self,
         indicator_name: &str,
@@ -185,29 +508,60 @@ impl IndicatorPipeline {
     }
 
     pub fn reset_indicator(&self, indicator_name: &str) {
-        if let Some(mut indicator) = self.indicators.get_mut(indicator_name) {
-            indicator.reset();
+        if let Some(mut instances) = self.indicators.get_mut(indicator_name) {
+            for (_, indicator) in instances.iter_mut() {
+                indicator.reset();
+            }
             self.cache.clear_indicator(indicator_name);
         }
     }
 
     pub fn reset_all(&self) {
         for mut entry in self.indicators.iter_mut() {
-            entry.value_mut().reset();
+            for (_, indicator) in entry.value_mut().iter_mut() {
+                indicator.reset();
+            }
         }
         self.cache.clear();
     }
 
+    /// Forward a session open to every indicator, dropping cached values of
+    /// indicators that reset as a result
+    pub fn notify_session_boundary(&self) {
+        for mut entry in self.indicators.iter_mut() {
+            let (name, instances) = entry.pair_mut();
+            for (timeframe, indicator) in instances.iter_mut() {
+                indicator.on_session_boundary();
+                if let (Some(timeframe), None) = (timeframe, indicator.current()) {
+                    self.cache.clear_entry(name, timeframe);
+                }
+            }
+        }
+    }
+
+    /// Remove a regular or derived indicator. Derived indicators reading
+    /// it stay registered but produce no values until it is re-added.
     pub fn remove_indicator(&self, indicator_name: &str) -> bool {
         self.cache.clear_indicator(indicator_name);
-        self.indicators.remove(indicator_name).is_some()
+        self.disabled.remove(indicator_name);
+        let removed_derived = self
+            .derived
+            .write()
+            .is_ok_and(|mut graph| graph.remove(indicator_name));
+        self.indicators.remove(indicator_name).is_some() || removed_derived
     }
 
+    /// Names of regular indicators followed by derived ones in update order
     pub fn get_indicator_names(&self) -> Vec<String> {
-        self.indicators
+        let mut names: Vec<String> = self
+            .indicators
             .iter()
             .map(|entry| entry.key().clone())
-            .collect()
+            .collect();
+        if let Ok(graph) = self.derived.read() {
+            names.extend(graph.iter().map(|(name, _)| name.clone()));
+        }
+        names
     }
 
     pub fn get_stats(&self) -> PipelineStats {
@@ -220,12 +574,193 @@ impl IndicatorPipeline {
     pub fn set_parallel_threshold(&mut self, threshold: usize) {
         self.parallel_threshold = threshold;
     }
+
+    /// Capture every registered indicator's internal state for a checkpoint.
+    ///
+    /// There is one snapshot per timeframe instance, keyed by
+    /// `"{name}@{timeframe}"`. An indicator no bar has reached yet is keyed
+    /// by the timeframe most recently passed to `update_all`. The result is
+    /// suitable for `CheckpointData::indicator_states`.
+    pub fn snapshot_all(&self) -> HashMap<String, IndicatorSnapshot> {
+        let last_timeframe = self
+            .last_timeframe
+            .read()
+            .ok()
+            .and_then(|tf| *tf)
+            .unwrap_or(Timeframe::M1);
+
+        self.indicators
+            .iter()
+            .flat_map(|entry| {
+                let name = entry.key().clone();
+                entry
+                    .value()
+                    .iter()
+                    .map(|(timeframe, indicator)| {
+                        self.snapshot_one(&name, timeframe.unwrap_or(last_timeframe), indicator)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn snapshot_one(
+        &self,
+        name: &str,
+        timeframe: Timeframe,
+        indicator: &BoxedIndicator,
+    ) -> (String, IndicatorSnapshot) {
+        let history = self.cache.get_history(name, timeframe, usize::MAX);
+
+        let mut parameters = HashMap::new();
+        parameters.insert(
+            "warm_up_period".to_string(),
+            indicator.warm_up_period() as f64,
+        );
+
+        let snapshot = IndicatorSnapshot {
+            name: name.to_string(),
+            timeframe,
+            values: history.iter().map(|v| v.value).collect(),
+            parameters,
+            last_update: history.last().map_or(0, |v| v.timestamp),
+            state: indicator.serialize_state(),
+        };
+
+        (format!("{}@{}", name, timeframe), snapshot)
+    }
+
+    /// Restore indicator state captured by `snapshot_all`.
+    ///
+    /// Snapshots are matched to registered indicators by name and restored
+    /// into the instance for their timeframe; snapshots for unregistered
+    /// indicators are ignored. Every snapshot is restored into a copy
+    /// first, so if any saved state is incompatible (e.g. a different
+    /// period) this fails without modifying any indicator.
+    pub fn restore_all(&self, snapshots: &HashMap<String, IndicatorSnapshot>) -> Result<()> {
+        let mut restored = Vec::new();
+        for snapshot in snapshots.values() {
+            let Some(instances) = self.indicators.get(&snapshot.name) else {
+                debug!(
+                    "Skipping snapshot for unregistered indicator: {}",
+                    snapshot.name
+                );
+                continue;
+            };
+
+            if snapshot.state.is_empty() {
+                continue;
+            }
+
+            let mut indicator = instances.copy_for_timeframe(snapshot.timeframe);
+            indicator
+                .restore_state(&snapshot.state)
+                .with_context(|| format!("Failed to restore indicator {}", snapshot.name))?;
+            restored.push((snapshot, indicator));
+        }
+
+        for (snapshot, indicator) in restored {
+            // Seed the cache so values are readable before the next update
+            self.cache.clear_entry(&snapshot.name, snapshot.timeframe);
+            if let Some(value) = indicator.current() {
+                self.cache.insert(
+                    snapshot.name.clone(),
+                    snapshot.timeframe,
+                    IndicatorValue {
+                        value,
+                        timestamp: snapshot.last_update,
+                    },
+                );
+            }
+
+            if let Some(mut instances) = self.indicators.get_mut(&snapshot.name) {
+                *instances.for_timeframe(snapshot.timeframe) = indicator;
+            }
+
+            if let Ok(mut last_timeframe) = self.last_timeframe.write() {
+                *last_timeframe = Some(snapshot.timeframe);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Set the absolute value above which `health_check` flags an indicator
+    pub fn set_magnitude_bound(&mut self, bound: f64) {
+        self.magnitude_bound = bound;
+    }
+
+    /// Report indicator instances whose latest value is NaN, infinite, or
+    /// beyond the magnitude bound, and volume indicators fed only bars
+    /// without volume. Healthy and not-yet-warmed-up instances are omitted.
+    pub fn health_check(&self) -> Vec<IndicatorHealth> {
+        let mut unhealthy: Vec<IndicatorHealth> = Vec::new();
+        for entry in self.indicators.iter() {
+            for (timeframe, indicator) in entry.value().iter() {
+                if indicator.volume_unavailable() {
+                    unhealthy.push(IndicatorHealth {
+                        name: entry.key().clone(),
+                        timeframe,
+                        value: indicator.current(),
+                        status: HealthStatus::VolumeUnavailable,
+                    });
+                    continue;
+                }
+
+                let Some(value) = indicator.current() else {
+                    continue;
+                };
+                let status = if !value.is_finite() {
+                    HealthStatus::NonFinite
+                } else if value.abs() > self.magnitude_bound {
+                    HealthStatus::OutOfBounds
+                } else {
+                    continue;
+                };
+
+                unhealthy.push(IndicatorHealth {
+                    name: entry.key().clone(),
+                    timeframe,
+                    value: Some(value),
+                    status,
+                });
+            }
+        }
+
+        unhealthy.sort_by(|a, b| a.name.cmp(&b.name));
+        unhealthy
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthStatus {
+    /// The latest value is NaN or infinite
+    NonFinite,
+    /// The latest value exceeds the pipeline's magnitude bound
+    OutOfBounds,
+    /// A volume indicator has only seen bars without volume, so it has no
+    /// value; the feed likely carries no volume at all
+    VolumeUnavailable,
+}
+
+/// A registered indicator whose latest value looks broken
+#[derive(Debug, Clone)]
+pub struct IndicatorHealth {
+    pub name: String,
+    /// Timeframe of the offending instance; `None` if no bar has reached
+    /// the indicator yet
+    pub timeframe: Option<Timeframe>,
+    /// Latest value; `None` for `VolumeUnavailable`
+    pub value: Option<f64>,
+    pub status: HealthStatus,
 }
 
 #[derive(Debug, Clone)]
 pub struct UpdateResult {
     pub updated_count: usize,
     pub failed_count: usize,
+    /// Registered indicators skipped because they are disabled
+    pub skipped_count: usize,
     pub duration_micros: u64,
 }
 
@@ -239,7 +774,7 @@ pub struct PipelineStats {
 mod tests {
     use super::*;
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     struct MockIndicator {
         name: String,
         value: f64,
@@ -269,6 +804,10 @@ mod tests {
         fn reset(&mut self) {
             self.value = 0.0;
         }
+
+        fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+            Box::new(self.clone())
+        }
     }
 
     #[test]
@@ -297,4 +836,485 @@ mod tests {
         let value = pipeline.get_value("TEST", Timeframe::M1);
         assert_eq!(value, Some(1.0));
     }
+
+    #[test]
+    fn test_warm_up_matches_incremental_feed() {
+        use crate::indicators::volatility::BollingerOutput;
+        use crate::indicators::{BollingerBands, EMA, RSI, SMA};
+
+        let bars: Vec<BarData> = (0..1_000)
+            .map(|i| {
+                let close = 1.1 + (i as f64 * 0.05).sin() * 0.01;
+                BarData {
+                    open: close,
+                    high: close + 0.001,
+                    low: close - 0.001,
+                    close,
+                    volume: 1000.0,
+                    timestamp: i,
+                }
+            })
+            .collect();
+
+        let register = |pipeline: &IndicatorPipeline| {
+            pipeline.register_indicator("SMA".to_string(), Box::new(SMA::new(20)));
+            pipeline.register_indicator("EMA".to_string(), Box::new(EMA::new(20)));
+            pipeline.register_indicator("RSI".to_string(), Box::new(RSI::new(14)));
+            pipeline.register_indicator("BB".to_string(), Box::new(BollingerBands::new(20, 2.0)));
+        };
+
+        let incremental = IndicatorPipeline::new(100);
+        register(&incremental);
+        for bar in &bars {
+            incremental.update_all(bar, Timeframe::H1).unwrap();
+        }
+
+        let batch = IndicatorPipeline::new(100);
+        register(&batch);
+        for name in ["SMA", "EMA", "RSI", "BB"] {
+            batch.warm_up(name, Timeframe::H1, &bars).unwrap();
+            assert_eq!(
+                batch.get_value(name, Timeframe::H1).map(f64::to_bits),
+                incremental.get_value(name, Timeframe::H1).map(f64::to_bits),
+                "{} differs",
+                name
+            );
+        }
+        assert_eq!(
+            batch.get_output::<BollingerOutput>("BB", Timeframe::H1),
+            incremental.get_output::<BollingerOutput>("BB", Timeframe::H1)
+        );
+        assert!(batch.warm_up("MISSING", Timeframe::H1, &bars).is_err());
+    }
+
+    #[derive(Debug, Clone)]
+    struct FixedIndicator {
+        value: Option<f64>,
+    }
+
+    impl Indicator for FixedIndicator {
+        type Input = BarData;
+        type Output = f64;
+
+        fn name(&self) -> &str {
+            "FIXED"
+        }
+
+        fn warm_up_period(&self) -> usize {
+            1
+        }
+
+        fn update(&mut self, _input: BarData) -> Option<f64> {
+            self.value
+        }
+
+        fn current(&self) -> Option<f64> {
+            self.value
+        }
+
+        fn reset(&mut self) {}
+
+        fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[test]
+    fn test_health_check_flags_bad_values() {
+        let mut pipeline = IndicatorPipeline::new(100);
+        pipeline.set_magnitude_bound(1e6);
+
+        let register = |name: &str, value: Option<f64>| {
+            pipeline.register_indicator(name.to_string(), Box::new(FixedIndicator { value }));
+        };
+        register("GOOD", Some(1.2345));
+        register("WARMING", None);
+        register("NAN", Some(f64::NAN));
+        register("INF", Some(f64::NEG_INFINITY));
+        register("RUNAWAY", Some(5e6));
+
+        let report = pipeline.health_check();
+        let flagged: Vec<(&str, HealthStatus)> =
+            report.iter().map(|h| (h.name.as_str(), h.status)).collect();
+
+        assert_eq!(
+            flagged,
+            vec![
+                ("INF", HealthStatus::NonFinite),
+                ("NAN", HealthStatus::NonFinite),
+                ("RUNAWAY", HealthStatus::OutOfBounds),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_health_check_clean_pipeline() {
+        let pipeline = IndicatorPipeline::new(100);
+        pipeline.register_indicator(
+            "TEST".to_string(),
+            Box::new(MockIndicator {
+                name: "TEST".to_string(),
+                value: 0.0,
+            }),
+        );
+        assert!(pipeline.health_check().is_empty());
+    }
+
+    #[test]
+    fn test_health_check_flags_missing_volume() {
+        use crate::indicators::{OBV, SMA, VWAP};
+
+        let pipeline = IndicatorPipeline::new(100);
+        pipeline.register_indicator("OBV".to_string(), Box::new(OBV::new()));
+        pipeline.register_indicator("VWAP".to_string(), Box::new(VWAP::new(false)));
+        pipeline.register_indicator("SMA".to_string(), Box::new(SMA::new(2)));
+
+        for i in 0..3 {
+            let bar = BarData {
+                volume: 0.0,
+                ..trending_bar(i)
+            };
+            pipeline.update_all(&bar, Timeframe::M1).unwrap();
+        }
+
+        assert_eq!(pipeline.get_value("OBV", Timeframe::M1), None);
+        let report = pipeline.health_check();
+        let flagged: Vec<(&str, HealthStatus, Option<f64>)> = report
+            .iter()
+            .map(|h| (h.name.as_str(), h.status, h.value))
+            .collect();
+        assert_eq!(
+            flagged,
+            vec![
+                ("OBV", HealthStatus::VolumeUnavailable, None),
+                ("VWAP", HealthStatus::VolumeUnavailable, None),
+            ]
+        );
+
+        pipeline
+            .update_all(&trending_bar(3), Timeframe::M1)
+            .unwrap();
+        assert!(pipeline.health_check().is_empty());
+        assert!(pipeline.get_value("OBV", Timeframe::M1).is_some());
+    }
+
+    fn trending_bar(i: i64) -> BarData {
+        let close = 100.0 + (i as f64 * 0.3).sin() * 3.0 + i as f64 * 0.05;
+        BarData {
+            open: close - 0.2,
+            high: close + 1.0,
+            low: close - 1.0,
+            close,
+            volume: 1000.0,
+            timestamp: i,
+        }
+    }
+
+    fn assert_typed_outputs(parallel_threshold: usize) {
+        use crate::indicators::{BollingerBands, Stochastic, MACD, SMA};
+
+        let mut pipeline = IndicatorPipeline::new(100);
+        pipeline.set_parallel_threshold(parallel_threshold);
+        pipeline.register_indicator("MACD".to_string(), Box::new(MACD::new(3, 6, 3)));
+        pipeline.register_indicator("BB".to_string(), Box::new(BollingerBands::new(5, 2.0)));
+        pipeline.register_indicator("STOCH".to_string(), Box::new(Stochastic::new(5, 3)));
+        pipeline.register_indicator("SMA".to_string(), Box::new(SMA::new(3)));
+
+        let mut macd = MACD::new(3, 6, 3);
+        let mut bollinger = BollingerBands::new(5, 2.0);
+        let mut stochastic = Stochastic::new(5, 3);
+
+        // Registered but not warmed up yet
+        let bar = trending_bar(0);
+        pipeline.update_all(&bar, Timeframe::M1).unwrap();
+        macd.update(bar);
+        bollinger.update(bar);
+        stochastic.update(bar);
+        assert!(pipeline.get_macd("MACD", Timeframe::M1).is_none());
+        assert!(pipeline.get_bollinger("BB", Timeframe::M1).is_none());
+        assert!(pipeline.get_stochastic("STOCH", Timeframe::M1).is_none());
+
+        for i in 1..30 {
+            let bar = trending_bar(i);
+            pipeline.update_all(&bar, Timeframe::M1).unwrap();
+            macd.update(bar);
+            bollinger.update(bar);
+            stochastic.update(bar);
+        }
+
+        assert_eq!(pipeline.get_macd("MACD", Timeframe::M1), macd.get_output());
+        assert_eq!(
+            pipeline.get_bollinger("BB", Timeframe::M1),
+            bollinger.get_bands()
+        );
+        assert_eq!(
+            pipeline.get_stochastic("STOCH", Timeframe::M1),
+            stochastic.get_output()
+        );
+        assert!(pipeline.get_macd("MACD", Timeframe::M1).is_some());
+
+        // Scalar indicators, wrong types, and other timeframes have no typed output
+        assert!(pipeline.get_macd("SMA", Timeframe::M1).is_none());
+        assert!(pipeline.get_bollinger("MACD", Timeframe::M1).is_none());
+        assert!(pipeline.get_macd("MACD", Timeframe::H1).is_none());
+        assert!(pipeline.get_macd("MISSING", Timeframe::M1).is_none());
+    }
+
+    #[test]
+    fn test_typed_outputs_sequential() {
+        assert_typed_outputs(usize::MAX);
+    }
+
+    #[test]
+    fn test_typed_outputs_parallel() {
+        assert_typed_outputs(0);
+    }
+
+    #[test]
+    fn test_typed_output_cleared_on_reset() {
+        use crate::indicators::MACD;
+
+        let pipeline = IndicatorPipeline::new(100);
+        pipeline.register_indicator("MACD".to_string(), Box::new(MACD::new(3, 6, 3)));
+        for i in 0..20 {
+            pipeline
+                .update_all(&trending_bar(i), Timeframe::M1)
+                .unwrap();
+        }
+        assert!(pipeline.get_macd("MACD", Timeframe::M1).is_some());
+
+        pipeline.reset_indicator("MACD");
+        assert!(pipeline.get_macd("MACD", Timeframe::M1).is_none());
+    }
+
+    fn mock(name: &str) -> Box<MockIndicator> {
+        Box::new(MockIndicator {
+            name: name.to_string(),
+            value: 0.0,
+        })
+    }
+
+    fn assert_disabled_keeps_state(parallel_threshold: usize) {
+        let mut pipeline = IndicatorPipeline::new(100);
+        pipeline.set_parallel_threshold(parallel_threshold);
+        pipeline.register_indicator("A".to_string(), mock("A"));
+        pipeline.register_indicator("B".to_string(), mock("B"));
+
+        pipeline
+            .update_all(&trending_bar(0), Timeframe::M1)
+            .unwrap();
+        pipeline.set_enabled("B", false);
+        assert!(!pipeline.is_enabled("B"));
+        assert!(pipeline.is_stale("B"));
+        assert!(!pipeline.is_stale("A"));
+
+        let result = pipeline
+            .update_all(&trending_bar(1), Timeframe::M1)
+            .unwrap();
+        assert_eq!(result.updated_count, 1);
+        assert_eq!(result.failed_count, 0);
+        assert_eq!(result.skipped_count, 1);
+
+        // The muted indicator still reports its last value
+        assert_eq!(pipeline.get_value("A", Timeframe::M1), Some(2.0));
+        assert_eq!(pipeline.get_value("B", Timeframe::M1), Some(1.0));
+
+        // Re-enabling resumes from the preserved state rather than re-warming
+        pipeline.set_enabled("B", true);
+        assert!(!pipeline.is_stale("B"));
+        let result = pipeline
+            .update_all(&trending_bar(2), Timeframe::M1)
+            .unwrap();
+        assert_eq!(result.skipped_count, 0);
+        assert_eq!(pipeline.get_value("B", Timeframe::M1), Some(2.0));
+    }
+
+    #[test]
+    fn test_disabled_indicator_sequential() {
+        assert_disabled_keeps_state(usize::MAX);
+    }
+
+    #[test]
+    fn test_disabled_indicator_parallel() {
+        assert_disabled_keeps_state(0);
+    }
+
+    #[test]
+    fn test_set_enabled_unknown_and_removed() {
+        let pipeline = IndicatorPipeline::new(100);
+        pipeline.set_enabled("MISSING", false);
+        assert!(!pipeline.is_stale("MISSING"));
+        assert!(!pipeline.is_enabled("MISSING"));
+
+        pipeline.register_indicator("A".to_string(), mock("A"));
+        pipeline.set_enabled("A", false);
+        let result = pipeline
+            .update_all(&trending_bar(0), Timeframe::M1)
+            .unwrap();
+        assert_eq!((result.updated_count, result.skipped_count), (0, 1));
+
+        // Re-registering under the same name starts enabled
+        pipeline.remove_indicator("A");
+        pipeline.register_indicator("A".to_string(), mock("A"));
+        assert!(pipeline.is_enabled("A"));
+    }
+
+    fn assert_derived_follow_sources(parallel_threshold: usize) {
+        use crate::indicators::{Chained, RSI, SMA};
+
+        let mut pipeline = IndicatorPipeline::new(100);
+        pipeline.set_parallel_threshold(parallel_threshold);
+        pipeline.register_indicator("RSI".to_string(), Box::new(RSI::new(5)));
+        pipeline.register_indicator(
+            "RSI_SMA".to_string(),
+            Box::new(Chained::new(RSI::new(5), SMA::new(3))),
+        );
+        pipeline
+            .register_derived(
+                "RSI_DIFF".to_string(),
+                DerivedIndicator::binary("RSI", "RSI_SMA", |rsi, sma| rsi - sma),
+            )
+            .unwrap();
+        pipeline
+            .register_derived(
+                "RSI_DIFF_ABS".to_string(),
+                DerivedIndicator::unary("RSI_DIFF", f64::abs),
+            )
+            .unwrap();
+
+        for i in 0..30 {
+            let result = pipeline
+                .update_all(&trending_bar(i), Timeframe::M1)
+                .unwrap();
+
+            let rsi = pipeline
+                .get_indicator_value("RSI", Timeframe::M1)
+                .filter(|v| v.timestamp == i);
+            let sma = pipeline
+                .get_indicator_value("RSI_SMA", Timeframe::M1)
+                .filter(|v| v.timestamp == i);
+            let diff = pipeline
+                .get_indicator_value("RSI_DIFF", Timeframe::M1)
+                .filter(|v| v.timestamp == i);
+            match (rsi, sma) {
+                (Some(rsi), Some(sma)) => {
+                    let diff = diff.unwrap().value;
+                    assert_eq!(diff, rsi.value - sma.value);
+                    assert_eq!(
+                        pipeline.get_value("RSI_DIFF_ABS", Timeframe::M1),
+                        Some(diff.abs())
+                    );
+                    assert_eq!(result.updated_count, 4);
+                }
+                _ => assert!(diff.is_none(), "bar {}", i),
+            }
+        }
+    }
+
+    #[test]
+    fn test_derived_indicators_sequential() {
+        assert_derived_follow_sources(usize::MAX);
+    }
+
+    #[test]
+    fn test_derived_indicators_parallel() {
+        assert_derived_follow_sources(0);
+    }
+
+    #[test]
+    fn test_derived_registration_errors() {
+        let pipeline = IndicatorPipeline::new(100);
+        pipeline.register_indicator("A".to_string(), mock("A"));
+
+        // Unknown source, self reference and a clash with a regular name
+        assert!(pipeline
+            .register_derived("D".to_string(), DerivedIndicator::unary("B", |a| a))
+            .is_err());
+        assert!(pipeline
+            .register_derived("D".to_string(), DerivedIndicator::unary("D", |a| a))
+            .is_err());
+        assert!(pipeline
+            .register_derived("A".to_string(), DerivedIndicator::unary("A", |a| a))
+            .is_err());
+
+        pipeline
+            .register_derived("D1".to_string(), DerivedIndicator::unary("A", |a| a))
+            .unwrap();
+        pipeline
+            .register_derived("D2".to_string(), DerivedIndicator::unary("D1", |a| a * 2.0))
+            .unwrap();
+        assert!(pipeline
+            .register_derived("D1".to_string(), DerivedIndicator::unary("D2", |a| a))
+            .is_err());
+
+        // The rejected registration left the graph intact
+        pipeline
+            .update_all(&trending_bar(0), Timeframe::M1)
+            .unwrap();
+        assert_eq!(pipeline.get_value("D2", Timeframe::M1), Some(2.0));
+        assert_eq!(pipeline.get_indicator_names(), ["A", "D1", "D2"]);
+
+        assert!(pipeline.remove_indicator("D1"));
+        let result = pipeline
+            .update_all(&trending_bar(1), Timeframe::M1)
+            .unwrap();
+        assert_eq!((result.updated_count, result.failed_count), (1, 1));
+    }
+
+    #[test]
+    fn test_clone_empty_matches_original() {
+        use crate::indicators::{EMA, SMA};
+
+        let pipeline = IndicatorPipeline::new(100);
+        pipeline.register_many(
+            (2..8)
+                .map(|period| -> (String, BoxedIndicator) {
+                    (format!("SMA_{}", period), Box::new(SMA::new(period)))
+                })
+                .collect(),
+        );
+        pipeline.register_indicator("EMA".to_string(), Box::new(EMA::new(4)));
+        pipeline
+            .register_derived(
+                "SPREAD".to_string(),
+                DerivedIndicator::binary("SMA_2", "SMA_7", |fast, slow| fast - slow),
+            )
+            .unwrap();
+        pipeline.set_enabled("SMA_3", false);
+
+        for i in 0..20 {
+            pipeline
+                .update_all(&trending_bar(i), Timeframe::M1)
+                .unwrap();
+        }
+
+        let names = pipeline.get_indicator_names();
+        let workers: Vec<_> = (0..3)
+            .map(|_| {
+                let copy = pipeline.clone_empty();
+                assert_eq!(copy.get_indicator_names().len(), names.len());
+                assert!(copy.get_value("SMA_7", Timeframe::M1).is_none());
+                assert!(!copy.is_enabled("SMA_3"));
+                std::thread::spawn(move || {
+                    for i in 0..20 {
+                        copy.update_all(&trending_bar(i), Timeframe::M1).unwrap();
+                    }
+                    copy
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            let copy = worker.join().unwrap();
+            for name in &names {
+                assert_eq!(
+                    copy.get_value(name, Timeframe::M1),
+                    pipeline.get_value(name, Timeframe::M1),
+                    "{}",
+                    name
+                );
+            }
+        }
+        assert!(pipeline.get_value("SPREAD", Timeframe::M1).is_some());
+    }
 }
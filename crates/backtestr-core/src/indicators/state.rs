@@ -0,0 +1,47 @@
+//! Encoding helpers for `Indicator::serialize_state` / `restore_state`.
+//!
+//! State is stored as a bincode-encoded `(name, state)` pair so that bytes
+//! from one indicator type are never silently decoded into another.
+
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Debug;
+
+pub(crate) fn encode<T: Serialize>(name: &str, state: &T) -> Vec<u8> {
+    bincode::serialize(&(name, state)).unwrap_or_default()
+}
+
+pub(crate) fn decode<T: DeserializeOwned>(name: &str, data: &[u8]) -> Result<T> {
+    let (stored_name, state): (String, T) =
+        bincode::deserialize(data).with_context(|| format!("Failed to decode {} state", name))?;
+
+    if stored_name != name {
+        bail!(
+            "Cannot restore {} state into a {} indicator",
+            stored_name,
+            name
+        );
+    }
+
+    Ok(state)
+}
+
+/// Fail if a restored parameter differs from the receiving indicator's
+pub(crate) fn ensure_parameter<T: PartialEq + Debug>(
+    name: &str,
+    parameter: &str,
+    expected: T,
+    found: T,
+) -> Result<()> {
+    if expected != found {
+        bail!(
+            "{} {} mismatch: indicator has {:?}, saved state has {:?}",
+            name,
+            parameter,
+            expected,
+            found
+        );
+    }
+    Ok(())
+}
@@ -1,14 +1,15 @@
-use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::indicator_trait::{BarData, Indicator, PriceSource};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DEMA {
     period: usize,
+    source: PriceSource,
     ema1: Ema,
     ema2: Ema,
     current_value: Option<f64>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Ema {
     period: usize,
     multiplier: f64,
@@ -59,11 +60,18 @@ impl DEMA {
     pub fn new(period: usize) -> Self {
         Self {
             period,
+            source: PriceSource::Close,
             ema1: Ema::new(period),
             ema2: Ema::new(period),
             current_value: None,
         }
     }
+
+    /// Price the average is computed on; `Close` by default
+    pub fn with_source(mut self, source: PriceSource) -> Self {
+        self.source = source;
+        self
+    }
 }
 
 impl Indicator for DEMA {
@@ -79,7 +87,7 @@ impl Indicator for DEMA {
     }
 
     fn update(&mut self, input: BarData) -> Option<f64> {
-        let value = input.close;
+        let value = self.source.price(&input);
 
         if let Some(ema1_value) = self.ema1.update(value) {
             if let Some(ema2_value) = self.ema2.update(ema1_value) {
@@ -103,6 +111,10 @@ impl Indicator for DEMA {
         self.ema2.reset();
         self.current_value = None;
     }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
 }
 
 #[cfg(test)]
@@ -1,8 +1,11 @@
-use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::indicator_trait::{BarData, Indicator, PriceSource};
+use crate::indicators::state;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EMA {
     period: usize,
+    source: PriceSource,
     multiplier: f64,
     current_value: Option<f64>,
     count: usize,
@@ -14,12 +17,19 @@ impl EMA {
         let multiplier = 2.0 / (period as f64 + 1.0);
         Self {
             period,
+            source: PriceSource::Close,
             multiplier,
             current_value: None,
             count: 0,
             sma_sum: 0.0,
         }
     }
+
+    /// Price the average is computed on; `Close` by default
+    pub fn with_source(mut self, source: PriceSource) -> Self {
+        self.source = source;
+        self
+    }
 }
 
 impl Indicator for EMA {
@@ -35,7 +45,7 @@ impl Indicator for EMA {
     }
 
     fn update(&mut self, input: BarData) -> Option<f64> {
-        let value = input.close;
+        let value = self.source.price(&input);
         self.count += 1;
 
         if self.count < self.period {
@@ -54,6 +64,30 @@ impl Indicator for EMA {
         }
     }
 
+    fn warm_up_batch(&mut self, inputs: &[BarData]) -> Option<f64> {
+        // Seed through `update` until the initial SMA exists, then run the
+        // recurrence on a local so the hot loop stays in registers
+        let seed = self.period.saturating_sub(self.count).min(inputs.len());
+        let mut last = None;
+        for input in &inputs[..seed] {
+            last = self.update(*input);
+        }
+
+        let rest = &inputs[seed..];
+        let Some(mut ema) = self.current_value else {
+            return last;
+        };
+        if rest.is_empty() {
+            return last;
+        }
+        for input in rest {
+            ema = (self.source.price(input) - ema) * self.multiplier + ema;
+        }
+        self.count += rest.len();
+        self.current_value = Some(ema);
+        self.current_value
+    }
+
     fn current(&self) -> Option<f64> {
         self.current_value
     }
@@ -63,6 +97,22 @@ impl Indicator for EMA {
         self.count = 0;
         self.sma_sum = 0.0;
     }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn serialize_state(&self) -> Vec<u8> {
+        state::encode(self.name(), self)
+    }
+
+    fn restore_state(&mut self, data: &[u8]) -> anyhow::Result<()> {
+        let restored: Self = state::decode(self.name(), data)?;
+        state::ensure_parameter(self.name(), "period", self.period, restored.period)?;
+        state::ensure_parameter(self.name(), "source", self.source, restored.source)?;
+        *self = restored;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -123,6 +173,34 @@ mod tests {
         assert!((result.unwrap() - expected).abs() < 0.001);
     }
 
+    #[test]
+    fn test_warm_up_batch_matches_incremental() {
+        let bars: Vec<BarData> = (0..300)
+            .map(|i| BarData {
+                open: 100.0,
+                high: 101.0,
+                low: 99.0,
+                close: 100.0 + (i as f64 * 0.29).cos() * 2.3,
+                volume: 1000.0,
+                timestamp: i,
+            })
+            .collect();
+
+        let mut incremental = EMA::new(12);
+        let expected: Vec<Option<u64>> = bars
+            .iter()
+            .map(|bar| incremental.update(*bar).map(f64::to_bits))
+            .collect();
+
+        // Split across calls, including one that ends inside the seed period
+        let mut batch = EMA::new(12);
+        batch.warm_up_batch(&bars[..5]);
+        batch.warm_up_batch(&bars[5..40]);
+        let last = batch.warm_up_batch(&bars[40..]);
+        assert_eq!(last.map(f64::to_bits), *expected.last().unwrap());
+        assert_eq!(batch.count, incremental.count);
+    }
+
     #[test]
     fn test_ema_smoothing() {
         let mut ema = EMA::new(5);
@@ -142,4 +220,30 @@ mod tests {
         let final_value = ema.current().unwrap();
         assert!(final_value > 105.0 && final_value < 110.0);
     }
+
+    #[test]
+    fn test_ema_median_source() {
+        let mut ema = EMA::new(3).with_source(PriceSource::Median);
+        for i in 0..3 {
+            ema.update(BarData {
+                open: 100.0,
+                high: 110.0 + i as f64,
+                low: 90.0 + i as f64,
+                close: 0.0,
+                volume: 1000.0,
+                timestamp: i,
+            });
+        }
+        // Seeded with the mean of medians 100, 101 and 102
+        assert_eq!(ema.current(), Some(101.0));
+    }
+
+    #[test]
+    fn test_restore_rejects_other_source() {
+        let mut ema = EMA::new(3);
+        let state = ema.serialize_state();
+        ema = ema.with_source(PriceSource::Typical);
+        let err = ema.restore_state(&state).unwrap_err();
+        assert!(err.to_string().contains("source mismatch"));
+    }
 }
@@ -0,0 +1,147 @@
+use super::wma::WMA;
+use crate::indicators::indicator_trait::{BarData, Indicator, PriceSource};
+
+/// Hull Moving Average: `WMA(2*WMA(n/2) - WMA(n), sqrt(n))`.
+///
+/// The difference of the half and full length WMAs cancels most of the
+/// lag, and the final short WMA smooths it. All three stages are the
+/// regular `WMA`.
+#[derive(Debug, Clone)]
+pub struct HMA {
+    period: usize,
+    sqrt_period: usize,
+    half: WMA,
+    full: WMA,
+    smooth: WMA,
+    current_value: Option<f64>,
+}
+
+impl HMA {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        let half_period = (period / 2).max(1);
+        let sqrt_period = ((period as f64).sqrt() as usize).max(1);
+        Self {
+            period,
+            sqrt_period,
+            half: WMA::new(half_period),
+            full: WMA::new(period),
+            smooth: WMA::new(sqrt_period),
+            current_value: None,
+        }
+    }
+
+    /// Price the half and full length WMAs are computed on; `Close` by
+    /// default
+    pub fn with_source(mut self, source: PriceSource) -> Self {
+        self.half = WMA::new((self.period / 2).max(1)).with_source(source);
+        self.full = WMA::new(self.period).with_source(source);
+        self
+    }
+}
+
+impl Indicator for HMA {
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        "HMA"
+    }
+
+    fn warm_up_period(&self) -> usize {
+        // The smoothing WMA starts on the first bar the full WMA is ready
+        self.period + self.sqrt_period - 1
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        // The half WMA must see every bar, so update it before bailing out
+        let half = self.half.update(input);
+        let full = self.full.update(input)?;
+        let raw = 2.0 * half? - full;
+
+        let hma = self.smooth.update(BarData {
+            open: raw,
+            high: raw,
+            low: raw,
+            close: raw,
+            volume: input.volume,
+            timestamp: input.timestamp,
+        })?;
+        self.current_value = Some(hma);
+        Some(hma)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current_value
+    }
+
+    fn reset(&mut self) {
+        self.half.reset();
+        self.full.reset();
+        self.smooth.reset();
+        self.current_value = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(i: i64, close: f64) -> BarData {
+        BarData {
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 1000.0,
+            timestamp: i,
+        }
+    }
+
+    #[test]
+    fn test_hma_warm_up() {
+        let mut hma = HMA::new(9);
+        // 9 bars for the full WMA, then 3 for the sqrt(9) smoothing
+        assert_eq!(hma.warm_up_period(), 11);
+
+        for i in 0..10 {
+            assert!(hma.update(bar(i, 100.0 + i as f64)).is_none());
+        }
+        assert!(hma.update(bar(10, 110.0)).is_some());
+    }
+
+    #[test]
+    fn test_hma_less_lag_than_wma() {
+        let mut hma = HMA::new(16);
+        let mut wma = WMA::new(16);
+
+        for i in 0..60 {
+            let b = bar(i, 100.0 + i as f64);
+            hma.update(b);
+            wma.update(b);
+        }
+
+        let last_close = 159.0;
+        let hma_lag = last_close - hma.current().unwrap();
+        let wma_lag = last_close - wma.current().unwrap();
+        assert!(hma_lag.abs() < 1.0);
+        assert!(wma_lag > 4.0);
+    }
+
+    #[test]
+    fn test_hma_reset() {
+        let mut hma = HMA::new(4);
+        for i in 0..10 {
+            hma.update(bar(i, 100.0));
+        }
+        assert!((hma.current().unwrap() - 100.0).abs() < 1e-9);
+
+        hma.reset();
+        assert!(hma.current().is_none());
+        assert!(hma.update(bar(0, 100.0)).is_none());
+    }
+}
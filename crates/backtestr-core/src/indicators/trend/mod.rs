@@ -1,9 +1,13 @@
 pub mod dema;
 pub mod ema;
+pub mod hma;
 pub mod sma;
+pub mod tema;
 pub mod wma;
 
 pub use dema::DEMA;
 pub use ema::EMA;
+pub use hma::HMA;
 pub use sma::SMA;
+pub use tema::TEMA;
 pub use wma::WMA;
@@ -1,9 +1,12 @@
-use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::indicator_trait::{BarData, Indicator, PriceSource};
+use crate::indicators::state;
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SMA {
     period: usize,
+    source: PriceSource,
     values: VecDeque<f64>,
     sum: f64,
     current_value: Option<f64>,
@@ -13,11 +16,18 @@ impl SMA {
     pub fn new(period: usize) -> Self {
         Self {
             period,
+            source: PriceSource::Close,
             values: VecDeque::with_capacity(period),
             sum: 0.0,
             current_value: None,
         }
     }
+
+    /// Price the average is computed on; `Close` by default
+    pub fn with_source(mut self, source: PriceSource) -> Self {
+        self.source = source;
+        self
+    }
 }
 
 impl Indicator for SMA {
@@ -33,7 +43,7 @@ impl Indicator for SMA {
     }
 
     fn update(&mut self, input: BarData) -> Option<f64> {
-        let value = input.close;
+        let value = self.source.price(&input);
 
         self.values.push_back(value);
         self.sum += value;
@@ -52,6 +62,34 @@ impl Indicator for SMA {
         }
     }
 
+    fn warm_up_batch(&mut self, inputs: &[BarData]) -> Option<f64> {
+        if !self.values.is_empty() || self.period == 0 {
+            let mut last = None;
+            for input in inputs {
+                last = self.update(*input);
+            }
+            return last;
+        }
+
+        // Same add/subtract order as `update`, without the deque churn
+        for (i, input) in inputs.iter().enumerate() {
+            self.sum += self.source.price(input);
+            if i >= self.period {
+                self.sum -= self.source.price(&inputs[i - self.period]);
+            }
+        }
+
+        let kept = inputs.len().saturating_sub(self.period);
+        self.values
+            .extend(inputs[kept..].iter().map(|bar| self.source.price(bar)));
+        if self.values.len() == self.period {
+            self.current_value = Some(self.sum / self.period as f64);
+            self.current_value
+        } else {
+            None
+        }
+    }
+
     fn current(&self) -> Option<f64> {
         self.current_value
     }
@@ -61,6 +99,22 @@ impl Indicator for SMA {
         self.sum = 0.0;
         self.current_value = None;
     }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn serialize_state(&self) -> Vec<u8> {
+        state::encode(self.name(), self)
+    }
+
+    fn restore_state(&mut self, data: &[u8]) -> anyhow::Result<()> {
+        let restored: Self = state::decode(self.name(), data)?;
+        state::ensure_parameter(self.name(), "period", self.period, restored.period)?;
+        state::ensure_parameter(self.name(), "source", self.source, restored.source)?;
+        *self = restored;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -118,6 +172,37 @@ mod tests {
         assert!((result.unwrap() - 103.0).abs() < 0.001); // (102 + 103 + 104) / 3
     }
 
+    #[test]
+    fn test_warm_up_batch_matches_incremental() {
+        let bars: Vec<BarData> = (0..500)
+            .map(|i| BarData {
+                open: 100.0,
+                high: 101.0,
+                low: 99.0,
+                close: 100.0 + (i as f64 * 0.37).sin() * 3.1,
+                volume: 1000.0,
+                timestamp: i,
+            })
+            .collect();
+
+        let mut incremental = SMA::new(20);
+        let expected = bars.iter().map(|bar| incremental.update(*bar)).last();
+
+        let mut batch = SMA::new(20);
+        assert_eq!(batch.warm_up_batch(&bars), expected.unwrap());
+
+        // Both keep streaming identically afterwards
+        let next = bars[7];
+        assert_eq!(
+            batch.update(next).map(f64::to_bits),
+            incremental.update(next).map(f64::to_bits)
+        );
+
+        let mut short = SMA::new(20);
+        assert_eq!(short.warm_up_batch(&bars[..5]), None);
+        assert_eq!(short.current(), None);
+    }
+
     #[test]
     fn test_sma_reset() {
         let mut sma = SMA::new(2);
@@ -139,4 +224,38 @@ mod tests {
         assert!(sma.current().is_none());
         assert_eq!(sma.values.len(), 0);
     }
+
+    #[test]
+    fn test_sma_price_source() {
+        let bars: Vec<BarData> = (0..6)
+            .map(|i| BarData {
+                open: 100.0,
+                high: 104.0 + i as f64,
+                low: 98.0,
+                close: 101.0 + i as f64,
+                volume: 1000.0,
+                timestamp: i,
+            })
+            .collect();
+
+        let mut typical = SMA::new(3).with_source(PriceSource::Typical);
+        let mut batched = SMA::new(3).with_source(PriceSource::Typical);
+        for bar in &bars {
+            typical.update(*bar);
+        }
+        batched.warm_up_batch(&bars);
+
+        // Mean of (h + l + c) / 3 over the last three bars
+        let expected = bars[3..]
+            .iter()
+            .map(|b| (b.high + b.low + b.close) / 3.0)
+            .sum::<f64>()
+            / 3.0;
+        assert!((typical.current().unwrap() - expected).abs() < 1e-9);
+        assert!((batched.current().unwrap() - expected).abs() < 1e-9);
+
+        let mut close = SMA::new(3);
+        close.warm_up_batch(&bars);
+        assert_eq!(close.current(), Some(105.0));
+    }
 }
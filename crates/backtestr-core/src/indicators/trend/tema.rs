@@ -0,0 +1,143 @@
+use super::ema::EMA;
+use crate::indicators::indicator_trait::{BarData, Indicator, PriceSource};
+
+/// Triple Exponential Moving Average: `3*EMA - 3*EMA(EMA) + EMA(EMA(EMA))`.
+///
+/// Each stage is a regular `EMA` fed the previous stage's output, so the
+/// first value arrives once all three have seeded.
+#[derive(Debug, Clone)]
+pub struct TEMA {
+    period: usize,
+    ema1: EMA,
+    ema2: EMA,
+    ema3: EMA,
+    current_value: Option<f64>,
+}
+
+impl TEMA {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            ema1: EMA::new(period),
+            ema2: EMA::new(period),
+            ema3: EMA::new(period),
+            current_value: None,
+        }
+    }
+
+    /// Price the first EMA stage is computed on; `Close` by default
+    pub fn with_source(mut self, source: PriceSource) -> Self {
+        self.ema1 = EMA::new(self.period).with_source(source);
+        self
+    }
+}
+
+/// Carries a derived value into the next stage as a flat bar
+pub(crate) fn flat_bar(value: f64, input: &BarData) -> BarData {
+    BarData {
+        open: value,
+        high: value,
+        low: value,
+        close: value,
+        volume: input.volume,
+        timestamp: input.timestamp,
+    }
+}
+
+impl Indicator for TEMA {
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        "TEMA"
+    }
+
+    fn warm_up_period(&self) -> usize {
+        // Each nested EMA seeds on the first value of the one before it
+        self.period * 3 - 2
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        let ema1 = self.ema1.update(input)?;
+        let ema2 = self.ema2.update(flat_bar(ema1, &input))?;
+        let ema3 = self.ema3.update(flat_bar(ema2, &input))?;
+
+        let tema = 3.0 * ema1 - 3.0 * ema2 + ema3;
+        self.current_value = Some(tema);
+        Some(tema)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current_value
+    }
+
+    fn reset(&mut self) {
+        self.ema1.reset();
+        self.ema2.reset();
+        self.ema3.reset();
+        self.current_value = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(i: i64, close: f64) -> BarData {
+        BarData {
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 1000.0,
+            timestamp: i,
+        }
+    }
+
+    #[test]
+    fn test_tema_warm_up() {
+        let mut tema = TEMA::new(4);
+        assert_eq!(tema.warm_up_period(), 10);
+
+        for i in 0..9 {
+            assert!(tema.update(bar(i, 100.0 + i as f64)).is_none());
+        }
+        assert!(tema.update(bar(9, 109.0)).is_some());
+    }
+
+    #[test]
+    fn test_tema_tracks_linear_trend() {
+        let mut tema = TEMA::new(5);
+        let mut ema = EMA::new(5);
+
+        for i in 0..40 {
+            let b = bar(i, 100.0 + i as f64);
+            tema.update(b);
+            ema.update(b);
+        }
+
+        // On a straight line TEMA removes the lag that a plain EMA carries
+        let last_close = 139.0;
+        let tema_value = tema.current().unwrap();
+        assert!((tema_value - last_close).abs() < 0.01);
+        assert!(last_close - ema.current().unwrap() > 1.0);
+    }
+
+    #[test]
+    fn test_tema_reset() {
+        let mut tema = TEMA::new(3);
+        for i in 0..10 {
+            tema.update(bar(i, 100.0));
+        }
+        assert_eq!(tema.current(), Some(100.0));
+
+        tema.reset();
+        assert!(tema.current().is_none());
+        assert!(tema.update(bar(0, 100.0)).is_none());
+    }
+}
@@ -1,9 +1,10 @@
-use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::indicator_trait::{BarData, Indicator, PriceSource};
 use std::collections::VecDeque;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WMA {
     period: usize,
+    source: PriceSource,
     values: VecDeque<f64>,
     weight_sum: f64,
     current_value: Option<f64>,
@@ -14,11 +15,18 @@ impl WMA {
         let weight_sum = (period * (period + 1)) as f64 / 2.0;
         Self {
             period,
+            source: PriceSource::Close,
             values: VecDeque::with_capacity(period),
             weight_sum,
             current_value: None,
         }
     }
+
+    /// Price the average is computed on; `Close` by default
+    pub fn with_source(mut self, source: PriceSource) -> Self {
+        self.source = source;
+        self
+    }
 }
 
 impl Indicator for WMA {
@@ -34,7 +42,7 @@ impl Indicator for WMA {
     }
 
     fn update(&mut self, input: BarData) -> Option<f64> {
-        let value = input.close;
+        let value = self.source.price(&input);
 
         self.values.push_back(value);
 
@@ -65,6 +73,10 @@ impl Indicator for WMA {
         self.values.clear();
         self.current_value = None;
     }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
 }
 
 #[cfg(test)]
@@ -1,9 +1,24 @@
 use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::state;
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 
-#[derive(Debug)]
+/// How true ranges are averaged once the first ATR is seeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AtrSmoothing {
+    /// Wilder's running average, `(prev * (n - 1) + tr) / n`
+    #[default]
+    Wilder,
+    /// Simple average of the last `period` true ranges
+    Sma,
+    /// Exponential average with `2 / (n + 1)` weighting
+    Ema,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ATR {
     period: usize,
+    smoothing: AtrSmoothing,
     tr_values: VecDeque<f64>,
     current_atr: Option<f64>,
     previous_close: Option<f64>,
@@ -14,6 +29,7 @@ impl ATR {
     pub fn new(period: usize) -> Self {
         Self {
             period,
+            smoothing: AtrSmoothing::Wilder,
             tr_values: VecDeque::with_capacity(period),
             current_atr: None,
             previous_close: None,
@@ -21,15 +37,31 @@ impl ATR {
         }
     }
 
+    /// All methods seed from the simple average of the first `period` true
+    /// ranges, so the warm-up is the same.
+    pub fn with_smoothing(mut self, smoothing: AtrSmoothing) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    pub fn smoothing(&self) -> AtrSmoothing {
+        self.smoothing
+    }
+
     fn calculate_true_range(&self, bar: &BarData) -> f64 {
-        if let Some(prev_close) = self.previous_close {
-            let hl = bar.high - bar.low;
-            let hc = (bar.high - prev_close).abs();
-            let lc = (bar.low - prev_close).abs();
-            hl.max(hc).max(lc)
-        } else {
-            bar.high - bar.low
-        }
+        true_range(bar, self.previous_close)
+    }
+}
+
+/// The bar's range widened to reach the previous close, if there is one
+pub(crate) fn true_range(bar: &BarData, previous_close: Option<f64>) -> f64 {
+    if let Some(prev_close) = previous_close {
+        let hl = bar.high - bar.low;
+        let hc = (bar.high - prev_close).abs();
+        let lc = (bar.low - prev_close).abs();
+        hl.max(hc).max(lc)
+    } else {
+        bar.high - bar.low
     }
 }
 
@@ -47,6 +79,7 @@ impl Indicator for ATR {
 
     fn update(&mut self, input: BarData) -> Option<f64> {
         let tr = self.calculate_true_range(&input);
+        self.previous_close = Some(input.close);
         self.count += 1;
 
         if self.count <= self.period {
@@ -54,18 +87,24 @@ impl Indicator for ATR {
             if self.count == self.period {
                 let initial_atr = self.tr_values.iter().sum::<f64>() / self.period as f64;
                 self.current_atr = Some(initial_atr);
-                self.previous_close = Some(input.close);
                 return Some(initial_atr);
             }
-        } else if let Some(prev_atr) = self.current_atr {
-            let new_atr = ((prev_atr * (self.period - 1) as f64) + tr) / self.period as f64;
-            self.current_atr = Some(new_atr);
-            self.previous_close = Some(input.close);
-            return Some(new_atr);
+            return None;
         }
 
-        self.previous_close = Some(input.close);
-        None
+        let prev_atr = self.current_atr?;
+        let period = self.period as f64;
+        let new_atr = match self.smoothing {
+            AtrSmoothing::Wilder => (prev_atr * (period - 1.0) + tr) / period,
+            AtrSmoothing::Ema => prev_atr + (tr - prev_atr) * 2.0 / (period + 1.0),
+            AtrSmoothing::Sma => {
+                self.tr_values.push_back(tr);
+                self.tr_values.pop_front();
+                self.tr_values.iter().sum::<f64>() / period
+            }
+        };
+        self.current_atr = Some(new_atr);
+        Some(new_atr)
     }
 
     fn current(&self) -> Option<f64> {
@@ -78,6 +117,22 @@ impl Indicator for ATR {
         self.previous_close = None;
         self.count = 0;
     }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn serialize_state(&self) -> Vec<u8> {
+        state::encode(self.name(), self)
+    }
+
+    fn restore_state(&mut self, data: &[u8]) -> anyhow::Result<()> {
+        let restored: Self = state::decode(self.name(), data)?;
+        state::ensure_parameter(self.name(), "period", self.period, restored.period)?;
+        state::ensure_parameter(self.name(), "smoothing", self.smoothing, restored.smoothing)?;
+        *self = restored;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -197,4 +252,55 @@ mod tests {
         let value = atr.current().unwrap();
         assert!(value > 2.0); // Gap should increase ATR
     }
+
+    /// Bars around 100 with ranges 2, 4, 6, 8, 2, so each true range is
+    /// the bar's range
+    fn ranged_bars() -> Vec<BarData> {
+        [2.0, 4.0, 6.0, 8.0, 2.0]
+            .iter()
+            .enumerate()
+            .map(|(i, range)| BarData {
+                open: 100.0,
+                high: 100.0 + range / 2.0,
+                low: 100.0 - range / 2.0,
+                close: 100.0,
+                volume: 1000.0,
+                timestamp: i as i64,
+            })
+            .collect()
+    }
+
+    fn final_atr(smoothing: AtrSmoothing) -> f64 {
+        let mut atr = ATR::new(3).with_smoothing(smoothing);
+        let values: Vec<_> = ranged_bars().into_iter().map(|b| atr.update(b)).collect();
+        // Every method seeds from (2 + 4 + 6) / 3
+        assert_eq!(values[2], Some(4.0));
+        values[4].unwrap()
+    }
+
+    #[test]
+    fn test_smoothing_methods() {
+        assert_eq!(ATR::new(3).smoothing(), AtrSmoothing::Wilder);
+
+        // ((4 * 2 + 8) / 3 * 2 + 2) / 3
+        assert!((final_atr(AtrSmoothing::Wilder) - 38.0 / 9.0).abs() < 1e-12);
+        // (6 + 8 + 2) / 3
+        assert!((final_atr(AtrSmoothing::Sma) - 16.0 / 3.0).abs() < 1e-12);
+        // 4 -> 6 -> 4 with alpha 0.5
+        assert!((final_atr(AtrSmoothing::Ema) - 4.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_restore_rejects_other_smoothing() {
+        let mut sma = ATR::new(3).with_smoothing(AtrSmoothing::Sma);
+        for bar in ranged_bars() {
+            sma.update(bar);
+        }
+        let mut wilder = ATR::new(3);
+        assert!(wilder.restore_state(&sma.serialize_state()).is_err());
+
+        let mut other_sma = ATR::new(3).with_smoothing(AtrSmoothing::Sma);
+        other_sma.restore_state(&sma.serialize_state()).unwrap();
+        assert_eq!(other_sma.current(), sma.current());
+    }
 }
@@ -1,7 +1,8 @@
 use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::output::IndicatorOutput;
 use std::collections::VecDeque;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BollingerBands {
     period: usize,
     std_dev: f64,
@@ -11,7 +12,7 @@ pub struct BollingerBands {
     current_lower: Option<f64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct BollingerOutput {
     pub upper: f64,
     pub middle: f64,
@@ -106,6 +107,14 @@ impl Indicator for BollingerBands {
         self.current_upper = None;
         self.current_lower = None;
     }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn structured_output(&self) -> Option<IndicatorOutput> {
+        self.get_bands().map(IndicatorOutput::Bollinger)
+    }
 }
 
 #[cfg(test)]
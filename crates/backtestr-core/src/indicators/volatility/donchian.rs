@@ -1,7 +1,8 @@
 use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::output::IndicatorOutput;
 use std::collections::VecDeque;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DonchianChannels {
     period: usize,
     highs: VecDeque<f64>,
@@ -11,7 +12,7 @@ pub struct DonchianChannels {
     current_lower: Option<f64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct DonchianOutput {
     pub upper: f64,
     pub middle: f64,
@@ -92,6 +93,14 @@ impl Indicator for DonchianChannels {
         self.current_middle = None;
         self.current_lower = None;
     }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn structured_output(&self) -> Option<IndicatorOutput> {
+        self.get_channels().map(IndicatorOutput::Donchian)
+    }
 }
 
 #[cfg(test)]
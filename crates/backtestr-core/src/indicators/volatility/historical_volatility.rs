@@ -0,0 +1,162 @@
+use super::stddev::RollingMoments;
+use crate::indicators::indicator_trait::{BarData, Indicator};
+
+/// Annualized standard deviation of log returns over `period` returns.
+///
+/// Uses the sample estimate and scales by `sqrt(bars_per_year)`, so daily
+/// bars take 252 and the result is a fraction (0.2 is 20% a year). A bar
+/// with a zero or negative close has no log return; it is skipped and the
+/// next return is taken from the last valid close.
+#[derive(Debug, Clone)]
+pub struct HistoricalVolatility {
+    period: usize,
+    annualization: f64,
+    returns: RollingMoments,
+    previous_close: Option<f64>,
+    current_value: Option<f64>,
+}
+
+impl HistoricalVolatility {
+    pub fn new(period: usize, bars_per_year: f64) -> Self {
+        // The sample estimate needs two returns
+        let period = period.max(2);
+        Self {
+            period,
+            annualization: bars_per_year.sqrt(),
+            returns: RollingMoments::new(period),
+            previous_close: None,
+            current_value: None,
+        }
+    }
+}
+
+impl Indicator for HistoricalVolatility {
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        "HV"
+    }
+
+    fn warm_up_period(&self) -> usize {
+        // The first bar only provides the base for the first return
+        self.period + 1
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        if input.close <= 0.0 {
+            return self.current_value;
+        }
+
+        let previous = self.previous_close.replace(input.close)?;
+        self.returns.push((input.close / previous).ln());
+        if !self.returns.is_full() {
+            return None;
+        }
+
+        let volatility = self.returns.variance(true).sqrt() * self.annualization;
+        self.current_value = Some(volatility);
+        Some(volatility)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current_value
+    }
+
+    fn reset(&mut self) {
+        self.returns.clear();
+        self.previous_close = None;
+        self.current_value = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(i: i64, close: f64) -> BarData {
+        BarData {
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 1000.0,
+            timestamp: i,
+        }
+    }
+
+    #[test]
+    fn test_warm_up_needs_period_returns() {
+        let mut hv = HistoricalVolatility::new(3, 252.0);
+        assert_eq!(hv.warm_up_period(), 4);
+        for (i, close) in [100.0, 101.0, 99.0].into_iter().enumerate() {
+            assert!(hv.update(bar(i as i64, close)).is_none());
+        }
+        assert!(hv.update(bar(3, 102.0)).is_some());
+    }
+
+    #[test]
+    fn test_annualized_log_return_deviation() {
+        let closes = [100.0, 102.0, 99.0, 101.0, 100.5];
+        let mut hv = HistoricalVolatility::new(4, 252.0);
+        for (i, &close) in closes.iter().enumerate() {
+            hv.update(bar(i as i64, close));
+        }
+
+        let returns: Vec<f64> = closes.windows(2).map(|w| (w[1] / w[0]).ln()).collect();
+        let mean = returns.iter().sum::<f64>() / 4.0;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / 3.0;
+        let expected = variance.sqrt() * 252.0f64.sqrt();
+
+        assert!((hv.current().unwrap() - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_constant_growth_has_no_volatility() {
+        let mut hv = HistoricalVolatility::new(5, 252.0);
+        let mut close = 100.0;
+        for i in 0..10 {
+            hv.update(bar(i, close));
+            close *= 1.01;
+        }
+        assert!(hv.current().unwrap() < 1e-6);
+    }
+
+    #[test]
+    fn test_non_positive_close_is_skipped() {
+        let mut with_gap = HistoricalVolatility::new(2, 252.0);
+        let mut without = HistoricalVolatility::new(2, 252.0);
+
+        for (i, close) in [100.0, 0.0, 101.0, -5.0, 99.0, 102.0]
+            .into_iter()
+            .enumerate()
+        {
+            let value = with_gap.update(bar(i as i64, close));
+            if let Some(value) = value {
+                assert!(value.is_finite());
+            }
+        }
+        for (i, close) in [100.0, 101.0, 99.0, 102.0].into_iter().enumerate() {
+            without.update(bar(i as i64, close));
+        }
+
+        assert_eq!(with_gap.current(), without.current());
+    }
+
+    #[test]
+    fn test_hv_reset() {
+        let mut hv = HistoricalVolatility::new(2, 252.0);
+        for (i, close) in [100.0, 101.0, 99.0].into_iter().enumerate() {
+            hv.update(bar(i as i64, close));
+        }
+        assert!(hv.current().is_some());
+
+        hv.reset();
+        assert!(hv.current().is_none());
+        assert!(hv.update(bar(3, 100.0)).is_none());
+    }
+}
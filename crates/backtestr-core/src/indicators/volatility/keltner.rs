@@ -1,17 +1,19 @@
 use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::output::IndicatorOutput;
+use crate::indicators::volatility::atr::{AtrSmoothing, ATR};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct KeltnerChannels {
     period: usize,
     multiplier: f64,
     ema: Ema,
-    atr: Atr,
+    atr: ATR,
     current_middle: Option<f64>,
     current_upper: Option<f64>,
     current_lower: Option<f64>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Ema {
     period: usize,
     multiplier: f64,
@@ -20,15 +22,6 @@ struct Ema {
     sma_sum: f64,
 }
 
-#[derive(Debug)]
-struct Atr {
-    period: usize,
-    current_atr: Option<f64>,
-    previous_close: Option<f64>,
-    count: usize,
-    tr_sum: f64,
-}
-
 impl Ema {
     fn new(period: usize) -> Self {
         let multiplier = 2.0 / (period as f64 + 1.0);
@@ -67,57 +60,7 @@ impl Ema {
     }
 }
 
-impl Atr {
-    fn new(period: usize) -> Self {
-        Self {
-            period,
-            current_atr: None,
-            previous_close: None,
-            count: 0,
-            tr_sum: 0.0,
-        }
-    }
-
-    fn update(&mut self, bar: &BarData) -> Option<f64> {
-        let tr = if let Some(prev_close) = self.previous_close {
-            let hl = bar.high - bar.low;
-            let hc = (bar.high - prev_close).abs();
-            let lc = (bar.low - prev_close).abs();
-            hl.max(hc).max(lc)
-        } else {
-            bar.high - bar.low
-        };
-
-        self.count += 1;
-
-        if self.count <= self.period {
-            self.tr_sum += tr;
-            if self.count == self.period {
-                let initial_atr = self.tr_sum / self.period as f64;
-                self.current_atr = Some(initial_atr);
-                self.previous_close = Some(bar.close);
-                return Some(initial_atr);
-            }
-        } else if let Some(prev_atr) = self.current_atr {
-            let new_atr = ((prev_atr * (self.period - 1) as f64) + tr) / self.period as f64;
-            self.current_atr = Some(new_atr);
-            self.previous_close = Some(bar.close);
-            return Some(new_atr);
-        }
-
-        self.previous_close = Some(bar.close);
-        None
-    }
-
-    fn reset(&mut self) {
-        self.current_atr = None;
-        self.previous_close = None;
-        self.count = 0;
-        self.tr_sum = 0.0;
-    }
-}
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct KeltnerOutput {
     pub upper: f64,
     pub middle: f64,
@@ -130,13 +73,19 @@ impl KeltnerChannels {
             period,
             multiplier,
             ema: Ema::new(period),
-            atr: Atr::new(period),
+            atr: ATR::new(period),
             current_middle: None,
             current_upper: None,
             current_lower: None,
         }
     }
 
+    /// Smoothing for the ATR that sets the channel width; Wilder by default
+    pub fn with_atr_smoothing(mut self, smoothing: AtrSmoothing) -> Self {
+        self.atr = self.atr.with_smoothing(smoothing);
+        self
+    }
+
     pub fn get_channels(&self) -> Option<KeltnerOutput> {
         if let (Some(upper), Some(middle), Some(lower)) =
             (self.current_upper, self.current_middle, self.current_lower)
@@ -167,7 +116,7 @@ impl Indicator for KeltnerChannels {
     fn update(&mut self, input: BarData) -> Option<f64> {
         let typical_price = (input.high + input.low + input.close) / 3.0;
         let ema_value = self.ema.update(typical_price);
-        let atr_value = self.atr.update(&input);
+        let atr_value = self.atr.update(input);
 
         if let (Some(ema), Some(atr)) = (ema_value, atr_value) {
             let upper = ema + (self.multiplier * atr);
@@ -194,6 +143,14 @@ impl Indicator for KeltnerChannels {
         self.current_upper = None;
         self.current_lower = None;
     }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn structured_output(&self) -> Option<IndicatorOutput> {
+        self.get_channels().map(IndicatorOutput::Keltner)
+    }
 }
 
 #[cfg(test)]
@@ -266,4 +223,36 @@ mod tests {
         assert!(result.upper > result.middle);
         assert!(result.middle > result.lower);
     }
+
+    #[test]
+    fn test_atr_smoothing_sets_width() {
+        let bars: Vec<BarData> = (0..12)
+            .map(|i| {
+                let range = 1.0 + (i % 4) as f64;
+                BarData {
+                    open: 100.0,
+                    high: 100.0 + range,
+                    low: 100.0 - range,
+                    close: 100.0,
+                    volume: 1000.0,
+                    timestamp: i,
+                }
+            })
+            .collect();
+
+        let mut widths = Vec::new();
+        for smoothing in [AtrSmoothing::Wilder, AtrSmoothing::Sma, AtrSmoothing::Ema] {
+            let mut kc = KeltnerChannels::new(4, 2.0).with_atr_smoothing(smoothing);
+            let mut atr = ATR::new(4).with_smoothing(smoothing);
+            for bar in &bars {
+                kc.update(*bar);
+                atr.update(*bar);
+            }
+            let channels = kc.get_channels().unwrap();
+            let width = channels.upper - channels.middle;
+            assert!((width - 2.0 * atr.current().unwrap()).abs() < 1e-12);
+            widths.push(width);
+        }
+        assert!(widths[0] != widths[1] && widths[1] != widths[2] && widths[0] != widths[2]);
+    }
 }
@@ -1,9 +1,17 @@
 pub mod atr;
 pub mod bollinger;
 pub mod donchian;
+pub mod historical_volatility;
 pub mod keltner;
+pub mod squeeze;
+pub mod stddev;
+pub mod supertrend;
 
-pub use atr::ATR;
+pub use atr::{AtrSmoothing, ATR};
 pub use bollinger::{BollingerBands, BollingerOutput};
 pub use donchian::{DonchianChannels, DonchianOutput};
+pub use historical_volatility::HistoricalVolatility;
 pub use keltner::{KeltnerChannels, KeltnerOutput};
+pub use squeeze::{SqueezeIndicator, SqueezeOutput};
+pub use stddev::StdDev;
+pub use supertrend::{SuperTrend, SuperTrendOutput};
@@ -0,0 +1,207 @@
+use super::bollinger::BollingerBands;
+use super::keltner::KeltnerChannels;
+use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::output::IndicatorOutput;
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqueezeOutput {
+    /// Bollinger Bands are entirely inside the Keltner Channels
+    pub in_squeeze: bool,
+    /// Linear regression of close relative to the range midpoint; the
+    /// histogram value
+    pub momentum: f64,
+}
+
+/// TTM-style squeeze: Bollinger Bands inside Keltner Channels.
+///
+/// Both channels share `period`. Momentum is the end point of a `period`
+/// linear regression over the close's deviation from the mean of the
+/// Donchian midline and the SMA, so the first value arrives after
+/// `2 * period - 1` bars. The scalar output is the momentum.
+#[derive(Debug, Clone)]
+pub struct SqueezeIndicator {
+    period: usize,
+    bollinger: BollingerBands,
+    keltner: KeltnerChannels,
+    highs: VecDeque<f64>,
+    lows: VecDeque<f64>,
+    deviations: VecDeque<f64>,
+    current: Option<SqueezeOutput>,
+}
+
+impl SqueezeIndicator {
+    /// Classic settings are `new(20, 2.0, 1.5)`
+    pub fn new(period: usize, bb_std_dev: f64, kc_multiplier: f64) -> Self {
+        let period = period.max(2);
+        Self {
+            period,
+            bollinger: BollingerBands::new(period, bb_std_dev),
+            keltner: KeltnerChannels::new(period, kc_multiplier),
+            highs: VecDeque::with_capacity(period),
+            lows: VecDeque::with_capacity(period),
+            deviations: VecDeque::with_capacity(period),
+            current: None,
+        }
+    }
+
+    pub fn get_squeeze(&self) -> Option<SqueezeOutput> {
+        self.current.clone()
+    }
+
+    /// Value of the least-squares line through `deviations` at the last point
+    fn linear_regression(&self) -> f64 {
+        let n = self.deviations.len() as f64;
+        let mean_x = (n - 1.0) / 2.0;
+        let mean_y = self.deviations.iter().sum::<f64>() / n;
+
+        let (mut covariance, mut variance) = (0.0, 0.0);
+        for (x, &y) in self.deviations.iter().enumerate() {
+            let dx = x as f64 - mean_x;
+            covariance += dx * (y - mean_y);
+            variance += dx * dx;
+        }
+
+        let slope = covariance / variance;
+        mean_y + slope * (n - 1.0 - mean_x)
+    }
+}
+
+impl Indicator for SqueezeIndicator {
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        "Squeeze"
+    }
+
+    fn warm_up_period(&self) -> usize {
+        // Both channels must be ready before the first deviation, and the
+        // regression needs `period` deviations on top of that
+        let channels = self
+            .bollinger
+            .warm_up_period()
+            .max(self.keltner.warm_up_period());
+        channels + self.period - 1
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        self.bollinger.update(input);
+        self.keltner.update(input);
+
+        self.highs.push_back(input.high);
+        self.lows.push_back(input.low);
+        if self.highs.len() > self.period {
+            self.highs.pop_front();
+            self.lows.pop_front();
+        }
+
+        let (bands, channels) = match (self.bollinger.get_bands(), self.keltner.get_channels()) {
+            (Some(bands), Some(channels)) => (bands, channels),
+            _ => return None,
+        };
+
+        let highest = self.highs.iter().copied().fold(f64::MIN, f64::max);
+        let lowest = self.lows.iter().copied().fold(f64::MAX, f64::min);
+        let midline = ((highest + lowest) / 2.0 + bands.middle) / 2.0;
+
+        self.deviations.push_back(input.close - midline);
+        if self.deviations.len() > self.period {
+            self.deviations.pop_front();
+        }
+        if self.deviations.len() < self.period {
+            return None;
+        }
+
+        let output = SqueezeOutput {
+            in_squeeze: bands.lower > channels.lower && bands.upper < channels.upper,
+            momentum: self.linear_regression(),
+        };
+        let momentum = output.momentum;
+        self.current = Some(output);
+        Some(momentum)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current.as_ref().map(|output| output.momentum)
+    }
+
+    fn reset(&mut self) {
+        self.bollinger.reset();
+        self.keltner.reset();
+        self.highs.clear();
+        self.lows.clear();
+        self.deviations.clear();
+        self.current = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn structured_output(&self) -> Option<IndicatorOutput> {
+        self.get_squeeze().map(IndicatorOutput::Squeeze)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(i: i64, close: f64, range: f64) -> BarData {
+        BarData {
+            open: close,
+            high: close + range,
+            low: close - range,
+            close,
+            volume: 1000.0,
+            timestamp: i,
+        }
+    }
+
+    #[test]
+    fn test_warm_up_waits_for_regression() {
+        let mut squeeze = SqueezeIndicator::new(5, 2.0, 1.5);
+        assert_eq!(squeeze.warm_up_period(), 9);
+
+        for i in 0..8 {
+            assert!(squeeze.update(bar(i, 100.0, 0.5)).is_none());
+        }
+        assert!(squeeze.update(bar(8, 100.0, 0.5)).is_some());
+        assert!(squeeze.is_ready());
+    }
+
+    #[test]
+    fn test_squeeze_fires_and_releases() {
+        let mut squeeze = SqueezeIndicator::new(10, 2.0, 1.5);
+
+        // Tight closes with wide bar ranges: Bollinger sits inside Keltner
+        for i in 0..30 {
+            let close = 100.0 + if i % 2 == 0 { 0.05 } else { -0.05 };
+            squeeze.update(bar(i, close, 1.0));
+        }
+        assert!(squeeze.get_squeeze().unwrap().in_squeeze);
+
+        // A sharp rally widens the Bollinger Bands past the channels
+        for i in 30..36 {
+            squeeze.update(bar(i, 100.0 + (i - 29) as f64 * 2.0, 0.2));
+        }
+        let output = squeeze.get_squeeze().unwrap();
+        assert!(!output.in_squeeze);
+        assert!(output.momentum > 0.0);
+        assert_eq!(squeeze.current(), Some(output.momentum));
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut squeeze = SqueezeIndicator::new(3, 2.0, 1.5);
+        for i in 0..10 {
+            squeeze.update(bar(i, 100.0 + i as f64, 0.5));
+        }
+        assert!(squeeze.structured_output().is_some());
+
+        squeeze.reset();
+        assert!(squeeze.current().is_none());
+        assert!(squeeze.update(bar(0, 100.0, 0.5)).is_none());
+    }
+}
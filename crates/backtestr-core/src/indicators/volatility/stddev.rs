@@ -0,0 +1,182 @@
+use crate::indicators::indicator_trait::{BarData, Indicator};
+use std::collections::VecDeque;
+
+/// Rolling window with a running sum and sum of squares, so the variance is
+/// O(1) per update
+#[derive(Debug, Clone)]
+pub(crate) struct RollingMoments {
+    period: usize,
+    values: VecDeque<f64>,
+    sum: f64,
+    sum_sq: f64,
+}
+
+impl RollingMoments {
+    pub(crate) fn new(period: usize) -> Self {
+        Self {
+            period,
+            values: VecDeque::with_capacity(period + 1),
+            sum: 0.0,
+            sum_sq: 0.0,
+        }
+    }
+
+    pub(crate) fn push(&mut self, value: f64) {
+        self.values.push_back(value);
+        self.sum += value;
+        self.sum_sq += value * value;
+
+        if self.values.len() > self.period {
+            let oldest = self.values.pop_front().unwrap();
+            self.sum -= oldest;
+            self.sum_sq -= oldest * oldest;
+        }
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        self.values.len() == self.period
+    }
+
+    /// Variance of the window, dividing by `n - 1` when `sample` is set
+    pub(crate) fn variance(&self, sample: bool) -> f64 {
+        let n = self.values.len() as f64;
+        let mean = self.sum / n;
+        // Cancellation can leave a flat window slightly negative
+        let squared_deviations = (self.sum_sq - n * mean * mean).max(0.0);
+        let divisor = if sample { n - 1.0 } else { n };
+        squared_deviations / divisor
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.values.clear();
+        self.sum = 0.0;
+        self.sum_sq = 0.0;
+    }
+}
+
+/// Rolling standard deviation of closes.
+///
+/// Population (divide by `n`) by default, which matches the Bollinger Bands
+/// width; `with_sample(true)` divides by `n - 1` instead.
+#[derive(Debug, Clone)]
+pub struct StdDev {
+    period: usize,
+    sample: bool,
+    moments: RollingMoments,
+    current_value: Option<f64>,
+}
+
+impl StdDev {
+    pub fn new(period: usize) -> Self {
+        // The sample estimate needs two values
+        let period = period.max(2);
+        Self {
+            period,
+            sample: false,
+            moments: RollingMoments::new(period),
+            current_value: None,
+        }
+    }
+
+    pub fn with_sample(mut self, sample: bool) -> Self {
+        self.sample = sample;
+        self
+    }
+}
+
+impl Indicator for StdDev {
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        "StdDev"
+    }
+
+    fn warm_up_period(&self) -> usize {
+        self.period
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        self.moments.push(input.close);
+        if !self.moments.is_full() {
+            return None;
+        }
+
+        let std_dev = self.moments.variance(self.sample).sqrt();
+        self.current_value = Some(std_dev);
+        Some(std_dev)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current_value
+    }
+
+    fn reset(&mut self) {
+        self.moments.clear();
+        self.current_value = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(i: i64, close: f64) -> BarData {
+        BarData {
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 1000.0,
+            timestamp: i,
+        }
+    }
+
+    #[test]
+    fn test_population_and_sample() {
+        let closes = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+
+        let mut population = StdDev::new(8);
+        let mut sample = StdDev::new(8).with_sample(true);
+        for (i, &close) in closes.iter().enumerate() {
+            population.update(bar(i as i64, close));
+            sample.update(bar(i as i64, close));
+        }
+
+        assert!((population.current().unwrap() - 2.0).abs() < 1e-12);
+        assert!((sample.current().unwrap() - (32.0f64 / 7.0).sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_rolling_window() {
+        let mut std_dev = StdDev::new(3);
+        assert!(std_dev.update(bar(0, 100.0)).is_none());
+        assert!(std_dev.update(bar(1, 100.0)).is_none());
+        assert_eq!(std_dev.update(bar(2, 100.0)), Some(0.0));
+
+        // Window is now 100, 100, 103
+        let value = std_dev.update(bar(3, 103.0)).unwrap();
+        assert!((value - 2.0f64.sqrt()).abs() < 1e-9);
+
+        // The flat bars have left the window: 103, 103, 103
+        std_dev.update(bar(4, 103.0));
+        std_dev.update(bar(5, 103.0));
+        assert!(std_dev.current().unwrap() < 1e-6);
+    }
+
+    #[test]
+    fn test_std_dev_reset() {
+        let mut std_dev = StdDev::new(2);
+        std_dev.update(bar(0, 1.0));
+        std_dev.update(bar(1, 3.0));
+        assert_eq!(std_dev.current(), Some(1.0));
+
+        std_dev.reset();
+        assert!(std_dev.current().is_none());
+        assert!(std_dev.update(bar(2, 1.0)).is_none());
+    }
+}
@@ -0,0 +1,229 @@
+use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::output::IndicatorOutput;
+use crate::indicators::volatility::ATR;
+
+#[derive(Debug, Clone)]
+pub struct SuperTrend {
+    period: usize,
+    multiplier: f64,
+    atr: ATR,
+    previous_close: Option<f64>,
+    final_upper: Option<f64>,
+    final_lower: Option<f64>,
+    current_output: Option<SuperTrendOutput>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SuperTrendOutput {
+    /// Trend line: the lower band in an uptrend, the upper band in a downtrend
+    pub value: f64,
+    /// +1 for an uptrend, -1 for a downtrend
+    pub direction: i8,
+}
+
+impl SuperTrend {
+    pub fn new(period: usize, multiplier: f64) -> Self {
+        Self {
+            period,
+            multiplier,
+            atr: ATR::new(period),
+            previous_close: None,
+            final_upper: None,
+            final_lower: None,
+            current_output: None,
+        }
+    }
+
+    pub fn get_trend(&self) -> Option<SuperTrendOutput> {
+        self.current_output
+    }
+
+    /// Current final (upper, lower) bands
+    pub fn get_bands(&self) -> Option<(f64, f64)> {
+        self.final_upper.zip(self.final_lower)
+    }
+}
+
+impl Indicator for SuperTrend {
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        "SuperTrend"
+    }
+
+    fn warm_up_period(&self) -> usize {
+        self.period + 1
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        let previous_close = self.previous_close.replace(input.close);
+        let atr = self.atr.update(input)?;
+
+        let hl2 = (input.high + input.low) / 2.0;
+        let basic_upper = hl2 + self.multiplier * atr;
+        let basic_lower = hl2 - self.multiplier * atr;
+
+        // Bands only move towards price unless the prior close broke through them
+        let final_upper = match (self.final_upper, previous_close) {
+            (Some(prev_upper), Some(prev_close))
+                if basic_upper >= prev_upper && prev_close <= prev_upper =>
+            {
+                prev_upper
+            }
+            _ => basic_upper,
+        };
+        let final_lower = match (self.final_lower, previous_close) {
+            (Some(prev_lower), Some(prev_close))
+                if basic_lower <= prev_lower && prev_close >= prev_lower =>
+            {
+                prev_lower
+            }
+            _ => basic_lower,
+        };
+
+        let direction = match self.current_output {
+            Some(prev) if prev.direction > 0 => {
+                if input.close < final_lower {
+                    -1
+                } else {
+                    1
+                }
+            }
+            Some(_) => {
+                if input.close > final_upper {
+                    1
+                } else {
+                    -1
+                }
+            }
+            None => {
+                if input.close >= hl2 {
+                    1
+                } else {
+                    -1
+                }
+            }
+        };
+
+        let value = if direction > 0 {
+            final_lower
+        } else {
+            final_upper
+        };
+
+        self.final_upper = Some(final_upper);
+        self.final_lower = Some(final_lower);
+        self.current_output = Some(SuperTrendOutput { value, direction });
+
+        Some(value)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current_output.map(|output| output.value)
+    }
+
+    fn reset(&mut self) {
+        self.atr.reset();
+        self.previous_close = None;
+        self.final_upper = None;
+        self.final_lower = None;
+        self.current_output = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn structured_output(&self) -> Option<IndicatorOutput> {
+        self.get_trend().map(IndicatorOutput::SuperTrend)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(close: f64, timestamp: i64) -> BarData {
+        BarData {
+            open: close,
+            high: close + 1.0,
+            low: close - 1.0,
+            close,
+            volume: 1000.0,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_none_until_atr_warm() {
+        let mut st = SuperTrend::new(5, 3.0);
+        for i in 0..4 {
+            assert_eq!(st.update(bar(100.0 + i as f64, i)), None);
+            assert_eq!(st.current(), None);
+        }
+        assert!(st.update(bar(104.0, 4)).is_some());
+        assert!(st.get_trend().is_some());
+    }
+
+    #[test]
+    fn test_uptrend_band_is_sticky_and_flips_on_reversal() {
+        let mut st = SuperTrend::new(3, 2.0);
+
+        let mut previous_line: Option<f64> = None;
+        for i in 0..15 {
+            st.update(bar(100.0 + i as f64, i));
+            let trend = st.get_trend();
+            if let Some(trend) = trend {
+                assert_eq!(trend.direction, 1);
+                // Lower band never falls during an uptrend
+                if let Some(prev) = previous_line {
+                    assert!(trend.value >= prev);
+                }
+                previous_line = Some(trend.value);
+                assert_eq!(st.current(), Some(trend.value));
+            }
+        }
+
+        // Sharp selloff closes through the lower band
+        st.update(bar(90.0, 15));
+        let trend = st.get_trend().unwrap();
+        assert_eq!(trend.direction, -1);
+        let (upper, _) = st.get_bands().unwrap();
+        assert_eq!(trend.value, upper);
+        assert!(trend.value > 90.0);
+    }
+
+    #[test]
+    fn test_reset_clears_atr_and_bands() {
+        let mut st = SuperTrend::new(3, 2.0);
+        for i in 0..10 {
+            st.update(bar(100.0 + i as f64, i));
+        }
+        assert!(st.is_ready());
+
+        st.reset();
+        assert!(st.current().is_none());
+        assert!(st.get_bands().is_none());
+        assert_eq!(st.update(bar(100.0, 20)), None);
+    }
+
+    #[test]
+    fn test_pipeline_value_is_trend_line() {
+        use crate::indicators::IndicatorPipeline;
+        use backtestr_data::Timeframe;
+
+        let pipeline = IndicatorPipeline::new(100);
+        pipeline.register_indicator("ST".to_string(), Box::new(SuperTrend::new(3, 2.0)));
+        let mut reference = SuperTrend::new(3, 2.0);
+
+        for i in 0..10 {
+            let input = bar(100.0 + i as f64, i);
+            pipeline.update_all(&input, Timeframe::M1).unwrap();
+            reference.update(input);
+        }
+
+        let trend = reference.get_trend().unwrap();
+        assert_eq!(pipeline.get_value("ST", Timeframe::M1), Some(trend.value));
+    }
+}
@@ -0,0 +1,114 @@
+use crate::indicators::indicator_trait::{BarData, Indicator};
+
+/// Money-flow volume of one bar: `((c - l) - (h - c)) / (h - l) * volume`.
+///
+/// A flat bar (`high == low`) has no defined close location, so its
+/// multiplier counts as 0 instead of dividing by zero.
+pub(crate) fn money_flow_volume(bar: &BarData) -> f64 {
+    let range = bar.high - bar.low;
+    if range <= 0.0 {
+        return 0.0;
+    }
+    let multiplier = ((bar.close - bar.low) - (bar.high - bar.close)) / range;
+    multiplier * bar.volume
+}
+
+/// Accumulation/Distribution line, the running sum of money-flow volume
+#[derive(Debug, Clone)]
+pub struct AccDist {
+    current_value: Option<f64>,
+}
+
+impl AccDist {
+    pub fn new() -> Self {
+        Self {
+            current_value: None,
+        }
+    }
+}
+
+impl Default for AccDist {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Indicator for AccDist {
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        "A/D"
+    }
+
+    fn warm_up_period(&self) -> usize {
+        1
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        let line = self.current_value.unwrap_or(0.0) + money_flow_volume(&input);
+        self.current_value = Some(line);
+        Some(line)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current_value
+    }
+
+    fn reset(&mut self) {
+        self.current_value = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(high: f64, low: f64, close: f64, volume: f64, timestamp: i64) -> BarData {
+        BarData {
+            open: close,
+            high,
+            low,
+            close,
+            volume,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_acc_dist_accumulates() {
+        let mut ad = AccDist::new();
+        assert!(ad.current().is_none());
+
+        // Close at the high: full volume added
+        assert_eq!(ad.update(bar(12.0, 10.0, 12.0, 100.0, 1)), Some(100.0));
+        // Close at the low: full volume removed
+        assert_eq!(ad.update(bar(12.0, 10.0, 10.0, 40.0, 2)), Some(60.0));
+        // Close in the upper quarter: half the volume
+        assert_eq!(ad.update(bar(12.0, 10.0, 11.5, 20.0, 3)), Some(70.0));
+    }
+
+    #[test]
+    fn test_flat_bar_adds_nothing() {
+        let mut ad = AccDist::new();
+        ad.update(bar(12.0, 10.0, 12.0, 100.0, 1));
+
+        let value = ad.update(bar(11.0, 11.0, 11.0, 500.0, 2)).unwrap();
+        assert_eq!(value, 100.0);
+        assert!(value.is_finite());
+    }
+
+    #[test]
+    fn test_acc_dist_reset() {
+        let mut ad = AccDist::new();
+        ad.update(bar(12.0, 10.0, 12.0, 100.0, 1));
+
+        ad.reset();
+        assert!(ad.current().is_none());
+        assert_eq!(ad.update(bar(12.0, 10.0, 10.0, 10.0, 2)), Some(-10.0));
+    }
+}
@@ -0,0 +1,176 @@
+use crate::indicators::indicator_trait::{BarData, Indicator};
+use crate::indicators::output::IndicatorOutput;
+
+/// VWAP accumulated from a fixed anchor timestamp (a news release, swing
+/// low, ...) instead of the session open.
+///
+/// Bars stamped before the anchor are ignored; a bar exactly at the anchor
+/// is the first one included. The bands are the volume-weighted standard
+/// deviation of the typical price around the VWAP, scaled by
+/// `band_multiplier` (1.0 unless set with `with_band_multiplier`).
+#[derive(Debug, Clone)]
+pub struct AnchoredVWAP {
+    anchor_timestamp: i64,
+    band_multiplier: f64,
+    cumulative_volume: f64,
+    cumulative_pv: f64,
+    cumulative_pv2: f64,
+    current_output: Option<AnchoredVWAPOutput>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnchoredVWAPOutput {
+    pub vwap: f64,
+    pub upper_band: f64,
+    pub lower_band: f64,
+}
+
+impl AnchoredVWAP {
+    pub fn new(anchor_timestamp: i64) -> Self {
+        Self {
+            anchor_timestamp,
+            band_multiplier: 1.0,
+            cumulative_volume: 0.0,
+            cumulative_pv: 0.0,
+            cumulative_pv2: 0.0,
+            current_output: None,
+        }
+    }
+
+    pub fn with_band_multiplier(mut self, multiplier: f64) -> Self {
+        self.band_multiplier = multiplier;
+        self
+    }
+
+    pub fn anchor(&self) -> i64 {
+        self.anchor_timestamp
+    }
+
+    /// Move the anchor, discarding everything accumulated so far
+    pub fn set_anchor(&mut self, anchor_timestamp: i64) {
+        self.anchor_timestamp = anchor_timestamp;
+        self.reset();
+    }
+
+    pub fn get_bands(&self) -> Option<AnchoredVWAPOutput> {
+        self.current_output
+    }
+}
+
+impl Indicator for AnchoredVWAP {
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        "AnchoredVWAP"
+    }
+
+    fn warm_up_period(&self) -> usize {
+        1
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        if input.timestamp < self.anchor_timestamp {
+            return None;
+        }
+
+        let typical_price = (input.high + input.low + input.close) / 3.0;
+        self.cumulative_pv += typical_price * input.volume;
+        self.cumulative_pv2 += typical_price * typical_price * input.volume;
+        self.cumulative_volume += input.volume;
+
+        if self.cumulative_volume <= 0.0 {
+            return None;
+        }
+
+        let vwap = self.cumulative_pv / self.cumulative_volume;
+        let variance = (self.cumulative_pv2 / self.cumulative_volume - vwap * vwap).max(0.0);
+        let band = self.band_multiplier * variance.sqrt();
+
+        self.current_output = Some(AnchoredVWAPOutput {
+            vwap,
+            upper_band: vwap + band,
+            lower_band: vwap - band,
+        });
+
+        Some(vwap)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current_output.map(|output| output.vwap)
+    }
+
+    fn reset(&mut self) {
+        self.cumulative_volume = 0.0;
+        self.cumulative_pv = 0.0;
+        self.cumulative_pv2 = 0.0;
+        self.current_output = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn structured_output(&self) -> Option<IndicatorOutput> {
+        self.get_bands().map(IndicatorOutput::AnchoredVwap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(price: f64, volume: f64, timestamp: i64) -> BarData {
+        BarData {
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_ignores_bars_before_anchor() {
+        let mut avwap = AnchoredVWAP::new(1_000);
+
+        assert_eq!(avwap.update(bar(50.0, 1000.0, 999)), None);
+        assert_eq!(avwap.current(), None);
+        assert_eq!(avwap.get_bands(), None);
+
+        // Bar exactly at the anchor is included
+        assert_eq!(avwap.update(bar(100.0, 1000.0, 1_000)), Some(100.0));
+        let value = avwap.update(bar(110.0, 3000.0, 2_000)).unwrap();
+        assert!((value - 107.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_standard_deviation_bands() {
+        let mut avwap = AnchoredVWAP::new(0).with_band_multiplier(2.0);
+        avwap.update(bar(100.0, 1000.0, 0));
+        avwap.update(bar(110.0, 1000.0, 1));
+
+        // Equal weights: mean 105, standard deviation 5
+        let bands = avwap.get_bands().unwrap();
+        assert!((bands.vwap - 105.0).abs() < 1e-9);
+        assert!((bands.upper_band - 115.0).abs() < 1e-9);
+        assert!((bands.lower_band - 95.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_set_anchor_starts_fresh() {
+        let mut avwap = AnchoredVWAP::new(0);
+        avwap.update(bar(100.0, 1000.0, 0));
+        avwap.update(bar(110.0, 1000.0, 10));
+
+        avwap.set_anchor(20);
+        assert_eq!(avwap.anchor(), 20);
+        assert_eq!(avwap.current(), None);
+
+        assert_eq!(avwap.update(bar(120.0, 1000.0, 15)), None);
+        assert_eq!(avwap.update(bar(130.0, 1000.0, 20)), Some(130.0));
+        let bands = avwap.get_bands().unwrap();
+        assert!((bands.upper_band - bands.lower_band).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,127 @@
+use super::acc_dist::money_flow_volume;
+use crate::indicators::indicator_trait::{BarData, Indicator};
+use std::collections::VecDeque;
+
+/// Chaikin Money Flow: money-flow volume over the last `period` bars
+/// divided by the volume over the same bars, between -1 and 1
+#[derive(Debug, Clone)]
+pub struct CMF {
+    period: usize,
+    /// (money-flow volume, volume) per bar
+    flows: VecDeque<(f64, f64)>,
+    current_value: Option<f64>,
+}
+
+impl CMF {
+    /// Standard period is 20.
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            flows: VecDeque::with_capacity(period),
+            current_value: None,
+        }
+    }
+}
+
+impl Indicator for CMF {
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        "CMF"
+    }
+
+    fn warm_up_period(&self) -> usize {
+        self.period
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        self.flows
+            .push_back((money_flow_volume(&input), input.volume));
+        if self.flows.len() > self.period {
+            self.flows.pop_front();
+        }
+
+        if self.flows.len() < self.period {
+            return None;
+        }
+
+        let flow: f64 = self.flows.iter().map(|f| f.0).sum();
+        let volume: f64 = self.flows.iter().map(|f| f.1).sum();
+
+        // No volume traded means no money flow either way
+        let cmf = if volume > 0.0 { flow / volume } else { 0.0 };
+
+        self.current_value = Some(cmf);
+        Some(cmf)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current_value
+    }
+
+    fn reset(&mut self) {
+        self.flows.clear();
+        self.current_value = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(high: f64, low: f64, close: f64, volume: f64, timestamp: i64) -> BarData {
+        BarData {
+            open: close,
+            high,
+            low,
+            close,
+            volume,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_cmf_calculation() {
+        let mut cmf = CMF::new(3);
+        assert!(cmf.update(bar(12.0, 10.0, 12.0, 100.0, 1)).is_none()); // +100
+        assert!(cmf.update(bar(12.0, 10.0, 10.0, 100.0, 2)).is_none()); // -100
+
+        // +100 - 100 + 100 over 300
+        let value = cmf.update(bar(12.0, 10.0, 12.0, 100.0, 3)).unwrap();
+        assert!((value - 1.0 / 3.0).abs() < 1e-12);
+
+        // First bar leaves the window: -100 + 100 + 50 over 300
+        let value = cmf.update(bar(12.0, 10.0, 11.5, 100.0, 4)).unwrap();
+        assert!((value - 50.0 / 300.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_cmf_flat_bars() {
+        let mut cmf = CMF::new(2);
+        cmf.update(bar(11.0, 11.0, 11.0, 100.0, 1));
+        assert_eq!(cmf.update(bar(11.0, 11.0, 11.0, 100.0, 2)), Some(0.0));
+
+        // Zero volume too
+        let mut cmf = CMF::new(1);
+        assert_eq!(cmf.update(bar(12.0, 10.0, 12.0, 0.0, 1)), Some(0.0));
+    }
+
+    #[test]
+    fn test_cmf_reset() {
+        let mut cmf = CMF::new(2);
+        for i in 0..4 {
+            cmf.update(bar(12.0, 10.0, 12.0, 100.0, i));
+        }
+        assert_eq!(cmf.current(), Some(1.0));
+
+        cmf.reset();
+        assert!(cmf.current().is_none());
+        assert!(cmf.update(bar(12.0, 10.0, 12.0, 100.0, 5)).is_none());
+    }
+}
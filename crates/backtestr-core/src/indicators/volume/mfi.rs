@@ -0,0 +1,178 @@
+use crate::indicators::indicator_trait::{BarData, Indicator};
+use std::collections::VecDeque;
+
+/// Money Flow Index, a volume-weighted RSI on the typical price
+#[derive(Debug, Clone)]
+pub struct MFI {
+    period: usize,
+    flows: VecDeque<(f64, f64)>,
+    previous_typical: Option<f64>,
+    current_value: Option<f64>,
+}
+
+impl MFI {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            flows: VecDeque::with_capacity(period),
+            previous_typical: None,
+            current_value: None,
+        }
+    }
+}
+
+impl Indicator for MFI {
+    type Input = BarData;
+    type Output = f64;
+
+    fn name(&self) -> &str {
+        "MFI"
+    }
+
+    fn warm_up_period(&self) -> usize {
+        self.period + 1
+    }
+
+    fn update(&mut self, input: BarData) -> Option<f64> {
+        let typical = (input.high + input.low + input.close) / 3.0;
+        let raw_flow = typical * input.volume;
+
+        // The first bar only seeds the comparison price
+        let prev_typical = self.previous_typical.replace(typical)?;
+
+        // Unchanged typical price contributes to neither side
+        let flow = if typical > prev_typical {
+            (raw_flow, 0.0)
+        } else if typical < prev_typical {
+            (0.0, raw_flow)
+        } else {
+            (0.0, 0.0)
+        };
+
+        self.flows.push_back(flow);
+        if self.flows.len() > self.period {
+            self.flows.pop_front();
+        }
+
+        if self.flows.len() < self.period {
+            return None;
+        }
+
+        let positive: f64 = self.flows.iter().map(|f| f.0).sum();
+        let negative: f64 = self.flows.iter().map(|f| f.1).sum();
+
+        // A flat window has no flow either way: neutral. Otherwise no
+        // negative flow saturates at 100
+        let mfi = if positive == 0.0 && negative == 0.0 {
+            50.0
+        } else if negative == 0.0 {
+            100.0
+        } else {
+            let ratio = positive / negative;
+            100.0 - (100.0 / (1.0 + ratio))
+        };
+
+        self.current_value = Some(mfi);
+        Some(mfi)
+    }
+
+    fn current(&self) -> Option<f64> {
+        self.current_value
+    }
+
+    fn reset(&mut self) {
+        self.flows.clear();
+        self.previous_typical = None;
+        self.current_value = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(high: f64, low: f64, close: f64, volume: f64, timestamp: i64) -> BarData {
+        BarData {
+            open: close,
+            high,
+            low,
+            close,
+            volume,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_mfi_calculation() {
+        let mut mfi = MFI::new(3);
+        let bars = [
+            bar(11.0, 9.0, 10.0, 100.0, 1),  // typical 10
+            bar(12.0, 10.0, 11.0, 200.0, 2), // typical 11, +2200
+            bar(11.0, 9.0, 10.0, 100.0, 3),  // typical 10, -1000
+            bar(13.0, 11.0, 12.0, 300.0, 4), // typical 12, +3600
+            bar(12.0, 10.0, 11.0, 100.0, 5), // typical 11, -1100
+        ];
+
+        assert_eq!(mfi.warm_up_period(), 4);
+        for b in &bars[..3] {
+            assert_eq!(mfi.update(*b), None);
+        }
+
+        let value = mfi.update(bars[3]).unwrap();
+        let expected = 100.0 - 100.0 / (1.0 + 5800.0 / 1000.0);
+        assert!((value - expected).abs() < 1e-10);
+
+        // Window slides: +2200 drops out
+        let value = mfi.update(bars[4]).unwrap();
+        let expected = 100.0 - 100.0 / (1.0 + 3600.0 / 2100.0);
+        assert!((value - expected).abs() < 1e-10);
+        assert_eq!(mfi.current(), Some(value));
+    }
+
+    #[test]
+    fn test_mfi_saturates_without_negative_flow() {
+        let mut mfi = MFI::new(3);
+        let mut last = None;
+        for i in 0..5 {
+            let price = 100.0 + i as f64;
+            last = mfi.update(bar(price + 1.0, price - 1.0, price, 1000.0, i));
+        }
+        assert_eq!(last, Some(100.0));
+    }
+
+    #[test]
+    fn test_flat_window_is_neutral() {
+        let mut mfi = MFI::new(3);
+        let mut last = None;
+        for i in 0..5 {
+            last = mfi.update(bar(101.0, 99.0, 100.0, 1000.0, i));
+        }
+        assert_eq!(last, Some(50.0));
+
+        // So is a window without volume
+        let mut mfi = MFI::new(3);
+        for i in 0..5 {
+            let price = 100.0 + i as f64;
+            last = mfi.update(bar(price + 1.0, price - 1.0, price, 0.0, i));
+        }
+        assert_eq!(last, Some(50.0));
+    }
+
+    #[test]
+    fn test_mfi_reset() {
+        let mut mfi = MFI::new(2);
+        for i in 0..5 {
+            let price = 100.0 + (i % 2) as f64;
+            mfi.update(bar(price + 1.0, price - 1.0, price, 1000.0, i));
+        }
+        assert!(mfi.is_ready());
+
+        mfi.reset();
+        assert!(!mfi.is_ready());
+        assert_eq!(mfi.update(bar(101.0, 99.0, 100.0, 1000.0, 10)), None);
+    }
+}
@@ -1,7 +1,15 @@
+pub mod acc_dist;
+pub mod anchored_vwap;
+pub mod cmf;
+pub mod mfi;
 pub mod obv;
 pub mod volume_sma;
 pub mod vwap;
 
+pub use acc_dist::AccDist;
+pub use anchored_vwap::{AnchoredVWAP, AnchoredVWAPOutput};
+pub use cmf::CMF;
+pub use mfi::MFI;
 pub use obv::OBV;
 pub use volume_sma::VolumeSMA;
 pub use vwap::VWAP;
@@ -1,9 +1,14 @@
 use crate::indicators::indicator_trait::{BarData, Indicator};
 
-#[derive(Debug)]
+/// On-Balance Volume.
+///
+/// Produces no value until a bar with positive volume arrives, so a feed
+/// without volume reads as unavailable rather than a flat zero line.
+#[derive(Debug, Clone)]
 pub struct OBV {
     current_obv: f64,
     previous_close: Option<f64>,
+    has_volume: bool,
 }
 
 impl OBV {
@@ -11,8 +16,14 @@ impl OBV {
         Self {
             current_obv: 0.0,
             previous_close: None,
+            has_volume: false,
         }
     }
+
+    /// Whether any bar so far carried volume
+    pub fn has_volume(&self) -> bool {
+        self.has_volume
+    }
 }
 
 impl Default for OBV {
@@ -34,6 +45,8 @@ impl Indicator for OBV {
     }
 
     fn update(&mut self, input: BarData) -> Option<f64> {
+        self.has_volume |= input.volume > 0.0;
+
         if let Some(prev_close) = self.previous_close {
             if input.close > prev_close {
                 self.current_obv += input.volume;
@@ -46,11 +59,11 @@ impl Indicator for OBV {
         }
 
         self.previous_close = Some(input.close);
-        Some(self.current_obv)
+        self.current()
     }
 
     fn current(&self) -> Option<f64> {
-        if self.previous_close.is_some() {
+        if self.previous_close.is_some() && self.has_volume {
             Some(self.current_obv)
         } else {
             None
@@ -60,6 +73,15 @@ impl Indicator for OBV {
     fn reset(&mut self) {
         self.current_obv = 0.0;
         self.previous_close = None;
+        self.has_volume = false;
+    }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn volume_unavailable(&self) -> bool {
+        self.previous_close.is_some() && !self.has_volume
     }
 }
 
@@ -118,4 +140,31 @@ mod tests {
         let result4 = obv.update(bars[3].clone());
         assert_eq!(result4, Some(2200.0)); // Price up, add volume
     }
+
+    #[test]
+    fn test_obv_waits_for_volume() {
+        let bar = |close: f64, volume: f64, timestamp: i64| BarData {
+            open: close,
+            high: close + 1.0,
+            low: close - 1.0,
+            close,
+            volume,
+            timestamp,
+        };
+        let mut obv = OBV::new();
+        assert!(!obv.volume_unavailable());
+
+        assert_eq!(obv.update(bar(100.0, 0.0, 1)), None);
+        assert_eq!(obv.update(bar(101.0, 0.0, 2)), None);
+        assert!(obv.volume_unavailable());
+        assert!(!obv.has_volume());
+
+        // Direction still tracks the closes seen without volume
+        assert_eq!(obv.update(bar(102.0, 500.0, 3)), Some(500.0));
+        assert_eq!(obv.update(bar(101.0, 200.0, 4)), Some(300.0));
+        assert!(!obv.volume_unavailable());
+
+        obv.reset();
+        assert!(!obv.has_volume());
+    }
 }
@@ -1,7 +1,7 @@
 use crate::indicators::indicator_trait::{BarData, Indicator};
 use std::collections::VecDeque;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct VolumeSMA {
     period: usize,
     volumes: VecDeque<f64>,
@@ -61,6 +61,10 @@ impl Indicator for VolumeSMA {
         self.sum = 0.0;
         self.current_value = None;
     }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
 }
 
 #[cfg(test)]
@@ -1,12 +1,27 @@
 use crate::indicators::indicator_trait::{BarData, Indicator};
 
-#[derive(Debug)]
+const MS_PER_DAY: i64 = 86_400_000;
+
+/// Volume-weighted average of the typical price.
+///
+/// With `reset_on_session` the accumulation restarts whenever
+/// `notify_session_boundary` is called (the pipeline forwards this from
+/// `IndicatorPipeline::notify_session_boundary`, which `BarAggregator`
+/// and `MTFStateManager` call at each daily session close). Until the first
+/// such call it falls back to restarting at each UTC day, so a standalone
+/// VWAP still resets daily. Without `reset_on_session` it runs
+/// indefinitely. No value is produced until the accumulation has seen
+/// volume.
+#[derive(Debug, Clone)]
 pub struct VWAP {
     cumulative_volume: f64,
     cumulative_pv: f64,
     current_value: Option<f64>,
     session_start: Option<i64>,
     reset_on_session: bool,
+    /// Set by the first `notify_session_boundary`; from then on sessions
+    /// end only when notified instead of at UTC midnight
+    boundaries_notified: bool,
 }
 
 impl VWAP {
@@ -17,21 +32,36 @@ impl VWAP {
             current_value: None,
             session_start: None,
             reset_on_session,
+            boundaries_notified: false,
         }
     }
 
-    fn is_new_session(&self, timestamp: i64) -> bool {
-        if !self.reset_on_session {
-            return false;
+    /// Start a new session: the next bar begins a clean accumulation
+    pub fn notify_session_boundary(&mut self) {
+        if self.reset_on_session {
+            self.boundaries_notified = true;
+            self.reset();
         }
+    }
 
-        if let Some(start) = self.session_start {
-            // Simple day boundary check (assumes timestamps are in seconds)
-            let day_seconds = 86400;
-            timestamp / day_seconds != start / day_seconds
-        } else {
-            true
+    /// Whether `timestamp` starts a new UTC day, when no session boundaries
+    /// are supplied
+    fn is_new_day(&self, timestamp: i64) -> bool {
+        if !self.reset_on_session || self.boundaries_notified {
+            return false;
         }
+        self.session_start
+            .is_some_and(|start| timestamp.div_euclid(MS_PER_DAY) != start.div_euclid(MS_PER_DAY))
+    }
+
+    /// Whether the current accumulation has seen any volume
+    pub fn has_volume(&self) -> bool {
+        self.cumulative_volume > 0.0
+    }
+
+    /// Timestamp of the first bar in the current accumulation
+    pub fn session_start(&self) -> Option<i64> {
+        self.session_start
     }
 }
 
@@ -54,11 +84,10 @@ impl Indicator for VWAP {
     }
 
     fn update(&mut self, input: BarData) -> Option<f64> {
-        if self.is_new_session(input.timestamp) {
-            self.cumulative_volume = 0.0;
-            self.cumulative_pv = 0.0;
-            self.session_start = Some(input.timestamp);
+        if self.is_new_day(input.timestamp) {
+            self.reset();
         }
+        self.session_start.get_or_insert(input.timestamp);
 
         let typical_price = (input.high + input.low + input.close) / 3.0;
         self.cumulative_pv += typical_price * input.volume;
@@ -83,6 +112,18 @@ impl Indicator for VWAP {
         self.current_value = None;
         self.session_start = None;
     }
+
+    fn clone_box(&self) -> Box<dyn Indicator<Input = BarData, Output = f64>> {
+        Box::new(self.clone())
+    }
+
+    fn on_session_boundary(&mut self) {
+        self.notify_session_boundary();
+    }
+
+    fn volume_unavailable(&self) -> bool {
+        self.session_start.is_some() && !self.has_volume()
+    }
 }
 
 #[cfg(test)]
@@ -135,4 +176,87 @@ mod tests {
         let vwap_value = result3.unwrap();
         assert!(vwap_value > 100.0 && vwap_value < 103.0);
     }
+
+    fn bar(price: f64, volume: f64, timestamp: i64) -> BarData {
+        BarData {
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_session_reset_starts_clean_accumulation() {
+        let mut vwap = VWAP::new(true);
+        vwap.update(bar(100.0, 1000.0, 1_000));
+        vwap.update(bar(110.0, 3000.0, 2_000));
+        assert!((vwap.current().unwrap() - 107.5).abs() < 1e-9);
+
+        vwap.notify_session_boundary();
+        assert_eq!(vwap.current(), None);
+
+        // First bar after the reset is the whole accumulation
+        assert_eq!(vwap.update(bar(90.0, 500.0, 3_000)), Some(90.0));
+        assert_eq!(vwap.session_start(), Some(3_000));
+        let value = vwap.update(bar(100.0, 500.0, 4_000)).unwrap();
+        assert!((value - 95.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_resets_daily_without_session_boundaries() {
+        let day = MS_PER_DAY;
+        let mut vwap = VWAP::default();
+        vwap.update(bar(100.0, 1000.0, day + 1_000));
+        vwap.update(bar(110.0, 1000.0, 2 * day - 1_000));
+        assert!((vwap.current().unwrap() - 105.0).abs() < 1e-9);
+
+        // The first bar of the next UTC day starts over
+        assert_eq!(vwap.update(bar(90.0, 500.0, 2 * day)), Some(90.0));
+        assert_eq!(vwap.session_start(), Some(2 * day));
+
+        // Once boundaries are supplied, midnight no longer resets
+        vwap.notify_session_boundary();
+        vwap.update(bar(100.0, 1000.0, 2 * day + 1_000));
+        let value = vwap.update(bar(110.0, 1000.0, 3 * day)).unwrap();
+        assert!((value - 105.0).abs() < 1e-9);
+
+        // Nor without reset mode
+        let mut cumulative = VWAP::new(false);
+        cumulative.update(bar(100.0, 1000.0, day));
+        let value = cumulative.update(bar(110.0, 1000.0, 2 * day)).unwrap();
+        assert!((value - 105.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_session_boundary_ignored_without_reset_mode() {
+        let mut vwap = VWAP::new(false);
+        vwap.update(bar(100.0, 1000.0, 1_000));
+        vwap.notify_session_boundary();
+
+        let value = vwap.update(bar(110.0, 1000.0, 2_000)).unwrap();
+        assert!((value - 105.0).abs() < 1e-9);
+        assert_eq!(vwap.session_start(), Some(1_000));
+    }
+
+    #[test]
+    fn test_no_value_without_volume() {
+        let mut vwap = VWAP::new(true);
+        assert!(!vwap.volume_unavailable());
+
+        assert_eq!(vwap.update(bar(100.0, 0.0, 1_000)), None);
+        assert!(vwap.volume_unavailable());
+        assert!(!vwap.has_volume());
+
+        assert_eq!(vwap.update(bar(110.0, 100.0, 2_000)), Some(110.0));
+        assert!(!vwap.volume_unavailable());
+
+        // A new session has to see volume again
+        vwap.notify_session_boundary();
+        assert!(!vwap.volume_unavailable());
+        vwap.update(bar(120.0, 0.0, 3_000));
+        assert!(vwap.volume_unavailable());
+    }
 }
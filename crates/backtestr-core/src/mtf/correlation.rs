@@ -0,0 +1,275 @@
+use backtestr_data::{Bar, Timeframe};
+use std::collections::{HashMap, VecDeque};
+
+pub const DEFAULT_CORRELATION_WINDOW: usize = 100;
+
+/// Fewer aligned returns than this give no correlation; two points are
+/// always perfectly correlated
+const MIN_ALIGNED_RETURNS: usize = 3;
+
+/// Pairwise correlations for one timeframe, symbols sorted by name
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrelationMatrix {
+    pub timeframe: Timeframe,
+    pub symbols: Vec<String>,
+    /// `values[i][j]` correlates `symbols[i]` with `symbols[j]`
+    pub values: Vec<Vec<Option<f64>>>,
+}
+
+impl CorrelationMatrix {
+    pub fn get(&self, sym_a: &str, sym_b: &str) -> Option<f64> {
+        let i = self.symbols.iter().position(|s| s == sym_a)?;
+        let j = self.symbols.iter().position(|s| s == sym_b)?;
+        self.values[i][j]
+    }
+}
+
+/// Rolling Pearson correlation of close-to-close returns between symbols.
+///
+/// Keeps the last `window + 1` closes per symbol and timeframe. Series are
+/// aligned by bar start time: a pair is correlated over the timestamps both
+/// symbols have a bar for, with each return taken between consecutive shared
+/// timestamps, so a gap in one symbol never pairs returns from different
+/// periods.
+#[derive(Debug, Clone)]
+pub struct CorrelationTracker {
+    window: usize,
+    closes: HashMap<(String, Timeframe), VecDeque<(i64, f64)>>,
+}
+
+impl Default for CorrelationTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_CORRELATION_WINDOW)
+    }
+}
+
+impl CorrelationTracker {
+    /// `window` is the number of returns per symbol the correlation covers
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(MIN_ALIGNED_RETURNS),
+            closes: HashMap::new(),
+        }
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Record a completed bar. A bar with the same start time as the latest
+    /// one for its symbol replaces it.
+    pub fn on_bar(&mut self, bar: &Bar) {
+        let closes = self
+            .closes
+            .entry((bar.symbol.clone(), bar.timeframe))
+            .or_default();
+
+        match closes.back_mut() {
+            Some(last) if last.0 == bar.timestamp_start => last.1 = bar.close,
+            Some(last) if last.0 > bar.timestamp_start => return,
+            _ => closes.push_back((bar.timestamp_start, bar.close)),
+        }
+        if closes.len() > self.window + 1 {
+            closes.pop_front();
+        }
+    }
+
+    /// Pearson correlation of the two symbols' returns over their
+    /// overlapping bars. `None` until enough bars overlap or when either
+    /// series is flat.
+    pub fn correlation(&self, sym_a: &str, sym_b: &str, timeframe: Timeframe) -> Option<f64> {
+        let a = self.closes.get(&(sym_a.to_string(), timeframe))?;
+        let b = self.closes.get(&(sym_b.to_string(), timeframe))?;
+
+        // Both series are in time order, so merge them on timestamp
+        let mut aligned = Vec::with_capacity(a.len().min(b.len()));
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() && j < b.len() {
+            match a[i].0.cmp(&b[j].0) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    aligned.push((a[i].1, b[j].1));
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+
+        let returns: Vec<(f64, f64)> = aligned
+            .windows(2)
+            .map(|pair| (pair[1].0 / pair[0].0 - 1.0, pair[1].1 / pair[0].1 - 1.0))
+            .collect();
+        if returns.len() < MIN_ALIGNED_RETURNS {
+            return None;
+        }
+
+        pearson(&returns)
+    }
+
+    /// Correlations between every pair of symbols seen on `timeframe`
+    pub fn matrix(&self, timeframe: Timeframe) -> CorrelationMatrix {
+        let mut symbols: Vec<String> = self
+            .closes
+            .keys()
+            .filter(|(_, tf)| *tf == timeframe)
+            .map(|(symbol, _)| symbol.clone())
+            .collect();
+        symbols.sort();
+
+        let values = symbols
+            .iter()
+            .map(|a| {
+                symbols
+                    .iter()
+                    .map(|b| self.correlation(a, b, timeframe))
+                    .collect()
+            })
+            .collect();
+
+        CorrelationMatrix {
+            timeframe,
+            symbols,
+            values,
+        }
+    }
+
+    pub fn remove_symbol(&mut self, symbol: &str) {
+        self.closes.retain(|(s, _), _| s != symbol);
+    }
+
+    pub fn clear(&mut self) {
+        self.closes.clear();
+    }
+}
+
+fn pearson(pairs: &[(f64, f64)]) -> Option<f64> {
+    let n = pairs.len() as f64;
+    let mean_a = pairs.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_b = pairs.iter().map(|p| p.1).sum::<f64>() / n;
+
+    let (mut covariance, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for &(a, b) in pairs {
+        let (da, db) = (a - mean_a, b - mean_b);
+        covariance += da * db;
+        var_a += da * da;
+        var_b += db * db;
+    }
+
+    if var_a == 0.0 || var_b == 0.0 {
+        return None;
+    }
+    Some((covariance / (var_a * var_b).sqrt()).clamp(-1.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: i64 = 60_000;
+
+    fn bar(symbol: &str, minute: i64, close: f64) -> Bar {
+        let start = minute * MINUTE;
+        Bar::new(
+            symbol.to_string(),
+            Timeframe::M1,
+            start,
+            start + MINUTE,
+            close,
+            close,
+            close,
+            close,
+        )
+    }
+
+    fn wave(minute: i64) -> f64 {
+        100.0 + (minute as f64 * 0.7).sin() + minute as f64 * 0.01
+    }
+
+    #[test]
+    fn test_perfect_positive_and_negative() {
+        let mut tracker = CorrelationTracker::new(20);
+        for m in 0..30 {
+            tracker.on_bar(&bar("EURUSD", m, wave(m)));
+            tracker.on_bar(&bar("GBPUSD", m, 2.0 * wave(m)));
+            // Returns mirror EURUSD's
+            tracker.on_bar(&bar("USDCHF", m, 200.0 - wave(m)));
+        }
+
+        let same = tracker
+            .correlation("EURUSD", "GBPUSD", Timeframe::M1)
+            .unwrap();
+        assert!((same - 1.0).abs() < 1e-9);
+        let opposite = tracker
+            .correlation("EURUSD", "USDCHF", Timeframe::M1)
+            .unwrap();
+        assert!(opposite < -0.99);
+
+        assert!(tracker
+            .correlation("EURUSD", "GBPUSD", Timeframe::H1)
+            .is_none());
+        assert!(tracker
+            .correlation("EURUSD", "MISSING", Timeframe::M1)
+            .is_none());
+    }
+
+    #[test]
+    fn test_gaps_align_by_timestamp() {
+        let mut tracker = CorrelationTracker::new(50);
+        for m in 0..40 {
+            tracker.on_bar(&bar("EURUSD", m, wave(m)));
+            // GBPUSD skips every third bar; the remaining bars track EURUSD
+            if m % 3 != 0 {
+                tracker.on_bar(&bar("GBPUSD", m, 3.0 * wave(m)));
+            }
+        }
+
+        // Index-based pairing would mix periods and break the correlation
+        let correlation = tracker
+            .correlation("EURUSD", "GBPUSD", Timeframe::M1)
+            .unwrap();
+        assert!((correlation - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_window_and_insufficient_overlap() {
+        let mut tracker = CorrelationTracker::new(5);
+        for m in 0..3 {
+            tracker.on_bar(&bar("EURUSD", m, wave(m)));
+            tracker.on_bar(&bar("GBPUSD", m, wave(m)));
+        }
+        // Only two returns so far
+        assert!(tracker
+            .correlation("EURUSD", "GBPUSD", Timeframe::M1)
+            .is_none());
+
+        for m in 3..20 {
+            tracker.on_bar(&bar("EURUSD", m, wave(m)));
+        }
+        // GBPUSD's bars have rolled out of EURUSD's window
+        assert!(tracker
+            .correlation("EURUSD", "GBPUSD", Timeframe::M1)
+            .is_none());
+    }
+
+    #[test]
+    fn test_matrix() {
+        let mut tracker = CorrelationTracker::new(20);
+        for m in 0..25 {
+            tracker.on_bar(&bar("GBPUSD", m, wave(m)));
+            tracker.on_bar(&bar("EURUSD", m, wave(m) + 1.0));
+        }
+
+        let matrix = tracker.matrix(Timeframe::M1);
+        assert_eq!(matrix.symbols, vec!["EURUSD", "GBPUSD"]);
+        assert!((matrix.get("EURUSD", "EURUSD").unwrap() - 1.0).abs() < 1e-9);
+        assert_eq!(
+            matrix.get("EURUSD", "GBPUSD"),
+            matrix.get("GBPUSD", "EURUSD")
+        );
+        assert!(matrix.get("EURUSD", "GBPUSD").unwrap() > 0.99);
+
+        tracker.remove_symbol("GBPUSD");
+        assert_eq!(tracker.matrix(Timeframe::M1).symbols, vec!["EURUSD"]);
+    }
+}
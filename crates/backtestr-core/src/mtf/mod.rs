@@ -1,11 +1,21 @@
+mod correlation;
 mod partial_bar;
+mod snapshot_diff;
+mod spike_filter;
+mod spread_stats;
 mod state_manager;
 mod state_query;
+mod tick_buffer;
 mod tick_processor;
 mod timeframe_state;
 
+pub use correlation::{CorrelationMatrix, CorrelationTracker, DEFAULT_CORRELATION_WINDOW};
 pub use partial_bar::PartialBar;
+pub use snapshot_diff::{DiffValue, FieldChange, SnapshotDiff, DEFAULT_DIFF_EPSILON};
+pub use spike_filter::{SpikeFilter, SpikeFilterConfig};
+pub use spread_stats::{SpreadStats, DEFAULT_SPREAD_WINDOW};
 pub use state_manager::{MTFConfig, MTFStateManager, SymbolMTFState};
 pub use state_query::{MTFSnapshot, StateQuery};
-pub use tick_processor::TickProcessor;
+pub use tick_buffer::{TickBuffer, TickBufferPolicy, DEFAULT_BLOCK_TIMEOUT};
+pub use tick_processor::{ProcessError, TickProcessor};
 pub use timeframe_state::TimeframeState;
@@ -0,0 +1,185 @@
+use crate::mtf::{MTFSnapshot, PartialBar};
+use backtestr_data::{Bar, Timeframe};
+use std::fmt;
+
+/// Prices closer than this are treated as unchanged by `MTFSnapshot::diff`
+pub const DEFAULT_DIFF_EPSILON: f64 = 1e-9;
+
+/// A compared field's value on one side of a diff
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiffValue {
+    Float(f64),
+    Int(i64),
+    /// The field's parent (tick, partial bar, timeframe) is absent
+    Missing,
+}
+
+impl DiffValue {
+    fn matches(self, other: DiffValue, epsilon: f64) -> bool {
+        match (self, other) {
+            (DiffValue::Float(a), DiffValue::Float(b)) => (a - b).abs() <= epsilon,
+            _ => self == other,
+        }
+    }
+}
+
+impl fmt::Display for DiffValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffValue::Float(value) => write!(f, "{}", value),
+            DiffValue::Int(value) => write!(f, "{}", value),
+            DiffValue::Missing => write!(f, "-"),
+        }
+    }
+}
+
+/// One field that differs between two snapshots
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    /// `None` for snapshot-wide fields such as the timestamp
+    pub timeframe: Option<Timeframe>,
+    pub field: &'static str,
+    pub old: DiffValue,
+    pub new: DiffValue,
+}
+
+/// Field-by-field differences between two `MTFSnapshot`s.
+///
+/// Compares the snapshot timestamp, the current tick, and per timeframe the
+/// partial bar, the completed bar count and the latest completed bar. The
+/// query timing is ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotDiff {
+    pub symbol: String,
+    pub changes: Vec<FieldChange>,
+}
+
+impl SnapshotDiff {
+    pub(crate) fn between(old: &MTFSnapshot, new: &MTFSnapshot, epsilon: f64) -> Self {
+        let mut changes = Vec::new();
+        let mut push =
+            |timeframe, fields_old: Vec<(&'static str, DiffValue)>, fields_new: Vec<_>| {
+                for ((field, old), (_, new)) in fields_old.into_iter().zip(fields_new) {
+                    if !old.matches(new, epsilon) {
+                        changes.push(FieldChange {
+                            timeframe,
+                            field,
+                            old,
+                            new,
+                        });
+                    }
+                }
+            };
+
+        push(None, snapshot_fields(old), snapshot_fields(new));
+
+        let mut timeframes: Vec<Timeframe> = old
+            .partial_bars
+            .keys()
+            .chain(old.completed_bars.keys())
+            .chain(new.partial_bars.keys())
+            .chain(new.completed_bars.keys())
+            .copied()
+            .collect();
+        timeframes.sort_by_key(Timeframe::duration_ms);
+        timeframes.dedup();
+
+        for timeframe in timeframes {
+            push(
+                Some(timeframe),
+                timeframe_fields(old, timeframe),
+                timeframe_fields(new, timeframe),
+            );
+        }
+
+        Self {
+            symbol: new.symbol.clone(),
+            changes,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Changes on one timeframe
+    pub fn for_timeframe(&self, timeframe: Timeframe) -> impl Iterator<Item = &FieldChange> {
+        self.changes
+            .iter()
+            .filter(move |change| change.timeframe == Some(timeframe))
+    }
+}
+
+impl fmt::Display for SnapshotDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.changes.is_empty() {
+            return write!(f, "{}: no changes", self.symbol);
+        }
+
+        write!(f, "{}: {} change(s)", self.symbol, self.changes.len())?;
+        for change in &self.changes {
+            let scope = change.timeframe.as_ref().map_or("*", Timeframe::as_str);
+            write!(
+                f,
+                "\n  {:>4} {:<24} {} -> {}",
+                scope, change.field, change.old, change.new
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn float(value: Option<f64>) -> DiffValue {
+    value.map_or(DiffValue::Missing, DiffValue::Float)
+}
+
+fn int(value: Option<i64>) -> DiffValue {
+    value.map_or(DiffValue::Missing, DiffValue::Int)
+}
+
+fn snapshot_fields(snapshot: &MTFSnapshot) -> Vec<(&'static str, DiffValue)> {
+    let tick = snapshot.current_tick.as_ref();
+    vec![
+        ("timestamp", DiffValue::Int(snapshot.timestamp)),
+        ("tick.bid", float(tick.map(|t| t.bid))),
+        ("tick.ask", float(tick.map(|t| t.ask))),
+    ]
+}
+
+/// Always the same fields in the same order, `Missing` where absent, so
+/// both sides line up
+fn timeframe_fields(
+    snapshot: &MTFSnapshot,
+    timeframe: Timeframe,
+) -> Vec<(&'static str, DiffValue)> {
+    let partial: Option<&PartialBar> = snapshot
+        .partial_bars
+        .get(&timeframe)
+        .and_then(Option::as_ref);
+    let completed = snapshot.completed_bars.get(&timeframe);
+    let last: Option<&Bar> = completed.and_then(|bars| bars.last());
+
+    vec![
+        ("partial.open", float(partial.map(|p| p.open))),
+        ("partial.high", float(partial.map(|p| p.high))),
+        ("partial.low", float(partial.map(|p| p.low))),
+        ("partial.close", float(partial.map(|p| p.close))),
+        ("partial.volume", int(partial.map(|p| p.volume))),
+        (
+            "partial.tick_count",
+            int(partial.map(|p| p.tick_count as i64)),
+        ),
+        (
+            "completed_bars",
+            int(completed.map(|bars| bars.len() as i64)),
+        ),
+        (
+            "last_bar.timestamp_start",
+            int(last.map(|b| b.timestamp_start)),
+        ),
+        ("last_bar.open", float(last.map(|b| b.open))),
+        ("last_bar.high", float(last.map(|b| b.high))),
+        ("last_bar.low", float(last.map(|b| b.low))),
+        ("last_bar.close", float(last.map(|b| b.close))),
+    ]
+}
@@ -0,0 +1,220 @@
+use backtestr_data::Tick;
+use std::collections::HashMap;
+
+/// Configuration for news-spike detection
+#[derive(Debug, Clone)]
+pub struct SpikeFilterConfig {
+    /// Number of tick-to-tick moves averaged into the tick ATR
+    pub atr_period: usize,
+    /// A move larger than `atr_multiple * ATR` counts as a price jump
+    pub atr_multiple: f64,
+    /// A spread wider than this (in price units) counts as widened
+    pub max_spread: f64,
+    /// Keep flagged ticks out of bar formation
+    pub exclude_from_bars: bool,
+    /// How long new fills are blocked after a spike
+    pub cooldown_ms: i64,
+}
+
+impl Default for SpikeFilterConfig {
+    fn default() -> Self {
+        Self {
+            atr_period: 20,
+            atr_multiple: 5.0,
+            max_spread: 0.0005,
+            exclude_from_bars: true,
+            cooldown_ms: 60_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct SymbolSpikeState {
+    last_price: Option<f64>,
+    atr: f64,
+    samples: usize,
+    cooldown_until: Option<i64>,
+}
+
+/// Flags ticks that both jump several tick-ATRs from the prior tick and
+/// carry a widened spread, the typical signature of a news spike.
+///
+/// Flagged ticks do not update the running ATR or the reference price, so
+/// the reverting tick after a spike is measured against the pre-spike level.
+#[derive(Debug, Clone)]
+pub struct SpikeFilter {
+    config: SpikeFilterConfig,
+    symbols: HashMap<String, SymbolSpikeState>,
+    spikes_detected: u64,
+}
+
+impl SpikeFilter {
+    pub fn new(config: SpikeFilterConfig) -> Self {
+        Self {
+            config,
+            symbols: HashMap::new(),
+            spikes_detected: 0,
+        }
+    }
+
+    pub fn config(&self) -> &SpikeFilterConfig {
+        &self.config
+    }
+
+    /// Inspect a tick, returning `true` if it is a spike
+    pub fn check_tick(&mut self, tick: &Tick) -> bool {
+        let price = (tick.bid + tick.ask) / 2.0;
+        let spread = tick.ask - tick.bid;
+        let period = self.config.atr_period.max(1);
+
+        let state = self.symbols.entry(tick.symbol.clone()).or_default();
+
+        let last_price = match state.last_price {
+            Some(last) => last,
+            None => {
+                state.last_price = Some(price);
+                return false;
+            }
+        };
+
+        let jump = (price - last_price).abs();
+        let atr_ready = state.samples >= period && state.atr > 0.0;
+
+        if atr_ready
+            && jump > self.config.atr_multiple * state.atr
+            && spread > self.config.max_spread
+        {
+            state.cooldown_until = Some(tick.timestamp + self.config.cooldown_ms);
+            self.spikes_detected += 1;
+            return true;
+        }
+
+        // Simple average while warming up, Wilder smoothing afterwards
+        if state.samples < period {
+            state.atr = (state.atr * state.samples as f64 + jump) / (state.samples + 1) as f64;
+            state.samples += 1;
+        } else {
+            state.atr = (state.atr * (period - 1) as f64 + jump) / period as f64;
+        }
+        state.last_price = Some(price);
+
+        false
+    }
+
+    /// Whether new fills on `symbol` are blocked at `timestamp`
+    pub fn is_cooling_down(&self, symbol: &str, timestamp: i64) -> bool {
+        self.cooldown_until(symbol)
+            .is_some_and(|until| timestamp < until)
+    }
+
+    pub fn cooldown_until(&self, symbol: &str) -> Option<i64> {
+        self.symbols.get(symbol).and_then(|s| s.cooldown_until)
+    }
+
+    /// Current tick ATR for a symbol, once warmed up
+    pub fn tick_atr(&self, symbol: &str) -> Option<f64> {
+        self.symbols
+            .get(symbol)
+            .filter(|s| s.samples >= self.config.atr_period.max(1))
+            .map(|s| s.atr)
+    }
+
+    pub fn spikes_detected(&self) -> u64 {
+        self.spikes_detected
+    }
+
+    /// Forget the running ATR and cooldown of `symbol`
+    pub fn remove_symbol(&mut self, symbol: &str) {
+        self.symbols.remove(symbol);
+    }
+
+    pub fn reset(&mut self) {
+        self.symbols.clear();
+        self.spikes_detected = 0;
+    }
+}
+
+impl Default for SpikeFilter {
+    fn default() -> Self {
+        Self::new(SpikeFilterConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const T0: i64 = 1704067200000;
+
+    fn quiet_ticks(count: usize) -> Vec<Tick> {
+        (0..count)
+            .map(|i| {
+                let mid = 1.1000 + if i % 2 == 0 { 0.0 } else { 0.0001 };
+                Tick::new_with_millis(
+                    "EURUSD".to_string(),
+                    T0 + i as i64 * 1_000,
+                    mid - 0.00005,
+                    mid + 0.00005,
+                )
+            })
+            .collect()
+    }
+
+    fn filter() -> SpikeFilter {
+        SpikeFilter::new(SpikeFilterConfig {
+            atr_period: 10,
+            atr_multiple: 5.0,
+            max_spread: 0.0005,
+            exclude_from_bars: true,
+            cooldown_ms: 30_000,
+        })
+    }
+
+    #[test]
+    fn test_quiet_market_not_flagged() {
+        let mut filter = filter();
+        for tick in quiet_ticks(50) {
+            assert!(!filter.check_tick(&tick));
+        }
+        assert_eq!(filter.spikes_detected(), 0);
+        assert!((filter.tick_atr("EURUSD").unwrap() - 0.0001).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_spike_requires_jump_and_wide_spread() {
+        let mut filter = filter();
+        for tick in quiet_ticks(20) {
+            filter.check_tick(&tick);
+        }
+
+        // Large jump with a normal spread is a genuine move, not a spike
+        let jump_only = Tick::new_with_millis("EURUSD".to_string(), T0 + 21_000, 1.1019, 1.1020);
+        assert!(!filter.check_tick(&jump_only));
+
+        // Wide spread without a jump is not a spike either
+        let wide_only = Tick::new_with_millis("EURUSD".to_string(), T0 + 22_000, 1.1015, 1.1025);
+        assert!(!filter.check_tick(&wide_only));
+    }
+
+    #[test]
+    fn test_news_spike_flagged_and_cooldown() {
+        let mut filter = filter();
+        for tick in quiet_ticks(20) {
+            filter.check_tick(&tick);
+        }
+
+        let spike_time = T0 + 21_000;
+        let spike = Tick::new_with_millis("EURUSD".to_string(), spike_time, 1.1040, 1.1060);
+        assert!(filter.check_tick(&spike));
+        assert_eq!(filter.spikes_detected(), 1);
+
+        assert!(filter.is_cooling_down("EURUSD", spike_time + 29_999));
+        assert!(!filter.is_cooling_down("EURUSD", spike_time + 30_000));
+        assert!(!filter.is_cooling_down("GBPUSD", spike_time));
+
+        // Reversion back to the pre-spike level is not flagged
+        let revert =
+            Tick::new_with_millis("EURUSD".to_string(), spike_time + 1_000, 1.09995, 1.10005);
+        assert!(!filter.check_tick(&revert));
+    }
+}
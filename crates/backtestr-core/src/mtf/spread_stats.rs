@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+
+pub const DEFAULT_SPREAD_WINDOW: usize = 1000;
+
+/// Width of a percentile bucket: each one spans 2% of spread
+const BUCKET_RATIO: f64 = 1.02;
+/// Bucket for zero and crossed spreads
+const ZERO_BUCKET: i32 = i32::MIN;
+
+/// Spread statistics of one symbol over its last `window_len` ticks
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpreadStats {
+    /// Ask minus bid of the latest tick
+    pub current: f64,
+    pub average: f64,
+    pub min: f64,
+    pub max: f64,
+    /// Approximate, to within about 2%
+    pub median: f64,
+    /// Approximate, to within about 2%
+    pub p95: f64,
+    /// Ticks in the window
+    pub window_len: usize,
+    /// Ticks seen since the symbol started
+    pub tick_count: u64,
+}
+
+/// Rolling spread accumulator with O(1) amortized updates.
+///
+/// The window keeps the raw spreads so evictions can undo their effect on
+/// the running sum, the monotonic min/max deques and a log-spaced
+/// histogram; percentiles read the histogram instead of sorting the window.
+#[derive(Debug, Clone)]
+pub(crate) struct SpreadTracker {
+    window: usize,
+    spreads: VecDeque<f64>,
+    sum: f64,
+    /// Index of the next tick
+    index: u64,
+    /// (index, spread), spreads increasing from the front
+    mins: VecDeque<(u64, f64)>,
+    /// (index, spread), spreads decreasing from the front
+    maxs: VecDeque<(u64, f64)>,
+    histogram: BTreeMap<i32, usize>,
+}
+
+impl SpreadTracker {
+    pub(crate) fn new(window: usize) -> Self {
+        let window = window.max(1);
+        Self {
+            window,
+            spreads: VecDeque::with_capacity(window),
+            sum: 0.0,
+            index: 0,
+            mins: VecDeque::new(),
+            maxs: VecDeque::new(),
+            histogram: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, spread: f64) {
+        if !spread.is_finite() {
+            return;
+        }
+
+        if self.spreads.len() == self.window {
+            if let Some(old) = self.spreads.pop_front() {
+                self.sum -= old;
+                let bucket = bucket_of(old);
+                if let Some(count) = self.histogram.get_mut(&bucket) {
+                    *count -= 1;
+                    if *count == 0 {
+                        self.histogram.remove(&bucket);
+                    }
+                }
+            }
+        }
+
+        let index = self.index;
+        self.index += 1;
+        self.spreads.push_back(spread);
+        self.sum += spread;
+        *self.histogram.entry(bucket_of(spread)).or_default() += 1;
+
+        while self.mins.back().is_some_and(|&(_, s)| s >= spread) {
+            self.mins.pop_back();
+        }
+        self.mins.push_back((index, spread));
+        while self.maxs.back().is_some_and(|&(_, s)| s <= spread) {
+            self.maxs.pop_back();
+        }
+        self.maxs.push_back((index, spread));
+
+        let oldest = self.index - self.spreads.len() as u64;
+        while self.mins.front().is_some_and(|&(i, _)| i < oldest) {
+            self.mins.pop_front();
+        }
+        while self.maxs.front().is_some_and(|&(i, _)| i < oldest) {
+            self.maxs.pop_front();
+        }
+    }
+
+    pub(crate) fn window_len(&self) -> usize {
+        self.spreads.len()
+    }
+
+    pub(crate) fn stats(&self) -> Option<SpreadStats> {
+        let current = *self.spreads.back()?;
+        let len = self.spreads.len();
+        Some(SpreadStats {
+            current,
+            average: self.sum / len as f64,
+            min: self.mins.front()?.1,
+            max: self.maxs.front()?.1,
+            median: self.percentile(0.5),
+            p95: self.percentile(0.95),
+            window_len: len,
+            tick_count: self.index,
+        })
+    }
+
+    /// Representative spread of the bucket holding the `q` quantile
+    fn percentile(&self, q: f64) -> f64 {
+        let rank = ((q * self.spreads.len() as f64).ceil() as usize).max(1);
+        let mut seen = 0;
+        for (&bucket, &count) in &self.histogram {
+            seen += count;
+            if seen >= rank {
+                return bucket_value(bucket);
+            }
+        }
+        0.0
+    }
+}
+
+fn bucket_of(spread: f64) -> i32 {
+    if spread <= 0.0 {
+        ZERO_BUCKET
+    } else {
+        (spread.ln() / BUCKET_RATIO.ln()).floor() as i32
+    }
+}
+
+/// Geometric midpoint of a bucket
+fn bucket_value(bucket: i32) -> f64 {
+    if bucket == ZERO_BUCKET {
+        0.0
+    } else {
+        BUCKET_RATIO.powf(bucket as f64 + 0.5)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_statistics() {
+        let mut tracker = SpreadTracker::new(4);
+        assert!(tracker.stats().is_none());
+
+        for spread in [0.0002, 0.0005, 0.0001, 0.0003, 0.0004] {
+            tracker.push(spread);
+        }
+
+        // 0.0002 has left the window
+        let stats = tracker.stats().unwrap();
+        assert_eq!(stats.current, 0.0004);
+        assert!((stats.average - 0.000325).abs() < 1e-12);
+        assert_eq!(stats.min, 0.0001);
+        assert_eq!(stats.max, 0.0005);
+        assert_eq!(stats.window_len, 4);
+        assert_eq!(stats.tick_count, 5);
+    }
+
+    #[test]
+    fn test_rolling_max_expires() {
+        let mut tracker = SpreadTracker::new(3);
+        tracker.push(0.0010);
+        for _ in 0..3 {
+            tracker.push(0.0001);
+        }
+        let stats = tracker.stats().unwrap();
+        assert_eq!(stats.max, 0.0001);
+        assert_eq!(stats.min, 0.0001);
+    }
+
+    #[test]
+    fn test_percentiles_approximate_sorted_window() {
+        let mut tracker = SpreadTracker::new(500);
+        let spreads: Vec<f64> = (0..2000)
+            .map(|i| 0.0001 + ((i * 37) % 100) as f64 * 0.000005)
+            .collect();
+        for &spread in &spreads {
+            tracker.push(spread);
+        }
+
+        let mut window = spreads[spreads.len() - 500..].to_vec();
+        window.sort_by(f64::total_cmp);
+        let stats = tracker.stats().unwrap();
+        let exact_p95 = window[474];
+        let exact_median = window[249];
+        assert!((stats.p95 / exact_p95 - 1.0).abs() < 0.02);
+        assert!((stats.median / exact_median - 1.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_zero_and_crossed_spreads() {
+        let mut tracker = SpreadTracker::new(10);
+        tracker.push(0.0);
+        tracker.push(-0.0001);
+        tracker.push(f64::NAN);
+        let stats = tracker.stats().unwrap();
+        assert_eq!(stats.window_len, 2);
+        assert_eq!(stats.median, 0.0);
+        assert_eq!(stats.min, -0.0001);
+    }
+}
@@ -1,18 +1,52 @@
-use crate::mtf::{TickProcessor, TimeframeState};
+use crate::aggregation::SessionManager;
+use crate::events::{EventDispatcher, IndicatorUpdateEvent};
+use crate::indicators::{BarData, IndicatorPipeline};
+use crate::mtf::spread_stats::SpreadTracker;
+use crate::mtf::{
+    CorrelationTracker, PartialBar, ProcessError, SpikeFilter, SpikeFilterConfig, SpreadStats,
+    TickBuffer, TickBufferPolicy, TickProcessor, TimeframeState, DEFAULT_SPREAD_WINDOW,
+};
 use backtestr_data::{Bar, Tick, Timeframe};
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, OnceLock, RwLock};
 
 const DEFAULT_BAR_HISTORY: usize = 1000;
 const MAX_SYMBOLS: usize = 10;
 const MAX_MEMORY_MB: usize = 1000;
+const DEFAULT_TICK_BUFFER_SIZE: usize = 100_000;
 
 #[derive(Debug, Clone)]
 pub struct MTFConfig {
+    /// Completed bars kept per timeframe; older bars fall off the ring
     pub bar_history_limit: usize,
     pub max_symbols: usize,
     pub max_memory_mb: usize,
+    /// Timeframes that get a `TimeframeState`; every one in `Timeframe::all`
+    /// by default. Each one
+    /// aggregates straight from ticks, so any subset works, e.g. H1 without
+    /// M15. Queries for a timeframe not listed return `None`.
     pub enabled_timeframes: Vec<Timeframe>,
+    pub tick_buffer_size: usize,
+    pub tick_buffer_policy: TickBufferPolicy,
+    /// Reject ticks older than the last one processed for their symbol.
+    /// Debug aid for verifying temporal ordering; off by default.
+    pub check_tick_order: bool,
+    /// Ticks per symbol covered by `spread_stats`
+    pub spread_window: usize,
+}
+
+impl MTFConfig {
+    /// Reject settings the manager cannot run with
+    pub fn validate(&self) -> Result<(), String> {
+        if self.tick_buffer_size == 0 {
+            return Err("tick_buffer_size must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn is_timeframe_enabled(&self, timeframe: Timeframe) -> bool {
+        self.enabled_timeframes.contains(&timeframe)
+    }
 }
 
 impl Default for MTFConfig {
@@ -22,6 +56,10 @@ impl Default for MTFConfig {
             max_symbols: MAX_SYMBOLS,
             max_memory_mb: MAX_MEMORY_MB,
             enabled_timeframes: Timeframe::all(),
+            tick_buffer_size: DEFAULT_TICK_BUFFER_SIZE,
+            tick_buffer_policy: TickBufferPolicy::default(),
+            check_tick_order: false,
+            spread_window: DEFAULT_SPREAD_WINDOW,
         }
     }
 }
@@ -32,6 +70,15 @@ pub struct MTFStateManager {
     config: MTFConfig,
     #[allow(dead_code)]
     tick_processor: TickProcessor,
+    spike_filter: Option<Arc<RwLock<SpikeFilter>>>,
+    correlation: Option<Arc<RwLock<CorrelationTracker>>>,
+    /// One pipeline per symbol, since indicators hold per-instance state
+    pipelines: Arc<RwLock<HashMap<String, Arc<IndicatorPipeline>>>>,
+    dispatcher: Option<EventDispatcher>,
+    /// Decides when attached pipelines see a daily session boundary
+    session_manager: Arc<SessionManager>,
+    /// Allocated on first use so managers that never buffer stay small
+    tick_buffer: Arc<OnceLock<TickBuffer>>,
 }
 
 impl MTFStateManager {
@@ -40,6 +87,12 @@ impl MTFStateManager {
             states: Arc::new(RwLock::new(HashMap::new())),
             config,
             tick_processor: TickProcessor::new(),
+            spike_filter: None,
+            correlation: None,
+            pipelines: Arc::new(RwLock::new(HashMap::new())),
+            dispatcher: None,
+            session_manager: Arc::new(SessionManager::new()),
+            tick_buffer: Arc::new(OnceLock::new()),
         }
     }
 
@@ -47,7 +100,80 @@ impl MTFStateManager {
         Self::new(MTFConfig::default())
     }
 
+    /// Screen incoming ticks for news spikes
+    pub fn with_spike_filter(mut self, config: SpikeFilterConfig) -> Self {
+        self.spike_filter = Some(Arc::new(RwLock::new(SpikeFilter::new(config))));
+        self
+    }
+
+    /// Shared handle to the spike filter, e.g. to block fills in a `PositionManager`
+    pub fn spike_filter(&self) -> Option<Arc<RwLock<SpikeFilter>>> {
+        self.spike_filter.clone()
+    }
+
+    /// Track rolling return correlations between symbols over `window`
+    /// completed bars per timeframe
+    pub fn with_correlation_window(mut self, window: usize) -> Self {
+        self.correlation = Some(Arc::new(RwLock::new(CorrelationTracker::new(window))));
+        self
+    }
+
+    /// Shared handle to the correlation tracker fed with every completed bar
+    pub fn correlation_tracker(&self) -> Option<Arc<RwLock<CorrelationTracker>>> {
+        self.correlation.clone()
+    }
+
+    /// Publish `on_indicator_update` to `dispatcher`'s handlers whenever an
+    /// attached pipeline is updated
+    pub fn with_event_dispatcher(mut self, dispatcher: EventDispatcher) -> Self {
+        self.dispatcher = Some(dispatcher);
+        self
+    }
+
+    /// Market hours and schedule used to find daily session boundaries for
+    /// attached pipelines; forex hours for every symbol by default
+    pub fn with_session_manager(mut self, session_manager: SessionManager) -> Self {
+        self.session_manager = Arc::new(session_manager);
+        self
+    }
+
+    /// Run `pipeline` on every bar completed for `symbol`, replacing any
+    /// pipeline attached before.
+    ///
+    /// Indicators only ever see completed bars, never the partial bar, so
+    /// their values carry no look-ahead. Each symbol needs its own pipeline
+    /// because indicator state is per instance.
+    pub fn attach_pipeline(&self, symbol: &str, pipeline: IndicatorPipeline) {
+        if let Ok(mut pipelines) = self.pipelines.write() {
+            pipelines.insert(symbol.to_string(), Arc::new(pipeline));
+        }
+    }
+
+    pub fn pipeline(&self, symbol: &str) -> Option<Arc<IndicatorPipeline>> {
+        self.pipelines.read().ok()?.get(symbol).cloned()
+    }
+
+    /// Latest value of `name` in the pipeline attached to `symbol`, as of
+    /// the last completed `timeframe` bar
+    pub fn indicator_value(&self, symbol: &str, timeframe: Timeframe, name: &str) -> Option<f64> {
+        self.pipeline(symbol)?.get_value(name, timeframe)
+    }
+
     pub fn process_tick(&self, tick: &Tick) -> Result<Vec<Bar>, String> {
+        self.try_process_tick(tick).map_err(|e| e.to_string())
+    }
+
+    /// `process_tick` with a typed error, so callers can tell an ordering
+    /// violation from other rejections
+    pub fn try_process_tick(&self, tick: &Tick) -> Result<Vec<Bar>, ProcessError> {
+        // Screen for news spikes before the tick touches any bar
+        if let Some(filter) = &self.spike_filter {
+            let mut filter = filter.write().map_err(|e| format!("Lock error: {}", e))?;
+            if filter.check_tick(tick) && filter.config().exclude_from_bars {
+                return Ok(Vec::new());
+            }
+        }
+
         // Validate symbol count
         {
             let states = self
@@ -55,10 +181,10 @@ impl MTFStateManager {
                 .read()
                 .map_err(|e| format!("Lock error: {}", e))?;
             if !states.contains_key(&tick.symbol) && states.len() >= self.config.max_symbols {
-                return Err(format!(
+                return Err(ProcessError::Rejected(format!(
                     "Maximum symbols ({}) reached. Cannot add {}",
                     self.config.max_symbols, tick.symbol
-                ));
+                )));
             }
         }
 
@@ -68,21 +194,239 @@ impl MTFStateManager {
             .write()
             .map_err(|e| format!("Lock error: {}", e))?;
 
-        let symbol_state = states.entry(tick.symbol.clone()).or_insert_with(|| {
-            SymbolMTFState::new(
-                tick.symbol.clone(),
-                &self.config.enabled_timeframes,
-                self.config.bar_history_limit,
-            )
-        });
+        let symbol_state = states
+            .entry(tick.symbol.clone())
+            .or_insert_with(|| self.new_symbol_state(&tick.symbol));
+        self.check_order(symbol_state, tick)?;
+
+        let completed = Self::apply_tick(symbol_state, tick)?;
+        // Handlers may query the manager, so release the state lock first
+        drop(states);
+
+        self.on_bars_completed(&completed)?;
+        Ok(completed)
+    }
+
+    /// Apply a synchronized snapshot of ticks, typically one per symbol at
+    /// the same timestamp, as a single atomic update.
+    ///
+    /// The whole batch is applied under one write lock, so a reader never
+    /// sees some symbols updated and others not. Ticks are applied in
+    /// timestamp order, keeping input order for equal timestamps, so within
+    /// a batch the order across symbols does not matter. `check_tick_order`
+    /// is still enforced against each symbol's state from earlier batches;
+    /// a rejected batch leaves every symbol, and the spike filter, untouched.
+    pub fn process_tick_batch(&self, ticks: &[Tick]) -> Result<Vec<Bar>, ProcessError> {
+        // Screen against a copy of the filter, held locked until the batch
+        // is accepted, so a rejected batch does not move its reference prices
+        let mut filter = match &self.spike_filter {
+            Some(filter) => Some(filter.write().map_err(|e| format!("Lock error: {}", e))?),
+            None => None,
+        };
+        let mut screened = filter.as_deref().cloned();
+        let mut accepted: Vec<&Tick> = match &mut screened {
+            Some(screened) => {
+                let exclude = screened.config().exclude_from_bars;
+                ticks
+                    .iter()
+                    .filter(|tick| !(screened.check_tick(tick) && exclude))
+                    .collect()
+            }
+            None => ticks.iter().collect(),
+        };
+        accepted.sort_by_key(|tick| tick.timestamp);
+
+        let mut states = self
+            .states
+            .write()
+            .map_err(|e| format!("Lock error: {}", e))?;
+
+        // Validate the whole batch before touching any state
+        let mut new_symbols: Vec<&str> = Vec::new();
+        for tick in &accepted {
+            match states.get(&tick.symbol) {
+                Some(symbol_state) => self.check_order(symbol_state, tick)?,
+                None if !new_symbols.contains(&tick.symbol.as_str()) => {
+                    new_symbols.push(&tick.symbol)
+                }
+                None => {}
+            }
+        }
+        if states.len() + new_symbols.len() > self.config.max_symbols {
+            let rejected = new_symbols[self.config.max_symbols.saturating_sub(states.len())];
+            return Err(ProcessError::Rejected(format!(
+                "Maximum symbols ({}) reached. Cannot add {}",
+                self.config.max_symbols, rejected
+            )));
+        }
+        if let (Some(filter), Some(screened)) = (filter.as_deref_mut(), screened) {
+            *filter = screened;
+        }
+        drop(filter);
+
+        let mut completed = Vec::new();
+        for tick in accepted {
+            let symbol_state = states
+                .entry(tick.symbol.clone())
+                .or_insert_with(|| self.new_symbol_state(&tick.symbol));
+            completed.extend(Self::apply_tick(symbol_state, tick)?);
+        }
+        drop(states);
+
+        self.on_bars_completed(&completed)?;
+        Ok(completed)
+    }
+
+    pub(crate) fn new_symbol_state(&self, symbol: &str) -> SymbolMTFState {
+        SymbolMTFState::new(
+            symbol.to_string(),
+            &self.config.enabled_timeframes,
+            self.config.bar_history_limit,
+        )
+        .with_spread_window(self.config.spread_window)
+    }
 
+    fn check_order(&self, symbol_state: &SymbolMTFState, tick: &Tick) -> Result<(), ProcessError> {
+        if self.config.check_tick_order
+            && symbol_state.current_tick.is_some()
+            && tick.timestamp < symbol_state.last_update
+        {
+            return Err(ProcessError::OutOfOrderTick {
+                symbol: tick.symbol.clone(),
+                previous: symbol_state.last_update,
+                current: tick.timestamp,
+            });
+        }
+        Ok(())
+    }
+
+    fn apply_tick(symbol_state: &mut SymbolMTFState, tick: &Tick) -> Result<Vec<Bar>, String> {
         // Use mid-price for bar aggregation
         let price = (tick.bid + tick.ask) / 2.0;
         let volume = tick.bid_size.unwrap_or(0) + tick.ask_size.unwrap_or(0);
-
+        symbol_state.spread.push(tick.ask - tick.bid);
         symbol_state.process_tick(tick.timestamp, price, volume)
     }
 
+    /// Feed completed bars to the correlation tracker and attached pipelines
+    fn on_bars_completed(&self, completed: &[Bar]) -> Result<(), String> {
+        if completed.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(correlation) = &self.correlation {
+            let mut correlation = correlation
+                .write()
+                .map_err(|e| format!("Lock error: {}", e))?;
+            for bar in completed {
+                correlation.on_bar(bar);
+            }
+        }
+
+        let mut symbols: Vec<&str> = Vec::new();
+        for bar in completed {
+            if !symbols.contains(&bar.symbol.as_str()) {
+                symbols.push(&bar.symbol);
+            }
+        }
+        for symbol in symbols {
+            let bars: Vec<Bar> = completed
+                .iter()
+                .filter(|bar| bar.symbol == symbol)
+                .cloned()
+                .collect();
+            self.update_indicators(symbol, &bars)?;
+        }
+        Ok(())
+    }
+
+    fn update_indicators(&self, symbol: &str, completed: &[Bar]) -> Result<(), String> {
+        let Some(pipeline) = self.pipeline(symbol) else {
+            return Ok(());
+        };
+
+        let mut completed = completed.to_vec();
+        // Shorter timeframes first, so handlers see M1 before the M5 it closes
+        completed.sort_by_key(|bar| bar.timeframe.duration_ms());
+        for bar in &completed {
+            pipeline
+                .update_all(&BarData::from(bar), bar.timeframe)
+                .map_err(|e| format!("Indicator update failed: {}", e))?;
+
+            if let Some(dispatcher) = &self.dispatcher {
+                let values = pipeline
+                    .get_indicator_names()
+                    .into_iter()
+                    .filter(|name| pipeline.is_enabled(name))
+                    .filter_map(|name| {
+                        let value = pipeline.get_value(&name, bar.timeframe)?;
+                        Some((name, value))
+                    })
+                    .collect();
+                dispatcher.dispatch_indicator_update(&IndicatorUpdateEvent {
+                    symbol: symbol.to_string(),
+                    timeframe: bar.timeframe,
+                    bar_timestamp: bar.timestamp_start,
+                    values,
+                });
+            }
+        }
+
+        // Every timeframe closing at the daily close has been fed by now,
+        // so session-anchored indicators restart with the next bar
+        if completed.iter().any(|bar| {
+            self.session_manager
+                .is_session_boundary(symbol, Timeframe::D1, bar.timestamp_end)
+        }) {
+            pipeline.notify_session_boundary();
+        }
+        Ok(())
+    }
+
+    /// Timestamp of the last tick processed for `symbol`
+    pub fn last_processed_timestamp(&self, symbol: &str) -> Option<i64> {
+        self.states
+            .read()
+            .ok()
+            .and_then(|states| states.get(symbol).map(|state| state.last_update))
+    }
+
+    /// Queue a tick for later processing, applying the configured
+    /// `TickBufferPolicy` when the buffer is full. Fails if the config does
+    /// not pass `MTFConfig::validate`.
+    ///
+    /// `TickBufferPolicy::Block` waits for `process_buffered_ticks` on
+    /// another thread; called from the consuming thread itself it fails
+    /// after `DEFAULT_BLOCK_TIMEOUT` rather than deadlocking.
+    pub fn buffer_tick(&self, tick: Tick) -> Result<(), String> {
+        self.tick_buffer()?.push(tick)
+    }
+
+    /// Process every buffered tick in arrival order
+    pub fn process_buffered_ticks(&self) -> Result<Vec<Bar>, String> {
+        let mut completed = Vec::new();
+        for tick in self.tick_buffer()?.drain() {
+            completed.extend(self.process_tick(&tick)?);
+        }
+        Ok(completed)
+    }
+
+    pub fn buffered_tick_count(&self) -> usize {
+        self.tick_buffer.get().map_or(0, TickBuffer::len)
+    }
+
+    /// Ticks discarded by `TickBufferPolicy::DropOldest`
+    pub fn dropped_tick_count(&self) -> u64 {
+        self.tick_buffer.get().map_or(0, TickBuffer::dropped_count)
+    }
+
+    fn tick_buffer(&self) -> Result<&TickBuffer, String> {
+        self.config.validate()?;
+        Ok(self.tick_buffer.get_or_init(|| {
+            TickBuffer::new(self.config.tick_buffer_size, self.config.tick_buffer_policy)
+        }))
+    }
+
     pub fn get_symbol_state(&self, symbol: &str) -> Option<SymbolMTFState> {
         self.states
             .read()
@@ -90,6 +434,83 @@ impl MTFStateManager {
             .and_then(|states| states.get(symbol).cloned())
     }
 
+    /// States of several symbols read under one lock, so they reflect the
+    /// same point in processing. Unknown symbols are omitted.
+    pub fn get_symbol_states(&self, symbols: &[&str]) -> Vec<SymbolMTFState> {
+        self.states
+            .read()
+            .ok()
+            .map(|states| {
+                symbols
+                    .iter()
+                    .filter_map(|symbol| states.get(*symbol).cloned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Last `n` completed bars for a timeframe, oldest first.
+    ///
+    /// Reads the bar ring in place instead of cloning the whole symbol state.
+    pub fn recent_bars(&self, symbol: &str, timeframe: Timeframe, n: usize) -> Vec<Bar> {
+        self.states
+            .read()
+            .ok()
+            .and_then(|states| {
+                states
+                    .get(symbol)
+                    .and_then(|state| state.get_timeframe_state(timeframe))
+                    .map(|tf_state| tf_state.get_latest_bars(n))
+            })
+            .unwrap_or_default()
+    }
+
+    /// Rolling spread statistics over the last `MTFConfig::spread_window`
+    /// ticks of `symbol`
+    pub fn spread_stats(&self, symbol: &str) -> Option<SpreadStats> {
+        self.states.read().ok()?.get(symbol)?.spread_stats()
+    }
+
+    /// The engine clock: the latest tick timestamp across all symbols
+    pub fn current_timestamp(&self) -> Option<i64> {
+        let states = self.states.read().ok()?;
+        states
+            .values()
+            .filter(|state| state.current_tick.is_some())
+            .map(|state| state.last_update)
+            .max()
+    }
+
+    /// The in-progress bar for the current period of `timeframe`.
+    ///
+    /// `None` until a tick for `symbol` has opened the period the engine
+    /// clock is in, so a bar left over from before a data gap is not returned.
+    pub fn partial_bar(&self, symbol: &str, timeframe: Timeframe) -> Option<PartialBar> {
+        let now = self.current_timestamp()?;
+        let states = self.states.read().ok()?;
+        let tf_state = states.get(symbol)?.get_timeframe_state(timeframe)?;
+
+        if tf_state.bar_start_time != timeframe.bar_start_timestamp(now) {
+            return None;
+        }
+        tf_state.current_bar.clone()
+    }
+
+    /// Fraction (0.0-1.0) of the current `timeframe` period elapsed on the
+    /// engine clock. Tracks elapsed time even when `symbol` has not ticked
+    /// in the current period.
+    pub fn partial_bar_progress(&self, symbol: &str, timeframe: Timeframe) -> Option<f64> {
+        let now = self.current_timestamp()?;
+        {
+            let states = self.states.read().ok()?;
+            states.get(symbol)?.get_timeframe_state(timeframe)?;
+        }
+
+        let bar_start = timeframe.bar_start_timestamp(now);
+        let length = timeframe.bar_end_timestamp(bar_start) - bar_start;
+        Some(((now - bar_start) as f64 / length as f64).clamp(0.0, 1.0))
+    }
+
     pub fn get_all_symbols(&self) -> Vec<String> {
         self.states
             .read()
@@ -98,12 +519,78 @@ impl MTFStateManager {
             .unwrap_or_default()
     }
 
+    pub fn config(&self) -> &MTFConfig {
+        &self.config
+    }
+
+    /// Symbols with state, sorted
+    pub fn active_symbols(&self) -> Vec<String> {
+        let mut symbols = self.get_all_symbols();
+        symbols.sort_unstable();
+        symbols
+    }
+
+    /// Replace the state of every symbol, e.g. with one restored from a
+    /// checkpoint
+    pub(crate) fn replace_symbol_states(
+        &self,
+        restored: HashMap<String, SymbolMTFState>,
+    ) -> Result<(), String> {
+        if restored.len() > self.config.max_symbols {
+            return Err(format!(
+                "Maximum symbols ({}) reached. Cannot restore {} symbols",
+                self.config.max_symbols,
+                restored.len()
+            ));
+        }
+        let mut states = self
+            .states
+            .write()
+            .map_err(|e| format!("Lock error: {}", e))?;
+        *states = restored;
+        Ok(())
+    }
+
+    /// Drop everything held for `symbol`: its bar state on every timeframe,
+    /// the attached pipeline, and its correlation and spike filter history.
+    ///
+    /// Returns whether the symbol had state. Other symbols keep processing
+    /// while this runs, and a later tick for `symbol` starts it afresh like
+    /// any new symbol.
+    pub fn remove_symbol(&self, symbol: &str) -> bool {
+        // Locks are taken one at a time, never nested, so this cannot
+        // deadlock against tick processing
+        let removed = match self.states.write() {
+            Ok(mut states) => states.remove(symbol).is_some(),
+            Err(_) => false,
+        };
+        if let Ok(mut pipelines) = self.pipelines.write() {
+            pipelines.remove(symbol);
+        }
+        if let Some(correlation) = &self.correlation {
+            if let Ok(mut correlation) = correlation.write() {
+                correlation.remove_symbol(symbol);
+            }
+        }
+        if let Some(filter) = &self.spike_filter {
+            if let Ok(mut filter) = filter.write() {
+                filter.remove_symbol(symbol);
+            }
+        }
+        removed
+    }
+
     pub fn clear_symbol(&self, symbol: &str) -> Result<(), String> {
         let mut states = self
             .states
             .write()
             .map_err(|e| format!("Lock error: {}", e))?;
         states.remove(symbol);
+        if let Some(correlation) = &self.correlation {
+            if let Ok(mut correlation) = correlation.write() {
+                correlation.remove_symbol(symbol);
+            }
+        }
         Ok(())
     }
 
@@ -113,6 +600,11 @@ impl MTFStateManager {
             .write()
             .map_err(|e| format!("Lock error: {}", e))?;
         states.clear();
+        if let Some(correlation) = &self.correlation {
+            if let Ok(mut correlation) = correlation.write() {
+                correlation.clear();
+            }
+        }
         Ok(())
     }
 
@@ -138,6 +630,9 @@ impl MTFStateManager {
                 total_bytes += 64;
             }
 
+            // Spread window
+            total_bytes += state.spread.window_len() * 8;
+
             // State overhead
             total_bytes += 128;
         }
@@ -152,6 +647,7 @@ pub struct SymbolMTFState {
     pub current_tick: Option<Tick>,
     pub timeframes: HashMap<Timeframe, TimeframeState>,
     pub last_update: i64,
+    spread: SpreadTracker,
 }
 
 impl SymbolMTFState {
@@ -166,9 +662,20 @@ impl SymbolMTFState {
             current_tick: None,
             timeframes: tf_states,
             last_update: 0,
+            spread: SpreadTracker::new(DEFAULT_SPREAD_WINDOW),
         }
     }
 
+    /// Track spread statistics over the last `window` ticks
+    pub fn with_spread_window(mut self, window: usize) -> Self {
+        self.spread = SpreadTracker::new(window);
+        self
+    }
+
+    pub fn spread_stats(&self) -> Option<SpreadStats> {
+        self.spread.stats()
+    }
+
     pub fn process_tick(
         &mut self,
         timestamp: i64,
@@ -270,6 +777,41 @@ mod tests {
         assert!(result.unwrap_err().contains("Maximum symbols"));
     }
 
+    #[test]
+    fn test_out_of_order_tick_rejected_when_checked() {
+        let tick_at =
+            |timestamp| Tick::new_with_millis("EURUSD".to_string(), timestamp, 1.0920, 1.0922);
+
+        let unchecked = MTFStateManager::with_default_config();
+        unchecked.process_tick(&tick_at(1704067230000)).unwrap();
+        assert!(unchecked.process_tick(&tick_at(1704067229000)).is_ok());
+
+        let manager = MTFStateManager::new(MTFConfig {
+            check_tick_order: true,
+            ..Default::default()
+        });
+        manager.try_process_tick(&tick_at(1704067230000)).unwrap();
+        // Equal timestamps are still in order
+        manager.try_process_tick(&tick_at(1704067230000)).unwrap();
+
+        let err = manager
+            .try_process_tick(&tick_at(1704067229000))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ProcessError::OutOfOrderTick {
+                symbol: "EURUSD".to_string(),
+                previous: 1704067230000,
+                current: 1704067229000,
+            }
+        );
+        assert_eq!(
+            manager.last_processed_timestamp("EURUSD"),
+            Some(1704067230000)
+        );
+        assert_eq!(manager.last_processed_timestamp("GBPUSD"), None);
+    }
+
     #[test]
     fn test_clear_symbol() {
         let manager = MTFStateManager::with_default_config();
@@ -282,6 +824,458 @@ mod tests {
         assert_eq!(manager.get_all_symbols().len(), 0);
     }
 
+    #[test]
+    fn test_remove_symbol_recreates_state_lazily() {
+        let manager = MTFStateManager::with_default_config().with_correlation_window(10);
+        manager.attach_pipeline("EURUSD", IndicatorPipeline::new(10));
+        for (symbol, timestamp) in [("GBPUSD", 1704067230000), ("EURUSD", 1704067290000)] {
+            let tick = Tick::new_with_millis(symbol.to_string(), timestamp, 1.0920, 1.0922);
+            manager.process_tick(&tick).unwrap();
+        }
+        assert_eq!(manager.active_symbols(), vec!["EURUSD", "GBPUSD"]);
+
+        assert!(manager.remove_symbol("EURUSD"));
+        assert!(!manager.remove_symbol("EURUSD"));
+        assert_eq!(manager.active_symbols(), vec!["GBPUSD"]);
+        assert!(manager.pipeline("EURUSD").is_none());
+        assert!(manager.get_symbol_state("EURUSD").is_none());
+
+        // An earlier timestamp is accepted: the old ordering state is gone
+        let tick = Tick::new_with_millis("EURUSD".to_string(), 1704067200000, 1.0930, 1.0932);
+        manager.process_tick(&tick).unwrap();
+        let state = manager.get_symbol_state("EURUSD").unwrap();
+        assert_eq!(state.last_update, 1704067200000);
+        assert!(state.timeframes[&Timeframe::M1].completed_bars.is_empty());
+    }
+
+    #[test]
+    fn test_remove_symbol_during_processing() {
+        let manager = Arc::new(MTFStateManager::with_default_config());
+        let worker = {
+            let manager = Arc::clone(&manager);
+            std::thread::spawn(move || {
+                for i in 0..500 {
+                    let tick = Tick::new_with_millis(
+                        "GBPUSD".to_string(),
+                        1704067200000 + i * 1_000,
+                        1.2700,
+                        1.2702,
+                    );
+                    manager.process_tick(&tick).unwrap();
+                }
+            })
+        };
+
+        for i in 0..200 {
+            let tick = Tick::new_with_millis(
+                "EURUSD".to_string(),
+                1704067200000 + i * 1_000,
+                1.0920,
+                1.0922,
+            );
+            manager.process_tick(&tick).unwrap();
+            manager.remove_symbol("EURUSD");
+        }
+        worker.join().unwrap();
+
+        assert_eq!(manager.active_symbols(), vec!["GBPUSD"]);
+        assert_eq!(
+            manager.last_processed_timestamp("GBPUSD"),
+            Some(1704067200000 + 499 * 1_000)
+        );
+    }
+
+    fn h1_bars(enabled_timeframes: Vec<Timeframe>) -> Vec<Bar> {
+        let manager = MTFStateManager::new(MTFConfig {
+            enabled_timeframes,
+            ..Default::default()
+        });
+        let mut completed = Vec::new();
+        // One tick every 7 minutes across three hours
+        for i in 0..26 {
+            let mid = 1.0920 + (i as f64 * 0.7).sin() * 0.001;
+            let tick =
+                Tick::new_with_millis("EURUSD".to_string(), 1704067200000 + i * 420_000, mid, mid);
+            completed.extend(manager.process_tick(&tick).unwrap());
+        }
+        completed.retain(|bar| bar.timeframe == Timeframe::H1);
+        completed
+    }
+
+    #[test]
+    fn test_h1_without_intermediate_timeframes() {
+        let sparse = h1_bars(vec![Timeframe::M1, Timeframe::H1]);
+        let full = h1_bars(Timeframe::all());
+
+        assert_eq!(sparse.len(), 2);
+        assert_eq!(sparse.len(), full.len());
+        for (a, b) in sparse.iter().zip(&full) {
+            assert_eq!(
+                (
+                    a.timestamp_start,
+                    a.open,
+                    a.high,
+                    a.low,
+                    a.close,
+                    a.tick_count
+                ),
+                (
+                    b.timestamp_start,
+                    b.open,
+                    b.high,
+                    b.low,
+                    b.close,
+                    b.tick_count
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_only_enabled_timeframes_are_built() {
+        let manager = MTFStateManager::new(MTFConfig {
+            enabled_timeframes: vec![Timeframe::M1, Timeframe::M5],
+            ..Default::default()
+        });
+        assert!(manager.config().is_timeframe_enabled(Timeframe::M5));
+        assert!(!manager.config().is_timeframe_enabled(Timeframe::H4));
+
+        let tick = Tick::new_with_millis("EURUSD".to_string(), 1704067230000, 1.0920, 1.0922);
+        manager.process_tick(&tick).unwrap();
+
+        let state = manager.get_symbol_state("EURUSD").unwrap();
+        assert_eq!(state.timeframes.len(), 2);
+        assert!(state.get_timeframe_state(Timeframe::D1).is_none());
+    }
+
+    #[test]
+    fn test_spike_excluded_from_bars() {
+        let manager = MTFStateManager::with_default_config().with_spike_filter(SpikeFilterConfig {
+            atr_period: 5,
+            ..Default::default()
+        });
+
+        for i in 0..10 {
+            let offset = if i % 2 == 0 { 0.0 } else { 0.0001 };
+            let tick = Tick::new_with_millis(
+                "EURUSD".to_string(),
+                1704067200000 + i * 1_000,
+                1.0920 + offset,
+                1.0922 + offset,
+            );
+            manager.process_tick(&tick).unwrap();
+        }
+
+        let spike = Tick::new_with_millis("EURUSD".to_string(), 1704067211000, 1.0990, 1.1010);
+        assert!(manager.process_tick(&spike).unwrap().is_empty());
+
+        let state = manager.get_symbol_state("EURUSD").unwrap();
+        let partial = state.timeframes[&Timeframe::M1]
+            .current_bar
+            .clone()
+            .unwrap();
+        assert!(partial.high < 1.0930);
+        assert_eq!(partial.tick_count, 10);
+
+        let filter = manager.spike_filter().unwrap();
+        assert_eq!(filter.read().unwrap().spikes_detected(), 1);
+        assert!(filter
+            .read()
+            .unwrap()
+            .is_cooling_down("EURUSD", 1704067212000));
+    }
+
+    #[test]
+    fn test_correlation_fed_from_completed_bars() {
+        let manager = MTFStateManager::new(MTFConfig {
+            enabled_timeframes: vec![Timeframe::M1],
+            ..Default::default()
+        })
+        .with_correlation_window(20);
+
+        for minute in 0..12 {
+            let move_ = (minute as f64 * 0.9).sin() * 0.001;
+            let timestamp = 1704067200000 + minute * 60_000;
+            for (symbol, base) in [("EURUSD", 1.0920), ("GBPUSD", 1.2700)] {
+                let mid = base + move_;
+                let tick = Tick::new_with_millis(symbol.to_string(), timestamp, mid, mid);
+                manager.process_tick(&tick).unwrap();
+            }
+        }
+
+        let tracker = manager.correlation_tracker().unwrap();
+        let correlation = tracker
+            .read()
+            .unwrap()
+            .correlation("EURUSD", "GBPUSD", Timeframe::M1)
+            .unwrap();
+        assert!(correlation > 0.99);
+
+        manager.clear_symbol("GBPUSD").unwrap();
+        assert_eq!(
+            tracker.read().unwrap().matrix(Timeframe::M1).symbols,
+            vec!["EURUSD"]
+        );
+    }
+
+    fn snapshot_batch(timestamp: i64) -> Vec<Tick> {
+        vec![
+            Tick::new_with_millis("EURUSD".to_string(), timestamp, 1.0920, 1.0922),
+            Tick::new_with_millis("GBPUSD".to_string(), timestamp, 1.2700, 1.2702),
+        ]
+    }
+
+    #[test]
+    fn test_tick_batch_order_within_batch_irrelevant() {
+        let forward = MTFStateManager::with_default_config();
+        let reversed = MTFStateManager::with_default_config();
+
+        for minute in 0..3 {
+            let batch = snapshot_batch(1704067200000 + minute * 60_000);
+            let mut forward_bars = forward.process_tick_batch(&batch).unwrap();
+            let mut reversed_batch = batch.clone();
+            reversed_batch.reverse();
+            let mut reversed_bars = reversed.process_tick_batch(&reversed_batch).unwrap();
+
+            forward_bars.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+            reversed_bars.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+            assert_eq!(forward_bars, reversed_bars);
+        }
+
+        for symbol in ["EURUSD", "GBPUSD"] {
+            assert_eq!(
+                forward.recent_bars(symbol, Timeframe::M1, 10),
+                reversed.recent_bars(symbol, Timeframe::M1, 10)
+            );
+            assert_eq!(forward.recent_bars(symbol, Timeframe::M1, 10).len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_tick_batch_order_enforced_across_batches() {
+        let manager = MTFStateManager::new(MTFConfig {
+            check_tick_order: true,
+            ..Default::default()
+        });
+        manager
+            .process_tick_batch(&snapshot_batch(1704067230000))
+            .unwrap();
+
+        // GBPUSD is in order but the batch is rejected as a whole
+        let mut late = snapshot_batch(1704067240000);
+        late[0].timestamp = 1704067220000;
+        let err = manager.process_tick_batch(&late).unwrap_err();
+        assert!(matches!(err, ProcessError::OutOfOrderTick { .. }));
+        assert_eq!(
+            manager.last_processed_timestamp("GBPUSD"),
+            Some(1704067230000)
+        );
+
+        let limited = MTFStateManager::new(MTFConfig {
+            max_symbols: 1,
+            ..Default::default()
+        });
+        assert!(limited
+            .process_tick_batch(&snapshot_batch(1704067230000))
+            .is_err());
+        assert!(limited.get_all_symbols().is_empty());
+    }
+
+    #[test]
+    fn test_rejected_tick_batch_leaves_spike_filter_untouched() {
+        let manager = MTFStateManager::new(MTFConfig {
+            check_tick_order: true,
+            ..Default::default()
+        })
+        .with_spike_filter(SpikeFilterConfig {
+            atr_period: 2,
+            ..Default::default()
+        });
+        manager
+            .process_tick_batch(&snapshot_batch(1704067230000))
+            .unwrap();
+
+        // EURUSD is out of order, so GBPUSD's move must not be screened in
+        let mut late = snapshot_batch(1704067240000);
+        late[0].timestamp = 1704067220000;
+        late[1].bid = 1.2750;
+        late[1].ask = 1.2752;
+        assert!(manager.process_tick_batch(&late).is_err());
+
+        let filter = manager.spike_filter().unwrap();
+        assert_eq!(filter.read().unwrap().tick_atr("GBPUSD"), None);
+
+        manager
+            .process_tick_batch(&snapshot_batch(1704067240000))
+            .unwrap();
+        manager
+            .process_tick_batch(&snapshot_batch(1704067250000))
+            .unwrap();
+        assert_eq!(filter.read().unwrap().tick_atr("GBPUSD"), Some(0.0));
+    }
+
+    #[test]
+    fn test_tick_batch_never_observed_half_applied() {
+        use crate::mtf::StateQuery;
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let manager = MTFStateManager::with_default_config();
+        manager
+            .process_tick_batch(&snapshot_batch(1704067200000))
+            .unwrap();
+        let done = Arc::new(AtomicBool::new(false));
+
+        let reader = {
+            let manager = manager.clone();
+            let done = Arc::clone(&done);
+            std::thread::spawn(move || {
+                let query = StateQuery::new(&manager);
+                while !done.load(Ordering::Relaxed) {
+                    let snapshots = query.get_snapshots(&["EURUSD", "GBPUSD"]);
+                    assert_eq!(snapshots.len(), 2);
+                    assert_eq!(snapshots[0].timestamp, snapshots[1].timestamp);
+                }
+            })
+        };
+
+        for i in 1..500 {
+            manager
+                .process_tick_batch(&snapshot_batch(1704067200000 + i * 1_000))
+                .unwrap();
+        }
+        done.store(true, Ordering::Relaxed);
+        reader.join().unwrap();
+    }
+
+    #[derive(Default)]
+    struct IndicatorRecorder {
+        events: std::sync::Mutex<Vec<IndicatorUpdateEvent>>,
+    }
+
+    impl crate::events::EventHandler for IndicatorRecorder {
+        fn on_tick(&self, _event: &crate::events::TickEvent) {}
+
+        fn on_bar(&self, _event: &crate::events::BarEvent) {}
+
+        fn on_indicator_update(&self, event: &IndicatorUpdateEvent) {
+            self.events.lock().unwrap().push(event.clone());
+        }
+    }
+
+    #[test]
+    fn test_attached_pipeline_sees_only_completed_bars() {
+        use crate::indicators::SMA;
+
+        let recorder = Arc::new(IndicatorRecorder::default());
+        let mut dispatcher = EventDispatcher::new();
+        dispatcher.add_handler(recorder.clone());
+
+        let manager = MTFStateManager::new(MTFConfig {
+            enabled_timeframes: vec![Timeframe::M1],
+            ..Default::default()
+        })
+        .with_event_dispatcher(dispatcher);
+
+        let pipeline = IndicatorPipeline::new(100);
+        pipeline.register_indicator("SMA_2".to_string(), Box::new(SMA::new(2)));
+        manager.attach_pipeline("EURUSD", pipeline);
+
+        let tick = |minute: i64, mid: f64| {
+            Tick::new_with_millis(
+                "EURUSD".to_string(),
+                1704067200000 + minute * 60_000,
+                mid,
+                mid,
+            )
+        };
+
+        manager.process_tick(&tick(0, 1.0)).unwrap();
+        manager.process_tick(&tick(1, 3.0)).unwrap();
+        assert_eq!(
+            manager.indicator_value("EURUSD", Timeframe::M1, "SMA_2"),
+            None
+        );
+
+        // Completes the 3.0 bar; the open 100.0 bar must not leak in
+        manager.process_tick(&tick(2, 100.0)).unwrap();
+        assert_eq!(
+            manager.indicator_value("EURUSD", Timeframe::M1, "SMA_2"),
+            Some(2.0)
+        );
+
+        let events = recorder.events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].value("SMA_2"), None);
+        assert_eq!(events[1].value("SMA_2"), Some(2.0));
+        assert_eq!(events[1].bar_timestamp, 1704067200000 + 60_000);
+
+        // Symbols without a pipeline are left alone
+        assert_eq!(
+            manager.indicator_value("GBPUSD", Timeframe::M1, "SMA_2"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_attached_session_vwap_resets_at_daily_close() {
+        use crate::indicators::VWAP;
+
+        let manager = MTFStateManager::new(MTFConfig {
+            enabled_timeframes: vec![Timeframe::M1, Timeframe::M5],
+            ..Default::default()
+        });
+        let pipeline = IndicatorPipeline::new(100);
+        pipeline.register_indicator("VWAP".to_string(), Box::new(VWAP::new(true)));
+        manager.attach_pipeline("EURUSD", pipeline);
+
+        // 2024-01-01 21:58 UTC, 16:58 Eastern; the 21:59 bar closes at the
+        // 17:00 Eastern session boundary
+        let tick = |minute: i64, mid: f64| {
+            Tick::new_with_millis(
+                "EURUSD".to_string(),
+                1704146280000 + minute * 60_000,
+                mid,
+                mid,
+            )
+            .with_sizes(500, 500)
+        };
+
+        manager.process_tick(&tick(0, 1.10)).unwrap();
+        manager.process_tick(&tick(1, 1.20)).unwrap();
+        let before = manager
+            .indicator_value("EURUSD", Timeframe::M1, "VWAP")
+            .unwrap();
+        assert!((before - 1.10).abs() < 1e-9);
+
+        // Completes the 21:59 M1 bar and the 21:55 M5 bar, then resets
+        manager.process_tick(&tick(2, 1.30)).unwrap();
+        assert_eq!(
+            manager.indicator_value("EURUSD", Timeframe::M1, "VWAP"),
+            None
+        );
+
+        // The first bar of the new session does not inherit the old totals
+        manager.process_tick(&tick(3, 1.40)).unwrap();
+        let after = manager
+            .indicator_value("EURUSD", Timeframe::M1, "VWAP")
+            .unwrap();
+        assert!((after - 1.30).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_zero_tick_buffer_rejected() {
+        let config = MTFConfig {
+            tick_buffer_size: 0,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let manager = MTFStateManager::new(config);
+        let tick = Tick::new_with_millis("EURUSD".to_string(), 0, 1.0920, 1.0922);
+        assert!(manager.buffer_tick(tick).is_err());
+        assert!(manager.process_buffered_ticks().is_err());
+    }
+
     #[test]
     fn test_memory_estimate() {
         let manager = MTFStateManager::with_default_config();
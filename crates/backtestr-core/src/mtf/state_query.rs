@@ -1,4 +1,6 @@
-use crate::mtf::{MTFStateManager, PartialBar};
+use crate::mtf::{
+    MTFStateManager, PartialBar, SnapshotDiff, SpreadStats, SymbolMTFState, DEFAULT_DIFF_EPSILON,
+};
 use backtestr_data::{Bar, Tick, Timeframe};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -14,6 +16,19 @@ pub struct MTFSnapshot {
     pub query_time_us: u64,
 }
 
+impl MTFSnapshot {
+    /// Fields that changed from `self` to `other`, with prices compared to
+    /// within `DEFAULT_DIFF_EPSILON`
+    pub fn diff(&self, other: &MTFSnapshot) -> SnapshotDiff {
+        self.diff_with_epsilon(other, DEFAULT_DIFF_EPSILON)
+    }
+
+    /// `diff` with prices closer than `epsilon` treated as equal
+    pub fn diff_with_epsilon(&self, other: &MTFSnapshot, epsilon: f64) -> SnapshotDiff {
+        SnapshotDiff::between(self, other, epsilon.abs())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimeframeSnapshot {
     pub timeframe: Timeframe,
@@ -34,9 +49,22 @@ impl<'a> StateQuery<'a> {
 
     pub fn get_snapshot(&self, symbol: &str) -> Option<MTFSnapshot> {
         let start = Instant::now();
-
         let state = self.manager.get_symbol_state(symbol)?;
+        Some(Self::snapshot_of(state, start))
+    }
+
+    /// Snapshots of several symbols taken at the same point in processing,
+    /// e.g. after a `process_tick_batch`. Unknown symbols are omitted.
+    pub fn get_snapshots(&self, symbols: &[&str]) -> Vec<MTFSnapshot> {
+        let start = Instant::now();
+        self.manager
+            .get_symbol_states(symbols)
+            .into_iter()
+            .map(|state| Self::snapshot_of(state, start))
+            .collect()
+    }
 
+    fn snapshot_of(state: SymbolMTFState, start: Instant) -> MTFSnapshot {
         let mut partial_bars = HashMap::new();
         let mut completed_bars = HashMap::new();
 
@@ -47,14 +75,14 @@ impl<'a> StateQuery<'a> {
 
         let query_time_us = start.elapsed().as_micros() as u64;
 
-        Some(MTFSnapshot {
-            symbol: symbol.to_string(),
+        MTFSnapshot {
+            symbol: state.symbol,
             timestamp: state.last_update,
             current_tick: state.current_tick,
             partial_bars,
             completed_bars,
             query_time_us,
-        })
+        }
     }
 
     pub fn get_timeframe_snapshot(
@@ -93,10 +121,45 @@ impl<'a> StateQuery<'a> {
         Some(tf_state.get_latest_bars(count))
     }
 
+    /// Last `n` closed bars, oldest first. Returns fewer when the history
+    /// holds fewer, capped by `MTFConfig::bar_history_limit`.
+    pub fn recent_bars(&self, symbol: &str, timeframe: Timeframe, n: usize) -> Vec<Bar> {
+        self.manager.recent_bars(symbol, timeframe, n)
+    }
+
+    /// Timestamp of the last tick processed for `symbol`. No completed bar
+    /// returned by this query ends after it.
+    pub fn last_processed_timestamp(&self, symbol: &str) -> Option<i64> {
+        self.manager.last_processed_timestamp(symbol)
+    }
+
+    /// Full OHLC of the bar in progress, `None` if no tick has started the
+    /// current period yet
+    pub fn partial_bar(&self, symbol: &str, timeframe: Timeframe) -> Option<PartialBar> {
+        self.manager.partial_bar(symbol, timeframe)
+    }
+
+    /// How far through the current bar the engine is, from 0.0 to 1.0
+    pub fn partial_bar_progress(&self, symbol: &str, timeframe: Timeframe) -> Option<f64> {
+        self.manager.partial_bar_progress(symbol, timeframe)
+    }
+
+    /// Current, average, min/max and percentile spread over the symbol's
+    /// recent ticks
+    pub fn spread_stats(&self, symbol: &str) -> Option<SpreadStats> {
+        self.manager.spread_stats(symbol)
+    }
+
     pub fn get_all_symbols(&self) -> Vec<String> {
         self.manager.get_all_symbols()
     }
 
+    /// Whether `timeframe` is maintained at all; per-timeframe queries for a
+    /// disabled one return `None`
+    pub fn is_timeframe_enabled(&self, timeframe: Timeframe) -> bool {
+        self.manager.config().is_timeframe_enabled(timeframe)
+    }
+
     pub fn has_symbol(&self, symbol: &str) -> bool {
         self.manager.get_symbol_state(symbol).is_some()
     }
@@ -104,6 +167,11 @@ impl<'a> StateQuery<'a> {
     pub fn get_memory_usage(&self) -> usize {
         self.manager.get_memory_usage_estimate()
     }
+
+    /// Non-zero once `TickBufferPolicy::DropOldest` has discarded ticks
+    pub fn get_dropped_tick_count(&self) -> u64 {
+        self.manager.dropped_tick_count()
+    }
 }
 
 #[cfg(test)]
@@ -207,4 +275,226 @@ mod tests {
         let memory = query.get_memory_usage();
         assert!(memory > 0);
     }
+
+    #[test]
+    fn test_dropped_tick_count_surfaces_drop_oldest() {
+        use crate::mtf::TickBufferPolicy;
+
+        let config = MTFConfig {
+            tick_buffer_size: 2,
+            tick_buffer_policy: TickBufferPolicy::DropOldest,
+            ..Default::default()
+        };
+        let manager = MTFStateManager::new(config);
+        let query = StateQuery::new(&manager);
+        assert_eq!(query.get_dropped_tick_count(), 0);
+
+        for i in 0..5 {
+            let tick = Tick::new_with_millis(
+                "EURUSD".to_string(),
+                1704067230000 + i * 1000,
+                1.0920,
+                1.0922,
+            );
+            manager.buffer_tick(tick).unwrap();
+        }
+
+        assert_eq!(query.get_dropped_tick_count(), 3);
+        assert_eq!(manager.buffered_tick_count(), 2);
+
+        manager.process_buffered_ticks().unwrap();
+        assert_eq!(manager.buffered_tick_count(), 0);
+        let snapshot = query.get_snapshot("EURUSD").unwrap();
+        assert_eq!(snapshot.timestamp, 1704067234000);
+    }
+
+    #[test]
+    fn test_recent_bars_bounded_by_history_limit() {
+        let config = MTFConfig {
+            bar_history_limit: 5,
+            enabled_timeframes: vec![Timeframe::M1],
+            ..Default::default()
+        };
+        let manager = MTFStateManager::new(config);
+
+        // One tick per minute completes a bar each time after the first
+        for i in 0..10 {
+            let tick = Tick::new_with_millis(
+                "EURUSD".to_string(),
+                1704067200000 + i * 60_000,
+                1.0920 + i as f64 * 0.0001,
+                1.0922 + i as f64 * 0.0001,
+            );
+            manager.process_tick(&tick).unwrap();
+        }
+
+        let query = StateQuery::new(&manager);
+        let bars = query.recent_bars("EURUSD", Timeframe::M1, 3);
+        assert_eq!(bars.len(), 3);
+        assert_eq!(bars[2].timestamp_start, 1704067200000 + 8 * 60_000);
+        assert!(bars[0].timestamp_start < bars[1].timestamp_start);
+
+        assert_eq!(query.recent_bars("EURUSD", Timeframe::M1, 100).len(), 5);
+        assert!(query.recent_bars("EURUSD", Timeframe::H1, 3).is_empty());
+        assert!(query.recent_bars("GBPUSD", Timeframe::M1, 3).is_empty());
+    }
+
+    #[test]
+    fn test_partial_bar_progress_follows_engine_clock() {
+        let config = MTFConfig {
+            enabled_timeframes: vec![Timeframe::M1, Timeframe::M5],
+            ..Default::default()
+        };
+        let manager = MTFStateManager::new(config);
+        let query = StateQuery::new(&manager);
+        assert_eq!(query.partial_bar_progress("EURUSD", Timeframe::M1), None);
+
+        // 10:00:30 for EURUSD, then GBPUSD moves the clock to 10:03:45
+        let eur = Tick::new_with_millis("EURUSD".to_string(), 1704103230000, 1.0920, 1.0922);
+        manager.process_tick(&eur).unwrap();
+        assert_eq!(
+            query.partial_bar_progress("EURUSD", Timeframe::M1),
+            Some(0.5)
+        );
+
+        let gbp = Tick::new_with_millis("GBPUSD".to_string(), 1704103425000, 1.2710, 1.2712);
+        manager.process_tick(&gbp).unwrap();
+
+        // No EURUSD tick in the current minute: progress still advances
+        assert_eq!(
+            query.partial_bar_progress("EURUSD", Timeframe::M1),
+            Some(0.75)
+        );
+        assert!(query.partial_bar("EURUSD", Timeframe::M1).is_none());
+
+        // The M5 bar opened at 10:00 is still current
+        assert_eq!(
+            query.partial_bar_progress("EURUSD", Timeframe::M5),
+            Some(0.75)
+        );
+        let bar = query.partial_bar("EURUSD", Timeframe::M5).unwrap();
+        assert_eq!(bar.open, 1.0921);
+
+        assert!(query.partial_bar("GBPUSD", Timeframe::M1).is_some());
+        assert_eq!(query.partial_bar_progress("EURUSD", Timeframe::H1), None);
+    }
+
+    #[test]
+    fn test_disabled_timeframe_queries_return_none() {
+        let manager = MTFStateManager::new(MTFConfig {
+            enabled_timeframes: vec![Timeframe::M1],
+            ..Default::default()
+        });
+        let tick = Tick::new_with_millis("EURUSD".to_string(), 1704067230000, 1.0920, 1.0922);
+        manager.process_tick(&tick).unwrap();
+
+        let query = StateQuery::new(&manager);
+        assert!(query.is_timeframe_enabled(Timeframe::M1));
+        assert!(!query.is_timeframe_enabled(Timeframe::H4));
+
+        assert!(query
+            .get_timeframe_snapshot("EURUSD", Timeframe::M1)
+            .is_some());
+        assert!(query
+            .get_timeframe_snapshot("EURUSD", Timeframe::H4)
+            .is_none());
+        assert!(query
+            .get_latest_completed_bars("EURUSD", Timeframe::H4, 5)
+            .is_none());
+        assert!(query.partial_bar("EURUSD", Timeframe::H4).is_none());
+        assert!(query
+            .partial_bar_progress("EURUSD", Timeframe::H4)
+            .is_none());
+
+        let snapshot = query.get_snapshot("EURUSD").unwrap();
+        assert!(!snapshot.partial_bars.contains_key(&Timeframe::H4));
+    }
+
+    #[test]
+    fn test_snapshot_diff() {
+        use crate::mtf::DiffValue;
+
+        let manager = MTFStateManager::new(MTFConfig {
+            enabled_timeframes: vec![Timeframe::M1, Timeframe::M5],
+            ..Default::default()
+        });
+        let query = StateQuery::new(&manager);
+        let tick = Tick::new_with_millis("EURUSD".to_string(), 1704067230000, 1.0920, 1.0922);
+        manager.process_tick(&tick).unwrap();
+        let before = query.get_snapshot("EURUSD").unwrap();
+        assert!(before.diff(&before).is_empty());
+        assert_eq!(before.diff(&before).to_string(), "EURUSD: no changes");
+
+        // The next minute closes the M1 bar and extends the M5 one
+        let tick = Tick::new_with_millis("EURUSD".to_string(), 1704067290000, 1.0930, 1.0932);
+        manager.process_tick(&tick).unwrap();
+        let after = query.get_snapshot("EURUSD").unwrap();
+
+        let diff = before.diff(&after);
+        assert!(diff.changes.iter().any(|c| c.timeframe.is_none()
+            && c.field == "timestamp"
+            && c.new == DiffValue::Int(1704067290000)));
+
+        let m1: Vec<_> = diff.for_timeframe(Timeframe::M1).collect();
+        assert!(m1.iter().any(|c| c.field == "completed_bars"
+            && c.old == DiffValue::Int(0)
+            && c.new == DiffValue::Int(1)));
+        assert!(m1
+            .iter()
+            .any(|c| c.field == "last_bar.close" && c.old == DiffValue::Missing));
+
+        // The M5 open is unchanged, its high and close moved
+        let m5: Vec<_> = diff.for_timeframe(Timeframe::M5).map(|c| c.field).collect();
+        assert!(!m5.contains(&"partial.open"));
+        assert!(m5.contains(&"partial.high"));
+        assert!(m5.contains(&"partial.close"));
+
+        let report = diff.to_string();
+        assert!(report.starts_with(&format!("EURUSD: {} change(s)", diff.changes.len())));
+        assert!(report.contains("partial.close"));
+    }
+
+    #[test]
+    fn test_snapshot_diff_epsilon() {
+        let manager = MTFStateManager::with_default_config();
+        let tick = Tick::new_with_millis("EURUSD".to_string(), 1704067230000, 1.0923, 1.0923);
+        manager.process_tick(&tick).unwrap();
+        let before = StateQuery::new(&manager).get_snapshot("EURUSD").unwrap();
+
+        let mut after = before.clone();
+        after.current_tick.as_mut().unwrap().bid = 1.09230000001;
+        assert!(before.diff(&after).is_empty());
+
+        let diff = before.diff_with_epsilon(&after, 1e-12);
+        assert_eq!(diff.changes.len(), 1);
+        assert_eq!(diff.changes[0].field, "tick.bid");
+    }
+
+    #[test]
+    fn test_spread_stats_from_ticks() {
+        let manager = MTFStateManager::new(MTFConfig {
+            spread_window: 3,
+            ..Default::default()
+        });
+        let query = StateQuery::new(&manager);
+        assert!(query.spread_stats("EURUSD").is_none());
+
+        for (i, spread) in [0.0004, 0.0001, 0.0002, 0.0003].iter().enumerate() {
+            let tick = Tick::new_with_millis(
+                "EURUSD".to_string(),
+                1704067200000 + i as i64 * 1_000,
+                1.0920,
+                1.0920 + spread,
+            );
+            manager.process_tick(&tick).unwrap();
+        }
+
+        let stats = query.spread_stats("EURUSD").unwrap();
+        assert!((stats.current - 0.0003).abs() < 1e-12);
+        assert!((stats.average - 0.0002).abs() < 1e-12);
+        assert!((stats.max - 0.0003).abs() < 1e-12);
+        assert_eq!(stats.window_len, 3);
+        assert_eq!(stats.tick_count, 4);
+        assert!(query.spread_stats("GBPUSD").is_none());
+    }
 }
@@ -0,0 +1,227 @@
+use backtestr_data::Tick;
+use crossbeam::channel::{bounded, Receiver, SendTimeoutError, Sender, TrySendError};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long `TickBufferPolicy::Block` waits for room by default
+pub const DEFAULT_BLOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What to do with an incoming tick when the ingestion buffer is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TickBufferPolicy {
+    /// Wait for the consumer to make room. Never loses ticks, so this is the
+    /// only policy that keeps backtest state exact. The consumer must run on
+    /// another thread; a push that finds no room within the block timeout
+    /// fails instead of waiting forever.
+    #[default]
+    Block,
+    /// Evict the oldest buffered tick. For live or approximate runs only.
+    DropOldest,
+    /// Reject the incoming tick with an error
+    Error,
+}
+
+impl FromStr for TickBufferPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "block" => Ok(Self::Block),
+            "drop_oldest" | "dropoldest" => Ok(Self::DropOldest),
+            "error" => Ok(Self::Error),
+            other => Err(format!("Unknown tick buffer policy: {}", other)),
+        }
+    }
+}
+
+/// Bounded tick queue between producers and the MTF state manager
+#[derive(Debug, Clone)]
+pub struct TickBuffer {
+    sender: Sender<Tick>,
+    receiver: Receiver<Tick>,
+    capacity: usize,
+    policy: TickBufferPolicy,
+    block_timeout: Duration,
+    dropped: Arc<AtomicU64>,
+}
+
+impl TickBuffer {
+    pub fn new(capacity: usize, policy: TickBufferPolicy) -> Self {
+        let (sender, receiver) = bounded(capacity);
+        Self {
+            sender,
+            receiver,
+            capacity,
+            policy,
+            block_timeout: DEFAULT_BLOCK_TIMEOUT,
+            dropped: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Wait at most `timeout` for room under `TickBufferPolicy::Block`
+    /// instead of `DEFAULT_BLOCK_TIMEOUT`
+    pub fn with_block_timeout(mut self, timeout: Duration) -> Self {
+        self.block_timeout = timeout;
+        self
+    }
+
+    pub fn push(&self, tick: Tick) -> Result<(), String> {
+        // A zero-capacity channel only hands ticks to a waiting receiver,
+        // so nothing could ever be buffered
+        if self.capacity == 0 {
+            return Err("Tick buffer has zero capacity".to_string());
+        }
+
+        match self.policy {
+            TickBufferPolicy::Block => {
+                self.sender
+                    .send_timeout(tick, self.block_timeout)
+                    .map_err(|e| match e {
+                        SendTimeoutError::Timeout(_) => format!(
+                            "Tick buffer full for {:?} (capacity {}); nothing is draining it",
+                            self.block_timeout, self.capacity
+                        ),
+                        SendTimeoutError::Disconnected(_) => "Tick buffer disconnected".to_string(),
+                    })
+            }
+            TickBufferPolicy::DropOldest => {
+                let mut tick = tick;
+                loop {
+                    match self.sender.try_send(tick) {
+                        Ok(()) => return Ok(()),
+                        Err(TrySendError::Full(rejected)) => {
+                            if self.receiver.try_recv().is_ok() {
+                                self.dropped.fetch_add(1, Ordering::Relaxed);
+                            }
+                            tick = rejected;
+                        }
+                        Err(TrySendError::Disconnected(_)) => {
+                            return Err("Tick buffer disconnected".to_string())
+                        }
+                    }
+                }
+            }
+            TickBufferPolicy::Error => self.sender.try_send(tick).map_err(|e| match e {
+                TrySendError::Full(_) => {
+                    format!("Tick buffer full (capacity {})", self.capacity)
+                }
+                TrySendError::Disconnected(_) => "Tick buffer disconnected".to_string(),
+            }),
+        }
+    }
+
+    /// Take every buffered tick in arrival order
+    pub fn drain(&self) -> Vec<Tick> {
+        self.receiver.try_iter().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.receiver.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.receiver.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn policy(&self) -> TickBufferPolicy {
+        self.policy
+    }
+
+    /// Ticks evicted by `DropOldest` since creation
+    pub fn dropped_count(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick(timestamp: i64) -> Tick {
+        Tick::new_with_millis("EURUSD".to_string(), timestamp, 1.0920, 1.0922)
+    }
+
+    #[test]
+    fn test_drop_oldest_evicts_and_counts() {
+        let buffer = TickBuffer::new(2, TickBufferPolicy::DropOldest);
+        for ts in 0..5 {
+            buffer.push(tick(ts)).unwrap();
+        }
+
+        assert_eq!(buffer.dropped_count(), 3);
+        let remaining: Vec<i64> = buffer.drain().iter().map(|t| t.timestamp).collect();
+        assert_eq!(remaining, vec![3, 4]);
+    }
+
+    #[test]
+    fn test_error_policy_rejects_when_full() {
+        let buffer = TickBuffer::new(1, TickBufferPolicy::Error);
+        buffer.push(tick(0)).unwrap();
+        assert!(buffer.push(tick(1)).is_err());
+        assert_eq!(buffer.dropped_count(), 0);
+        assert_eq!(buffer.len(), 1);
+    }
+
+    #[test]
+    fn test_block_waits_for_consumer() {
+        let buffer = TickBuffer::new(1, TickBufferPolicy::Block);
+        buffer.push(tick(0)).unwrap();
+
+        let consumer = buffer.clone();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            consumer.drain()
+        });
+
+        // Blocks until the consumer drains the first tick
+        buffer.push(tick(1)).unwrap();
+        let mut received = handle.join().unwrap();
+        received.extend(buffer.drain());
+
+        let timestamps: Vec<i64> = received.iter().map(|t| t.timestamp).collect();
+        assert_eq!(timestamps, vec![0, 1]);
+        assert_eq!(buffer.dropped_count(), 0);
+    }
+
+    #[test]
+    fn test_block_times_out_without_consumer() {
+        let buffer = TickBuffer::new(1, TickBufferPolicy::Block)
+            .with_block_timeout(Duration::from_millis(10));
+        buffer.push(tick(0)).unwrap();
+
+        // The caller is the only consumer, so waiting could never succeed
+        assert!(buffer.push(tick(1)).is_err());
+        assert_eq!(buffer.len(), 1);
+    }
+
+    #[test]
+    fn test_zero_capacity_rejects_every_policy() {
+        for policy in [
+            TickBufferPolicy::Block,
+            TickBufferPolicy::DropOldest,
+            TickBufferPolicy::Error,
+        ] {
+            assert!(TickBuffer::new(0, policy).push(tick(0)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_policy_from_str() {
+        assert_eq!(
+            "drop_oldest".parse::<TickBufferPolicy>(),
+            Ok(TickBufferPolicy::DropOldest)
+        );
+        assert_eq!(
+            "Block".parse::<TickBufferPolicy>(),
+            Ok(TickBufferPolicy::Block)
+        );
+        assert!("never".parse::<TickBufferPolicy>().is_err());
+    }
+}
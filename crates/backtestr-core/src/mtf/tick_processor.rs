@@ -1,5 +1,25 @@
 use backtestr_data::{Bar, Tick};
 use std::time::Instant;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ProcessError {
+    #[error("Out-of-order tick for {symbol}: {current} arrived after {previous}")]
+    OutOfOrderTick {
+        symbol: String,
+        previous: i64,
+        current: i64,
+    },
+
+    #[error("{0}")]
+    Rejected(String),
+}
+
+impl From<String> for ProcessError {
+    fn from(message: String) -> Self {
+        ProcessError::Rejected(message)
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct TickProcessor {
@@ -131,6 +131,13 @@ impl TimeframeState {
             .collect()
     }
 
+    /// Replace the completed bars, oldest first, keeping the newest within
+    /// the history limit
+    pub(crate) fn restore_completed_bars(&mut self, bars: Vec<Bar>) {
+        let skip = bars.len().saturating_sub(self.history_limit);
+        self.completed_bars = bars.into_iter().skip(skip).collect();
+    }
+
     pub fn get_completion_percentage(&self) -> f32 {
         self.current_bar
             .as_ref()
@@ -2,12 +2,17 @@
 
 use super::compression::compress_data;
 use super::serialization::{
-    CheckpointData, CheckpointMetadata, MTFStateSnapshot, CHECKPOINT_VERSION,
+    CheckpointData, CheckpointMetadata, IndicatorSnapshot, MTFStateSnapshot, PositionsSnapshot,
+    CHECKPOINT_VERSION,
 };
 use super::validation::calculate_checksum;
+use crate::indicators::IndicatorPipeline;
 use crate::mtf::MTFStateManager;
+use crate::positions::PositionManager;
 use anyhow::{Context, Result};
+use backtestr_data::Timeframe;
 use chrono::Utc;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
 use tokio::fs;
@@ -28,6 +33,28 @@ pub struct CheckpointManager {
     last_checkpoint: Instant,
     tick_count_since_checkpoint: u64,
     backtest_id: String,
+    sequence: u64,
+    delta: Option<DeltaTracking>,
+}
+
+/// Bookkeeping for incremental checkpoints
+struct DeltaTracking {
+    baseline_interval: usize,
+    baseline_id: Option<String>,
+    parent_id: Option<String>,
+    deltas_since_baseline: usize,
+    /// Files of the current baseline and its diffs, protected from cleanup
+    chain_paths: Vec<PathBuf>,
+    versions: HashMap<(String, Timeframe), StateVersion>,
+}
+
+/// Cheap fingerprint of a `TimeframeState` used to detect changes
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct StateVersion {
+    bar_start_time: i64,
+    tick_count: u32,
+    completed_bars: usize,
+    current_bar: Option<(u32, u64, i64)>,
 }
 
 impl CheckpointManager {
@@ -48,9 +75,28 @@ impl CheckpointManager {
             last_checkpoint: Instant::now(),
             tick_count_since_checkpoint: 0,
             backtest_id: uuid::Uuid::new_v4().to_string(),
+            sequence: 0,
+            delta: None,
         })
     }
 
+    /// Write incremental checkpoints.
+    ///
+    /// After a full baseline, checkpoints only carry the symbol/timeframe
+    /// states that changed since the previous checkpoint. A new baseline is
+    /// written after every `baseline_interval` diffs.
+    pub fn with_delta_checkpoints(mut self, baseline_interval: usize) -> Self {
+        self.delta = Some(DeltaTracking {
+            baseline_interval,
+            baseline_id: None,
+            parent_id: None,
+            deltas_since_baseline: 0,
+            chain_paths: Vec::new(),
+            versions: HashMap::new(),
+        });
+        self
+    }
+
     pub fn should_checkpoint(&self) -> Option<CheckpointTrigger> {
         if self.last_checkpoint.elapsed() >= self.checkpoint_interval {
             return Some(CheckpointTrigger::TimeElapsed);
@@ -71,8 +117,49 @@ impl CheckpointManager {
         &mut self,
         state: &MTFStateManager,
         tick_count: u64,
+    ) -> Result<PathBuf> {
+        self.write_checkpoint(state, HashMap::new(), None, tick_count)
+            .await
+    }
+
+    /// Create a checkpoint that also captures the pipeline's indicator state
+    pub async fn create_checkpoint_with_indicators(
+        &mut self,
+        state: &MTFStateManager,
+        pipeline: &IndicatorPipeline,
+        tick_count: u64,
+    ) -> Result<PathBuf> {
+        self.write_checkpoint(state, pipeline.snapshot_all(), None, tick_count)
+            .await
+    }
+
+    /// Create a checkpoint that also captures the positions and account of
+    /// `positions`, restored with `PositionManager::restore_positions`
+    pub async fn create_checkpoint_with_positions(
+        &mut self,
+        state: &MTFStateManager,
+        positions: &PositionManager,
+        tick_count: u64,
+    ) -> Result<PathBuf> {
+        self.write_checkpoint(
+            state,
+            HashMap::new(),
+            Some(positions.snapshot_positions()?),
+            tick_count,
+        )
+        .await
+    }
+
+    async fn write_checkpoint(
+        &mut self,
+        state: &MTFStateManager,
+        indicator_states: HashMap<String, IndicatorSnapshot>,
+        positions: Option<PositionsSnapshot>,
+        tick_count: u64,
     ) -> Result<PathBuf> {
         let snapshot = state.create_snapshot()?;
+        let versions = state_versions(state);
+        let checkpoint_id = uuid::Uuid::new_v4().to_string();
 
         let metadata = CheckpointMetadata {
             created_at: Utc::now().timestamp_millis(),
@@ -82,16 +169,30 @@ impl CheckpointManager {
             engine_version: env!("CARGO_PKG_VERSION").to_string(),
         };
 
-        let checkpoint_data = CheckpointData {
+        let mut checkpoint_data = CheckpointData {
             version: CHECKPOINT_VERSION,
             timestamp: Utc::now().timestamp_millis(),
             tick_count,
             mtf_state: snapshot,
-            indicator_states: Default::default(), // TODO: Get from state
+            indicator_states,
             metadata,
             checksum: 0,
+            checkpoint_id: checkpoint_id.clone(),
+            base_checkpoint_id: None,
+            parent_checkpoint_id: None,
+            changed_states: HashMap::new(),
+            removed_symbols: Vec::new(),
+            positions,
         };
 
+        if let Some(delta) = &self.delta {
+            if let Some(base_id) = &delta.baseline_id {
+                if delta.deltas_since_baseline < delta.baseline_interval {
+                    make_delta(&mut checkpoint_data, delta, &versions, base_id.clone());
+                }
+            }
+        }
+
         // Serialize with bincode (checksum will be calculated separately)
         let serialized = bincode::serialize(&checkpoint_data)?;
 
@@ -106,11 +207,13 @@ impl CheckpointManager {
         let mut final_data = compressed;
         final_data.extend_from_slice(&checksum.to_le_bytes());
 
-        // Generate filename
+        // Generate filename; the sequence keeps checkpoints within one second apart
+        self.sequence += 1;
         let filename = format!(
-            "checkpoint_{}_{}.btck",
+            "checkpoint_{}_{}_{:06}.btck",
             self.backtest_id,
-            Utc::now().format("%Y%m%d_%H%M%S")
+            Utc::now().format("%Y%m%d_%H%M%S"),
+            self.sequence
         );
         let checkpoint_path = self.checkpoint_dir.join(&filename);
 
@@ -130,6 +233,19 @@ impl CheckpointManager {
 
         fs::rename(&temp_path, &checkpoint_path).await?;
 
+        if let Some(delta) = &mut self.delta {
+            if checkpoint_data.is_delta() {
+                delta.deltas_since_baseline += 1;
+            } else {
+                delta.baseline_id = Some(checkpoint_id.clone());
+                delta.deltas_since_baseline = 0;
+                delta.chain_paths.clear();
+            }
+            delta.chain_paths.push(checkpoint_path.clone());
+            delta.parent_id = Some(checkpoint_id);
+            delta.versions = versions;
+        }
+
         // Cleanup old checkpoints
         self.cleanup_old_checkpoints().await?;
 
@@ -175,7 +291,13 @@ impl CheckpointManager {
         checkpoints.sort_by_key(|&(_, time)| time);
         let to_remove = checkpoints.len() - self.max_checkpoints;
 
-        for (path, _) in checkpoints.iter().take(to_remove) {
+        // Removing part of the live chain would leave later diffs unrecoverable
+        let chain = self.delta.as_ref().map(|delta| &delta.chain_paths);
+        let removable = checkpoints
+            .iter()
+            .filter(|(path, _)| !chain.is_some_and(|chain| chain.contains(path)));
+
+        for (path, _) in removable.take(to_remove) {
             fs::remove_file(path).await?;
         }
 
@@ -198,6 +320,79 @@ impl CheckpointManager {
     }
 }
 
+/// Reduce a full checkpoint to the states that changed since the parent
+fn make_delta(
+    checkpoint: &mut CheckpointData,
+    delta: &DeltaTracking,
+    versions: &HashMap<(String, Timeframe), StateVersion>,
+    base_id: String,
+) {
+    let changed: Vec<(String, Timeframe)> = versions
+        .iter()
+        .filter(|(key, version)| delta.versions.get(*key) != Some(*version))
+        .map(|(key, _)| key.clone())
+        .chain(
+            delta
+                .versions
+                .keys()
+                .filter(|key| !versions.contains_key(*key))
+                .cloned(),
+        )
+        .collect();
+
+    let snapshot = &mut checkpoint.mtf_state;
+    let changed_symbols: HashSet<String> =
+        changed.iter().map(|(symbol, _)| symbol.clone()).collect();
+    let removed_symbols: HashSet<&String> = delta
+        .versions
+        .keys()
+        .map(|(symbol, _)| symbol)
+        .filter(|symbol| !snapshot.symbol_states.contains_key(*symbol))
+        .collect();
+    checkpoint.removed_symbols = removed_symbols.into_iter().cloned().collect();
+
+    checkpoint.changed_states = changed
+        .into_iter()
+        .map(|key| {
+            let partial = snapshot.partial_bars.get(&key).cloned();
+            (key, partial)
+        })
+        .collect();
+    snapshot.partial_bars.clear();
+    snapshot
+        .symbol_states
+        .retain(|symbol, _| changed_symbols.contains(symbol));
+    snapshot
+        .completed_bar_ids
+        .retain(|(symbol, _), _| changed_symbols.contains(symbol));
+
+    checkpoint.base_checkpoint_id = Some(base_id);
+    checkpoint.parent_checkpoint_id = delta.parent_id.clone();
+}
+
+fn state_versions(state: &MTFStateManager) -> HashMap<(String, Timeframe), StateVersion> {
+    let mut versions = HashMap::new();
+
+    for symbol in state.get_all_symbols() {
+        if let Some(symbol_state) = state.get_symbol_state(&symbol) {
+            for (timeframe, tf_state) in &symbol_state.timeframes {
+                let version = StateVersion {
+                    bar_start_time: tf_state.bar_start_time,
+                    tick_count: tf_state.tick_count,
+                    completed_bars: tf_state.completed_bars.len(),
+                    current_bar: tf_state
+                        .current_bar
+                        .as_ref()
+                        .map(|bar| (bar.tick_count, bar.close.to_bits(), bar.volume)),
+                };
+                versions.insert((symbol.clone(), *timeframe), version);
+            }
+        }
+    }
+
+    versions
+}
+
 fn calculate_total_bars(snapshot: &MTFStateSnapshot) -> usize {
     snapshot
         .symbol_states
@@ -10,8 +10,8 @@ pub mod serialization;
 pub mod validation;
 
 pub use checkpoint_manager::{CheckpointManager, CheckpointTrigger};
-pub use recovery::StateRecovery;
-pub use serialization::{CheckpointData, MTFStateSnapshot};
+pub use recovery::{RecoveryError, StateRecovery};
+pub use serialization::{CheckpointData, IndicatorSnapshot, MTFStateSnapshot, PositionsSnapshot};
 pub use validation::ChecksumValidator;
 
 use std::path::PathBuf;
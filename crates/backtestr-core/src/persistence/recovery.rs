@@ -2,14 +2,36 @@
 
 use super::compression::decompress_data;
 use super::serialization::{CheckpointData, CHECKPOINT_VERSION};
-use super::validation::calculate_checksum;
+use super::validation::{calculate_checksum, ChecksumValidator};
 use crate::mtf::MTFStateManager;
-use anyhow::{bail, Context, Result};
-use std::path::Path;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
 use tokio::fs;
+use tracing::warn;
+
+/// Reasons a checkpoint file cannot be trusted
+#[derive(Debug, Error)]
+pub enum RecoveryError {
+    #[error("Checkpoint file is empty: {0}")]
+    EmptyFile(PathBuf),
+
+    #[error("Checkpoint file too small to contain checksum: {0}")]
+    Truncated(PathBuf),
+
+    #[error("Checkpoint checksum mismatch: expected {expected}, got {actual}")]
+    ChecksumMismatch { expected: u64, actual: u64 },
+
+    #[error("Incompatible checkpoint version: expected {expected}, got {found}")]
+    IncompatibleVersion { expected: u32, found: u32 },
+
+    #[error("Corrupt checkpoint {path}: {reason}")]
+    Corrupt { path: PathBuf, reason: String },
+}
 
 pub struct StateRecovery {
-    checkpoint_dir: std::path::PathBuf,
+    checkpoint_dir: PathBuf,
 }
 
 impl StateRecovery {
@@ -19,14 +41,28 @@ impl StateRecovery {
         }
     }
 
+    /// Recover from the newest checkpoint that passes validation.
+    ///
+    /// Corrupt checkpoints are skipped in favour of older ones. Returns
+    /// `Ok(None)` only when the directory holds no checkpoints at all; if
+    /// every checkpoint is corrupt the newest one's error is returned.
     pub async fn recover_state(&self) -> Result<Option<(MTFStateManager, u64)>> {
-        let checkpoint_path = match self.find_latest_valid_checkpoint().await? {
-            Some(path) => path,
-            None => return Ok(None),
-        };
+        let mut first_error = None;
+
+        for path in self.checkpoints_newest_first().await? {
+            match self.load_checkpoint(&path).await {
+                Ok(recovered) => return Ok(Some(recovered)),
+                Err(e) => {
+                    warn!("Skipping checkpoint {}: {:#}", path.display(), e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
 
-        let (state, tick_count) = self.load_checkpoint(&checkpoint_path).await?;
-        Ok(Some((state, tick_count)))
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(None),
+        }
     }
 
     pub async fn recover_from_specific(
@@ -36,50 +72,75 @@ impl StateRecovery {
         self.load_checkpoint(checkpoint_file).await
     }
 
-    async fn load_checkpoint(&self, path: &Path) -> Result<(MTFStateManager, u64)> {
-        // Read checkpoint file with checksum
-        let file_data = fs::read(path)
-            .await
-            .context("Failed to read checkpoint file")?;
+    /// Read and validate a checkpoint without reconstructing engine state.
+    ///
+    /// Use this to restore components held outside the MTF state manager,
+    /// e.g. `IndicatorPipeline::restore_all(&data.indicator_states)` or
+    /// `PositionManager::restore_positions` with `data.positions`.
+    ///
+    /// Incremental checkpoints are resolved against their baseline, so the
+    /// result always describes the full state.
+    pub async fn load_checkpoint_data(&self, path: &Path) -> Result<CheckpointData> {
+        let checkpoint = self.read_checkpoint(path).await?;
+        self.resolve_delta_chain(checkpoint).await
+    }
 
-        // Extract checksum from the last 8 bytes
-        if file_data.len() < 8 {
-            bail!("Checkpoint file too small to contain checksum");
-        }
-        let (compressed, checksum_bytes) = file_data.split_at(file_data.len() - 8);
-        let stored_checksum = u64::from_le_bytes(
-            checksum_bytes
-                .try_into()
-                .context("Failed to read checksum")?,
-        );
-
-        // Decompress
-        let decompressed =
-            decompress_data(compressed).context("Failed to decompress checkpoint")?;
-
-        // Validate checksum
-        let calculated_checksum = calculate_checksum(&decompressed);
-        if calculated_checksum != stored_checksum {
-            bail!(
-                "Checkpoint checksum validation failed: expected {}, got {}",
-                stored_checksum,
-                calculated_checksum
-            );
+    /// Apply the chain of diffs between a checkpoint's baseline and itself.
+    ///
+    /// If any intermediate diff is missing the chain cannot be trusted and
+    /// the baseline alone is returned.
+    async fn resolve_delta_chain(&self, target: CheckpointData) -> Result<CheckpointData> {
+        let base_id = match &target.base_checkpoint_id {
+            Some(base_id) => base_id.clone(),
+            None => return Ok(target),
+        };
+
+        let mut baseline = None;
+        let mut deltas_by_parent = HashMap::new();
+        for path in self.checkpoints_newest_first().await? {
+            let checkpoint = match self.read_checkpoint(&path).await {
+                Ok(checkpoint) => checkpoint,
+                Err(_) => continue,
+            };
+            if checkpoint.checkpoint_id == base_id {
+                baseline = Some(checkpoint);
+            } else if checkpoint.base_checkpoint_id.as_ref() == Some(&base_id) {
+                if let Some(parent) = checkpoint.parent_checkpoint_id.clone() {
+                    deltas_by_parent.insert(parent, checkpoint);
+                }
+            }
         }
 
-        // Deserialize
-        let checkpoint: CheckpointData =
-            bincode::deserialize(&decompressed).context("Failed to deserialize checkpoint")?;
-
-        // Validate version
-        if checkpoint.version != CHECKPOINT_VERSION {
-            bail!(
-                "Incompatible checkpoint version: expected {}, got {}",
-                CHECKPOINT_VERSION,
-                checkpoint.version
-            );
+        let baseline =
+            baseline.with_context(|| format!("Baseline checkpoint {} is missing", base_id))?;
+        let mut resolved = baseline.clone();
+
+        while resolved.checkpoint_id != target.checkpoint_id {
+            match deltas_by_parent.remove(&resolved.checkpoint_id) {
+                Some(delta) => resolved.apply_delta(delta),
+                None => {
+                    warn!(
+                        "Checkpoint chain from {} is broken after {}; falling back to baseline",
+                        base_id, resolved.checkpoint_id
+                    );
+                    return Ok(baseline);
+                }
+            }
         }
 
+        Ok(resolved)
+    }
+
+    async fn read_checkpoint(&self, path: &Path) -> Result<CheckpointData> {
+        let file_data = fs::read(path)
+            .await
+            .context("Failed to read checkpoint file")?;
+        Ok(decode_checkpoint(path, &file_data)?)
+    }
+
+    async fn load_checkpoint(&self, path: &Path) -> Result<(MTFStateManager, u64)> {
+        let checkpoint = self.load_checkpoint_data(path).await?;
+
         // Reconstruct state
         let mut state = MTFStateManager::with_default_config();
         state
@@ -92,7 +153,7 @@ impl StateRecovery {
         Ok((state, checkpoint.tick_count))
     }
 
-    async fn find_latest_valid_checkpoint(&self) -> Result<Option<std::path::PathBuf>> {
+    async fn checkpoints_newest_first(&self) -> Result<Vec<PathBuf>> {
         let mut entries = fs::read_dir(&self.checkpoint_dir).await?;
         let mut checkpoints = Vec::new();
 
@@ -104,45 +165,8 @@ impl StateRecovery {
             }
         }
 
-        // Sort by modification time (newest first)
-        checkpoints.sort_by(|a, b| b.1.cmp(&a.1));
-
-        // Try each checkpoint until we find a valid one
-        for (path, _) in checkpoints {
-            if self.validate_checkpoint(&path).await.is_ok() {
-                return Ok(Some(path));
-            }
-        }
-
-        Ok(None)
-    }
-
-    async fn validate_checkpoint(&self, path: &Path) -> Result<()> {
-        let file_data = fs::read(path).await?;
-
-        // Extract checksum from the last 8 bytes
-        if file_data.len() < 8 {
-            bail!("Checkpoint file too small");
-        }
-        let (compressed, checksum_bytes) = file_data.split_at(file_data.len() - 8);
-        let stored_checksum = u64::from_le_bytes(checksum_bytes.try_into()?);
-
-        // Decompress
-        let decompressed = decompress_data(compressed)?;
-
-        // Validate checksum
-        let calculated_checksum = calculate_checksum(&decompressed);
-        if calculated_checksum != stored_checksum {
-            bail!("Invalid checksum");
-        }
-
-        // Deserialize and validate version
-        let checkpoint: CheckpointData = bincode::deserialize(&decompressed)?;
-        if checkpoint.version != CHECKPOINT_VERSION {
-            bail!("Invalid version");
-        }
-
-        Ok(())
+        checkpoints.sort_by_key(|(_, time)| std::cmp::Reverse(*time));
+        Ok(checkpoints.into_iter().map(|(path, _)| path).collect())
     }
 
     pub async fn list_available_checkpoints(&self) -> Result<Vec<CheckpointInfo>> {
@@ -158,21 +182,13 @@ impl StateRecovery {
             }
         }
 
-        checkpoints.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        checkpoints.sort_by_key(|c| std::cmp::Reverse(c.created_at));
         Ok(checkpoints)
     }
 
     async fn get_checkpoint_info(&self, path: &Path) -> Result<CheckpointInfo> {
         let file_data = fs::read(path).await?;
-
-        // Extract checksum from the last 8 bytes
-        if file_data.len() < 8 {
-            bail!("Checkpoint file too small");
-        }
-        let (compressed, _) = file_data.split_at(file_data.len() - 8);
-
-        let decompressed = decompress_data(compressed)?;
-        let checkpoint: CheckpointData = bincode::deserialize(&decompressed)?;
+        let checkpoint = decode_checkpoint(path, &file_data)?;
 
         Ok(CheckpointInfo {
             path: path.to_path_buf(),
@@ -186,6 +202,57 @@ impl StateRecovery {
     }
 }
 
+/// Verify and decode the bytes of a checkpoint file.
+///
+/// The file is the compressed checkpoint followed by the little-endian
+/// checksum of the uncompressed bytes; nothing is deserialized until the
+/// checksum matches.
+fn decode_checkpoint(path: &Path, file_data: &[u8]) -> Result<CheckpointData, RecoveryError> {
+    if file_data.is_empty() {
+        return Err(RecoveryError::EmptyFile(path.to_path_buf()));
+    }
+    if file_data.len() < 8 {
+        return Err(RecoveryError::Truncated(path.to_path_buf()));
+    }
+
+    let corrupt = |reason: String| RecoveryError::Corrupt {
+        path: path.to_path_buf(),
+        reason,
+    };
+
+    let (compressed, checksum_bytes) = file_data.split_at(file_data.len() - 8);
+    let mut stored = [0u8; 8];
+    stored.copy_from_slice(checksum_bytes);
+    let stored_checksum = u64::from_le_bytes(stored);
+
+    let decompressed =
+        decompress_data(compressed).map_err(|e| corrupt(format!("decompression failed: {}", e)))?;
+
+    if !ChecksumValidator::verify(&decompressed, stored_checksum) {
+        return Err(RecoveryError::ChecksumMismatch {
+            expected: stored_checksum,
+            actual: calculate_checksum(&decompressed),
+        });
+    }
+
+    // `version` is the leading field, so it can be read before the rest of
+    // the layout, which may differ between versions
+    let version: u32 = bincode::deserialize(&decompressed)
+        .map_err(|e| corrupt(format!("deserialization failed: {}", e)))?;
+    if version != CHECKPOINT_VERSION {
+        return Err(RecoveryError::IncompatibleVersion {
+            expected: CHECKPOINT_VERSION,
+            found: version,
+        });
+    }
+
+    let mut checkpoint: CheckpointData = bincode::deserialize(&decompressed)
+        .map_err(|e| corrupt(format!("deserialization failed: {}", e)))?;
+
+    checkpoint.checksum = stored_checksum;
+    Ok(checkpoint)
+}
+
 #[derive(Debug, Clone)]
 pub struct CheckpointInfo {
     pub path: std::path::PathBuf,
@@ -210,4 +277,34 @@ mod tests {
         let result = recovery.recover_state().await.unwrap();
         assert!(result.is_none());
     }
+
+    #[test]
+    fn test_empty_file_is_corrupt() {
+        let err = decode_checkpoint(Path::new("empty.btck"), &[]).unwrap_err();
+        assert!(matches!(err, RecoveryError::EmptyFile(_)));
+
+        let err = decode_checkpoint(Path::new("short.btck"), &[1, 2, 3]).unwrap_err();
+        assert!(matches!(err, RecoveryError::Truncated(_)));
+    }
+
+    #[test]
+    fn test_checksum_checked_before_deserializing() {
+        let payload = b"not a checkpoint".to_vec();
+        let mut file_data = super::super::compression::compress_data(&payload, 3).unwrap();
+        file_data.extend_from_slice(&(calculate_checksum(&payload) + 1).to_le_bytes());
+
+        let err = decode_checkpoint(Path::new("bad.btck"), &file_data).unwrap_err();
+        assert!(matches!(err, RecoveryError::ChecksumMismatch { .. }));
+    }
+
+    #[test]
+    fn test_older_version_rejected_before_decoding() {
+        let mut payload = bincode::serialize(&(CHECKPOINT_VERSION - 1)).unwrap();
+        payload.extend_from_slice(b"older checkpoint layout");
+        let mut file_data = super::super::compression::compress_data(&payload, 3).unwrap();
+        file_data.extend_from_slice(&calculate_checksum(&payload).to_le_bytes());
+
+        let err = decode_checkpoint(Path::new("old.btck"), &file_data).unwrap_err();
+        assert!(matches!(err, RecoveryError::IncompatibleVersion { .. }));
+    }
 }
@@ -1,11 +1,20 @@
 //! State serialization for MTF engine components
 
 use crate::mtf::{MTFStateManager, PartialBar, SymbolMTFState};
+use crate::positions::{Account, Order, Position, PositionRequest};
 use backtestr_data::{Bar, Tick, Timeframe};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use uuid::Uuid;
 
-pub const CHECKPOINT_VERSION: u32 = 1;
+/// Bumped whenever the bincode layout of `CheckpointData` changes,
+/// including through `Tick`, `Bar` and `Position`. Version 2 added
+/// `IndicatorSnapshot::state` and the diff checkpoint fields; version 3
+/// added `positions`; version 4 added `Tick::last` and `Tick::last_size`; version
+/// 5 added pending orders and queued requests to `PositionsSnapshot`;
+/// version 6 keeps each symbol's completed bars and last tick; version 7
+/// added the random source state to `PositionsSnapshot`.
+pub const CHECKPOINT_VERSION: u32 = 7;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CheckpointData {
@@ -17,6 +26,71 @@ pub struct CheckpointData {
     pub metadata: CheckpointMetadata,
     #[serde(skip)]
     pub checksum: u64,
+    pub checkpoint_id: String,
+    /// Full checkpoint a diff builds on; `None` for a full checkpoint
+    pub base_checkpoint_id: Option<String>,
+    /// Checkpoint immediately preceding a diff in its chain
+    pub parent_checkpoint_id: Option<String>,
+    /// Partial bars changed since the parent checkpoint. `None` marks a
+    /// symbol/timeframe whose partial bar no longer exists.
+    pub changed_states: HashMap<(String, Timeframe), Option<PartialBarSnapshot>>,
+    /// Symbols removed since the parent checkpoint; empty in a full
+    /// checkpoint
+    pub removed_symbols: Vec<String>,
+    /// Positions and account of a `PositionManager`, when one was captured.
+    /// Always complete, also in a diff.
+    pub positions: Option<PositionsSnapshot>,
+}
+
+impl CheckpointData {
+    pub fn is_delta(&self) -> bool {
+        self.base_checkpoint_id.is_some()
+    }
+
+    /// Fold the next diff of the chain into this checkpoint.
+    ///
+    /// Afterwards `self` describes the full state as of `delta`.
+    pub fn apply_delta(&mut self, delta: CheckpointData) {
+        let partial_bars = &mut self.mtf_state.partial_bars;
+        for (key, partial) in delta.changed_states {
+            match partial {
+                Some(partial) => {
+                    partial_bars.insert(key, partial);
+                }
+                None => {
+                    partial_bars.remove(&key);
+                }
+            }
+        }
+
+        for symbol in &delta.removed_symbols {
+            self.mtf_state.symbol_states.remove(symbol);
+            self.mtf_state
+                .partial_bars
+                .retain(|(bar_symbol, _), _| bar_symbol != symbol);
+            self.mtf_state
+                .completed_bar_ids
+                .retain(|(bar_symbol, _), _| bar_symbol != symbol);
+        }
+
+        self.mtf_state
+            .symbol_states
+            .extend(delta.mtf_state.symbol_states);
+        self.mtf_state
+            .completed_bar_ids
+            .extend(delta.mtf_state.completed_bar_ids);
+        self.mtf_state.current_tick = delta.mtf_state.current_tick;
+        self.mtf_state.last_processed_timestamp = delta.mtf_state.last_processed_timestamp;
+        self.indicator_states.extend(delta.indicator_states);
+        self.positions = delta.positions;
+
+        self.timestamp = delta.timestamp;
+        self.tick_count = delta.tick_count;
+        self.metadata = delta.metadata;
+        self.checkpoint_id = delta.checkpoint_id;
+        self.base_checkpoint_id = delta.base_checkpoint_id;
+        self.parent_checkpoint_id = delta.parent_checkpoint_id;
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,9 +105,11 @@ pub struct MTFStateSnapshot {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SymbolMTFStateSnapshot {
     pub symbol: String,
-    pub timeframe_bars: HashMap<Timeframe, Option<Bar>>,
+    /// Completed bars still held per timeframe, oldest first
+    pub timeframe_bars: HashMap<Timeframe, Vec<Bar>>,
     pub bar_counts: HashMap<Timeframe, usize>,
     pub last_tick_timestamp: i64,
+    pub current_tick: Option<Tick>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +133,29 @@ pub struct IndicatorSnapshot {
     pub values: Vec<f64>,
     pub parameters: HashMap<String, f64>,
     pub last_update: i64,
+    /// Opaque internal state from `Indicator::serialize_state`
+    pub state: Vec<u8>,
+}
+
+/// Every position a `PositionManager` tracks, with its account and the
+/// orders and requests still waiting to fill
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PositionsSnapshot {
+    pub positions: Vec<Position>,
+    pub account: Account,
+    /// Orders waiting for their trigger, including bracket exits already
+    /// attached to an open position
+    pub pending_orders: Vec<Order>,
+    /// Exit orders of bracket entries that have not filled yet, by entry
+    /// order id
+    pub bracket_exits: Vec<(Uuid, Vec<Order>)>,
+    /// Requests waiting for a free slot, with the order id they are
+    /// reported under
+    pub queued_requests: Vec<(Uuid, PositionRequest)>,
+    /// Seed of the random source for fills and ids
+    pub seed: u64,
+    /// Words drawn so far from the fill and id streams of `seed`
+    pub rng_word_pos: (u128, u128),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,32 +173,30 @@ impl MTFStateManager {
         let symbols = self.get_all_symbols();
 
         let mut symbol_states = HashMap::new();
+        let mut partial_bars = HashMap::new();
         for symbol in symbols {
             if let Some(state) = self.get_symbol_state(&symbol) {
+                for (&timeframe, tf_state) in &state.timeframes {
+                    if let Some(bar) = &tf_state.current_bar {
+                        let snapshot = PartialBarSnapshot {
+                            symbol: symbol.clone(),
+                            timeframe,
+                            open: bar.open,
+                            high: bar.high,
+                            low: bar.low,
+                            close: bar.close,
+                            volume: bar.volume as u64,
+                            tick_count: bar.tick_count,
+                            start_time: tf_state.bar_start_time,
+                            last_update: tf_state.bar_start_time + bar.milliseconds_elapsed,
+                        };
+                        partial_bars.insert((symbol.clone(), timeframe), snapshot);
+                    }
+                }
                 symbol_states.insert(symbol, state.to_snapshot());
             }
         }
 
-        let partial_bars = self
-            .get_internal_partial_bars()
-            .into_iter()
-            .map(|((symbol, tf), bar)| {
-                let snapshot = PartialBarSnapshot {
-                    symbol: symbol.clone(),
-                    timeframe: tf,
-                    open: bar.open,
-                    high: bar.high,
-                    low: bar.low,
-                    close: bar.close,
-                    volume: bar.volume as u64,
-                    tick_count: bar.tick_count,
-                    start_time: chrono::Utc::now().timestamp_millis() - bar.milliseconds_elapsed,
-                    last_update: chrono::Utc::now().timestamp_millis(),
-                };
-                ((symbol, tf), snapshot)
-            })
-            .collect();
-
         Ok(MTFStateSnapshot {
             current_tick: self.get_internal_current_tick(),
             symbol_states,
@@ -109,18 +206,35 @@ impl MTFStateManager {
         })
     }
 
+    /// Replace the bar state of every symbol with `snapshot`'s.
+    ///
+    /// Nothing changes unless the whole snapshot applies: a timeframe this
+    /// manager does not track, or a partial bar without its symbol's state,
+    /// fails the restore.
     pub fn restore_from_snapshot(
         &mut self,
         snapshot: MTFStateSnapshot,
     ) -> Result<(), anyhow::Error> {
+        let mut states = HashMap::new();
         for (symbol, state_snapshot) in snapshot.symbol_states {
-            self.restore_symbol_state(symbol, state_snapshot)?;
+            let state = self.restore_symbol_state(&symbol, state_snapshot)?;
+            states.insert(symbol, state);
         }
 
         for ((symbol, timeframe), partial) in snapshot.partial_bars {
-            self.restore_partial_bar(symbol, timeframe, partial)?;
+            let state = states.get_mut(&symbol).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Partial {} bar for {} has no symbol state",
+                    timeframe,
+                    symbol
+                )
+            })?;
+            Self::restore_partial_bar(state, timeframe, partial)?;
         }
 
+        self.replace_symbol_states(states)
+            .map_err(anyhow::Error::msg)?;
+
         self.set_completed_bar_ids(snapshot.completed_bar_ids);
         self.set_last_timestamp(snapshot.last_processed_timestamp);
 
@@ -131,23 +245,6 @@ impl MTFStateManager {
         Ok(())
     }
 
-    fn get_internal_partial_bars(&self) -> HashMap<(String, Timeframe), PartialBar> {
-        // Use public API to get partial bars
-        let mut partial_bars = HashMap::new();
-
-        for symbol in self.get_all_symbols() {
-            if let Some(state) = self.get_symbol_state(&symbol) {
-                for (timeframe, partial_opt) in state.get_all_partial_bars() {
-                    if let Some(partial) = partial_opt {
-                        partial_bars.insert((symbol.clone(), timeframe), partial);
-                    }
-                }
-            }
-        }
-
-        partial_bars
-    }
-
     fn get_internal_completed_bar_ids(&self) -> HashMap<(String, Timeframe), Vec<i64>> {
         HashMap::new() // TODO: Implement
     }
@@ -161,20 +258,57 @@ impl MTFStateManager {
     }
 
     fn restore_symbol_state(
-        &mut self,
-        _symbol: String,
-        _snapshot: SymbolMTFStateSnapshot,
-    ) -> Result<(), anyhow::Error> {
-        Ok(()) // TODO: Implement
+        &self,
+        symbol: &str,
+        snapshot: SymbolMTFStateSnapshot,
+    ) -> Result<SymbolMTFState, anyhow::Error> {
+        let mut state = self.new_symbol_state(symbol);
+        state.current_tick = snapshot.current_tick;
+        state.last_update = snapshot.last_tick_timestamp;
+
+        for (timeframe, bars) in snapshot.timeframe_bars {
+            let tf_state = state.timeframes.get_mut(&timeframe).ok_or_else(|| {
+                anyhow::anyhow!("{} is not an enabled timeframe for {}", timeframe, symbol)
+            })?;
+            tf_state.restore_completed_bars(bars);
+        }
+
+        Ok(state)
     }
 
+    /// Resume the partial bar of `timeframe` in `state` where the snapshot
+    /// left it
     fn restore_partial_bar(
-        &mut self,
-        _symbol: String,
-        _timeframe: Timeframe,
-        _snapshot: PartialBarSnapshot,
+        state: &mut SymbolMTFState,
+        timeframe: Timeframe,
+        snapshot: PartialBarSnapshot,
     ) -> Result<(), anyhow::Error> {
-        Ok(()) // TODO: Implement
+        let tf_state = state.timeframes.get_mut(&timeframe).ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} is not an enabled timeframe for {}",
+                timeframe,
+                snapshot.symbol
+            )
+        })?;
+
+        let bar_end = timeframe.bar_end_timestamp(snapshot.start_time);
+        let mut bar = PartialBar::new(
+            snapshot.open,
+            snapshot.volume as i64,
+            snapshot.last_update,
+            snapshot.start_time,
+            bar_end,
+        );
+        bar.high = snapshot.high;
+        bar.low = snapshot.low;
+        bar.close = snapshot.close;
+        bar.tick_count = snapshot.tick_count;
+
+        tf_state.bar_start_time = snapshot.start_time;
+        tf_state.bar_end_time = bar_end;
+        tf_state.tick_count = snapshot.tick_count;
+        tf_state.current_bar = Some(bar);
+        Ok(())
     }
 
     fn set_completed_bar_ids(&mut self, _ids: HashMap<(String, Timeframe), Vec<i64>>) {
@@ -200,10 +334,21 @@ impl MTFStateManager {
 impl SymbolMTFState {
     fn to_snapshot(&self) -> SymbolMTFStateSnapshot {
         SymbolMTFStateSnapshot {
-            symbol: String::new(), // TODO: Get from actual state
-            timeframe_bars: HashMap::new(),
-            bar_counts: HashMap::new(),
-            last_tick_timestamp: 0,
+            symbol: self.symbol.clone(),
+            timeframe_bars: self
+                .timeframes
+                .iter()
+                .map(|(&timeframe, state)| {
+                    (timeframe, state.completed_bars.iter().cloned().collect())
+                })
+                .collect(),
+            bar_counts: self
+                .timeframes
+                .iter()
+                .map(|(&timeframe, state)| (timeframe, state.completed_bars.len()))
+                .collect(),
+            last_tick_timestamp: self.last_update,
+            current_tick: self.current_tick.clone(),
         }
     }
 }
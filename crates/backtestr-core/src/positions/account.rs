@@ -0,0 +1,228 @@
+use super::error::{PositionError, Result};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Balance a `PositionManager` starts from unless configured otherwise
+pub const DEFAULT_STARTING_BALANCE: f64 = 10_000.0;
+/// Currency the account is held in when no `PnlCalculator` says otherwise
+pub const DEFAULT_ACCOUNT_CURRENCY: &str = "USD";
+/// Margin level, in percent, below which a margin call is raised
+pub const DEFAULT_MARGIN_CALL_LEVEL: f64 = 100.0;
+/// Leverage for symbols without their own setting, unless configured
+/// otherwise
+pub const DEFAULT_LEVERAGE: f64 = 100.0;
+
+/// Live account figures, in the account currency
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Account {
+    /// Starting balance plus the net realized P&L of every close
+    pub balance: f64,
+    /// `balance` plus the floating P&L of open positions
+    pub equity: f64,
+    /// Margin held by open positions
+    pub used_margin: f64,
+    /// `equity - used_margin`, what new positions can draw on
+    pub free_margin: f64,
+    /// `equity / used_margin` in percent; `None` with no margin in use
+    pub margin_level: Option<f64>,
+}
+
+impl Account {
+    pub fn new(balance: f64) -> Self {
+        Self {
+            balance,
+            equity: balance,
+            used_margin: 0.0,
+            free_margin: balance,
+            margin_level: None,
+        }
+    }
+
+    fn refresh(&mut self, floating_pnl: f64, used_margin: f64) {
+        self.equity = self.balance + floating_pnl;
+        self.used_margin = used_margin;
+        self.free_margin = self.equity - used_margin;
+        self.margin_level = (used_margin > 0.0).then(|| self.equity / used_margin * 100.0);
+    }
+}
+
+impl Default for Account {
+    fn default() -> Self {
+        Self::new(DEFAULT_STARTING_BALANCE)
+    }
+}
+
+/// The account shared by a `PositionManager` and its clones, with the
+/// margin call threshold and whether a call is currently raised
+#[derive(Debug, Clone)]
+pub(crate) struct AccountTracker {
+    account: Arc<RwLock<Account>>,
+    margin_call_level: f64,
+    /// Set while the margin level is below the threshold, so each breach
+    /// raises one call
+    in_margin_call: Arc<AtomicBool>,
+}
+
+impl AccountTracker {
+    pub(crate) fn new(starting_balance: f64, margin_call_level: f64) -> Self {
+        Self {
+            account: Arc::new(RwLock::new(Account::new(starting_balance))),
+            margin_call_level,
+            in_margin_call: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> Account {
+        self.account
+            .read()
+            .map(|account| *account)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn margin_call_level(&self) -> f64 {
+        self.margin_call_level
+    }
+
+    /// Replace the account with one restored from a checkpoint. A margin
+    /// call already raised for it is not raised again.
+    pub(crate) fn restore(&self, restored: Account) {
+        if let Ok(mut account) = self.account.write() {
+            *account = restored;
+        }
+        let breached = restored
+            .margin_level
+            .is_some_and(|level| level < self.margin_call_level);
+        self.in_margin_call.store(breached, Ordering::SeqCst);
+    }
+
+    pub(crate) fn book(&self, realized_pnl: f64) {
+        if let Ok(mut account) = self.account.write() {
+            account.balance += realized_pnl;
+        }
+    }
+
+    /// Recompute equity and margin, returning the account if this update
+    /// took the margin level below the threshold
+    pub(crate) fn refresh(&self, floating_pnl: f64, used_margin: f64) -> Option<Account> {
+        let account = {
+            let mut account = self.account.write().ok()?;
+            account.refresh(floating_pnl, used_margin);
+            *account
+        };
+
+        let breached = account
+            .margin_level
+            .is_some_and(|level| level < self.margin_call_level);
+        let was_breached = self.in_margin_call.swap(breached, Ordering::SeqCst);
+        (breached && !was_breached).then_some(account)
+    }
+}
+
+impl Default for AccountTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_STARTING_BALANCE, DEFAULT_MARGIN_CALL_LEVEL)
+    }
+}
+
+/// Leverage per symbol with a fallback for the rest, shared by a
+/// `PositionManager` and its clones
+#[derive(Debug, Clone)]
+pub(crate) struct LeverageTable {
+    default: f64,
+    per_symbol: Arc<DashMap<String, f64>>,
+}
+
+impl LeverageTable {
+    pub(crate) fn validate(leverage: f64) -> Result<f64> {
+        if leverage.is_finite() && leverage > 0.0 {
+            Ok(leverage)
+        } else {
+            Err(PositionError::InvalidLeverage(leverage))
+        }
+    }
+
+    pub(crate) fn with_default(&self, leverage: f64) -> Result<Self> {
+        Ok(Self {
+            default: Self::validate(leverage)?,
+            per_symbol: self.per_symbol.clone(),
+        })
+    }
+
+    pub(crate) fn default_leverage(&self) -> f64 {
+        self.default
+    }
+
+    pub(crate) fn set(&self, symbol: &str, leverage: f64) -> Result<()> {
+        self.per_symbol
+            .insert(symbol.to_string(), Self::validate(leverage)?);
+        Ok(())
+    }
+
+    pub(crate) fn get(&self, symbol: &str) -> f64 {
+        self.per_symbol
+            .get(symbol)
+            .map(|leverage| *leverage)
+            .unwrap_or(self.default)
+    }
+}
+
+impl Default for LeverageTable {
+    fn default() -> Self {
+        Self {
+            default: DEFAULT_LEVERAGE,
+            per_symbol: Arc::new(DashMap::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refresh_figures() {
+        let tracker = AccountTracker::new(1_000.0, 50.0);
+        tracker.book(100.0);
+        assert!(tracker.refresh(-50.0, 200.0).is_none());
+
+        let account = tracker.snapshot();
+        assert_eq!(account.balance, 1_100.0);
+        assert_eq!(account.equity, 1_050.0);
+        assert_eq!(account.free_margin, 850.0);
+        assert_eq!(account.margin_level, Some(525.0));
+
+        tracker.refresh(0.0, 0.0);
+        assert_eq!(tracker.snapshot().margin_level, None);
+    }
+
+    #[test]
+    fn test_margin_call_raised_once_per_breach() {
+        let tracker = AccountTracker::new(1_000.0, 100.0);
+        assert!(tracker.refresh(-950.0, 100.0).is_some());
+        assert!(tracker.refresh(-960.0, 100.0).is_none());
+
+        // Recovers, then breaches again
+        assert!(tracker.refresh(0.0, 100.0).is_none());
+        let call = tracker.refresh(-950.0, 100.0).unwrap();
+        assert_eq!(call.margin_level, Some(50.0));
+    }
+
+    #[test]
+    fn test_leverage_falls_back_to_default() {
+        let table = LeverageTable::default().with_default(50.0).unwrap();
+        table.set("EURUSD", 30.0).unwrap();
+        assert_eq!(table.get("EURUSD"), 30.0);
+        assert_eq!(table.get("US500"), 50.0);
+
+        for invalid in [0.0, -20.0, f64::NAN] {
+            assert!(matches!(
+                table.set("US500", invalid),
+                Err(PositionError::InvalidLeverage(_))
+            ));
+        }
+        assert!(LeverageTable::default().with_default(0.0).is_err());
+        assert_eq!(table.get("US500"), 50.0);
+    }
+}
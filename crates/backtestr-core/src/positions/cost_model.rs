@@ -0,0 +1,79 @@
+use super::{Position, PositionSide};
+
+/// Units of base currency in one standard lot
+pub const LOT_SIZE: f64 = 100_000.0;
+
+const MS_PER_DAY: f64 = 86_400_000.0;
+
+/// Trading costs deducted from realized P&L when a position closes.
+///
+/// All amounts are in the quote currency per standard lot. Commission is
+/// charged on both the open and the close. Swap rates are charged per day
+/// held; a negative rate is a credit.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CostModel {
+    pub commission_per_lot: f64,
+    pub swap_long: f64,
+    pub swap_short: f64,
+}
+
+impl CostModel {
+    pub fn new(commission_per_lot: f64, swap_long: f64, swap_short: f64) -> Self {
+        Self {
+            commission_per_lot,
+            swap_long,
+            swap_short,
+        }
+    }
+
+    /// Round-trip commission for `quantity` units
+    pub fn commission(&self, quantity: f64) -> f64 {
+        2.0 * self.commission_per_lot * quantity / LOT_SIZE
+    }
+
+    /// Swap accrued on `quantity` units held for `held_ms` milliseconds
+    pub fn swap(&self, side: PositionSide, quantity: f64, held_ms: i64) -> f64 {
+        let rate = match side {
+            PositionSide::Long => self.swap_long,
+            PositionSide::Short => self.swap_short,
+        };
+        let days = held_ms.max(0) as f64 / MS_PER_DAY;
+        rate * quantity / LOT_SIZE * days
+    }
+
+    /// (commission, swap) for a position closed at `closed_at`
+    pub fn costs_for(&self, position: &Position, closed_at: i64) -> (f64, f64) {
+        (
+            self.commission(position.quantity),
+            self.swap(
+                position.side,
+                position.quantity,
+                closed_at - position.opened_at,
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_commission() {
+        let model = CostModel::new(3.5, 0.0, 0.0);
+        assert!((model.commission(100_000.0) - 7.0).abs() < 1e-9);
+        assert!((model.commission(50_000.0) - 3.5).abs() < 1e-9);
+        assert_eq!(CostModel::default().commission(100_000.0), 0.0);
+    }
+
+    #[test]
+    fn test_swap_accrues_per_day_by_side() {
+        let model = CostModel::new(0.0, 6.0, -2.0);
+        let two_days = 2 * 86_400_000;
+
+        assert!((model.swap(PositionSide::Long, 100_000.0, two_days) - 12.0).abs() < 1e-9);
+        assert!((model.swap(PositionSide::Short, 100_000.0, two_days) + 4.0).abs() < 1e-9);
+        // Partial days accrue proportionally
+        assert!((model.swap(PositionSide::Long, 100_000.0, 43_200_000) - 3.0).abs() < 1e-9);
+    }
+}
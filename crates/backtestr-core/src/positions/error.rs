@@ -0,0 +1,63 @@
+use super::{PositionSide, PositionState};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum PositionError {
+    #[error("Position not found: {0}")]
+    NotFound(Uuid),
+
+    #[error("Invalid state transition: {from} -> {to}")]
+    StateTransition {
+        from: PositionState,
+        to: PositionState,
+    },
+
+    #[error("Position {id} is {state}, not open")]
+    NotOpen { id: Uuid, state: PositionState },
+
+    #[error("Position limit reached for {symbol}: {limit} open")]
+    LimitReached { symbol: String, limit: usize },
+
+    #[error("Fills blocked for {symbol} until {until}")]
+    FillsBlocked { symbol: String, until: i64 },
+
+    #[error("Invalid quantity: {0}")]
+    InvalidQuantity(f64),
+
+    #[error("Invalid price: {0}")]
+    InvalidPrice(f64),
+
+    #[error("Invalid stop distance: {0}")]
+    InvalidStopDistance(f64),
+
+    #[error("Invalid risk percentage: {0}")]
+    InvalidRisk(f64),
+
+    #[error("Invalid currency pair symbol: {0}")]
+    InvalidSymbol(String),
+
+    #[error("No conversion rate from {from} to {to}")]
+    MissingRate { from: String, to: String },
+
+    #[error("No account currency configured for P&L conversion")]
+    NoAccountCurrency,
+
+    #[error("Invalid bracket: take-profit {take_profit} and stop {stop} are on the wrong sides for a {side:?} entry")]
+    InvalidBracket {
+        side: PositionSide,
+        take_profit: f64,
+        stop: f64,
+    },
+
+    #[error("Bracket entry on {symbol} would net against the open position")]
+    BracketWouldNet { symbol: String },
+
+    #[error("Invalid leverage: {0}")]
+    InvalidLeverage(f64),
+
+    #[error("Insufficient margin: {required} required, {available} free")]
+    InsufficientMargin { required: f64, available: f64 },
+}
+
+pub type Result<T> = std::result::Result<T, PositionError>;
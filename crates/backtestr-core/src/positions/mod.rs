@@ -1 +1,41 @@
-pub struct Placeholder;
+//! Position management for Epic 3
+//!
+//! Tracks any number of concurrent positions per symbol with unique ids,
+//! independent stop/target parameters and optional parent-child links.
+
+mod account;
+mod cost_model;
+mod error;
+mod order;
+mod pnl_calculator;
+mod position;
+mod position_manager;
+mod position_sizer;
+mod position_state;
+mod report;
+mod slippage;
+mod statistics;
+mod trade_event;
+
+pub use account::{
+    Account, DEFAULT_ACCOUNT_CURRENCY, DEFAULT_LEVERAGE, DEFAULT_MARGIN_CALL_LEVEL,
+    DEFAULT_STARTING_BALANCE,
+};
+pub use cost_model::{CostModel, LOT_SIZE};
+pub use error::{PositionError, Result};
+pub use order::{Order, OrderType, TriggerFillPolicy};
+pub use pnl_calculator::{EquityPoint, PnlCalculator};
+pub use position::{Position, PositionSide};
+pub use position_manager::{
+    Bracket, CloseReason, NettingMode, OpenOutcome, OverflowPolicy, PendingClose, PositionLimit,
+    PositionManager, PositionRequest,
+};
+pub use position_sizer::PositionSizer;
+pub use position_state::{PositionState, StateValidator};
+pub use report::PerformanceReport;
+pub use slippage::{
+    ExecutionModel, FixedPips, NoSlippage, OrderSide, RandomPips, SlippageModel,
+    SpreadProportional, DEFAULT_FILL_SEED, REALISTIC_SPREAD_FRACTION,
+};
+pub use statistics::PositionStatistics;
+pub use trade_event::TradeEvent;
@@ -0,0 +1,205 @@
+use super::error::{PositionError, Result};
+use super::{OrderSide, PositionSide};
+use backtestr_data::Tick;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderType {
+    /// Fills on the next tick at the touch
+    Market,
+    /// Fills at the trigger or better: buys at or below, sells at or above
+    Limit,
+    /// Fills once price breaks through the trigger: buys at or above,
+    /// sells at or below
+    Stop,
+}
+
+/// Price a pending order fills at when a tick gaps through its trigger
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TriggerFillPolicy {
+    /// Fill at the trigger price
+    #[default]
+    AtTrigger,
+    /// Fill at the tick's touch price: the ask for buys, the bid for sells
+    AtTickPrice,
+}
+
+/// An order waiting in `PositionManager` for price to reach its trigger
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Order {
+    pub id: Uuid,
+    pub symbol: String,
+    pub side: PositionSide,
+    pub order_type: OrderType,
+    /// Ignored for market orders
+    pub trigger_price: f64,
+    pub quantity: f64,
+    /// Orders sharing a group cancel each other: once one fills, the rest
+    /// are withdrawn
+    pub oco_group: Option<Uuid>,
+    /// Position this order closes when it fills; `None` for orders that
+    /// open a position
+    pub closes_position: Option<Uuid>,
+}
+
+impl Order {
+    pub fn new(
+        symbol: String,
+        side: PositionSide,
+        order_type: OrderType,
+        trigger_price: f64,
+        quantity: f64,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            symbol,
+            side,
+            order_type,
+            trigger_price,
+            quantity,
+            oco_group: None,
+            closes_position: None,
+        }
+    }
+
+    pub fn with_oco_group(mut self, group: Uuid) -> Self {
+        self.oco_group = Some(group);
+        self
+    }
+
+    /// Close `position_id` when the order fills instead of opening a new
+    /// position
+    pub fn closing(mut self, position_id: Uuid) -> Self {
+        self.closes_position = Some(position_id);
+        self
+    }
+
+    pub fn market(symbol: String, side: PositionSide, quantity: f64) -> Self {
+        Self::new(symbol, side, OrderType::Market, 0.0, quantity)
+    }
+
+    pub fn limit(symbol: String, side: PositionSide, trigger_price: f64, quantity: f64) -> Self {
+        Self::new(symbol, side, OrderType::Limit, trigger_price, quantity)
+    }
+
+    pub fn stop(symbol: String, side: PositionSide, trigger_price: f64, quantity: f64) -> Self {
+        Self::new(symbol, side, OrderType::Stop, trigger_price, quantity)
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        if !self.quantity.is_finite() || self.quantity <= 0.0 {
+            return Err(PositionError::InvalidQuantity(self.quantity));
+        }
+        if self.order_type != OrderType::Market
+            && (!self.trigger_price.is_finite() || self.trigger_price <= 0.0)
+        {
+            return Err(PositionError::InvalidPrice(self.trigger_price));
+        }
+        Ok(())
+    }
+
+    /// Fill price if `tick` reaches the order, or `None` if it stays pending.
+    ///
+    /// Buys are checked against the ask and sells against the bid.
+    pub fn fill_price(&self, tick: &Tick, policy: TriggerFillPolicy) -> Option<f64> {
+        let side = OrderSide::entry(self.side);
+        let touch = match side {
+            OrderSide::Buy => tick.ask,
+            OrderSide::Sell => tick.bid,
+        };
+
+        let triggered = match (self.order_type, side) {
+            (OrderType::Market, _) => return Some(touch),
+            (OrderType::Limit, OrderSide::Buy) | (OrderType::Stop, OrderSide::Sell) => {
+                touch <= self.trigger_price
+            }
+            (OrderType::Limit, OrderSide::Sell) | (OrderType::Stop, OrderSide::Buy) => {
+                touch >= self.trigger_price
+            }
+        };
+        if !triggered {
+            return None;
+        }
+
+        Some(match policy {
+            TriggerFillPolicy::AtTrigger => self.trigger_price,
+            TriggerFillPolicy::AtTickPrice => touch,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick(bid: f64, ask: f64) -> Tick {
+        Tick::new_with_millis("EURUSD".to_string(), 0, bid, ask)
+    }
+
+    #[test]
+    fn test_limit_and_stop_triggers() {
+        let limit_buy = Order::limit("EURUSD".to_string(), PositionSide::Long, 1.1000, 1_000.0);
+        assert_eq!(
+            limit_buy.fill_price(&tick(1.1000, 1.1002), TriggerFillPolicy::AtTrigger),
+            None
+        );
+        assert_eq!(
+            limit_buy.fill_price(&tick(1.0998, 1.1000), TriggerFillPolicy::AtTrigger),
+            Some(1.1000)
+        );
+
+        let stop_buy = Order::stop("EURUSD".to_string(), PositionSide::Long, 1.1010, 1_000.0);
+        assert_eq!(
+            stop_buy.fill_price(&tick(1.1000, 1.1002), TriggerFillPolicy::AtTrigger),
+            None
+        );
+        assert_eq!(
+            stop_buy.fill_price(&tick(1.1008, 1.1010), TriggerFillPolicy::AtTrigger),
+            Some(1.1010)
+        );
+
+        let limit_sell = Order::limit("EURUSD".to_string(), PositionSide::Short, 1.1010, 1_000.0);
+        assert!(limit_sell
+            .fill_price(&tick(1.1010, 1.1012), TriggerFillPolicy::AtTrigger)
+            .is_some());
+        let stop_sell = Order::stop("EURUSD".to_string(), PositionSide::Short, 1.0990, 1_000.0);
+        assert!(stop_sell
+            .fill_price(&tick(1.1000, 1.1002), TriggerFillPolicy::AtTrigger)
+            .is_none());
+    }
+
+    #[test]
+    fn test_trigger_fill_policy() {
+        let stop_buy = Order::stop("EURUSD".to_string(), PositionSide::Long, 1.1010, 1_000.0);
+        let gap = tick(1.1048, 1.1050);
+
+        assert_eq!(
+            stop_buy.fill_price(&gap, TriggerFillPolicy::AtTrigger),
+            Some(1.1010)
+        );
+        assert_eq!(
+            stop_buy.fill_price(&gap, TriggerFillPolicy::AtTickPrice),
+            Some(1.1050)
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(
+            Order::market("EURUSD".to_string(), PositionSide::Long, 1_000.0)
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            Order::limit("EURUSD".to_string(), PositionSide::Long, 0.0, 1_000.0)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            Order::limit("EURUSD".to_string(), PositionSide::Long, 1.1, -1.0)
+                .validate()
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,377 @@
+use super::error::{PositionError, Result};
+use super::{PerformanceReport, Position};
+use std::collections::{BTreeSet, HashMap};
+
+/// Account equity after the trades closed at `timestamp`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EquityPoint {
+    pub timestamp: i64,
+    pub equity: f64,
+    /// Distance below the running equity peak, zero or positive
+    pub drawdown: f64,
+    /// `drawdown` as a percentage of the running peak
+    pub drawdown_pct: f64,
+}
+
+/// Converts position P&L from the quote currency into the account currency.
+///
+/// Rates are keyed by six-letter pair symbols (`"EURUSD"`, `"USDJPY"`, ...)
+/// and quote the price of the base in the quote currency. A conversion uses
+/// the direct pair, its inverse, or a cross through one intermediate
+/// currency, in that order. When several intermediates link both sides,
+/// the alphabetically first is used, so results don't depend on map order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PnlCalculator {
+    account_currency: String,
+}
+
+impl PnlCalculator {
+    pub fn new(account_currency: impl Into<String>) -> Self {
+        Self {
+            account_currency: account_currency.into().to_uppercase(),
+        }
+    }
+
+    pub fn account_currency(&self) -> &str {
+        &self.account_currency
+    }
+
+    /// Realized P&L for closed positions, unrealized P&L for open ones,
+    /// in the quote currency
+    pub fn calculate_pnl(&self, position: &Position) -> f64 {
+        if position.is_open() {
+            position.unrealized_pnl
+        } else {
+            position.realized_pnl
+        }
+    }
+
+    /// P&L of `position` converted into the account currency
+    pub fn calculate_pnl_in_account_ccy(
+        &self,
+        position: &Position,
+        rates: &HashMap<String, f64>,
+    ) -> Result<f64> {
+        let (_, quote) = split_symbol(&position.symbol)?;
+        let rate = self.conversion_rate(quote, rates)?;
+        Ok(self.calculate_pnl(position) * rate)
+    }
+
+    /// Equity and running drawdown after each close, starting from
+    /// `starting_balance`.
+    ///
+    /// The first point is the starting balance at the earliest open time.
+    /// Positions closed at the same timestamp are booked together as one
+    /// point, so timestamps are strictly increasing. Realized P&L is summed
+    /// as stored; open positions are ignored.
+    pub fn equity_curve(
+        &self,
+        starting_balance: f64,
+        closed_positions: &[Position],
+    ) -> Vec<EquityPoint> {
+        let mut closed: Vec<&Position> = closed_positions
+            .iter()
+            .filter(|p| p.closed_at.is_some())
+            .collect();
+        closed.sort_by_key(|p| (p.closed_at, p.opened_at, p.id));
+
+        let start = closed.iter().map(|p| p.opened_at).min().unwrap_or(0);
+        let mut curve = vec![EquityPoint {
+            timestamp: start,
+            equity: starting_balance,
+            drawdown: 0.0,
+            drawdown_pct: 0.0,
+        }];
+
+        let mut equity = starting_balance;
+        let mut peak = starting_balance;
+        for (i, position) in closed.iter().enumerate() {
+            let timestamp = position.closed_at.unwrap_or_default();
+            equity += self.calculate_pnl(position);
+
+            // Book every close at this timestamp before emitting the point
+            let same_time_follows = closed
+                .get(i + 1)
+                .is_some_and(|next| next.closed_at == position.closed_at);
+            if same_time_follows {
+                continue;
+            }
+
+            peak = peak.max(equity);
+            let drawdown = peak - equity;
+            let point = EquityPoint {
+                timestamp,
+                equity,
+                drawdown,
+                drawdown_pct: if peak > 0.0 {
+                    drawdown / peak * 100.0
+                } else {
+                    0.0
+                },
+            };
+
+            match curve.last_mut() {
+                // A close at the start time replaces the starting point
+                Some(last) if last.timestamp == timestamp => *last = point,
+                _ => curve.push(point),
+            }
+        }
+
+        curve
+    }
+
+    /// Margin held by `quantity` units at `price` with `leverage`, in the
+    /// quote currency
+    pub fn calculate_margin_required(quantity: f64, price: f64, leverage: f64) -> f64 {
+        quantity.abs() * price / leverage
+    }
+
+    /// Longest time spent below a previous equity high, in milliseconds.
+    /// A drawdown still open at the end of the curve counts up to its last point.
+    pub fn max_drawdown_duration_ms(curve: &[EquityPoint]) -> i64 {
+        let Some(first) = curve.first() else {
+            return 0;
+        };
+
+        let mut peak = first.equity;
+        let mut peak_time = first.timestamp;
+        let mut longest = 0;
+        for point in curve {
+            if point.equity >= peak {
+                longest = longest.max(point.timestamp - peak_time);
+                peak = point.equity;
+                peak_time = point.timestamp;
+            }
+        }
+
+        let last = curve.last().map(|p| p.timestamp).unwrap_or(peak_time);
+        longest.max(last - peak_time)
+    }
+
+    /// Win rate, profit factor, drawdown and the other trade statistics of
+    /// `closed_positions`, starting from `starting_balance`. Open positions
+    /// are ignored.
+    pub fn build_report(
+        &self,
+        closed_positions: &[Position],
+        starting_balance: f64,
+    ) -> PerformanceReport {
+        PerformanceReport::build(self, closed_positions, starting_balance)
+    }
+
+    /// Amount of account currency per one unit of `currency`
+    pub fn conversion_rate(&self, currency: &str, rates: &HashMap<String, f64>) -> Result<f64> {
+        conversion_rate(currency, &self.account_currency, rates)
+    }
+}
+
+/// Amount of `account` currency per one unit of `currency`, directly or
+/// crossed through a currency both sides have a rate with
+pub(crate) fn conversion_rate(
+    currency: &str,
+    account: &str,
+    rates: &HashMap<String, f64>,
+) -> Result<f64> {
+    if currency == account {
+        return Ok(1.0);
+    }
+
+    if let Some(rate) = direct_rate(currency, account, rates) {
+        return Ok(rate);
+    }
+
+    // Cross through the first currency, in sorted order, that links both
+    // sides; walking the map directly would pick a route at random
+    let intermediates: BTreeSet<&str> = rates
+        .keys()
+        .filter_map(|symbol| split_symbol(symbol).ok())
+        .flat_map(|(base, quote)| [base, quote])
+        .filter(|&intermediate| intermediate != currency && intermediate != account)
+        .collect();
+    for intermediate in intermediates {
+        if let (Some(first), Some(second)) = (
+            direct_rate(currency, intermediate, rates),
+            direct_rate(intermediate, account, rates),
+        ) {
+            return Ok(first * second);
+        }
+    }
+
+    Err(PositionError::MissingRate {
+        from: currency.to_string(),
+        to: account.to_string(),
+    })
+}
+
+/// Split a six-letter pair symbol into (base, quote)
+pub(crate) fn split_symbol(symbol: &str) -> Result<(&str, &str)> {
+    if symbol.len() != 6 || !symbol.is_ascii() {
+        return Err(PositionError::InvalidSymbol(symbol.to_string()));
+    }
+    Ok(symbol.split_at(3))
+}
+
+/// Units of `to` per unit of `from`, from the pair or its inverse
+fn direct_rate(from: &str, to: &str, rates: &HashMap<String, f64>) -> Option<f64> {
+    let valid = |rate: &f64| rate.is_finite() && *rate > 0.0;
+
+    if let Some(rate) = rates.get(&format!("{}{}", from, to)).filter(|r| valid(r)) {
+        return Some(*rate);
+    }
+    rates
+        .get(&format!("{}{}", to, from))
+        .filter(|r| valid(r))
+        .map(|rate| 1.0 / rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::positions::PositionSide;
+
+    fn closed(symbol: &str, side: PositionSide, entry: f64, exit: f64) -> Position {
+        let mut position = Position::new(symbol.to_string(), side, 100_000.0, entry, 0);
+        position.close(exit, 60_000).unwrap();
+        position
+    }
+
+    fn rates(pairs: &[(&str, f64)]) -> HashMap<String, f64> {
+        pairs.iter().map(|(s, r)| (s.to_string(), *r)).collect()
+    }
+
+    #[test]
+    fn test_account_is_quote() {
+        let calc = PnlCalculator::new("USD");
+        let position = closed("EURUSD", PositionSide::Long, 1.1000, 1.1010);
+        let pnl = calc
+            .calculate_pnl_in_account_ccy(&position, &HashMap::new())
+            .unwrap();
+        assert!((pnl - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_account_is_base() {
+        let calc = PnlCalculator::new("USD");
+        // 10 pips on 100k USDJPY is 10,000 JPY
+        let position = closed("USDJPY", PositionSide::Long, 150.00, 150.10);
+        let pnl = calc
+            .calculate_pnl_in_account_ccy(&position, &rates(&[("USDJPY", 150.0)]))
+            .unwrap();
+        assert!((pnl - 10_000.0 / 150.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_cross_rate_via_intermediate() {
+        let calc = PnlCalculator::new("EUR");
+        let position = closed("GBPJPY", PositionSide::Short, 190.00, 189.90);
+        let rates = rates(&[("USDJPY", 150.0), ("EURUSD", 1.10)]);
+
+        // 10,000 JPY -> USD -> EUR
+        let pnl = calc
+            .calculate_pnl_in_account_ccy(&position, &rates)
+            .unwrap();
+        assert!((pnl - 10_000.0 / 150.0 / 1.10).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_cross_rate_route_is_deterministic() {
+        // JPY -> CHF links through both EUR and USD, and the two routes
+        // disagree; EUR sorts first
+        let pairs = [
+            ("EURJPY", 160.0),
+            ("EURCHF", 0.95),
+            ("USDJPY", 150.0),
+            ("USDCHF", 0.90),
+        ];
+        for _ in 0..32 {
+            // Each map gets its own hasher seed, so its key order varies
+            let rate = conversion_rate("JPY", "CHF", &rates(&pairs)).unwrap();
+            assert!((rate - 0.95 / 160.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_missing_rate_is_an_error() {
+        let calc = PnlCalculator::new("USD");
+        let position = closed("USDJPY", PositionSide::Long, 150.00, 150.10);
+        assert_eq!(
+            calc.calculate_pnl_in_account_ccy(&position, &rates(&[("EURUSD", 1.1)])),
+            Err(PositionError::MissingRate {
+                from: "JPY".to_string(),
+                to: "USD".to_string(),
+            })
+        );
+    }
+
+    fn trade(opened_at: i64, closed_at: i64, exit: f64) -> Position {
+        let mut position = Position::new(
+            "EURUSD".to_string(),
+            PositionSide::Long,
+            100_000.0,
+            1.1000,
+            opened_at,
+        );
+        position.close(exit, closed_at).unwrap();
+        position
+    }
+
+    #[test]
+    fn test_equity_curve_and_drawdown() {
+        let calc = PnlCalculator::new("USD");
+        let trades = vec![
+            trade(0, 3_000, 1.0980),
+            trade(0, 1_000, 1.1010),
+            trade(500, 3_000, 1.0990),
+            trade(1_000, 6_000, 1.1050),
+        ];
+
+        let curve = calc.equity_curve(10_000.0, &trades);
+        let equity: Vec<f64> = curve.iter().map(|p| p.equity.round()).collect();
+        let timestamps: Vec<i64> = curve.iter().map(|p| p.timestamp).collect();
+
+        // Both closes at 3_000 are booked as one point
+        assert_eq!(timestamps, vec![0, 1_000, 3_000, 6_000]);
+        assert_eq!(equity, vec![10_000.0, 10_100.0, 9_800.0, 10_300.0]);
+        assert!((curve[2].drawdown - 300.0).abs() < 1e-6);
+        assert!((curve[2].drawdown_pct - 300.0 / 10_100.0 * 100.0).abs() < 1e-6);
+        assert_eq!(curve[3].drawdown, 0.0);
+
+        // Under the 1_000 high until the new high at 6_000
+        assert_eq!(PnlCalculator::max_drawdown_duration_ms(&curve), 5_000);
+    }
+
+    #[test]
+    fn test_equity_curve_input_order_does_not_matter() {
+        let calc = PnlCalculator::new("USD");
+        let mut trades = vec![
+            trade(0, 2_000, 1.0990),
+            trade(0, 2_000, 1.1030),
+            trade(0, 4_000, 1.0950),
+        ];
+        let forward = calc.equity_curve(1_000.0, &trades);
+        trades.reverse();
+        assert_eq!(calc.equity_curve(1_000.0, &trades), forward);
+
+        // Still in drawdown at the end of the data
+        assert_eq!(PnlCalculator::max_drawdown_duration_ms(&forward), 2_000);
+    }
+
+    #[test]
+    fn test_empty_equity_curve() {
+        let curve = PnlCalculator::new("USD").equity_curve(5_000.0, &[]);
+        assert_eq!(curve.len(), 1);
+        assert_eq!(curve[0].equity, 5_000.0);
+        assert_eq!(curve[0].drawdown, 0.0);
+        assert_eq!(PnlCalculator::max_drawdown_duration_ms(&curve), 0);
+    }
+
+    #[test]
+    fn test_invalid_symbol() {
+        let calc = PnlCalculator::new("USD");
+        let position = closed("XAU", PositionSide::Long, 2000.0, 2001.0);
+        assert_eq!(
+            calc.calculate_pnl_in_account_ccy(&position, &HashMap::new()),
+            Err(PositionError::InvalidSymbol("XAU".to_string()))
+        );
+    }
+}
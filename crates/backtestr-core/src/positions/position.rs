@@ -0,0 +1,455 @@
+use super::error::{PositionError, Result};
+use super::{PositionState, StateValidator};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Direction of a position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PositionSide {
+    Long,
+    Short,
+}
+
+impl PositionSide {
+    /// +1.0 for longs, -1.0 for shorts
+    pub fn direction(&self) -> f64 {
+        match self {
+            PositionSide::Long => 1.0,
+            PositionSide::Short => -1.0,
+        }
+    }
+
+    pub fn opposite(&self) -> Self {
+        match self {
+            PositionSide::Long => PositionSide::Short,
+            PositionSide::Short => PositionSide::Long,
+        }
+    }
+}
+
+/// A single position with independent parameters.
+///
+/// Quantity is expressed in units of the base currency, so P&L is in units
+/// of the quote currency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub id: Uuid,
+    pub symbol: String,
+    pub side: PositionSide,
+    pub quantity: f64,
+    pub entry_price: f64,
+    pub current_price: f64,
+    pub stop_loss: Option<f64>,
+    pub take_profit: Option<f64>,
+    pub state: PositionState,
+    pub opened_at: i64,
+    pub closed_at: Option<i64>,
+    pub close_price: Option<f64>,
+    /// Realized P&L, net of any commission and swap applied on close
+    pub realized_pnl: f64,
+    pub unrealized_pnl: f64,
+    pub parent_id: Option<Uuid>,
+    pub commission: f64,
+    pub swap: f64,
+    /// Timestamp at or after which the position is force-closed
+    pub expires_at: Option<i64>,
+    /// Best unrealized P&L seen while open, never below zero
+    pub max_favorable_excursion: f64,
+    /// Worst unrealized P&L seen while open, never above zero
+    pub max_adverse_excursion: f64,
+}
+
+impl Position {
+    pub fn new(
+        symbol: String,
+        side: PositionSide,
+        quantity: f64,
+        entry_price: f64,
+        opened_at: i64,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            symbol,
+            side,
+            quantity,
+            entry_price,
+            current_price: entry_price,
+            stop_loss: None,
+            take_profit: None,
+            state: PositionState::Open,
+            opened_at,
+            closed_at: None,
+            close_price: None,
+            realized_pnl: 0.0,
+            unrealized_pnl: 0.0,
+            parent_id: None,
+            commission: 0.0,
+            swap: 0.0,
+            expires_at: None,
+            max_favorable_excursion: 0.0,
+            max_adverse_excursion: 0.0,
+        }
+    }
+
+    pub fn with_stop_loss(mut self, stop_loss: f64) -> Self {
+        self.stop_loss = Some(stop_loss);
+        self
+    }
+
+    pub fn with_take_profit(mut self, take_profit: f64) -> Self {
+        self.take_profit = Some(take_profit);
+        self
+    }
+
+    pub fn with_parent(mut self, parent_id: Uuid) -> Self {
+        self.parent_id = Some(parent_id);
+        self
+    }
+
+    pub fn with_expiry(mut self, expires_at: i64) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.state == PositionState::Open
+    }
+
+    /// P&L of the position if it were closed at `price`
+    pub fn pnl_at(&self, price: f64) -> f64 {
+        (price - self.entry_price) * self.quantity * self.side.direction()
+    }
+
+    /// Mark the position to a new market price
+    pub fn update_price(&mut self, price: f64) -> Result<()> {
+        if !StateValidator::can_modify(self.state) {
+            return Err(PositionError::StateTransition {
+                from: self.state,
+                to: self.state,
+            });
+        }
+
+        self.current_price = price;
+        if self.is_open() {
+            self.unrealized_pnl = self.pnl_at(price);
+            self.track_excursion(self.unrealized_pnl);
+        }
+        Ok(())
+    }
+
+    fn track_excursion(&mut self, pnl: f64) {
+        self.max_favorable_excursion = self.max_favorable_excursion.max(pnl);
+        self.max_adverse_excursion = self.max_adverse_excursion.min(pnl);
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    pub fn is_stop_loss_hit(&self, price: f64) -> bool {
+        match (self.stop_loss, self.side) {
+            (Some(stop), PositionSide::Long) => price <= stop,
+            (Some(stop), PositionSide::Short) => price >= stop,
+            (None, _) => false,
+        }
+    }
+
+    pub fn is_take_profit_hit(&self, price: f64) -> bool {
+        match (self.take_profit, self.side) {
+            (Some(target), PositionSide::Long) => price >= target,
+            (Some(target), PositionSide::Short) => price <= target,
+            (None, _) => false,
+        }
+    }
+
+    /// Close the position, returning the realized P&L
+    pub fn close(&mut self, price: f64, timestamp: i64) -> Result<f64> {
+        StateValidator::validate(self.state, PositionState::Closed)?;
+
+        // The exit itself may be the best or worst point of the trade
+        self.track_excursion(self.pnl_at(price));
+        self.current_price = price;
+        self.close_price = Some(price);
+        self.closed_at = Some(timestamp);
+        self.realized_pnl = self.pnl_at(price);
+        self.unrealized_pnl = 0.0;
+        self.state = PositionState::Closed;
+
+        Ok(self.realized_pnl)
+    }
+
+    /// Add `quantity` bought or sold at `price`, moving the entry to the
+    /// quantity-weighted average. `opened_at` is kept, and later P&L and
+    /// partial closes are measured from the averaged entry.
+    pub fn add(&mut self, quantity: f64, price: f64) -> Result<()> {
+        if !StateValidator::can_add(self.state) {
+            return Err(PositionError::NotOpen {
+                id: self.id,
+                state: self.state,
+            });
+        }
+
+        let total = self.quantity + quantity;
+        self.entry_price = (self.entry_price * self.quantity + price * quantity) / total;
+        self.quantity = total;
+        self.update_price(self.current_price)
+    }
+
+    /// Fill a pending position at `price`, opening it at `timestamp`
+    pub fn activate(&mut self, price: f64, timestamp: i64) -> Result<()> {
+        StateValidator::validate(self.state, PositionState::Open)?;
+
+        self.entry_price = price;
+        self.current_price = price;
+        self.opened_at = timestamp;
+        self.state = PositionState::Open;
+        Ok(())
+    }
+
+    /// Withdraw a position that never filled
+    pub fn cancel(&mut self) -> Result<()> {
+        StateValidator::validate(self.state, PositionState::Cancelled)?;
+
+        self.state = PositionState::Cancelled;
+        Ok(())
+    }
+
+    /// Deduct trading costs from the realized P&L, returning the net P&L
+    pub fn apply_costs(&mut self, commission: f64, swap: f64) -> f64 {
+        self.commission = commission;
+        self.swap = swap;
+        self.realized_pnl = self.gross_pnl() - commission - swap;
+        self.realized_pnl
+    }
+
+    /// Move `quantity` into a new open position with id `id`, scaling
+    /// the P&L and excursions of both parts by their share of the quantity
+    pub(crate) fn split_off(&mut self, quantity: f64, id: Uuid) -> Position {
+        let share = quantity / self.quantity;
+        let mut portion = self.clone();
+        portion.id = id;
+        portion.quantity = quantity;
+        portion.unrealized_pnl *= share;
+        portion.max_favorable_excursion *= share;
+        portion.max_adverse_excursion *= share;
+
+        self.quantity -= quantity;
+        self.unrealized_pnl *= 1.0 - share;
+        self.max_favorable_excursion *= 1.0 - share;
+        self.max_adverse_excursion *= 1.0 - share;
+        portion
+    }
+
+    /// Realized P&L before costs
+    pub fn gross_pnl(&self) -> f64 {
+        match self.close_price {
+            Some(price) => self.pnl_at(price),
+            None => 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_long_pnl() {
+        let mut position = Position::new(
+            "EURUSD".to_string(),
+            PositionSide::Long,
+            10_000.0,
+            1.1000,
+            1704067200000,
+        );
+
+        position.update_price(1.1050).unwrap();
+        assert!((position.unrealized_pnl - 50.0).abs() < 1e-9);
+
+        let pnl = position.close(1.1020, 1704067260000).unwrap();
+        assert!((pnl - 20.0).abs() < 1e-9);
+        assert_eq!(position.state, PositionState::Closed);
+        assert_eq!(position.unrealized_pnl, 0.0);
+    }
+
+    #[test]
+    fn test_short_pnl() {
+        let mut position = Position::new(
+            "EURUSD".to_string(),
+            PositionSide::Short,
+            10_000.0,
+            1.1000,
+            1704067200000,
+        );
+
+        let pnl = position.close(1.0950, 1704067260000).unwrap();
+        assert!((pnl - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_double_close_rejected() {
+        let mut position = Position::new(
+            "EURUSD".to_string(),
+            PositionSide::Long,
+            1_000.0,
+            1.1000,
+            1704067200000,
+        );
+
+        position.close(1.1000, 1704067260000).unwrap();
+        assert!(matches!(
+            position.close(1.1000, 1704067320000),
+            Err(PositionError::StateTransition { .. })
+        ));
+    }
+
+    #[test]
+    fn test_add_averages_entry() {
+        let mut short = Position::new(
+            "EURUSD".to_string(),
+            PositionSide::Short,
+            10_000.0,
+            1.1000,
+            1704067200000,
+        );
+        short.add(30_000.0, 1.0960).unwrap();
+        assert_eq!(short.quantity, 40_000.0);
+        assert!((short.entry_price - 1.0970).abs() < 1e-9);
+        assert_eq!(short.opened_at, 1704067200000);
+
+        // Half closed at 1.0950 books 20 pips on the averaged basis
+        let mut half = short.split_off(20_000.0, Uuid::new_v4());
+        let pnl = half.close(1.0950, 1704067260000).unwrap();
+        assert!((pnl - 40.0).abs() < 1e-9);
+        assert!((short.entry_price - 1.0970).abs() < 1e-9);
+
+        short.close(1.0950, 1704067260000).unwrap();
+        assert!(matches!(
+            short.add(1_000.0, 1.0950),
+            Err(PositionError::NotOpen {
+                state: PositionState::Closed,
+                ..
+            })
+        ));
+
+        // A long averages up the same way
+        let mut long = Position::new(
+            "EURUSD".to_string(),
+            PositionSide::Long,
+            10_000.0,
+            1.1000,
+            1704067200000,
+        );
+        long.add(30_000.0, 1.1040).unwrap();
+        assert!((long.entry_price - 1.1030).abs() < 1e-9);
+        let mut half = long.split_off(20_000.0, Uuid::new_v4());
+        let pnl = half.close(1.1050, 1704067260000).unwrap();
+        assert!((pnl - 40.0).abs() < 1e-9);
+        assert!((long.entry_price - 1.1030).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_only_pending_positions_cancel() {
+        let mut position = Position::new(
+            "EURUSD".to_string(),
+            PositionSide::Long,
+            1_000.0,
+            1.1000,
+            1704067200000,
+        );
+        assert_eq!(
+            position.cancel(),
+            Err(PositionError::StateTransition {
+                from: PositionState::Open,
+                to: PositionState::Cancelled,
+            })
+        );
+
+        position.state = PositionState::Pending;
+        position.cancel().unwrap();
+        assert_eq!(position.state, PositionState::Cancelled);
+        assert!(position.activate(1.1000, 1704067260000).is_err());
+        assert!(position.cancel().is_err());
+    }
+
+    #[test]
+    fn test_stop_and_target_hits() {
+        let long = Position::new(
+            "EURUSD".to_string(),
+            PositionSide::Long,
+            1_000.0,
+            1.1000,
+            1704067200000,
+        )
+        .with_stop_loss(1.0950)
+        .with_take_profit(1.1100);
+
+        assert!(long.is_stop_loss_hit(1.0950));
+        assert!(!long.is_stop_loss_hit(1.0960));
+        assert!(long.is_take_profit_hit(1.1100));
+
+        let short = Position::new(
+            "EURUSD".to_string(),
+            PositionSide::Short,
+            1_000.0,
+            1.1000,
+            1704067200000,
+        )
+        .with_stop_loss(1.1050)
+        .with_take_profit(1.0900);
+
+        assert!(short.is_stop_loss_hit(1.1060));
+        assert!(short.is_take_profit_hit(1.0890));
+        assert!(!short.is_take_profit_hit(1.0950));
+    }
+
+    #[test]
+    fn test_excursions_track_best_and_worst() {
+        let mut position = Position::new(
+            "EURUSD".to_string(),
+            PositionSide::Long,
+            10_000.0,
+            1.1000,
+            1704067200000,
+        );
+        assert_eq!(position.max_favorable_excursion, 0.0);
+        assert_eq!(position.max_adverse_excursion, 0.0);
+
+        for price in [1.0980, 1.1030, 1.1010] {
+            position.update_price(price).unwrap();
+        }
+        // Closes profitable but still records the 20 pip drawdown
+        let pnl = position.close(1.1020, 1704067260000).unwrap();
+        assert!(pnl > 0.0);
+        assert!((position.max_adverse_excursion + 20.0).abs() < 1e-9);
+        assert!((position.max_favorable_excursion - 30.0).abs() < 1e-9);
+
+        let bytes = bincode::serialize(&position).unwrap();
+        let restored: Position = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(
+            restored.max_adverse_excursion,
+            position.max_adverse_excursion
+        );
+    }
+
+    #[test]
+    fn test_expiry_survives_serialization() {
+        let position = Position::new(
+            "EURUSD".to_string(),
+            PositionSide::Long,
+            1_000.0,
+            1.1000,
+            1704067200000,
+        )
+        .with_expiry(1704070800000);
+
+        assert!(!position.is_expired(1704070799999));
+        assert!(position.is_expired(1704070800000));
+
+        // Checkpoints are bincode-encoded
+        let bytes = bincode::serialize(&position).unwrap();
+        let restored: Position = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(restored.expires_at, Some(1704070800000));
+        assert_eq!(restored, position);
+    }
+}
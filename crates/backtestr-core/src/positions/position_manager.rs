@@ -0,0 +1,3513 @@
+use super::account::{AccountTracker, LeverageTable, DEFAULT_ACCOUNT_CURRENCY};
+use super::error::{PositionError, Result};
+use super::pnl_calculator::{conversion_rate, split_symbol};
+use super::slippage::SeededRng;
+use super::trade_event::TradeListener;
+use super::{
+    Account, CostModel, ExecutionModel, Order, OrderSide, OrderType, PnlCalculator, Position,
+    PositionSide, PositionState, PositionStatistics, SlippageModel, StateValidator, TradeEvent,
+    TriggerFillPolicy,
+};
+use crate::events::{BarEvent, EventHandler, TickEvent};
+use crate::mtf::SpikeFilter;
+use crate::persistence::serialization::PositionsSnapshot;
+use crossbeam::queue::SegQueue;
+use dashmap::{DashMap, DashSet};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+
+/// Parameters for opening a new position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PositionRequest {
+    pub symbol: String,
+    pub side: PositionSide,
+    pub quantity: f64,
+    pub price: f64,
+    pub stop_loss: Option<f64>,
+    pub take_profit: Option<f64>,
+    pub parent_id: Option<Uuid>,
+    pub expires_at: Option<i64>,
+}
+
+impl PositionRequest {
+    pub fn new(symbol: String, side: PositionSide, quantity: f64, price: f64) -> Self {
+        Self {
+            symbol,
+            side,
+            quantity,
+            price,
+            stop_loss: None,
+            take_profit: None,
+            parent_id: None,
+            expires_at: None,
+        }
+    }
+
+    pub fn with_stop_loss(mut self, stop_loss: f64) -> Self {
+        self.stop_loss = Some(stop_loss);
+        self
+    }
+
+    pub fn with_take_profit(mut self, take_profit: f64) -> Self {
+        self.take_profit = Some(take_profit);
+        self
+    }
+
+    pub fn with_parent(mut self, parent_id: Uuid) -> Self {
+        self.parent_id = Some(parent_id);
+        self
+    }
+
+    /// Force-close the position once `close_expired` is called at or after `expires_at`
+    pub fn with_expiry(mut self, expires_at: i64) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !self.quantity.is_finite() || self.quantity <= 0.0 {
+            return Err(PositionError::InvalidQuantity(self.quantity));
+        }
+        if !self.price.is_finite() || self.price <= 0.0 {
+            return Err(PositionError::InvalidPrice(self.price));
+        }
+        Ok(())
+    }
+
+    fn into_position(self, id: Uuid, timestamp: i64) -> Position {
+        let mut position =
+            Position::new(self.symbol, self.side, self.quantity, self.price, timestamp);
+        position.id = id;
+        position.stop_loss = self.stop_loss;
+        position.take_profit = self.take_profit;
+        position.parent_id = self.parent_id;
+        position.expires_at = self.expires_at;
+        position
+    }
+}
+
+/// What happens to an open request once a symbol is at its position limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Fail with `PositionError::LimitReached`
+    Reject,
+    /// Hold the request until a slot frees up
+    Queue,
+}
+
+/// Maximum number of concurrently open positions per symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PositionLimit {
+    pub max_per_symbol: usize,
+    pub overflow: OverflowPolicy,
+}
+
+impl PositionLimit {
+    pub fn new(max_per_symbol: usize, overflow: OverflowPolicy) -> Self {
+        Self {
+            max_per_symbol,
+            overflow,
+        }
+    }
+}
+
+/// How opposite-side fills on the same symbol interact
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NettingMode {
+    /// Every fill opens its own position
+    #[default]
+    Hedging,
+    /// Fills offset the open position on the symbol, reducing, closing or
+    /// flipping it; same-side fills add to it at the average entry price
+    Netting,
+}
+
+/// Quantities closer than this are treated as fully offset
+const NETTING_EPSILON: f64 = 1e-9;
+
+/// Result of an `open_position` call
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OpenOutcome {
+    Opened(Uuid),
+    /// The request was queued; `queue_position` is 1-based. `order_id`
+    /// identifies it in a later `TradeEvent::OrderRejected`.
+    Queued {
+        order_id: Uuid,
+        queue_position: usize,
+    },
+    /// The fill was netted against the symbol's open position.
+    /// `position_id` is the open position afterwards, `None` when flat or
+    /// when the rest of a flip was queued for lack of a free slot.
+    Netted {
+        position_id: Option<Uuid>,
+        realized_pnl: f64,
+    },
+}
+
+impl OpenOutcome {
+    pub fn position_id(&self) -> Option<Uuid> {
+        match self {
+            OpenOutcome::Opened(id) => Some(*id),
+            OpenOutcome::Queued { .. } => None,
+            OpenOutcome::Netted { position_id, .. } => *position_id,
+        }
+    }
+}
+
+/// Why a pending close was triggered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseReason {
+    StopLoss,
+    TakeProfit,
+}
+
+/// A stop-loss or take-profit trigger waiting to be executed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PendingClose {
+    pub id: Uuid,
+    pub price: f64,
+    pub timestamp: i64,
+    pub reason: CloseReason,
+}
+
+/// Ids of the orders placed by `PositionManager::place_bracket`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bracket {
+    pub entry: Uuid,
+    pub take_profit: Uuid,
+    pub stop: Uuid,
+}
+
+/// Tracks any number of concurrent positions with O(1) lookup by id.
+///
+/// All operations take `&self`; state lives behind shared maps so the
+/// manager can be cloned cheaply and handed to event handlers.
+#[derive(Clone, Default)]
+pub struct PositionManager {
+    positions: Arc<DashMap<Uuid, Position>>,
+    /// Open position ids per symbol, in opening order
+    symbol_index: Arc<DashMap<String, Vec<Uuid>>>,
+    /// Child position ids per parent
+    hierarchy_index: Arc<DashMap<Uuid, Vec<Uuid>>>,
+    /// Requests waiting for a free slot, per symbol, with the order id
+    /// they are reported under
+    queued_requests: Arc<DashMap<String, VecDeque<(Uuid, PositionRequest)>>>,
+    limit: Option<PositionLimit>,
+    netting_mode: NettingMode,
+    /// Blocks new fills while a symbol is cooling down after a news spike
+    fill_guard: Option<Arc<RwLock<SpikeFilter>>>,
+    /// Commission and swap deducted from realized P&L on close
+    cost_model: CostModel,
+    trade_listener: Option<TradeListener>,
+    /// Converts floating P&L into the account currency
+    pnl_calculator: Option<PnlCalculator>,
+    /// Stop/target triggers found in `on_tick`, closed by `process_pending_closes`
+    pending_closes: Arc<SegQueue<PendingClose>>,
+    /// Ids currently in `pending_closes`, so a trigger is queued only once
+    pending_ids: Arc<DashSet<Uuid>>,
+    /// Adjusts entry and exit prices; fills are idealized when unset
+    slippage: Option<Arc<dyn SlippageModel>>,
+    /// Random source for randomized slippage models and for the ids of
+    /// positions and orders
+    rng: SeededRng,
+    /// Latest (bid, ask) per symbol seen in `on_tick`
+    quotes: Arc<DashMap<String, (f64, f64)>>,
+    /// Latest mark or fill price per symbol, kept after positions close so
+    /// amounts can still be converted into the account currency
+    last_prices: Arc<DashMap<String, f64>>,
+    /// Limit, stop and market orders waiting to fill, per symbol in placement order
+    pending_orders: Arc<DashMap<String, Vec<Order>>>,
+    /// Exit orders of bracket entries that have not filled yet, by entry
+    /// order id
+    bracket_exits: Arc<DashMap<Uuid, Vec<Order>>>,
+    trigger_fill_policy: TriggerFillPolicy,
+    /// Balance, equity and margin, refreshed after every open, close and
+    /// price update while a trade listener can receive margin calls, and
+    /// otherwise when read
+    account: AccountTracker,
+    /// Reject opens the free margin can't cover; set by `with_starting_balance`
+    enforce_margin: bool,
+    /// Leverage per symbol for margin, with a default for the rest
+    leverage: LeverageTable,
+}
+
+impl PositionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit how many positions may be open at once for each symbol
+    pub fn with_position_limit(mut self, limit: PositionLimit) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn position_limit(&self) -> Option<PositionLimit> {
+        self.limit
+    }
+
+    /// Net opposite-side fills against each other instead of hedging
+    pub fn with_netting_mode(mut self, mode: NettingMode) -> Self {
+        self.netting_mode = mode;
+        self
+    }
+
+    pub fn netting_mode(&self) -> NettingMode {
+        self.netting_mode
+    }
+
+    /// Reject new fills while `filter` reports a spike cooldown
+    pub fn with_fill_guard(mut self, filter: Arc<RwLock<SpikeFilter>>) -> Self {
+        self.fill_guard = Some(filter);
+        self
+    }
+
+    /// Deduct commission and swap from realized P&L when positions close
+    pub fn with_cost_model(mut self, model: CostModel) -> Self {
+        self.cost_model = model;
+        self
+    }
+
+    pub fn cost_model(&self) -> CostModel {
+        self.cost_model
+    }
+
+    /// Fill entries and exits through `model` instead of at the requested
+    /// price, using the latest quote seen in `on_tick`
+    pub fn with_slippage_model(mut self, model: Arc<dyn SlippageModel>) -> Self {
+        self.slippage = Some(model);
+        self
+    }
+
+    /// Seed the random source of randomized slippage models and of the
+    /// ids this manager assigns. Without this the fixed `DEFAULT_FILL_SEED`
+    /// is used, so runs are deterministic either way; the same seed and
+    /// inputs give the same fills and ids.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = SeededRng::new(seed);
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.rng.seed()
+    }
+
+    /// Shorthand for the slippage model of `model`
+    pub fn with_execution_model(self, model: ExecutionModel) -> Self {
+        self.with_slippage_model(model.slippage_model())
+    }
+
+    /// Choose the fill price of pending orders whose trigger a tick gaps past
+    pub fn with_trigger_fill_policy(mut self, policy: TriggerFillPolicy) -> Self {
+        self.trigger_fill_policy = policy;
+        self
+    }
+
+    /// Report aggregate P&L, and keep the account, in the calculator's
+    /// account currency
+    pub fn with_pnl_calculator(mut self, calculator: PnlCalculator) -> Self {
+        self.pnl_calculator = Some(calculator);
+        self
+    }
+
+    /// Start the account from `balance` instead of `DEFAULT_STARTING_BALANCE`
+    /// and reject opens whose margin exceeds the free margin.
+    ///
+    /// Margin is converted into the account currency at the latest prices
+    /// seen, as for `account`.
+    pub fn with_starting_balance(mut self, balance: f64) -> Self {
+        self.account = AccountTracker::new(balance, self.account.margin_call_level());
+        self.enforce_margin = true;
+        self
+    }
+
+    /// Raise `TradeEvent::MarginCall` when the margin level drops below
+    /// `level` percent instead of `DEFAULT_MARGIN_CALL_LEVEL`
+    pub fn with_margin_call_level(mut self, level: f64) -> Self {
+        self.account = AccountTracker::new(self.account.snapshot().balance, level);
+        self
+    }
+
+    /// Leverage for symbols without `set_leverage`, instead of
+    /// `DEFAULT_LEVERAGE`. Fails unless `leverage` is positive.
+    pub fn with_default_leverage(mut self, leverage: f64) -> Result<Self> {
+        self.leverage = self.leverage.with_default(leverage)?;
+        Ok(self)
+    }
+
+    pub fn default_leverage(&self) -> f64 {
+        self.leverage.default_leverage()
+    }
+
+    /// Margin leverage for `symbol`, e.g. 30 for 1:30. Fails unless
+    /// `leverage` is positive.
+    ///
+    /// Open positions on `symbol` are re-margined at the new leverage.
+    pub fn set_leverage(&self, symbol: &str, leverage: f64) -> Result<()> {
+        self.leverage.set(symbol, leverage)?;
+        self.refresh_account();
+        Ok(())
+    }
+
+    /// Leverage margin on `symbol` is computed with
+    pub fn leverage(&self, symbol: &str) -> f64 {
+        self.leverage.get(symbol)
+    }
+
+    /// Current balance, equity and margin, in the account currency: the
+    /// `PnlCalculator`'s, or `DEFAULT_ACCOUNT_CURRENCY` without one.
+    ///
+    /// Amounts are converted at the latest prices seen, crossing through
+    /// another pair where needed. Fails with `MissingRate` if an open
+    /// position's quote currency can't be converted.
+    pub fn account(&self) -> Result<Account> {
+        if self.trade_listener.is_none() {
+            self.recompute_account()?;
+        } else {
+            // Kept current by refresh_account, which can't report failures
+            self.open_exposure()?;
+        }
+        Ok(self.account.snapshot())
+    }
+
+    /// Positions, account, pending orders and queued requests for a
+    /// checkpoint.
+    ///
+    /// Open positions come first, per symbol in opening order, followed by
+    /// pending, closed and cancelled ones. Orders and queued requests are
+    /// listed by symbol, each symbol's in placement or queue order.
+    pub fn snapshot_positions(&self) -> Result<PositionsSnapshot> {
+        let mut positions: Vec<Position> = self
+            .symbol_index
+            .iter()
+            .flat_map(|ids| {
+                ids.iter()
+                    .filter_map(|id| self.positions.get(id).map(|p| p.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        let mut rest: Vec<Position> = self
+            .positions
+            .iter()
+            .filter(|p| !p.is_open())
+            .map(|p| p.clone())
+            .collect();
+        rest.sort_by_key(|p| p.opened_at);
+        positions.extend(rest);
+
+        let mut pending_orders: Vec<(String, Vec<Order>)> = self
+            .pending_orders
+            .iter()
+            .map(|orders| (orders.key().clone(), orders.clone()))
+            .collect();
+        pending_orders.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut bracket_exits: Vec<(Uuid, Vec<Order>)> = self
+            .bracket_exits
+            .iter()
+            .map(|exits| (*exits.key(), exits.clone()))
+            .collect();
+        bracket_exits.sort_by_key(|(entry, _)| *entry);
+        let mut queued_requests: Vec<(String, Vec<(Uuid, PositionRequest)>)> = self
+            .queued_requests
+            .iter()
+            .map(|queue| (queue.key().clone(), queue.iter().cloned().collect()))
+            .collect();
+        queued_requests.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Ok(PositionsSnapshot {
+            positions,
+            account: self.account()?,
+            pending_orders: pending_orders
+                .into_iter()
+                .flat_map(|(_, orders)| orders)
+                .collect(),
+            bracket_exits,
+            queued_requests: queued_requests
+                .into_iter()
+                .flat_map(|(_, queue)| queue)
+                .collect(),
+            seed: self.rng.seed(),
+            rng_word_pos: self.rng.word_pos(),
+        })
+    }
+
+    /// Replace every position, the account, the pending orders, the
+    /// queued requests and the random source with a checkpoint's, so the
+    /// run continues with the fills and ids it would have had.
+    ///
+    /// Configuration such as limits, leverage and the cost model is not
+    /// part of the snapshot and stays as set on this manager.
+    pub fn restore_positions(&self, snapshot: &PositionsSnapshot) {
+        self.positions.clear();
+        self.symbol_index.clear();
+        self.hierarchy_index.clear();
+        self.pending_ids.clear();
+        self.pending_orders.clear();
+        self.bracket_exits.clear();
+        self.queued_requests.clear();
+
+        for position in &snapshot.positions {
+            if position.is_open() {
+                self.symbol_index
+                    .entry(position.symbol.clone())
+                    .or_default()
+                    .push(position.id);
+            }
+            // Cancelling a position unlinks it from its parent
+            let linked = position.state != PositionState::Cancelled;
+            if let Some(parent_id) = position.parent_id.filter(|_| linked) {
+                self.hierarchy_index
+                    .entry(parent_id)
+                    .or_default()
+                    .push(position.id);
+            }
+            self.positions.insert(position.id, position.clone());
+        }
+        for order in &snapshot.pending_orders {
+            self.pending_orders
+                .entry(order.symbol.clone())
+                .or_default()
+                .push(order.clone());
+        }
+        for (entry, exits) in &snapshot.bracket_exits {
+            self.bracket_exits.insert(*entry, exits.clone());
+        }
+        for (order_id, request) in &snapshot.queued_requests {
+            self.queued_requests
+                .entry(request.symbol.clone())
+                .or_default()
+                .push_back((*order_id, request.clone()));
+        }
+        self.account.restore(snapshot.account);
+        self.rng.restore(snapshot.seed, snapshot.rng_word_pos);
+    }
+
+    /// Receive a `TradeEvent` for every position closed by this manager
+    pub fn with_trade_listener<F>(mut self, listener: F) -> Self
+    where
+        F: Fn(&TradeEvent) + Send + Sync + 'static,
+    {
+        self.trade_listener = Some(Arc::new(listener));
+        self
+    }
+
+    /// Open a position, or queue/reject it if the symbol is at its limit
+    pub fn open_position(&self, request: PositionRequest, timestamp: i64) -> Result<OpenOutcome> {
+        request.validate()?;
+        let price = self.fill_price(
+            OrderSide::entry(request.side),
+            &request.symbol,
+            request.price,
+        );
+        self.open_filled(
+            self.rng.next_id(),
+            PositionRequest { price, ..request },
+            timestamp,
+        )
+    }
+
+    /// Open at an already filled price, without applying slippage.
+    ///
+    /// Margin is only checked when the request actually opens or nets; a
+    /// queued request holds none until its slot frees up.
+    fn open_filled(
+        &self,
+        order_id: Uuid,
+        request: PositionRequest,
+        timestamp: i64,
+    ) -> Result<OpenOutcome> {
+        if let Some(until) = self.fills_blocked_until(&request.symbol, timestamp) {
+            return Err(PositionError::FillsBlocked {
+                symbol: request.symbol,
+                until,
+            });
+        }
+
+        // Requests already waiting get any free slot first, and a new
+        // request waits behind them while they are still queued
+        if self.queued_count(&request.symbol) > 0 {
+            self.open_queued(&request.symbol, request.price, timestamp);
+        }
+        if self.queued_count(&request.symbol) == 0 {
+            if self.netting_mode == NettingMode::Netting
+                && self.open_position_count(&request.symbol) > 0
+            {
+                self.ensure_margin(&request)?;
+                let (outcome, remainder) = self.open_netted(request, timestamp)?;
+                if let Some(remainder) = remainder {
+                    self.overflow(order_id, remainder)?;
+                }
+                return Ok(outcome);
+            }
+            if self.has_free_slot(&request.symbol) {
+                self.ensure_margin(&request)?;
+                if let Some(id) = self.try_open(&request, timestamp) {
+                    return Ok(OpenOutcome::Opened(id));
+                }
+            }
+        }
+
+        self.overflow(order_id, request)
+            .map(|queue_position| OpenOutcome::Queued {
+                order_id,
+                queue_position,
+            })
+    }
+
+    /// Whether `symbol` is below its position limit
+    fn has_free_slot(&self, symbol: &str) -> bool {
+        self.limit
+            .is_none_or(|limit| self.open_position_count(symbol) < limit.max_per_symbol)
+    }
+
+    /// Reject or queue a request the symbol has no slot for, per the
+    /// limit's overflow policy, returning its 1-based queue position
+    fn overflow(&self, order_id: Uuid, request: PositionRequest) -> Result<usize> {
+        // Only a configured limit makes try_open fail or requests queue, so
+        // without one the request can only be waiting behind queued ones
+        let Some(limit) = self.limit else {
+            return Ok(self.enqueue(order_id, request));
+        };
+        match limit.overflow {
+            OverflowPolicy::Reject => Err(PositionError::LimitReached {
+                symbol: request.symbol,
+                limit: limit.max_per_symbol,
+            }),
+            OverflowPolicy::Queue => Ok(self.enqueue(order_id, request)),
+        }
+    }
+
+    /// Queue a request behind any already waiting for its symbol,
+    /// returning its 1-based queue position
+    fn enqueue(&self, order_id: Uuid, request: PositionRequest) -> usize {
+        let mut queue = self
+            .queued_requests
+            .entry(request.symbol.clone())
+            .or_default();
+        queue.push_back((order_id, request));
+        queue.len()
+    }
+
+    /// Record a position that has not filled yet.
+    ///
+    /// A pending position holds no margin, does not count towards the
+    /// symbol's limit and is not marked to market. It is linked to its
+    /// parent straight away, and stays pending until `activate_position`
+    /// or `cancel_position`.
+    pub fn place_pending_position(&self, request: PositionRequest, timestamp: i64) -> Result<Uuid> {
+        request.validate()?;
+        let mut position = request.into_position(self.rng.next_id(), timestamp);
+        position.state = PositionState::Pending;
+        let id = position.id;
+
+        if let Some(parent_id) = position.parent_id {
+            self.hierarchy_index.entry(parent_id).or_default().push(id);
+        }
+        self.positions.insert(id, position);
+        Ok(id)
+    }
+
+    /// Fill a pending position at `price` and open it at `timestamp`.
+    ///
+    /// The fill goes through slippage, the spike guard, the margin check
+    /// and the position limit like `open_position`, but always opens the
+    /// position under its own id, without netting. On any error the
+    /// position stays pending.
+    pub fn activate_position(&self, id: &Uuid, price: f64, timestamp: i64) -> Result<()> {
+        let request = {
+            let position = self.positions.get(id).ok_or(PositionError::NotFound(*id))?;
+            StateValidator::validate(position.state, PositionState::Open)?;
+            PositionRequest::new(
+                position.symbol.clone(),
+                position.side,
+                position.quantity,
+                price,
+            )
+        };
+        request.validate()?;
+
+        let price = self.fill_price(
+            OrderSide::entry(request.side),
+            &request.symbol,
+            request.price,
+        );
+        let request = PositionRequest { price, ..request };
+        if let Some(until) = self.fills_blocked_until(&request.symbol, timestamp) {
+            return Err(PositionError::FillsBlocked {
+                symbol: request.symbol,
+                until,
+            });
+        }
+        self.ensure_margin(&request)?;
+
+        // Holding the index entry makes the limit check and insert atomic
+        let mut open_ids = self.symbol_index.entry(request.symbol.clone()).or_default();
+        if let Some(limit) = self.limit {
+            if open_ids.len() >= limit.max_per_symbol {
+                return Err(PositionError::LimitReached {
+                    symbol: request.symbol,
+                    limit: limit.max_per_symbol,
+                });
+            }
+        }
+        self.positions
+            .get_mut(id)
+            .ok_or(PositionError::NotFound(*id))?
+            .activate(price, timestamp)?;
+        open_ids.push(*id);
+        drop(open_ids);
+
+        self.refresh_account();
+        Ok(())
+    }
+
+    /// Cancel a pending position and unlink it from its parent.
+    ///
+    /// Fails with `PositionError::StateTransition` if the position already
+    /// opened, closed or was cancelled. Its own children are left alone;
+    /// see `cancel_with_children`. The cancelled record stays available
+    /// through `get_position`.
+    pub fn cancel_position(&self, id: &Uuid) -> Result<()> {
+        let (symbol, parent_id) = {
+            let mut position = self
+                .positions
+                .get_mut(id)
+                .ok_or(PositionError::NotFound(*id))?;
+            position.cancel()?;
+            (position.symbol.clone(), position.parent_id)
+        };
+
+        if let Some(parent_id) = parent_id {
+            if let Some(mut children) = self.hierarchy_index.get_mut(&parent_id) {
+                children.retain(|child| child != id);
+            }
+            self.hierarchy_index
+                .remove_if(&parent_id, |_, children| children.is_empty());
+        }
+
+        if let Some(listener) = &self.trade_listener {
+            listener(&TradeEvent::PositionCancelled { id: *id, symbol });
+        }
+        Ok(())
+    }
+
+    /// Cancel a pending position and every pending position below it,
+    /// returning the cancelled ids parent first.
+    ///
+    /// Fails like `cancel_position` if `id` itself is not pending.
+    /// Descendants that already opened or closed are skipped, but their
+    /// own pending children are still cancelled.
+    pub fn cancel_with_children(&self, id: &Uuid) -> Result<Vec<Uuid>> {
+        let mut descendants = Vec::new();
+        let mut frontier = self.get_children(id);
+        while let Some(child) = frontier.pop() {
+            frontier.extend(self.get_children(&child));
+            descendants.push(child);
+        }
+
+        self.cancel_position(id)?;
+        let mut cancelled = vec![*id];
+        for child in descendants {
+            if self.cancel_position(&child).is_ok() {
+                cancelled.push(child);
+            }
+        }
+        Ok(cancelled)
+    }
+
+    /// Cancel every pending position on `symbol`, oldest first, returning
+    /// the cancelled ids
+    pub fn cancel_all_pending(&self, symbol: &str) -> Vec<Uuid> {
+        let mut pending: Vec<(i64, Uuid)> = self
+            .positions
+            .iter()
+            .filter(|p| p.symbol == symbol && p.state == PositionState::Pending)
+            .map(|p| (p.opened_at, p.id))
+            .collect();
+        pending.sort();
+
+        pending
+            .into_iter()
+            .filter_map(|(_, id)| self.cancel_position(&id).ok().map(|_| id))
+            .collect()
+    }
+
+    /// Queue an order that opens a position once a tick reaches its trigger,
+    /// returning the id it is tracked under.
+    ///
+    /// Orders are only evaluated by `check_pending_orders`; market orders
+    /// fill on the next tick checked.
+    pub fn place_order(&self, mut order: Order) -> Result<Uuid> {
+        order.validate()?;
+        order.id = self.rng.next_id();
+        Ok(self.push_order(order))
+    }
+
+    fn push_order(&self, order: Order) -> Uuid {
+        let id = order.id;
+        self.pending_orders
+            .entry(order.symbol.clone())
+            .or_default()
+            .push(order);
+        id
+    }
+
+    /// Place an entry order with a take-profit and a stop that close the
+    /// position it opens.
+    ///
+    /// The exits join the book once the entry opens its own position. An
+    /// entry that would be queued behind the position limit or netted
+    /// against an open position is rejected with its exits instead, and
+    /// reported as `TradeEvent::OrderRejected`. The exits share an OCO
+    /// group, so whichever fills first withdraws the other, even when both
+    /// are reached in the same `check_pending_orders` call. Closing the
+    /// position any other way withdraws both.
+    ///
+    /// The stop must lie below the take-profit for a long entry and above
+    /// it for a short one; for limit and stop entries the trigger price
+    /// must lie strictly between them.
+    pub fn place_bracket(&self, mut entry: Order, take_profit: f64, stop: f64) -> Result<Bracket> {
+        entry.validate()?;
+        let (low, high) = match entry.side {
+            PositionSide::Long => (stop, take_profit),
+            PositionSide::Short => (take_profit, stop),
+        };
+        let wrong_sides = match entry.order_type {
+            OrderType::Market => low >= high,
+            OrderType::Limit | OrderType::Stop => {
+                !(low < entry.trigger_price && entry.trigger_price < high)
+            }
+        };
+        if wrong_sides {
+            return Err(PositionError::InvalidBracket {
+                side: entry.side,
+                take_profit,
+                stop,
+            });
+        }
+
+        let exit_side = entry.side.opposite();
+        let group = self.rng.next_id();
+        let mut take_profit_order =
+            Order::limit(entry.symbol.clone(), exit_side, take_profit, entry.quantity)
+                .with_oco_group(group);
+        let mut stop_order = Order::stop(entry.symbol.clone(), exit_side, stop, entry.quantity)
+            .with_oco_group(group);
+        take_profit_order.validate()?;
+        stop_order.validate()?;
+        entry.id = self.rng.next_id();
+        take_profit_order.id = self.rng.next_id();
+        stop_order.id = self.rng.next_id();
+
+        let bracket = Bracket {
+            entry: entry.id,
+            take_profit: take_profit_order.id,
+            stop: stop_order.id,
+        };
+        self.bracket_exits
+            .insert(entry.id, vec![take_profit_order, stop_order]);
+        self.push_order(entry);
+        Ok(bracket)
+    }
+
+    /// Withdraw a pending order, returning `false` if it already filled or
+    /// was never placed. A cancelled order can no longer fill; cancelling a
+    /// bracket entry drops its exits too.
+    pub fn cancel_order(&self, order_id: &Uuid) -> bool {
+        self.bracket_exits.remove(order_id);
+        for mut orders in self.pending_orders.iter_mut() {
+            if let Some(index) = orders.iter().position(|order| order.id == *order_id) {
+                orders.remove(index);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Orders still waiting to fill on `symbol`, in placement order
+    pub fn pending_orders(&self, symbol: &str) -> Vec<Order> {
+        self.pending_orders
+            .get(symbol)
+            .map(|orders| orders.clone())
+            .unwrap_or_default()
+    }
+
+    /// Fill every pending order on the tick's symbol that the tick reaches,
+    /// returning each filled order id with the outcome of its open.
+    /// Bracket exits report `OpenOutcome::Netted` with no position left.
+    ///
+    /// A tick that gaps past a trigger fills at the price chosen by the
+    /// `TriggerFillPolicy`; no slippage is applied on top. Triggered orders
+    /// are removed from the book before opening, so each fills at most once.
+    /// A triggered order whose fill fails, e.g. for lack of margin, is
+    /// dropped with its bracket exits and reported as
+    /// `TradeEvent::OrderRejected`; it does not withdraw its OCO siblings.
+    /// Bracket entries also fail rather than queue or net.
+    /// While fills are blocked by a spike cooldown orders stay pending.
+    pub fn check_pending_orders(&self, tick: &backtestr_data::Tick) -> Vec<(Uuid, OpenOutcome)> {
+        if self
+            .fills_blocked_until(&tick.symbol, tick.timestamp)
+            .is_some()
+        {
+            return Vec::new();
+        }
+
+        let triggered: Vec<(Order, f64)> = match self.pending_orders.get_mut(&tick.symbol) {
+            Some(mut orders) => {
+                let mut triggered = Vec::new();
+                orders.retain(
+                    |order| match order.fill_price(tick, self.trigger_fill_policy) {
+                        Some(price) => {
+                            triggered.push((order.clone(), price));
+                            false
+                        }
+                        None => true,
+                    },
+                );
+                triggered
+            }
+            None => return Vec::new(),
+        };
+
+        let mut filled = Vec::new();
+        let mut filled_groups = HashSet::new();
+        for (order, price) in triggered {
+            let exits = self.bracket_exits.remove(&order.id).map(|(_, exits)| exits);
+            // A sibling already filled on this tick
+            if order
+                .oco_group
+                .is_some_and(|group| filled_groups.contains(&group))
+            {
+                continue;
+            }
+
+            let outcome = match order.closes_position {
+                Some(position_id) => {
+                    self.close_filled(&position_id, price, tick.timestamp)
+                        .map(|realized_pnl| OpenOutcome::Netted {
+                            position_id: None,
+                            realized_pnl,
+                        })
+                }
+                None => {
+                    let request = PositionRequest::new(
+                        order.symbol.clone(),
+                        order.side,
+                        order.quantity,
+                        price,
+                    );
+                    if exits.is_some() {
+                        self.open_bracket_entry(request, tick.timestamp)
+                            .map(OpenOutcome::Opened)
+                    } else {
+                        self.open_filled(order.id, request, tick.timestamp)
+                    }
+                }
+            };
+            let outcome = match outcome {
+                Ok(outcome) => outcome,
+                Err(error) => {
+                    if let Some(listener) = &self.trade_listener {
+                        listener(&TradeEvent::OrderRejected {
+                            order_id: order.id,
+                            symbol: order.symbol.clone(),
+                            error,
+                        });
+                    }
+                    continue;
+                }
+            };
+            filled_groups.extend(order.oco_group);
+            if let (Some(exits), OpenOutcome::Opened(position_id)) = (exits, outcome) {
+                self.pending_orders
+                    .entry(order.symbol.clone())
+                    .or_default()
+                    .extend(exits.into_iter().map(|exit| exit.closing(position_id)));
+            }
+
+            if let Some(listener) = &self.trade_listener {
+                listener(&TradeEvent::OrderFilled {
+                    order_id: order.id,
+                    position_id: order.closes_position.or(outcome.position_id()),
+                    symbol: order.symbol.clone(),
+                    side: order.side,
+                    fill_price: price,
+                    filled_at: tick.timestamp,
+                });
+            }
+            filled.push((order.id, outcome));
+        }
+
+        // Siblings of filled orders still resting in the book
+        if !filled_groups.is_empty() {
+            if let Some(mut orders) = self.pending_orders.get_mut(&tick.symbol) {
+                orders.retain(|order| {
+                    !order
+                        .oco_group
+                        .is_some_and(|group| filled_groups.contains(&group))
+                });
+            }
+        }
+        filled
+    }
+
+    /// Open a bracket entry as its own position, failing where a plain
+    /// fill would be netted or queued, since its exits need a position
+    fn open_bracket_entry(&self, request: PositionRequest, timestamp: i64) -> Result<Uuid> {
+        self.ensure_margin(&request)?;
+        if self.netting_mode == NettingMode::Netting
+            && self.open_position_count(&request.symbol) > 0
+        {
+            return Err(PositionError::BracketWouldNet {
+                symbol: request.symbol,
+            });
+        }
+
+        // Queued requests keep their place ahead of the entry
+        if self.queued_count(&request.symbol) == 0 {
+            if let Some(id) = self.try_open(&request, timestamp) {
+                return Ok(id);
+            }
+        }
+        Err(PositionError::LimitReached {
+            limit: self.limit.map_or(0, |limit| limit.max_per_symbol),
+            symbol: request.symbol,
+        })
+    }
+
+    /// Close a position at `price`, returning the realized P&L net of
+    /// the configured commission and swap.
+    ///
+    /// With a slippage model the position closes at the model's fill price
+    /// rather than `price`. Queued requests for the same symbol are opened
+    /// at that price and `timestamp` as soon as the close frees a slot.
+    pub fn close_position(&self, id: &Uuid, price: f64, timestamp: i64) -> Result<f64> {
+        let price = match (&self.slippage, self.positions.get(id)) {
+            (Some(_), Some(position)) => {
+                let (side, symbol) = (position.side, position.symbol.clone());
+                drop(position);
+                self.fill_price(OrderSide::exit(side), &symbol, price)
+            }
+            _ => price,
+        };
+        self.close_filled(id, price, timestamp)
+    }
+
+    /// Close at an already filled price, without applying slippage, then
+    /// open queued requests in the freed slot
+    fn close_filled(&self, id: &Uuid, price: f64, timestamp: i64) -> Result<f64> {
+        let (symbol, net_pnl) = self.book_close(id, price, timestamp)?;
+        self.open_queued(&symbol, price, timestamp);
+        Ok(net_pnl)
+    }
+
+    /// Close at an already filled price, returning the symbol and the net
+    /// realized P&L. Leaves the queue alone, so netting can reuse the
+    /// freed slot for the rest of its own fill.
+    fn book_close(&self, id: &Uuid, price: f64, timestamp: i64) -> Result<(String, f64)> {
+        // Find the rate first, so a close that can't be booked leaves the
+        // position open
+        let symbol = self
+            .positions
+            .get(id)
+            .ok_or(PositionError::NotFound(*id))?
+            .symbol
+            .clone();
+        let mut rates = self.conversion_rates();
+        rates.insert(symbol.clone(), price);
+        let rate = self.to_account_ccy(&symbol, 1.0, &rates)?;
+
+        let (symbol, net_pnl, event) = {
+            let mut position = self
+                .positions
+                .get_mut(id)
+                .ok_or(PositionError::NotFound(*id))?;
+            let gross_pnl = position.close(price, timestamp)?;
+            let (commission, swap) = self.cost_model.costs_for(&position, timestamp);
+            let net_pnl = position.apply_costs(commission, swap);
+
+            let event = TradeEvent::PositionClosed {
+                id: *id,
+                symbol: position.symbol.clone(),
+                side: position.side,
+                close_price: price,
+                closed_at: timestamp,
+                gross_pnl,
+                commission,
+                swap,
+                net_pnl,
+            };
+            (position.symbol.clone(), net_pnl, event)
+        };
+
+        if let Some(mut ids) = self.symbol_index.get_mut(&symbol) {
+            ids.retain(|open_id| open_id != id);
+        }
+        // Bracket exits of a closed position can never fill
+        if let Some(mut orders) = self.pending_orders.get_mut(&symbol) {
+            orders.retain(|order| order.closes_position != Some(*id));
+        }
+
+        self.last_prices.insert(symbol.clone(), price);
+        self.account.book(net_pnl * rate);
+        if let Some(listener) = &self.trade_listener {
+            listener(&event);
+        }
+        self.refresh_account();
+
+        Ok((symbol, net_pnl))
+    }
+
+    /// Net a fill against the symbol's open positions in opening order.
+    ///
+    /// Offsets are booked as closes so they carry costs and emit
+    /// `PositionClosed`; a partial offset splits the closed quantity into its
+    /// own position record and leaves the remainder open under the same id.
+    ///
+    /// A fill that flips through flat opens the rest on the other side and
+    /// returns it as the second value if the symbol has no free slot.
+    fn open_netted(
+        &self,
+        request: PositionRequest,
+        timestamp: i64,
+    ) -> Result<(OpenOutcome, Option<PositionRequest>)> {
+        let mut remaining = request.quantity;
+        let mut realized_pnl = 0.0;
+
+        for position in self.get_open_positions(&request.symbol) {
+            if position.side == request.side {
+                self.average_into(&position.id, remaining, request.price)?;
+                let outcome = OpenOutcome::Netted {
+                    position_id: Some(position.id),
+                    realized_pnl,
+                };
+                return Ok((outcome, None));
+            }
+
+            if remaining < position.quantity - NETTING_EPSILON {
+                realized_pnl +=
+                    self.reduce_position(&position.id, remaining, request.price, timestamp)?;
+                let outcome = OpenOutcome::Netted {
+                    position_id: Some(position.id),
+                    realized_pnl,
+                };
+                return Ok((outcome, None));
+            }
+
+            realized_pnl += self.book_close(&position.id, request.price, timestamp)?.1;
+            remaining -= position.quantity;
+            if remaining <= NETTING_EPSILON {
+                break;
+            }
+        }
+
+        let mut unopened = None;
+        let mut position_id = None;
+        if remaining > NETTING_EPSILON {
+            // Flipped through flat; the remainder opens on the other side
+            let request = PositionRequest {
+                quantity: remaining,
+                ..request
+            };
+            position_id = self.try_open(&request, timestamp);
+            if position_id.is_none() {
+                unopened = Some(request);
+            }
+        }
+
+        let outcome = OpenOutcome::Netted {
+            position_id,
+            realized_pnl,
+        };
+        Ok((outcome, unopened))
+    }
+
+    /// Scale into an open position, moving its entry to the
+    /// quantity-weighted average of the old entry and `price`.
+    ///
+    /// Unlike opening a child position this keeps one position with one
+    /// id and its original `opened_at`; P&L, the margin it holds and any
+    /// later partial close use the averaged entry. The added quantity is
+    /// filled through the slippage model and checked against free margin
+    /// like any other fill.
+    pub fn add_to_position(&self, id: &Uuid, quantity: f64, price: f64) -> Result<()> {
+        let (symbol, side) = {
+            let position = self.positions.get(id).ok_or(PositionError::NotFound(*id))?;
+            if !StateValidator::can_add(position.state) {
+                return Err(PositionError::NotOpen {
+                    id: *id,
+                    state: position.state,
+                });
+            }
+            (position.symbol.clone(), position.side)
+        };
+
+        let request = PositionRequest::new(symbol, side, quantity, price);
+        request.validate()?;
+        let price = self.fill_price(OrderSide::entry(side), &request.symbol, price);
+        self.ensure_margin(&PositionRequest { price, ..request })?;
+
+        self.average_into(id, quantity, price)
+    }
+
+    /// Grow a position at an already filled price, moving its entry to the
+    /// quantity-weighted average
+    fn average_into(&self, id: &Uuid, quantity: f64, price: f64) -> Result<()> {
+        self.positions
+            .get_mut(id)
+            .ok_or(PositionError::NotFound(*id))?
+            .add(quantity, price)?;
+
+        self.refresh_account();
+        Ok(())
+    }
+
+    /// Close `quantity` of a position at `price`, leaving the rest open
+    fn reduce_position(&self, id: &Uuid, quantity: f64, price: f64, timestamp: i64) -> Result<f64> {
+        let portion = {
+            let mut position = self
+                .positions
+                .get_mut(id)
+                .ok_or(PositionError::NotFound(*id))?;
+            position.split_off(quantity, self.rng.next_id())
+        };
+
+        let portion_id = portion.id;
+        self.positions.insert(portion_id, portion);
+        Ok(self.book_close(&portion_id, price, timestamp)?.1)
+    }
+
+    /// Close every position whose stop-loss or take-profit fired in `on_tick`,
+    /// returning the id and net realized P&L of each close.
+    ///
+    /// `EventHandler::on_tick` only has `&self` access and cannot close
+    /// positions itself, so callers must invoke this after each tick batch.
+    /// Positions that were closed some other way in the meantime are skipped.
+    /// A close that fails is reported as `TradeEvent::CloseFailed`.
+    pub fn process_pending_closes(&self) -> Vec<(Uuid, f64)> {
+        let mut closed = Vec::new();
+
+        while let Some(pending) = self.pending_closes.pop() {
+            self.pending_ids.remove(&pending.id);
+
+            let still_open = self
+                .positions
+                .get(&pending.id)
+                .map(|p| p.is_open())
+                .unwrap_or(false);
+            if !still_open {
+                continue;
+            }
+
+            match self.close_position(&pending.id, pending.price, pending.timestamp) {
+                Ok(pnl) => closed.push((pending.id, pnl)),
+                Err(error) => {
+                    if let Some(listener) = &self.trade_listener {
+                        let symbol = self
+                            .positions
+                            .get(&pending.id)
+                            .map(|p| p.symbol.clone())
+                            .unwrap_or_default();
+                        listener(&TradeEvent::CloseFailed {
+                            id: pending.id,
+                            symbol,
+                            reason: pending.reason,
+                            error,
+                        });
+                    }
+                }
+            }
+        }
+
+        closed
+    }
+
+    /// Close every open position whose expiry is at or before `now`,
+    /// returning the id and net realized P&L of each close.
+    ///
+    /// Positions close at `price_lookup(symbol)`, or at their last marked
+    /// `current_price` when no price is available, so an expired position
+    /// never lingers. Closes happen in expiry order.
+    pub fn close_expired<F>(&self, now: i64, price_lookup: F) -> Vec<(Uuid, f64)>
+    where
+        F: Fn(&str) -> Option<f64>,
+    {
+        let mut expired: Vec<Position> = self
+            .positions
+            .iter()
+            .filter(|p| p.is_open() && p.is_expired(now))
+            .map(|p| p.clone())
+            .collect();
+        expired.sort_by_key(|p| (p.expires_at, p.opened_at));
+
+        expired
+            .into_iter()
+            .filter_map(|position| {
+                let price = price_lookup(&position.symbol).unwrap_or(position.current_price);
+                self.close_position(&position.id, price, now)
+                    .ok()
+                    .map(|pnl| (position.id, pnl))
+            })
+            .collect()
+    }
+
+    pub fn pending_close_count(&self) -> usize {
+        self.pending_closes.len()
+    }
+
+    /// Queue closes for positions on the tick's symbol whose stop or target
+    /// was hit. Longs exit at the bid and shorts at the ask.
+    fn check_triggers(&self, tick: &backtestr_data::Tick) {
+        for position in self.get_open_positions(&tick.symbol) {
+            let exit_price = match position.side {
+                PositionSide::Long => tick.bid,
+                PositionSide::Short => tick.ask,
+            };
+
+            let reason = if position.is_stop_loss_hit(exit_price) {
+                CloseReason::StopLoss
+            } else if position.is_take_profit_hit(exit_price) {
+                CloseReason::TakeProfit
+            } else {
+                continue;
+            };
+
+            if self.pending_ids.insert(position.id) {
+                self.pending_closes.push(PendingClose {
+                    id: position.id,
+                    price: exit_price,
+                    timestamp: tick.timestamp,
+                    reason,
+                });
+            }
+        }
+    }
+
+    /// Mark every open position on `symbol` to `price`
+    pub fn update_price(&self, symbol: &str, price: f64) {
+        self.last_prices.insert(symbol.to_string(), price);
+        let ids = match self.symbol_index.get(symbol) {
+            Some(ids) => ids.clone(),
+            None => return,
+        };
+
+        for id in ids {
+            if let Some(mut position) = self.positions.get_mut(&id) {
+                let _ = position.update_price(price);
+            }
+        }
+        self.refresh_account();
+    }
+
+    pub fn get_position(&self, id: &Uuid) -> Option<Position> {
+        self.positions.get(id).map(|p| p.clone())
+    }
+
+    /// Open positions for a symbol, in opening order
+    pub fn get_open_positions(&self, symbol: &str) -> Vec<Position> {
+        self.symbol_index
+            .get(symbol)
+            .map(|ids| {
+                ids.iter()
+                    .filter_map(|id| self.positions.get(id).map(|p| p.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn get_all_open_positions(&self) -> Vec<Position> {
+        self.positions
+            .iter()
+            .filter(|p| p.is_open())
+            .map(|p| p.clone())
+            .collect()
+    }
+
+    pub fn get_closed_positions(&self) -> Vec<Position> {
+        let mut closed: Vec<Position> = self
+            .positions
+            .iter()
+            .filter(|p| p.closed_at.is_some())
+            .map(|p| p.clone())
+            .collect();
+        closed.sort_by_key(|p| p.closed_at);
+        closed
+    }
+
+    /// Win/loss counts, realized P&L and average MAE/MFE of closed positions
+    pub fn statistics(&self) -> PositionStatistics {
+        PositionStatistics::from_positions(&self.get_closed_positions())
+    }
+
+    pub fn get_children(&self, parent_id: &Uuid) -> Vec<Uuid> {
+        self.hierarchy_index
+            .get(parent_id)
+            .map(|children| children.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn open_position_count(&self, symbol: &str) -> usize {
+        self.symbol_index
+            .get(symbol)
+            .map(|ids| ids.len())
+            .unwrap_or(0)
+    }
+
+    pub fn queued_count(&self, symbol: &str) -> usize {
+        self.queued_requests
+            .get(symbol)
+            .map(|queue| queue.len())
+            .unwrap_or(0)
+    }
+
+    /// Drop all queued requests for a symbol, returning how many were removed
+    pub fn clear_queue(&self, symbol: &str) -> usize {
+        self.queued_requests
+            .remove(symbol)
+            .map(|(_, queue)| queue.len())
+            .unwrap_or(0)
+    }
+
+    /// Total number of tracked positions, including closed ones
+    pub fn position_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Sum of unrealized P&L across all open positions.
+    ///
+    /// Without `rates` the sum is of raw quote-currency amounts. With
+    /// `rates` each position is converted into the account currency of the
+    /// configured `PnlCalculator` first.
+    pub fn get_total_floating_pnl(&self, rates: Option<&HashMap<String, f64>>) -> Result<f64> {
+        let open = self.positions.iter().filter(|p| p.is_open());
+
+        match rates {
+            None => Ok(open.map(|p| p.unrealized_pnl).sum()),
+            Some(rates) => {
+                let calculator = self
+                    .pnl_calculator
+                    .as_ref()
+                    .ok_or(PositionError::NoAccountCurrency)?;
+                open.map(|p| calculator.calculate_pnl_in_account_ccy(&p, rates))
+                    .sum()
+            }
+        }
+    }
+
+    /// Open the request if the symbol has a free slot, returning the new id
+    fn try_open(&self, request: &PositionRequest, timestamp: i64) -> Option<Uuid> {
+        // Holding the index entry makes the limit check and insert atomic
+        let mut open_ids = self.symbol_index.entry(request.symbol.clone()).or_default();
+
+        if let Some(limit) = self.limit {
+            if open_ids.len() >= limit.max_per_symbol {
+                return None;
+            }
+        }
+
+        let position = request.clone().into_position(self.rng.next_id(), timestamp);
+        let id = position.id;
+
+        if let Some(parent_id) = position.parent_id {
+            self.hierarchy_index.entry(parent_id).or_default().push(id);
+        }
+
+        self.positions.insert(id, position);
+        open_ids.push(id);
+        drop(open_ids);
+
+        self.last_prices
+            .insert(request.symbol.clone(), request.price);
+        self.refresh_account();
+        Some(id)
+    }
+
+    /// Margin held by every open position, in the account currency.
+    /// Fails with `MissingRate` like `account`.
+    pub fn calculate_total_margin(&self) -> Result<f64> {
+        Ok(self.open_exposure()?.1)
+    }
+
+    /// Reject `request` if the margin it adds exceeds the free margin. In
+    /// netting mode only the quantity left after offsetting open positions
+    /// on the other side adds margin.
+    ///
+    /// Whether or not margin is enforced, a request whose amounts can't be
+    /// converted into the account currency fails with `MissingRate`, so
+    /// every open position can be valued and booked on close.
+    fn ensure_margin(&self, request: &PositionRequest) -> Result<()> {
+        let mut quantity = request.quantity;
+        if self.netting_mode == NettingMode::Netting {
+            let opposite: f64 = self
+                .get_open_positions(&request.symbol)
+                .iter()
+                .filter(|p| p.side != request.side)
+                .map(|p| p.quantity)
+                .sum();
+            quantity = (quantity - opposite).max(0.0);
+        }
+
+        let mut rates = self.conversion_rates();
+        rates.insert(request.symbol.clone(), request.price);
+        let required = PnlCalculator::calculate_margin_required(
+            quantity,
+            request.price,
+            self.leverage.get(&request.symbol),
+        );
+        let required = self.to_account_ccy(&request.symbol, required, &rates)?;
+        if !self.enforce_margin {
+            return Ok(());
+        }
+        let available = self.account()?.free_margin;
+        if required > 0.0 && required > available {
+            return Err(PositionError::InsufficientMargin {
+                required,
+                available,
+            });
+        }
+        Ok(())
+    }
+
+    /// Recompute the account from the open positions and raise a margin
+    /// call if this update breached the threshold.
+    ///
+    /// Only margin calls need the account current after every change, so
+    /// without a trade listener this does nothing and `account` brings the
+    /// figures up to date when they are read.
+    fn refresh_account(&self) {
+        if self.trade_listener.is_none() {
+            return;
+        }
+        // A missing rate leaves the last figures; `account` reports it
+        let Ok(Some(account)) = self.recompute_account() else {
+            return;
+        };
+
+        if let (Some(listener), Some(margin_level)) = (&self.trade_listener, account.margin_level) {
+            listener(&TradeEvent::MarginCall {
+                equity: account.equity,
+                used_margin: account.used_margin,
+                margin_level,
+            });
+        }
+    }
+
+    /// Recompute equity and margin from the open positions, returning the
+    /// account if this took the margin level below the threshold. Walks
+    /// only the open ids, so closed positions cost nothing.
+    fn recompute_account(&self) -> Result<Option<Account>> {
+        let (floating_pnl, used_margin) = self.open_exposure()?;
+        Ok(self.account.refresh(floating_pnl, used_margin))
+    }
+
+    /// Floating P&L and margin of the open positions in the account
+    /// currency
+    fn open_exposure(&self) -> Result<(f64, f64)> {
+        let rates = self.conversion_rates();
+        let mut floating_pnl = 0.0;
+        let mut used_margin = 0.0;
+        for ids in self.symbol_index.iter() {
+            for id in ids.iter() {
+                let Some(position) = self.positions.get(id) else {
+                    continue;
+                };
+                let rate = self.to_account_ccy(&position.symbol, 1.0, &rates)?;
+                floating_pnl += position.unrealized_pnl * rate;
+                used_margin += rate
+                    * PnlCalculator::calculate_margin_required(
+                        position.quantity,
+                        position.entry_price,
+                        self.leverage.get(&position.symbol),
+                    );
+            }
+        }
+        Ok((floating_pnl, used_margin))
+    }
+
+    /// Latest price per symbol, from the marks of open positions, which
+    /// also cover positions restored from a checkpoint, and the latest
+    /// marks and fills seen
+    fn conversion_rates(&self) -> HashMap<String, f64> {
+        let mut rates: HashMap<String, f64> = HashMap::new();
+        for ids in self.symbol_index.iter() {
+            if let Some(position) = ids.first().and_then(|id| self.positions.get(id)) {
+                rates.insert(ids.key().clone(), position.current_price);
+            }
+        }
+        rates.extend(
+            self.last_prices
+                .iter()
+                .map(|price| (price.key().clone(), *price.value())),
+        );
+        rates
+    }
+
+    /// `amount` in the quote currency of `symbol` converted into the
+    /// account currency. Symbols that aren't currency pairs are taken to
+    /// be quoted in the account currency.
+    fn to_account_ccy(
+        &self,
+        symbol: &str,
+        amount: f64,
+        rates: &HashMap<String, f64>,
+    ) -> Result<f64> {
+        let Ok((_, quote)) = split_symbol(symbol) else {
+            return Ok(amount);
+        };
+        let account = self
+            .pnl_calculator
+            .as_ref()
+            .map_or(DEFAULT_ACCOUNT_CURRENCY, |calculator| {
+                calculator.account_currency()
+            });
+        Ok(amount * conversion_rate(quote, account, rates)?)
+    }
+
+    /// Price a fill on `symbol` gets through the slippage model, against the
+    /// latest quote or, before any tick, a zero-width quote at `requested`
+    fn fill_price(&self, side: OrderSide, symbol: &str, requested: f64) -> f64 {
+        let Some(model) = &self.slippage else {
+            return requested;
+        };
+        let spread = self
+            .quotes
+            .get(symbol)
+            .map(|quote| *quote)
+            .unwrap_or((requested, requested));
+        self.rng
+            .with_fills(|rng| model.fill_price(side, requested, spread, None, rng))
+    }
+
+    /// End of the spike cooldown if fills on `symbol` are blocked at `timestamp`
+    fn fills_blocked_until(&self, symbol: &str, timestamp: i64) -> Option<i64> {
+        let filter = self.fill_guard.as_ref()?.read().ok()?;
+        if filter.is_cooling_down(symbol, timestamp) {
+            filter.cooldown_until(symbol)
+        } else {
+            None
+        }
+    }
+
+    /// Open queued requests for a symbol while slots are available.
+    ///
+    /// Runs after every close and on every tick with requests waiting, so
+    /// a queue held back by a spike cooldown drains on the first tick or
+    /// close once the cooldown has passed. In netting mode a queued request
+    /// nets against the symbol's open position like any other fill; if it
+    /// flips without a free slot, the rest goes back to the head of the
+    /// queue. A request that cannot fill once it reaches a slot, e.g. for
+    /// lack of margin, is dropped and reported as
+    /// `TradeEvent::OrderRejected` so it does not hold up the rest.
+    fn open_queued(&self, symbol: &str, price: f64, timestamp: i64) {
+        // Queued requests wait out a spike cooldown like any other fill
+        if self.fills_blocked_until(symbol, timestamp).is_some() {
+            return;
+        }
+
+        loop {
+            let (order_id, request) = match self.queued_requests.get_mut(symbol) {
+                Some(mut queue) => match queue.pop_front() {
+                    Some(entry) => entry,
+                    None => return,
+                },
+                None => return,
+            };
+
+            let nets =
+                self.netting_mode == NettingMode::Netting && self.open_position_count(symbol) > 0;
+            if !nets && !self.has_free_slot(symbol) {
+                // Still full; put the request back at the head of the queue
+                self.queued_requests
+                    .entry(symbol.to_string())
+                    .or_default()
+                    .push_front((order_id, request));
+                return;
+            }
+
+            let price = self.fill_price(OrderSide::entry(request.side), symbol, price);
+            let request = PositionRequest { price, ..request };
+            let filled = self.ensure_margin(&request).and_then(|()| {
+                if nets {
+                    self.open_netted(request.clone(), timestamp)
+                        .map(|(_, remainder)| remainder)
+                } else {
+                    Ok(self
+                        .try_open(&request, timestamp)
+                        .is_none()
+                        .then_some(request))
+                }
+            });
+            match filled {
+                Ok(None) => {}
+                Ok(Some(remainder)) => {
+                    self.queued_requests
+                        .entry(symbol.to_string())
+                        .or_default()
+                        .push_front((order_id, remainder));
+                    return;
+                }
+                Err(error) => {
+                    if let Some(listener) = &self.trade_listener {
+                        listener(&TradeEvent::OrderRejected {
+                            order_id,
+                            symbol: symbol.to_string(),
+                            error,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl EventHandler for PositionManager {
+    fn on_tick(&self, event: &TickEvent) {
+        let tick = &event.tick;
+        if self.slippage.is_some() {
+            self.quotes
+                .insert(tick.symbol.clone(), (tick.bid, tick.ask));
+        }
+        let mid = (tick.bid + tick.ask) / 2.0;
+        self.update_price(&tick.symbol, mid);
+        if self.queued_count(&tick.symbol) > 0 {
+            self.open_queued(&tick.symbol, mid, tick.timestamp);
+        }
+        self.check_triggers(tick);
+    }
+
+    fn on_bar(&self, _event: &BarEvent) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::positions::PositionState;
+
+    const T0: i64 = 1704067200000;
+
+    fn long_request(price: f64) -> PositionRequest {
+        PositionRequest::new("EURUSD".to_string(), PositionSide::Long, 10_000.0, price)
+    }
+
+    #[test]
+    fn test_open_and_close() {
+        let manager = PositionManager::new();
+
+        let id = manager
+            .open_position(long_request(1.1000), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+        assert_eq!(manager.open_position_count("EURUSD"), 1);
+
+        manager.update_price("EURUSD", 1.1010);
+        assert!((manager.get_total_floating_pnl(None).unwrap() - 10.0).abs() < 1e-9);
+
+        let pnl = manager.close_position(&id, 1.1020, T0 + 60_000).unwrap();
+        assert!((pnl - 20.0).abs() < 1e-9);
+        assert_eq!(manager.open_position_count("EURUSD"), 0);
+        assert_eq!(manager.get_closed_positions().len(), 1);
+        assert_eq!(
+            manager.get_position(&id).unwrap().state,
+            PositionState::Closed
+        );
+    }
+
+    #[test]
+    fn test_unlimited_positions_by_default() {
+        let manager = PositionManager::new();
+        for _ in 0..5 {
+            manager.open_position(long_request(1.1000), T0).unwrap();
+        }
+        assert_eq!(manager.open_position_count("EURUSD"), 5);
+    }
+
+    #[test]
+    fn test_invalid_requests_rejected() {
+        let manager = PositionManager::new();
+
+        let zero_quantity =
+            PositionRequest::new("EURUSD".to_string(), PositionSide::Long, 0.0, 1.1000);
+        assert_eq!(
+            manager.open_position(zero_quantity, T0),
+            Err(PositionError::InvalidQuantity(0.0))
+        );
+
+        let bad_price =
+            PositionRequest::new("EURUSD".to_string(), PositionSide::Long, 1_000.0, -1.0);
+        assert_eq!(
+            manager.open_position(bad_price, T0),
+            Err(PositionError::InvalidPrice(-1.0))
+        );
+    }
+
+    #[test]
+    fn test_close_unknown_position() {
+        let manager = PositionManager::new();
+        let id = Uuid::new_v4();
+        assert_eq!(
+            manager.close_position(&id, 1.1000, T0),
+            Err(PositionError::NotFound(id))
+        );
+    }
+
+    #[test]
+    fn test_limit_rejects_second_open() {
+        let manager = PositionManager::new()
+            .with_position_limit(PositionLimit::new(1, OverflowPolicy::Reject));
+
+        manager.open_position(long_request(1.1000), T0).unwrap();
+        let result = manager.open_position(long_request(1.1005), T0 + 1_000);
+
+        assert_eq!(
+            result,
+            Err(PositionError::LimitReached {
+                symbol: "EURUSD".to_string(),
+                limit: 1,
+            })
+        );
+        assert_eq!(manager.open_position_count("EURUSD"), 1);
+        assert_eq!(manager.queued_count("EURUSD"), 0);
+
+        // Other symbols have their own slots
+        let gbp = PositionRequest::new("GBPUSD".to_string(), PositionSide::Short, 1_000.0, 1.27);
+        assert!(matches!(
+            manager.open_position(gbp, T0),
+            Ok(OpenOutcome::Opened(_))
+        ));
+    }
+
+    #[test]
+    fn test_limit_queues_and_opens_after_close() {
+        let manager = PositionManager::new()
+            .with_position_limit(PositionLimit::new(1, OverflowPolicy::Queue));
+
+        let first = manager
+            .open_position(long_request(1.1000), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+
+        let outcome = manager
+            .open_position(long_request(1.1005).with_stop_loss(1.0950), T0 + 1_000)
+            .unwrap();
+        assert!(matches!(
+            outcome,
+            OpenOutcome::Queued {
+                queue_position: 1,
+                ..
+            }
+        ));
+        assert_eq!(manager.open_position_count("EURUSD"), 1);
+        assert_eq!(manager.queued_count("EURUSD"), 1);
+
+        manager.close_position(&first, 1.1030, T0 + 60_000).unwrap();
+
+        let open = manager.get_open_positions("EURUSD");
+        assert_eq!(open.len(), 1);
+        assert_ne!(open[0].id, first);
+        assert_eq!(open[0].entry_price, 1.1030);
+        assert_eq!(open[0].opened_at, T0 + 60_000);
+        assert_eq!(open[0].stop_loss, Some(1.0950));
+        assert_eq!(manager.queued_count("EURUSD"), 0);
+    }
+
+    #[test]
+    fn test_queue_drains_in_order() {
+        let manager = PositionManager::new()
+            .with_position_limit(PositionLimit::new(1, OverflowPolicy::Queue));
+
+        let first = manager
+            .open_position(long_request(1.1000), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+
+        let short = PositionRequest::new("EURUSD".to_string(), PositionSide::Short, 500.0, 1.1);
+        manager.open_position(short, T0).unwrap();
+        let outcome = manager.open_position(long_request(1.1), T0).unwrap();
+        assert!(matches!(
+            outcome,
+            OpenOutcome::Queued {
+                queue_position: 2,
+                ..
+            }
+        ));
+
+        manager.close_position(&first, 1.1010, T0 + 1_000).unwrap();
+
+        // Only one slot freed, so only the oldest queued request opens
+        let open = manager.get_open_positions("EURUSD");
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].side, PositionSide::Short);
+        assert_eq!(manager.queued_count("EURUSD"), 1);
+
+        assert_eq!(manager.clear_queue("EURUSD"), 1);
+        assert_eq!(manager.queued_count("EURUSD"), 0);
+    }
+
+    #[test]
+    fn test_queued_request_short_of_margin_is_rejected_when_its_slot_frees() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let manager = PositionManager::new()
+            .with_starting_balance(1_000.0)
+            .with_position_limit(PositionLimit::new(1, OverflowPolicy::Queue))
+            .with_trade_listener(move |event| sink.lock().unwrap().push(event.clone()));
+
+        let first = manager
+            .open_position(long_request(1.1000), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+
+        // Needs 11,000 of margin, but a queued request holds none yet
+        let too_big =
+            PositionRequest::new("EURUSD".to_string(), PositionSide::Long, 1_000_000.0, 1.1);
+        let Ok(OpenOutcome::Queued {
+            order_id: too_big,
+            queue_position: 1,
+        }) = manager.open_position(too_big, T0 + 1_000)
+        else {
+            panic!("expected the request to queue");
+        };
+        manager
+            .open_position(long_request(1.1), T0 + 2_000)
+            .unwrap();
+        assert_eq!(manager.queued_count("EURUSD"), 2);
+
+        // The unaffordable request is dropped instead of blocking the one
+        // behind it
+        manager.close_position(&first, 1.1000, T0 + 60_000).unwrap();
+        let open = manager.get_open_positions("EURUSD");
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].quantity, 10_000.0);
+        assert_eq!(manager.queued_count("EURUSD"), 0);
+        assert!(events.lock().unwrap().iter().any(|event| matches!(
+            event,
+            TradeEvent::OrderRejected {
+                order_id,
+                error: PositionError::InsufficientMargin { .. },
+                ..
+            } if *order_id == too_big
+        )));
+    }
+
+    #[test]
+    fn test_fills_blocked_during_spike_cooldown() {
+        use crate::mtf::SpikeFilterConfig;
+        use backtestr_data::Tick;
+
+        let filter = Arc::new(RwLock::new(SpikeFilter::new(SpikeFilterConfig {
+            atr_period: 5,
+            cooldown_ms: 10_000,
+            ..Default::default()
+        })));
+        let manager = PositionManager::new().with_fill_guard(filter.clone());
+
+        for i in 0..10 {
+            let offset = if i % 2 == 0 { 0.0 } else { 0.0001 };
+            let tick = Tick::new_with_millis(
+                "EURUSD".to_string(),
+                T0 + i * 1_000,
+                1.0999 + offset,
+                1.1001 + offset,
+            );
+            filter.write().unwrap().check_tick(&tick);
+        }
+
+        let spike_time = T0 + 10_000;
+        let spike = Tick::new_with_millis("EURUSD".to_string(), spike_time, 1.1060, 1.1080);
+        assert!(filter.write().unwrap().check_tick(&spike));
+
+        assert_eq!(
+            manager.open_position(long_request(1.1070), spike_time + 1_000),
+            Err(PositionError::FillsBlocked {
+                symbol: "EURUSD".to_string(),
+                until: spike_time + 10_000,
+            })
+        );
+
+        // Other symbols and fills after the cooldown are unaffected
+        let gbp = PositionRequest::new("GBPUSD".to_string(), PositionSide::Long, 1_000.0, 1.27);
+        assert!(manager.open_position(gbp, spike_time + 1_000).is_ok());
+        assert!(manager
+            .open_position(long_request(1.1000), spike_time + 10_000)
+            .is_ok());
+    }
+
+    /// A warmed-up spike filter for EURUSD with a 10s cooldown
+    fn warm_guard() -> Arc<RwLock<SpikeFilter>> {
+        use crate::mtf::SpikeFilterConfig;
+
+        let filter = Arc::new(RwLock::new(SpikeFilter::new(SpikeFilterConfig {
+            atr_period: 5,
+            cooldown_ms: 10_000,
+            ..Default::default()
+        })));
+        for i in 0..10 {
+            let offset = if i % 2 == 0 { 0.0 } else { 0.0001 };
+            let tick = backtestr_data::Tick::new_with_millis(
+                "EURUSD".to_string(),
+                T0 + i * 1_000,
+                1.0999 + offset,
+                1.1001 + offset,
+            );
+            filter.write().unwrap().check_tick(&tick);
+        }
+        filter
+    }
+
+    /// Start a cooldown on `guard`, returning the spike's timestamp
+    fn trigger_spike(guard: &RwLock<SpikeFilter>) -> i64 {
+        let spike_time = T0 + 10_000;
+        let spike =
+            backtestr_data::Tick::new_with_millis("EURUSD".to_string(), spike_time, 1.1060, 1.1080);
+        assert!(guard.write().unwrap().check_tick(&spike));
+        spike_time
+    }
+
+    /// One long open and a short queued behind it, on a limit of one
+    fn full_queueing_manager(guard: Arc<RwLock<SpikeFilter>>) -> (PositionManager, Uuid) {
+        let manager = PositionManager::new()
+            .with_fill_guard(guard)
+            .with_position_limit(PositionLimit::new(1, OverflowPolicy::Queue));
+        let first = manager
+            .open_position(long_request(1.1000), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+        let queued = PositionRequest::new("EURUSD".to_string(), PositionSide::Short, 500.0, 1.1);
+        manager.open_position(queued, T0).unwrap();
+        (manager, first)
+    }
+
+    #[test]
+    fn test_queue_drains_on_first_tick_after_cooldown() {
+        let guard = warm_guard();
+        let (manager, first) = full_queueing_manager(guard.clone());
+        let spike_time = trigger_spike(&guard);
+
+        // The close frees the slot during the cooldown, so nothing opens yet
+        manager.close_position(&first, 1.1070, spike_time).unwrap();
+        assert_eq!(manager.open_position_count("EURUSD"), 0);
+        assert_eq!(manager.queued_count("EURUSD"), 1);
+
+        let during = backtestr_data::Tick::new_with_millis(
+            "EURUSD".to_string(),
+            spike_time + 5_000,
+            1.1,
+            1.1002,
+        );
+        manager.on_tick(&TickEvent::from_tick(during));
+        assert_eq!(manager.open_position_count("EURUSD"), 0);
+
+        let after = backtestr_data::Tick::new_with_millis(
+            "EURUSD".to_string(),
+            spike_time + 10_000,
+            1.1,
+            1.1002,
+        );
+        manager.on_tick(&TickEvent::from_tick(after));
+        let open = manager.get_open_positions("EURUSD");
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].side, PositionSide::Short);
+        assert_eq!(manager.queued_count("EURUSD"), 0);
+    }
+
+    #[test]
+    fn test_new_request_waits_behind_queue() {
+        let guard = warm_guard();
+        let (manager, first) = full_queueing_manager(guard.clone());
+        let spike_time = trigger_spike(&guard);
+        manager.close_position(&first, 1.1070, spike_time).unwrap();
+
+        // The older request takes the free slot; the new one queues behind it
+        let outcome = manager
+            .open_position(long_request(1.1), spike_time + 10_000)
+            .unwrap();
+        assert!(matches!(
+            outcome,
+            OpenOutcome::Queued {
+                queue_position: 1,
+                ..
+            }
+        ));
+        let open = manager.get_open_positions("EURUSD");
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].side, PositionSide::Short);
+        assert_eq!(manager.queued_count("EURUSD"), 1);
+    }
+
+    #[test]
+    fn test_overflow_without_limit_queues() {
+        let manager = PositionManager::new();
+        assert_eq!(manager.overflow(Uuid::new_v4(), long_request(1.1)), Ok(1));
+        assert_eq!(manager.overflow(Uuid::new_v4(), long_request(1.1)), Ok(2));
+
+        // Nothing stops the waiting requests from opening on the next fill
+        manager.open_position(long_request(1.1), T0).unwrap();
+        assert_eq!(manager.queued_count("EURUSD"), 0);
+        assert_eq!(manager.get_open_positions("EURUSD").len(), 3);
+    }
+
+    #[test]
+    fn test_cost_model_deducted_on_close() {
+        let closed = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = closed.clone();
+        let manager = PositionManager::new()
+            .with_cost_model(CostModel::new(3.5, 6.0, -2.0))
+            .with_trade_listener(move |event| sink.lock().unwrap().push(event.clone()));
+
+        let id = manager
+            .open_position(
+                PositionRequest::new("EURUSD".to_string(), PositionSide::Long, 100_000.0, 1.1),
+                T0,
+            )
+            .unwrap()
+            .position_id()
+            .unwrap();
+
+        // Gross +100, commission 7, two days of swap at 6 per lot
+        let net = manager
+            .close_position(&id, 1.1010, T0 + 2 * 86_400_000)
+            .unwrap();
+        assert!((net - 81.0).abs() < 1e-9);
+
+        let position = manager.get_position(&id).unwrap();
+        assert!((position.realized_pnl - 81.0).abs() < 1e-9);
+        assert!((position.gross_pnl() - 100.0).abs() < 1e-9);
+        assert!((position.commission - 7.0).abs() < 1e-9);
+        assert!((position.swap - 12.0).abs() < 1e-9);
+
+        let events = closed.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            TradeEvent::PositionClosed {
+                id: event_id,
+                gross_pnl,
+                net_pnl,
+                ..
+            } => {
+                assert_eq!(event_id, &id);
+                assert!((gross_pnl - 100.0).abs() < 1e-9);
+                assert!((net_pnl - 81.0).abs() < 1e-9);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_costs_can_turn_winner_into_loser() {
+        let manager = PositionManager::new().with_cost_model(CostModel::new(5.0, 0.0, 0.0));
+        let id = manager
+            .open_position(
+                PositionRequest::new("EURUSD".to_string(), PositionSide::Short, 100_000.0, 1.1),
+                T0,
+            )
+            .unwrap()
+            .position_id()
+            .unwrap();
+
+        // Gross +5 does not cover the 10 round-trip commission
+        let net = manager.close_position(&id, 1.09995, T0 + 60_000).unwrap();
+        assert!(net < 0.0);
+        assert!((net + 5.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_on_tick_queues_triggers_for_processing() {
+        use backtestr_data::Tick;
+
+        let manager = PositionManager::new();
+        let long = manager
+            .open_position(long_request(1.1000).with_stop_loss(1.0950), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+        let short = manager
+            .open_position(
+                PositionRequest::new("EURUSD".to_string(), PositionSide::Short, 10_000.0, 1.1)
+                    .with_take_profit(1.0960),
+                T0,
+            )
+            .unwrap()
+            .position_id()
+            .unwrap();
+
+        let tick = |bid: f64, ask: f64, ts: i64| {
+            TickEvent::from_tick(Tick::new_with_millis("EURUSD".to_string(), ts, bid, ask))
+        };
+
+        manager.on_tick(&tick(1.0990, 1.0992, T0 + 1_000));
+        assert_eq!(manager.pending_close_count(), 0);
+
+        // Bid breaks the long's stop and ask reaches the short's target
+        manager.on_tick(&tick(1.0948, 1.0950, T0 + 2_000));
+        // Repeated triggers before processing are not queued twice
+        manager.on_tick(&tick(1.0945, 1.0947, T0 + 3_000));
+        assert_eq!(manager.pending_close_count(), 2);
+        assert_eq!(manager.get_open_positions("EURUSD").len(), 2);
+
+        let closed = manager.process_pending_closes();
+        assert_eq!(closed.len(), 2);
+        assert_eq!(manager.open_position_count("EURUSD"), 0);
+
+        let long_position = manager.get_position(&long).unwrap();
+        assert_eq!(long_position.close_price, Some(1.0948));
+        assert_eq!(long_position.closed_at, Some(T0 + 2_000));
+        assert_eq!(
+            manager.get_position(&short).unwrap().close_price,
+            Some(1.0950)
+        );
+
+        assert!(manager.process_pending_closes().is_empty());
+    }
+
+    #[test]
+    fn test_pending_close_skips_already_closed_position() {
+        use backtestr_data::Tick;
+
+        let manager = PositionManager::new();
+        let id = manager
+            .open_position(long_request(1.1000).with_take_profit(1.1010), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+
+        let tick = Tick::new_with_millis("EURUSD".to_string(), T0 + 1_000, 1.1012, 1.1014);
+        manager.on_tick(&TickEvent::from_tick(tick));
+        assert_eq!(manager.pending_close_count(), 1);
+
+        // Closed manually before the queue is drained
+        manager.close_position(&id, 1.1011, T0 + 1_500).unwrap();
+
+        assert!(manager.process_pending_closes().is_empty());
+        let position = manager.get_position(&id).unwrap();
+        assert_eq!(position.close_price, Some(1.1011));
+        assert_eq!(manager.get_closed_positions().len(), 1);
+    }
+
+    #[test]
+    fn test_failed_pending_close_is_reported() {
+        use backtestr_data::Tick;
+
+        let manager = PositionManager::new().with_pnl_calculator(PnlCalculator::new("GBP"));
+        let id = manager
+            .open_position(
+                PositionRequest::new("EURGBP".to_string(), PositionSide::Long, 10_000.0, 0.8600)
+                    .with_stop_loss(0.8550),
+                T0,
+            )
+            .unwrap()
+            .position_id()
+            .unwrap();
+
+        // The account switches to USD while the position is open, and no
+        // GBP rate is known, so the stop can't be booked
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let manager = manager
+            .clone()
+            .with_pnl_calculator(PnlCalculator::new("USD"))
+            .with_trade_listener(move |event| sink.lock().unwrap().push(event.clone()));
+        let tick = Tick::new_with_millis("EURGBP".to_string(), T0 + 1_000, 0.8540, 0.8542);
+        manager.on_tick(&TickEvent::from_tick(tick));
+        assert!(manager.process_pending_closes().is_empty());
+        assert!(manager.get_position(&id).unwrap().is_open());
+        assert_eq!(
+            events.lock().unwrap().as_slice(),
+            [TradeEvent::CloseFailed {
+                id,
+                symbol: "EURGBP".to_string(),
+                reason: CloseReason::StopLoss,
+                error: PositionError::MissingRate {
+                    from: "GBP".to_string(),
+                    to: "USD".to_string(),
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn test_floating_pnl_in_account_currency() {
+        let manager = PositionManager::new().with_pnl_calculator(PnlCalculator::new("USD"));
+        manager.open_position(long_request(1.1000), T0).unwrap();
+        manager
+            .open_position(
+                PositionRequest::new("USDJPY".to_string(), PositionSide::Long, 10_000.0, 150.0),
+                T0,
+            )
+            .unwrap();
+
+        manager.update_price("EURUSD", 1.1010);
+        manager.update_price("USDJPY", 150.10);
+
+        // 10 USD + 1,000 JPY
+        let raw = manager.get_total_floating_pnl(None).unwrap();
+        assert!((raw - 1_010.0).abs() < 1e-6);
+
+        let rates = HashMap::from([("USDJPY".to_string(), 150.10)]);
+        let converted = manager.get_total_floating_pnl(Some(&rates)).unwrap();
+        assert!((converted - (10.0 + 1_000.0 / 150.10)).abs() < 1e-6);
+
+        assert!(matches!(
+            manager.get_total_floating_pnl(Some(&HashMap::new())),
+            Err(PositionError::MissingRate { .. })
+        ));
+        assert_eq!(
+            PositionManager::new().get_total_floating_pnl(Some(&rates)),
+            Err(PositionError::NoAccountCurrency)
+        );
+    }
+
+    #[test]
+    fn test_close_expired_positions() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let manager = PositionManager::new()
+            .with_trade_listener(move |event| sink.lock().unwrap().push(event.clone()));
+
+        let expiring = manager
+            .open_position(long_request(1.1000).with_expiry(T0 + 60_000), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+        let no_price = manager
+            .open_position(
+                PositionRequest::new("GBPUSD".to_string(), PositionSide::Short, 10_000.0, 1.2700)
+                    .with_expiry(T0 + 30_000),
+                T0,
+            )
+            .unwrap()
+            .position_id()
+            .unwrap();
+        let later = manager
+            .open_position(long_request(1.1000).with_expiry(T0 + 120_000), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+        manager.open_position(long_request(1.1000), T0).unwrap();
+        manager.update_price("GBPUSD", 1.2690);
+
+        assert!(manager
+            .close_expired(T0 + 10_000, |_| Some(1.1010))
+            .is_empty());
+
+        let lookup = |symbol: &str| (symbol == "EURUSD").then_some(1.1010);
+        let closed = manager.close_expired(T0 + 60_000, lookup);
+
+        // Closed in expiry order; GBPUSD falls back to its last marked price
+        assert_eq!(closed.len(), 2);
+        assert_eq!(closed[0].0, no_price);
+        assert!((closed[0].1 - 10.0).abs() < 1e-9);
+        assert_eq!(closed[1].0, expiring);
+        assert!((closed[1].1 - 10.0).abs() < 1e-9);
+
+        assert_eq!(
+            manager.get_position(&no_price).unwrap().close_price,
+            Some(1.2690)
+        );
+        assert!(manager.get_position(&later).unwrap().is_open());
+        assert_eq!(manager.open_position_count("EURUSD"), 2);
+        assert_eq!(events.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_netting_reduces_then_flips() {
+        let manager = PositionManager::new().with_netting_mode(NettingMode::Netting);
+        let sell = |quantity| {
+            PositionRequest::new("EURUSD".to_string(), PositionSide::Short, quantity, 1.1010)
+        };
+
+        let long = manager
+            .open_position(
+                PositionRequest::new("EURUSD".to_string(), PositionSide::Long, 50_000.0, 1.1000),
+                T0,
+            )
+            .unwrap()
+            .position_id()
+            .unwrap();
+
+        // Selling 30k leaves a single 20k long and books 10 pips on 30k
+        let outcome = manager.open_position(sell(30_000.0), T0 + 60_000).unwrap();
+        match outcome {
+            OpenOutcome::Netted {
+                position_id,
+                realized_pnl,
+            } => {
+                assert_eq!(position_id, Some(long));
+                assert!((realized_pnl - 30.0).abs() < 1e-9);
+            }
+            other => panic!("expected netted outcome, got {:?}", other),
+        }
+        let open = manager.get_open_positions("EURUSD");
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].side, PositionSide::Long);
+        assert!((open[0].quantity - 20_000.0).abs() < 1e-9);
+        assert_eq!(manager.get_closed_positions().len(), 1);
+        assert!((manager.statistics().total_realized_pnl - 30.0).abs() < 1e-9);
+
+        // Selling 40k more closes the remaining 20k and flips to a 20k short
+        let outcome = manager.open_position(sell(40_000.0), T0 + 120_000).unwrap();
+        let short = outcome.position_id().unwrap();
+        assert_ne!(short, long);
+        let open = manager.get_open_positions("EURUSD");
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].side, PositionSide::Short);
+        assert!((open[0].quantity - 20_000.0).abs() < 1e-9);
+        assert!(!manager.get_position(&long).unwrap().is_open());
+    }
+
+    #[test]
+    fn test_netting_flip_keeps_its_slot_and_queue_nets() {
+        let manager = PositionManager::new()
+            .with_netting_mode(NettingMode::Netting)
+            .with_position_limit(PositionLimit::new(1, OverflowPolicy::Queue));
+        let request = |side, quantity, price| {
+            PositionRequest::new("EURUSD".to_string(), side, quantity, price)
+        };
+        let long = manager
+            .open_position(request(PositionSide::Long, 50_000.0, 1.1000), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+        // A request left waiting from when the symbol was full
+        manager
+            .queued_requests
+            .entry("EURUSD".to_string())
+            .or_default()
+            .push_back((
+                Uuid::new_v4(),
+                request(PositionSide::Long, 10_000.0, 1.1000),
+            ));
+
+        // The queued long nets into the open one first, then the sell closes
+        // all 60k and its 20k remainder takes the freed slot
+        let outcome = manager
+            .open_position(request(PositionSide::Short, 80_000.0, 1.0990), T0 + 60_000)
+            .unwrap();
+        assert_eq!(manager.queued_count("EURUSD"), 0);
+        let open = manager.get_open_positions("EURUSD");
+        assert_eq!(open.len(), 1);
+        assert_eq!(outcome.position_id(), Some(open[0].id));
+        assert_eq!(open[0].side, PositionSide::Short);
+        assert!((open[0].quantity - 20_000.0).abs() < 1e-9);
+        let closed = manager.get_position(&long).unwrap();
+        assert!((closed.quantity - 60_000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_netting_flips_in_one_fill() {
+        let manager = PositionManager::new().with_netting_mode(NettingMode::Netting);
+        manager
+            .open_position(
+                PositionRequest::new("EURUSD".to_string(), PositionSide::Long, 50_000.0, 1.1000),
+                T0,
+            )
+            .unwrap();
+
+        let outcome = manager
+            .open_position(
+                PositionRequest::new("EURUSD".to_string(), PositionSide::Short, 70_000.0, 1.0990),
+                T0 + 60_000,
+            )
+            .unwrap();
+        match outcome {
+            OpenOutcome::Netted { realized_pnl, .. } => {
+                assert!((realized_pnl + 50.0).abs() < 1e-9)
+            }
+            other => panic!("expected netted outcome, got {:?}", other),
+        }
+
+        let open = manager.get_open_positions("EURUSD");
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].side, PositionSide::Short);
+        assert!((open[0].quantity - 20_000.0).abs() < 1e-9);
+        assert_eq!(open[0].entry_price, 1.0990);
+    }
+
+    #[test]
+    fn test_netting_same_side_averages_entry() {
+        let manager = PositionManager::new().with_netting_mode(NettingMode::Netting);
+        let id = manager
+            .open_position(long_request(1.1000), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+        let outcome = manager.open_position(long_request(1.1020), T0).unwrap();
+        assert_eq!(outcome.position_id(), Some(id));
+
+        let position = manager.get_position(&id).unwrap();
+        assert!((position.quantity - 20_000.0).abs() < 1e-9);
+        assert!((position.entry_price - 1.1010).abs() < 1e-9);
+        assert_eq!(manager.open_position_count("EURUSD"), 1);
+    }
+
+    #[test]
+    fn test_add_to_position_averages_one_position() {
+        let manager = PositionManager::new().with_netting_mode(NettingMode::Netting);
+        let id = manager
+            .open_position(
+                PositionRequest::new("EURUSD".to_string(), PositionSide::Short, 10_000.0, 1.1000),
+                T0,
+            )
+            .unwrap()
+            .position_id()
+            .unwrap();
+
+        manager.add_to_position(&id, 30_000.0, 1.0960).unwrap();
+        let position = manager.get_position(&id).unwrap();
+        assert!((position.quantity - 40_000.0).abs() < 1e-9);
+        assert!((position.entry_price - 1.0970).abs() < 1e-9);
+        assert_eq!(position.opened_at, T0);
+        assert_eq!(manager.get_open_positions("EURUSD").len(), 1);
+        assert!(manager.get_children(&id).is_empty());
+
+        // Buying back half at 1.0950 books 20 pips from the averaged entry
+        let outcome = manager
+            .open_position(
+                PositionRequest::new("EURUSD".to_string(), PositionSide::Long, 20_000.0, 1.0950),
+                T0 + 60_000,
+            )
+            .unwrap();
+        match outcome {
+            OpenOutcome::Netted { realized_pnl, .. } => {
+                assert!((realized_pnl - 40.0).abs() < 1e-9)
+            }
+            other => panic!("expected netted outcome, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_add_to_long_position_averages_up() {
+        let manager = PositionManager::new().with_netting_mode(NettingMode::Netting);
+        let id = manager
+            .open_position(long_request(1.1000), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+
+        // 10k at 1.1000 and 30k at 1.1040 average to 1.1030
+        manager.add_to_position(&id, 30_000.0, 1.1040).unwrap();
+        let position = manager.get_position(&id).unwrap();
+        assert!((position.quantity - 40_000.0).abs() < 1e-9);
+        assert!((position.entry_price - 1.1030).abs() < 1e-9);
+
+        manager.update_price("EURUSD", 1.1040);
+        let position = manager.get_position(&id).unwrap();
+        assert!((position.unrealized_pnl - 40.0).abs() < 1e-9);
+
+        // Selling half at 1.1050 books 20 pips from the averaged entry and
+        // leaves the rest open at the same entry
+        let outcome = manager
+            .open_position(
+                PositionRequest::new("EURUSD".to_string(), PositionSide::Short, 20_000.0, 1.1050),
+                T0 + 60_000,
+            )
+            .unwrap();
+        match outcome {
+            OpenOutcome::Netted { realized_pnl, .. } => {
+                assert!((realized_pnl - 40.0).abs() < 1e-9)
+            }
+            other => panic!("expected netted outcome, got {:?}", other),
+        }
+        let position = manager.get_position(&id).unwrap();
+        assert!(position.is_open());
+        assert!((position.quantity - 20_000.0).abs() < 1e-9);
+        assert!((position.entry_price - 1.1030).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_add_to_position_validation() {
+        let manager = PositionManager::new().with_starting_balance(1_000.0);
+        let id = manager
+            .open_position(long_request(1.1000), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+
+        assert!(manager.add_to_position(&id, 0.0, 1.1000).is_err());
+        assert!(manager.add_to_position(&id, 10_000.0, f64::NAN).is_err());
+        assert!(matches!(
+            manager.add_to_position(&Uuid::new_v4(), 10_000.0, 1.1000),
+            Err(PositionError::NotFound(_))
+        ));
+
+        // 10k more at 1:100 fits in 1,000; another 1m does not
+        manager.add_to_position(&id, 10_000.0, 1.1020).unwrap();
+        assert!(matches!(
+            manager.add_to_position(&id, 1_000_000.0, 1.1020),
+            Err(PositionError::InsufficientMargin { .. })
+        ));
+
+        manager.close_position(&id, 1.1010, T0 + 60_000).unwrap();
+        assert_eq!(
+            manager.add_to_position(&id, 10_000.0, 1.1010),
+            Err(PositionError::NotOpen {
+                id,
+                state: PositionState::Closed
+            })
+        );
+
+        let pending = manager
+            .place_pending_position(long_request(1.0950), T0)
+            .unwrap();
+        assert!(matches!(
+            manager.add_to_position(&pending, 10_000.0, 1.0950),
+            Err(PositionError::NotOpen {
+                state: PositionState::Pending,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_hedging_keeps_opposite_positions() {
+        let manager = PositionManager::new();
+        manager.open_position(long_request(1.1000), T0).unwrap();
+        manager
+            .open_position(
+                PositionRequest::new("EURUSD".to_string(), PositionSide::Short, 10_000.0, 1.1000),
+                T0,
+            )
+            .unwrap();
+        assert_eq!(manager.open_position_count("EURUSD"), 2);
+    }
+
+    #[test]
+    fn test_child_positions_indexed() {
+        let manager = PositionManager::new();
+        let parent = manager
+            .open_position(long_request(1.1000), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+        let child = manager
+            .open_position(long_request(1.1010).with_parent(parent), T0 + 1_000)
+            .unwrap()
+            .position_id()
+            .unwrap();
+
+        assert_eq!(manager.get_children(&parent), vec![child]);
+        assert_eq!(
+            manager.get_position(&child).unwrap().parent_id,
+            Some(parent)
+        );
+    }
+
+    #[test]
+    fn test_slippage_applied_to_entry_and_exit() {
+        use crate::positions::FixedPips;
+        use backtestr_data::Tick;
+
+        let manager = PositionManager::new().with_slippage_model(Arc::new(FixedPips::new(1.0)));
+        let tick = Tick::new_with_millis("EURUSD".to_string(), T0, 1.1000, 1.1002);
+        manager.on_tick(&TickEvent::from_tick(tick));
+
+        let long = manager
+            .open_position(long_request(1.1001), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+        let short = manager
+            .open_position(
+                PositionRequest::new("EURUSD".to_string(), PositionSide::Short, 10_000.0, 1.1001),
+                T0,
+            )
+            .unwrap()
+            .position_id()
+            .unwrap();
+
+        // Buys fill at ask + 1 pip, sells at bid - 1 pip
+        assert!((manager.get_position(&long).unwrap().entry_price - 1.1003).abs() < 1e-12);
+        assert!((manager.get_position(&short).unwrap().entry_price - 1.0999).abs() < 1e-12);
+
+        manager.close_position(&long, 1.1001, T0 + 1).unwrap();
+        manager.close_position(&short, 1.1001, T0 + 1).unwrap();
+        assert!((manager.get_position(&long).unwrap().close_price.unwrap() - 1.0999).abs() < 1e-12);
+        assert!(
+            (manager.get_position(&short).unwrap().close_price.unwrap() - 1.1003).abs() < 1e-12
+        );
+    }
+
+    #[test]
+    fn test_perfect_execution_fills_at_requested_price() {
+        let manager = PositionManager::new().with_execution_model(ExecutionModel::Perfect);
+        let id = manager
+            .open_position(long_request(1.1001), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+        assert_eq!(manager.get_position(&id).unwrap().entry_price, 1.1001);
+    }
+
+    /// Closed trades of a fixed session with randomized slippage; ids are
+    /// random and left out
+    fn seeded_trade_log(seed: Option<u64>) -> Vec<TradeEvent> {
+        use crate::positions::RandomPips;
+        use backtestr_data::Tick;
+
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let mut manager = PositionManager::new()
+            .with_slippage_model(Arc::new(RandomPips::new(0.0, 3.0)))
+            .with_trade_listener(move |event| sink.lock().unwrap().push(event.clone()));
+        if let Some(seed) = seed {
+            manager = manager.with_seed(seed);
+        }
+
+        for i in 0..10 {
+            let timestamp = T0 + i * 1_000;
+            let bid = 1.1000 + i as f64 * 0.0001;
+            let tick = Tick::new_with_millis("EURUSD".to_string(), timestamp, bid, bid + 0.0002);
+            manager.on_tick(&TickEvent::from_tick(tick));
+
+            let id = manager
+                .open_position(long_request(bid + 0.0001), timestamp)
+                .unwrap()
+                .position_id()
+                .unwrap();
+            manager
+                .close_position(&id, bid + 0.0001, timestamp)
+                .unwrap();
+        }
+
+        let events = events.lock().unwrap().clone();
+        events
+    }
+
+    #[test]
+    fn test_seeded_fills_are_reproducible() {
+        assert_eq!(
+            PositionManager::new().seed(),
+            crate::positions::DEFAULT_FILL_SEED
+        );
+
+        let first = seeded_trade_log(Some(11));
+        assert_eq!(first.len(), 10);
+        assert_eq!(first, seeded_trade_log(Some(11)));
+        // No seed still means a fixed seed
+        assert_eq!(seeded_trade_log(None), seeded_trade_log(None));
+
+        // Another seed changes the ids and fill prices, not the trades
+        let other = seeded_trade_log(Some(12));
+        assert_ne!(first, other);
+        for (a, b) in first.iter().zip(&other) {
+            match (a, b) {
+                (
+                    TradeEvent::PositionClosed {
+                        symbol: sa,
+                        side: da,
+                        closed_at: ta,
+                        ..
+                    },
+                    TradeEvent::PositionClosed {
+                        symbol: sb,
+                        side: db,
+                        closed_at: tb,
+                        ..
+                    },
+                ) => assert_eq!((sa, da, ta), (sb, db, tb)),
+                _ => panic!("unexpected event"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_restored_manager_continues_ids_and_fills() {
+        use crate::positions::RandomPips;
+        use backtestr_data::Tick;
+
+        let new_manager = || {
+            PositionManager::new()
+                .with_slippage_model(Arc::new(RandomPips::new(0.0, 3.0)))
+                .with_seed(11)
+        };
+        let trade = |manager: &PositionManager, i: i64| {
+            let timestamp = T0 + i * 1_000;
+            let bid = 1.1000 + i as f64 * 0.0001;
+            let tick = Tick::new_with_millis("EURUSD".to_string(), timestamp, bid, bid + 0.0002);
+            manager.on_tick(&TickEvent::from_tick(tick));
+            let id = manager
+                .open_position(long_request(bid + 0.0001), timestamp)
+                .unwrap()
+                .position_id()
+                .unwrap();
+            (id, manager.get_position(&id).unwrap().entry_price)
+        };
+
+        let manager = new_manager();
+        for i in 0..3 {
+            trade(&manager, i);
+        }
+        let snapshot = manager.snapshot_positions().unwrap();
+        let expected: Vec<_> = (3..6).map(|i| trade(&manager, i)).collect();
+
+        // A manager seeded differently picks up where the checkpoint left off
+        let restored = PositionManager::new()
+            .with_slippage_model(Arc::new(RandomPips::new(0.0, 3.0)))
+            .with_seed(12);
+        restored.restore_positions(&snapshot);
+        assert_eq!(restored.seed(), 11);
+        let resumed: Vec<_> = (3..6).map(|i| trade(&restored, i)).collect();
+        assert_eq!(resumed, expected);
+        assert_ne!(
+            trade(&new_manager(), 3),
+            expected[0],
+            "a fresh manager would repeat the first draws"
+        );
+    }
+
+    #[test]
+    fn test_pending_orders_fill_when_reached() {
+        use crate::positions::Order;
+        use backtestr_data::Tick;
+
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let manager = PositionManager::new()
+            .with_trade_listener(move |event| sink.lock().unwrap().push(event.clone()));
+
+        let limit = manager
+            .place_order(Order::limit(
+                "EURUSD".to_string(),
+                PositionSide::Long,
+                1.0990,
+                10_000.0,
+            ))
+            .unwrap();
+        let stop = manager
+            .place_order(Order::stop(
+                "EURUSD".to_string(),
+                PositionSide::Long,
+                1.1010,
+                10_000.0,
+            ))
+            .unwrap();
+        let tick = |timestamp, bid: f64| {
+            Tick::new_with_millis("EURUSD".to_string(), timestamp, bid, bid + 0.0002)
+        };
+
+        assert!(manager.check_pending_orders(&tick(T0, 1.1000)).is_empty());
+        assert_eq!(manager.pending_orders("EURUSD").len(), 2);
+
+        // Gaps straight through the stop; fills at the trigger by default
+        let filled = manager.check_pending_orders(&tick(T0 + 1, 1.1030));
+        assert_eq!(filled.len(), 1);
+        assert_eq!(filled[0].0, stop);
+        let position = manager
+            .get_position(&filled[0].1.position_id().unwrap())
+            .unwrap();
+        assert_eq!(position.entry_price, 1.1010);
+        assert_eq!(position.opened_at, T0 + 1);
+        assert_eq!(manager.pending_orders("EURUSD")[0].id, limit);
+
+        let events = events.lock().unwrap();
+        assert!(matches!(
+            &events[0],
+            TradeEvent::OrderFilled { order_id, fill_price, .. }
+                if *order_id == stop && *fill_price == 1.1010
+        ));
+    }
+
+    #[test]
+    fn test_gap_fill_at_tick_price_and_cancel() {
+        use crate::positions::{Order, TriggerFillPolicy};
+        use backtestr_data::Tick;
+
+        let manager =
+            PositionManager::new().with_trigger_fill_policy(TriggerFillPolicy::AtTickPrice);
+        manager
+            .place_order(Order::stop(
+                "EURUSD".to_string(),
+                PositionSide::Short,
+                1.0990,
+                10_000.0,
+            ))
+            .unwrap();
+        let cancelled = manager
+            .place_order(Order::limit(
+                "EURUSD".to_string(),
+                PositionSide::Short,
+                1.0950,
+                10_000.0,
+            ))
+            .unwrap();
+        assert!(manager.cancel_order(&cancelled));
+        assert!(!manager.cancel_order(&cancelled));
+
+        // The cancelled limit sell would also be reached by this tick
+        let gap = Tick::new_with_millis("EURUSD".to_string(), T0, 1.0960, 1.0962);
+        let filled = manager.check_pending_orders(&gap);
+        assert_eq!(filled.len(), 1);
+        let position = manager
+            .get_position(&filled[0].1.position_id().unwrap())
+            .unwrap();
+        assert_eq!(position.entry_price, 1.0960);
+        assert!(manager.pending_orders("EURUSD").is_empty());
+    }
+
+    #[test]
+    fn test_rejected_fill_is_reported() {
+        use crate::positions::Order;
+        use backtestr_data::Tick;
+
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let manager = PositionManager::new()
+            .with_position_limit(PositionLimit::new(1, OverflowPolicy::Reject))
+            .with_trade_listener(move |event| sink.lock().unwrap().push(event.clone()));
+        manager.open_position(long_request(1.1000), T0).unwrap();
+
+        let order = manager
+            .place_order(Order::limit(
+                "EURUSD".to_string(),
+                PositionSide::Long,
+                1.1000,
+                10_000.0,
+            ))
+            .unwrap();
+        let tick = Tick::new_with_millis("EURUSD".to_string(), T0, 1.0998, 1.1000);
+        assert!(manager.check_pending_orders(&tick).is_empty());
+        assert!(manager.pending_orders("EURUSD").is_empty());
+
+        let events = events.lock().unwrap();
+        assert!(matches!(
+            events.last(),
+            Some(TradeEvent::OrderRejected {
+                order_id,
+                error: PositionError::LimitReached { .. },
+                ..
+            }) if *order_id == order
+        ));
+    }
+
+    #[test]
+    fn test_account_tracks_balance_equity_and_margin() {
+        let manager = PositionManager::new()
+            .with_starting_balance(1_000.0)
+            .with_cost_model(CostModel::new(7.0, 0.0, 0.0));
+        assert_eq!(manager.account(), Ok(Account::new(1_000.0)));
+
+        let id = manager
+            .open_position(long_request(1.1000), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+        manager.update_price("EURUSD", 1.0990);
+
+        // 10k at 1.10 on 1:100 holds 110; floating -10
+        let account = manager.account().unwrap();
+        assert!((account.used_margin - 110.0).abs() < 1e-9);
+        assert!((account.equity - 990.0).abs() < 1e-9);
+        assert!((account.free_margin - 880.0).abs() < 1e-9);
+        assert!((account.margin_level.unwrap() - 900.0).abs() < 1e-9);
+        assert_eq!(
+            manager.calculate_total_margin().unwrap(),
+            account.used_margin
+        );
+
+        // +20 gross, less the 0.1-lot round turn commission
+        manager.close_position(&id, 1.1020, T0 + 60_000).unwrap();
+        let account = manager.account().unwrap();
+        let net = manager.get_position(&id).unwrap().realized_pnl;
+        assert!((account.balance - (1_000.0 + net)).abs() < 1e-9);
+        assert_eq!(account.equity, account.balance);
+        assert_eq!(account.used_margin, 0.0);
+        assert_eq!(account.margin_level, None);
+    }
+
+    #[test]
+    fn test_open_beyond_free_margin_rejected() {
+        let manager = PositionManager::new().with_starting_balance(1_000.0);
+        manager
+            .open_position(
+                PositionRequest::new("EURUSD".to_string(), PositionSide::Long, 80_000.0, 1.1),
+                T0,
+            )
+            .unwrap();
+
+        // 880 held, 120 free; another 20k needs 220
+        let result = manager.open_position(
+            PositionRequest::new("EURUSD".to_string(), PositionSide::Long, 20_000.0, 1.1),
+            T0,
+        );
+        match result {
+            Err(PositionError::InsufficientMargin {
+                required,
+                available,
+            }) => {
+                assert!((required - 220.0).abs() < 1e-9);
+                assert!((available - 120.0).abs() < 1e-9);
+            }
+            other => panic!("expected InsufficientMargin, got {:?}", other),
+        }
+        assert_eq!(manager.open_position_count("EURUSD"), 1);
+
+        // Closing part of the exposure in netting mode needs no margin
+        let netting = PositionManager::new()
+            .with_starting_balance(1_000.0)
+            .with_netting_mode(NettingMode::Netting);
+        netting
+            .open_position(
+                PositionRequest::new("EURUSD".to_string(), PositionSide::Long, 80_000.0, 1.1),
+                T0,
+            )
+            .unwrap();
+        assert!(netting
+            .open_position(
+                PositionRequest::new("EURUSD".to_string(), PositionSide::Short, 80_000.0, 1.1),
+                T0,
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn test_margin_is_checked_in_account_currency() {
+        let usdjpy = |quantity| {
+            PositionRequest::new("USDJPY".to_string(), PositionSide::Long, quantity, 150.0)
+        };
+
+        // 15,000 JPY of margin is 100 USD
+        let manager = PositionManager::new()
+            .with_pnl_calculator(PnlCalculator::new("USD"))
+            .with_starting_balance(10_000.0);
+        manager.open_position(usdjpy(10_000.0), T0).unwrap();
+        assert!((manager.calculate_total_margin().unwrap() - 100.0).abs() < 1e-9);
+
+        manager.update_price("USDJPY", 151.5);
+        let account = manager.account().unwrap();
+        // 15,000 JPY of floating profit at 151.5
+        assert!((account.equity - (10_000.0 + 15_000.0 / 151.5)).abs() < 1e-9);
+        assert!(matches!(
+            manager.open_position(usdjpy(2_000_000.0), T0),
+            Err(PositionError::InsufficientMargin { .. })
+        ));
+
+        // Without a calculator the account is in USD: 1.5M JPY is 10,000 USD
+        let manager = PositionManager::new().with_starting_balance(1_000.0);
+        assert!(matches!(
+            manager.open_position(usdjpy(1_000_000.0), T0),
+            Err(PositionError::InsufficientMargin { required, .. })
+                if (required - 10_000.0).abs() < 1e-9
+        ));
+
+        // A cross with no rate linking it to USD is rejected, not let through
+        let eurjpy =
+            PositionRequest::new("EURJPY".to_string(), PositionSide::Long, 10_000.0, 160.0);
+        assert!(matches!(
+            manager.open_position(eurjpy.clone(), T0),
+            Err(PositionError::MissingRate { .. })
+        ));
+        manager.update_price("USDJPY", 150.0);
+        assert!(manager.open_position(eurjpy, T0).is_ok());
+
+        // Margin is only enforced once a starting balance is configured
+        let manager = PositionManager::new();
+        assert!(manager
+            .open_position(
+                PositionRequest::new("EURUSD".to_string(), PositionSide::Long, 10_000_000.0, 1.1),
+                T0,
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn test_close_books_pnl_in_account_currency() {
+        let manager = PositionManager::new().with_starting_balance(10_000.0);
+        let id = manager
+            .open_position(
+                PositionRequest::new("USDJPY".to_string(), PositionSide::Long, 10_000.0, 150.0),
+                T0,
+            )
+            .unwrap()
+            .position_id()
+            .unwrap();
+
+        // 1,500 JPY of profit is booked as 10 USD at the close price
+        let net = manager.close_position(&id, 150.15, T0 + 60_000).unwrap();
+        assert!((net - 1_500.0).abs() < 1e-6);
+        let balance = manager.account().unwrap().balance;
+        assert!((balance - (10_000.0 + 1_500.0 / 150.15)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_close_without_rate_leaves_position_open() {
+        let source = PositionManager::new();
+        source.update_price("USDJPY", 150.0);
+        let id = source
+            .open_position(
+                PositionRequest::new("EURJPY".to_string(), PositionSide::Long, 10_000.0, 160.0),
+                T0,
+            )
+            .unwrap()
+            .position_id()
+            .unwrap();
+
+        // Restored without the USDJPY price that linked EURJPY to USD
+        let manager = PositionManager::new();
+        manager.restore_positions(&source.snapshot_positions().unwrap());
+        assert!(matches!(
+            manager.account(),
+            Err(PositionError::MissingRate { .. })
+        ));
+        assert!(matches!(
+            manager.close_position(&id, 160.5, T0 + 60_000),
+            Err(PositionError::MissingRate { .. })
+        ));
+        assert!(manager.get_position(&id).unwrap().is_open());
+
+        manager.update_price("USDJPY", 150.0);
+        manager.close_position(&id, 160.5, T0 + 60_000).unwrap();
+        assert!(manager.account().is_ok());
+    }
+
+    #[test]
+    fn test_margin_call_emitted_on_breach() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let manager = PositionManager::new()
+            .with_starting_balance(1_000.0)
+            .with_margin_call_level(50.0)
+            .with_trade_listener(move |event| sink.lock().unwrap().push(event.clone()));
+
+        manager
+            .open_position(
+                PositionRequest::new("EURUSD".to_string(), PositionSide::Long, 80_000.0, 1.1),
+                T0,
+            )
+            .unwrap();
+
+        // -600 leaves equity 400 against 880 held: 45%
+        manager.update_price("EURUSD", 1.0925);
+        manager.update_price("EURUSD", 1.0920);
+
+        let events = events.lock().unwrap();
+        let calls: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                TradeEvent::MarginCall { margin_level, .. } => Some(*margin_level),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(calls.len(), 1);
+        assert!((calls[0] - 400.0 / 880.0 * 100.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_margin_uses_symbol_leverage() {
+        let manager = PositionManager::new()
+            .with_starting_balance(100_000.0)
+            .with_default_leverage(20.0)
+            .unwrap();
+        manager.set_leverage("EURUSD", 30.0).unwrap();
+        assert_eq!(manager.leverage("EURUSD"), 30.0);
+        assert_eq!(manager.leverage("US500"), 20.0);
+
+        let open = |symbol: &str, price: f64| {
+            manager
+                .open_position(
+                    PositionRequest::new(symbol.to_string(), PositionSide::Long, 30_000.0, price),
+                    T0,
+                )
+                .unwrap();
+        };
+        open("EURUSD", 1.0);
+        open("US500", 2.0);
+
+        // 30,000 / 30 + 60,000 / 20
+        assert!((manager.calculate_total_margin().unwrap() - 4_000.0).abs() < 1e-9);
+
+        // Re-margined at the new leverage
+        manager.set_leverage("US500", 10.0).unwrap();
+        assert!((manager.account().unwrap().used_margin - 7_000.0).abs() < 1e-9);
+
+        assert_eq!(
+            manager.set_leverage("US500", 0.0),
+            Err(PositionError::InvalidLeverage(0.0))
+        );
+        assert!(PositionManager::new().with_default_leverage(-1.0).is_err());
+        assert_eq!(
+            PositionManager::new().default_leverage(),
+            crate::positions::DEFAULT_LEVERAGE
+        );
+    }
+
+    #[test]
+    fn test_cancel_pending_position() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let manager = PositionManager::new()
+            .with_trade_listener(move |event| sink.lock().unwrap().push(event.clone()));
+
+        let parent = manager
+            .open_position(long_request(1.1000), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+        let pending = manager
+            .place_pending_position(long_request(1.0950).with_parent(parent), T0)
+            .unwrap();
+        assert_eq!(manager.get_children(&parent), vec![pending]);
+        assert_eq!(manager.open_position_count("EURUSD"), 1);
+        assert_eq!(manager.account().unwrap().used_margin, 110.0);
+
+        manager.cancel_position(&pending).unwrap();
+        assert_eq!(
+            manager.get_position(&pending).unwrap().state,
+            PositionState::Cancelled
+        );
+        assert!(manager.get_children(&parent).is_empty());
+        assert!(manager.get_closed_positions().is_empty());
+        assert_eq!(
+            events.lock().unwrap().last(),
+            Some(&TradeEvent::PositionCancelled {
+                id: pending,
+                symbol: "EURUSD".to_string()
+            })
+        );
+
+        // Open, closed and already cancelled positions refuse to cancel
+        let transition = |from| {
+            Err(PositionError::StateTransition {
+                from,
+                to: PositionState::Cancelled,
+            })
+        };
+        assert_eq!(
+            manager.cancel_position(&parent),
+            transition(PositionState::Open)
+        );
+        assert_eq!(
+            manager.cancel_position(&pending),
+            transition(PositionState::Cancelled)
+        );
+        manager.close_position(&parent, 1.1000, T0 + 1).unwrap();
+        assert_eq!(
+            manager.cancel_position(&parent),
+            transition(PositionState::Closed)
+        );
+        let unknown = Uuid::new_v4();
+        assert_eq!(
+            manager.cancel_position(&unknown),
+            Err(PositionError::NotFound(unknown))
+        );
+    }
+
+    #[test]
+    fn test_cancel_cascades_to_pending_children() {
+        let manager = PositionManager::new();
+        let parent = manager
+            .place_pending_position(long_request(1.1000), T0)
+            .unwrap();
+        let child = manager
+            .place_pending_position(long_request(1.1010).with_parent(parent), T0)
+            .unwrap();
+        let filled = manager
+            .place_pending_position(long_request(1.1020).with_parent(parent), T0)
+            .unwrap();
+        let grandchild = manager
+            .place_pending_position(long_request(1.1030).with_parent(filled), T0)
+            .unwrap();
+        manager.activate_position(&filled, 1.1020, T0 + 1).unwrap();
+
+        let cancelled = manager.cancel_with_children(&parent).unwrap();
+        assert_eq!(cancelled[0], parent);
+        assert_eq!(cancelled.len(), 3);
+        assert!(cancelled.contains(&child) && cancelled.contains(&grandchild));
+        assert!(manager.get_position(&filled).unwrap().is_open());
+        assert_eq!(manager.get_children(&parent), vec![filled]);
+
+        assert!(manager.cancel_with_children(&parent).is_err());
+    }
+
+    #[test]
+    fn test_cancel_all_pending_and_activate() {
+        let manager = PositionManager::new()
+            .with_position_limit(PositionLimit::new(1, OverflowPolicy::Reject));
+        let first = manager
+            .place_pending_position(long_request(1.1000), T0)
+            .unwrap();
+        let second = manager
+            .place_pending_position(long_request(1.0990), T0 + 1)
+            .unwrap();
+        let other = manager
+            .place_pending_position(
+                PositionRequest::new("GBPUSD".to_string(), PositionSide::Short, 1_000.0, 1.27),
+                T0,
+            )
+            .unwrap();
+
+        manager.activate_position(&first, 1.1005, T0 + 10).unwrap();
+        let position = manager.get_position(&first).unwrap();
+        assert_eq!(position.entry_price, 1.1005);
+        assert_eq!(position.opened_at, T0 + 10);
+        assert_eq!(manager.open_position_count("EURUSD"), 1);
+
+        // The limit is full, so the second stays pending
+        assert!(matches!(
+            manager.activate_position(&second, 1.0990, T0 + 20),
+            Err(PositionError::LimitReached { .. })
+        ));
+        assert_eq!(
+            manager.get_position(&second).unwrap().state,
+            PositionState::Pending
+        );
+
+        assert_eq!(manager.cancel_all_pending("EURUSD"), vec![second]);
+        assert!(manager.cancel_all_pending("EURUSD").is_empty());
+        assert_eq!(
+            manager.get_position(&other).unwrap().state,
+            PositionState::Pending
+        );
+    }
+
+    #[test]
+    fn test_bracket_stop_withdraws_take_profit() {
+        use crate::positions::Order;
+        use backtestr_data::Tick;
+
+        let manager = PositionManager::new();
+        let tick = |timestamp, bid: f64| {
+            Tick::new_with_millis("EURUSD".to_string(), timestamp, bid, bid + 0.0002)
+        };
+        let bracket = manager
+            .place_bracket(
+                Order::market("EURUSD".to_string(), PositionSide::Long, 10_000.0),
+                1.1050,
+                1.0950,
+            )
+            .unwrap();
+        // Exits wait for the entry
+        assert_eq!(manager.pending_orders("EURUSD").len(), 1);
+
+        let filled = manager.check_pending_orders(&tick(T0, 1.1000));
+        assert_eq!(filled[0].0, bracket.entry);
+        let position_id = filled[0].1.position_id().unwrap();
+        let exits = manager.pending_orders("EURUSD");
+        assert_eq!(
+            exits.iter().map(|o| o.id).collect::<Vec<_>>(),
+            vec![bracket.take_profit, bracket.stop]
+        );
+        assert!(exits
+            .iter()
+            .all(|o| o.closes_position == Some(position_id) && o.side == PositionSide::Short));
+
+        let filled = manager.check_pending_orders(&tick(T0 + 1, 1.0940));
+        assert_eq!(filled.len(), 1);
+        assert_eq!(filled[0].0, bracket.stop);
+        let position = manager.get_position(&position_id).unwrap();
+        assert_eq!(position.close_price, Some(1.0950));
+        assert!(manager.pending_orders("EURUSD").is_empty());
+
+        // The take-profit is gone, so a later rally fills nothing
+        assert!(manager
+            .check_pending_orders(&tick(T0 + 2, 1.1060))
+            .is_empty());
+        assert_eq!(manager.get_closed_positions().len(), 1);
+    }
+
+    #[test]
+    fn test_snapshot_keeps_orders_and_queued_requests() {
+        use crate::positions::Order;
+        use backtestr_data::Tick;
+
+        let new_manager = || {
+            PositionManager::new().with_position_limit(PositionLimit::new(1, OverflowPolicy::Queue))
+        };
+        let gbpusd =
+            || PositionRequest::new("GBPUSD".to_string(), PositionSide::Long, 10_000.0, 1.27);
+        let manager = new_manager();
+        let open = manager
+            .open_position(gbpusd(), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+        let queued = manager.open_position(gbpusd(), T0).unwrap();
+        assert!(matches!(queued, OpenOutcome::Queued { .. }));
+        let bracket = manager
+            .place_bracket(
+                Order::market("EURUSD".to_string(), PositionSide::Long, 10_000.0),
+                1.1050,
+                1.0950,
+            )
+            .unwrap();
+
+        let snapshot = manager.snapshot_positions().unwrap();
+        assert_eq!(snapshot.pending_orders.len(), 1);
+        assert_eq!(snapshot.bracket_exits.len(), 1);
+        assert_eq!(snapshot.queued_requests.len(), 1);
+        let restored = new_manager();
+        restored.restore_positions(&snapshot);
+        assert_eq!(restored.snapshot_positions().unwrap(), snapshot);
+
+        // The bracket entry still brings its stop and take-profit with it
+        let tick = |timestamp, bid: f64| {
+            Tick::new_with_millis("EURUSD".to_string(), timestamp, bid, bid + 0.0002)
+        };
+        let filled = restored.check_pending_orders(&tick(T0 + 1, 1.1000));
+        assert_eq!(filled[0].0, bracket.entry);
+        let filled = restored.check_pending_orders(&tick(T0 + 2, 1.0940));
+        assert_eq!(filled[0].0, bracket.stop);
+        assert!(restored.pending_orders("EURUSD").is_empty());
+
+        // And the queued request takes the slot once it frees up
+        restored.close_position(&open, 1.2710, T0 + 3).unwrap();
+        assert_eq!(restored.queued_count("GBPUSD"), 0);
+        assert_eq!(restored.open_position_count("GBPUSD"), 1);
+    }
+
+    #[test]
+    fn test_oco_siblings_fill_once_on_the_same_tick() {
+        use crate::positions::Order;
+        use backtestr_data::Tick;
+
+        let manager = PositionManager::new();
+        let position_id = manager
+            .open_position(long_request(1.1000), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+
+        // Both sells are reached by a 1.0995 bid
+        let group = Uuid::new_v4();
+        let first = Order::limit("EURUSD".to_string(), PositionSide::Short, 1.0990, 10_000.0)
+            .with_oco_group(group)
+            .closing(position_id);
+        let second = Order::stop("EURUSD".to_string(), PositionSide::Short, 1.0995, 10_000.0)
+            .with_oco_group(group)
+            .closing(position_id);
+        let first = manager.place_order(first).unwrap();
+        manager.place_order(second).unwrap();
+
+        let tick = Tick::new_with_millis("EURUSD".to_string(), T0 + 1, 1.0995, 1.0997);
+        let filled = manager.check_pending_orders(&tick);
+        assert_eq!(filled.len(), 1);
+        assert_eq!(filled[0].0, first);
+        assert!(manager.pending_orders("EURUSD").is_empty());
+        assert_eq!(manager.get_closed_positions().len(), 1);
+    }
+
+    #[test]
+    fn test_rejected_fill_is_reported_and_spares_siblings() {
+        use crate::positions::Order;
+        use backtestr_data::Tick;
+
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let manager = PositionManager::new()
+            .with_starting_balance(1_000.0)
+            .with_trade_listener(move |event| sink.lock().unwrap().push(event.clone()));
+
+        // The first order needs 1,100 of margin; its sibling needs 110
+        let group = Uuid::new_v4();
+        let too_big = Order::limit("EURUSD".to_string(), PositionSide::Long, 1.1000, 100_000.0)
+            .with_oco_group(group);
+        let affordable = Order::limit("EURUSD".to_string(), PositionSide::Long, 1.1000, 10_000.0)
+            .with_oco_group(group);
+        let too_big = manager.place_order(too_big).unwrap();
+        let affordable = manager.place_order(affordable).unwrap();
+
+        let tick = Tick::new_with_millis("EURUSD".to_string(), T0, 1.0998, 1.1000);
+        let filled = manager.check_pending_orders(&tick);
+        assert_eq!(filled.len(), 1);
+        assert_eq!(filled[0].0, affordable);
+        assert!(manager.pending_orders("EURUSD").is_empty());
+
+        let events = events.lock().unwrap();
+        assert!(matches!(
+            &events[0],
+            TradeEvent::OrderRejected {
+                order_id,
+                error: PositionError::InsufficientMargin { .. },
+                ..
+            } if *order_id == too_big
+        ));
+        assert!(matches!(
+            &events[1],
+            TradeEvent::OrderFilled { order_id, .. } if *order_id == affordable
+        ));
+    }
+
+    #[test]
+    fn test_bracket_entry_rejected_instead_of_netting() {
+        use crate::positions::Order;
+        use backtestr_data::Tick;
+
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let manager = PositionManager::new()
+            .with_netting_mode(NettingMode::Netting)
+            .with_trade_listener(move |event| sink.lock().unwrap().push(event.clone()));
+        let open = manager
+            .open_position(long_request(1.1000), T0)
+            .unwrap()
+            .position_id()
+            .unwrap();
+
+        let bracket = manager
+            .place_bracket(
+                Order::market("EURUSD".to_string(), PositionSide::Short, 5_000.0),
+                1.0950,
+                1.1050,
+            )
+            .unwrap();
+        let tick = Tick::new_with_millis("EURUSD".to_string(), T0 + 1, 1.1000, 1.1002);
+        assert!(manager.check_pending_orders(&tick).is_empty());
+
+        // Neither the entry nor its exits are left, and the long is untouched
+        assert!(manager.pending_orders("EURUSD").is_empty());
+        assert!(manager.bracket_exits.is_empty());
+        assert!(manager.get_position(&open).unwrap().is_open());
+        assert!(matches!(
+            &events.lock().unwrap()[..],
+            [TradeEvent::OrderRejected {
+                order_id,
+                error: PositionError::BracketWouldNet { .. },
+                ..
+            }] if *order_id == bracket.entry
+        ));
+    }
+
+    #[test]
+    fn test_bracket_validation_and_cleanup() {
+        use crate::positions::Order;
+        use backtestr_data::Tick;
+
+        let manager = PositionManager::new();
+        let short = Order::limit("EURUSD".to_string(), PositionSide::Short, 1.1000, 1_000.0);
+        // Exits swapped, or both on one side of the entry's trigger
+        for (take_profit, stop) in [(1.1050, 1.0950), (1.1020, 1.1050), (1.0900, 1.0950)] {
+            assert!(matches!(
+                manager.place_bracket(short.clone(), take_profit, stop),
+                Err(PositionError::InvalidBracket { .. })
+            ));
+        }
+        assert!(manager.pending_orders("EURUSD").is_empty());
+
+        // Cancelling the entry drops the exits with it
+        let bracket = manager.place_bracket(short, 1.0950, 1.1050).unwrap();
+        assert!(manager.cancel_order(&bracket.entry));
+        assert!(manager.bracket_exits.is_empty());
+
+        // Closing the position by hand withdraws both exits
+        manager
+            .place_bracket(
+                Order::market("EURUSD".to_string(), PositionSide::Long, 1_000.0),
+                1.1050,
+                1.0950,
+            )
+            .unwrap();
+        let tick = Tick::new_with_millis("EURUSD".to_string(), T0, 1.1000, 1.1002);
+        let filled = manager.check_pending_orders(&tick);
+        let position_id = filled[0].1.position_id().unwrap();
+        assert_eq!(manager.pending_orders("EURUSD").len(), 2);
+        manager
+            .close_position(&position_id, 1.1010, T0 + 1)
+            .unwrap();
+        assert!(manager.pending_orders("EURUSD").is_empty());
+    }
+}
@@ -0,0 +1,242 @@
+use super::error::{PositionError, Result};
+use super::{PositionSide, LOT_SIZE};
+
+/// Tolerance for float error when flooring to the lot step
+const STEP_EPSILON: f64 = 1e-9;
+
+/// Computes position quantities from account risk and stop distance.
+///
+/// Quantities are in units of the base currency, like `Position::quantity`,
+/// and are rounded down to a whole number of lot steps. `pip_value` is the
+/// account-currency value of a one pip move on one standard lot, so it also
+/// gives the rate from the quote to the account currency, e.g. about
+/// 1/150 for USDJPY in a USD account.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionSizer {
+    /// Smallest tradable quantity increment, in units
+    pub lot_step: f64,
+    /// Price distance of one pip
+    pub pip_size: f64,
+    /// Caps quantity at `balance * leverage` of notional, in the account
+    /// currency, when set
+    pub leverage: Option<f64>,
+}
+
+impl Default for PositionSizer {
+    fn default() -> Self {
+        Self {
+            // Micro lot
+            lot_step: 1_000.0,
+            pip_size: 0.0001,
+            leverage: None,
+        }
+    }
+}
+
+impl PositionSizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_lot_step(mut self, lot_step: f64) -> Self {
+        self.lot_step = lot_step;
+        self
+    }
+
+    /// Use 0.01 for JPY pairs
+    pub fn with_pip_size(mut self, pip_size: f64) -> Self {
+        self.pip_size = pip_size;
+        self
+    }
+
+    /// Never size beyond what the balance can margin at `leverage`
+    pub fn with_leverage(mut self, leverage: f64) -> Self {
+        self.leverage = Some(leverage);
+        self
+    }
+
+    /// Quantity that loses `risk_pct` percent of `account_balance` if a
+    /// `side` position is stopped out at `stop` after entering at `entry`.
+    ///
+    /// Fails with `InvalidStopDistance` when the stop is at the entry or on
+    /// the wrong side of it: below for longs, above for shorts.
+    pub fn size_by_risk(
+        &self,
+        side: PositionSide,
+        account_balance: f64,
+        risk_pct: f64,
+        entry: f64,
+        stop: f64,
+        pip_value: f64,
+    ) -> Result<f64> {
+        let stop_distance = (entry - stop) * side.direction();
+        self.size_for_distance(account_balance, risk_pct, entry, stop_distance, pip_value)
+    }
+
+    /// Like `size_by_risk` with the stop placed `atr_multiple` ATRs from
+    /// `entry`, e.g. `ATR::current()` for the trading timeframe. A zero or
+    /// negative ATR distance is an `InvalidStopDistance`.
+    pub fn size_by_atr(
+        &self,
+        account_balance: f64,
+        risk_pct: f64,
+        entry: f64,
+        atr: f64,
+        atr_multiple: f64,
+        pip_value: f64,
+    ) -> Result<f64> {
+        self.size_for_distance(
+            account_balance,
+            risk_pct,
+            entry,
+            atr * atr_multiple,
+            pip_value,
+        )
+    }
+
+    fn size_for_distance(
+        &self,
+        account_balance: f64,
+        risk_pct: f64,
+        entry: f64,
+        stop_distance: f64,
+        pip_value: f64,
+    ) -> Result<f64> {
+        if !stop_distance.is_finite() || stop_distance <= 0.0 {
+            return Err(PositionError::InvalidStopDistance(stop_distance));
+        }
+        if !risk_pct.is_finite() || risk_pct <= 0.0 || risk_pct > 100.0 {
+            return Err(PositionError::InvalidRisk(risk_pct));
+        }
+        if !entry.is_finite() || entry <= 0.0 {
+            return Err(PositionError::InvalidPrice(entry));
+        }
+        if !pip_value.is_finite() || pip_value <= 0.0 {
+            return Err(PositionError::InvalidPrice(pip_value));
+        }
+
+        let risk_amount = account_balance.max(0.0) * risk_pct / 100.0;
+        let loss_per_lot = stop_distance / self.pip_size * pip_value;
+        let mut quantity = risk_amount / loss_per_lot * LOT_SIZE;
+
+        if let Some(leverage) = self.leverage {
+            let quote_rate = pip_value / (self.pip_size * LOT_SIZE);
+            let max_quantity = account_balance.max(0.0) * leverage / (entry * quote_rate);
+            quantity = quantity.min(max_quantity);
+        }
+
+        Ok(self.round_to_step(quantity))
+    }
+
+    fn round_to_step(&self, quantity: f64) -> f64 {
+        if self.lot_step <= 0.0 {
+            return quantity;
+        }
+        (quantity / self.lot_step + STEP_EPSILON).floor() * self.lot_step
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_by_risk_long_and_short() {
+        let sizer = PositionSizer::new();
+
+        // 1% of 10k over a 50 pip stop at $10/pip/lot is 0.2 lots
+        let long = sizer
+            .size_by_risk(PositionSide::Long, 10_000.0, 1.0, 1.1000, 1.0950, 10.0)
+            .unwrap();
+        assert!((long - 20_000.0).abs() < 1e-9);
+
+        let short = sizer
+            .size_by_risk(PositionSide::Short, 10_000.0, 1.0, 1.1000, 1.1050, 10.0)
+            .unwrap();
+        assert!((short - 20_000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rounds_down_to_lot_step() {
+        // Raw size is 0.3 lots over a 33 pip stop: 30,303 units
+        let size = PositionSizer::new()
+            .size_by_risk(PositionSide::Long, 10_000.0, 1.0, 1.1000, 1.0967, 10.0)
+            .unwrap();
+        assert!((size - 30_000.0).abs() < 1e-9);
+
+        let standard = PositionSizer::new()
+            .with_lot_step(LOT_SIZE)
+            .size_by_risk(PositionSide::Long, 10_000.0, 1.0, 1.1000, 1.0967, 10.0)
+            .unwrap();
+        assert_eq!(standard, 0.0);
+    }
+
+    #[test]
+    fn test_zero_stop_distance_rejected() {
+        let sizer = PositionSizer::new();
+        assert!(matches!(
+            sizer.size_by_risk(PositionSide::Long, 10_000.0, 1.0, 1.1000, 1.1000, 10.0),
+            Err(PositionError::InvalidStopDistance(_))
+        ));
+        assert!(matches!(
+            sizer.size_by_atr(10_000.0, 1.0, 1.1000, 0.0010, -2.0, 10.0),
+            Err(PositionError::InvalidStopDistance(_))
+        ));
+        assert!(matches!(
+            sizer.size_by_risk(PositionSide::Long, 10_000.0, 0.0, 1.1000, 1.0950, 10.0),
+            Err(PositionError::InvalidRisk(_))
+        ));
+    }
+
+    #[test]
+    fn test_stop_on_wrong_side_rejected() {
+        let sizer = PositionSizer::new();
+        assert!(matches!(
+            sizer.size_by_risk(PositionSide::Long, 10_000.0, 1.0, 1.1000, 1.1050, 10.0),
+            Err(PositionError::InvalidStopDistance(_))
+        ));
+        assert!(matches!(
+            sizer.size_by_risk(PositionSide::Short, 10_000.0, 1.0, 1.1000, 1.0950, 10.0),
+            Err(PositionError::InvalidStopDistance(_))
+        ));
+    }
+
+    #[test]
+    fn test_size_by_atr() {
+        // 2 x 25 pip ATR is the same 50 pip stop
+        let size = PositionSizer::new()
+            .size_by_atr(10_000.0, 1.0, 1.1000, 0.0025, 2.0, 10.0)
+            .unwrap();
+        assert!((size - 20_000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_leverage_caps_size() {
+        // A 2 pip stop would allow 5 lots, but 10:1 margins only ~90k units
+        let sizer = PositionSizer::new().with_leverage(10.0);
+        let size = sizer
+            .size_by_risk(PositionSide::Long, 10_000.0, 1.0, 1.1000, 1.0998, 10.0)
+            .unwrap();
+        assert!((size - 90_000.0).abs() < 1e-9);
+        assert!(size * 1.1000 <= 10_000.0 * 10.0);
+    }
+
+    #[test]
+    fn test_leverage_cap_in_account_currency() {
+        // USDJPY in a USD account: a pip on a lot is 1,000 JPY, $6.67 at
+        // 150. The notional of 100k units is $100k, not 15M, so 10:1 on
+        // $10k still allows the full lot.
+        let sizer = PositionSizer::new().with_pip_size(0.01).with_leverage(10.0);
+        let pip_value = 1_000.0 / 150.0;
+        let size = sizer
+            .size_by_risk(PositionSide::Long, 10_000.0, 1.0, 150.00, 149.85, pip_value)
+            .unwrap();
+        assert!((size - 100_000.0).abs() < 1e-9);
+
+        // A 1 pip stop would allow 15 lots, but the cap holds it to $100k
+        let capped = sizer
+            .size_by_risk(PositionSide::Long, 10_000.0, 1.0, 150.00, 149.99, pip_value)
+            .unwrap();
+        assert!((capped - 100_000.0).abs() < 1e-9);
+    }
+}
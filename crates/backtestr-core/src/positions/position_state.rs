@@ -0,0 +1,149 @@
+use super::error::{PositionError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Lifecycle state of a position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PositionState {
+    Pending,
+    Open,
+    Closed,
+    Cancelled,
+}
+
+impl PositionState {
+    pub fn is_active(&self) -> bool {
+        matches!(self, PositionState::Pending | PositionState::Open)
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, PositionState::Closed | PositionState::Cancelled)
+    }
+}
+
+impl fmt::Display for PositionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PositionState::Pending => "Pending",
+            PositionState::Open => "Open",
+            PositionState::Closed => "Closed",
+            PositionState::Cancelled => "Cancelled",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Guards which operations are legal for a given position state
+pub struct StateValidator;
+
+impl StateValidator {
+    /// The complete state machine: a pending position opens or is
+    /// cancelled, an open position closes, and closed or cancelled
+    /// positions never change again
+    pub fn can_transition(from: PositionState, to: PositionState) -> bool {
+        matches!(
+            (from, to),
+            (PositionState::Pending, PositionState::Open)
+                | (PositionState::Pending, PositionState::Cancelled)
+                | (PositionState::Open, PositionState::Closed)
+        )
+    }
+
+    /// States reachable from `from` in one step
+    pub fn allowed_transitions(from: PositionState) -> Vec<PositionState> {
+        [
+            PositionState::Pending,
+            PositionState::Open,
+            PositionState::Closed,
+            PositionState::Cancelled,
+        ]
+        .into_iter()
+        .filter(|to| Self::can_transition(from, *to))
+        .collect()
+    }
+
+    /// `Ok` if `from -> to` is legal, else `PositionError::StateTransition`
+    pub fn validate(from: PositionState, to: PositionState) -> Result<()> {
+        if Self::can_transition(from, to) {
+            Ok(())
+        } else {
+            Err(PositionError::StateTransition { from, to })
+        }
+    }
+
+    /// Prices, stops and targets may only change while the position is live
+    pub fn can_modify(state: PositionState) -> bool {
+        state.is_active()
+    }
+
+    /// Only open positions can be closed
+    pub fn can_close(state: PositionState) -> bool {
+        Self::can_transition(state, PositionState::Closed)
+    }
+
+    /// Only open positions can be scaled into; a pending one has no fill
+    /// to average with yet
+    pub fn can_add(state: PositionState) -> bool {
+        Self::can_modify(state) && state == PositionState::Open
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_flags() {
+        assert!(PositionState::Open.is_active());
+        assert!(PositionState::Pending.is_active());
+        assert!(PositionState::Closed.is_terminal());
+        assert!(PositionState::Cancelled.is_terminal());
+    }
+
+    #[test]
+    fn test_validator() {
+        assert!(StateValidator::can_close(PositionState::Open));
+        assert!(!StateValidator::can_close(PositionState::Pending));
+        assert!(!StateValidator::can_close(PositionState::Closed));
+
+        assert!(StateValidator::can_modify(PositionState::Pending));
+        assert!(!StateValidator::can_modify(PositionState::Cancelled));
+
+        assert!(StateValidator::can_add(PositionState::Open));
+        assert!(!StateValidator::can_add(PositionState::Pending));
+        assert!(!StateValidator::can_add(PositionState::Closed));
+    }
+
+    #[test]
+    fn test_transition_table() {
+        use PositionState::*;
+        let all = [Pending, Open, Closed, Cancelled];
+        let legal = [(Pending, Open), (Pending, Cancelled), (Open, Closed)];
+
+        for from in all {
+            for to in all {
+                assert_eq!(
+                    StateValidator::can_transition(from, to),
+                    legal.contains(&(from, to)),
+                    "{from} -> {to}"
+                );
+            }
+        }
+
+        assert_eq!(
+            StateValidator::allowed_transitions(Pending),
+            vec![Open, Cancelled]
+        );
+        assert_eq!(StateValidator::allowed_transitions(Open), vec![Closed]);
+        assert!(StateValidator::allowed_transitions(Closed).is_empty());
+        assert!(StateValidator::allowed_transitions(Cancelled).is_empty());
+
+        assert_eq!(
+            StateValidator::validate(Closed, Open),
+            Err(PositionError::StateTransition {
+                from: Closed,
+                to: Open
+            })
+        );
+    }
+}
@@ -0,0 +1,282 @@
+use super::{PnlCalculator, Position, PositionStatistics};
+use serde::Serialize;
+use std::fmt;
+
+/// Trade statistics of a finished backtest, from `PnlCalculator::build_report`.
+///
+/// Money figures are in the same currency as the positions' realized P&L.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PerformanceReport {
+    pub starting_balance: f64,
+    pub ending_balance: f64,
+    pub total_return_pct: f64,
+    pub total_trades: usize,
+    pub winning_trades: usize,
+    pub losing_trades: usize,
+    /// Winning trades as a percentage of all closed trades
+    pub win_rate_pct: f64,
+    /// Sum of winning P&L
+    pub gross_profit: f64,
+    /// Sum of losing P&L, as a positive amount
+    pub gross_loss: f64,
+    pub net_profit: f64,
+    /// `gross_profit / gross_loss`; `f64::INFINITY` with profits and no
+    /// losing trades, see `profit_factor_unbounded`
+    pub profit_factor: f64,
+    /// Set when there were profits but no losses to divide by. JSON has
+    /// no infinity, so `to_json` writes `profit_factor` as `null` then.
+    pub profit_factor_unbounded: bool,
+    pub average_win: f64,
+    /// Zero or negative
+    pub average_loss: f64,
+    pub largest_win: f64,
+    /// Zero or negative
+    pub largest_loss: f64,
+    /// Mean P&L per closed trade
+    pub expectancy: f64,
+    /// Mean over sample deviation of per-trade returns on the equity before
+    /// each trade, not annualized; `None` with fewer than two trades or no
+    /// variation
+    pub sharpe_ratio: Option<f64>,
+    pub max_drawdown: f64,
+    pub max_drawdown_pct: f64,
+    pub max_drawdown_duration_ms: i64,
+}
+
+impl PerformanceReport {
+    pub(crate) fn build(
+        calculator: &PnlCalculator,
+        closed_positions: &[Position],
+        starting_balance: f64,
+    ) -> Self {
+        let stats = PositionStatistics::from_positions(closed_positions);
+        let curve = calculator.equity_curve(starting_balance, closed_positions);
+
+        let mut closed: Vec<&Position> = closed_positions
+            .iter()
+            .filter(|p| p.closed_at.is_some())
+            .collect();
+        closed.sort_by_key(|p| (p.closed_at, p.opened_at, p.id));
+        let pnls: Vec<f64> = closed.iter().map(|p| calculator.calculate_pnl(p)).collect();
+
+        let wins = pnls.iter().copied().filter(|pnl| *pnl > 0.0);
+        let losses = pnls.iter().copied().filter(|pnl| *pnl < 0.0);
+        let gross_profit: f64 = wins.clone().sum();
+        let gross_loss: f64 = -losses.clone().sum::<f64>();
+        let net_profit = gross_profit - gross_loss;
+
+        let profit_factor_unbounded = gross_loss == 0.0 && gross_profit > 0.0;
+        let profit_factor = if gross_loss > 0.0 {
+            gross_profit / gross_loss
+        } else if profit_factor_unbounded {
+            f64::INFINITY
+        } else {
+            0.0
+        };
+
+        let ending_balance = starting_balance + net_profit;
+        let mean = |total: f64, count: usize| {
+            if count > 0 {
+                total / count as f64
+            } else {
+                0.0
+            }
+        };
+
+        Self {
+            starting_balance,
+            ending_balance,
+            total_return_pct: if starting_balance > 0.0 {
+                net_profit / starting_balance * 100.0
+            } else {
+                0.0
+            },
+            total_trades: stats.closed_trades,
+            winning_trades: stats.winning_trades,
+            losing_trades: stats.losing_trades,
+            win_rate_pct: mean(stats.winning_trades as f64 * 100.0, stats.closed_trades),
+            gross_profit,
+            gross_loss,
+            net_profit,
+            profit_factor,
+            profit_factor_unbounded,
+            average_win: mean(gross_profit, stats.winning_trades),
+            average_loss: mean(-gross_loss, stats.losing_trades),
+            largest_win: wins.fold(0.0, f64::max),
+            largest_loss: losses.fold(0.0, f64::min),
+            expectancy: mean(net_profit, stats.closed_trades),
+            sharpe_ratio: sharpe_ratio(&trade_returns(starting_balance, &pnls)),
+            max_drawdown: curve.iter().map(|p| p.drawdown).fold(0.0, f64::max),
+            max_drawdown_pct: curve.iter().map(|p| p.drawdown_pct).fold(0.0, f64::max),
+            max_drawdown_duration_ms: PnlCalculator::max_drawdown_duration_ms(&curve),
+        }
+    }
+
+    /// Pretty-printed JSON of every field
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Two-column text table, the same as `Display`
+    pub fn to_table(&self) -> String {
+        let profit_factor = if self.profit_factor_unbounded {
+            "inf (no losing trades)".to_string()
+        } else {
+            format!("{:.2}", self.profit_factor)
+        };
+        let sharpe = self
+            .sharpe_ratio
+            .map_or("n/a".to_string(), |sharpe| format!("{:.2}", sharpe));
+
+        let rows = [
+            ("Starting balance", format!("{:.2}", self.starting_balance)),
+            ("Ending balance", format!("{:.2}", self.ending_balance)),
+            ("Total return", format!("{:.2}%", self.total_return_pct)),
+            ("Net profit", format!("{:.2}", self.net_profit)),
+            ("Trades", self.total_trades.to_string()),
+            (
+                "Wins / losses",
+                format!("{} / {}", self.winning_trades, self.losing_trades),
+            ),
+            ("Win rate", format!("{:.2}%", self.win_rate_pct)),
+            ("Gross profit", format!("{:.2}", self.gross_profit)),
+            ("Gross loss", format!("{:.2}", self.gross_loss)),
+            ("Profit factor", profit_factor),
+            ("Average win", format!("{:.2}", self.average_win)),
+            ("Average loss", format!("{:.2}", self.average_loss)),
+            ("Largest win", format!("{:.2}", self.largest_win)),
+            ("Largest loss", format!("{:.2}", self.largest_loss)),
+            ("Expectancy", format!("{:.2}", self.expectancy)),
+            ("Sharpe (per trade)", sharpe),
+            (
+                "Max drawdown",
+                format!("{:.2} ({:.2}%)", self.max_drawdown, self.max_drawdown_pct),
+            ),
+            (
+                "Max drawdown duration",
+                format!("{} ms", self.max_drawdown_duration_ms),
+            ),
+        ];
+
+        rows.iter()
+            .map(|(label, value)| format!("{:<22} {:>24}", label, value))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl fmt::Display for PerformanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_table())
+    }
+}
+
+/// Each trade's P&L over the equity just before it closed
+fn trade_returns(starting_balance: f64, pnls: &[f64]) -> Vec<f64> {
+    let mut equity = starting_balance;
+    pnls.iter()
+        .filter_map(|pnl| {
+            let base = equity;
+            equity += pnl;
+            (base > 0.0).then(|| pnl / base)
+        })
+        .collect()
+}
+
+fn sharpe_ratio(returns: &[f64]) -> Option<f64> {
+    if returns.len() < 2 {
+        return None;
+    }
+    let n = returns.len() as f64;
+    let mean = returns.iter().sum::<f64>() / n;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    let deviation = variance.sqrt();
+    (deviation > 0.0).then(|| mean / deviation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::positions::PositionSide;
+
+    fn trade(closed_at: i64, exit: f64) -> Position {
+        let mut position = Position::new(
+            "EURUSD".to_string(),
+            PositionSide::Long,
+            100_000.0,
+            1.1000,
+            0,
+        );
+        position.close(exit, closed_at).unwrap();
+        position
+    }
+
+    #[test]
+    fn test_report_figures() {
+        let calc = PnlCalculator::new("USD");
+        // +100, -50, +200, -150
+        let trades = vec![
+            trade(1_000, 1.1010),
+            trade(2_000, 1.0995),
+            trade(3_000, 1.1020),
+            trade(4_000, 1.0985),
+        ];
+        let report = calc.build_report(&trades, 10_000.0);
+
+        assert_eq!(report.total_trades, 4);
+        assert_eq!(report.win_rate_pct, 50.0);
+        assert!((report.gross_profit - 300.0).abs() < 1e-6);
+        assert!((report.gross_loss - 200.0).abs() < 1e-6);
+        assert!((report.profit_factor - 1.5).abs() < 1e-6);
+        assert!(!report.profit_factor_unbounded);
+        assert!((report.average_win - 150.0).abs() < 1e-6);
+        assert!((report.average_loss + 100.0).abs() < 1e-6);
+        assert!((report.largest_win - 200.0).abs() < 1e-6);
+        assert!((report.largest_loss + 150.0).abs() < 1e-6);
+        assert!((report.expectancy - 25.0).abs() < 1e-6);
+        assert!((report.ending_balance - 10_100.0).abs() < 1e-6);
+        assert!((report.total_return_pct - 1.0).abs() < 1e-6);
+
+        // Down 150 from the 10,250 peak at the end; under the 10,100 high
+        // from 1_000 until 3_000 is the longest spell
+        assert!((report.max_drawdown - 150.0).abs() < 1e-6);
+        assert_eq!(report.max_drawdown_duration_ms, 2_000);
+
+        let returns = [
+            100.0 / 10_000.0,
+            -50.0 / 10_100.0,
+            200.0 / 10_050.0,
+            -150.0 / 10_250.0,
+        ];
+        let expected = sharpe_ratio(&returns).unwrap();
+        assert!((report.sharpe_ratio.unwrap() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_no_losing_trades() {
+        let calc = PnlCalculator::new("USD");
+        let report = calc.build_report(&[trade(1_000, 1.1010), trade(2_000, 1.1030)], 10_000.0);
+
+        assert_eq!(report.losing_trades, 0);
+        assert_eq!(report.profit_factor, f64::INFINITY);
+        assert!(report.profit_factor_unbounded);
+        assert_eq!(report.average_loss, 0.0);
+        assert!(report.to_table().contains("inf (no losing trades)"));
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert!(json["profit_factor"].is_null());
+        assert_eq!(json["profit_factor_unbounded"], true);
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let report = PnlCalculator::new("USD").build_report(&[], 5_000.0);
+        assert_eq!(report.total_trades, 0);
+        assert_eq!(report.profit_factor, 0.0);
+        assert!(!report.profit_factor_unbounded);
+        assert_eq!(report.win_rate_pct, 0.0);
+        assert_eq!(report.sharpe_ratio, None);
+        assert_eq!(report.ending_balance, 5_000.0);
+        assert_eq!(report.to_string(), report.to_table());
+    }
+}
@@ -0,0 +1,407 @@
+use super::PositionSide;
+use backtestr_data::Bar;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex, MutexGuard};
+use uuid::Uuid;
+
+/// Fraction of the spread a `Realistic` fill pays beyond the touch
+pub const REALISTIC_SPREAD_FRACTION: f64 = 0.5;
+
+/// Seed of the fill RNG when none is given, so randomized fills are
+/// reproducible by default rather than drawn from system entropy
+pub const DEFAULT_FILL_SEED: u64 = 0x5EED;
+
+/// Streams of a seed that fills and ids are drawn from, so turning on a
+/// randomized slippage model leaves ids unchanged
+const FILL_STREAM: u64 = 0;
+const ID_STREAM: u64 = 1;
+
+/// Direction of a single fill
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+impl OrderSide {
+    /// The fill that opens a position on `side`
+    pub fn entry(side: PositionSide) -> Self {
+        match side {
+            PositionSide::Long => OrderSide::Buy,
+            PositionSide::Short => OrderSide::Sell,
+        }
+    }
+
+    /// The fill that closes a position on `side`
+    pub fn exit(side: PositionSide) -> Self {
+        match side {
+            PositionSide::Long => OrderSide::Sell,
+            PositionSide::Short => OrderSide::Buy,
+        }
+    }
+}
+
+/// Turns a requested price into the price a fill actually gets.
+///
+/// `spread` is the `(bid, ask)` quote at the time of the fill. Models that
+/// charge slippage start from the touch, so buys fill at or above the ask
+/// and sells at or below the bid; `bar` is the bar being traded, if known.
+/// Randomized models must draw only from `rng`, which the caller seeds, so
+/// runs with the same seed fill identically.
+pub trait SlippageModel: Send + Sync + Debug {
+    fn fill_price(
+        &self,
+        side: OrderSide,
+        requested: f64,
+        spread: (f64, f64),
+        bar: Option<&Bar>,
+        rng: &mut dyn RngCore,
+    ) -> f64;
+}
+
+/// Seeded random sources for the fills and ids of a `PositionManager`,
+/// shared with its clones.
+///
+/// ChaCha8 is portable and its position can be saved, so a checkpointed
+/// run resumes with the same draws it would have made.
+#[derive(Debug, Clone)]
+pub(crate) struct SeededRng(Arc<Mutex<Streams>>);
+
+#[derive(Debug)]
+struct Streams {
+    seed: u64,
+    fills: ChaCha8Rng,
+    ids: ChaCha8Rng,
+}
+
+impl Streams {
+    fn new(seed: u64) -> Self {
+        let stream = |stream| {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            rng.set_stream(stream);
+            rng
+        };
+        Self {
+            seed,
+            fills: stream(FILL_STREAM),
+            ids: stream(ID_STREAM),
+        }
+    }
+}
+
+impl SeededRng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(Arc::new(Mutex::new(Streams::new(seed))))
+    }
+
+    fn streams(&self) -> MutexGuard<'_, Streams> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn seed(&self) -> u64 {
+        self.streams().seed
+    }
+
+    pub(crate) fn with_fills<T>(&self, f: impl FnOnce(&mut ChaCha8Rng) -> T) -> T {
+        f(&mut self.streams().fills)
+    }
+
+    /// A random (version 4) UUID drawn from the id stream
+    pub(crate) fn next_id(&self) -> Uuid {
+        uuid::Builder::from_random_bytes(self.streams().ids.gen()).into_uuid()
+    }
+
+    /// Words drawn so far from the fill and id streams, for checkpoints
+    pub(crate) fn word_pos(&self) -> (u128, u128) {
+        let streams = self.streams();
+        (streams.fills.get_word_pos(), streams.ids.get_word_pos())
+    }
+
+    /// Reseed this source and its clones, then skip to a checkpointed
+    /// `word_pos`
+    pub(crate) fn restore(&self, seed: u64, (fills, ids): (u128, u128)) {
+        let mut streams = self.streams();
+        *streams = Streams::new(seed);
+        streams.fills.set_word_pos(fills);
+        streams.ids.set_word_pos(ids);
+    }
+}
+
+impl Default for SeededRng {
+    fn default() -> Self {
+        Self::new(DEFAULT_FILL_SEED)
+    }
+}
+
+/// Fills at exactly the requested price
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoSlippage;
+
+impl SlippageModel for NoSlippage {
+    fn fill_price(
+        &self,
+        _side: OrderSide,
+        requested: f64,
+        _spread: (f64, f64),
+        _bar: Option<&Bar>,
+        _rng: &mut dyn RngCore,
+    ) -> f64 {
+        requested
+    }
+}
+
+/// Fills a fixed number of pips beyond the touch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedPips {
+    pub pips: f64,
+    /// Price distance of one pip
+    pub pip_size: f64,
+}
+
+impl FixedPips {
+    pub fn new(pips: f64) -> Self {
+        Self {
+            pips,
+            pip_size: 0.0001,
+        }
+    }
+
+    /// Use 0.01 for JPY pairs
+    pub fn with_pip_size(mut self, pip_size: f64) -> Self {
+        self.pip_size = pip_size;
+        self
+    }
+}
+
+impl SlippageModel for FixedPips {
+    fn fill_price(
+        &self,
+        side: OrderSide,
+        _requested: f64,
+        spread: (f64, f64),
+        _bar: Option<&Bar>,
+        _rng: &mut dyn RngCore,
+    ) -> f64 {
+        let slippage = self.pips * self.pip_size;
+        match side {
+            OrderSide::Buy => spread.1 + slippage,
+            OrderSide::Sell => spread.0 - slippage,
+        }
+    }
+}
+
+/// Fills a fraction of the current spread beyond the touch, so slippage
+/// widens with the spread around news and thin sessions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpreadProportional(pub f64);
+
+impl SlippageModel for SpreadProportional {
+    fn fill_price(
+        &self,
+        side: OrderSide,
+        _requested: f64,
+        spread: (f64, f64),
+        _bar: Option<&Bar>,
+        _rng: &mut dyn RngCore,
+    ) -> f64 {
+        let (bid, ask) = spread;
+        let slippage = self.0 * (ask - bid).max(0.0);
+        match side {
+            OrderSide::Buy => ask + slippage,
+            OrderSide::Sell => bid - slippage,
+        }
+    }
+}
+
+/// Fills a uniformly random number of pips in `[min_pips, max_pips]`
+/// beyond the touch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RandomPips {
+    pub min_pips: f64,
+    pub max_pips: f64,
+    /// Price distance of one pip
+    pub pip_size: f64,
+}
+
+impl RandomPips {
+    pub fn new(min_pips: f64, max_pips: f64) -> Self {
+        Self {
+            min_pips: min_pips.min(max_pips),
+            max_pips: max_pips.max(min_pips),
+            pip_size: 0.0001,
+        }
+    }
+
+    /// Use 0.01 for JPY pairs
+    pub fn with_pip_size(mut self, pip_size: f64) -> Self {
+        self.pip_size = pip_size;
+        self
+    }
+}
+
+impl SlippageModel for RandomPips {
+    fn fill_price(
+        &self,
+        side: OrderSide,
+        _requested: f64,
+        spread: (f64, f64),
+        _bar: Option<&Bar>,
+        rng: &mut dyn RngCore,
+    ) -> f64 {
+        let pips = self.min_pips + rng.gen::<f64>() * (self.max_pips - self.min_pips);
+        let slippage = pips * self.pip_size;
+        match side {
+            OrderSide::Buy => spread.1 + slippage,
+            OrderSide::Sell => spread.0 - slippage,
+        }
+    }
+}
+
+/// Execution assumptions for a backtest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExecutionModel {
+    /// Idealized fills at the requested price
+    #[default]
+    Perfect,
+    /// Fills at the touch plus `REALISTIC_SPREAD_FRACTION` of the spread
+    Realistic,
+}
+
+impl ExecutionModel {
+    pub fn slippage_model(&self) -> Arc<dyn SlippageModel> {
+        match self {
+            ExecutionModel::Perfect => Arc::new(NoSlippage),
+            ExecutionModel::Realistic => Arc::new(SpreadProportional(REALISTIC_SPREAD_FRACTION)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUOTE: (f64, f64) = (1.1000, 1.1002);
+
+    fn rng() -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(DEFAULT_FILL_SEED)
+    }
+
+    #[test]
+    fn test_buy_at_ask_plus_sell_at_bid_minus() {
+        let fixed = FixedPips::new(1.5);
+        assert!(
+            (fixed.fill_price(OrderSide::Buy, 1.1001, QUOTE, None, &mut rng()) - 1.10035).abs()
+                < 1e-12
+        );
+        assert!(
+            (fixed.fill_price(OrderSide::Sell, 1.1001, QUOTE, None, &mut rng()) - 1.09985).abs()
+                < 1e-12
+        );
+
+        let proportional = SpreadProportional(0.5);
+        assert!(
+            (proportional.fill_price(OrderSide::Buy, 1.1001, QUOTE, None, &mut rng()) - 1.1003)
+                .abs()
+                < 1e-12
+        );
+        assert!(
+            (proportional.fill_price(OrderSide::Sell, 1.1001, QUOTE, None, &mut rng()) - 1.0999)
+                .abs()
+                < 1e-12
+        );
+
+        let jpy = FixedPips::new(2.0).with_pip_size(0.01);
+        assert!(
+            (jpy.fill_price(OrderSide::Buy, 150.0, (150.00, 150.02), None, &mut rng()) - 150.04)
+                .abs()
+                < 1e-9
+        );
+    }
+
+    #[test]
+    fn test_execution_model_mapping() {
+        let perfect = ExecutionModel::Perfect.slippage_model();
+        assert_eq!(
+            perfect.fill_price(OrderSide::Buy, 1.1001, QUOTE, None, &mut rng()),
+            1.1001
+        );
+
+        let realistic = ExecutionModel::Realistic.slippage_model();
+        let expected = SpreadProportional(REALISTIC_SPREAD_FRACTION).fill_price(
+            OrderSide::Sell,
+            1.1001,
+            QUOTE,
+            None,
+            &mut rng(),
+        );
+        assert_eq!(
+            realistic.fill_price(OrderSide::Sell, 1.1001, QUOTE, None, &mut rng()),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_order_side_for_position_side() {
+        assert_eq!(OrderSide::entry(PositionSide::Long), OrderSide::Buy);
+        assert_eq!(OrderSide::exit(PositionSide::Long), OrderSide::Sell);
+        assert_eq!(OrderSide::entry(PositionSide::Short), OrderSide::Sell);
+        assert_eq!(OrderSide::exit(PositionSide::Short), OrderSide::Buy);
+    }
+
+    #[test]
+    fn test_random_pips_follow_seed() {
+        let model = RandomPips::new(0.5, 2.0);
+        let fills = |seed| {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            (0..20)
+                .map(|_| model.fill_price(OrderSide::Buy, 1.1001, QUOTE, None, &mut rng))
+                .collect::<Vec<_>>()
+        };
+
+        let first = fills(7);
+        assert_eq!(first, fills(7));
+        assert_ne!(first, fills(8));
+        for price in first {
+            assert!((1.10025 - 1e-12..=1.1004 + 1e-12).contains(&price));
+        }
+    }
+
+    #[test]
+    fn test_seeded_rng_shared_between_clones() {
+        let rng = SeededRng::new(3);
+        let clone = rng.clone();
+        let a: u64 = rng.with_fills(|r| r.gen());
+        let b: u64 = clone.with_fills(|r| r.gen());
+
+        let mut reference = ChaCha8Rng::seed_from_u64(3);
+        assert_eq!(a, reference.gen::<u64>());
+        assert_eq!(b, reference.gen::<u64>());
+        assert_eq!(clone.seed(), 3);
+    }
+
+    #[test]
+    fn test_seeded_rng_resumes_from_word_pos() {
+        let rng = SeededRng::new(3);
+        rng.next_id();
+        rng.with_fills(|r| r.gen::<f64>());
+        let resumed = SeededRng::new(9);
+        resumed.restore(3, rng.word_pos());
+        assert_eq!(resumed.seed(), 3);
+        assert_eq!(resumed.next_id(), rng.next_id());
+        assert_eq!(
+            resumed.with_fills(|r| r.gen::<u64>()),
+            rng.with_fills(|r| r.gen::<u64>())
+        );
+        assert_eq!(rng.next_id().get_version_num(), 4);
+    }
+
+    #[test]
+    fn test_fills_do_not_shift_ids() {
+        let quiet = SeededRng::new(3);
+        let busy = SeededRng::new(3);
+        busy.with_fills(|r| r.gen::<u64>());
+        assert_eq!(quiet.next_id(), busy.next_id());
+    }
+}
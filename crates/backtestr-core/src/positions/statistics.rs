@@ -0,0 +1,91 @@
+use super::Position;
+
+/// Aggregate figures over closed positions
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PositionStatistics {
+    pub closed_trades: usize,
+    pub winning_trades: usize,
+    pub losing_trades: usize,
+    /// Net realized P&L across all closed trades
+    pub total_realized_pnl: f64,
+    /// Mean maximum favorable excursion, zero or positive
+    pub average_mfe: f64,
+    /// Mean maximum adverse excursion, zero or negative
+    pub average_mae: f64,
+}
+
+impl PositionStatistics {
+    /// Statistics over the closed positions in `positions`; open ones are ignored
+    pub fn from_positions<'a>(positions: impl IntoIterator<Item = &'a Position>) -> Self {
+        let mut stats = Self::default();
+
+        for position in positions.into_iter().filter(|p| p.closed_at.is_some()) {
+            stats.closed_trades += 1;
+            if position.realized_pnl > 0.0 {
+                stats.winning_trades += 1;
+            } else if position.realized_pnl < 0.0 {
+                stats.losing_trades += 1;
+            }
+            stats.total_realized_pnl += position.realized_pnl;
+            stats.average_mfe += position.max_favorable_excursion;
+            stats.average_mae += position.max_adverse_excursion;
+        }
+
+        if stats.closed_trades > 0 {
+            stats.average_mfe /= stats.closed_trades as f64;
+            stats.average_mae /= stats.closed_trades as f64;
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::positions::PositionSide;
+
+    fn trade(path: &[f64], exit: f64) -> Position {
+        let mut position = Position::new(
+            "EURUSD".to_string(),
+            PositionSide::Long,
+            10_000.0,
+            1.1000,
+            1704067200000,
+        );
+        for &price in path {
+            position.update_price(price).unwrap();
+        }
+        position.close(exit, 1704067260000).unwrap();
+        position
+    }
+
+    #[test]
+    fn test_average_excursions() {
+        let positions = vec![
+            trade(&[1.0990, 1.1040], 1.1030),
+            trade(&[1.1010, 1.0970], 1.0980),
+            Position::new(
+                "EURUSD".to_string(),
+                PositionSide::Long,
+                10_000.0,
+                1.1000,
+                1704067200000,
+            ),
+        ];
+
+        let stats = PositionStatistics::from_positions(&positions);
+        assert_eq!(stats.closed_trades, 2);
+        assert_eq!(stats.winning_trades, 1);
+        assert_eq!(stats.losing_trades, 1);
+        assert!((stats.total_realized_pnl - 10.0).abs() < 1e-9);
+        // MFE (40 + 10) / 2, MAE (-10 + -30) / 2
+        assert!((stats.average_mfe - 25.0).abs() < 1e-9);
+        assert!((stats.average_mae + 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_empty() {
+        let stats = PositionStatistics::from_positions(&[]);
+        assert_eq!(stats, PositionStatistics::default());
+    }
+}
@@ -0,0 +1,63 @@
+use super::{CloseReason, PositionError, PositionSide};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Trade lifecycle events emitted by `PositionManager`
+#[derive(Debug, Clone, PartialEq)]
+pub enum TradeEvent {
+    PositionClosed {
+        id: Uuid,
+        symbol: String,
+        side: PositionSide,
+        close_price: f64,
+        closed_at: i64,
+        /// P&L before costs
+        gross_pnl: f64,
+        commission: f64,
+        swap: f64,
+        /// P&L after commission and swap
+        net_pnl: f64,
+    },
+    /// A pending position was cancelled before it filled
+    PositionCancelled { id: Uuid, symbol: String },
+    /// A pending order reached its trigger. `position_id` is the position
+    /// a bracket exit closed, or `None` when the fill was netted flat or
+    /// queued behind a position limit.
+    OrderFilled {
+        order_id: Uuid,
+        position_id: Option<Uuid>,
+        symbol: String,
+        side: PositionSide,
+        fill_price: f64,
+        filled_at: i64,
+    },
+    /// A pending order reached its trigger, or a queued request reached a
+    /// free slot, but could not fill, e.g. for lack of margin or a free
+    /// slot. The order has left the book or the queue.
+    OrderRejected {
+        order_id: Uuid,
+        symbol: String,
+        error: PositionError,
+    },
+    /// A stop-loss or take-profit fired, but `process_pending_closes` could
+    /// not close the position, e.g. for lack of a conversion rate. The
+    /// position stays open and is queued again by the next tick that
+    /// reaches its trigger.
+    CloseFailed {
+        id: Uuid,
+        symbol: String,
+        reason: CloseReason,
+        error: PositionError,
+    },
+    /// The account's margin level fell below the configured threshold.
+    /// Raised once per breach; it is raised again only after the level has
+    /// recovered.
+    MarginCall {
+        equity: f64,
+        used_margin: f64,
+        /// Percent
+        margin_level: f64,
+    },
+}
+
+pub(crate) type TradeListener = Arc<dyn Fn(&TradeEvent) + Send + Sync>;
@@ -1,3 +1,4 @@
+use backtestr_core::indicators::other::VortexOutput;
 use backtestr_core::indicators::*;
 use backtestr_data::Timeframe;
 
@@ -218,6 +219,93 @@ fn test_multiple_timeframes() {
     assert!(m15_value.is_some());
 }
 
+#[test]
+fn test_timeframes_keep_independent_state() {
+    let pipeline = IndicatorPipeline::new(100);
+    pipeline.register_indicator("SMA".to_string(), Box::new(SMA::new(3)));
+
+    let bar = |close: f64, timestamp: i64| BarData {
+        open: close,
+        high: close,
+        low: close,
+        close,
+        volume: 1000.0,
+        timestamp,
+    };
+
+    // Interleave two unrelated price series on different timeframes
+    for i in 0..10 {
+        pipeline
+            .update_all(&bar(1.0 + i as f64, i), Timeframe::M1)
+            .unwrap();
+        pipeline
+            .update_all(&bar(100.0 * (i + 1) as f64, i), Timeframe::M5)
+            .unwrap();
+    }
+
+    // Each SMA only averages its own timeframe's last three closes
+    assert_eq!(pipeline.get_value("SMA", Timeframe::M1), Some(9.0));
+    assert_eq!(pipeline.get_value("SMA", Timeframe::M5), Some(900.0));
+
+    // A timeframe seen for the first time starts warming up from scratch
+    pipeline.update_all(&bar(50.0, 10), Timeframe::H1).unwrap();
+    assert_eq!(pipeline.get_value("SMA", Timeframe::H1), None);
+}
+
+#[test]
+fn test_clone_fresh_discards_state() {
+    let indicators: Vec<Box<dyn Indicator<Input = BarData, Output = f64>>> = vec![
+        Box::new(SMA::new(5)),
+        Box::new(EMA::new(5)),
+        Box::new(TEMA::new(5)),
+        Box::new(HMA::new(9)),
+        Box::new(RSI::new(5)),
+        Box::new(MACD::new(5, 10, 3)),
+        Box::new(Stochastic::new(5, 3)),
+        Box::new(BollingerBands::new(5, 2.0)),
+        Box::new(KeltnerChannels::new(5, 2.0)),
+        Box::new(ATR::new(5)),
+        Box::new(ADX::new(5)),
+        Box::new(ParabolicSAR::new(0.02, 0.2)),
+        Box::new(VWAP::new(false)),
+        Box::new(OBV::new()),
+        Box::new(AccDist::new()),
+        Box::new(LinearRegression::new(5)),
+        Box::new(HistoricalVolatility::new(5, 252.0)),
+        Box::new(Chained::new(RSI::new(5), SMA::new(3))),
+    ];
+
+    let bars: Vec<BarData> = (0..40)
+        .map(|i| {
+            let base = 100.0 + (i as f64 * 0.4).sin() * 5.0;
+            BarData {
+                open: base,
+                high: base + 2.0,
+                low: base - 1.5,
+                close: base + 0.5,
+                volume: 1000.0 + i as f64 * 10.0,
+                timestamp: i,
+            }
+        })
+        .collect();
+
+    for mut original in indicators {
+        let expected: Vec<Option<u64>> = bars
+            .iter()
+            .map(|bar| original.update(*bar).map(f64::to_bits))
+            .collect();
+
+        // Built after the original has consumed every bar
+        let mut fresh = original.clone_fresh();
+        assert!(fresh.current().is_none(), "{} kept state", original.name());
+        let replayed: Vec<Option<u64>> = bars
+            .iter()
+            .map(|bar| fresh.update(*bar).map(f64::to_bits))
+            .collect();
+        assert_eq!(replayed, expected, "{} diverged", original.name());
+    }
+}
+
 #[test]
 fn test_indicator_warm_up_periods() {
     let pipeline = IndicatorPipeline::new(100);
@@ -302,21 +390,32 @@ fn test_new_parabolic_sar_implementation() {
 fn test_all_indicators_produce_values() {
     let pipeline = IndicatorPipeline::new(1000);
 
-    // Register all 20 indicators
+    // Register all 35 indicators
     pipeline.register_indicator("SMA".to_string(), Box::new(SMA::new(5)));
     pipeline.register_indicator("EMA".to_string(), Box::new(EMA::new(5)));
     pipeline.register_indicator("WMA".to_string(), Box::new(WMA::new(5)));
     pipeline.register_indicator("DEMA".to_string(), Box::new(DEMA::new(5)));
+    pipeline.register_indicator("TEMA".to_string(), Box::new(TEMA::new(5)));
+    pipeline.register_indicator("HMA".to_string(), Box::new(HMA::new(5)));
     pipeline.register_indicator("RSI".to_string(), Box::new(RSI::new(5)));
     pipeline.register_indicator("MACD".to_string(), Box::new(MACD::new(5, 10, 3)));
     pipeline.register_indicator("Stochastic".to_string(), Box::new(Stochastic::new(5, 3)));
     pipeline.register_indicator("CCI".to_string(), Box::new(CCI::new(5)));
     pipeline.register_indicator("WilliamsR".to_string(), Box::new(WilliamsR::new(5)));
+    pipeline.register_indicator("ROC".to_string(), Box::new(ROC::new(5)));
+    pipeline.register_indicator("Momentum".to_string(), Box::new(Momentum::new(5)));
+    pipeline.register_indicator("CMO".to_string(), Box::new(CMO::new(5)));
+    pipeline.register_indicator("TRIX".to_string(), Box::new(TRIX::new(5)));
     pipeline.register_indicator(
         "BollingerBands".to_string(),
         Box::new(BollingerBands::new(5, 2.0)),
     );
     pipeline.register_indicator("ATR".to_string(), Box::new(ATR::new(5)));
+    pipeline.register_indicator("StdDev".to_string(), Box::new(StdDev::new(5)));
+    pipeline.register_indicator(
+        "HistoricalVolatility".to_string(),
+        Box::new(HistoricalVolatility::new(5, 252.0)),
+    );
     pipeline.register_indicator(
         "KeltnerChannels".to_string(),
         Box::new(KeltnerChannels::new(5, 2.0)),
@@ -328,6 +427,8 @@ fn test_all_indicators_produce_values() {
     pipeline.register_indicator("OBV".to_string(), Box::new(OBV::new()));
     pipeline.register_indicator("VolumeSMA".to_string(), Box::new(VolumeSMA::new(5)));
     pipeline.register_indicator("VWAP".to_string(), Box::new(VWAP::new(false)));
+    pipeline.register_indicator("MFI".to_string(), Box::new(MFI::new(5)));
+    pipeline.register_indicator("CMF".to_string(), Box::new(CMF::new(5)));
     pipeline.register_indicator("PivotPoints".to_string(), Box::new(PivotPoints::new()));
     pipeline.register_indicator(
         "SupportResistance".to_string(),
@@ -338,6 +439,14 @@ fn test_all_indicators_produce_values() {
         "ParabolicSAR".to_string(),
         Box::new(ParabolicSAR::new(0.02, 0.2)),
     );
+    pipeline.register_indicator("Aroon".to_string(), Box::new(Aroon::new(5)));
+    pipeline.register_indicator("Vortex".to_string(), Box::new(Vortex::new(5)));
+    pipeline.register_indicator(
+        "LinearRegression".to_string(),
+        Box::new(LinearRegression::new(5)),
+    );
+    pipeline.register_indicator("ZigZag".to_string(), Box::new(ZigZag::new(2.0)));
+    pipeline.register_indicator("ElderRay".to_string(), Box::new(ElderRay::new(5)));
 
     // Generate enough bars for all indicators
     let mut bars = Vec::new();
@@ -361,6 +470,7 @@ fn test_all_indicators_produce_values() {
     // Check that most indicators have values (some may still be warming up)
     let mut indicators_with_values = 0;
     let indicator_names = pipeline.get_indicator_names();
+    assert_eq!(indicator_names.len(), 35);
 
     for name in &indicator_names {
         if pipeline.get_value(name, Timeframe::M1).is_some() {
@@ -368,10 +478,48 @@ fn test_all_indicators_produce_values() {
         }
     }
 
-    // At least 18 out of 20 should have values after 50 bars
+    // Vortex caches its oscillator as the scalar and the full pair as output
+    let vortex: VortexOutput = pipeline.get_output("Vortex", Timeframe::M1).unwrap();
+    assert_eq!(
+        pipeline.get_value("Vortex", Timeframe::M1),
+        Some(vortex.vi_plus - vortex.vi_minus)
+    );
+
+    // At least 33 out of 35 should have values after 50 bars
     assert!(
-        indicators_with_values >= 18,
-        "Expected at least 18 indicators with values, got {}",
+        indicators_with_values >= 33,
+        "Expected at least 33 indicators with values, got {}",
         indicators_with_values
     );
+
+    // The newest indicators warm up well within 50 bars
+    for name in ["ElderRay", "CMO", "TRIX"] {
+        assert!(
+            pipeline.get_value(name, Timeframe::M1).is_some(),
+            "{} should have a value after 50 bars",
+            name
+        );
+    }
+}
+
+#[test]
+fn test_squeeze_in_pipeline() {
+    use backtestr_core::indicators::volatility::SqueezeOutput;
+
+    let pipeline = IndicatorPipeline::new(100);
+    let squeeze = SqueezeIndicator::new(5, 2.0, 1.5);
+    assert_eq!(squeeze.warm_up_period(), 9);
+    pipeline.register_indicator("Squeeze".to_string(), Box::new(squeeze));
+
+    for (i, bar) in create_test_bars().iter().enumerate() {
+        pipeline.update_all(bar, Timeframe::M1).unwrap();
+        let output = pipeline.get_output::<SqueezeOutput>("Squeeze", Timeframe::M1);
+        assert_eq!(output.is_some(), i >= 8, "bar {}", i);
+        if let Some(output) = output {
+            assert_eq!(
+                pipeline.get_value("Squeeze", Timeframe::M1),
+                Some(output.momentum)
+            );
+        }
+    }
 }
@@ -1,10 +1,12 @@
-use backtestr_core::mtf::{MTFConfig, MTFStateManager};
+use backtestr_core::indicators::{
+    BarData, Indicator, IndicatorPipeline, ATR, EMA, MACD, OBV, RSI, SMA,
+};
+use backtestr_core::mtf::MTFStateManager;
 use backtestr_core::persistence::{
     CheckpointData, CheckpointManager, CheckpointTrigger, MTFStateSnapshot, PersistenceConfig,
-    StateRecovery,
+    RecoveryError, StateRecovery,
 };
 use backtestr_data::{Tick, Timeframe};
-use std::path::PathBuf;
 use tempfile::tempdir;
 
 #[test]
@@ -34,11 +36,17 @@ fn test_checkpoint_data_serialization() {
             engine_version: "1.0.0".to_string(),
         },
         checksum: 0,
+        checkpoint_id: "test-checkpoint".to_string(),
+        base_checkpoint_id: None,
+        parent_checkpoint_id: None,
+        changed_states: Default::default(),
+        removed_symbols: Vec::new(),
+        positions: None,
     };
 
     // Test serialization
     let serialized = bincode::serialize(&checkpoint).unwrap();
-    assert!(serialized.len() > 0);
+    assert!(!serialized.is_empty());
 
     // Test deserialization
     let deserialized: CheckpointData = bincode::deserialize(&serialized).unwrap();
@@ -132,7 +140,7 @@ async fn test_checkpoint_and_recovery_roundtrip() {
     let recovered = recovery.recover_state().await.unwrap();
 
     assert!(recovered.is_some());
-    let (recovered_state, tick_count) = recovered.unwrap();
+    let (_recovered_state, tick_count) = recovered.unwrap();
     assert_eq!(tick_count, 100);
 }
 
@@ -168,3 +176,465 @@ fn test_mtf_state_snapshot() {
     assert!(snapshot.current_tick.is_none() || snapshot.current_tick.is_some());
     assert_eq!(snapshot.last_processed_timestamp, 0); // TODO: Fix implementation
 }
+
+fn indicator_bars(count: i64, offset: i64) -> Vec<BarData> {
+    (offset..offset + count)
+        .map(|i| {
+            let close = 100.0 + (i as f64 * 0.4).sin() * 4.0 + i as f64 * 0.05;
+            BarData {
+                open: close - 0.3,
+                high: close + 1.2,
+                low: close - 1.1,
+                close,
+                volume: 1000.0 + i as f64,
+                timestamp: i,
+            }
+        })
+        .collect()
+}
+
+fn assert_state_roundtrip<I>(mut original: I, mut restored: I)
+where
+    I: Indicator<Input = BarData, Output = f64>,
+{
+    for bar in indicator_bars(30, 0) {
+        original.update(bar);
+    }
+
+    restored.restore_state(&original.serialize_state()).unwrap();
+    assert_eq!(restored.current(), original.current());
+
+    // Continuity: both produce identical values going forward
+    for bar in indicator_bars(10, 30) {
+        assert_eq!(restored.update(bar), original.update(bar));
+    }
+}
+
+#[test]
+fn test_indicator_state_roundtrip() {
+    assert_state_roundtrip(SMA::new(5), SMA::new(5));
+    assert_state_roundtrip(EMA::new(5), EMA::new(5));
+    assert_state_roundtrip(RSI::new(14), RSI::new(14));
+    assert_state_roundtrip(MACD::new(5, 10, 3), MACD::new(5, 10, 3));
+    assert_state_roundtrip(ATR::new(14), ATR::new(14));
+}
+
+#[test]
+fn test_indicator_restore_rejects_mismatch() {
+    let mut sma5 = SMA::new(5);
+    for bar in indicator_bars(10, 0) {
+        sma5.update(bar);
+    }
+    let state = sma5.serialize_state();
+
+    let mut sma10 = SMA::new(10);
+    let err = sma10.restore_state(&state).unwrap_err();
+    assert!(err.to_string().contains("period mismatch"));
+    assert!(!sma10.is_ready());
+
+    let mut ema = EMA::new(5);
+    assert!(ema.restore_state(&state).is_err());
+
+    let mut macd = MACD::new(12, 26, 9);
+    let other_macd = MACD::new(12, 26, 5);
+    assert!(macd.restore_state(&other_macd.serialize_state()).is_err());
+
+    assert!(sma10.restore_state(&[1, 2, 3]).is_err());
+}
+
+#[tokio::test]
+async fn test_pipeline_checkpoint_roundtrip() {
+    let dir = tempdir().unwrap();
+    let mut manager = CheckpointManager::new(dir.path().to_path_buf(), 60, 6, 5).unwrap();
+    let state = MTFStateManager::with_default_config();
+
+    let register = |pipeline: &IndicatorPipeline| {
+        pipeline.register_indicator("SMA_5".to_string(), Box::new(SMA::new(5)));
+        pipeline.register_indicator("MACD".to_string(), Box::new(MACD::new(5, 10, 3)));
+        pipeline.register_indicator("OBV".to_string(), Box::new(OBV::new()));
+    };
+
+    let original = IndicatorPipeline::new(100);
+    register(&original);
+    for bar in indicator_bars(30, 0) {
+        original.update_all(&bar, Timeframe::M5).unwrap();
+    }
+
+    let path = manager
+        .create_checkpoint_with_indicators(&state, &original, 30)
+        .await
+        .unwrap();
+
+    let data = StateRecovery::new(dir.path())
+        .load_checkpoint_data(&path)
+        .await
+        .unwrap();
+    assert_eq!(data.indicator_states.len(), 3);
+    let macd_key = format!("MACD@{}", Timeframe::M5);
+    assert_eq!(data.indicator_states[&macd_key].timeframe, Timeframe::M5);
+
+    let restored = IndicatorPipeline::new(100);
+    register(&restored);
+    restored.restore_all(&data.indicator_states).unwrap();
+
+    assert_eq!(
+        restored.get_value("SMA_5", Timeframe::M5),
+        original.get_value("SMA_5", Timeframe::M5)
+    );
+
+    for bar in indicator_bars(5, 30) {
+        original.update_all(&bar, Timeframe::M5).unwrap();
+        restored.update_all(&bar, Timeframe::M5).unwrap();
+    }
+    assert_eq!(
+        restored.get_value("SMA_5", Timeframe::M5),
+        original.get_value("SMA_5", Timeframe::M5)
+    );
+    assert_eq!(
+        restored.get_macd("MACD", Timeframe::M5),
+        original.get_macd("MACD", Timeframe::M5)
+    );
+}
+
+#[test]
+fn test_pipeline_restore_rejects_changed_period() {
+    let original = IndicatorPipeline::new(100);
+    original.register_indicator("SMA".to_string(), Box::new(SMA::new(5)));
+    for bar in indicator_bars(10, 0) {
+        original.update_all(&bar, Timeframe::M1).unwrap();
+    }
+
+    let changed = IndicatorPipeline::new(100);
+    changed.register_indicator("SMA".to_string(), Box::new(SMA::new(20)));
+    let err = changed.restore_all(&original.snapshot_all()).unwrap_err();
+    assert!(format!("{:#}", err).contains("period mismatch"));
+    assert_eq!(changed.get_value("SMA", Timeframe::M1), None);
+}
+
+#[test]
+fn test_pipeline_restore_is_all_or_nothing() {
+    let original = IndicatorPipeline::new(100);
+    original.register_indicator("EMA".to_string(), Box::new(EMA::new(5)));
+    original.register_indicator("SMA".to_string(), Box::new(SMA::new(5)));
+    for bar in indicator_bars(10, 0) {
+        original.update_all(&bar, Timeframe::M1).unwrap();
+    }
+
+    let changed = IndicatorPipeline::new(100);
+    changed.register_indicator("EMA".to_string(), Box::new(EMA::new(5)));
+    changed.register_indicator("SMA".to_string(), Box::new(SMA::new(20)));
+    // Only the SMA is incompatible; the EMA must not be restored either
+    assert!(changed.restore_all(&original.snapshot_all()).is_err());
+    assert_eq!(changed.get_value("EMA", Timeframe::M1), None);
+
+    let fresh = IndicatorPipeline::new(100);
+    fresh.register_indicator("EMA".to_string(), Box::new(EMA::new(5)));
+    for bar in indicator_bars(5, 10) {
+        changed.update_all(&bar, Timeframe::M1).unwrap();
+        fresh.update_all(&bar, Timeframe::M1).unwrap();
+    }
+    assert!(fresh.get_value("EMA", Timeframe::M1).is_some());
+    assert_eq!(
+        changed.get_value("EMA", Timeframe::M1),
+        fresh.get_value("EMA", Timeframe::M1)
+    );
+}
+
+const DELTA_SYMBOLS: [&str; 6] = ["EURUSD", "GBPUSD", "USDJPY", "AUDUSD", "USDCAD", "NZDUSD"];
+
+fn tick_symbol(state: &MTFStateManager, symbol: &str, timestamp: i64, bid: f64) {
+    let tick = Tick::new_with_millis(symbol.to_string(), timestamp, bid, bid + 0.0002);
+    state.process_tick(&tick).unwrap();
+}
+
+#[tokio::test]
+async fn test_delta_checkpoint_resolves_to_full_state() {
+    let dir = tempdir().unwrap();
+    let mut manager = CheckpointManager::new(dir.path().to_path_buf(), 60, 6, 10)
+        .unwrap()
+        .with_delta_checkpoints(10);
+    let state = MTFStateManager::with_default_config();
+
+    for symbol in DELTA_SYMBOLS {
+        tick_symbol(&state, symbol, 1704067200000, 1.0920);
+    }
+    let baseline_path = manager.create_checkpoint(&state, 6).await.unwrap();
+
+    tick_symbol(&state, "EURUSD", 1704067201000, 1.0930);
+    let first_delta_path = manager.create_checkpoint(&state, 7).await.unwrap();
+
+    tick_symbol(&state, "GBPUSD", 1704067202000, 1.2710);
+    let second_delta_path = manager.create_checkpoint(&state, 8).await.unwrap();
+
+    let baseline_size = std::fs::metadata(&baseline_path).unwrap().len();
+    let delta_size = std::fs::metadata(&first_delta_path).unwrap().len();
+    assert!(delta_size < baseline_size);
+
+    let recovery = StateRecovery::new(dir.path());
+    let first_delta = recovery.load_checkpoint_data(&first_delta_path).await;
+    assert!(first_delta.unwrap().base_checkpoint_id.is_some());
+
+    let resolved = recovery
+        .load_checkpoint_data(&second_delta_path)
+        .await
+        .unwrap();
+    assert_eq!(resolved.tick_count, 8);
+
+    let expected = state.create_snapshot().unwrap();
+    assert_eq!(
+        resolved.mtf_state.partial_bars.len(),
+        expected.partial_bars.len()
+    );
+    for (key, bar) in &expected.partial_bars {
+        let restored = &resolved.mtf_state.partial_bars[key];
+        assert_eq!(restored.close, bar.close);
+        assert_eq!(restored.tick_count, bar.tick_count);
+    }
+
+    let (_, tick_count) = recovery.recover_state().await.unwrap().unwrap();
+    assert_eq!(tick_count, 8);
+}
+
+fn assert_same_state(original: &MTFStateManager, restored: &MTFStateManager) {
+    let mut symbols = original.get_all_symbols();
+    symbols.sort();
+    let mut restored_symbols = restored.get_all_symbols();
+    restored_symbols.sort();
+    assert_eq!(restored_symbols, symbols);
+
+    for symbol in symbols {
+        let expected = original.get_symbol_state(&symbol).unwrap();
+        let actual = restored.get_symbol_state(&symbol).unwrap();
+        assert_eq!(actual.last_update, expected.last_update);
+        assert_eq!(actual.current_tick, expected.current_tick);
+        assert_eq!(actual.timeframes.len(), expected.timeframes.len());
+        for (timeframe, expected_tf) in &expected.timeframes {
+            let actual_tf = &actual.timeframes[timeframe];
+            assert_eq!(actual_tf.completed_bars, expected_tf.completed_bars);
+            assert_eq!(actual_tf.current_bar, expected_tf.current_bar);
+            assert_eq!(actual_tf.bar_start_time, expected_tf.bar_start_time);
+            assert_eq!(actual_tf.bar_end_time, expected_tf.bar_end_time);
+            assert_eq!(actual_tf.tick_count, expected_tf.tick_count);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_recovered_state_matches_checkpointed_state() {
+    let dir = tempdir().unwrap();
+    let mut manager = CheckpointManager::new(dir.path().to_path_buf(), 60, 6, 10)
+        .unwrap()
+        .with_delta_checkpoints(10);
+    let state = MTFStateManager::with_default_config();
+
+    // Several completed M1 and M5 bars per symbol, with bars left open
+    let start = 1704067200000;
+    for i in 0..40 {
+        let bid = 1.0920 + (i % 7) as f64 * 0.0001;
+        tick_symbol(&state, "EURUSD", start + i * 20_000, bid);
+        tick_symbol(&state, "GBPUSD", start + i * 20_000 + 500, bid + 0.18);
+    }
+    manager.create_checkpoint(&state, 80).await.unwrap();
+
+    // The latest checkpoint is a diff touching one symbol
+    for i in 40..50 {
+        tick_symbol(&state, "EURUSD", start + i * 20_000, 1.0930);
+    }
+    manager.create_checkpoint(&state, 90).await.unwrap();
+
+    let recovery = StateRecovery::new(dir.path());
+    let (recovered, tick_count) = recovery.recover_state().await.unwrap().unwrap();
+    assert_eq!(tick_count, 90);
+    assert_same_state(&state, &recovered);
+
+    // Both close the open bars alike on the next tick
+    let next = Tick::new_with_millis("EURUSD".to_string(), start + 3_600_000, 1.0950, 1.0952);
+    let close_bars = |manager: &MTFStateManager| {
+        let mut bars = manager.process_tick(&next).unwrap();
+        bars.sort_by_key(|bar| bar.timeframe.duration_ms());
+        bars
+    };
+    assert_eq!(close_bars(&recovered), close_bars(&state));
+}
+
+#[tokio::test]
+async fn test_restore_rejects_untracked_timeframe() {
+    let state = MTFStateManager::with_default_config();
+    tick_symbol(&state, "EURUSD", 1704067200000, 1.0920);
+    let snapshot = state.create_snapshot().unwrap();
+
+    let mut restored = MTFStateManager::new(backtestr_core::mtf::MTFConfig {
+        enabled_timeframes: vec![Timeframe::M1],
+        ..Default::default()
+    });
+    let err = restored.restore_from_snapshot(snapshot).unwrap_err();
+    assert!(err.to_string().contains("not an enabled timeframe"));
+    assert!(restored.get_all_symbols().is_empty());
+}
+
+#[tokio::test]
+async fn test_delta_chain_with_missing_diff_falls_back_to_baseline() {
+    let dir = tempdir().unwrap();
+    let mut manager = CheckpointManager::new(dir.path().to_path_buf(), 60, 6, 10)
+        .unwrap()
+        .with_delta_checkpoints(10);
+    let state = MTFStateManager::with_default_config();
+
+    tick_symbol(&state, "EURUSD", 1704067200000, 1.0920);
+    manager.create_checkpoint(&state, 1).await.unwrap();
+
+    tick_symbol(&state, "EURUSD", 1704067201000, 1.0930);
+    let first_delta_path = manager.create_checkpoint(&state, 2).await.unwrap();
+
+    tick_symbol(&state, "EURUSD", 1704067202000, 1.0940);
+    let second_delta_path = manager.create_checkpoint(&state, 3).await.unwrap();
+
+    std::fs::remove_file(&first_delta_path).unwrap();
+
+    let recovery = StateRecovery::new(dir.path());
+    let resolved = recovery
+        .load_checkpoint_data(&second_delta_path)
+        .await
+        .unwrap();
+    assert_eq!(resolved.tick_count, 1);
+    assert!(!resolved.is_delta());
+    let bar = &resolved.mtf_state.partial_bars[&("EURUSD".to_string(), Timeframe::M1)];
+    assert_eq!(bar.close, 1.0921);
+}
+
+#[tokio::test]
+async fn test_delta_checkpoints_rebaseline_after_interval() {
+    let dir = tempdir().unwrap();
+    let mut manager = CheckpointManager::new(dir.path().to_path_buf(), 60, 6, 10)
+        .unwrap()
+        .with_delta_checkpoints(1);
+    let state = MTFStateManager::with_default_config();
+    let recovery = StateRecovery::new(dir.path());
+
+    let mut is_delta = Vec::new();
+    for i in 0..4 {
+        tick_symbol(&state, "EURUSD", 1704067200000 + i * 1000, 1.0920);
+        let path = manager.create_checkpoint(&state, i as u64).await.unwrap();
+        let data = recovery.load_checkpoint_data(&path).await.unwrap();
+        is_delta.push(data.is_delta());
+    }
+
+    assert_eq!(is_delta, vec![false, true, false, true]);
+}
+
+#[tokio::test]
+async fn test_delta_checkpoint_drops_removed_symbol() {
+    let dir = tempdir().unwrap();
+    let mut manager = CheckpointManager::new(dir.path().to_path_buf(), 60, 6, 10)
+        .unwrap()
+        .with_delta_checkpoints(10);
+    let state = MTFStateManager::with_default_config();
+
+    for symbol in DELTA_SYMBOLS {
+        tick_symbol(&state, symbol, 1704067200000, 1.0920);
+    }
+    manager.create_checkpoint(&state, 1).await.unwrap();
+
+    assert!(state.remove_symbol("GBPUSD"));
+    let delta_path = manager.create_checkpoint(&state, 2).await.unwrap();
+
+    let recovery = StateRecovery::new(dir.path());
+    let resolved = recovery.load_checkpoint_data(&delta_path).await.unwrap();
+    let snapshot = &resolved.mtf_state;
+    assert!(!snapshot.symbol_states.contains_key("GBPUSD"));
+    assert!(snapshot.symbol_states.contains_key("EURUSD"));
+    assert!(snapshot
+        .partial_bars
+        .keys()
+        .chain(snapshot.completed_bar_ids.keys())
+        .all(|(symbol, _)| symbol != "GBPUSD"));
+}
+
+#[tokio::test]
+async fn test_recovery_falls_back_past_corrupt_checkpoint() {
+    let dir = tempdir().unwrap();
+    let mut manager = CheckpointManager::new(dir.path().to_path_buf(), 60, 6, 5).unwrap();
+    let state = MTFStateManager::with_default_config();
+    tick_symbol(&state, "EURUSD", 1704067200000, 1.0920);
+
+    manager.create_checkpoint(&state, 100).await.unwrap();
+    tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+    let latest = manager.create_checkpoint(&state, 200).await.unwrap();
+
+    // Truncate the newest checkpoint mid-stream
+    let bytes = std::fs::read(&latest).unwrap();
+    std::fs::write(&latest, &bytes[..bytes.len() / 2]).unwrap();
+
+    let recovery = StateRecovery::new(dir.path());
+    let err = recovery.load_checkpoint_data(&latest).await.unwrap_err();
+    assert!(err.downcast_ref::<RecoveryError>().is_some());
+
+    let (_, tick_count) = recovery.recover_state().await.unwrap().unwrap();
+    assert_eq!(tick_count, 100);
+}
+
+#[tokio::test]
+async fn test_recovery_reports_empty_checkpoint_as_corrupt() {
+    let dir = tempdir().unwrap();
+    std::fs::write(dir.path().join("checkpoint_empty.btck"), []).unwrap();
+
+    let recovery = StateRecovery::new(dir.path());
+    let err = recovery.recover_state().await.err().unwrap();
+    assert!(matches!(
+        err.downcast_ref::<RecoveryError>(),
+        Some(RecoveryError::EmptyFile(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_positions_and_account_survive_checkpoint() {
+    use backtestr_core::positions::{PositionManager, PositionRequest, PositionSide};
+
+    let dir = tempdir().unwrap();
+    let mut manager = CheckpointManager::new(dir.path().to_path_buf(), 60, 6, 5).unwrap();
+    let state = MTFStateManager::with_default_config();
+
+    let positions = PositionManager::new().with_starting_balance(5_000.0);
+    let request = |side| PositionRequest::new("EURUSD".to_string(), side, 10_000.0, 1.1000);
+    let open = positions
+        .open_position(
+            request(PositionSide::Long).with_expiry(1704070800000),
+            1704067200000,
+        )
+        .unwrap()
+        .position_id()
+        .unwrap();
+    let closed = positions
+        .open_position(request(PositionSide::Short), 1704067200000)
+        .unwrap()
+        .position_id()
+        .unwrap();
+    positions.update_price("EURUSD", 1.0980);
+    positions.update_price("EURUSD", 1.1030);
+    positions
+        .close_position(&closed, 1.1030, 1704067260000)
+        .unwrap();
+    let expected = positions.snapshot_positions();
+
+    let path = manager
+        .create_checkpoint_with_positions(&state, &positions, 100)
+        .await
+        .unwrap();
+    let data = StateRecovery::new(dir.path())
+        .load_checkpoint_data(&path)
+        .await
+        .unwrap();
+
+    let restored = PositionManager::new();
+    restored.restore_positions(data.positions.as_ref().unwrap());
+    assert_eq!(restored.snapshot_positions(), expected);
+    assert_eq!(restored.account(), positions.account());
+    assert!(restored.account().unwrap().balance < 5_000.0);
+
+    let position = restored.get_position(&open).unwrap();
+    assert_eq!(position.expires_at, Some(1704070800000));
+    assert!(position.max_favorable_excursion > 0.0);
+    assert!(position.max_adverse_excursion < 0.0);
+    assert_eq!(restored.open_position_count("EURUSD"), 1);
+    assert!(!restored.get_position(&closed).unwrap().is_open());
+}
@@ -23,6 +23,8 @@ fn create_test_ticks(count: usize) -> Vec<Tick> {
             ask: price + 0.00010, // 1 pip spread
             bid_size: Some(1000000),
             ask_size: Some(1000000),
+            last: None,
+            last_size: None,
         });
     }
 
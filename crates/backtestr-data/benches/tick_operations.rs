@@ -28,6 +28,41 @@ fn bench_insert_ticks(c: &mut Criterion) {
     });
 }
 
+fn bench_batch_inserts(c: &mut Criterion) {
+    let ticks = generate_ticks(100_000);
+    let mut group = c.benchmark_group("insert_100k_batch");
+
+    group.bench_function("per_row", |b| {
+        b.iter(|| {
+            let mut db = Database::new_memory().unwrap();
+            db.insert_batch(black_box(&ticks)).unwrap();
+        });
+    });
+    group.bench_function("multi_row", |b| {
+        b.iter(|| {
+            let mut db = Database::new_memory().unwrap();
+            db.insert_batch_chunked(black_box(&ticks)).unwrap();
+        });
+    });
+
+    group.finish();
+}
+
+fn bench_single_inserts(c: &mut Criterion) {
+    let ticks = generate_ticks(10_000);
+
+    // One statement per call: measures per-call overhead, which the
+    // statement cache keeps from re-preparing the SQL
+    c.bench_function("insert_10k_single_ticks", |b| {
+        b.iter(|| {
+            let db = Database::new_memory().unwrap();
+            for tick in &ticks {
+                db.insert_tick(black_box(tick)).unwrap();
+            }
+        });
+    });
+}
+
 fn bench_query_ticks(c: &mut Criterion) {
     let db = Database::new_memory().unwrap();
     let ticks = generate_ticks(10_000);
@@ -67,6 +102,8 @@ fn bench_memory_usage(c: &mut Criterion) {
 criterion_group!(
     benches,
     bench_insert_ticks,
+    bench_single_inserts,
+    bench_batch_inserts,
     bench_query_ticks,
     bench_memory_usage
 );
@@ -0,0 +1,264 @@
+use crate::models::{Bar, Tick};
+
+/// Order in which the intrabar extremes are visited when replaying a bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntrabarPath {
+    /// open → high → low → close
+    HighFirst,
+    /// open → low → high → close
+    LowFirst,
+    /// Visit whichever extreme is closer to the open first
+    NearestFirst,
+}
+
+impl IntrabarPath {
+    /// Path that hits the adverse extreme first for a position.
+    ///
+    /// Longs are stopped out on the low, shorts on the high, so visiting
+    /// that extreme first gives the worst-case outcome for stops.
+    pub fn pessimistic(is_long: bool) -> Self {
+        if is_long {
+            IntrabarPath::LowFirst
+        } else {
+            IntrabarPath::HighFirst
+        }
+    }
+
+    /// Path that hits the favourable extreme first for a position
+    pub fn optimistic(is_long: bool) -> Self {
+        Self::pessimistic(!is_long)
+    }
+}
+
+/// Shortest bar span, in milliseconds, that holds four distinct tick
+/// timestamps
+const MIN_REPLAY_SPAN_MS: i64 = 4;
+
+/// Synthesizes a deterministic tick path from OHLC bars.
+///
+/// Each bar is replayed as four ticks (open, first extreme, second extreme,
+/// close) spread across the bar's time range, so bar-only datasets can drive
+/// tick-based consumers. Tick midpoints equal the bar prices, which means the
+/// ticks aggregate back into the original OHLC.
+#[derive(Debug, Clone)]
+pub struct BarReplayer {
+    path: IntrabarPath,
+    spread: f64,
+}
+
+impl Default for BarReplayer {
+    fn default() -> Self {
+        Self::new(IntrabarPath::NearestFirst)
+    }
+}
+
+impl BarReplayer {
+    pub fn new(path: IntrabarPath) -> Self {
+        Self { path, spread: 0.0 }
+    }
+
+    /// Set the bid/ask spread applied around each synthesized price
+    pub fn with_spread(mut self, spread: f64) -> Self {
+        self.spread = spread.max(0.0);
+        self
+    }
+
+    pub fn path(&self) -> IntrabarPath {
+        self.path
+    }
+
+    /// Replay a single bar into ticks
+    pub fn replay_bar(&self, bar: &Bar) -> Vec<Tick> {
+        let visit_high_first = match self.path {
+            IntrabarPath::HighFirst => true,
+            IntrabarPath::LowFirst => false,
+            IntrabarPath::NearestFirst => (bar.high - bar.open) <= (bar.open - bar.low),
+        };
+
+        let (first, second) = if visit_high_first {
+            (bar.high, bar.low)
+        } else {
+            (bar.low, bar.high)
+        };
+
+        // Spread the ticks over [timestamp_start, timestamp_end), one apart at
+        // least. A range too short for that, e.g. a bar stored with its end
+        // equal to its start, is spread over its timeframe instead.
+        let mut duration = bar.timestamp_end - bar.timestamp_start;
+        if duration < MIN_REPLAY_SPAN_MS {
+            duration = bar.timeframe.duration_ms();
+        }
+        let timestamps: [i64; 4] =
+            std::array::from_fn(|i| bar.timestamp_start + i as i64 * (duration - 1) / 3);
+
+        let half_spread = self.spread / 2.0;
+        [bar.open, first, second, bar.close]
+            .iter()
+            .zip(timestamps.iter())
+            .map(|(&price, &timestamp)| {
+                Tick::new_with_millis(
+                    bar.symbol.clone(),
+                    timestamp,
+                    price - half_spread,
+                    price + half_spread,
+                )
+            })
+            .collect()
+    }
+
+    /// Replay a sequence of bars into a single ordered tick stream
+    pub fn replay_bars(&self, bars: &[Bar]) -> Vec<Tick> {
+        bars.iter().flat_map(|bar| self.replay_bar(bar)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggregation::TickToBarAggregator;
+    use crate::timeframe::Timeframe;
+
+    fn create_test_bar(open: f64, high: f64, low: f64, close: f64) -> Bar {
+        let start = 1704067200000; // 2024-01-01 00:00:00
+        Bar::new(
+            "EURUSD".to_string(),
+            Timeframe::M1,
+            start,
+            start + Timeframe::M1.duration_ms(),
+            open,
+            high,
+            low,
+            close,
+        )
+    }
+
+    fn midpoints(ticks: &[Tick]) -> Vec<f64> {
+        ticks.iter().map(|t| (t.bid + t.ask) / 2.0).collect()
+    }
+
+    #[test]
+    fn test_path_ordering() {
+        let bar = create_test_bar(1.0920, 1.0930, 1.0900, 1.0925);
+
+        let high_first = BarReplayer::new(IntrabarPath::HighFirst).replay_bar(&bar);
+        assert_eq!(midpoints(&high_first), vec![1.0920, 1.0930, 1.0900, 1.0925]);
+
+        let low_first = BarReplayer::new(IntrabarPath::LowFirst).replay_bar(&bar);
+        assert_eq!(midpoints(&low_first), vec![1.0920, 1.0900, 1.0930, 1.0925]);
+
+        // High is closer to the open than the low
+        let nearest = BarReplayer::new(IntrabarPath::NearestFirst).replay_bar(&bar);
+        assert_eq!(midpoints(&nearest), vec![1.0920, 1.0930, 1.0900, 1.0925]);
+    }
+
+    #[test]
+    fn test_ticks_stay_within_bar() {
+        let bar = create_test_bar(1.0920, 1.0930, 1.0900, 1.0925);
+        let ticks = BarReplayer::default().with_spread(0.0002).replay_bar(&bar);
+
+        assert_eq!(ticks.len(), 4);
+        for window in ticks.windows(2) {
+            assert!(window[0].timestamp < window[1].timestamp);
+        }
+        assert_eq!(ticks[0].timestamp, bar.timestamp_start);
+        assert!(ticks[3].timestamp < bar.timestamp_end);
+        assert!((ticks[0].ask - ticks[0].bid - 0.0002).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_short_bar_ticks_keep_distinct_timestamps() {
+        let mut bar = create_test_bar(1.0920, 1.0930, 1.0900, 1.0925);
+
+        // Four milliseconds fit one tick each
+        bar.timestamp_end = bar.timestamp_start + 4;
+        let timestamps: Vec<i64> = BarReplayer::default()
+            .replay_bar(&bar)
+            .iter()
+            .map(|t| t.timestamp - bar.timestamp_start)
+            .collect();
+        assert_eq!(timestamps, vec![0, 1, 2, 3]);
+
+        // Anything shorter is spread over the bar's timeframe
+        for span in [0, 1, 3] {
+            bar.timestamp_end = bar.timestamp_start + span;
+            let ticks = BarReplayer::default().replay_bar(&bar);
+            for window in ticks.windows(2) {
+                assert!(window[0].timestamp < window[1].timestamp);
+            }
+            assert_eq!(ticks[0].timestamp, bar.timestamp_start);
+            assert_eq!(ticks[3].timestamp, bar.timestamp_start + 59_999);
+        }
+    }
+
+    #[test]
+    fn test_replayed_ticks_reproduce_ohlc() {
+        let bars = vec![
+            create_test_bar(1.0920, 1.0930, 1.0900, 1.0925),
+            Bar::new(
+                "EURUSD".to_string(),
+                Timeframe::M1,
+                1704067260000,
+                1704067320000,
+                1.0925,
+                1.0940,
+                1.0921,
+                1.0922,
+            ),
+        ];
+
+        for path in [
+            IntrabarPath::HighFirst,
+            IntrabarPath::LowFirst,
+            IntrabarPath::NearestFirst,
+        ] {
+            let replayer = BarReplayer::new(path);
+            let mut aggregator = TickToBarAggregator::new();
+            for tick in replayer.replay_bars(&bars) {
+                aggregator.process_tick(&tick);
+            }
+            aggregator.flush();
+
+            let rebuilt: Vec<&Bar> = aggregator
+                .get_completed_bars()
+                .iter()
+                .filter(|b| b.timeframe == Timeframe::M1)
+                .collect();
+            assert_eq!(rebuilt.len(), bars.len());
+
+            for (original, rebuilt) in bars.iter().zip(rebuilt) {
+                assert_eq!(rebuilt.timestamp_start, original.timestamp_start);
+                assert_eq!(rebuilt.open, original.open);
+                assert_eq!(rebuilt.high, original.high);
+                assert_eq!(rebuilt.low, original.low);
+                assert_eq!(rebuilt.close, original.close);
+                assert_eq!(rebuilt.tick_count, Some(4));
+            }
+        }
+    }
+
+    #[test]
+    fn test_pessimistic_ordering_triggers_stop() {
+        // Long entered at the open with both the stop and the target inside the bar
+        let bar = create_test_bar(1.0920, 1.0940, 1.0900, 1.0925);
+        let stop_loss = 1.0905;
+        let take_profit = 1.0935;
+
+        let first_exit = |path: IntrabarPath| {
+            BarReplayer::new(path)
+                .replay_bar(&bar)
+                .iter()
+                .find_map(|tick| {
+                    if tick.bid <= stop_loss {
+                        Some("stop")
+                    } else if tick.bid >= take_profit {
+                        Some("target")
+                    } else {
+                        None
+                    }
+                })
+        };
+
+        assert_eq!(first_exit(IntrabarPath::pessimistic(true)), Some("stop"));
+        assert_eq!(first_exit(IntrabarPath::optimistic(true)), Some("target"));
+    }
+}
@@ -0,0 +1,135 @@
+use crate::models::Bar;
+
+/// Converts standard OHLC bars into Heikin-Ashi candles.
+///
+/// - `ha_close = (open + high + low + close) / 4`
+/// - `ha_open = (prev_ha_open + prev_ha_close) / 2`, seeded with
+///   `(open + close) / 2` for the first bar
+/// - `ha_high` / `ha_low` are the extremes of the raw high/low and the HA open/close
+///
+/// Symbol, timeframe, timestamps, volume and tick count are carried over.
+/// The chain is continuous across gaps in the input; only `reset()` starts
+/// a new chain.
+#[derive(Debug, Clone, Default)]
+pub struct HeikinAshiTransformer {
+    previous: Option<(f64, f64)>,
+}
+
+impl HeikinAshiTransformer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Transform the next bar in the series
+    pub fn push(&mut self, bar: &Bar) -> Bar {
+        let ha_close = (bar.open + bar.high + bar.low + bar.close) / 4.0;
+        let ha_open = match self.previous {
+            Some((prev_open, prev_close)) => (prev_open + prev_close) / 2.0,
+            None => (bar.open + bar.close) / 2.0,
+        };
+        self.previous = Some((ha_open, ha_close));
+
+        Bar {
+            id: None,
+            open: ha_open,
+            high: bar.high.max(ha_open).max(ha_close),
+            low: bar.low.min(ha_open).min(ha_close),
+            close: ha_close,
+            ..bar.clone()
+        }
+    }
+
+    /// Transform a whole series, continuing from any previously pushed bars
+    pub fn transform(&mut self, bars: &[Bar]) -> Vec<Bar> {
+        bars.iter().map(|bar| self.push(bar)).collect()
+    }
+
+    /// Start a new Heikin-Ashi chain
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timeframe::Timeframe;
+
+    const START: i64 = 1704067200000; // 2024-01-01 00:00:00
+
+    fn create_test_bar(index: i64, open: f64, high: f64, low: f64, close: f64) -> Bar {
+        let duration = Timeframe::M1.duration_ms();
+        Bar::new(
+            "EURUSD".to_string(),
+            Timeframe::M1,
+            START + index * duration,
+            START + (index + 1) * duration,
+            open,
+            high,
+            low,
+            close,
+        )
+        .with_volume(100 + index)
+        .with_tick_count(10)
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-12,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_first_bar_seed() {
+        let bar = create_test_bar(0, 10.0, 12.0, 9.0, 11.0);
+        let ha = HeikinAshiTransformer::new().push(&bar);
+
+        assert_close(ha.open, 10.5);
+        assert_close(ha.close, 10.5);
+        assert_close(ha.high, 12.0);
+        assert_close(ha.low, 9.0);
+        assert_eq!(ha.symbol, "EURUSD");
+        assert_eq!(ha.timeframe, Timeframe::M1);
+        assert_eq!(ha.timestamp_start, bar.timestamp_start);
+        assert_eq!(ha.volume, Some(100));
+        assert_eq!(ha.tick_count, Some(10));
+    }
+
+    #[test]
+    fn test_chain_and_high_low_include_ha_body() {
+        let bars = vec![
+            create_test_bar(0, 10.0, 12.0, 9.0, 11.0),
+            create_test_bar(1, 11.0, 11.5, 10.8, 11.2),
+        ];
+        let ha = HeikinAshiTransformer::new().transform(&bars);
+
+        // ha_open = (10.5 + 10.5) / 2, below the raw low of 10.8
+        assert_close(ha[1].open, 10.5);
+        assert_close(ha[1].close, (11.0 + 11.5 + 10.8 + 11.2) / 4.0);
+        assert_close(ha[1].high, 11.5);
+        assert_close(ha[1].low, 10.5);
+    }
+
+    #[test]
+    fn test_incremental_matches_batch_and_gaps_keep_chain() {
+        // Bar 3 is missing; the chain continues regardless
+        let bars = vec![
+            create_test_bar(0, 10.0, 12.0, 9.0, 11.0),
+            create_test_bar(1, 11.0, 11.5, 10.8, 11.2),
+            create_test_bar(2, 11.2, 11.9, 11.0, 11.8),
+            create_test_bar(4, 11.8, 12.4, 11.6, 12.2),
+        ];
+        let batch = HeikinAshiTransformer::new().transform(&bars);
+
+        let mut incremental = HeikinAshiTransformer::new();
+        for (bar, expected) in bars.iter().zip(&batch) {
+            assert_eq!(&incremental.push(bar), expected);
+        }
+        assert_close(batch[3].open, (batch[2].open + batch[2].close) / 2.0);
+
+        incremental.reset();
+        let reseeded = incremental.push(&bars[3]);
+        assert_close(reseeded.open, (11.8 + 12.2) / 2.0);
+    }
+}
@@ -0,0 +1,239 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+use super::tick_to_bar::TickToBarAggregator;
+use crate::database::Database;
+use crate::models::Bar;
+use crate::timeframe::Timeframe;
+
+const DEFAULT_CHUNK_SIZE: usize = 50_000;
+
+#[derive(Debug, Clone)]
+pub struct AggregationSummary {
+    pub symbol: String,
+    pub ticks_processed: usize,
+    /// Bars written per requested timeframe, in request order
+    pub bars_written: Vec<(Timeframe, usize)>,
+    pub duration: Duration,
+}
+
+impl AggregationSummary {
+    pub fn total_bars(&self) -> usize {
+        self.bars_written.iter().map(|(_, count)| count).sum()
+    }
+}
+
+/// Builds bars from stored ticks and writes them back to the database.
+///
+/// Ticks are streamed in chunks through a `TickToBarAggregator` and the
+/// bars completed by each chunk are written before the next is read. Bars
+/// are stored with `INSERT OR REPLACE`, so re-running over the same range
+/// rewrites rather than duplicates them. Bars whose period runs past the
+/// end of the range are not written, so a narrower run never replaces a
+/// complete bar with a truncated one.
+pub struct BarMaterializer<'a> {
+    database: &'a mut Database,
+    chunk_size: usize,
+}
+
+impl<'a> BarMaterializer<'a> {
+    pub fn new(database: &'a mut Database) -> Self {
+        Self {
+            database,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    pub fn run(
+        &mut self,
+        symbol: &str,
+        timeframes: &[Timeframe],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<AggregationSummary> {
+        let start_time = Instant::now();
+        let mut aggregator = TickToBarAggregator::new();
+        let mut counts: Vec<(Timeframe, usize)> = timeframes.iter().map(|&tf| (tf, 0)).collect();
+        let mut ticks_processed = 0;
+
+        // Widen to the first bar boundary so the opening bars are complete
+        let start_ms = start.timestamp_millis();
+        let mut after = timeframes
+            .iter()
+            .map(|tf| tf.bar_start_timestamp(start_ms))
+            .min()
+            .unwrap_or(start_ms)
+            - 1;
+
+        info!("Aggregating {} ticks into {:?}", symbol, timeframes);
+
+        loop {
+            let ticks = self
+                .database
+                .query_ticks_after(symbol, after, end, self.chunk_size)
+                .context("Failed to read ticks")?;
+            let Some(last) = ticks.last() else { break };
+            after = last.timestamp;
+            ticks_processed += ticks.len();
+
+            let completed: Vec<Bar> = ticks
+                .iter()
+                .flat_map(|tick| aggregator.process_tick(tick))
+                .collect();
+            // Bars are written below; don't let the aggregator hold a second copy
+            aggregator.clear_completed_bars();
+            self.write(&completed, &mut counts)?;
+            debug!("Aggregated chunk of {} ticks", ticks.len());
+        }
+
+        // The last bar of each timeframe is still open at the end of the
+        // data. It holds every stored tick of its period only if that period
+        // ends within the range.
+        let end_ms = end.timestamp_millis();
+        let remaining: Vec<Bar> = aggregator
+            .flush()
+            .into_iter()
+            .filter(|bar| bar.timestamp_end <= end_ms)
+            .collect();
+        self.write(&remaining, &mut counts)?;
+
+        let summary = AggregationSummary {
+            symbol: symbol.to_string(),
+            ticks_processed,
+            bars_written: counts,
+            duration: start_time.elapsed(),
+        };
+        info!(
+            "Aggregation completed: {} ticks into {} bars in {:?}",
+            summary.ticks_processed,
+            summary.total_bars(),
+            summary.duration
+        );
+        Ok(summary)
+    }
+
+    fn write(&mut self, bars: &[Bar], counts: &mut [(Timeframe, usize)]) -> Result<()> {
+        let selected: Vec<Bar> = bars
+            .iter()
+            .filter(|bar| counts.iter().any(|(tf, _)| *tf == bar.timeframe))
+            .cloned()
+            .collect();
+        if selected.is_empty() {
+            return Ok(());
+        }
+
+        self.database
+            .batch_insert_bars(&selected)
+            .context("Failed to write bars")?;
+
+        for bar in &selected {
+            if let Some((_, count)) = counts.iter_mut().find(|(tf, _)| *tf == bar.timeframe) {
+                *count += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Tick;
+
+    const START: i64 = 1704067200000;
+
+    fn seeded_database() -> Database {
+        let db = Database::new_memory().unwrap();
+        // One tick every 20s for 10 minutes
+        let ticks: Vec<Tick> = (0..30)
+            .map(|i| {
+                Tick::new_with_millis(
+                    "EURUSD".to_string(),
+                    START + i * 20_000,
+                    1.0920 + i as f64 * 0.0001,
+                    1.0922 + i as f64 * 0.0001,
+                )
+            })
+            .collect();
+        db.insert_ticks(&ticks).unwrap();
+        db
+    }
+
+    fn range() -> (DateTime<Utc>, DateTime<Utc>) {
+        (
+            DateTime::from_timestamp_millis(START).unwrap(),
+            DateTime::from_timestamp_millis(START + 3_600_000).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_materialize_selected_timeframes_with_flush() {
+        let mut db = seeded_database();
+        let (start, end) = range();
+
+        let summary = BarMaterializer::new(&mut db)
+            .with_chunk_size(7)
+            .run("EURUSD", &[Timeframe::M1, Timeframe::M5], start, end)
+            .unwrap();
+
+        assert_eq!(summary.ticks_processed, 30);
+        // The final partial bars are flushed, not dropped
+        assert_eq!(
+            summary.bars_written,
+            vec![(Timeframe::M1, 10), (Timeframe::M5, 2)]
+        );
+        assert_eq!(db.count_bars().unwrap(), 12);
+
+        let m5 = db.query_bars("EURUSD", Timeframe::M5, start, end).unwrap();
+        assert_eq!(m5[0].tick_count, Some(15));
+        assert!(db
+            .query_bars("EURUSD", Timeframe::H1, start, end)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_rerun_is_idempotent() {
+        let mut db = seeded_database();
+        let (start, end) = range();
+
+        for _ in 0..2 {
+            BarMaterializer::new(&mut db)
+                .run("EURUSD", &Timeframe::all(), start, end)
+                .unwrap();
+        }
+
+        let m1 = db.query_bars("EURUSD", Timeframe::M1, start, end).unwrap();
+        assert_eq!(m1.len(), 10);
+        // H4 and longer bars run past the hour and are left out
+        assert_eq!(db.count_bars().unwrap(), 10 + 2 + 1 + 1);
+    }
+
+    #[test]
+    fn test_narrower_rerun_keeps_complete_bars() {
+        let mut db = seeded_database();
+        let (start, end) = range();
+        BarMaterializer::new(&mut db)
+            .run("EURUSD", &[Timeframe::M5], start, end)
+            .unwrap();
+
+        // Ending seven minutes in cuts the second M5 bar short
+        let narrow_end = DateTime::from_timestamp_millis(START + 7 * 60_000).unwrap();
+        let summary = BarMaterializer::new(&mut db)
+            .run("EURUSD", &[Timeframe::M5], start, narrow_end)
+            .unwrap();
+        assert_eq!(summary.bars_written, vec![(Timeframe::M5, 1)]);
+
+        let m5 = db.query_bars("EURUSD", Timeframe::M5, start, end).unwrap();
+        assert_eq!(m5.len(), 2);
+        assert_eq!(m5[1].tick_count, Some(15));
+        assert!((m5[1].close - 1.0950).abs() < 1e-9);
+    }
+}
@@ -1,3 +1,15 @@
+mod bar_to_tick;
+mod heikin_ashi;
+mod materializer;
+mod range_bar;
+mod renko;
 mod tick_to_bar;
 
-pub use tick_to_bar::{BarAggregator, TickToBarAggregator};
+pub use bar_to_tick::{BarReplayer, IntrabarPath};
+pub use heikin_ashi::HeikinAshiTransformer;
+pub use materializer::{AggregationSummary, BarMaterializer};
+pub use range_bar::RangeBarAggregator;
+pub use renko::RenkoAggregator;
+pub use tick_to_bar::{
+    BarAggregator, BarPriceSource, BarValidationStats, InvalidBarPolicy, TickToBarAggregator,
+};
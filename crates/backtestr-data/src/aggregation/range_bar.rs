@@ -0,0 +1,257 @@
+use super::tick_to_bar::BarAggregator;
+use crate::database::{DatabaseError, Result};
+use crate::models::{Bar, Tick};
+use crate::timeframe::Timeframe;
+use std::collections::HashMap;
+
+/// Tolerance for float noise when comparing a bar's range to the threshold
+const RANGE_EPSILON: f64 = 1e-9;
+
+/// Builds range bars from tick midpoints.
+///
+/// A bar closes as soon as its `high - low` reaches `range`, including the
+/// tick that breached it, so a tick that gaps far past the threshold yields
+/// one bar with the real high/low rather than several artificial ones. The
+/// next bar opens at the breaching price and its timestamps and tick count
+/// cover only the ticks that follow.
+///
+/// Range bars are not time-based, so `timeframe` on the emitted bars is only
+/// a label (M1 unless set with `with_timeframe`).
+#[derive(Debug, Clone)]
+pub struct RangeBarAggregator {
+    range: f64,
+    timeframe: Timeframe,
+    active_bars: HashMap<String, RangeBarBuilder>,
+}
+
+#[derive(Debug, Clone)]
+struct RangeBarBuilder {
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    timestamp_start: Option<i64>,
+    timestamp_end: i64,
+    volume: i64,
+    tick_count: i32,
+}
+
+impl RangeBarBuilder {
+    fn open_at(price: f64) -> Self {
+        Self {
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            timestamp_start: None,
+            timestamp_end: 0,
+            volume: 0,
+            tick_count: 0,
+        }
+    }
+
+    fn add_tick(&mut self, tick: &Tick, price: f64) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        self.timestamp_start.get_or_insert(tick.timestamp);
+        self.timestamp_end = tick.timestamp;
+
+        if let (Some(bid_size), Some(ask_size)) = (tick.bid_size, tick.ask_size) {
+            self.volume += (bid_size + ask_size) / 2;
+        }
+
+        self.tick_count += 1;
+    }
+
+    fn build(&self, symbol: &str, timeframe: Timeframe) -> Option<Bar> {
+        let timestamp_start = self.timestamp_start?;
+
+        let mut bar = Bar::new(
+            symbol.to_string(),
+            timeframe,
+            timestamp_start,
+            self.timestamp_end,
+            self.open,
+            self.high,
+            self.low,
+            self.close,
+        )
+        .with_tick_count(self.tick_count);
+
+        if self.volume > 0 {
+            bar = bar.with_volume(self.volume);
+        }
+
+        Some(bar)
+    }
+}
+
+impl RangeBarAggregator {
+    /// Fails unless `range` is a positive, finite price distance
+    pub fn new(range: f64) -> Result<Self> {
+        if !(range.is_finite() && range > 0.0) {
+            return Err(DatabaseError::InvalidParameter(format!(
+                "range must be positive, got {}",
+                range
+            )));
+        }
+        Ok(Self {
+            range,
+            timeframe: Timeframe::M1,
+            active_bars: HashMap::new(),
+        })
+    }
+
+    /// Timeframe label applied to emitted bars
+    pub fn with_timeframe(mut self, timeframe: Timeframe) -> Self {
+        self.timeframe = timeframe;
+        self
+    }
+
+    pub fn range(&self) -> f64 {
+        self.range
+    }
+
+    /// Process a tick, returning the bar it completed, if any
+    pub fn process_tick(&mut self, tick: &Tick) -> Vec<Bar> {
+        let price = (tick.bid + tick.ask) / 2.0;
+
+        let builder = self
+            .active_bars
+            .entry(tick.symbol.clone())
+            .or_insert_with(|| RangeBarBuilder::open_at(price));
+
+        builder.add_tick(tick, price);
+
+        if builder.high - builder.low + RANGE_EPSILON < self.range {
+            return Vec::new();
+        }
+
+        let completed = builder.build(&tick.symbol, self.timeframe);
+        *builder = RangeBarBuilder::open_at(price);
+
+        completed.into_iter().collect()
+    }
+
+    /// Complete all partially built bars (e.g., at end of data)
+    pub fn flush(&mut self) -> Vec<Bar> {
+        let completed = self
+            .active_bars
+            .iter()
+            .filter_map(|(symbol, builder)| builder.build(symbol, self.timeframe))
+            .collect();
+
+        self.active_bars.clear();
+        completed
+    }
+}
+
+impl BarAggregator for RangeBarAggregator {
+    fn process_tick(&mut self, tick: &Tick) -> Vec<Bar> {
+        self.process_tick(tick)
+    }
+
+    fn flush(&mut self) -> Vec<Bar> {
+        self.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: i64 = 1704067200000; // 2024-01-01 00:00:00
+
+    fn tick(timestamp: i64, mid: f64) -> Tick {
+        Tick::new_with_millis(
+            "EURUSD".to_string(),
+            timestamp,
+            mid - 0.00005,
+            mid + 0.00005,
+        )
+        .with_sizes(1_000, 1_000)
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_bar_closes_when_range_reached() {
+        let mut aggregator = RangeBarAggregator::new(0.0010).unwrap();
+
+        assert!(aggregator.process_tick(&tick(START, 1.1000)).is_empty());
+        assert!(aggregator
+            .process_tick(&tick(START + 1_000, 1.1006))
+            .is_empty());
+        assert!(aggregator
+            .process_tick(&tick(START + 2_000, 1.0997))
+            .is_empty());
+
+        let bars = aggregator.process_tick(&tick(START + 3_000, 1.1007));
+        assert_eq!(bars.len(), 1);
+        let bar = &bars[0];
+        assert_close(bar.open, 1.1000);
+        assert_close(bar.high, 1.1007);
+        assert_close(bar.low, 1.0997);
+        assert_close(bar.close, 1.1007);
+        assert_eq!(bar.timestamp_start, START);
+        assert_eq!(bar.timestamp_end, START + 3_000);
+        assert_eq!(bar.tick_count, Some(4));
+        assert_eq!(bar.volume, Some(4_000));
+    }
+
+    #[test]
+    fn test_next_bar_opens_at_breaching_price() {
+        let mut aggregator = RangeBarAggregator::new(0.0010).unwrap();
+        aggregator.process_tick(&tick(START, 1.1000));
+        aggregator.process_tick(&tick(START + 1_000, 1.1010));
+
+        aggregator.process_tick(&tick(START + 5_000, 1.1004));
+        let bars = aggregator.flush();
+        assert_eq!(bars.len(), 1);
+        let bar = &bars[0];
+        assert_close(bar.open, 1.1010);
+        assert_close(bar.high, 1.1010);
+        assert_close(bar.low, 1.1004);
+        assert_eq!(bar.timestamp_start, START + 5_000);
+        assert_eq!(bar.tick_count, Some(1));
+    }
+
+    #[test]
+    fn test_gap_produces_single_bar_with_real_extremes() {
+        let mut aggregator = RangeBarAggregator::new(0.0010).unwrap();
+        aggregator.process_tick(&tick(START, 1.1000));
+
+        let bars = aggregator.process_tick(&tick(START + 1_000, 1.1050));
+        assert_eq!(bars.len(), 1);
+        assert_close(bars[0].high, 1.1050);
+        assert_close(bars[0].low, 1.1000);
+        assert_close(bars[0].range(), 0.0050);
+    }
+
+    #[test]
+    fn test_drop_in_bar_aggregator() {
+        let mut aggregator: Box<dyn BarAggregator> =
+            Box::new(RangeBarAggregator::new(0.0010).unwrap());
+        aggregator.process_tick(&tick(START, 1.1000));
+        assert_eq!(aggregator.flush().len(), 1);
+        assert!(aggregator.flush().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_range_rejected() {
+        for invalid in [0.0, -0.0010, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                RangeBarAggregator::new(invalid),
+                Err(DatabaseError::InvalidParameter(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use super::tick_to_bar::BarAggregator;
+use crate::database::{DatabaseError, Result};
+use crate::models::{Bar, Tick};
+use crate::timeframe::Timeframe;
+use std::collections::HashMap;
+
+/// Tolerance for float noise when measuring whole bricks
+const BRICK_EPSILON: f64 = 1e-9;
+
+/// Builds Renko bricks from tick midpoints.
+///
+/// A brick is emitted each time price moves a full `brick_size` away from the
+/// close of the previous brick, up or down. The first tick of a symbol anchors
+/// the brick grid. A single tick that jumps several bricks emits every
+/// intervening brick in order.
+///
+/// Bricks are not time-based, so `timeframe` on the emitted bars is only a
+/// label (M1 unless set with `with_timeframe`). The first brick completed by
+/// a tick spans from the first tick after the previous brick to that tick and
+/// carries their tick count and volume; any further bricks from the same tick
+/// start and end at its timestamp.
+#[derive(Debug, Clone)]
+pub struct RenkoAggregator {
+    brick_size: f64,
+    timeframe: Timeframe,
+    states: HashMap<String, RenkoState>,
+}
+
+#[derive(Debug, Clone)]
+struct RenkoState {
+    anchor: f64,
+    /// Close of the last brick, in bricks from the anchor
+    level: i64,
+    timestamp_start: Option<i64>,
+    tick_count: i32,
+    volume: i64,
+}
+
+impl RenkoAggregator {
+    /// Fails unless `brick_size` is a positive, finite price distance
+    pub fn new(brick_size: f64) -> Result<Self> {
+        if !(brick_size.is_finite() && brick_size > 0.0) {
+            return Err(DatabaseError::InvalidParameter(format!(
+                "brick size must be positive, got {}",
+                brick_size
+            )));
+        }
+        Ok(Self {
+            brick_size,
+            timeframe: Timeframe::M1,
+            states: HashMap::new(),
+        })
+    }
+
+    /// Timeframe label applied to emitted bricks
+    pub fn with_timeframe(mut self, timeframe: Timeframe) -> Self {
+        self.timeframe = timeframe;
+        self
+    }
+
+    pub fn brick_size(&self) -> f64 {
+        self.brick_size
+    }
+
+    /// Process a tick, returning any bricks it completed
+    pub fn process_tick(&mut self, tick: &Tick) -> Vec<Bar> {
+        let price = (tick.bid + tick.ask) / 2.0;
+
+        let state = self
+            .states
+            .entry(tick.symbol.clone())
+            .or_insert_with(|| RenkoState {
+                anchor: price,
+                level: 0,
+                timestamp_start: None,
+                tick_count: 0,
+                volume: 0,
+            });
+
+        state.timestamp_start.get_or_insert(tick.timestamp);
+        state.tick_count += 1;
+        if let (Some(bid_size), Some(ask_size)) = (tick.bid_size, tick.ask_size) {
+            state.volume += (bid_size + ask_size) / 2;
+        }
+
+        let last_close = state.anchor + state.level as f64 * self.brick_size;
+        let moved = (price - last_close) / self.brick_size;
+        let bricks = if moved >= 0.0 {
+            (moved + BRICK_EPSILON).floor() as i64
+        } else {
+            (moved - BRICK_EPSILON).ceil() as i64
+        };
+
+        if bricks == 0 {
+            return Vec::new();
+        }
+
+        let step = bricks.signum();
+        let mut completed = Vec::with_capacity(bricks.unsigned_abs() as usize);
+        for i in 0..bricks.abs() {
+            let open = state.anchor + state.level as f64 * self.brick_size;
+            state.level += step;
+            let close = state.anchor + state.level as f64 * self.brick_size;
+
+            let timestamp_start = if i == 0 {
+                state.timestamp_start.unwrap_or(tick.timestamp)
+            } else {
+                tick.timestamp
+            };
+
+            let mut bar = Bar::new(
+                tick.symbol.clone(),
+                self.timeframe,
+                timestamp_start,
+                tick.timestamp,
+                open,
+                open.max(close),
+                open.min(close),
+                close,
+            );
+
+            if i == 0 {
+                bar = bar.with_tick_count(state.tick_count);
+                if state.volume > 0 {
+                    bar = bar.with_volume(state.volume);
+                }
+            }
+
+            completed.push(bar);
+        }
+
+        state.timestamp_start = None;
+        state.tick_count = 0;
+        state.volume = 0;
+
+        completed
+    }
+
+    /// Drop all partial bricks. Renko only emits complete bricks, so nothing
+    /// is returned.
+    pub fn flush(&mut self) -> Vec<Bar> {
+        self.states.clear();
+        Vec::new()
+    }
+}
+
+impl BarAggregator for RenkoAggregator {
+    fn process_tick(&mut self, tick: &Tick) -> Vec<Bar> {
+        self.process_tick(tick)
+    }
+
+    fn flush(&mut self) -> Vec<Bar> {
+        self.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: i64 = 1704067200000; // 2024-01-01 00:00:00
+
+    fn tick(timestamp: i64, mid: f64) -> Tick {
+        Tick::new_with_millis(
+            "EURUSD".to_string(),
+            timestamp,
+            mid - 0.00005,
+            mid + 0.00005,
+        )
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_brick_emitted_after_full_move() {
+        let mut renko = RenkoAggregator::new(0.0010).unwrap();
+
+        assert!(renko.process_tick(&tick(START, 1.1000)).is_empty());
+        assert!(renko.process_tick(&tick(START + 1_000, 1.1009)).is_empty());
+
+        let bricks = renko.process_tick(&tick(START + 2_000, 1.1010));
+        assert_eq!(bricks.len(), 1);
+        let brick = &bricks[0];
+        assert_close(brick.open, 1.1000);
+        assert_close(brick.close, 1.1010);
+        assert_close(brick.high, 1.1010);
+        assert_close(brick.low, 1.1000);
+        assert_eq!(brick.timestamp_start, START);
+        assert_eq!(brick.timestamp_end, START + 2_000);
+        assert_eq!(brick.tick_count, Some(3));
+    }
+
+    #[test]
+    fn test_large_jump_emits_every_brick_in_order() {
+        let mut renko = RenkoAggregator::new(0.0010).unwrap();
+        renko.process_tick(&tick(START, 1.1000));
+
+        let bricks = renko.process_tick(&tick(START + 5_000, 1.1035));
+        assert_eq!(bricks.len(), 3);
+        for (i, brick) in bricks.iter().enumerate() {
+            assert_close(brick.open, 1.1000 + i as f64 * 0.0010);
+            assert_close(brick.close, 1.1010 + i as f64 * 0.0010);
+            assert_eq!(brick.timestamp_end, START + 5_000);
+        }
+        assert_eq!(bricks[0].timestamp_start, START);
+        assert_eq!(bricks[1].timestamp_start, START + 5_000);
+
+        // Next brick is measured from the last close, 1.1030
+        assert!(renko.process_tick(&tick(START + 6_000, 1.1039)).is_empty());
+        let bricks = renko.process_tick(&tick(START + 7_000, 1.1040));
+        assert_eq!(bricks.len(), 1);
+        assert_close(bricks[0].open, 1.1030);
+    }
+
+    #[test]
+    fn test_down_bricks() {
+        let mut renko = RenkoAggregator::new(0.0010)
+            .unwrap()
+            .with_timeframe(Timeframe::M5);
+        renko.process_tick(&tick(START, 1.1000));
+
+        let bricks = renko.process_tick(&tick(START + 1_000, 1.0978));
+        assert_eq!(bricks.len(), 2);
+        assert_close(bricks[1].open, 1.0990);
+        assert_close(bricks[1].close, 1.0980);
+        assert_close(bricks[1].high, 1.0990);
+        assert_close(bricks[1].low, 1.0980);
+        assert!(bricks.iter().all(|b| b.is_bearish()));
+        assert!(bricks.iter().all(|b| b.timeframe == Timeframe::M5));
+    }
+
+    #[test]
+    fn test_flush_discards_partial_brick() {
+        let mut renko = RenkoAggregator::new(0.0010).unwrap();
+        renko.process_tick(&tick(START, 1.1000));
+        renko.process_tick(&tick(START + 1_000, 1.1005));
+
+        assert!(renko.flush().is_empty());
+        // A new anchor is taken after flushing
+        assert!(renko.process_tick(&tick(START + 2_000, 1.1012)).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_brick_size_rejected() {
+        for invalid in [0.0, -0.0010, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                RenkoAggregator::new(invalid),
+                Err(DatabaseError::InvalidParameter(_))
+            ));
+        }
+    }
+}
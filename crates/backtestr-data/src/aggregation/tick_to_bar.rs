@@ -1,13 +1,48 @@
-use crate::models::{Bar, Tick};
+use crate::models::{round_price, Bar, PricePrecision, Tick};
 use crate::timeframe::Timeframe;
 use std::collections::HashMap;
 
+/// What to do with a completed bar whose prices are inconsistent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InvalidBarPolicy {
+    /// Swap a high below the low, then clamp open and close into the range
+    #[default]
+    Repair,
+    /// Discard the bar
+    Drop,
+}
+
+/// Price each tick contributes to a bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BarPriceSource {
+    /// Midpoint of bid and ask
+    #[default]
+    Midpoint,
+    /// Last trade price and size. Quote-only ticks still close finished
+    /// bars but add no price, volume or tick count.
+    Last,
+}
+
+/// Bars caught by OHLC validation since the aggregator was created
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BarValidationStats {
+    pub repaired: u64,
+    pub dropped: u64,
+}
+
 /// Aggregates ticks into bars for multiple timeframes
 pub struct TickToBarAggregator {
     /// Active bar builders indexed by symbol and timeframe
     active_bars: HashMap<(String, Timeframe), BarBuilder>,
     /// Completed bars ready to be persisted
     completed_bars: Vec<Bar>,
+    /// Decimal digits per symbol; midpoints are rounded before aggregation
+    precisions: HashMap<String, u8>,
+    /// Check `low <= open, close <= high` on every completed bar
+    validate: bool,
+    invalid_bar_policy: InvalidBarPolicy,
+    validation_stats: BarValidationStats,
+    price_source: BarPriceSource,
 }
 
 impl Default for TickToBarAggregator {
@@ -21,13 +56,67 @@ impl TickToBarAggregator {
         Self {
             active_bars: HashMap::new(),
             completed_bars: Vec::new(),
+            precisions: HashMap::new(),
+            validate: false,
+            invalid_bar_policy: InvalidBarPolicy::default(),
+            validation_stats: BarValidationStats::default(),
+            price_source: BarPriceSource::default(),
         }
     }
 
+    /// Check every completed bar for `low <= open, close <= high` and
+    /// finite prices, handling violations by `policy`. Off by default.
+    ///
+    /// Bars with a non-finite price cannot be repaired and are always
+    /// dropped.
+    pub fn with_validation(mut self, policy: InvalidBarPolicy) -> Self {
+        self.validate = true;
+        self.invalid_bar_policy = policy;
+        self
+    }
+
+    /// Counts of bars repaired and dropped by validation
+    pub fn validation_stats(&self) -> BarValidationStats {
+        self.validation_stats
+    }
+
+    /// Round tick midpoints to each symbol's quoted precision
+    pub fn with_price_precision(mut self, precision: PricePrecision) -> Self {
+        self.precisions = precision.digits;
+        self
+    }
+
+    /// Build bars from the last trade instead of the bid/ask midpoint
+    pub fn with_price_source(mut self, source: BarPriceSource) -> Self {
+        self.price_source = source;
+        self
+    }
+
+    pub fn set_precision(&mut self, symbol: &str, digits: u8) {
+        self.precisions.insert(symbol.to_string(), digits);
+    }
+
     /// Process a tick and potentially complete bars
     pub fn process_tick(&mut self, tick: &Tick) -> Vec<Bar> {
         let mut completed = Vec::new();
 
+        let sample = match self.price_source {
+            BarPriceSource::Last => tick.last.map(|last| (last, tick.last_size)),
+            BarPriceSource::Midpoint => Some((
+                (tick.bid + tick.ask) / 2.0,
+                tick.bid_size
+                    .zip(tick.ask_size)
+                    .map(|(bid_size, ask_size)| (bid_size + ask_size) / 2),
+            )),
+        };
+        // Round once up front so high/low comparisons see the same value as close
+        let sample = sample.map(|(price, volume)| match self.precisions.get(&tick.symbol) {
+            Some(&digits) => (round_price(price, digits), volume),
+            None => (price, volume),
+        });
+
+        let validation = self.validate.then_some(self.invalid_bar_policy);
+
         // Process for all timeframes
         for timeframe in Timeframe::all() {
             let key = (tick.symbol.clone(), timeframe);
@@ -43,7 +132,10 @@ impl TickToBarAggregator {
             if tick.timestamp >= builder.timestamp_end {
                 // Complete current bar if it has data
                 if builder.tick_count > 0 {
-                    if let Some(bar) = builder.build() {
+                    if let Some(bar) = builder
+                        .build()
+                        .and_then(|bar| check_bar(bar, validation, &mut self.validation_stats))
+                    {
                         completed.push(bar.clone());
                         self.completed_bars.push(bar);
                     }
@@ -54,7 +146,9 @@ impl TickToBarAggregator {
             }
 
             // Add tick to current bar
-            builder.add_tick(tick);
+            if let Some((price, volume)) = sample {
+                builder.add_tick(price, volume);
+            }
         }
 
         completed
@@ -63,10 +157,14 @@ impl TickToBarAggregator {
     /// Force completion of all active bars (e.g., at end of data)
     pub fn flush(&mut self) -> Vec<Bar> {
         let mut completed = Vec::new();
+        let validation = self.validate.then_some(self.invalid_bar_policy);
 
         for builder in self.active_bars.values() {
             if builder.tick_count > 0 {
-                if let Some(bar) = builder.build() {
+                if let Some(bar) = builder
+                    .build()
+                    .and_then(|bar| check_bar(bar, validation, &mut self.validation_stats))
+                {
                     completed.push(bar.clone());
                     self.completed_bars.push(bar);
                 }
@@ -88,6 +186,45 @@ impl TickToBarAggregator {
     }
 }
 
+/// Pass `bar` through OHLC validation when `policy` is set, counting any
+/// repair or drop in `stats`
+fn check_bar(
+    mut bar: Bar,
+    policy: Option<InvalidBarPolicy>,
+    stats: &mut BarValidationStats,
+) -> Option<Bar> {
+    let Some(policy) = policy else {
+        return Some(bar);
+    };
+
+    let prices = [bar.open, bar.high, bar.low, bar.close];
+    if prices.iter().any(|price| !price.is_finite()) {
+        stats.dropped += 1;
+        return None;
+    }
+
+    let in_range = |price: f64| bar.low <= price && price <= bar.high;
+    if bar.high >= bar.low && in_range(bar.open) && in_range(bar.close) {
+        return Some(bar);
+    }
+
+    match policy {
+        InvalidBarPolicy::Drop => {
+            stats.dropped += 1;
+            None
+        }
+        InvalidBarPolicy::Repair => {
+            if bar.high < bar.low {
+                std::mem::swap(&mut bar.high, &mut bar.low);
+            }
+            bar.open = bar.open.clamp(bar.low, bar.high);
+            bar.close = bar.close.clamp(bar.low, bar.high);
+            stats.repaired += 1;
+            Some(bar)
+        }
+    }
+}
+
 /// Builder for a single bar
 #[derive(Debug, Clone)]
 struct BarBuilder {
@@ -119,26 +256,24 @@ impl BarBuilder {
         }
     }
 
-    fn add_tick(&mut self, tick: &Tick) {
-        let midpoint = (tick.bid + tick.ask) / 2.0;
-
+    fn add_tick(&mut self, price: f64, volume: Option<i64>) {
         // Set open on first tick
         if self.open.is_none() {
-            self.open = Some(midpoint);
+            self.open = Some(price);
         }
 
         // Update high
-        self.high = Some(self.high.map_or(midpoint, |h| h.max(midpoint)));
+        self.high = Some(self.high.map_or(price, |h| h.max(price)));
 
         // Update low
-        self.low = Some(self.low.map_or(midpoint, |l| l.min(midpoint)));
+        self.low = Some(self.low.map_or(price, |l| l.min(price)));
 
         // Always update close with latest tick
-        self.close = Some(midpoint);
+        self.close = Some(price);
 
         // Add volume if available
-        if let (Some(bid_size), Some(ask_size)) = (tick.bid_size, tick.ask_size) {
-            self.volume += (bid_size + ask_size) / 2;
+        if let Some(volume) = volume {
+            self.volume += volume;
         }
 
         self.tick_count += 1;
@@ -328,4 +463,198 @@ mod tests {
 
         assert_eq!(m1_bar.volume, Some(1500000)); // (1000000 + 1000000)/2 + (500000 + 500000)/2
     }
+
+    #[test]
+    fn test_last_price_source() {
+        let base_time = 1704067200000;
+        let ticks = [
+            create_test_tick("ES", base_time + 10000, 4800.00, 4800.50).with_last(4800.25, Some(3)),
+            create_test_tick("ES", base_time + 20000, 4801.00, 4801.50).with_last(4801.50, Some(5)),
+            // A quote without a trade adds nothing to a trade bar
+            create_test_tick("ES", base_time + 30000, 4799.50, 4800.00).with_sizes(40, 60),
+            create_test_tick("ES", base_time + 40000, 4800.50, 4801.00).with_last(4800.75, None),
+            // A quote-only tick in the next minute still closes the first bar
+            create_test_tick("ES", base_time + 70000, 4802.00, 4802.50).with_sizes(10, 10),
+        ];
+
+        let m1_bars = |source| {
+            let mut aggregator = TickToBarAggregator::new().with_price_source(source);
+            let mut bars: Vec<Bar> = ticks
+                .iter()
+                .flat_map(|tick| aggregator.process_tick(tick))
+                .collect();
+            bars.extend(aggregator.flush());
+            bars.retain(|b| b.timeframe == Timeframe::M1);
+            bars.sort_by_key(|b| b.timestamp_start);
+            bars
+        };
+
+        let last = m1_bars(BarPriceSource::Last);
+        assert_eq!(last.len(), 1);
+        assert_eq!(
+            (last[0].open, last[0].high, last[0].low, last[0].close),
+            (4800.25, 4801.50, 4800.25, 4800.75)
+        );
+        // Quoted sizes never count as traded volume
+        assert_eq!(last[0].volume, Some(8));
+        assert_eq!(last[0].tick_count, Some(3));
+
+        // The midpoint stays the default and ignores trades
+        let midpoint = m1_bars(BarPriceSource::default());
+        assert_eq!(midpoint.len(), 2);
+        assert_eq!(
+            (
+                midpoint[0].open,
+                midpoint[0].high,
+                midpoint[0].low,
+                midpoint[0].close
+            ),
+            (4800.25, 4801.25, 4799.75, 4800.75)
+        );
+        assert_eq!(midpoint[0].volume, Some(50));
+        assert_eq!(midpoint[1].volume, Some(10));
+    }
+
+    #[test]
+    fn test_midpoint_rounded_before_high_low() {
+        let mut aggregator = TickToBarAggregator::new()
+            .with_price_precision(PricePrecision::new().with_symbol("EURUSD", 5));
+
+        let base = 1704067200000;
+        // Midpoints carry float noise just above and below 1.0923
+        aggregator.process_tick(&create_test_tick("EURUSD", base, 1.09220000000003, 1.0924));
+        aggregator.process_tick(&create_test_tick(
+            "EURUSD",
+            base + 1000,
+            1.0921,
+            1.09249999999996,
+        ));
+        aggregator.process_tick(&create_test_tick(
+            "EURUSD",
+            base + 2000,
+            1.09220000000001,
+            1.0924,
+        ));
+
+        let completed = aggregator.flush();
+        let m1_bar = completed
+            .iter()
+            .find(|b| b.timeframe == Timeframe::M1)
+            .unwrap();
+
+        assert_eq!(m1_bar.open, 1.0923);
+        assert_eq!(m1_bar.high, 1.0923);
+        assert_eq!(m1_bar.low, 1.0923);
+        assert_eq!(m1_bar.close, m1_bar.high);
+    }
+
+    #[test]
+    fn test_symbols_without_precision_are_not_rounded() {
+        let mut aggregator = TickToBarAggregator::new();
+        aggregator.set_precision("USDJPY", 3);
+
+        let base = 1704067200000;
+        aggregator.process_tick(&create_test_tick("USDJPY", base, 149.2501, 149.2622));
+        aggregator.process_tick(&create_test_tick("EURUSD", base, 1.09221, 1.09222));
+
+        let completed = aggregator.flush();
+        let m1 = |symbol: &str| {
+            completed
+                .iter()
+                .find(|b| b.symbol == symbol && b.timeframe == Timeframe::M1)
+                .unwrap()
+                .clone()
+        };
+
+        assert_eq!(m1("USDJPY").close, 149.256);
+        assert_eq!(m1("EURUSD").close, (1.09221 + 1.09222) / 2.0);
+    }
+
+    fn bar(open: f64, high: f64, low: f64, close: f64) -> Bar {
+        Bar::new(
+            "EURUSD".to_string(),
+            Timeframe::M1,
+            0,
+            60_000,
+            open,
+            high,
+            low,
+            close,
+        )
+    }
+
+    #[test]
+    fn test_check_bar_repairs_by_clamping() {
+        let mut stats = BarValidationStats::default();
+        let policy = Some(InvalidBarPolicy::Repair);
+
+        let valid = bar(1.1, 1.2, 1.0, 1.15);
+        assert_eq!(check_bar(valid.clone(), policy, &mut stats), Some(valid));
+
+        let repaired = check_bar(bar(1.25, 1.2, 1.0, 0.9), policy, &mut stats).unwrap();
+        assert_eq!((repaired.open, repaired.close), (1.2, 1.0));
+
+        // High and low arrive swapped
+        let repaired = check_bar(bar(1.1, 1.0, 1.2, 1.15), policy, &mut stats).unwrap();
+        assert_eq!((repaired.high, repaired.low), (1.2, 1.0));
+        assert_eq!((repaired.open, repaired.close), (1.1, 1.15));
+
+        assert_eq!(
+            stats,
+            BarValidationStats {
+                repaired: 2,
+                dropped: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_check_bar_drop_policy_and_unchecked() {
+        let mut stats = BarValidationStats::default();
+        assert!(check_bar(
+            bar(1.3, 1.2, 1.0, 1.1),
+            Some(InvalidBarPolicy::Drop),
+            &mut stats
+        )
+        .is_none());
+        assert!(check_bar(
+            bar(f64::NAN, 1.2, 1.0, 1.1),
+            Some(InvalidBarPolicy::Repair),
+            &mut stats
+        )
+        .is_none());
+        assert_eq!(stats.dropped, 2);
+
+        // Without validation nothing is checked or counted
+        assert!(check_bar(bar(1.3, 1.2, 1.0, 1.1), None, &mut stats).is_some());
+        assert_eq!(stats.dropped, 2);
+        assert_eq!(stats.repaired, 0);
+    }
+
+    #[test]
+    fn test_validation_drops_bars_with_bad_ticks() {
+        let base = 1704067200000;
+        let ticks = [
+            create_test_tick("EURUSD", base, f64::NAN, 1.0922),
+            create_test_tick("EURUSD", base + 1000, 1.0920, 1.0922),
+        ];
+
+        let mut unchecked = TickToBarAggregator::new();
+        let mut validated = TickToBarAggregator::new().with_validation(InvalidBarPolicy::Repair);
+        for tick in &ticks {
+            unchecked.process_tick(tick);
+            validated.process_tick(tick);
+        }
+
+        let unchecked_bars = unchecked.flush();
+        assert!(unchecked_bars.iter().all(|b| b.open.is_nan()));
+        assert_eq!(unchecked.validation_stats(), BarValidationStats::default());
+
+        // The NaN open poisons every timeframe's bar
+        assert!(validated.flush().is_empty());
+        assert_eq!(
+            validated.validation_stats().dropped,
+            unchecked_bars.len() as u64
+        );
+    }
 }
@@ -1,10 +1,45 @@
 use super::error::{DatabaseError, Result};
+use super::options::{DatabaseOptions, DEFAULT_BUSY_TIMEOUT};
 use super::schema::initialize_schema;
-use rusqlite::Connection;
+use crate::migration::{run_pending, MigrationReport, MIGRATIONS};
+use r2d2::{Pool, PooledConnection};
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{Connection, OpenFlags};
+use std::ops::Deref;
 use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
 
+/// Prepared statements kept per connection; covers every statement the
+/// operations layer issues, so hot paths never re-prepare
+const STATEMENT_CACHE_CAPACITY: usize = 32;
+
+/// SQLite store for ticks and bars.
+///
+/// Writes always go through a single connection and are serialized. A
+/// database opened with `new_file_pooled` additionally keeps a pool of
+/// read-only connections in WAL mode, so queries from several threads run
+/// concurrently with each other and with the writer.
 pub struct Database {
-    conn: Connection,
+    conn: Mutex<Connection>,
+    read_pool: Option<Pool<SqliteConnectionManager>>,
+}
+
+/// A connection borrowed for a read: from the pool if there is one,
+/// otherwise the shared writer
+pub(crate) enum ReadConnection<'a> {
+    Pooled(PooledConnection<SqliteConnectionManager>),
+    Shared(MutexGuard<'a, Connection>),
+}
+
+impl Deref for ReadConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        match self {
+            ReadConnection::Pooled(conn) => conn,
+            ReadConnection::Shared(conn) => conn,
+        }
+    }
 }
 
 impl Database {
@@ -14,24 +49,99 @@ impl Database {
 
         initialize_schema(&conn)?;
 
-        Ok(Self { conn })
+        Ok(Self::single(conn))
     }
 
+    /// Open a file database with the default options: WAL journaling and
+    /// a busy timeout, so concurrent processes wait rather than fail
     pub fn new_file(path: &Path) -> Result<Self> {
+        Self::new_file_with_options(path, DatabaseOptions::default())
+    }
+
+    pub fn new_file_with_options(path: &Path, options: DatabaseOptions) -> Result<Self> {
         let conn = Connection::open(path)
             .map_err(|e| DatabaseError::InitializationError(e.to_string()))?;
 
+        options.apply(&conn)?;
         initialize_schema(&conn)?;
 
-        Ok(Self { conn })
+        Ok(Self::single(conn))
+    }
+
+    /// Open a file database with up to `pool_size` concurrent readers.
+    ///
+    /// Uses the default options, whose WAL mode lets readers run alongside
+    /// the writer. An in-memory path or a `pool_size` of zero falls back to
+    /// the single shared connection.
+    pub fn new_file_pooled(path: &Path, pool_size: usize) -> Result<Self> {
+        if path == Path::new(":memory:") {
+            return Self::new_memory();
+        }
+
+        let mut db = Self::new_file(path)?;
+        if pool_size == 0 {
+            return Ok(db);
+        }
+
+        let manager = SqliteConnectionManager::file(path)
+            .with_flags(OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+            .with_init(|conn| {
+                conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+                conn.busy_timeout(DEFAULT_BUSY_TIMEOUT)
+            });
+        let pool = Pool::builder()
+            .max_size(pool_size as u32)
+            .build(manager)
+            .map_err(|e| DatabaseError::InitializationError(e.to_string()))?;
+
+        db.read_pool = Some(pool);
+        Ok(db)
     }
 
-    pub(crate) fn connection(&self) -> &Connection {
-        &self.conn
+    /// Apply any migrations the file is missing.
+    ///
+    /// Opening a database already migrates it, so this normally reports
+    /// nothing to do; it fails if the file is newer than this build.
+    pub fn migrate(&mut self) -> Result<MigrationReport> {
+        run_pending(self.connection_mut(), MIGRATIONS)
+    }
+
+    /// Whether reads are served from a connection pool
+    pub fn is_pooled(&self) -> bool {
+        self.read_pool.is_some()
+    }
+
+    fn single(conn: Connection) -> Self {
+        conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+        Self {
+            conn: Mutex::new(conn),
+            read_pool: None,
+        }
+    }
+
+    /// The writer connection. A panic while it was held leaves no
+    /// transaction open, so a poisoned lock is still safe to reuse.
+    pub(crate) fn connection(&self) -> MutexGuard<'_, Connection> {
+        self.conn
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
     }
 
     pub(crate) fn connection_mut(&mut self) -> &mut Connection {
-        &mut self.conn
+        self.conn
+            .get_mut()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// A connection for read-only queries
+    pub(crate) fn reader(&self) -> Result<ReadConnection<'_>> {
+        match &self.read_pool {
+            Some(pool) => pool
+                .get()
+                .map(ReadConnection::Pooled)
+                .map_err(|e| DatabaseError::QueryError(e.to_string())),
+            None => Ok(ReadConnection::Shared(self.connection())),
+        }
     }
 }
 
@@ -43,8 +153,7 @@ mod tests {
     #[test]
     fn test_memory_database() -> Result<()> {
         let db = Database::new_memory()?;
-        // SQLite doesn't have is_autocommit() method
-        assert!(db.connection() as *const _ != std::ptr::null());
+        assert!(db.connection().is_autocommit());
         Ok(())
     }
 
@@ -54,10 +163,103 @@ mod tests {
         let db_path = temp_dir.path().join("test.db");
 
         let db = Database::new_file(&db_path)?;
-        // SQLite doesn't have is_autocommit() method
-        assert!(db.connection() as *const _ != std::ptr::null());
+        assert!(db.connection().is_autocommit());
         assert!(db_path.exists());
 
         Ok(())
     }
+
+    fn pragma<T: rusqlite::types::FromSql>(db: &Database, name: &str) -> T {
+        db.connection()
+            .query_row(&format!("PRAGMA {}", name), [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_file_database_defaults_to_wal_with_busy_timeout() -> Result<()> {
+        let temp_dir = tempdir().unwrap();
+        let db = Database::new_file(&temp_dir.path().join("defaults.db"))?;
+
+        assert_eq!(pragma::<String>(&db, "journal_mode").to_lowercase(), "wal");
+        assert_eq!(
+            pragma::<i64>(&db, "busy_timeout"),
+            DEFAULT_BUSY_TIMEOUT.as_millis() as i64
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_database_with_options() -> Result<()> {
+        use crate::database::{JournalMode, Synchronous};
+        use std::time::Duration;
+
+        let temp_dir = tempdir().unwrap();
+        let options = DatabaseOptions::default()
+            .with_journal_mode(JournalMode::Delete)
+            .with_synchronous(Synchronous::Full)
+            .with_cache_size(-2_000)
+            .with_busy_timeout(Duration::from_millis(250));
+        let db = Database::new_file_with_options(&temp_dir.path().join("custom.db"), options)?;
+
+        assert_eq!(
+            pragma::<String>(&db, "journal_mode").to_lowercase(),
+            "delete"
+        );
+        // FULL is 2
+        assert_eq!(pragma::<i64>(&db, "synchronous"), 2);
+        assert_eq!(pragma::<i64>(&db, "cache_size"), -2_000);
+        assert_eq!(pragma::<i64>(&db, "busy_timeout"), 250);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_and_refuse_newer_schema() -> Result<()> {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("versioned.db");
+
+        let mut db = Database::new_file(&db_path)?;
+        let report = db.migrate()?;
+        assert!(report.is_up_to_date());
+        assert_eq!(report.to_version, crate::migration::latest_version());
+
+        db.connection()
+            .execute("INSERT INTO db_version (version) VALUES (99)", [])
+            .unwrap();
+        drop(db);
+
+        assert!(matches!(
+            Database::new_file(&db_path),
+            Err(DatabaseError::UnsupportedSchemaVersion { found: 99, .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pooled_database_uses_wal() -> Result<()> {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("pooled.db");
+
+        let db = Database::new_file_pooled(&db_path, 4)?;
+        assert!(db.is_pooled());
+
+        let mode: String = db
+            .reader()?
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode.to_lowercase(), "wal");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pooled_memory_falls_back_to_single_connection() -> Result<()> {
+        let db = Database::new_file_pooled(Path::new(":memory:"), 4)?;
+        assert!(!db.is_pooled());
+        assert!(db.reader()?.is_autocommit());
+
+        Ok(())
+    }
 }
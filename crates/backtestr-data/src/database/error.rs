@@ -17,6 +17,12 @@ pub enum DatabaseError {
     #[error("Invalid parameter: {0}")]
     InvalidParameter(String),
 
+    #[error("Migration failed: {0}")]
+    MigrationError(String),
+
+    #[error("Database schema version {found} is newer than this build supports ({supported})")]
+    UnsupportedSchemaVersion { found: u32, supported: u32 },
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 }
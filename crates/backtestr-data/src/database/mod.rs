@@ -1,7 +1,17 @@
 mod connection;
 mod error;
 mod operations;
-mod schema;
+mod options;
+mod page;
+pub(crate) mod schema;
+mod stream;
 
 pub use connection::Database;
 pub use error::{DatabaseError, Result};
+pub use operations::TICKS_PER_STATEMENT;
+pub(crate) use operations::{bar_from_row, tick_from_row};
+pub use options::{
+    DatabaseOptions, JournalMode, Synchronous, DEFAULT_BUSY_TIMEOUT, DEFAULT_CACHE_SIZE,
+};
+pub use page::Page;
+pub use stream::STREAM_CHUNK_SIZE;
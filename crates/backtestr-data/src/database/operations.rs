@@ -1,41 +1,53 @@
 use super::connection::Database;
 use super::error::{DatabaseError, Result};
+use super::page::Page;
 use crate::models::{Bar, Tick};
 use crate::timeframe::Timeframe;
 use chrono::{DateTime, Utc};
-use rusqlite::params;
+use rusqlite::{params, ToSql};
 use std::str::FromStr;
 
+const INSERT_TICK_SQL: &str = "INSERT INTO ticks
+    (symbol, timestamp, bid, ask, bid_size, ask_size, last, last_size)
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?)";
+
+const QUERY_TICKS_SQL: &str =
+    "SELECT id, symbol, timestamp, bid, ask, bid_size, ask_size, last, last_size
+    FROM ticks
+    WHERE symbol = ? AND timestamp >= ? AND timestamp <= ?
+    ORDER BY timestamp";
+
 impl Database {
     pub fn insert_tick(&self, tick: &Tick) -> Result<()> {
-        let sql = "INSERT INTO ticks (symbol, timestamp, bid, ask, bid_size, ask_size)
-                   VALUES (?, ?, ?, ?, ?, ?)";
-
-        self.connection()
-            .execute(
-                sql,
-                params![
-                    tick.symbol,
-                    tick.timestamp,
-                    tick.bid,
-                    tick.ask,
-                    tick.bid_size,
-                    tick.ask_size
-                ],
-            )
+        let conn = self.connection();
+        let mut stmt = conn
+            .prepare_cached(INSERT_TICK_SQL)
             .map_err(|e| DatabaseError::InsertError(e.to_string()))?;
 
+        stmt.execute(params![
+            tick.symbol,
+            tick.timestamp,
+            tick.bid,
+            tick.ask,
+            tick.bid_size,
+            tick.ask_size,
+            tick.last,
+            tick.last_size
+        ])
+        .map_err(|e| DatabaseError::InsertError(e.to_string()))?;
+
         Ok(())
     }
 
     pub fn insert_ticks(&self, ticks: &[Tick]) -> Result<()> {
         // Use prepared statements for batch insert
-        let sql = "INSERT INTO ticks (symbol, timestamp, bid, ask, bid_size, ask_size)
-                   VALUES (?, ?, ?, ?, ?, ?)";
+        let sql = "INSERT INTO ticks
+                   (symbol, timestamp, bid, ask, bid_size, ask_size, last, last_size)
+                   VALUES (?, ?, ?, ?, ?, ?, ?, ?)";
 
-        let mut stmt = self
-            .connection()
-            .prepare(sql)
+        let conn = self.connection();
+        let mut stmt = conn
+            .prepare_cached(sql)
             .map_err(|e| DatabaseError::InsertError(e.to_string()))?;
 
         for tick in ticks {
@@ -45,7 +57,9 @@ impl Database {
                 tick.bid,
                 tick.ask,
                 tick.bid_size,
-                tick.ask_size
+                tick.ask_size,
+                tick.last,
+                tick.last_size
             ])
             .map_err(|e| DatabaseError::InsertError(e.to_string()))?;
         }
@@ -53,39 +67,83 @@ impl Database {
         Ok(())
     }
 
-    pub fn insert_batch(&mut self, ticks: &[Tick]) -> Result<()> {
+    /// Insert `ticks` in one transaction, ignoring any identical in every
+    /// field to one already stored. Returns the number of rows written.
+    pub fn insert_batch(&mut self, ticks: &[Tick]) -> Result<usize> {
         // Use transaction for batch insert performance
         let conn = self.connection_mut();
         let tx = conn
             .transaction()
             .map_err(|e| DatabaseError::InsertError(e.to_string()))?;
 
+        let mut written = 0;
         {
-            let sql =
-                "INSERT OR IGNORE INTO ticks (symbol, timestamp, bid, ask, bid_size, ask_size)
-                       VALUES (?, ?, ?, ?, ?, ?)";
+            let sql = "INSERT OR IGNORE INTO ticks
+                       (symbol, timestamp, bid, ask, bid_size, ask_size, last, last_size)
+                       VALUES (?, ?, ?, ?, ?, ?, ?, ?)";
 
             let mut stmt = tx
-                .prepare(sql)
+                .prepare_cached(sql)
                 .map_err(|e| DatabaseError::InsertError(e.to_string()))?;
 
             for tick in ticks {
-                stmt.execute(params![
-                    tick.symbol,
-                    tick.timestamp,
-                    tick.bid,
-                    tick.ask,
-                    tick.bid_size,
-                    tick.ask_size
-                ])
+                written += stmt
+                    .execute(params![
+                        tick.symbol,
+                        tick.timestamp,
+                        tick.bid,
+                        tick.ask,
+                        tick.bid_size,
+                        tick.ask_size,
+                        tick.last,
+                        tick.last_size
+                    ])
+                    .map_err(|e| DatabaseError::InsertError(e.to_string()))?;
+            }
+        }
+
+        tx.commit()
+            .map_err(|e| DatabaseError::InsertError(e.to_string()))?;
+
+        Ok(written)
+    }
+
+    /// Like `insert_batch`, but binds up to `TICKS_PER_STATEMENT` ticks per
+    /// multi-row `INSERT`, cutting the per-row statement overhead on large
+    /// imports. Duplicates are still ignored, everything commits in one
+    /// transaction and the number of rows written is returned.
+    pub fn insert_batch_chunked(&mut self, ticks: &[Tick]) -> Result<usize> {
+        let conn = self.connection_mut();
+        let tx = conn
+            .transaction()
+            .map_err(|e| DatabaseError::InsertError(e.to_string()))?;
+
+        let mut written = 0;
+        let mut chunks = ticks.chunks_exact(TICKS_PER_STATEMENT);
+        if chunks.len() > 0 {
+            let mut stmt = tx
+                .prepare_cached(&multi_row_tick_insert(TICKS_PER_STATEMENT))
                 .map_err(|e| DatabaseError::InsertError(e.to_string()))?;
+            for chunk in &mut chunks {
+                written += stmt
+                    .execute(tick_insert_values(chunk).as_slice())
+                    .map_err(|e| DatabaseError::InsertError(e.to_string()))?;
             }
         }
 
+        // The shorter tail statement is used once, so keep it out of the cache
+        let tail = chunks.remainder();
+        if !tail.is_empty() {
+            written += tx
+                .prepare(&multi_row_tick_insert(tail.len()))
+                .and_then(|mut stmt| stmt.execute(tick_insert_values(tail).as_slice()))
+                .map_err(|e| DatabaseError::InsertError(e.to_string()))?;
+        }
+
         tx.commit()
             .map_err(|e| DatabaseError::InsertError(e.to_string()))?;
 
-        Ok(())
+        Ok(written)
     }
 
     pub fn query_ticks(
@@ -94,30 +152,15 @@ impl Database {
         start: DateTime<Utc>,
         end: DateTime<Utc>,
     ) -> Result<Vec<Tick>> {
-        let sql = "SELECT id, symbol, timestamp, bid, ask, bid_size, ask_size
-                   FROM ticks
-                   WHERE symbol = ? AND timestamp >= ? AND timestamp <= ?
-                   ORDER BY timestamp";
-
-        let mut stmt = self
-            .connection()
-            .prepare(sql)
+        let conn = self.reader()?;
+        let mut stmt = conn
+            .prepare_cached(QUERY_TICKS_SQL)
             .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
 
         let ticks = stmt
             .query_map(
                 params![symbol, start.timestamp_millis(), end.timestamp_millis()],
-                |row| {
-                    Ok(Tick {
-                        id: row.get(0)?,
-                        symbol: row.get(1)?,
-                        timestamp: row.get(2)?,
-                        bid: row.get(3)?,
-                        ask: row.get(4)?,
-                        bid_size: row.get(5)?,
-                        ask_size: row.get(6)?,
-                    })
-                },
+                tick_from_row,
             )
             .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
 
@@ -129,9 +172,218 @@ impl Database {
         Ok(result)
     }
 
+    /// Query one page of ticks, pushing the limit and offset into SQL.
+    ///
+    /// An offset past the end yields an empty page.
+    pub fn query_ticks_paged(
+        &self,
+        symbol: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Page<Tick>> {
+        let range = params![symbol, start.timestamp_millis(), end.timestamp_millis()];
+
+        let total: i64 = self
+            .reader()?
+            .query_row(
+                "SELECT COUNT(*) FROM ticks
+                 WHERE symbol = ? AND timestamp >= ? AND timestamp <= ?",
+                range,
+                |row| row.get(0),
+            )
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+
+        let sql = "SELECT id, symbol, timestamp, bid, ask, bid_size, ask_size, last, last_size
+                   FROM ticks
+                   WHERE symbol = ? AND timestamp >= ? AND timestamp <= ?
+                   ORDER BY timestamp
+                   LIMIT ? OFFSET ?";
+
+        let conn = self.reader()?;
+        let mut stmt = conn
+            .prepare_cached(sql)
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+
+        let items = stmt
+            .query_map(
+                params![
+                    symbol,
+                    start.timestamp_millis(),
+                    end.timestamp_millis(),
+                    limit as i64,
+                    offset as i64
+                ],
+                tick_from_row,
+            )
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+
+        Ok(Page {
+            items,
+            total: total as usize,
+            offset,
+        })
+    }
+
+    /// Up to `limit` ticks with `after < timestamp <= end`, oldest first.
+    ///
+    /// Resume from the last returned timestamp to walk a range in chunks;
+    /// unlike `query_ticks_paged` the cost of a chunk does not grow with its
+    /// position in the range. Ticks sharing the last timestamp are never
+    /// split across chunks, so a chunk can run a few rows past `limit`.
+    pub fn query_ticks_after(
+        &self,
+        symbol: &str,
+        after: i64,
+        end: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Tick>> {
+        // Cut the chunk at the timestamp of its `limit`-th tick rather than
+        // at the row itself
+        let sql = "SELECT id, symbol, timestamp, bid, ask, bid_size, ask_size, last, last_size
+                   FROM ticks
+                   WHERE symbol = ?1 AND timestamp > ?2 AND timestamp <= COALESCE(
+                       (SELECT timestamp FROM ticks
+                        WHERE symbol = ?1 AND timestamp > ?2 AND timestamp <= ?3
+                        ORDER BY timestamp
+                        LIMIT 1 OFFSET ?4 - 1),
+                       ?3)
+                   ORDER BY timestamp, id";
+
+        let conn = self.reader()?;
+        let mut stmt = conn
+            .prepare_cached(sql)
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+
+        let ticks = stmt
+            .query_map(
+                params![symbol, after, end.timestamp_millis(), limit as i64],
+                tick_from_row,
+            )
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+
+        Ok(ticks)
+    }
+
+    /// The last tick of every `bucket_ms` bucket in the range, oldest first.
+    ///
+    /// Buckets are aligned to the epoch and grouped in SQL, so only one row
+    /// per bucket leaves the database. Buckets without ticks are omitted.
+    pub fn query_ticks_downsampled(
+        &self,
+        symbol: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        bucket_ms: i64,
+    ) -> Result<Vec<Tick>> {
+        if bucket_ms <= 0 {
+            return Err(DatabaseError::InvalidParameter(format!(
+                "bucket_ms must be positive, got {}",
+                bucket_ms
+            )));
+        }
+
+        // Ticks can share a timestamp, so break ties on insertion order
+        let sql = "SELECT id, symbol, timestamp, bid, ask, bid_size, ask_size, last, last_size
+                   FROM (
+                       SELECT *, ROW_NUMBER() OVER (
+                           PARTITION BY timestamp / ?4 ORDER BY timestamp DESC, id DESC
+                       ) AS rank
+                       FROM ticks
+                       WHERE symbol = ?1 AND timestamp >= ?2 AND timestamp <= ?3
+                   )
+                   WHERE rank = 1
+                   ORDER BY timestamp";
+
+        let conn = self.reader()?;
+        let mut stmt = conn
+            .prepare_cached(sql)
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+
+        let ticks = stmt
+            .query_map(
+                params![
+                    symbol,
+                    start.timestamp_millis(),
+                    end.timestamp_millis(),
+                    bucket_ms
+                ],
+                tick_from_row,
+            )
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+
+        Ok(ticks)
+    }
+
+    /// Spans between consecutive ticks longer than `expected_interval_ms`,
+    /// as `(last tick before, first tick after)` timestamps.
+    ///
+    /// Walks the range in one pass over a row cursor, so memory does not
+    /// grow with the number of ticks. `is_expected` can excuse a gap, e.g.
+    /// one that falls on a weekend or while the market is closed.
+    pub fn find_tick_gaps(
+        &self,
+        symbol: &str,
+        expected_interval_ms: i64,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        is_expected: Option<&dyn Fn(i64, i64) -> bool>,
+    ) -> Result<Vec<(i64, i64)>> {
+        if expected_interval_ms <= 0 {
+            return Err(DatabaseError::InvalidParameter(format!(
+                "expected_interval_ms must be positive, got {}",
+                expected_interval_ms
+            )));
+        }
+
+        let sql = "SELECT timestamp
+                   FROM ticks
+                   WHERE symbol = ? AND timestamp >= ? AND timestamp <= ?
+                   ORDER BY timestamp";
+
+        let conn = self.reader()?;
+        let mut stmt = conn
+            .prepare_cached(sql)
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+        let mut rows = stmt
+            .query(params![
+                symbol,
+                start.timestamp_millis(),
+                end.timestamp_millis()
+            ])
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+
+        let mut gaps = Vec::new();
+        let mut previous: Option<i64> = None;
+        while let Some(row) = rows
+            .next()
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?
+        {
+            let timestamp: i64 = row
+                .get(0)
+                .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+            if let Some(prev) = previous {
+                let excused = is_expected.is_some_and(|expected| expected(prev, timestamp));
+                if timestamp - prev > expected_interval_ms && !excused {
+                    gaps.push((prev, timestamp));
+                }
+            }
+            previous = Some(timestamp);
+        }
+
+        Ok(gaps)
+    }
+
     pub fn count_ticks(&self) -> Result<usize> {
         let count: i64 = self
-            .connection()
+            .reader()?
             .query_row("SELECT COUNT(*) FROM ticks", [], |row| row.get(0))
             .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
 
@@ -171,24 +423,25 @@ impl Database {
                     open, high, low, close, volume, tick_count)
                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
 
-        self.connection()
-            .execute(
-                sql,
-                params![
-                    bar.symbol,
-                    bar.timeframe.as_str(),
-                    bar.timestamp_start,
-                    bar.timestamp_end,
-                    bar.open,
-                    bar.high,
-                    bar.low,
-                    bar.close,
-                    bar.volume,
-                    bar.tick_count
-                ],
-            )
+        let conn = self.connection();
+        let mut stmt = conn
+            .prepare_cached(sql)
             .map_err(|e| DatabaseError::InsertError(e.to_string()))?;
 
+        stmt.execute(params![
+            bar.symbol,
+            bar.timeframe.as_str(),
+            bar.timestamp_start,
+            bar.timestamp_end,
+            bar.open,
+            bar.high,
+            bar.low,
+            bar.close,
+            bar.volume,
+            bar.tick_count
+        ])
+        .map_err(|e| DatabaseError::InsertError(e.to_string()))?;
+
         Ok(())
     }
 
@@ -205,7 +458,7 @@ impl Database {
                        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
 
             let mut stmt = tx
-                .prepare(sql)
+                .prepare_cached(sql)
                 .map_err(|e| DatabaseError::InsertError(e.to_string()))?;
 
             for bar in bars {
@@ -245,9 +498,9 @@ impl Database {
                    AND timestamp_start >= ? AND timestamp_start <= ?
                    ORDER BY timestamp_start";
 
-        let mut stmt = self
-            .connection()
-            .prepare(sql)
+        let conn = self.reader()?;
+        let mut stmt = conn
+            .prepare_cached(sql)
             .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
 
         let bars = stmt
@@ -258,30 +511,7 @@ impl Database {
                     start.timestamp_millis(),
                     end.timestamp_millis()
                 ],
-                |row| {
-                    let timeframe_str: String = row.get(2)?;
-                    let timeframe = Timeframe::from_str(&timeframe_str).map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            2,
-                            rusqlite::types::Type::Text,
-                            Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
-                        )
-                    })?;
-
-                    Ok(Bar {
-                        id: row.get(0)?,
-                        symbol: row.get(1)?,
-                        timeframe,
-                        timestamp_start: row.get(3)?,
-                        timestamp_end: row.get(4)?,
-                        open: row.get(5)?,
-                        high: row.get(6)?,
-                        low: row.get(7)?,
-                        close: row.get(8)?,
-                        volume: row.get(9)?,
-                        tick_count: row.get(10)?,
-                    })
-                },
+                bar_from_row,
             )
             .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
 
@@ -293,6 +523,142 @@ impl Database {
         Ok(result)
     }
 
+    /// Up to `limit` bars with `after < timestamp_start <= end`, oldest
+    /// first. The bar counterpart of `query_ticks_after`.
+    pub fn query_bars_after(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        after: i64,
+        end: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Bar>> {
+        let sql = "SELECT id, symbol, timeframe, timestamp_start, timestamp_end,
+                   open, high, low, close, volume, tick_count
+                   FROM bars
+                   WHERE symbol = ? AND timeframe = ?
+                   AND timestamp_start > ? AND timestamp_start <= ?
+                   ORDER BY timestamp_start
+                   LIMIT ?";
+
+        let conn = self.reader()?;
+        let mut stmt = conn
+            .prepare_cached(sql)
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+
+        let bars = stmt
+            .query_map(
+                params![
+                    symbol,
+                    timeframe.as_str(),
+                    after,
+                    end.timestamp_millis(),
+                    limit as i64
+                ],
+                bar_from_row,
+            )
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+
+        Ok(bars)
+    }
+
+    /// Query one page of bars, pushing the limit and offset into SQL.
+    ///
+    /// An offset past the end yields an empty page.
+    pub fn query_bars_paged(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Page<Bar>> {
+        let total: i64 = self
+            .reader()?
+            .query_row(
+                "SELECT COUNT(*) FROM bars
+                 WHERE symbol = ? AND timeframe = ?
+                 AND timestamp_start >= ? AND timestamp_start <= ?",
+                params![
+                    symbol,
+                    timeframe.as_str(),
+                    start.timestamp_millis(),
+                    end.timestamp_millis()
+                ],
+                |row| row.get(0),
+            )
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+
+        let sql = "SELECT id, symbol, timeframe, timestamp_start, timestamp_end,
+                   open, high, low, close, volume, tick_count
+                   FROM bars
+                   WHERE symbol = ? AND timeframe = ?
+                   AND timestamp_start >= ? AND timestamp_start <= ?
+                   ORDER BY timestamp_start
+                   LIMIT ? OFFSET ?";
+
+        let conn = self.reader()?;
+        let mut stmt = conn
+            .prepare_cached(sql)
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+
+        let items = stmt
+            .query_map(
+                params![
+                    symbol,
+                    timeframe.as_str(),
+                    start.timestamp_millis(),
+                    end.timestamp_millis(),
+                    limit as i64,
+                    offset as i64
+                ],
+                bar_from_row,
+            )
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+
+        Ok(Page {
+            items,
+            total: total as usize,
+            offset,
+        })
+    }
+
+    /// Lowest low and highest high of the bars in range, or `None` when
+    /// there are none
+    pub fn bar_price_range(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Option<(f64, f64)>> {
+        let range: (Option<f64>, Option<f64>) = self
+            .reader()?
+            .query_row(
+                "SELECT MIN(low), MAX(high) FROM bars
+                 WHERE symbol = ? AND timeframe = ?
+                 AND timestamp_start >= ? AND timestamp_start <= ?",
+                params![
+                    symbol,
+                    timeframe.as_str(),
+                    start.timestamp_millis(),
+                    end.timestamp_millis()
+                ],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+
+        Ok(match range {
+            (Some(low), Some(high)) => Some((low, high)),
+            _ => None,
+        })
+    }
+
     pub fn get_latest_bar(&self, symbol: &str, timeframe: Timeframe) -> Result<Option<Bar>> {
         let sql = "SELECT id, symbol, timeframe, timestamp_start, timestamp_end,
                    open, high, low, close, volume, tick_count
@@ -301,9 +667,9 @@ impl Database {
                    ORDER BY timestamp_start DESC
                    LIMIT 1";
 
-        let mut stmt = self
-            .connection()
-            .prepare(sql)
+        let conn = self.reader()?;
+        let mut stmt = conn
+            .prepare_cached(sql)
             .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
 
         let mut bars = stmt
@@ -359,7 +725,7 @@ impl Database {
 
     pub fn count_bars(&self) -> Result<usize> {
         let count: i64 = self
-            .connection()
+            .reader()?
             .query_row("SELECT COUNT(*) FROM bars", [], |row| row.get(0))
             .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
 
@@ -367,10 +733,89 @@ impl Database {
     }
 }
 
+/// SQLite's default cap on bound parameters per statement
+const SQLITE_MAX_VARIABLES: usize = 999;
+
+const TICK_INSERT_COLUMNS: usize = 8;
+
+/// Largest number of ticks one multi-row insert can bind
+pub const TICKS_PER_STATEMENT: usize = SQLITE_MAX_VARIABLES / TICK_INSERT_COLUMNS;
+
+const _: () = assert!(TICKS_PER_STATEMENT * TICK_INSERT_COLUMNS <= SQLITE_MAX_VARIABLES);
+
+fn multi_row_tick_insert(rows: usize) -> String {
+    let placeholders = vec!["(?, ?, ?, ?, ?, ?, ?, ?)"; rows].join(", ");
+    format!(
+        "INSERT OR IGNORE INTO ticks
+         (symbol, timestamp, bid, ask, bid_size, ask_size, last, last_size)
+         VALUES {}",
+        placeholders
+    )
+}
+
+/// Parameters for `multi_row_tick_insert(ticks.len())`, row by row
+fn tick_insert_values(ticks: &[Tick]) -> Vec<&dyn ToSql> {
+    ticks
+        .iter()
+        .flat_map(|tick| -> [&dyn ToSql; TICK_INSERT_COLUMNS] {
+            [
+                &tick.symbol,
+                &tick.timestamp,
+                &tick.bid,
+                &tick.ask,
+                &tick.bid_size,
+                &tick.ask_size,
+                &tick.last,
+                &tick.last_size,
+            ]
+        })
+        .collect()
+}
+
+pub(crate) fn tick_from_row(row: &rusqlite::Row) -> rusqlite::Result<Tick> {
+    Ok(Tick {
+        id: row.get(0)?,
+        symbol: row.get(1)?,
+        timestamp: row.get(2)?,
+        bid: row.get(3)?,
+        ask: row.get(4)?,
+        bid_size: row.get(5)?,
+        ask_size: row.get(6)?,
+        last: row.get(7)?,
+        last_size: row.get(8)?,
+    })
+}
+
+pub(crate) fn bar_from_row(row: &rusqlite::Row) -> rusqlite::Result<Bar> {
+    let timeframe_str: String = row.get(2)?;
+    let timeframe = Timeframe::from_str(&timeframe_str).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(
+            2,
+            rusqlite::types::Type::Text,
+            Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+        )
+    })?;
+
+    Ok(Bar {
+        id: row.get(0)?,
+        symbol: row.get(1)?,
+        timeframe,
+        timestamp_start: row.get(3)?,
+        timestamp_end: row.get(4)?,
+        open: row.get(5)?,
+        high: row.get(6)?,
+        low: row.get(7)?,
+        close: row.get(8)?,
+        volume: row.get(9)?,
+        tick_count: row.get(10)?,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use chrono::Duration;
+    use rusqlite::StatementStatus;
 
     fn create_test_tick(symbol: &str, offset_secs: i64) -> Tick {
         let timestamp = Utc::now() + Duration::seconds(offset_secs);
@@ -390,6 +835,74 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_last_trade_round_trips() -> Result<()> {
+        let mut db = Database::new_memory()?;
+        let now = Utc::now();
+        let traded = Tick::new("ES".to_string(), now, 4800.0, 4800.5).with_last(4800.25, Some(3));
+        db.insert_tick(&traded)?;
+        db.insert_tick(&Tick::new(
+            "ES".to_string(),
+            now + Duration::seconds(1),
+            4800.0,
+            4800.5,
+        ))?;
+        db.insert_batch_chunked(&[Tick::new(
+            "ES".to_string(),
+            now + Duration::seconds(2),
+            4800.0,
+            4800.5,
+        )
+        .with_last(4800.5, None)])?;
+
+        let ticks = db.query_ticks("ES", now, now + Duration::seconds(2))?;
+        assert_eq!(
+            ticks
+                .iter()
+                .map(|t| (t.last, t.last_size))
+                .collect::<Vec<_>>(),
+            vec![(Some(4800.25), Some(3)), (None, None), (Some(4800.5), None)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_cached_statements_reused_across_calls() -> Result<()> {
+        let db = Database::new_memory()?;
+        let now = Utc::now();
+        for i in 0..100 {
+            db.insert_tick(&Tick::new(
+                "EURUSD".to_string(),
+                now + Duration::seconds(i),
+                1.0920,
+                1.0922,
+            ))?;
+        }
+
+        let (start, end) = (now, now + Duration::seconds(49));
+        let first = db.query_ticks("EURUSD", start, end)?;
+        let second = db.query_ticks("EURUSD", start, end)?;
+        assert_eq!(first.len(), 50);
+        assert_eq!(
+            first.iter().map(|t| t.timestamp).collect::<Vec<_>>(),
+            second.iter().map(|t| t.timestamp).collect::<Vec<_>>()
+        );
+        assert_eq!(db.count_ticks()?, 100);
+
+        // A statement's run counter survives the cache, so these counts
+        // only add up if every call above reused the same statement
+        let conn = db.connection();
+        let runs = |sql| {
+            conn.prepare_cached(sql)
+                .unwrap()
+                .get_status(StatementStatus::Run)
+        };
+        assert_eq!(runs(INSERT_TICK_SQL), 100);
+        assert_eq!(runs(QUERY_TICKS_SQL), 2);
+
+        Ok(())
+    }
+
     #[test]
     fn test_insert_batch_ticks() -> Result<()> {
         let db = Database::new_memory()?;
@@ -407,6 +920,56 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_multi_row_insert_stays_under_bind_limit() {
+        assert_eq!(TICKS_PER_STATEMENT, 124);
+        assert_eq!(
+            multi_row_tick_insert(3).matches('?').count(),
+            3 * TICK_INSERT_COLUMNS
+        );
+    }
+
+    #[test]
+    fn test_insert_batch_chunked_matches_insert_batch() -> Result<()> {
+        let base = 1_700_000_000_000;
+        // Two full statements plus a tail, with duplicates across chunks
+        let mut ticks: Vec<Tick> = (0..(2 * TICKS_PER_STATEMENT + 17) as i64)
+            .map(|i| {
+                Tick::new_with_millis(
+                    "EURUSD".to_string(),
+                    base + i * 1_000,
+                    1.0920 + i as f64 * 1e-5,
+                    1.0922 + i as f64 * 1e-5,
+                )
+                .with_sizes(1_000_000, 500_000)
+            })
+            .collect();
+        ticks.push(ticks[3].clone());
+        ticks.push(ticks[TICKS_PER_STATEMENT + 5].clone());
+
+        let mut per_row = Database::new_memory()?;
+        assert_eq!(per_row.insert_batch(&ticks)?, ticks.len() - 2);
+        let mut chunked = Database::new_memory()?;
+        assert_eq!(chunked.insert_batch_chunked(&ticks)?, ticks.len() - 2);
+
+        let start = DateTime::from_timestamp_millis(base).unwrap();
+        let end = DateTime::from_timestamp_millis(base + 1_000_000).unwrap();
+        let expected = per_row.query_ticks("EURUSD", start, end)?;
+        let actual = chunked.query_ticks("EURUSD", start, end)?;
+        assert_eq!(actual.len(), 2 * TICKS_PER_STATEMENT + 17);
+        for (a, e) in actual.iter().zip(&expected) {
+            assert_eq!(
+                (a.timestamp, a.bid, a.ask, a.bid_size, a.ask_size),
+                (e.timestamp, e.bid, e.ask, e.bid_size, e.ask_size)
+            );
+        }
+
+        assert_eq!(chunked.insert_batch_chunked(&[])?, 0);
+        assert_eq!(chunked.count_ticks()?, actual.len());
+
+        Ok(())
+    }
+
     #[test]
     fn test_query_ticks_by_symbol_and_time() -> Result<()> {
         let db = Database::new_memory()?;
@@ -439,6 +1002,227 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_pooled_concurrent_queries() -> Result<()> {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut db = Database::new_file_pooled(&temp_dir.path().join("pooled.db"), 4)?;
+
+        let now = Utc::now();
+        let ticks: Vec<Tick> = (0..200)
+            .map(|i| {
+                Tick::new(
+                    "EURUSD".to_string(),
+                    now + Duration::seconds(i),
+                    1.0920,
+                    1.0922,
+                )
+            })
+            .collect();
+        db.insert_batch(&ticks)?;
+
+        let (start, end) = (now - Duration::hours(1), now + Duration::hours(1));
+        let expected: Vec<i64> = db
+            .query_ticks("EURUSD", start, end)?
+            .iter()
+            .map(|t| t.timestamp)
+            .collect();
+        assert_eq!(expected.len(), 200);
+
+        let db = &db;
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| scope.spawn(move || db.query_ticks("EURUSD", start, end)))
+                .collect();
+            for handle in handles {
+                let queried: Vec<i64> = handle
+                    .join()
+                    .unwrap()
+                    .unwrap()
+                    .iter()
+                    .map(|t| t.timestamp)
+                    .collect();
+                assert_eq!(queried, expected);
+            }
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_ticks_paged() -> Result<()> {
+        let db = Database::new_memory()?;
+        let now = Utc::now();
+        let ticks: Vec<Tick> = (0..10)
+            .map(|i| {
+                Tick::new(
+                    "EURUSD".to_string(),
+                    now - Duration::seconds(10 - i),
+                    1.0920 + i as f64 * 0.0001,
+                    1.0922 + i as f64 * 0.0001,
+                )
+            })
+            .collect();
+        db.insert_ticks(&ticks)?;
+
+        let start = now - Duration::hours(1);
+        let page = db.query_ticks_paged("EURUSD", start, now, 3, 4)?;
+        assert_eq!(page.total, 10);
+        assert_eq!(page.items.len(), 3);
+        assert_eq!(page.items[0].timestamp, ticks[4].timestamp);
+        assert_eq!(page.items[2].timestamp, ticks[6].timestamp);
+        assert!(page.has_more());
+
+        let last = db.query_ticks_paged("EURUSD", start, now, 5, 8)?;
+        assert_eq!(last.items.len(), 2);
+        assert!(!last.has_more());
+
+        // Offset past the end is an empty page, not an error
+        let beyond = db.query_ticks_paged("EURUSD", start, now, 5, 50)?;
+        assert!(beyond.items.is_empty());
+        assert_eq!(beyond.total, 10);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_ticks_after_walks_range_in_chunks() -> Result<()> {
+        let db = Database::new_memory()?;
+        let now = Utc::now();
+        let ticks: Vec<Tick> = (0..7)
+            .map(|i| {
+                Tick::new(
+                    "EURUSD".to_string(),
+                    now - Duration::seconds(7 - i),
+                    1.0920,
+                    1.0922,
+                )
+            })
+            .collect();
+        db.insert_ticks(&ticks)?;
+
+        let mut after = (now - Duration::hours(1)).timestamp_millis();
+        let mut chunks = Vec::new();
+        loop {
+            let chunk = db.query_ticks_after("EURUSD", after, now, 3)?;
+            let Some(last) = chunk.last() else { break };
+            after = last.timestamp;
+            chunks.push(chunk.len());
+        }
+
+        assert_eq!(chunks, vec![3, 3, 1]);
+        assert_eq!(after, ticks[6].timestamp);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_ticks_after_keeps_shared_timestamps_together() -> Result<()> {
+        let db = Database::new_memory()?;
+        let now = Utc::now();
+        let ticks: Vec<Tick> = [0, 1, 1, 1, 2]
+            .iter()
+            .enumerate()
+            .map(|(i, &secs)| {
+                Tick::new(
+                    "EURUSD".to_string(),
+                    now - Duration::seconds(3 - secs),
+                    1.0920 + i as f64 * 0.0001,
+                    1.0922 + i as f64 * 0.0001,
+                )
+            })
+            .collect();
+        db.insert_ticks(&ticks)?;
+
+        let after = (now - Duration::hours(1)).timestamp_millis();
+        let chunk = db.query_ticks_after("EURUSD", after, now, 2)?;
+        let bids: Vec<f64> = chunk.iter().map(|t| t.bid).collect();
+        assert_eq!(
+            bids,
+            vec![ticks[0].bid, ticks[1].bid, ticks[2].bid, ticks[3].bid]
+        );
+
+        let rest = db.query_ticks_after("EURUSD", chunk[3].timestamp, now, 2)?;
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].bid, ticks[4].bid);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_ticks_downsampled_keeps_last_tick_per_bucket() -> Result<()> {
+        let db = Database::new_memory()?;
+        let base = 1_700_000_000_000;
+        // Second bucket is empty
+        let offsets = [0, 300, 900, 2_100, 2_500, 3_000];
+        let ticks: Vec<Tick> = offsets
+            .iter()
+            .enumerate()
+            .map(|(i, &offset)| {
+                let bid = 1.0920 + i as f64 * 0.0001;
+                Tick::new_with_millis("EURUSD".to_string(), base + offset, bid, bid + 0.0002)
+            })
+            .collect();
+        db.insert_ticks(&ticks)?;
+        db.insert_tick(&Tick::new_with_millis(
+            "GBPUSD".to_string(),
+            base + 1_500,
+            1.25,
+            1.2502,
+        ))?;
+
+        let start = DateTime::from_timestamp_millis(base).unwrap();
+        let end = DateTime::from_timestamp_millis(base + 10_000).unwrap();
+        let sampled = db.query_ticks_downsampled("EURUSD", start, end, 1_000)?;
+
+        let timestamps: Vec<i64> = sampled.iter().map(|t| t.timestamp - base).collect();
+        assert_eq!(timestamps, vec![900, 2_500, 3_000]);
+        assert!((sampled[0].bid - ticks[2].bid).abs() < 1e-12);
+        assert!(sampled.iter().all(|t| t.symbol == "EURUSD"));
+
+        assert!(matches!(
+            db.query_ticks_downsampled("EURUSD", start, end, 0),
+            Err(DatabaseError::InvalidParameter(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_tick_gaps() -> Result<()> {
+        let db = Database::new_memory()?;
+        let base = 1_700_000_000_000;
+        let offsets = [0, 1_000, 2_000, 10_000, 11_000, 30_000, 31_000];
+        let ticks: Vec<Tick> = offsets
+            .iter()
+            .map(|&offset| {
+                Tick::new_with_millis("EURUSD".to_string(), base + offset, 1.0920, 1.0922)
+            })
+            .collect();
+        db.insert_ticks(&ticks)?;
+
+        let start = DateTime::from_timestamp_millis(base).unwrap();
+        let end = DateTime::from_timestamp_millis(base + 60_000).unwrap();
+        let gaps = db.find_tick_gaps("EURUSD", 5_000, start, end, None)?;
+        assert_eq!(
+            gaps,
+            vec![
+                (base + 2_000, base + 10_000),
+                (base + 11_000, base + 30_000)
+            ]
+        );
+
+        // Excuse the longer gap, as a closed session would
+        let closed = |from: i64, to: i64| to - from > 15_000;
+        let gaps = db.find_tick_gaps("EURUSD", 5_000, start, end, Some(&closed))?;
+        assert_eq!(gaps, vec![(base + 2_000, base + 10_000)]);
+
+        assert!(db
+            .find_tick_gaps("GBPUSD", 5_000, start, end, None)?
+            .is_empty());
+
+        Ok(())
+    }
+
     #[test]
     fn test_delete_ticks_by_symbol() -> Result<()> {
         let db = Database::new_memory()?;
@@ -612,6 +1396,84 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_query_bars_paged() -> Result<()> {
+        let mut db = Database::new_memory()?;
+        let base_time = 1704067200000; // 2024-01-01 00:00:00
+        let bars: Vec<Bar> = (0..5)
+            .map(|i| {
+                let open_time = base_time + i * 60000;
+                Bar::new(
+                    "EURUSD".to_string(),
+                    Timeframe::M1,
+                    open_time,
+                    open_time + 60000,
+                    1.0920,
+                    1.0925,
+                    1.0918,
+                    1.0923,
+                )
+            })
+            .collect();
+        db.batch_insert_bars(&bars)?;
+
+        let start = DateTime::from_timestamp_millis(base_time).unwrap();
+        let end = DateTime::from_timestamp_millis(base_time + 600000).unwrap();
+
+        let page = db.query_bars_paged("EURUSD", Timeframe::M1, start, end, 2, 2)?;
+        assert_eq!(page.total, 5);
+        assert_eq!(page.offset, 2);
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.items[0].timestamp_start, base_time + 120000);
+
+        let other = db.query_bars_paged("EURUSD", Timeframe::M5, start, end, 2, 0)?;
+        assert_eq!(other.total, 0);
+        assert!(other.items.is_empty());
+
+        let beyond = db.query_bars_paged("EURUSD", Timeframe::M1, start, end, 2, 5)?;
+        assert!(beyond.items.is_empty());
+        assert!(!beyond.has_more());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bar_price_range() -> Result<()> {
+        let mut db = Database::new_memory()?;
+        let base_time = 1704067200000;
+        let bars: Vec<Bar> = [(1.0925, 1.0918), (1.0931, 1.0921), (1.0927, 1.0910)]
+            .iter()
+            .enumerate()
+            .map(|(i, &(high, low))| {
+                let open_time = base_time + i as i64 * 60000;
+                Bar::new(
+                    "EURUSD".to_string(),
+                    Timeframe::M1,
+                    open_time,
+                    open_time + 60000,
+                    1.0920,
+                    high,
+                    low,
+                    1.0922,
+                )
+            })
+            .collect();
+        db.batch_insert_bars(&bars)?;
+
+        let start = DateTime::from_timestamp_millis(base_time).unwrap();
+        let end = DateTime::from_timestamp_millis(base_time + 60000).unwrap();
+        assert_eq!(
+            db.bar_price_range("EURUSD", Timeframe::M1, start, end)?,
+            Some((1.0918, 1.0931))
+        );
+        assert_eq!(
+            db.bar_price_range("EURUSD", Timeframe::M5, start, end)?,
+            None
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_get_latest_bar() -> Result<()> {
         let mut db = Database::new_memory()?;
@@ -0,0 +1,126 @@
+use super::error::{DatabaseError, Result};
+use rusqlite::Connection;
+use std::time::Duration;
+
+/// Long enough for a second process to wait out a typical batch commit
+pub const DEFAULT_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Negative sizes are in KiB, so this is a 64 MiB page cache
+pub const DEFAULT_CACHE_SIZE: i64 = -64_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JournalMode {
+    Delete,
+    Truncate,
+    Persist,
+    Memory,
+    /// Readers never block the writer and a crash never leaves a
+    /// rollback journal holding the lock
+    #[default]
+    Wal,
+    Off,
+}
+
+impl JournalMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JournalMode::Delete => "DELETE",
+            JournalMode::Truncate => "TRUNCATE",
+            JournalMode::Persist => "PERSIST",
+            JournalMode::Memory => "MEMORY",
+            JournalMode::Wal => "WAL",
+            JournalMode::Off => "OFF",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Synchronous {
+    Off,
+    /// Safe against corruption in WAL mode; only the last commits can be
+    /// lost on power failure
+    #[default]
+    Normal,
+    Full,
+    Extra,
+}
+
+impl Synchronous {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Synchronous::Off => "OFF",
+            Synchronous::Normal => "NORMAL",
+            Synchronous::Full => "FULL",
+            Synchronous::Extra => "EXTRA",
+        }
+    }
+}
+
+/// Pragmas applied to a file database right after it is opened
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatabaseOptions {
+    pub journal_mode: JournalMode,
+    pub synchronous: Synchronous,
+    /// Pages when positive, KiB when negative, as in `PRAGMA cache_size`
+    pub cache_size: i64,
+    /// How long to wait on a lock held by another connection before
+    /// failing with "database is locked"
+    pub busy_timeout: Duration,
+}
+
+impl Default for DatabaseOptions {
+    fn default() -> Self {
+        Self {
+            journal_mode: JournalMode::default(),
+            synchronous: Synchronous::default(),
+            cache_size: DEFAULT_CACHE_SIZE,
+            busy_timeout: DEFAULT_BUSY_TIMEOUT,
+        }
+    }
+}
+
+impl DatabaseOptions {
+    pub fn with_journal_mode(mut self, journal_mode: JournalMode) -> Self {
+        self.journal_mode = journal_mode;
+        self
+    }
+
+    pub fn with_synchronous(mut self, synchronous: Synchronous) -> Self {
+        self.synchronous = synchronous;
+        self
+    }
+
+    pub fn with_cache_size(mut self, cache_size: i64) -> Self {
+        self.cache_size = cache_size;
+        self
+    }
+
+    pub fn with_busy_timeout(mut self, busy_timeout: Duration) -> Self {
+        self.busy_timeout = busy_timeout;
+        self
+    }
+
+    /// The busy timeout goes first so the journal mode switch, which needs
+    /// an exclusive lock, waits out other processes too
+    pub(crate) fn apply(&self, conn: &Connection) -> Result<()> {
+        let init_err = |e: rusqlite::Error| DatabaseError::InitializationError(e.to_string());
+
+        conn.busy_timeout(self.busy_timeout).map_err(init_err)?;
+        // Reports the resulting mode rather than failing when the mode is
+        // unavailable, so read the row back instead of using execute
+        conn.query_row(
+            &format!("PRAGMA journal_mode = {}", self.journal_mode.as_str()),
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .map_err(init_err)?;
+        conn.execute_batch(&format!(
+            "PRAGMA synchronous = {}; PRAGMA cache_size = {};",
+            self.synchronous.as_str(),
+            self.cache_size
+        ))
+        .map_err(init_err)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,15 @@
+/// One page of query results plus the size of the full result set
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Number of rows matching the query, ignoring limit and offset
+    pub total: usize,
+    pub offset: usize,
+}
+
+impl<T> Page<T> {
+    /// Whether rows remain after this page
+    pub fn has_more(&self) -> bool {
+        self.offset + self.items.len() < self.total
+    }
+}
@@ -1,7 +1,8 @@
 use super::error::Result;
+use crate::migration::{run_pending, MIGRATIONS};
 use rusqlite::Connection;
 
-const TICK_TABLE_SCHEMA: &str = r#"
+pub(crate) const TICK_TABLE_SCHEMA: &str = r#"
 CREATE TABLE IF NOT EXISTS ticks (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     symbol TEXT NOT NULL,
@@ -13,12 +14,23 @@ CREATE TABLE IF NOT EXISTS ticks (
     UNIQUE(symbol, timestamp)
 )"#;
 
-const TICK_INDEX_SCHEMA: &str = r#"
+pub(crate) const TICK_INDEX_SCHEMA: &str = r#"
 CREATE INDEX IF NOT EXISTS idx_ticks_timestamp
 ON ticks(timestamp)
 "#;
 
-const BAR_TABLE_SCHEMA: &str = r#"
+/// Ticks may share a timestamp; only a row identical in every field is a
+/// duplicate. NULLs never compare equal in a unique index, so the optional
+/// columns are folded to a sentinel.
+pub(crate) const TICK_IDENTITY_INDEX_SCHEMA: &str = r#"
+CREATE UNIQUE INDEX IF NOT EXISTS idx_ticks_identity
+ON ticks(
+    symbol, timestamp, bid, ask,
+    IFNULL(bid_size, -1), IFNULL(ask_size, -1), IFNULL(last, -1), IFNULL(last_size, -1)
+)
+"#;
+
+pub(crate) const BAR_TABLE_SCHEMA: &str = r#"
 CREATE TABLE IF NOT EXISTS bars (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     symbol TEXT NOT NULL,
@@ -35,40 +47,21 @@ CREATE TABLE IF NOT EXISTS bars (
     UNIQUE(symbol, timeframe, timestamp_start)
 )"#;
 
-const BAR_INDEX_SCHEMA: &str = r#"
+pub(crate) const BAR_INDEX_SCHEMA: &str = r#"
 CREATE INDEX IF NOT EXISTS idx_bars_symbol_timeframe_timestamp
 ON bars(symbol, timeframe, timestamp_start DESC)
 "#;
 
-const VERSION_TABLE_SCHEMA: &str = r#"
+pub(crate) const VERSION_TABLE_SCHEMA: &str = r#"
 CREATE TABLE IF NOT EXISTS db_version (
     version INTEGER PRIMARY KEY,
     migrated_at INTEGER DEFAULT (strftime('%s', 'now') * 1000)
 )"#;
 
+/// Bring a connection's schema up to date, refusing files from a newer
+/// build
 pub fn initialize_schema(conn: &Connection) -> Result<()> {
-    // Create version table first
-    conn.execute(VERSION_TABLE_SCHEMA, [])?;
-
-    // Check current version
-    let current_version: Option<i32> = conn
-        .query_row("SELECT MAX(version) FROM db_version", [], |row| row.get(0))
-        .unwrap_or(None);
-
-    // Create tick tables (version 1)
-    if current_version.is_none() || current_version.unwrap() < 1 {
-        conn.execute(TICK_TABLE_SCHEMA, [])?;
-        conn.execute(TICK_INDEX_SCHEMA, [])?;
-        conn.execute("INSERT OR IGNORE INTO db_version (version) VALUES (1)", [])?;
-    }
-
-    // Create bar tables (version 2)
-    if current_version.is_none() || current_version.unwrap() < 2 {
-        conn.execute(BAR_TABLE_SCHEMA, [])?;
-        conn.execute(BAR_INDEX_SCHEMA, [])?;
-        conn.execute("INSERT OR IGNORE INTO db_version (version) VALUES (2)", [])?;
-    }
-
+    run_pending(conn, MIGRATIONS)?;
     Ok(())
 }
 
@@ -101,7 +94,7 @@ mod tests {
         // Check version table exists and has correct version
         let version: i32 =
             conn.query_row("SELECT MAX(version) FROM db_version", [], |row| row.get(0))?;
-        assert_eq!(version, 2);
+        assert_eq!(version, 4);
 
         Ok(())
     }
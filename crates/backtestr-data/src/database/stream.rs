@@ -0,0 +1,203 @@
+use super::connection::Database;
+use super::error::Result;
+use crate::models::{Bar, Tick};
+use crate::timeframe::Timeframe;
+use chrono::{DateTime, Utc};
+
+/// Rows fetched per round trip by `stream_ticks` and `stream_bars`
+pub const STREAM_CHUNK_SIZE: usize = 10_000;
+
+/// Iterator that walks a range in keyset-paged chunks.
+///
+/// Each chunk is a short query resuming after the last key seen, so the
+/// connection is only held while a chunk is fetched and memory stays
+/// bounded by the chunk size however long the range is.
+struct ChunkedStream<'a, T> {
+    fetch: Box<dyn FnMut(i64) -> Result<Vec<T>> + 'a>,
+    key: fn(&T) -> i64,
+    chunk_size: usize,
+    buffer: std::vec::IntoIter<T>,
+    after: i64,
+    done: bool,
+}
+
+impl<T> Iterator for ChunkedStream<'_, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(row) = self.buffer.next() {
+            return Some(Ok(row));
+        }
+        if self.done {
+            return None;
+        }
+
+        match (self.fetch)(self.after) {
+            Ok(rows) => {
+                // A short chunk means the range is exhausted
+                self.done = rows.len() < self.chunk_size;
+                self.after = rows.last().map_or(self.after, self.key);
+                self.buffer = rows.into_iter();
+                self.buffer.next().map(Ok)
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl Database {
+    /// Ticks with `start <= timestamp <= end`, oldest first, fetched lazily
+    /// in chunks of `STREAM_CHUNK_SIZE`.
+    ///
+    /// Ticks written past the current position while iterating are picked
+    /// up by later chunks. A failed fetch is yielded once and ends the
+    /// stream.
+    pub fn stream_ticks<'a>(
+        &'a self,
+        symbol: &'a str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> impl Iterator<Item = Result<Tick>> + 'a {
+        self.stream_ticks_chunked(symbol, start, end, STREAM_CHUNK_SIZE)
+    }
+
+    /// Bars with `start <= timestamp_start <= end`, oldest first. The bar
+    /// counterpart of `stream_ticks`.
+    pub fn stream_bars<'a>(
+        &'a self,
+        symbol: &'a str,
+        timeframe: Timeframe,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> impl Iterator<Item = Result<Bar>> + 'a {
+        self.stream_bars_chunked(symbol, timeframe, start, end, STREAM_CHUNK_SIZE)
+    }
+
+    fn stream_ticks_chunked<'a>(
+        &'a self,
+        symbol: &'a str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        chunk_size: usize,
+    ) -> impl Iterator<Item = Result<Tick>> + 'a {
+        ChunkedStream {
+            fetch: Box::new(move |after| self.query_ticks_after(symbol, after, end, chunk_size)),
+            key: |tick: &Tick| tick.timestamp,
+            chunk_size,
+            buffer: Vec::new().into_iter(),
+            after: start.timestamp_millis() - 1,
+            done: false,
+        }
+    }
+
+    fn stream_bars_chunked<'a>(
+        &'a self,
+        symbol: &'a str,
+        timeframe: Timeframe,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        chunk_size: usize,
+    ) -> impl Iterator<Item = Result<Bar>> + 'a {
+        ChunkedStream {
+            fetch: Box::new(move |after| {
+                self.query_bars_after(symbol, timeframe, after, end, chunk_size)
+            }),
+            key: |bar: &Bar| bar.timestamp_start,
+            chunk_size,
+            buffer: Vec::new().into_iter(),
+            after: start.timestamp_millis() - 1,
+            done: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const T0: i64 = 1704186000000;
+
+    fn at(millis: i64) -> DateTime<Utc> {
+        Utc.timestamp_millis_opt(millis).unwrap()
+    }
+
+    #[test]
+    fn test_stream_ticks_matches_query() -> Result<()> {
+        let db = Database::new_memory()?;
+        let ticks: Vec<Tick> = (0..25)
+            .map(|i| Tick::new_with_millis("EURUSD".to_string(), T0 + i * 1000, 1.1, 1.1002))
+            .collect();
+        db.insert_ticks(&ticks)?;
+
+        let (start, end) = (at(T0), at(T0 + 19_000));
+        let streamed = db
+            .stream_ticks_chunked("EURUSD", start, end, 7)
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(streamed, db.query_ticks("EURUSD", start, end)?);
+        assert_eq!(streamed.len(), 20);
+
+        // An exact multiple of the chunk size ends on an empty fetch
+        let exact = db.stream_ticks_chunked("EURUSD", start, end, 5).count();
+        assert_eq!(exact, 20);
+
+        assert_eq!(db.stream_ticks("GBPUSD", start, end).count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stream_bars_matches_query() -> Result<()> {
+        let mut db = Database::new_memory()?;
+        let bars: Vec<Bar> = (0..12)
+            .map(|i| {
+                let start = T0 + i * 60_000;
+                Bar::new(
+                    "EURUSD".to_string(),
+                    Timeframe::M1,
+                    start,
+                    start + 60_000,
+                    1.1,
+                    1.1005,
+                    1.0995,
+                    1.1002,
+                )
+            })
+            .collect();
+        db.batch_insert_bars(&bars)?;
+
+        let (start, end) = (at(T0 + 60_000), at(T0 + 11 * 60_000));
+        let streamed = db
+            .stream_bars_chunked("EURUSD", Timeframe::M1, start, end, 4)
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(
+            streamed,
+            db.query_bars("EURUSD", Timeframe::M1, start, end)?
+        );
+        assert_eq!(streamed.len(), 11);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stream_releases_connection_between_chunks() -> Result<()> {
+        let db = Database::new_memory()?;
+        let ticks: Vec<Tick> = (0..10)
+            .map(|i| Tick::new_with_millis("EURUSD".to_string(), T0 + i * 1000, 1.1, 1.1002))
+            .collect();
+        db.insert_ticks(&ticks)?;
+
+        let mut stream = db.stream_ticks_chunked("EURUSD", at(T0), at(T0 + 60_000), 4);
+        assert!(stream.next().is_some());
+        // Writing mid-stream must not deadlock on the shared connection
+        db.insert_tick(&Tick::new_with_millis(
+            "EURUSD".to_string(),
+            T0 + 30_000,
+            1.1,
+            1.1002,
+        ))?;
+        assert_eq!(stream.count(), 10);
+        Ok(())
+    }
+}
@@ -0,0 +1,585 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use parquet::basic::{LogicalType, Repetition, Type as PhysicalType};
+use parquet::data_type::{ByteArray, ByteArrayType, DataType, DoubleType, Int32Type, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::{SerializedFileWriter, SerializedRowGroupWriter};
+use parquet::schema::types::Type;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+use crate::database::Database;
+use crate::models::{Bar, Tick};
+use crate::timeframe::Timeframe;
+
+const DEFAULT_CHUNK_SIZE: usize = 50_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+    Json,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "parquet" => Ok(ExportFormat::Parquet),
+            "json" => Ok(ExportFormat::Json),
+            _ => Err(format!(
+                "Invalid export format: {} (supported: csv, parquet, json)",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportSummary {
+    pub file_path: PathBuf,
+    pub rows_exported: usize,
+    pub chunks: usize,
+    pub duration: Duration,
+}
+
+/// Writes ticks or bars from the database to a file.
+///
+/// Rows are read in chunks of `chunk_size` and written as they arrive, so
+/// memory use does not depend on the size of the exported range. CSV and
+/// Parquet output can be read back by the importers.
+pub struct Exporter<'a> {
+    database: &'a Database,
+    chunk_size: usize,
+}
+
+impl<'a> Exporter<'a> {
+    pub fn new(database: &'a Database) -> Self {
+        Self {
+            database,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    pub fn export_ticks(
+        &self,
+        symbol: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        format: ExportFormat,
+        path: &Path,
+    ) -> Result<ExportSummary> {
+        info!("Exporting {} ticks to: {}", symbol, path.display());
+        self.export(format, path, start, |after| {
+            let ticks = self
+                .database
+                .query_ticks_after(symbol, after, end, self.chunk_size)?;
+            let next = ticks.last().map(|tick| tick.timestamp);
+            Ok((ticks, next))
+        })
+    }
+
+    pub fn export_bars(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        format: ExportFormat,
+        path: &Path,
+    ) -> Result<ExportSummary> {
+        info!(
+            "Exporting {} {} bars to: {}",
+            symbol,
+            timeframe,
+            path.display()
+        );
+        self.export(format, path, start, |after| {
+            let bars =
+                self.database
+                    .query_bars_after(symbol, timeframe, after, end, self.chunk_size)?;
+            let next = bars.last().map(|bar| bar.timestamp_start);
+            Ok((bars, next))
+        })
+    }
+
+    /// Drive `next_chunk` from `start` until it runs dry, writing each chunk
+    fn export<R: ExportRecord>(
+        &self,
+        format: ExportFormat,
+        path: &Path,
+        start: DateTime<Utc>,
+        mut next_chunk: impl FnMut(i64) -> Result<(Vec<R>, Option<i64>)>,
+    ) -> Result<ExportSummary> {
+        let start_time = Instant::now();
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create file: {}", path.display()))?;
+        let mut sink = Sink::open::<R>(format, file)?;
+
+        // Keys are exclusive, so begin just before the first wanted row
+        let mut after = start.timestamp_millis() - 1;
+        let mut rows_exported = 0;
+        let mut chunks = 0;
+
+        loop {
+            let (rows, next) = next_chunk(after)?;
+            let Some(next) = next else { break };
+
+            sink.write_chunk(&rows)?;
+            rows_exported += rows.len();
+            chunks += 1;
+            after = next;
+            debug!("Exported chunk of {} rows", rows.len());
+        }
+
+        sink.finish()?;
+
+        let summary = ExportSummary {
+            file_path: path.to_path_buf(),
+            rows_exported,
+            chunks,
+            duration: start_time.elapsed(),
+        };
+        info!(
+            "Export completed: {} rows in {:?}",
+            summary.rows_exported, summary.duration
+        );
+        Ok(summary)
+    }
+}
+
+/// Per-record layout for each export format
+trait ExportRecord: serde::Serialize {
+    const CSV_HEADER: &'static str;
+
+    fn csv_row(&self) -> String;
+
+    fn parquet_schema() -> Result<Type>;
+
+    fn write_row_group(
+        rows: &[Self],
+        writer: &mut SerializedRowGroupWriter<'_, File>,
+    ) -> Result<()>
+    where
+        Self: Sized;
+}
+
+impl ExportRecord for Tick {
+    const CSV_HEADER: &'static str = "symbol,timestamp,bid,ask,bid_size,ask_size,last,last_size";
+
+    fn csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{}",
+            self.symbol,
+            self.timestamp,
+            self.bid,
+            self.ask,
+            optional(self.bid_size),
+            optional(self.ask_size),
+            optional(self.last),
+            optional(self.last_size)
+        )
+    }
+
+    fn parquet_schema() -> Result<Type> {
+        group(
+            "tick",
+            vec![
+                string_column("symbol")?,
+                column("timestamp", PhysicalType::INT64, Repetition::REQUIRED)?,
+                column("bid", PhysicalType::DOUBLE, Repetition::REQUIRED)?,
+                column("ask", PhysicalType::DOUBLE, Repetition::REQUIRED)?,
+                column("bid_size", PhysicalType::INT64, Repetition::OPTIONAL)?,
+                column("ask_size", PhysicalType::INT64, Repetition::OPTIONAL)?,
+                column("last", PhysicalType::DOUBLE, Repetition::OPTIONAL)?,
+                column("last_size", PhysicalType::INT64, Repetition::OPTIONAL)?,
+            ],
+        )
+    }
+
+    fn write_row_group(
+        rows: &[Self],
+        writer: &mut SerializedRowGroupWriter<'_, File>,
+    ) -> Result<()> {
+        let symbols: Vec<ByteArray> = rows.iter().map(|t| t.symbol.as_str().into()).collect();
+        write_column::<ByteArrayType>(writer, &symbols, None)?;
+        let timestamps: Vec<i64> = rows.iter().map(|t| t.timestamp).collect();
+        write_column::<Int64Type>(writer, &timestamps, None)?;
+        let bids: Vec<f64> = rows.iter().map(|t| t.bid).collect();
+        write_column::<DoubleType>(writer, &bids, None)?;
+        let asks: Vec<f64> = rows.iter().map(|t| t.ask).collect();
+        write_column::<DoubleType>(writer, &asks, None)?;
+        write_optional_column::<Int64Type>(writer, rows.iter().map(|t| t.bid_size))?;
+        write_optional_column::<Int64Type>(writer, rows.iter().map(|t| t.ask_size))?;
+        write_optional_column::<DoubleType>(writer, rows.iter().map(|t| t.last))?;
+        write_optional_column::<Int64Type>(writer, rows.iter().map(|t| t.last_size))
+    }
+}
+
+impl ExportRecord for Bar {
+    const CSV_HEADER: &'static str =
+        "symbol,timeframe,timestamp_start,timestamp_end,open,high,low,close,volume,tick_count";
+
+    fn csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{}",
+            self.symbol,
+            self.timeframe,
+            self.timestamp_start,
+            self.timestamp_end,
+            self.open,
+            self.high,
+            self.low,
+            self.close,
+            optional(self.volume),
+            optional(self.tick_count)
+        )
+    }
+
+    fn parquet_schema() -> Result<Type> {
+        group(
+            "bar",
+            vec![
+                string_column("symbol")?,
+                string_column("timeframe")?,
+                column("timestamp_start", PhysicalType::INT64, Repetition::REQUIRED)?,
+                column("timestamp_end", PhysicalType::INT64, Repetition::REQUIRED)?,
+                column("open", PhysicalType::DOUBLE, Repetition::REQUIRED)?,
+                column("high", PhysicalType::DOUBLE, Repetition::REQUIRED)?,
+                column("low", PhysicalType::DOUBLE, Repetition::REQUIRED)?,
+                column("close", PhysicalType::DOUBLE, Repetition::REQUIRED)?,
+                column("volume", PhysicalType::INT64, Repetition::OPTIONAL)?,
+                column("tick_count", PhysicalType::INT32, Repetition::OPTIONAL)?,
+            ],
+        )
+    }
+
+    fn write_row_group(
+        rows: &[Self],
+        writer: &mut SerializedRowGroupWriter<'_, File>,
+    ) -> Result<()> {
+        let symbols: Vec<ByteArray> = rows.iter().map(|b| b.symbol.as_str().into()).collect();
+        write_column::<ByteArrayType>(writer, &symbols, None)?;
+        let timeframes: Vec<ByteArray> = rows.iter().map(|b| b.timeframe.as_str().into()).collect();
+        write_column::<ByteArrayType>(writer, &timeframes, None)?;
+        let starts: Vec<i64> = rows.iter().map(|b| b.timestamp_start).collect();
+        write_column::<Int64Type>(writer, &starts, None)?;
+        let ends: Vec<i64> = rows.iter().map(|b| b.timestamp_end).collect();
+        write_column::<Int64Type>(writer, &ends, None)?;
+        for price in [
+            |b: &Bar| b.open,
+            |b: &Bar| b.high,
+            |b: &Bar| b.low,
+            |b: &Bar| b.close,
+        ] {
+            let values: Vec<f64> = rows.iter().map(price).collect();
+            write_column::<DoubleType>(writer, &values, None)?;
+        }
+        write_optional_column::<Int64Type>(writer, rows.iter().map(|b| b.volume))?;
+        write_optional_column::<Int32Type>(writer, rows.iter().map(|b| b.tick_count))
+    }
+}
+
+enum Sink {
+    Csv(BufWriter<File>),
+    Json {
+        writer: BufWriter<File>,
+        first: bool,
+    },
+    Parquet(SerializedFileWriter<File>),
+}
+
+impl Sink {
+    fn open<R: ExportRecord>(format: ExportFormat, file: File) -> Result<Self> {
+        match format {
+            ExportFormat::Csv => {
+                let mut writer = BufWriter::new(file);
+                writeln!(writer, "{}", R::CSV_HEADER)?;
+                Ok(Sink::Csv(writer))
+            }
+            ExportFormat::Json => {
+                let mut writer = BufWriter::new(file);
+                write!(writer, "[")?;
+                Ok(Sink::Json {
+                    writer,
+                    first: true,
+                })
+            }
+            ExportFormat::Parquet => {
+                let schema = Arc::new(R::parquet_schema()?);
+                let props = Arc::new(WriterProperties::builder().build());
+                Ok(Sink::Parquet(SerializedFileWriter::new(
+                    file, schema, props,
+                )?))
+            }
+        }
+    }
+
+    fn write_chunk<R: ExportRecord>(&mut self, rows: &[R]) -> Result<()> {
+        match self {
+            Sink::Csv(writer) => {
+                for row in rows {
+                    writeln!(writer, "{}", row.csv_row())?;
+                }
+            }
+            Sink::Json { writer, first } => {
+                for row in rows {
+                    if !*first {
+                        write!(writer, ",")?;
+                    }
+                    *first = false;
+                    write!(writer, "\n  ")?;
+                    serde_json::to_writer(&mut *writer, row)?;
+                }
+            }
+            Sink::Parquet(writer) => {
+                // One row group per chunk keeps the writer's buffer bounded
+                let mut row_group = writer.next_row_group()?;
+                R::write_row_group(rows, &mut row_group)?;
+                row_group.close()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        match self {
+            Sink::Csv(mut writer) => writer.flush()?,
+            Sink::Json { mut writer, first } => {
+                write!(writer, "{}]", if first { "" } else { "\n" })?;
+                writeln!(writer)?;
+                writer.flush()?;
+            }
+            Sink::Parquet(writer) => {
+                writer.close()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn group(name: &str, fields: Vec<Arc<Type>>) -> Result<Type> {
+    Ok(Type::group_type_builder(name).with_fields(fields).build()?)
+}
+
+fn column(name: &str, physical: PhysicalType, repetition: Repetition) -> Result<Arc<Type>> {
+    Ok(Arc::new(
+        Type::primitive_type_builder(name, physical)
+            .with_repetition(repetition)
+            .build()?,
+    ))
+}
+
+fn string_column(name: &str) -> Result<Arc<Type>> {
+    Ok(Arc::new(
+        Type::primitive_type_builder(name, PhysicalType::BYTE_ARRAY)
+            .with_repetition(Repetition::REQUIRED)
+            .with_logical_type(Some(LogicalType::String))
+            .build()?,
+    ))
+}
+
+fn write_column<T: DataType>(
+    writer: &mut SerializedRowGroupWriter<'_, File>,
+    values: &[T::T],
+    def_levels: Option<&[i16]>,
+) -> Result<()> {
+    let mut column = writer
+        .next_column()?
+        .context("Parquet schema has fewer columns than the record")?;
+    column.typed::<T>().write_batch(values, def_levels, None)?;
+    column.close()?;
+    Ok(())
+}
+
+fn write_optional_column<T: DataType>(
+    writer: &mut SerializedRowGroupWriter<'_, File>,
+    values: impl Iterator<Item = Option<T::T>>,
+) -> Result<()> {
+    let mut present = Vec::new();
+    let mut def_levels = Vec::new();
+    for value in values {
+        def_levels.push(i16::from(value.is_some()));
+        present.extend(value);
+    }
+    write_column::<T>(writer, &present, Some(&def_levels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::{CsvImporter, ParquetImporter};
+    use tempfile::tempdir;
+
+    const START: i64 = 1704067200000;
+
+    #[test]
+    fn test_export_format_from_str() {
+        assert_eq!("csv".parse(), Ok(ExportFormat::Csv));
+        assert_eq!("Parquet".parse(), Ok(ExportFormat::Parquet));
+        assert_eq!(" json ".parse(), Ok(ExportFormat::Json));
+        assert!("xlsx".parse::<ExportFormat>().is_err());
+    }
+
+    fn seeded_database() -> Database {
+        let db = Database::new_memory().unwrap();
+        let ticks: Vec<Tick> = (0..25)
+            .map(|i| {
+                let tick = Tick::new_with_millis(
+                    "EURUSD".to_string(),
+                    START + i * 1000,
+                    1.0920 + i as f64 * 0.0001,
+                    1.0922 + i as f64 * 0.0001,
+                );
+                match i % 3 {
+                    0 => tick.with_sizes(1_000_000, 500_000),
+                    1 => tick.with_last(1.0921 + i as f64 * 0.0001, Some(100_000)),
+                    _ => tick,
+                }
+            })
+            .collect();
+        db.insert_ticks(&ticks).unwrap();
+        db
+    }
+
+    fn range() -> (DateTime<Utc>, DateTime<Utc>) {
+        (
+            DateTime::from_timestamp_millis(START).unwrap(),
+            DateTime::from_timestamp_millis(START + 3_600_000).unwrap(),
+        )
+    }
+
+    fn reimported(path: &Path, format: ExportFormat) -> Vec<Tick> {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("reimport.sqlite");
+        let database = Database::new_file(&db_path).unwrap();
+        let summary = match format {
+            ExportFormat::Csv => CsvImporter::new(database).import_file(path),
+            _ => ParquetImporter::new(database).import_file(path),
+        }
+        .unwrap();
+        assert_eq!(summary.rows_skipped, 0);
+
+        let (start, end) = range();
+        Database::new_file(&db_path)
+            .unwrap()
+            .query_ticks("EURUSD", start, end)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_tick_export_roundtrips_through_importers() {
+        let db = seeded_database();
+        let dir = tempdir().unwrap();
+        let (start, end) = range();
+        let exporter = Exporter::new(&db).with_chunk_size(10);
+
+        for (format, name) in [
+            (ExportFormat::Csv, "ticks.csv"),
+            (ExportFormat::Parquet, "ticks.parquet"),
+        ] {
+            let path = dir.path().join(name);
+            let summary = exporter
+                .export_ticks("EURUSD", start, end, format, &path)
+                .unwrap();
+            assert_eq!(summary.rows_exported, 25);
+            assert_eq!(summary.chunks, 3);
+
+            let ticks = reimported(&path, format);
+            assert_eq!(ticks.len(), 25);
+            assert_eq!(ticks[0].bid_size, Some(1_000_000));
+            assert_eq!(ticks[1].bid_size, None);
+            assert_eq!(ticks[0].last, None);
+            assert_eq!(ticks[1].last, Some(1.0921 + 0.0001));
+            assert_eq!(ticks[1].last_size, Some(100_000));
+            assert_eq!(ticks[2].last_size, None);
+            assert_eq!(ticks[24].timestamp, START + 24_000);
+        }
+    }
+
+    #[test]
+    fn test_json_export_is_valid_array() {
+        let db = seeded_database();
+        let dir = tempdir().unwrap();
+        let (start, end) = range();
+        let path = dir.path().join("ticks.json");
+
+        Exporter::new(&db)
+            .with_chunk_size(7)
+            .export_ticks("EURUSD", start, end, ExportFormat::Json, &path)
+            .unwrap();
+
+        let ticks: Vec<Tick> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(ticks.len(), 25);
+
+        let empty = dir.path().join("empty.json");
+        let summary = Exporter::new(&db)
+            .export_ticks("GBPUSD", start, end, ExportFormat::Json, &empty)
+            .unwrap();
+        assert_eq!(summary.rows_exported, 0);
+        let ticks: Vec<Tick> =
+            serde_json::from_str(&std::fs::read_to_string(&empty).unwrap()).unwrap();
+        assert!(ticks.is_empty());
+    }
+
+    #[test]
+    fn test_bar_export_csv() {
+        let mut db = Database::new_memory().unwrap();
+        let bars: Vec<Bar> = (0..3)
+            .map(|i| {
+                Bar::new(
+                    "EURUSD".to_string(),
+                    Timeframe::M1,
+                    START + i * 60_000,
+                    START + (i + 1) * 60_000,
+                    1.0920,
+                    1.0925,
+                    1.0915,
+                    1.0922,
+                )
+                .with_volume(100)
+            })
+            .collect();
+        db.batch_insert_bars(&bars).unwrap();
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bars.csv");
+        let (start, end) = range();
+        let summary = Exporter::new(&db)
+            .export_bars(
+                "EURUSD",
+                Timeframe::M1,
+                start,
+                end,
+                ExportFormat::Csv,
+                &path,
+            )
+            .unwrap();
+        assert_eq!(summary.rows_exported, 3);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[1],
+            "EURUSD,1m,1704067200000,1704067260000,1.092,1.0925,1.0915,1.0922,100,"
+        );
+    }
+}
@@ -1,23 +1,24 @@
 use anyhow::{Context, Result};
-use csv::Reader;
+use csv::{Position, Reader, StringRecord};
 use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 use thiserror::Error;
 use tracing::{debug, error, info, warn};
 
-use super::validator::{validate_tick_data, ValidationError};
+use super::validator::{validate_tick_data, ValidationConfig, ValidationError};
 use crate::database::Database;
 use crate::models::Tick;
 
 const BATCH_SIZE: usize = 1000;
-const MAX_FILE_SIZE: u64 = 100 * 1024 * 1024; // 100MB
+const PROGRESS_INTERVAL: usize = 10_000;
 
 #[derive(Error, Debug)]
 pub enum ImportError {
-    #[error("File too large: {0} bytes (max: {MAX_FILE_SIZE} bytes)")]
-    FileTooLarge(u64),
+    #[error("File too large: {size} bytes (max: {max} bytes)")]
+    FileTooLarge { size: u64, max: u64 },
 
     #[error("Line {line}: {error}")]
     ParseError { line: usize, error: String },
@@ -41,6 +42,15 @@ pub struct ImportSummary {
     pub total_rows: usize,
     pub rows_imported: usize,
     pub rows_skipped: usize,
+    /// Exact repeats of a symbol's previous tick, dropped before insert,
+    /// and rows identical in every field to one already stored, which the
+    /// insert ignores
+    pub rows_deduplicated: usize,
+    /// Rows a resumed import skipped because an earlier run committed them
+    pub rows_resumed: usize,
+    /// Rows rejected before insert, by reason (`ValidationError::reason`,
+    /// or `parse_error` for rows that could not be read)
+    pub rejections: BTreeMap<&'static str, usize>,
     pub errors: Vec<String>,
     pub duration: Duration,
 }
@@ -55,6 +65,17 @@ impl ImportSummary {
     }
 }
 
+/// (timestamp, bid, ask, bid_size, ask_size, last, last_size)
+type TickFields = (
+    i64,
+    f64,
+    f64,
+    Option<i64>,
+    Option<i64>,
+    Option<f64>,
+    Option<i64>,
+);
+
 #[derive(Debug, Deserialize)]
 struct CsvRow {
     symbol: String,
@@ -65,24 +86,75 @@ struct CsvRow {
     bid_size: Option<i64>,
     #[serde(default)]
     ask_size: Option<i64>,
+    #[serde(default)]
+    last: Option<f64>,
+    #[serde(default)]
+    last_size: Option<i64>,
 }
 
+/// Callback invoked with the number of rows processed so far
+pub type ProgressCallback = Box<dyn Fn(usize)>;
+
+/// Streams ticks from a CSV file into the database.
+///
+/// Rows are read one at a time and flushed in batches of `BATCH_SIZE`, so
+/// memory use stays flat regardless of file size. There is no size limit
+/// unless one is set with `with_max_file_size`.
 pub struct CsvImporter {
     database: Database,
+    max_file_size: Option<u64>,
+    progress: Option<ProgressCallback>,
+    resume: bool,
+    validation: ValidationConfig,
 }
 
 impl CsvImporter {
     pub fn new(database: Database) -> Self {
-        Self { database }
+        Self {
+            database,
+            max_file_size: None,
+            progress: None,
+            resume: false,
+            validation: ValidationConfig::default(),
+        }
+    }
+
+    /// Reject files larger than `bytes` before reading them
+    pub fn with_max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// Invoke `callback` every 10,000 rows with the number of rows processed
+    pub fn with_progress(mut self, callback: ProgressCallback) -> Self {
+        self.progress = Some(callback);
+        self
+    }
+
+    /// Keep a checkpoint next to the source file (see `checkpoint_path`)
+    /// and, when one exists, continue after the last committed batch
+    /// instead of starting over. The checkpoint is removed once the whole
+    /// file has been imported.
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    /// Apply the optional tick rules of `config` to every row
+    pub fn with_validation(mut self, config: ValidationConfig) -> Self {
+        self.validation = config;
+        self
     }
 
     pub fn import_file(&mut self, path: &Path) -> Result<ImportSummary> {
         let start_time = Instant::now();
+        self.validation.validate()?;
 
-        // Check file size
-        let metadata = std::fs::metadata(path)?;
-        if metadata.len() > MAX_FILE_SIZE {
-            return Err(ImportError::FileTooLarge(metadata.len()).into());
+        if let Some(max) = self.max_file_size {
+            let size = std::fs::metadata(path)?.len();
+            if size > max {
+                return Err(ImportError::FileTooLarge { size, max }.into());
+            }
         }
 
         info!("Starting CSV import from: {}", path.display());
@@ -91,15 +163,46 @@ impl CsvImporter {
             File::open(path).with_context(|| format!("Failed to open file: {}", path.display()))?;
 
         let mut reader = Reader::from_reader(file);
+        let headers = reader.headers()?.clone();
+
+        let checkpoint_file = checkpoint_path(path);
+        let source = SourceStamp::of(path)?;
+        let mut rows_resumed = 0;
+        if self.resume {
+            if let Some(checkpoint) = Checkpoint::load(&checkpoint_file, source) {
+                info!(
+                    "Resuming import after {} rows (byte {})",
+                    checkpoint.rows,
+                    checkpoint.position.byte()
+                );
+                reader.seek(checkpoint.position.clone())?;
+                rows_resumed = checkpoint.rows;
+            }
+        }
+
         let mut batch = Vec::with_capacity(BATCH_SIZE);
         let mut total_rows = 0;
         let mut rows_imported = 0;
         let mut rows_skipped = 0;
+        let mut rows_deduplicated = 0;
+        let mut rejections = BTreeMap::new();
         let mut errors = Vec::new();
-
-        for (line_num, result) in reader.deserialize::<CsvRow>().enumerate() {
+        // Every field of the last tick seen per symbol
+        let mut last_seen: HashMap<String, TickFields> = HashMap::new();
+        // Stops advancing once a batch fails, so a resume retries it
+        let mut checkpoint_valid = true;
+        let mut record = StringRecord::new();
+
+        loop {
+            let result = match reader.read_record(&mut record) {
+                Ok(false) => break,
+                Ok(true) => record.deserialize::<CsvRow>(Some(&headers)),
+                Err(e) => Err(e),
+            };
             total_rows += 1;
-            let line = line_num + 2; // Account for header and 1-based indexing
+            let line = record
+                .position()
+                .map_or(total_rows + 1, |pos| pos.line() as usize);
 
             match result {
                 Ok(row) => {
@@ -109,9 +212,12 @@ impl CsvImporter {
                         Some(&row.timestamp),
                         Some(row.bid),
                         Some(row.ask),
+                        row.last,
+                        &self.validation,
                     ) {
                         warn!("Line {}: Validation failed: {}", line, e);
                         errors.push(format!("Line {}: {}", line, e));
+                        *rejections.entry(e.reason()).or_default() += 1;
                         rows_skipped += 1;
                         continue;
                     }
@@ -125,11 +231,35 @@ impl CsvImporter {
                                 line, row.timestamp, e
                             );
                             errors.push(format!("Line {}: Invalid timestamp: {}", line, e));
+                            *rejections.entry("invalid_timestamp").or_default() += 1;
                             rows_skipped += 1;
                             continue;
                         }
                     };
 
+                    // Only exact repeats are dropped; a price or size change
+                    // at the same timestamp is a tick of its own
+                    let key = (
+                        timestamp,
+                        row.bid,
+                        row.ask,
+                        row.bid_size,
+                        row.ask_size,
+                        row.last,
+                        row.last_size,
+                    );
+                    match last_seen.get_mut(&row.symbol) {
+                        Some(last) if *last == key => {
+                            debug!("Line {}: Skipping duplicate tick", line);
+                            rows_deduplicated += 1;
+                            continue;
+                        }
+                        Some(last) => *last = key,
+                        None => {
+                            last_seen.insert(row.symbol.clone(), key);
+                        }
+                    }
+
                     // Create tick
                     let tick = Tick {
                         id: None,
@@ -139,6 +269,8 @@ impl CsvImporter {
                         ask: row.ask,
                         bid_size: row.bid_size,
                         ask_size: row.ask_size,
+                        last: row.last,
+                        last_size: row.last_size,
                     };
 
                     batch.push(tick);
@@ -146,14 +278,26 @@ impl CsvImporter {
                     // Process batch when it reaches BATCH_SIZE
                     if batch.len() >= BATCH_SIZE {
                         match self.database.insert_batch(&batch) {
-                            Ok(_) => {
-                                rows_imported += batch.len();
-                                debug!("Imported batch of {} ticks", batch.len());
+                            Ok(written) => {
+                                rows_imported += written;
+                                rows_deduplicated += batch.len() - written;
+                                debug!("Imported batch of {} ticks", written);
+                                // The batch is committed; everything before the
+                                // reader's position is safe to skip next time
+                                if self.resume && checkpoint_valid {
+                                    Checkpoint {
+                                        position: reader.position().clone(),
+                                        rows: rows_resumed + total_rows,
+                                        source,
+                                    }
+                                    .save(&checkpoint_file)?;
+                                }
                             }
                             Err(e) => {
                                 error!("Failed to insert batch: {}", e);
                                 errors.push(format!("Batch insert failed at line {}: {}", line, e));
                                 rows_skipped += batch.len();
+                                checkpoint_valid = false;
                             }
                         }
                         batch.clear();
@@ -162,31 +306,41 @@ impl CsvImporter {
                 Err(e) => {
                     warn!("Line {}: Failed to parse CSV row: {}", line, e);
                     errors.push(format!("Line {}: Parse error: {}", line, e));
+                    *rejections.entry("parse_error").or_default() += 1;
                     rows_skipped += 1;
                 }
             }
 
-            // Log progress every 10000 rows
-            if total_rows % 10000 == 0 {
+            if total_rows % PROGRESS_INTERVAL == 0 {
                 info!("Processed {} rows...", total_rows);
+                if let Some(progress) = &self.progress {
+                    progress(total_rows);
+                }
             }
         }
 
         // Process remaining batch
         if !batch.is_empty() {
             match self.database.insert_batch(&batch) {
-                Ok(_) => {
-                    rows_imported += batch.len();
-                    debug!("Imported final batch of {} ticks", batch.len());
+                Ok(written) => {
+                    rows_imported += written;
+                    rows_deduplicated += batch.len() - written;
+                    debug!("Imported final batch of {} ticks", written);
                 }
                 Err(e) => {
                     error!("Failed to insert final batch: {}", e);
                     errors.push(format!("Final batch insert failed: {}", e));
                     rows_skipped += batch.len();
+                    checkpoint_valid = false;
                 }
             }
         }
 
+        // A finished import has nothing left to resume
+        if self.resume && checkpoint_valid && checkpoint_file.exists() {
+            std::fs::remove_file(&checkpoint_file)?;
+        }
+
         let duration = start_time.elapsed();
 
         let summary = ImportSummary {
@@ -194,6 +348,9 @@ impl CsvImporter {
             total_rows,
             rows_imported,
             rows_skipped,
+            rows_deduplicated,
+            rows_resumed,
+            rejections,
             errors: errors.into_iter().take(100).collect(), // Limit errors to first 100
             duration,
         };
@@ -210,7 +367,100 @@ impl CsvImporter {
     }
 }
 
-fn parse_timestamp(timestamp_str: &str) -> Result<i64> {
+/// Sidecar file a resumable import of `path` records its progress in
+pub fn checkpoint_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".checkpoint");
+    PathBuf::from(name)
+}
+
+/// Size and modification time of a source file, so a checkpoint is only
+/// applied to the exact file it was written for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SourceStamp {
+    len: u64,
+    /// Nanoseconds since the Unix epoch, 0 where the platform has no mtime
+    modified: u64,
+}
+
+impl SourceStamp {
+    fn of(path: &Path) -> std::io::Result<Self> {
+        let metadata = std::fs::metadata(path)?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .and_then(|since| u64::try_from(since.as_nanos()).ok())
+            .unwrap_or(0);
+        Ok(Self {
+            len: metadata.len(),
+            modified,
+        })
+    }
+}
+
+/// Reader position just past the last committed batch
+struct Checkpoint {
+    position: Position,
+    /// Data rows before `position`
+    rows: usize,
+    /// The source file as it was when the checkpoint was written
+    source: SourceStamp,
+}
+
+impl Checkpoint {
+    /// `None` when there is no usable checkpoint, including one written for
+    /// a file that has since been changed or replaced
+    fn load(path: &Path, source: SourceStamp) -> Option<Self> {
+        let contents = std::fs::read_to_string(path).ok()?;
+        let fields: Vec<u64> = contents
+            .trim()
+            .split(',')
+            .map(|field| field.parse().ok())
+            .collect::<Option<_>>()?;
+        let [byte, line, record, rows, len, modified] = fields[..] else {
+            warn!("Ignoring malformed checkpoint {}", path.display());
+            return None;
+        };
+        if (SourceStamp { len, modified }) != source {
+            warn!(
+                "Ignoring checkpoint {} for a source file that has changed",
+                path.display()
+            );
+            return None;
+        }
+
+        let mut position = Position::new();
+        position.set_byte(byte).set_line(line).set_record(record);
+        Some(Self {
+            position,
+            rows: rows as usize,
+            source,
+        })
+    }
+
+    /// Written to a temporary file and renamed so a crash never leaves a
+    /// half-written checkpoint
+    fn save(&self, path: &Path) -> std::io::Result<()> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        std::fs::write(
+            &tmp,
+            format!(
+                "{},{},{},{},{},{}",
+                self.position.byte(),
+                self.position.line(),
+                self.position.record(),
+                self.rows,
+                self.source.len,
+                self.source.modified
+            ),
+        )?;
+        std::fs::rename(&tmp, path)
+    }
+}
+
+pub(crate) fn parse_timestamp(timestamp_str: &str) -> Result<i64> {
     // Try parsing as ISO 8601
     if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(timestamp_str) {
         return Ok(dt.timestamp_millis());
@@ -234,6 +484,7 @@ fn parse_timestamp(timestamp_str: &str) -> Result<i64> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::query::TickQuery;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -286,6 +537,32 @@ EURUSD,2024-01-01T00:00:02Z,1.0920,1.0922,2000000,1500000"#;
         assert_eq!(summary.success_rate(), 100.0);
     }
 
+    #[test]
+    fn test_import_optional_last_columns() {
+        let csv_content = r#"symbol,timestamp,bid,ask,last,last_size
+ES,2024-01-01T00:00:00Z,4800.00,4800.50,4800.25,3
+ES,2024-01-01T00:00:01Z,4800.25,4800.75,,"#;
+
+        let csv_file = create_csv_file(csv_content);
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("last.db");
+        let mut importer = CsvImporter::new(Database::new_file(&db_path).unwrap());
+        let summary = importer.import_file(csv_file.path()).unwrap();
+        assert_eq!(summary.rows_imported, 2);
+
+        let ticks = TickQuery::new()
+            .symbol("ES")
+            .execute(&Database::new_file(&db_path).unwrap())
+            .unwrap();
+        assert_eq!(
+            ticks
+                .iter()
+                .map(|t| (t.last, t.last_size))
+                .collect::<Vec<_>>(),
+            vec![(Some(4800.25), Some(3)), (None, None)]
+        );
+    }
+
     #[test]
     fn test_import_with_invalid_rows() {
         let csv_content = r#"symbol,timestamp,bid,ask
@@ -307,6 +584,165 @@ EURUSD,2024-01-01T00:00:03Z,1.0925,1.0927"#;
         assert_eq!(summary.success_rate(), 50.0);
     }
 
+    #[test]
+    fn test_consecutive_duplicates_are_dropped() {
+        let csv_content = r#"symbol,timestamp,bid,ask
+EURUSD,2024-01-01T00:00:00Z,1.0921,1.0923
+EURUSD,2024-01-01T00:00:00Z,1.0921,1.0923
+GBPUSD,2024-01-01T00:00:00Z,1.2701,1.2703
+EURUSD,2024-01-01T00:00:00Z,1.0921,1.0923
+EURUSD,2024-01-01T00:00:00Z,1.0922,1.0924
+EURUSD,2024-01-01T00:00:01Z,1.0922,1.0924"#;
+
+        let csv_file = create_csv_file(csv_content);
+        let mut importer = CsvImporter::new(create_test_db());
+        let summary = importer.import_file(csv_file.path()).unwrap();
+
+        // An interleaved symbol doesn't hide a repeat, and a price change at
+        // the same timestamp is kept as a tick of its own
+        assert_eq!(summary.total_rows, 6);
+        assert_eq!(summary.rows_deduplicated, 2);
+        assert_eq!(summary.rows_imported, 4);
+        assert_eq!(summary.rows_skipped, 0);
+        assert_eq!(importer.database.count_ticks().unwrap(), 4);
+    }
+
+    #[test]
+    fn test_progress_callback() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut csv_content = String::from("symbol,timestamp,bid,ask\n");
+        for i in 0..25_000i64 {
+            csv_content.push_str(&format!("EURUSD,{},1.0921,1.0923\n", 1704067200000 + i));
+        }
+        let csv_file = create_csv_file(&csv_content);
+
+        let reported = Rc::new(RefCell::new(Vec::new()));
+        let sink = Rc::clone(&reported);
+        let mut importer = CsvImporter::new(create_test_db())
+            .with_progress(Box::new(move |rows| sink.borrow_mut().push(rows)));
+
+        let summary = importer.import_file(csv_file.path()).unwrap();
+        assert_eq!(summary.rows_imported, 25_000);
+        assert_eq!(*reported.borrow(), vec![10_000, 20_000]);
+    }
+
+    #[test]
+    fn test_resume_after_interrupted_import() {
+        let mut csv_content = String::from("symbol,timestamp,bid,ask\n");
+        for i in 0..25_000i64 {
+            csv_content.push_str(&format!("EURUSD,{},1.0921,1.0923\n", 1704067200000 + i));
+        }
+        let csv_file = create_csv_file(&csv_content);
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("resume.db");
+
+        // Die right after the batch ending at row 10,000 commits
+        let mut importer = CsvImporter::new(Database::new_file(&db_path).unwrap())
+            .with_resume(true)
+            .with_progress(Box::new(|_| panic!("simulated crash")));
+        let crashed = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            importer.import_file(csv_file.path())
+        }));
+        assert!(crashed.is_err());
+        drop(importer);
+        assert!(checkpoint_path(csv_file.path()).exists());
+
+        let mut importer =
+            CsvImporter::new(Database::new_file(&db_path).unwrap()).with_resume(true);
+        let summary = importer.import_file(csv_file.path()).unwrap();
+
+        // Nothing committed before the crash is read again
+        assert_eq!(summary.rows_resumed, 10_000);
+        assert_eq!(summary.total_rows, 15_000);
+        assert_eq!(summary.rows_imported, 15_000);
+        assert!(!checkpoint_path(csv_file.path()).exists());
+        assert_eq!(
+            Database::new_file(&db_path).unwrap().count_ticks().unwrap(),
+            25_000
+        );
+    }
+
+    #[test]
+    fn test_stale_checkpoint_is_ignored() {
+        let csv_content = "symbol,timestamp,bid,ask\nEURUSD,2024-01-01T00:00:00Z,1.0921,1.0923\n";
+        let csv_file = create_csv_file(csv_content);
+        std::fs::write(checkpoint_path(csv_file.path()), "999999,10,9,8").unwrap();
+
+        let mut importer = CsvImporter::new(create_test_db()).with_resume(true);
+        let summary = importer.import_file(csv_file.path()).unwrap();
+        assert_eq!(summary.rows_resumed, 0);
+        assert_eq!(summary.rows_imported, 1);
+        assert!(!checkpoint_path(csv_file.path()).exists());
+    }
+
+    #[test]
+    fn test_checkpoint_for_replaced_file_is_ignored() {
+        let mut csv_content = String::from("symbol,timestamp,bid,ask\n");
+        for i in 0..3i64 {
+            csv_content.push_str(&format!("EURUSD,{},1.0921,1.0923\n", 1704067200000 + i));
+        }
+        let csv_file = create_csv_file(&csv_content);
+        let first_row = "symbol,timestamp,bid,ask\n".len() as u64;
+        let checkpoint = |rows: usize| {
+            let mut position = Position::new();
+            position.set_byte(first_row).set_line(2).set_record(1);
+            Checkpoint {
+                position,
+                rows,
+                source: SourceStamp::of(csv_file.path()).unwrap(),
+            }
+            .save(&checkpoint_path(csv_file.path()))
+            .unwrap();
+        };
+
+        // Written for this exact file, so it applies
+        checkpoint(1);
+        let mut importer = CsvImporter::new(create_test_db()).with_resume(true);
+        let summary = importer.import_file(csv_file.path()).unwrap();
+        assert_eq!(summary.rows_resumed, 1);
+        assert_eq!(summary.total_rows, 3);
+
+        // Same length, different contents and modification time
+        checkpoint(1);
+        let replaced = csv_content.replace("1.0921", "1.0931");
+        std::fs::write(csv_file.path(), &replaced).unwrap();
+        let earlier = std::time::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        File::options()
+            .write(true)
+            .open(csv_file.path())
+            .unwrap()
+            .set_modified(earlier)
+            .unwrap();
+
+        let mut importer = CsvImporter::new(create_test_db()).with_resume(true);
+        let summary = importer.import_file(csv_file.path()).unwrap();
+        assert_eq!(summary.rows_resumed, 0);
+        assert_eq!(summary.rows_imported, 3);
+        assert!(!checkpoint_path(csv_file.path()).exists());
+    }
+
+    #[test]
+    fn test_max_file_size() {
+        let csv_content = "symbol,timestamp,bid,ask\nEURUSD,2024-01-01T00:00:00Z,1.0921,1.0923\n";
+        let csv_file = create_csv_file(csv_content);
+
+        let mut importer = CsvImporter::new(create_test_db()).with_max_file_size(10);
+        let err = importer.import_file(csv_file.path()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ImportError>(),
+            Some(ImportError::FileTooLarge { max: 10, .. })
+        ));
+
+        // Unlimited by default
+        let mut importer = CsvImporter::new(create_test_db());
+        assert_eq!(
+            importer.import_file(csv_file.path()).unwrap().rows_imported,
+            1
+        );
+    }
+
     #[test]
     fn test_import_empty_file() {
         let csv_content = "symbol,timestamp,bid,ask\n";
@@ -1,5 +1,7 @@
 pub mod csv_import;
+pub mod parquet_import;
 pub mod validator;
 
-pub use csv_import::{CsvImporter, ImportError, ImportSummary};
-pub use validator::{validate_tick_data, ValidationError};
+pub use csv_import::{checkpoint_path, CsvImporter, ImportError, ImportSummary, ProgressCallback};
+pub use parquet_import::ParquetImporter;
+pub use validator::{validate_tick_data, ValidationConfig, ValidationError};
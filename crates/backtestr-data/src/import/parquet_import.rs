@@ -0,0 +1,384 @@
+use anyhow::{Context, Result};
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::{Field, Row};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::Path;
+use std::time::Instant;
+use tracing::{debug, error, info, warn};
+
+use super::csv_import::{parse_timestamp, ImportSummary};
+use super::validator::{validate_tick_data, ValidationConfig, ValidationError};
+use crate::database::Database;
+use crate::models::Tick;
+
+const BATCH_SIZE: usize = 1000;
+
+/// Imports ticks from Parquet files.
+///
+/// Expects columns `symbol`, `timestamp`, `bid` and `ask`, with optional
+/// `bid_size`, `ask_size`, `last` and `last_size`. The timestamp may be an
+/// int64 epoch-ms column, a millisecond/microsecond timestamp logical type,
+/// or a string in any format accepted by the CSV importer. Rows are
+/// streamed, so memory use does not depend on file size.
+pub struct ParquetImporter {
+    database: Database,
+    validation: ValidationConfig,
+}
+
+impl ParquetImporter {
+    pub fn new(database: Database) -> Self {
+        Self {
+            database,
+            validation: ValidationConfig::default(),
+        }
+    }
+
+    /// Apply the optional tick rules of `config` to every row
+    pub fn with_validation(mut self, config: ValidationConfig) -> Self {
+        self.validation = config;
+        self
+    }
+
+    pub fn import_file(&mut self, path: &Path) -> Result<ImportSummary> {
+        let start_time = Instant::now();
+        self.validation.validate()?;
+
+        info!("Starting Parquet import from: {}", path.display());
+
+        let file =
+            File::open(path).with_context(|| format!("Failed to open file: {}", path.display()))?;
+        let reader = SerializedFileReader::new(file)
+            .with_context(|| format!("Failed to read Parquet file: {}", path.display()))?;
+
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+        let mut total_rows = 0;
+        let mut rows_imported = 0;
+        let mut rows_skipped = 0;
+        let mut rows_deduplicated = 0;
+        let mut rejections = BTreeMap::new();
+        let mut errors = Vec::new();
+
+        for (index, result) in reader.get_row_iter(None)?.enumerate() {
+            total_rows += 1;
+            let row_num = index + 1;
+
+            let tick = match result
+                .map_err(|e| ("parse_error", e.to_string()))
+                .and_then(|row| {
+                    row_to_tick(&row, &self.validation).map_err(|e| (e.reason(), e.to_string()))
+                }) {
+                Ok(tick) => tick,
+                Err((reason, e)) => {
+                    warn!("Row {}: {}", row_num, e);
+                    errors.push(format!("Row {}: {}", row_num, e));
+                    *rejections.entry(reason).or_default() += 1;
+                    rows_skipped += 1;
+                    continue;
+                }
+            };
+
+            batch.push(tick);
+
+            if batch.len() >= BATCH_SIZE {
+                match self.database.insert_batch(&batch) {
+                    Ok(written) => {
+                        rows_imported += written;
+                        rows_deduplicated += batch.len() - written;
+                        debug!("Imported batch of {} ticks", written);
+                    }
+                    Err(e) => {
+                        error!("Failed to insert batch: {}", e);
+                        errors.push(format!("Batch insert failed at row {}: {}", row_num, e));
+                        rows_skipped += batch.len();
+                    }
+                }
+                batch.clear();
+            }
+
+            if total_rows % 10000 == 0 {
+                info!("Processed {} rows...", total_rows);
+            }
+        }
+
+        if !batch.is_empty() {
+            match self.database.insert_batch(&batch) {
+                Ok(written) => {
+                    rows_imported += written;
+                    rows_deduplicated += batch.len() - written;
+                    debug!("Imported final batch of {} ticks", written);
+                }
+                Err(e) => {
+                    error!("Failed to insert final batch: {}", e);
+                    errors.push(format!("Final batch insert failed: {}", e));
+                    rows_skipped += batch.len();
+                }
+            }
+        }
+
+        let summary = ImportSummary {
+            file_path: path.to_path_buf(),
+            total_rows,
+            rows_imported,
+            rows_skipped,
+            rows_deduplicated,
+            rows_resumed: 0,
+            rejections,
+            errors: errors.into_iter().take(100).collect(),
+            duration: start_time.elapsed(),
+        };
+
+        info!(
+            "Import completed: {} rows imported, {} skipped ({}% success rate) in {:?}",
+            summary.rows_imported,
+            summary.rows_skipped,
+            summary.success_rate(),
+            summary.duration
+        );
+
+        Ok(summary)
+    }
+}
+
+fn row_to_tick(row: &Row, config: &ValidationConfig) -> std::result::Result<Tick, ValidationError> {
+    let mut symbol = None;
+    let mut timestamp = None;
+    let mut bid = None;
+    let mut ask = None;
+    let mut bid_size = None;
+    let mut ask_size = None;
+    let mut last = None;
+    let mut last_size = None;
+
+    for (name, field) in row.get_column_iter() {
+        match name.as_str() {
+            "symbol" => symbol = field_to_string(field),
+            "timestamp" => {
+                timestamp =
+                    Some(field_to_timestamp(field).map_err(ValidationError::InvalidTimestamp)?)
+            }
+            "bid" => bid = field_to_f64(field),
+            "ask" => ask = field_to_f64(field),
+            "bid_size" => bid_size = field_to_i64(field),
+            "ask_size" => ask_size = field_to_i64(field),
+            "last" => last = field_to_f64(field),
+            "last_size" => last_size = field_to_i64(field),
+            _ => {}
+        }
+    }
+
+    let timestamp_str = timestamp.map(|ts| ts.to_string());
+    validate_tick_data(
+        symbol.as_deref(),
+        timestamp_str.as_deref(),
+        bid,
+        ask,
+        last,
+        config,
+    )?;
+
+    // Validation guarantees the required fields are present
+    Ok(Tick {
+        id: None,
+        symbol: symbol.unwrap_or_default(),
+        timestamp: timestamp.unwrap_or_default(),
+        bid: bid.unwrap_or_default(),
+        ask: ask.unwrap_or_default(),
+        bid_size,
+        ask_size,
+        last,
+        last_size,
+    })
+}
+
+fn field_to_string(field: &Field) -> Option<String> {
+    match field {
+        Field::Str(s) => Some(s.clone()),
+        Field::Bytes(b) => b.as_utf8().ok().map(str::to_string),
+        _ => None,
+    }
+}
+
+fn field_to_timestamp(field: &Field) -> std::result::Result<i64, String> {
+    match field {
+        Field::Long(ms) => Ok(*ms),
+        Field::TimestampMillis(ms) => Ok(*ms),
+        Field::TimestampMicros(us) => Ok(us.div_euclid(1000)),
+        Field::Str(s) => parse_timestamp(s).map_err(|e| format!("Invalid timestamp: {}", e)),
+        Field::Null => Err("Missing required field: timestamp".to_string()),
+        other => Err(format!("Unsupported timestamp type: {}", other)),
+    }
+}
+
+fn field_to_f64(field: &Field) -> Option<f64> {
+    match field {
+        Field::Double(v) => Some(*v),
+        Field::Float(v) => Some(*v as f64),
+        _ => None,
+    }
+}
+
+fn field_to_i64(field: &Field) -> Option<i64> {
+    match field {
+        Field::Long(v) => Some(*v),
+        Field::Int(v) => Some(*v as i64),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet::basic::{LogicalType, Repetition, TimeUnit, Type as PhysicalType};
+    use parquet::data_type::{ByteArray, ByteArrayType, DoubleType, Int64Type};
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::types::Type;
+    use std::sync::Arc;
+    use tempfile::NamedTempFile;
+
+    struct Rows<'a> {
+        symbols: &'a [&'a str],
+        timestamps: &'a [i64],
+        bids: &'a [f64],
+        asks: &'a [f64],
+        sizes: Option<&'a [i64]>,
+    }
+
+    fn column(name: &str, physical: PhysicalType, logical: Option<LogicalType>) -> Arc<Type> {
+        Arc::new(
+            Type::primitive_type_builder(name, physical)
+                .with_repetition(Repetition::REQUIRED)
+                .with_logical_type(logical)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    fn write_parquet(rows: &Rows, timestamp_type: Option<LogicalType>) -> NamedTempFile {
+        let mut fields = vec![
+            column(
+                "symbol",
+                PhysicalType::BYTE_ARRAY,
+                Some(LogicalType::String),
+            ),
+            column("timestamp", PhysicalType::INT64, timestamp_type),
+            column("bid", PhysicalType::DOUBLE, None),
+            column("ask", PhysicalType::DOUBLE, None),
+        ];
+        if rows.sizes.is_some() {
+            fields.push(column("bid_size", PhysicalType::INT64, None));
+            fields.push(column("ask_size", PhysicalType::INT64, None));
+        }
+        let schema = Arc::new(
+            Type::group_type_builder("tick")
+                .with_fields(fields)
+                .build()
+                .unwrap(),
+        );
+
+        let file = NamedTempFile::new().unwrap();
+        let props = Arc::new(WriterProperties::builder().build());
+        let mut writer = SerializedFileWriter::new(file.reopen().unwrap(), schema, props).unwrap();
+        let mut row_group = writer.next_row_group().unwrap();
+
+        let symbols: Vec<ByteArray> = rows.symbols.iter().map(|s| (*s).into()).collect();
+        let mut col = row_group.next_column().unwrap().unwrap();
+        col.typed::<ByteArrayType>()
+            .write_batch(&symbols, None, None)
+            .unwrap();
+        col.close().unwrap();
+
+        let mut col = row_group.next_column().unwrap().unwrap();
+        col.typed::<Int64Type>()
+            .write_batch(rows.timestamps, None, None)
+            .unwrap();
+        col.close().unwrap();
+
+        for values in [rows.bids, rows.asks] {
+            let mut col = row_group.next_column().unwrap().unwrap();
+            col.typed::<DoubleType>()
+                .write_batch(values, None, None)
+                .unwrap();
+            col.close().unwrap();
+        }
+
+        if let Some(sizes) = rows.sizes {
+            for _ in 0..2 {
+                let mut col = row_group.next_column().unwrap().unwrap();
+                col.typed::<Int64Type>()
+                    .write_batch(sizes, None, None)
+                    .unwrap();
+                col.close().unwrap();
+            }
+        }
+
+        row_group.close().unwrap();
+        writer.close().unwrap();
+        file
+    }
+
+    fn import(file: &NamedTempFile) -> (ImportSummary, Vec<Tick>) {
+        let db = Database::new_memory().unwrap();
+        let mut importer = ParquetImporter::new(db);
+        let summary = importer.import_file(file.path()).unwrap();
+        let ticks = importer
+            .database
+            .query_ticks(
+                "EURUSD",
+                chrono::DateTime::from_timestamp_millis(0).unwrap(),
+                chrono::DateTime::from_timestamp_millis(4_102_444_800_000).unwrap(),
+            )
+            .unwrap();
+        (summary, ticks)
+    }
+
+    #[test]
+    fn test_import_epoch_millis_with_sizes() {
+        let file = write_parquet(
+            &Rows {
+                symbols: &["EURUSD", "EURUSD", "EURUSD"],
+                timestamps: &[1704067200000, 1704067201000, 1704067202000],
+                bids: &[1.0921, 1.0922, -1.0],
+                asks: &[1.0923, 1.0924, 1.0922],
+                sizes: Some(&[1_000_000, 500_000, 250_000]),
+            },
+            None,
+        );
+
+        let (summary, ticks) = import(&file);
+        assert_eq!(summary.total_rows, 3);
+        assert_eq!(summary.rows_imported, 2);
+        assert_eq!(summary.rows_skipped, 1);
+        assert_eq!(summary.rejections.get("negative_price"), Some(&1));
+        assert!(summary.errors[0].starts_with("Row 3"));
+
+        assert_eq!(ticks.len(), 2);
+        assert_eq!(ticks[0].timestamp, 1704067200000);
+        assert_eq!(ticks[1].bid_size, Some(500_000));
+    }
+
+    #[test]
+    fn test_import_timestamp_logical_type_without_sizes() {
+        let file = write_parquet(
+            &Rows {
+                symbols: &["EURUSD", "EURUSD"],
+                timestamps: &[1704067200000000, 1704067201500000],
+                bids: &[1.0921, 1.0922],
+                asks: &[1.0923, 1.0924],
+                sizes: None,
+            },
+            Some(LogicalType::Timestamp {
+                is_adjusted_to_u_t_c: true,
+                unit: TimeUnit::MICROS(Default::default()),
+            }),
+        );
+
+        let (summary, ticks) = import(&file);
+        assert_eq!(summary.rows_imported, 2);
+        assert_eq!(summary.success_rate(), 100.0);
+        assert_eq!(ticks[1].timestamp, 1704067201500);
+        assert_eq!(ticks[0].bid_size, None);
+        assert_eq!(ticks[0].ask_size, None);
+    }
+}
@@ -16,6 +16,67 @@ pub enum ValidationError {
 
     #[error("Invalid spread: bid ({bid}) > ask ({ask})")]
     InvalidSpread { bid: f64, ask: f64 },
+
+    #[error("Crossed or locked quote: bid ({bid}) >= ask ({ask})")]
+    CrossedQuote { bid: f64, ask: f64 },
+
+    #[error("Spread too wide: {ratio} of mid exceeds {max}")]
+    SpreadTooWide { ratio: f64, max: f64 },
+}
+
+impl ValidationError {
+    /// Short stable name of the rule that failed, for per-reason counts
+    pub fn reason(&self) -> &'static str {
+        match self {
+            ValidationError::MissingField(_) => "missing_field",
+            ValidationError::InvalidValue { .. } => "invalid_value",
+            ValidationError::InvalidTimestamp(_) => "invalid_timestamp",
+            ValidationError::NegativePrice { .. } => "negative_price",
+            ValidationError::InvalidSpread { .. } => "invalid_spread",
+            ValidationError::CrossedQuote { .. } => "crossed_quote",
+            ValidationError::SpreadTooWide { .. } => "spread_too_wide",
+        }
+    }
+}
+
+/// Optional tick rules on top of the basic checks.
+///
+/// The default enables neither, matching the original validation: crossed
+/// quotes pass unless the bid is more than 10% above the ask.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ValidationConfig {
+    /// Reject ticks with `bid >= ask`
+    pub reject_crossed: bool,
+    /// Reject ticks whose `(ask - bid) / mid` exceeds this ratio
+    pub max_spread_ratio: Option<f64>,
+}
+
+impl ValidationConfig {
+    pub fn with_reject_crossed(mut self, reject: bool) -> Self {
+        self.reject_crossed = reject;
+        self
+    }
+
+    /// E.g. `0.01` rejects spreads wider than 1% of the mid price. Fails
+    /// for a negative or NaN ratio, which no tick could pass.
+    pub fn with_max_spread_ratio(mut self, ratio: f64) -> Result<Self, ValidationError> {
+        self.max_spread_ratio = Some(ratio);
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// Check the rules themselves, before any tick is validated against them
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(ratio) = self.max_spread_ratio {
+            if ratio.is_nan() || ratio < 0.0 {
+                return Err(ValidationError::InvalidValue {
+                    field: "max_spread_ratio".to_string(),
+                    reason: format!("{} is not a non-negative ratio", ratio),
+                });
+            }
+        }
+        Ok(())
+    }
 }
 
 pub fn validate_tick_data(
@@ -23,6 +84,8 @@ pub fn validate_tick_data(
     timestamp: Option<&str>,
     bid: Option<f64>,
     ask: Option<f64>,
+    last: Option<f64>,
+    config: &ValidationConfig,
 ) -> Result<(), ValidationError> {
     // Validate required fields
     if symbol.is_none() || symbol == Some("") {
@@ -44,6 +107,16 @@ pub fn validate_tick_data(
         });
     }
 
+    // The last trade price is optional, but when present it must be a real price
+    if let Some(last_val) = last {
+        if !last_val.is_finite() || last_val <= 0.0 {
+            return Err(ValidationError::InvalidValue {
+                field: "last".to_string(),
+                reason: format!("{} is not a positive price", last_val),
+            });
+        }
+    }
+
     // Validate spread (bid should be less than ask in normal conditions)
     // Note: We allow bid >= ask for crossed markets, but flag extreme cases
     if bid_val > ask_val * 1.1 {
@@ -54,6 +127,20 @@ pub fn validate_tick_data(
         });
     }
 
+    if config.reject_crossed && bid_val >= ask_val {
+        return Err(ValidationError::CrossedQuote {
+            bid: bid_val,
+            ask: ask_val,
+        });
+    }
+
+    if let Some(max) = config.max_spread_ratio {
+        let ratio = (ask_val - bid_val) / ((bid_val + ask_val) / 2.0);
+        if ratio > max {
+            return Err(ValidationError::SpreadTooWide { ratio, max });
+        }
+    }
+
     Ok(())
 }
 
@@ -68,6 +155,8 @@ mod tests {
             Some("2024-01-01T00:00:00Z"),
             Some(1.0921),
             Some(1.0923),
+            None,
+            &ValidationConfig::default(),
         );
         assert!(result.is_ok());
     }
@@ -79,6 +168,8 @@ mod tests {
             Some("2024-01-01T00:00:00Z"),
             Some(1.0921),
             Some(1.0923),
+            None,
+            &ValidationConfig::default(),
         );
         assert!(matches!(result, Err(ValidationError::MissingField(_))));
     }
@@ -90,6 +181,8 @@ mod tests {
             Some("2024-01-01T00:00:00Z"),
             Some(-1.0921),
             Some(1.0923),
+            None,
+            &ValidationConfig::default(),
         );
         assert!(matches!(result, Err(ValidationError::NegativePrice { .. })));
     }
@@ -101,7 +194,95 @@ mod tests {
             Some("2024-01-01T00:00:00Z"),
             Some(2.0),
             Some(1.0),
+            None,
+            &ValidationConfig::default(),
         );
         assert!(matches!(result, Err(ValidationError::InvalidSpread { .. })));
     }
+
+    fn validate_quote(bid: f64, ask: f64, config: ValidationConfig) -> Result<(), ValidationError> {
+        validate_tick_data(
+            Some("EURUSD"),
+            Some("2024-01-01T00:00:00Z"),
+            Some(bid),
+            Some(ask),
+            None,
+            &config,
+        )
+    }
+
+    #[test]
+    fn test_crossed_quotes() {
+        // Allowed by default, as before
+        assert!(validate_quote(1.0923, 1.0921, ValidationConfig::default()).is_ok());
+
+        let strict = ValidationConfig::default().with_reject_crossed(true);
+        for (bid, ask) in [(1.0923, 1.0921), (1.0922, 1.0922)] {
+            let error = validate_quote(bid, ask, strict).unwrap_err();
+            assert!(matches!(error, ValidationError::CrossedQuote { .. }));
+            assert_eq!(error.reason(), "crossed_quote");
+        }
+        assert!(validate_quote(1.0921, 1.0923, strict).is_ok());
+    }
+
+    #[test]
+    fn test_max_spread() {
+        let config = ValidationConfig::default()
+            .with_max_spread_ratio(0.001)
+            .unwrap();
+        // About 2 pips on 1.09 is well under 0.1%
+        assert!(validate_quote(1.0921, 1.0923, config).is_ok());
+
+        let error = validate_quote(1.0900, 1.1000, config).unwrap_err();
+        match error {
+            ValidationError::SpreadTooWide { ratio, max } => {
+                assert!((ratio - 0.01 / 1.095).abs() < 1e-12);
+                assert_eq!(max, 0.001);
+            }
+            other => panic!("expected SpreadTooWide, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_negative_max_spread_rejected() {
+        for ratio in [-0.01, f64::NAN] {
+            let error = ValidationConfig::default()
+                .with_max_spread_ratio(ratio)
+                .unwrap_err();
+            assert!(matches!(error, ValidationError::InvalidValue { .. }));
+        }
+
+        // Set directly, the bad ratio is still caught before any tick
+        let config = ValidationConfig {
+            max_spread_ratio: Some(-0.01),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+        assert!(ValidationConfig::default()
+            .with_max_spread_ratio(0.0)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_last_price_must_be_positive() {
+        let validate_last = |last| {
+            validate_tick_data(
+                Some("EURUSD"),
+                Some("2024-01-01T00:00:00Z"),
+                Some(1.0921),
+                Some(1.0923),
+                Some(last),
+                &ValidationConfig::default(),
+            )
+        };
+
+        assert!(validate_last(1.0922).is_ok());
+        for last in [f64::NAN, f64::INFINITY, 0.0, -1.0922] {
+            let error = validate_last(last).unwrap_err();
+            assert!(
+                matches!(error, ValidationError::InvalidValue { ref field, .. } if field == "last")
+            );
+            assert_eq!(error.reason(), "invalid_value");
+        }
+    }
 }
@@ -1,14 +1,26 @@
 pub mod aggregation;
 pub mod database;
+pub mod export;
 pub mod import;
 pub mod migration;
 pub mod models;
 pub mod query;
 pub mod storage;
+pub mod synthetic;
 pub mod timeframe;
 
-pub use aggregation::{BarAggregator, TickToBarAggregator};
-pub use database::{Database, DatabaseError, Result};
-pub use import::{CsvImporter, ImportError, ImportSummary};
-pub use models::{Bar, Tick};
-pub use timeframe::Timeframe;
+pub use aggregation::{
+    AggregationSummary, BarAggregator, BarMaterializer, BarPriceSource, BarReplayer,
+    BarValidationStats, HeikinAshiTransformer, IntrabarPath, InvalidBarPolicy, RangeBarAggregator,
+    RenkoAggregator, TickToBarAggregator,
+};
+pub use database::{Database, DatabaseError, Page, Result};
+pub use export::{ExportFormat, ExportSummary, Exporter};
+pub use import::{
+    CsvImporter, ImportError, ImportSummary, ParquetImporter, ValidationConfig, ValidationError,
+};
+pub use migration::{Migration, MigrationReport};
+pub use models::{Bar, PricePrecision, Tick};
+pub use query::{BarQuery, TickQuery};
+pub use synthetic::{MicrostructureJitter, SyntheticMarket, SyntheticSeries};
+pub use timeframe::{Timeframe, WeekStart};
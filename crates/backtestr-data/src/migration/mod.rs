@@ -1 +1,268 @@
-pub struct Placeholder;
+use crate::database::schema::{
+    BAR_INDEX_SCHEMA, BAR_TABLE_SCHEMA, TICK_IDENTITY_INDEX_SCHEMA, TICK_INDEX_SCHEMA,
+    TICK_TABLE_SCHEMA, VERSION_TABLE_SCHEMA,
+};
+use crate::database::{DatabaseError, Result};
+use rusqlite::Connection;
+
+/// One forward step of the schema
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    pub up: fn(&Connection) -> rusqlite::Result<()>,
+}
+
+/// Every migration this build knows, in version order. Versions 1 and 2
+/// are the ticks and bars tables that files created before the runner
+/// already record in `db_version`; later versions only alter them.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "ticks table",
+        up: |conn| {
+            conn.execute(TICK_TABLE_SCHEMA, [])?;
+            conn.execute(TICK_INDEX_SCHEMA, [])?;
+            Ok(())
+        },
+    },
+    Migration {
+        version: 2,
+        description: "bars table",
+        up: |conn| {
+            conn.execute(BAR_TABLE_SCHEMA, [])?;
+            conn.execute(BAR_INDEX_SCHEMA, [])?;
+            Ok(())
+        },
+    },
+    Migration {
+        version: 3,
+        description: "last trade price and size on ticks",
+        up: |conn| {
+            conn.execute("ALTER TABLE ticks ADD COLUMN last REAL", [])?;
+            conn.execute("ALTER TABLE ticks ADD COLUMN last_size INTEGER", [])?;
+            Ok(())
+        },
+    },
+    Migration {
+        version: 4,
+        description: "ticks unique on every field instead of symbol and timestamp",
+        up: |conn| {
+            // SQLite can't drop a table constraint, so rebuild the table
+            conn.execute_batch(
+                "CREATE TABLE ticks_v4 (
+                     id INTEGER PRIMARY KEY AUTOINCREMENT,
+                     symbol TEXT NOT NULL,
+                     timestamp INTEGER NOT NULL,
+                     bid REAL NOT NULL,
+                     ask REAL NOT NULL,
+                     bid_size INTEGER,
+                     ask_size INTEGER,
+                     last REAL,
+                     last_size INTEGER
+                 );
+                 INSERT INTO ticks_v4
+                 SELECT id, symbol, timestamp, bid, ask, bid_size, ask_size, last, last_size
+                 FROM ticks;
+                 DROP TABLE ticks;
+                 ALTER TABLE ticks_v4 RENAME TO ticks;",
+            )?;
+            conn.execute(TICK_INDEX_SCHEMA, [])?;
+            conn.execute(TICK_IDENTITY_INDEX_SCHEMA, [])?;
+            Ok(())
+        },
+    },
+];
+
+/// Newest schema version this build can operate on
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationReport {
+    pub from_version: u32,
+    pub to_version: u32,
+    /// Versions applied by this run, oldest first
+    pub applied: Vec<u32>,
+}
+
+impl MigrationReport {
+    pub fn is_up_to_date(&self) -> bool {
+        self.applied.is_empty()
+    }
+}
+
+/// Version recorded in the file, 0 for a fresh database
+pub fn current_version(conn: &Connection) -> Result<u32> {
+    conn.execute(VERSION_TABLE_SCHEMA, [])?;
+    let version: Option<u32> =
+        conn.query_row("SELECT MAX(version) FROM db_version", [], |row| row.get(0))?;
+    Ok(version.unwrap_or(0))
+}
+
+/// Apply every pending migration in one transaction.
+///
+/// Fails without touching the file if it was written by a newer build.
+pub(crate) fn run_pending(conn: &Connection, migrations: &[Migration]) -> Result<MigrationReport> {
+    let from_version = current_version(conn)?;
+    let supported = migrations.last().map_or(0, |m| m.version);
+    if from_version > supported {
+        return Err(DatabaseError::UnsupportedSchemaVersion {
+            found: from_version,
+            supported,
+        });
+    }
+
+    let pending: Vec<&Migration> = migrations
+        .iter()
+        .filter(|m| m.version > from_version)
+        .collect();
+    if pending.is_empty() {
+        return Ok(MigrationReport {
+            from_version,
+            to_version: from_version,
+            applied: Vec::new(),
+        });
+    }
+
+    // Callers only hold a shared connection, and nothing else runs a
+    // transaction on it while the schema is being set up
+    let tx = conn.unchecked_transaction()?;
+    let mut applied = Vec::with_capacity(pending.len());
+    for migration in pending {
+        (migration.up)(&tx).map_err(|e| {
+            DatabaseError::MigrationError(format!(
+                "v{} ({}): {}",
+                migration.version, migration.description, e
+            ))
+        })?;
+        tx.execute(
+            "INSERT OR IGNORE INTO db_version (version) VALUES (?)",
+            [migration.version],
+        )?;
+        applied.push(migration.version);
+    }
+    tx.commit()?;
+
+    Ok(MigrationReport {
+        from_version,
+        to_version: *applied.last().unwrap_or(&from_version),
+        applied,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_is_ordered() {
+        assert!(MIGRATIONS.windows(2).all(|w| w[0].version < w[1].version));
+        assert_eq!(latest_version(), 4);
+    }
+
+    #[test]
+    fn test_run_pending_applies_only_new_versions() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+
+        let report = run_pending(&conn, &MIGRATIONS[..1])?;
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.applied, vec![1]);
+
+        let report = run_pending(&conn, MIGRATIONS)?;
+        assert_eq!(report.from_version, 1);
+        assert_eq!(report.to_version, 4);
+        assert_eq!(report.applied, vec![2, 3, 4]);
+
+        assert!(run_pending(&conn, MIGRATIONS)?.is_up_to_date());
+        Ok(())
+    }
+
+    #[test]
+    fn test_existing_ticks_read_back_without_last() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        run_pending(&conn, &MIGRATIONS[..2])?;
+        conn.execute(
+            "INSERT INTO ticks (symbol, timestamp, bid, ask) VALUES ('EURUSD', 1, 1.1, 1.1002)",
+            [],
+        )?;
+
+        run_pending(&conn, MIGRATIONS)?;
+        let tick = conn.query_row(
+            "SELECT id, symbol, timestamp, bid, ask, bid_size, ask_size, last, last_size
+             FROM ticks",
+            [],
+            crate::database::tick_from_row,
+        )?;
+        assert_eq!((tick.last, tick.last_size), (None, None));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ticks_sharing_a_timestamp_survive_v4() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        run_pending(&conn, &MIGRATIONS[..3])?;
+        conn.execute(
+            "INSERT INTO ticks (symbol, timestamp, bid, ask) VALUES ('EURUSD', 1, 1.1, 1.1002)",
+            [],
+        )?;
+
+        run_pending(&conn, MIGRATIONS)?;
+        let insert = "INSERT OR IGNORE INTO ticks (symbol, timestamp, bid, ask, bid_size)
+                      VALUES ('EURUSD', 1, ?, 1.1002, ?)";
+        // A new price at the same timestamp is stored...
+        assert_eq!(
+            conn.execute(insert, rusqlite::params![1.1001, None::<i64>])?,
+            1
+        );
+        // ...an exact repeat, NULL sizes included, is not...
+        assert_eq!(
+            conn.execute(insert, rusqlite::params![1.1, None::<i64>])?,
+            0
+        );
+        // ...and a size change alone is a tick of its own
+        assert_eq!(conn.execute(insert, rusqlite::params![1.1, Some(5)])?, 1);
+
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM ticks", [], |row| row.get(0))?;
+        assert_eq!(count, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_migration_rolls_back() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        let broken = [
+            MIGRATIONS[0],
+            Migration {
+                version: 2,
+                description: "broken",
+                up: |conn| conn.execute_batch("CREATE TABLE nope (;"),
+            },
+        ];
+
+        assert!(matches!(
+            run_pending(&conn, &broken),
+            Err(DatabaseError::MigrationError(_))
+        ));
+        // Version 1 was part of the same transaction
+        assert_eq!(current_version(&conn)?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_newer_schema_is_refused() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        run_pending(&conn, MIGRATIONS)?;
+        conn.execute("INSERT INTO db_version (version) VALUES (99)", [])?;
+
+        match run_pending(&conn, MIGRATIONS) {
+            Err(DatabaseError::UnsupportedSchemaVersion { found, supported }) => {
+                assert_eq!(found, 99);
+                assert_eq!(supported, latest_version());
+            }
+            other => panic!("expected UnsupportedSchemaVersion, got {:?}", other),
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use super::precision::round_price;
 use crate::timeframe::Timeframe;
 use serde::{Deserialize, Serialize};
 
@@ -53,6 +54,15 @@ impl Bar {
         self
     }
 
+    /// Round open, high, low and close to `digits` decimal places
+    pub fn round_to(mut self, digits: u8) -> Self {
+        self.open = round_price(self.open, digits);
+        self.high = round_price(self.high, digits);
+        self.low = round_price(self.low, digits);
+        self.close = round_price(self.close, digits);
+        self
+    }
+
     pub fn midpoint(&self) -> f64 {
         (self.high + self.low) / 2.0
     }
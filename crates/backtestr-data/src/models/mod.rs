@@ -1,5 +1,7 @@
 mod bar;
+mod precision;
 mod tick;
 
 pub use bar::Bar;
+pub use precision::{round_price, PricePrecision};
 pub use tick::Tick;
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+/// Round `value` to `digits` decimal places
+pub fn round_price(value: f64, digits: u8) -> f64 {
+    let scale = 10f64.powi(digits as i32);
+    (value * scale).round() / scale
+}
+
+/// Number of quoted decimal digits per symbol (EURUSD=5, USDJPY=3, ...)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PricePrecision {
+    pub(crate) digits: HashMap<String, u8>,
+}
+
+impl PricePrecision {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_symbol(mut self, symbol: &str, digits: u8) -> Self {
+        self.digits.insert(symbol.to_string(), digits);
+        self
+    }
+
+    pub fn digits(&self, symbol: &str) -> Option<u8> {
+        self.digits.get(symbol).copied()
+    }
+
+    /// Round `value` for `symbol`, leaving it unchanged if no precision is set
+    pub fn round(&self, symbol: &str, value: f64) -> f64 {
+        match self.digits(symbol) {
+            Some(digits) => round_price(value, digits),
+            None => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_price_removes_float_noise() {
+        assert_eq!(round_price(1.09230000000002, 5), 1.0923);
+        assert_eq!(round_price(149.2564999, 3), 149.256);
+        assert_eq!(round_price(1.092155, 5), 1.09216);
+    }
+
+    #[test]
+    fn test_precision_by_symbol() {
+        let precision = PricePrecision::new()
+            .with_symbol("EURUSD", 5)
+            .with_symbol("USDJPY", 3);
+
+        assert_eq!(precision.digits("USDJPY"), Some(3));
+        assert_eq!(precision.round("USDJPY", 149.25649), 149.256);
+        // Unknown symbols pass through untouched
+        assert_eq!(precision.round("GBPUSD", 1.2700000001), 1.2700000001);
+    }
+
+    #[test]
+    fn test_tick_and_bar_round_to() {
+        use crate::models::{Bar, Tick};
+        use crate::timeframe::Timeframe;
+
+        let tick = Tick::new_with_millis("EURUSD".to_string(), 0, 1.09230000000002, 1.0925000001)
+            .round_to(5);
+        assert_eq!((tick.bid, tick.ask), (1.0923, 1.0925));
+
+        let bar = Bar::new(
+            "USDJPY".to_string(),
+            Timeframe::M1,
+            0,
+            60_000,
+            149.2504,
+            149.30000001,
+            149.1999,
+            149.2555,
+        )
+        .round_to(3);
+        assert_eq!(
+            (bar.open, bar.high, bar.low, bar.close),
+            (149.25, 149.3, 149.2, 149.256)
+        );
+    }
+}
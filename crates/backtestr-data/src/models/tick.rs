@@ -1,3 +1,4 @@
+use super::precision::round_price;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -10,6 +11,10 @@ pub struct Tick {
     pub ask: f64,
     pub bid_size: Option<i64>,
     pub ask_size: Option<i64>,
+    /// Last trade price, for feeds that report trades alongside quotes
+    pub last: Option<f64>,
+    /// Size of the last trade
+    pub last_size: Option<i64>,
 }
 
 impl Tick {
@@ -22,6 +27,8 @@ impl Tick {
             ask,
             bid_size: None,
             ask_size: None,
+            last: None,
+            last_size: None,
         }
     }
 
@@ -34,6 +41,8 @@ impl Tick {
             ask,
             bid_size: None,
             ask_size: None,
+            last: None,
+            last_size: None,
         }
     }
 
@@ -43,6 +52,21 @@ impl Tick {
         self
     }
 
+    /// Attach the last trade price and, when known, its size
+    pub fn with_last(mut self, last: f64, last_size: Option<i64>) -> Self {
+        self.last = Some(last);
+        self.last_size = last_size;
+        self
+    }
+
+    /// Round bid, ask and last to `digits` decimal places
+    pub fn round_to(mut self, digits: u8) -> Self {
+        self.bid = round_price(self.bid, digits);
+        self.ask = round_price(self.ask, digits);
+        self.last = self.last.map(|last| round_price(last, digits));
+        self
+    }
+
     pub fn timestamp_as_datetime(&self) -> DateTime<Utc> {
         DateTime::from_timestamp_millis(self.timestamp).unwrap_or_else(Utc::now)
     }
@@ -0,0 +1,239 @@
+use super::Filters;
+use crate::database::{bar_from_row, Database, DatabaseError, Result};
+use crate::models::Bar;
+use crate::timeframe::Timeframe;
+use chrono::{DateTime, Utc};
+
+const SELECT: &str = "SELECT id, symbol, timeframe, timestamp_start, timestamp_end,
+                      open, high, low, close, volume, tick_count FROM bars";
+
+/// Fluent bar query, oldest first. The bar counterpart of `TickQuery`;
+/// time bounds apply to `timestamp_start`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BarQuery {
+    symbol: Option<String>,
+    timeframe: Option<Timeframe>,
+    start: Option<i64>,
+    end: Option<i64>,
+    min_volume: Option<i64>,
+    min_range: Option<f64>,
+    limit: Option<usize>,
+}
+
+impl BarQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn symbol(mut self, symbol: &str) -> Self {
+        self.symbol = Some(symbol.to_string());
+        self
+    }
+
+    pub fn timeframe(mut self, timeframe: Timeframe) -> Self {
+        self.timeframe = Some(timeframe);
+        self
+    }
+
+    /// Bars with `start <= timestamp_start <= end`
+    pub fn between(self, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        self.since(start).until(end)
+    }
+
+    /// Bars starting at or after `start`
+    pub fn since(mut self, start: DateTime<Utc>) -> Self {
+        self.start = Some(start.timestamp_millis());
+        self
+    }
+
+    /// Bars starting at or before `end`
+    pub fn until(mut self, end: DateTime<Utc>) -> Self {
+        self.end = Some(end.timestamp_millis());
+        self
+    }
+
+    /// Bars with at least `volume`; bars without a volume never match
+    pub fn min_volume(mut self, volume: i64) -> Self {
+        self.min_volume = Some(volume);
+        self
+    }
+
+    /// Bars whose high minus low is at least `range`
+    pub fn min_range(mut self, range: f64) -> Self {
+        self.min_range = Some(range);
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Run the query. Fails without a symbol: the bars index leads with
+    /// it, so any other query would scan the whole table. A query matching
+    /// nothing returns no bars.
+    pub fn execute(&self, db: &Database) -> Result<Vec<Bar>> {
+        let (sql, filters) = self.build()?;
+        filters.execute(db, &sql, bar_from_row)
+    }
+
+    fn build(&self) -> Result<(String, Filters)> {
+        if self.symbol.is_none() {
+            return Err(DatabaseError::InvalidParameter(
+                "bar query needs a symbol".to_string(),
+            ));
+        }
+        if let Some(range) = self.min_range.filter(|range| !range.is_finite()) {
+            return Err(DatabaseError::InvalidParameter(format!(
+                "range must be finite, got {}",
+                range
+            )));
+        }
+
+        let mut filters = Filters::default();
+        if let Some(symbol) = &self.symbol {
+            filters.push("symbol = ?", symbol.clone());
+        }
+        if let Some(timeframe) = self.timeframe {
+            filters.push("timeframe = ?", timeframe.as_str().to_string());
+        }
+        if let Some(start) = self.start {
+            filters.push("timestamp_start >= ?", start);
+        }
+        if let Some(end) = self.end {
+            filters.push("timestamp_start <= ?", end);
+        }
+        if let Some(volume) = self.min_volume {
+            filters.push("volume >= ?", volume);
+        }
+        if let Some(range) = self.min_range {
+            filters.push("high - low >= ?", range);
+        }
+
+        let sql = filters.sql(SELECT, "timestamp_start", self.limit);
+        Ok((sql, filters))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const T0: i64 = 1704186000000;
+
+    fn at(offset_mins: i64) -> DateTime<Utc> {
+        Utc.timestamp_millis_opt(T0 + offset_mins * 60_000).unwrap()
+    }
+
+    fn seeded() -> Database {
+        let mut db = Database::new_memory().unwrap();
+        let mut bars = Vec::new();
+        for i in 0..10 {
+            let start = T0 + i * 60_000;
+            let high = if i < 5 { 1.1005 } else { 1.1020 };
+            bars.push(
+                Bar::new(
+                    "EURUSD".to_string(),
+                    Timeframe::M1,
+                    start,
+                    start + 60_000,
+                    1.1000,
+                    high,
+                    1.0995,
+                    1.1002,
+                )
+                .with_volume(i * 100),
+            );
+        }
+        bars.push(Bar::new(
+            "EURUSD".to_string(),
+            Timeframe::H1,
+            T0,
+            T0 + 3_600_000,
+            1.1000,
+            1.1020,
+            1.0995,
+            1.1002,
+        ));
+        db.batch_insert_bars(&bars).unwrap();
+        db
+    }
+
+    #[test]
+    fn test_filters_compose() {
+        let db = seeded();
+
+        let bars = BarQuery::new()
+            .symbol("EURUSD")
+            .timeframe(Timeframe::M1)
+            .between(at(2), at(8))
+            .min_volume(400)
+            .min_range(0.0020)
+            .limit(2)
+            .execute(&db)
+            .unwrap();
+        assert_eq!(
+            bars.iter().map(|b| b.timestamp_start).collect::<Vec<_>>(),
+            vec![at(5).timestamp_millis(), at(6).timestamp_millis()]
+        );
+
+        // Without a timeframe both M1 and H1 bars match
+        let first = BarQuery::new()
+            .symbol("EURUSD")
+            .until(at(0))
+            .execute(&db)
+            .unwrap();
+        assert_eq!(first.len(), 2);
+    }
+
+    #[test]
+    fn test_requires_symbol() {
+        let db = seeded();
+        assert!(matches!(
+            BarQuery::new().timeframe(Timeframe::M1).execute(&db),
+            Err(DatabaseError::InvalidParameter(_))
+        ));
+        // A time bound alone would still scan every symbol's bars
+        assert!(matches!(
+            BarQuery::new().between(at(0), at(5)).execute(&db),
+            Err(DatabaseError::InvalidParameter(_))
+        ));
+        assert!(BarQuery::new()
+            .symbol("EURUSD")
+            .since(at(0))
+            .timeframe(Timeframe::D1)
+            .execute(&db)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_uses_index() {
+        let db = seeded();
+        let (sql, filters) = BarQuery::new()
+            .symbol("EURUSD")
+            .timeframe(Timeframe::M1)
+            .between(at(0), at(5))
+            .build()
+            .unwrap();
+
+        let conn = db.reader().unwrap();
+        let mut stmt = conn
+            .prepare(&format!("EXPLAIN QUERY PLAN {}", sql))
+            .unwrap();
+        let plan = stmt
+            .query_map(rusqlite::params_from_iter(filters.params), |row| {
+                row.get::<_, String>(3)
+            })
+            .unwrap()
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .unwrap()
+            .join("\n");
+        assert!(
+            plan.contains("idx_bars_symbol_timeframe_timestamp"),
+            "{}",
+            plan
+        );
+    }
+}
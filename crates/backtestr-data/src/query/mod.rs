@@ -1 +1,62 @@
-pub struct Placeholder;
+//! Composable tick and bar queries.
+//!
+//! Each builder collects filters and runs them as one parameterized
+//! statement, so new filters do not need a bespoke `Database::query_*`
+//! method.
+
+mod bar_query;
+mod tick_query;
+
+pub use bar_query::BarQuery;
+pub use tick_query::TickQuery;
+
+use crate::database::{Database, DatabaseError, Result};
+use rusqlite::types::Value;
+
+/// WHERE clause and parameters collected by a builder
+#[derive(Debug, Default)]
+struct Filters {
+    conditions: Vec<&'static str>,
+    params: Vec<Value>,
+}
+
+impl Filters {
+    fn push(&mut self, condition: &'static str, value: impl Into<Value>) {
+        self.conditions.push(condition);
+        self.params.push(value.into());
+    }
+
+    fn sql(&self, select: &str, order_by: &str, limit: Option<usize>) -> String {
+        let mut sql = select.to_string();
+        if !self.conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&self.conditions.join(" AND "));
+        }
+        sql.push_str(" ORDER BY ");
+        sql.push_str(order_by);
+        if let Some(limit) = limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+        sql
+    }
+
+    fn execute<T>(
+        self,
+        db: &Database,
+        sql: &str,
+        from_row: fn(&rusqlite::Row) -> rusqlite::Result<T>,
+    ) -> Result<Vec<T>> {
+        let conn = db.reader()?;
+        let mut stmt = conn
+            .prepare_cached(sql)
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(self.params), from_row)
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+
+        Ok(rows)
+    }
+}
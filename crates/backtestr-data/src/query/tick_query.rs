@@ -0,0 +1,231 @@
+use super::Filters;
+use crate::database::{tick_from_row, Database, DatabaseError, Result};
+use crate::models::Tick;
+use chrono::{DateTime, Utc};
+
+const SELECT: &str = "SELECT id, symbol, timestamp, bid, ask, bid_size, ask_size, last, last_size
+                      FROM ticks";
+
+/// Fluent tick query, oldest first.
+///
+/// ```no_run
+/// # use backtestr_data::{Database, query::TickQuery};
+/// # use chrono::{TimeZone, Utc};
+/// # let db = Database::new_memory().unwrap();
+/// let start = Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap();
+/// let end = Utc.with_ymd_and_hms(2024, 1, 3, 0, 0, 0).unwrap();
+/// let ticks = TickQuery::new()
+///     .symbol("EURUSD")
+///     .between(start, end)
+///     .min_spread(0.0002)
+///     .limit(1000)
+///     .execute(&db)
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TickQuery {
+    symbol: Option<String>,
+    start: Option<i64>,
+    end: Option<i64>,
+    min_spread: Option<f64>,
+    max_spread: Option<f64>,
+    limit: Option<usize>,
+}
+
+impl TickQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn symbol(mut self, symbol: &str) -> Self {
+        self.symbol = Some(symbol.to_string());
+        self
+    }
+
+    /// Ticks with `start <= timestamp <= end`
+    pub fn between(self, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        self.since(start).until(end)
+    }
+
+    /// Ticks at or after `start`
+    pub fn since(mut self, start: DateTime<Utc>) -> Self {
+        self.start = Some(start.timestamp_millis());
+        self
+    }
+
+    /// Ticks at or before `end`
+    pub fn until(mut self, end: DateTime<Utc>) -> Self {
+        self.end = Some(end.timestamp_millis());
+        self
+    }
+
+    /// Ticks whose ask minus bid is at least `spread`
+    pub fn min_spread(mut self, spread: f64) -> Self {
+        self.min_spread = Some(spread);
+        self
+    }
+
+    /// Ticks whose ask minus bid is at most `spread`
+    pub fn max_spread(mut self, spread: f64) -> Self {
+        self.max_spread = Some(spread);
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Run the query. Fails without a symbol or time bound, which would
+    /// scan the whole table; a query matching nothing returns no ticks.
+    pub fn execute(&self, db: &Database) -> Result<Vec<Tick>> {
+        let (sql, filters) = self.build()?;
+        filters.execute(db, &sql, tick_from_row)
+    }
+
+    fn build(&self) -> Result<(String, Filters)> {
+        if self.symbol.is_none() && self.start.is_none() && self.end.is_none() {
+            return Err(DatabaseError::InvalidParameter(
+                "tick query needs a symbol or time bound".to_string(),
+            ));
+        }
+        for spread in [self.min_spread, self.max_spread].into_iter().flatten() {
+            if !spread.is_finite() {
+                return Err(DatabaseError::InvalidParameter(format!(
+                    "spread must be finite, got {}",
+                    spread
+                )));
+            }
+        }
+
+        let mut filters = Filters::default();
+        if let Some(symbol) = &self.symbol {
+            filters.push("symbol = ?", symbol.clone());
+        }
+        if let Some(start) = self.start {
+            filters.push("timestamp >= ?", start);
+        }
+        if let Some(end) = self.end {
+            filters.push("timestamp <= ?", end);
+        }
+        if let Some(spread) = self.min_spread {
+            filters.push("ask - bid >= ?", spread);
+        }
+        if let Some(spread) = self.max_spread {
+            filters.push("ask - bid <= ?", spread);
+        }
+
+        let sql = filters.sql(SELECT, "timestamp", self.limit);
+        Ok((sql, filters))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const T0: i64 = 1704186000000;
+
+    fn at(offset_secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_millis_opt(T0 + offset_secs * 1000).unwrap()
+    }
+
+    fn seeded() -> Database {
+        let db = Database::new_memory().unwrap();
+        let mut ticks = Vec::new();
+        for i in 0..10 {
+            let spread = if i % 2 == 0 { 0.0001 } else { 0.0005 };
+            ticks.push(Tick::new_with_millis(
+                "EURUSD".to_string(),
+                T0 + i * 1000,
+                1.1000,
+                1.1000 + spread,
+            ));
+            ticks.push(Tick::new_with_millis(
+                "GBPUSD".to_string(),
+                T0 + i * 1000,
+                1.2700,
+                1.2702,
+            ));
+        }
+        db.insert_ticks(&ticks).unwrap();
+        db
+    }
+
+    #[test]
+    fn test_filters_compose() {
+        let db = seeded();
+
+        let ticks = TickQuery::new()
+            .symbol("EURUSD")
+            .between(at(2), at(7))
+            .min_spread(0.0003)
+            .execute(&db)
+            .unwrap();
+        assert_eq!(
+            ticks.iter().map(|t| t.timestamp).collect::<Vec<_>>(),
+            vec![T0 + 3000, T0 + 5000, T0 + 7000]
+        );
+
+        let limited = TickQuery::new()
+            .symbol("EURUSD")
+            .max_spread(0.0002)
+            .limit(2)
+            .execute(&db)
+            .unwrap();
+        assert_eq!(
+            limited.iter().map(|t| t.timestamp).collect::<Vec<_>>(),
+            vec![T0, T0 + 2000]
+        );
+
+        // A time bound alone spans symbols
+        let both = TickQuery::new().between(at(0), at(0)).execute(&db).unwrap();
+        assert_eq!(both.len(), 2);
+    }
+
+    #[test]
+    fn test_requires_symbol_or_time_bound() {
+        let db = seeded();
+        assert!(matches!(
+            TickQuery::new().limit(10).execute(&db),
+            Err(DatabaseError::InvalidParameter(_))
+        ));
+        assert!(TickQuery::new()
+            .symbol("EURUSD")
+            .min_spread(f64::NAN)
+            .execute(&db)
+            .is_err());
+    }
+
+    #[test]
+    fn test_empty_result_is_ok() {
+        let db = seeded();
+        let ticks = TickQuery::new().symbol("USDJPY").execute(&db).unwrap();
+        assert!(ticks.is_empty());
+    }
+
+    #[test]
+    fn test_uses_index() {
+        let db = seeded();
+        let (sql, filters) = TickQuery::new()
+            .symbol("EURUSD")
+            .between(at(0), at(5))
+            .build()
+            .unwrap();
+
+        let conn = db.reader().unwrap();
+        let mut stmt = conn
+            .prepare(&format!("EXPLAIN QUERY PLAN {}", sql))
+            .unwrap();
+        let plan = stmt
+            .query_map(rusqlite::params_from_iter(filters.params), |row| {
+                row.get::<_, String>(3)
+            })
+            .unwrap()
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .unwrap()
+            .join("\n");
+        assert!(plan.contains("USING INDEX"), "{}", plan);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::models::Tick;
+use crate::timeframe::Timeframe;
+
+/// Microstructure imperfections injected into a synthetic tick stream
+#[derive(Debug, Clone, PartialEq)]
+pub struct MicrostructureJitter {
+    /// Maximum bid/ask bounce applied to each tick's mid price
+    pub bounce: f64,
+    /// Probability that a whole bar period has no ticks at all
+    pub missing_bar_probability: f64,
+    /// Probability that a tick is emitted twice with the same timestamp
+    pub duplicate_tick_probability: f64,
+}
+
+impl Default for MicrostructureJitter {
+    fn default() -> Self {
+        Self {
+            bounce: 0.00005,
+            missing_bar_probability: 0.02,
+            duplicate_tick_probability: 0.01,
+        }
+    }
+}
+
+/// Output of a synthetic market run
+#[derive(Debug, Clone, Default)]
+pub struct SyntheticSeries {
+    pub ticks: Vec<Tick>,
+    /// Start timestamps of the bar periods that were skipped
+    pub missing_bars: Vec<i64>,
+    /// Number of ticks that were emitted twice
+    pub duplicate_ticks: usize,
+}
+
+/// Deterministic random-walk tick generator for tests and stress runs.
+///
+/// The same seed and settings always produce the same tick stream, so tests
+/// for the import validator, gap detection and duplicate handling can assert
+/// exact counts.
+#[derive(Debug, Clone)]
+pub struct SyntheticMarket {
+    symbol: String,
+    start_timestamp: i64,
+    base_price: f64,
+    spread: f64,
+    volatility: f64,
+    tick_interval_ms: i64,
+    jitter: Option<MicrostructureJitter>,
+    seed: u64,
+}
+
+impl SyntheticMarket {
+    pub fn new(symbol: String, start_timestamp: i64, base_price: f64) -> Self {
+        Self {
+            symbol,
+            start_timestamp,
+            base_price,
+            spread: 0.0002,
+            volatility: 0.0001,
+            tick_interval_ms: 1_000,
+            jitter: None,
+            seed: 0,
+        }
+    }
+
+    pub fn with_spread(mut self, spread: f64) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    /// Maximum size of a single random-walk step
+    pub fn with_volatility(mut self, volatility: f64) -> Self {
+        self.volatility = volatility;
+        self
+    }
+
+    pub fn with_tick_interval_ms(mut self, interval_ms: i64) -> Self {
+        self.tick_interval_ms = interval_ms.max(1);
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: MicrostructureJitter) -> Self {
+        self.jitter = Some(jitter);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Generate `bar_count` bar periods worth of ticks for `timeframe`
+    pub fn generate(&self, bar_count: usize, timeframe: Timeframe) -> SyntheticSeries {
+        let mut rng = SplitMix64::new(self.seed);
+        let mut series = SyntheticSeries::default();
+
+        let half_spread = self.spread / 2.0;
+        let mut mid = self.base_price;
+        let mut next_bar_start = timeframe.bar_start_timestamp(self.start_timestamp);
+
+        for _ in 0..bar_count {
+            // Step by bar end rather than duration so months keep calendar lengths
+            let bar_start = next_bar_start;
+            next_bar_start = timeframe.bar_end_timestamp(bar_start);
+            let ticks_per_bar = ((next_bar_start - bar_start) / self.tick_interval_ms).max(1);
+
+            let skip_bar = self
+                .jitter
+                .as_ref()
+                .is_some_and(|j| rng.next_f64() < j.missing_bar_probability);
+
+            for tick_index in 0..ticks_per_bar {
+                // The walk keeps moving through a gap so prices resume elsewhere
+                mid += (rng.next_f64() * 2.0 - 1.0) * self.volatility;
+                if skip_bar {
+                    continue;
+                }
+
+                let mut price = mid;
+                if let Some(jitter) = &self.jitter {
+                    price += (rng.next_f64() * 2.0 - 1.0) * jitter.bounce;
+                }
+
+                let tick = Tick::new_with_millis(
+                    self.symbol.clone(),
+                    bar_start + tick_index * self.tick_interval_ms,
+                    price - half_spread,
+                    price + half_spread,
+                );
+
+                let duplicate = self
+                    .jitter
+                    .as_ref()
+                    .is_some_and(|j| rng.next_f64() < j.duplicate_tick_probability);
+                if duplicate {
+                    series.ticks.push(tick.clone());
+                    series.duplicate_ticks += 1;
+                }
+                series.ticks.push(tick);
+            }
+
+            if skip_bar {
+                series.missing_bars.push(bar_start);
+            }
+        }
+
+        series
+    }
+}
+
+/// Small self-contained PRNG so output never changes with dependency versions
+#[derive(Debug, Clone)]
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggregation::TickToBarAggregator;
+
+    const START: i64 = 1704067200000; // 2024-01-01 00:00:00
+
+    fn jittered_market(seed: u64) -> SyntheticMarket {
+        SyntheticMarket::new("EURUSD".to_string(), START, 1.0920)
+            .with_tick_interval_ms(5_000)
+            .with_jitter(MicrostructureJitter {
+                bounce: 0.00005,
+                missing_bar_probability: 0.05,
+                duplicate_tick_probability: 0.02,
+            })
+            .with_seed(seed)
+    }
+
+    fn count_duplicates(ticks: &[Tick]) -> usize {
+        ticks
+            .windows(2)
+            .filter(|w| w[0].timestamp == w[1].timestamp)
+            .count()
+    }
+
+    #[test]
+    fn test_clean_market_has_no_imperfections() {
+        let series = SyntheticMarket::new("EURUSD".to_string(), START, 1.0920)
+            .with_seed(7)
+            .generate(10, Timeframe::M1);
+
+        assert_eq!(series.ticks.len(), 600);
+        assert!(series.missing_bars.is_empty());
+        assert_eq!(series.duplicate_ticks, 0);
+        assert!(series.ticks.iter().all(|t| t.ask > t.bid));
+    }
+
+    #[test]
+    fn test_same_seed_is_reproducible() {
+        let first = jittered_market(42).generate(200, Timeframe::M1);
+        let second = jittered_market(42).generate(200, Timeframe::M1);
+        assert_eq!(first.ticks, second.ticks);
+        assert_eq!(first.missing_bars, second.missing_bars);
+
+        let other = jittered_market(43).generate(200, Timeframe::M1);
+        assert_ne!(first.ticks, other.ticks);
+    }
+
+    #[test]
+    fn test_known_gap_and_duplicate_counts() {
+        let series = jittered_market(42).generate(200, Timeframe::M1);
+
+        assert_eq!(series.missing_bars.len(), 13);
+        assert_eq!(series.duplicate_ticks, 39);
+
+        // Reported counts match what downstream consumers actually see
+        assert_eq!(count_duplicates(&series.ticks), series.duplicate_ticks);
+
+        let mut aggregator = TickToBarAggregator::new();
+        for tick in &series.ticks {
+            aggregator.process_tick(tick);
+        }
+        aggregator.flush();
+        let m1_bars = aggregator
+            .get_completed_bars()
+            .iter()
+            .filter(|b| b.timeframe == Timeframe::M1)
+            .count();
+        assert_eq!(m1_bars, 200 - series.missing_bars.len());
+        assert_eq!(series.ticks.len(), m1_bars * 12 + series.duplicate_ticks);
+    }
+}
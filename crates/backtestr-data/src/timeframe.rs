@@ -1,7 +1,10 @@
+use chrono::{DateTime, Datelike, Months, NaiveDate};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
 
+const MS_PER_DAY: i64 = 86_400_000;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Timeframe {
     M1,  // 1 minute
@@ -10,10 +13,23 @@ pub enum Timeframe {
     H1,  // 1 hour
     H4,  // 4 hours
     D1,  // 1 day
+    W1,  // 1 week
+    MN1, // 1 calendar month
+}
+
+/// First day of a `W1` bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
 }
 
 impl Timeframe {
-    /// Returns duration in milliseconds
+    /// Returns duration in milliseconds.
+    ///
+    /// `MN1` has no fixed length; this is a nominal 30 days. Use
+    /// `bar_start_timestamp`/`bar_end_timestamp` for month boundaries.
     pub fn duration_ms(&self) -> i64 {
         match self {
             Timeframe::M1 => 60_000,
@@ -21,7 +37,9 @@ impl Timeframe {
             Timeframe::M15 => 900_000,
             Timeframe::H1 => 3_600_000,
             Timeframe::H4 => 14_400_000,
-            Timeframe::D1 => 86_400_000,
+            Timeframe::D1 => MS_PER_DAY,
+            Timeframe::W1 => 7 * MS_PER_DAY,
+            Timeframe::MN1 => 30 * MS_PER_DAY,
         }
     }
 
@@ -39,6 +57,23 @@ impl Timeframe {
             Timeframe::H1 => "1h",
             Timeframe::H4 => "4h",
             Timeframe::D1 => "1d",
+            Timeframe::W1 => "1w",
+            Timeframe::MN1 => "1mo",
+        }
+    }
+
+    /// Strings accepted by `from_str` for this timeframe, case-insensitive.
+    /// The first alias is always `as_str`, so stored values parse back losslessly.
+    pub fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            Timeframe::M1 => &["1m", "m1", "1", "1min"],
+            Timeframe::M5 => &["5m", "m5", "5", "5min"],
+            Timeframe::M15 => &["15m", "m15", "15", "15min"],
+            Timeframe::H1 => &["1h", "h1", "60", "60m", "60min", "hourly"],
+            Timeframe::H4 => &["4h", "h4", "240", "240m", "240min"],
+            Timeframe::D1 => &["1d", "d1", "1440", "1440m", "d", "daily"],
+            Timeframe::W1 => &["1w", "w1", "10080", "w", "weekly"],
+            Timeframe::MN1 => &["1mo", "mn1", "mn", "monthly"],
         }
     }
 
@@ -51,24 +86,78 @@ impl Timeframe {
             Timeframe::H1,
             Timeframe::H4,
             Timeframe::D1,
+            Timeframe::W1,
+            Timeframe::MN1,
         ]
     }
 
-    /// Calculate the start timestamp for a bar given a tick timestamp
+    /// Calculate the start timestamp for a bar given a tick timestamp.
+    /// Weeks start on Monday.
     pub fn bar_start_timestamp(&self, tick_timestamp: i64) -> i64 {
-        let duration = self.duration_ms();
-        (tick_timestamp / duration) * duration
+        self.bar_start_timestamp_with(tick_timestamp, WeekStart::Monday)
+    }
+
+    /// Like `bar_start_timestamp` with `W1` bars opening on `week_start`
+    pub fn bar_start_timestamp_with(&self, tick_timestamp: i64, week_start: WeekStart) -> i64 {
+        match self {
+            Timeframe::W1 => {
+                let day = tick_timestamp.div_euclid(MS_PER_DAY);
+                // 1970-01-01 was a Thursday
+                let offset = match week_start {
+                    WeekStart::Monday => 3,
+                    WeekStart::Sunday => 4,
+                };
+                (day - (day + offset).rem_euclid(7)) * MS_PER_DAY
+            }
+            Timeframe::MN1 => month_start(tick_timestamp),
+            _ => {
+                let duration = self.duration_ms();
+                (tick_timestamp / duration) * duration
+            }
+        }
     }
 
     /// Calculate the end timestamp for a bar given its start timestamp
     pub fn bar_end_timestamp(&self, bar_start: i64) -> i64 {
-        bar_start + self.duration_ms()
+        match self {
+            // Months vary in length, so step by calendar month
+            Timeframe::MN1 => next_month_start(bar_start),
+            _ => bar_start + self.duration_ms(),
+        }
     }
 
     /// Check if a timestamp is at a bar boundary
     pub fn is_bar_boundary(&self, timestamp: i64) -> bool {
-        timestamp % self.duration_ms() == 0
+        self.is_bar_boundary_with(timestamp, WeekStart::Monday)
     }
+
+    /// Like `is_bar_boundary` with `W1` bars opening on `week_start`
+    pub fn is_bar_boundary_with(&self, timestamp: i64, week_start: WeekStart) -> bool {
+        self.bar_start_timestamp_with(timestamp, week_start) == timestamp
+    }
+}
+
+fn first_of_month(timestamp: i64) -> NaiveDate {
+    let date = DateTime::from_timestamp_millis(timestamp)
+        .map(|dt| dt.date_naive())
+        .unwrap_or_default();
+    date.with_day(1).unwrap_or(date)
+}
+
+fn month_start(timestamp: i64) -> i64 {
+    first_of_month(timestamp)
+        .and_hms_opt(0, 0, 0)
+        .map(|dt| dt.and_utc().timestamp_millis())
+        .unwrap_or(timestamp)
+}
+
+fn next_month_start(timestamp: i64) -> i64 {
+    let first = first_of_month(timestamp);
+    first
+        .checked_add_months(Months::new(1))
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp_millis())
+        .unwrap_or(timestamp + Timeframe::MN1.duration_ms())
 }
 
 impl fmt::Display for Timeframe {
@@ -81,15 +170,19 @@ impl FromStr for Timeframe {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "1m" | "m1" => Ok(Timeframe::M1),
-            "5m" | "m5" => Ok(Timeframe::M5),
-            "15m" | "m15" => Ok(Timeframe::M15),
-            "1h" | "h1" | "60m" => Ok(Timeframe::H1),
-            "4h" | "h4" | "240m" => Ok(Timeframe::H4),
-            "1d" | "d1" | "daily" => Ok(Timeframe::D1),
-            _ => Err(format!("Invalid timeframe: {}", s)),
-        }
+        let normalized = s.trim().to_lowercase();
+        Timeframe::all()
+            .into_iter()
+            .find(|tf| tf.aliases().contains(&normalized.as_str()))
+            .ok_or_else(|| {
+                let supported: Vec<String> =
+                    Timeframe::all().iter().map(|tf| tf.to_string()).collect();
+                format!(
+                    "Invalid timeframe: {} (supported: {})",
+                    s,
+                    supported.join(", ")
+                )
+            })
     }
 }
 
@@ -136,6 +229,34 @@ mod tests {
         assert!(Timeframe::from_str("invalid").is_err());
     }
 
+    #[test]
+    fn test_timeframe_broker_aliases() {
+        assert_eq!(Timeframe::from_str("1").unwrap(), Timeframe::M1);
+        assert_eq!(Timeframe::from_str("5min").unwrap(), Timeframe::M5);
+        assert_eq!(Timeframe::from_str("60").unwrap(), Timeframe::H1);
+        assert_eq!(Timeframe::from_str("240").unwrap(), Timeframe::H4);
+        assert_eq!(Timeframe::from_str("1440").unwrap(), Timeframe::D1);
+        assert_eq!(Timeframe::from_str(" H4 ").unwrap(), Timeframe::H4);
+
+        let err = Timeframe::from_str("2h").unwrap_err();
+        assert!(err.contains("2h"));
+        assert!(err.contains("supported"));
+    }
+
+    #[test]
+    fn test_timeframe_round_trip_is_lossless() {
+        let mut seen = std::collections::HashSet::new();
+        for tf in Timeframe::all() {
+            assert_eq!(tf.aliases()[0], tf.as_str());
+            assert_eq!(Timeframe::from_str(tf.as_str()).unwrap(), tf);
+            for alias in tf.aliases() {
+                // An alias must never be claimed by two timeframes
+                assert!(seen.insert(*alias), "duplicate alias {}", alias);
+                assert_eq!(Timeframe::from_str(alias).unwrap(), tf);
+            }
+        }
+    }
+
     #[test]
     fn test_bar_start_timestamp() {
         let tf = Timeframe::M1;
@@ -168,4 +289,54 @@ mod tests {
         assert!(tf.is_bar_boundary(1704067200000)); // Exactly on minute
         assert!(!tf.is_bar_boundary(1704067230000)); // 30 seconds into minute
     }
+
+    #[test]
+    fn test_weekly_bars() {
+        // Wednesday 2024-01-03 12:00 UTC
+        let wednesday = 1704283200000;
+        let monday = 1704067200000; // 2024-01-01
+        let sunday = 1703980800000; // 2023-12-31
+
+        assert_eq!(Timeframe::W1.bar_start_timestamp(wednesday), monday);
+        assert_eq!(
+            Timeframe::W1.bar_start_timestamp_with(wednesday, WeekStart::Sunday),
+            sunday
+        );
+        assert_eq!(
+            Timeframe::W1.bar_end_timestamp(monday),
+            monday + 7 * 86_400_000
+        );
+        assert!(Timeframe::W1.is_bar_boundary(monday));
+        assert!(!Timeframe::W1.is_bar_boundary(sunday));
+        assert!(Timeframe::W1.is_bar_boundary_with(sunday, WeekStart::Sunday));
+        assert!(!Timeframe::W1.is_bar_boundary_with(monday, WeekStart::Sunday));
+    }
+
+    #[test]
+    fn test_monthly_bars_use_calendar_months() {
+        let feb_1_2024 = 1706745600000;
+        let mar_1_2024 = 1709251200000;
+        let mid_feb = feb_1_2024 + 14 * 86_400_000;
+
+        assert_eq!(Timeframe::MN1.bar_start_timestamp(mid_feb), feb_1_2024);
+        // Leap-year February is 29 days, not the nominal 30
+        assert_eq!(Timeframe::MN1.bar_end_timestamp(feb_1_2024), mar_1_2024);
+        assert_eq!(mar_1_2024 - feb_1_2024, 29 * 86_400_000);
+        assert!(Timeframe::MN1.is_bar_boundary(mar_1_2024));
+        assert!(!Timeframe::MN1.is_bar_boundary(mid_feb));
+
+        // December rolls into the next year
+        let dec_1_2023 = 1701388800000;
+        assert_eq!(Timeframe::MN1.bar_end_timestamp(dec_1_2023), 1704067200000);
+    }
+
+    #[test]
+    fn test_weekly_monthly_parse() {
+        assert_eq!(Timeframe::from_str("W1").unwrap(), Timeframe::W1);
+        assert_eq!(Timeframe::from_str("MN1").unwrap(), Timeframe::MN1);
+        assert_eq!(Timeframe::from_str("monthly").unwrap(), Timeframe::MN1);
+        assert_eq!(Timeframe::from_str("1mo").unwrap(), Timeframe::MN1);
+        // "1M" stays minutes; months are "1mo"
+        assert_eq!(Timeframe::from_str("1M").unwrap(), Timeframe::M1);
+    }
 }
@@ -1,9 +1,14 @@
 use anyhow::{Context, Result};
-use backtestr_data::{CsvImporter, Database};
+use backtestr_core::aggregation::{SessionManager, VolumeAggregator, VolumeProfile};
+use backtestr_data::{
+    BarMaterializer, CsvImporter, Database, ExportFormat, Exporter, ParquetImporter, Tick,
+    Timeframe, ValidationConfig,
+};
 use chrono::{DateTime, Utc};
 use clap::{Parser, Subcommand};
 use comfy_table::{Cell, ContentArrangement, Table};
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
 #[derive(Parser)]
 #[command(name = "backtestr")]
@@ -27,11 +32,23 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Import tick data from CSV file
+    /// Import tick data from a CSV or Parquet file
     Import {
-        /// Path to CSV file
+        /// Path to CSV or Parquet (.parquet, .pq) file
         #[arg(short, long)]
         file: PathBuf,
+
+        /// Continue an interrupted CSV import from its last committed batch
+        #[arg(long)]
+        resume: bool,
+
+        /// Reject crossed or locked quotes (bid >= ask)
+        #[arg(long)]
+        reject_crossed: bool,
+
+        /// Reject ticks whose spread exceeds this fraction of the mid price (e.g. 0.01)
+        #[arg(long)]
+        max_spread: Option<f64>,
     },
 
     /// Query tick data
@@ -55,6 +72,128 @@ enum Commands {
         /// Output format
         #[arg(long, value_enum, default_value = "table")]
         format: OutputFormat,
+
+        /// Decimal places for prices (e.g. 3 for JPY pairs). Tables default
+        /// to 5; CSV and JSON keep full precision unless this is set.
+        #[arg(long)]
+        digits: Option<usize>,
+
+        /// Comma-separated columns to show
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            default_value = "symbol,timestamp,bid,ask,bid_size,ask_size"
+        )]
+        columns: Vec<Column>,
+    },
+
+    /// Export tick or bar data to a file
+    Export {
+        /// Symbol to export (e.g., EURUSD)
+        #[arg(short, long)]
+        symbol: String,
+
+        /// Start date (ISO format); defaults to the earliest data
+        #[arg(long)]
+        from: Option<String>,
+
+        /// End date (ISO format); defaults to now
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Output file format: csv, parquet or json
+        #[arg(long, default_value = "csv")]
+        format: ExportFormat,
+
+        /// Destination file
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Export bars of this timeframe (e.g. 1m, 1h) instead of ticks
+        #[arg(long)]
+        timeframe: Option<Timeframe>,
+    },
+
+    /// Aggregate stored ticks into bars and save them
+    Aggregate {
+        /// Symbol to aggregate (e.g., EURUSD)
+        #[arg(short, long)]
+        symbol: String,
+
+        /// Timeframe to build (e.g. 1m, 1h)
+        #[arg(long, required_unless_present = "all_timeframes")]
+        timeframe: Option<Timeframe>,
+
+        /// Build every supported timeframe
+        #[arg(long, conflicts_with = "timeframe")]
+        all_timeframes: bool,
+
+        /// Start date (ISO format); defaults to the earliest data
+        #[arg(long)]
+        from: Option<String>,
+
+        /// End date (ISO format); defaults to now
+        #[arg(long)]
+        to: Option<String>,
+    },
+
+    /// Report gaps in stored tick data
+    Gaps {
+        /// Symbol to audit (e.g., EURUSD)
+        #[arg(short, long)]
+        symbol: String,
+
+        /// Start date (ISO format); defaults to the earliest data
+        #[arg(long)]
+        from: Option<String>,
+
+        /// End date (ISO format); defaults to now
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Longest expected spacing between ticks, in milliseconds
+        #[arg(long, default_value = "60000")]
+        interval_ms: i64,
+
+        /// Also report gaps while the market is closed (weekends, holidays)
+        #[arg(long)]
+        include_closed: bool,
+    },
+
+    /// Show the volume profile of stored bars as a histogram
+    Profile {
+        /// Symbol to profile (e.g., EURUSD)
+        #[arg(short, long)]
+        symbol: String,
+
+        /// Bar timeframe to read (e.g. 1m, 1h)
+        #[arg(long, default_value = "1m")]
+        timeframe: Timeframe,
+
+        /// Start date (ISO format); defaults to the earliest data
+        #[arg(long)]
+        from: Option<String>,
+
+        /// End date (ISO format); defaults to now
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Number of price levels
+        #[arg(long, default_value = "24")]
+        levels: usize,
+
+        /// Percentage of volume inside the value area
+        #[arg(long, default_value = "70")]
+        value_area: f64,
+
+        /// Decimal places for prices (e.g. 3 for JPY pairs)
+        #[arg(long, default_value = "5")]
+        digits: usize,
+
+        /// Width of the longest histogram bar, in characters
+        #[arg(long, default_value = "50")]
+        width: usize,
     },
 
     /// Show database statistics
@@ -87,6 +226,81 @@ enum OutputFormat {
     Json,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+enum Column {
+    Symbol,
+    Timestamp,
+    Bid,
+    Ask,
+    #[value(name = "bid_size")]
+    BidSize,
+    #[value(name = "ask_size")]
+    AskSize,
+    /// Ask minus bid
+    Spread,
+    /// Midpoint of bid and ask
+    Mid,
+}
+
+impl Column {
+    fn header(self) -> &'static str {
+        match self {
+            Column::Symbol => "Symbol",
+            Column::Timestamp => "Timestamp",
+            Column::Bid => "Bid",
+            Column::Ask => "Ask",
+            Column::BidSize => "Bid Size",
+            Column::AskSize => "Ask Size",
+            Column::Spread => "Spread",
+            Column::Mid => "Mid",
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Column::Symbol => "symbol",
+            Column::Timestamp => "timestamp",
+            Column::Bid => "bid",
+            Column::Ask => "ask",
+            Column::BidSize => "bid_size",
+            Column::AskSize => "ask_size",
+            Column::Spread => "spread",
+            Column::Mid => "mid",
+        }
+    }
+
+    /// Raw value for CSV/JSON output; prices are rounded only when
+    /// `digits` is given
+    fn value(self, tick: &Tick, digits: Option<usize>) -> String {
+        let price = |price: f64| match digits {
+            Some(digits) => format!("{:.*}", digits, price),
+            None => price.to_string(),
+        };
+        match self {
+            Column::Symbol => tick.symbol.clone(),
+            Column::Timestamp => tick.timestamp.to_string(),
+            Column::Bid => price(tick.bid),
+            Column::Ask => price(tick.ask),
+            Column::BidSize => tick.bid_size.unwrap_or(0).to_string(),
+            Column::AskSize => tick.ask_size.unwrap_or(0).to_string(),
+            Column::Spread => price(tick.ask - tick.bid),
+            Column::Mid => price((tick.bid + tick.ask) / 2.0),
+        }
+    }
+
+    /// Human-readable value for table output
+    fn display(self, tick: &Tick, digits: usize) -> String {
+        match self {
+            Column::Timestamp => DateTime::from_timestamp_millis(tick.timestamp)
+                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_else(|| tick.timestamp.to_string()),
+            Column::BidSize => tick.bid_size.map_or("".to_string(), |s| s.to_string()),
+            Column::AskSize => tick.ask_size.map_or("".to_string(), |s| s.to_string()),
+            _ => self.value(tick, Some(digits)),
+        }
+    }
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
@@ -96,13 +310,28 @@ fn main() -> Result<()> {
     }
 
     match &cli.command {
-        Commands::Import { file } => handle_import(&cli, file),
+        Commands::Import {
+            file,
+            resume,
+            reject_crossed,
+            max_spread,
+        } => {
+            let mut validation = ValidationConfig::default().with_reject_crossed(*reject_crossed);
+            if let Some(ratio) = max_spread {
+                validation = validation
+                    .with_max_spread_ratio(*ratio)
+                    .context("Invalid --max-spread")?;
+            }
+            handle_import(&cli, file, *resume, validation)
+        }
         Commands::Query {
             symbol,
             from,
             to,
             limit,
             format,
+            digits,
+            columns,
         } => {
             let database = create_database(&cli)?;
             handle_query(
@@ -112,6 +341,87 @@ fn main() -> Result<()> {
                 to.clone(),
                 *limit,
                 format.clone(),
+                *digits,
+                columns,
+            )
+        }
+        Commands::Export {
+            symbol,
+            from,
+            to,
+            format,
+            output,
+            timeframe,
+        } => {
+            let database = create_database(&cli)?;
+            handle_export(
+                &database,
+                symbol,
+                from.as_deref(),
+                to.as_deref(),
+                *format,
+                output,
+                *timeframe,
+            )
+        }
+        Commands::Aggregate {
+            symbol,
+            timeframe,
+            all_timeframes,
+            from,
+            to,
+        } => {
+            let mut database = create_database(&cli)?;
+            let timeframes = match timeframe {
+                Some(timeframe) if !*all_timeframes => vec![*timeframe],
+                _ => Timeframe::all(),
+            };
+            handle_aggregate(
+                &mut database,
+                symbol,
+                &timeframes,
+                from.as_deref(),
+                to.as_deref(),
+            )
+        }
+        Commands::Gaps {
+            symbol,
+            from,
+            to,
+            interval_ms,
+            include_closed,
+        } => {
+            let database = create_database(&cli)?;
+            handle_gaps(
+                &database,
+                symbol,
+                from.as_deref(),
+                to.as_deref(),
+                *interval_ms,
+                *include_closed,
+            )
+        }
+        Commands::Profile {
+            symbol,
+            timeframe,
+            from,
+            to,
+            levels,
+            value_area,
+            digits,
+            width,
+        } => {
+            let database = create_database(&cli)?;
+            handle_profile(
+                &database,
+                symbol,
+                *timeframe,
+                from.as_deref(),
+                to.as_deref(),
+                *levels,
+                *value_area,
+                *digits,
+                *width,
             )
         }
         Commands::Stats => {
@@ -144,7 +454,7 @@ fn create_database(cli: &Cli) -> Result<Database> {
     }
 }
 
-fn handle_import(cli: &Cli, file: &Path) -> Result<()> {
+fn handle_import(cli: &Cli, file: &Path, resume: bool, validation: ValidationConfig) -> Result<()> {
     println!("Importing data from: {}", file.display());
 
     // Create a fresh database connection for the importer
@@ -154,15 +464,42 @@ fn handle_import(cli: &Cli, file: &Path) -> Result<()> {
         Database::new_file(&cli.db)?
     };
 
-    let mut importer = CsvImporter::new(database);
-    let summary = importer
-        .import_file(file)
-        .context("Failed to import CSV file")?;
+    let summary = if is_parquet(file) {
+        ParquetImporter::new(database)
+            .with_validation(validation)
+            .import_file(file)
+            .context("Failed to import Parquet file")?
+    } else {
+        let reported = Rc::new(std::cell::Cell::new(false));
+        let progress_reported = reported.clone();
+        let result = CsvImporter::new(database)
+            .with_resume(resume)
+            .with_validation(validation)
+            .with_progress(Box::new(move |rows| {
+                progress_reported.set(true);
+                eprint!("\r  Processed {} rows...", rows);
+            }))
+            .import_file(file);
+        // End the progress line, even when the import failed part way
+        if reported.get() {
+            eprintln!();
+        }
+        result.context("Failed to import CSV file")?
+    };
 
     println!("\n📊 Import Summary:");
+    if summary.rows_resumed > 0 {
+        println!("  Resumed after: {} rows", summary.rows_resumed);
+    }
     println!("  Total rows: {}", summary.total_rows);
     println!("  Imported: {}", summary.rows_imported);
     println!("  Skipped: {}", summary.rows_skipped);
+    for (reason, count) in &summary.rejections {
+        println!("    {}: {}", reason, count);
+    }
+    if summary.rows_deduplicated > 0 {
+        println!("  Duplicates dropped: {}", summary.rows_deduplicated);
+    }
     println!("  Success rate: {:.1}%", summary.success_rate());
     println!("  Duration: {:?}", summary.duration);
 
@@ -176,6 +513,13 @@ fn handle_import(cli: &Cli, file: &Path) -> Result<()> {
     Ok(())
 }
 
+fn is_parquet(file: &Path) -> bool {
+    file.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("parquet") || ext.eq_ignore_ascii_case("pq"))
+}
+
+#[allow(clippy::too_many_arguments)]
 fn handle_query(
     database: &Database,
     symbol: &str,
@@ -183,18 +527,20 @@ fn handle_query(
     to: Option<String>,
     limit: usize,
     format: OutputFormat,
+    digits: Option<usize>,
+    columns: &[Column],
 ) -> Result<()> {
     // Parse dates
     let start =
         parse_date(from.as_deref()).unwrap_or_else(|_| Utc::now() - chrono::Duration::days(30));
     let end = parse_date(to.as_deref()).unwrap_or_else(|_| Utc::now());
 
-    // Query ticks
-    let ticks = database
-        .query_ticks(symbol, start, end)
+    // Query only the requested page so --limit bounds the database work
+    let page = database
+        .query_ticks_paged(symbol, start, end, limit, 0)
         .context("Failed to query ticks")?;
 
-    if ticks.is_empty() {
+    if page.items.is_empty() {
         println!(
             "No ticks found for {} between {} and {}",
             symbol, start, end
@@ -202,81 +548,291 @@ fn handle_query(
         return Ok(());
     }
 
-    // Limit results
-    let ticks: Vec<_> = ticks.into_iter().take(limit).collect();
+    println!("{}", render_ticks(&page.items, &format, digits, columns));
+    if let OutputFormat::Table = format {
+        println!(
+            "\nShowing {} of {} total results",
+            page.items.len(),
+            page.total
+        );
+    }
 
-    // Format output
+    Ok(())
+}
+
+/// Price decimals in table output when `--digits` is not given
+const TABLE_DIGITS: usize = 5;
+
+fn render_ticks(
+    ticks: &[Tick],
+    format: &OutputFormat,
+    digits: Option<usize>,
+    columns: &[Column],
+) -> String {
     match format {
         OutputFormat::Table => {
+            let digits = digits.unwrap_or(TABLE_DIGITS);
             let mut table = Table::new();
             table
                 .set_content_arrangement(ContentArrangement::Dynamic)
-                .set_header(vec![
-                    "Symbol",
-                    "Timestamp",
-                    "Bid",
-                    "Ask",
-                    "Bid Size",
-                    "Ask Size",
-                ]);
-
-            for tick in &ticks {
-                let timestamp = DateTime::from_timestamp_millis(tick.timestamp)
-                    .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
-                    .unwrap_or_else(|| tick.timestamp.to_string());
-
-                table.add_row(vec![
-                    Cell::new(&tick.symbol),
-                    Cell::new(timestamp),
-                    Cell::new(format!("{:.5}", tick.bid)),
-                    Cell::new(format!("{:.5}", tick.ask)),
-                    Cell::new(tick.bid_size.map_or("".to_string(), |s| s.to_string())),
-                    Cell::new(tick.ask_size.map_or("".to_string(), |s| s.to_string())),
-                ]);
+                .set_header(columns.iter().map(|c| c.header()));
+
+            for tick in ticks {
+                table.add_row(
+                    columns
+                        .iter()
+                        .map(|c| Cell::new(c.display(tick, digits)))
+                        .collect::<Vec<_>>(),
+                );
             }
 
-            println!("{table}");
-            println!("\nShowing {} of {} total results", ticks.len(), ticks.len());
+            table.to_string()
         }
         OutputFormat::Csv => {
-            println!("symbol,timestamp,bid,ask,bid_size,ask_size");
-            for tick in &ticks {
-                println!(
-                    "{},{},{},{},{},{}",
-                    tick.symbol,
-                    tick.timestamp,
-                    tick.bid,
-                    tick.ask,
-                    tick.bid_size.unwrap_or(0),
-                    tick.ask_size.unwrap_or(0)
+            let mut lines = vec![columns
+                .iter()
+                .map(|c| c.key())
+                .collect::<Vec<_>>()
+                .join(",")];
+            for tick in ticks {
+                lines.push(
+                    columns
+                        .iter()
+                        .map(|c| c.value(tick, digits))
+                        .collect::<Vec<_>>()
+                        .join(","),
                 );
             }
+            lines.join("\n")
         }
         OutputFormat::Json => {
-            // For now, print a simple JSON-like format
-            // serde_json is already in workspace dependencies
-            println!("[");
+            // For now, build a simple JSON-like format by hand
+            let mut lines = vec!["[".to_string()];
             for (i, tick) in ticks.iter().enumerate() {
-                println!("  {{");
-                println!("    \"symbol\": \"{}\",", tick.symbol);
-                println!("    \"timestamp\": {},", tick.timestamp);
-                println!("    \"bid\": {},", tick.bid);
-                println!("    \"ask\": {},", tick.ask);
-                println!("    \"bid_size\": {},", tick.bid_size.unwrap_or(0));
-                println!("    \"ask_size\": {}", tick.ask_size.unwrap_or(0));
-                if i < ticks.len() - 1 {
-                    println!("  }},");
-                } else {
-                    println!("  }}");
+                lines.push("  {".to_string());
+                for (j, column) in columns.iter().enumerate() {
+                    let value = column.value(tick, digits);
+                    let value = match column {
+                        Column::Symbol => format!("\"{}\"", value),
+                        _ => value,
+                    };
+                    let separator = if j < columns.len() - 1 { "," } else { "" };
+                    lines.push(format!("    \"{}\": {}{}", column.key(), value, separator));
                 }
+                lines.push(if i < ticks.len() - 1 { "  }," } else { "  }" }.to_string());
             }
-            println!("]");
+            lines.push("]".to_string());
+            lines.join("\n")
         }
     }
+}
+
+fn handle_export(
+    database: &Database,
+    symbol: &str,
+    from: Option<&str>,
+    to: Option<&str>,
+    format: ExportFormat,
+    output: &Path,
+    timeframe: Option<Timeframe>,
+) -> Result<()> {
+    let (start, end) = parse_range(from, to)?;
+
+    let exporter = Exporter::new(database);
+    let summary = match timeframe {
+        Some(timeframe) => exporter.export_bars(symbol, timeframe, start, end, format, output),
+        None => exporter.export_ticks(symbol, start, end, format, output),
+    }
+    .context("Failed to export data")?;
+
+    println!("📦 Export Summary:");
+    println!("  Rows exported: {}", summary.rows_exported);
+    println!("  Output: {}", summary.file_path.display());
+    println!("  Duration: {:?}", summary.duration);
+
+    Ok(())
+}
+
+fn handle_aggregate(
+    database: &mut Database,
+    symbol: &str,
+    timeframes: &[Timeframe],
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<()> {
+    let (start, end) = parse_range(from, to)?;
+
+    let summary = BarMaterializer::new(database)
+        .run(symbol, timeframes, start, end)
+        .context("Failed to aggregate ticks")?;
+
+    println!("📊 Aggregation Summary:");
+    println!("  Ticks processed: {}", summary.ticks_processed);
+    for (timeframe, count) in &summary.bars_written {
+        println!("  {} bars: {}", timeframe, count);
+    }
+    println!("  Total bars: {}", summary.total_bars());
+    println!("  Duration: {:?}", summary.duration);
+
+    Ok(())
+}
+
+fn handle_gaps(
+    database: &Database,
+    symbol: &str,
+    from: Option<&str>,
+    to: Option<&str>,
+    interval_ms: i64,
+    include_closed: bool,
+) -> Result<()> {
+    let (start, end) = parse_range(from, to)?;
+    let gaps = find_gaps(database, symbol, start, end, interval_ms, include_closed)?;
+
+    println!("📊 Gap Report for {}:", symbol);
+    for &(prev, next) in &gaps {
+        println!(
+            "  {} -> {} ({:?})",
+            format_millis(prev),
+            format_millis(next),
+            std::time::Duration::from_millis((next - prev) as u64)
+        );
+    }
+
+    println!("  Gaps found: {}", gaps.len());
+    println!(
+        "  Total missing: {:?}",
+        std::time::Duration::from_millis(missing_ms(symbol, &gaps, include_closed) as u64)
+    );
+
+    Ok(())
+}
+
+/// Length of `gaps` in total. Unless `include_closed` is set, only the time
+/// the market was open counts, as in `find_gaps`.
+fn missing_ms(symbol: &str, gaps: &[(i64, i64)], include_closed: bool) -> i64 {
+    let sessions = SessionManager::new();
+    gaps.iter()
+        .map(|&(prev, next)| {
+            if include_closed {
+                next - prev
+            } else {
+                sessions.trading_time_between(symbol, prev, next)
+            }
+        })
+        .sum()
+}
+
+/// Tick gaps longer than `interval_ms`. Unless `include_closed` is set, only
+/// the part of a gap while the market is open counts against the interval.
+fn find_gaps(
+    database: &Database,
+    symbol: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    interval_ms: i64,
+    include_closed: bool,
+) -> Result<Vec<(i64, i64)>> {
+    // Subtract the closed-market time that overlaps the gap, by the
+    // symbol's session hours in its own timezone
+    let sessions = SessionManager::new();
+    let market_closed =
+        |prev: i64, next: i64| sessions.trading_time_between(symbol, prev, next) <= interval_ms;
+    let is_expected: Option<&dyn Fn(i64, i64) -> bool> = if include_closed {
+        None
+    } else {
+        Some(&market_closed)
+    };
+
+    database
+        .find_tick_gaps(symbol, interval_ms, start, end, is_expected)
+        .context("Failed to scan ticks for gaps")
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_profile(
+    database: &Database,
+    symbol: &str,
+    timeframe: Timeframe,
+    from: Option<&str>,
+    to: Option<&str>,
+    levels: usize,
+    value_area: f64,
+    digits: usize,
+    width: usize,
+) -> Result<()> {
+    anyhow::ensure!(levels > 0, "--levels must be at least 1");
+    anyhow::ensure!(
+        value_area > 0.0 && value_area <= 100.0,
+        "--value-area must be above 0 and at most 100"
+    );
+    let (start, end) = parse_range(from, to)?;
+
+    let profile = VolumeAggregator::new()
+        .query_volume_profile(database, symbol, timeframe, start, end, levels)
+        .context("Failed to build volume profile")?;
+
+    if profile.levels.is_empty() {
+        println!(
+            "No {} {} bars with a price range in this period",
+            symbol, timeframe
+        );
+        return Ok(());
+    }
 
+    println!("📊 Volume Profile for {} ({}):", symbol, timeframe);
+    println!("{}", render_profile(&profile, value_area, digits, width));
     Ok(())
 }
 
+/// ASCII histogram with the highest price on top. Levels inside the value
+/// area are drawn with `#`, the rest with `-`, and the POC row is flagged.
+fn render_profile(profile: &VolumeProfile, value_area: f64, digits: usize, width: usize) -> String {
+    let (va_low, va_high) = profile.get_value_area(value_area);
+    let max_volume = profile.levels.iter().map(|l| l.volume).max().unwrap_or(0);
+
+    let mut lines = vec![
+        format!("  POC: {:.*}", digits, profile.poc),
+        format!(
+            "  Value area ({}%): {:.*} - {:.*}",
+            value_area, digits, va_low, digits, va_high
+        ),
+    ];
+
+    for level in profile.levels.iter().rev() {
+        let length = if max_volume > 0 {
+            (level.volume as f64 / max_volume as f64 * width as f64).round() as usize
+        } else {
+            0
+        };
+        let fill = if level.price >= va_low && level.price <= va_high {
+            "#"
+        } else {
+            "-"
+        };
+        let poc = if level.volume > 0 && level.price == profile.poc {
+            "  <- POC"
+        } else {
+            ""
+        };
+        lines.push(format!(
+            "  {:>12.*} |{:<width$}| {}{}",
+            digits,
+            level.price,
+            fill.repeat(length),
+            level.volume,
+            poc,
+        ));
+    }
+
+    lines.join("\n")
+}
+
+fn format_millis(timestamp: i64) -> String {
+    DateTime::from_timestamp_millis(timestamp)
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
 fn handle_stats(database: &Database) -> Result<()> {
     let total_ticks = database.count_ticks()?;
 
@@ -320,6 +876,19 @@ fn handle_delete(
     Ok(())
 }
 
+/// Optional `--from`/`--to` bounds, open-ended when omitted
+fn parse_range(from: Option<&str>, to: Option<&str>) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+    let start = from
+        .map(|date| parse_date(Some(date)))
+        .transpose()?
+        .unwrap_or(DateTime::UNIX_EPOCH);
+    let end = to
+        .map(|date| parse_date(Some(date)))
+        .transpose()?
+        .unwrap_or_else(Utc::now);
+    Ok((start, end))
+}
+
 fn parse_date(date_str: Option<&str>) -> Result<DateTime<Utc>> {
     if let Some(date) = date_str {
         // Try parsing as full ISO 8601
@@ -358,6 +927,258 @@ mod tests {
         assert_eq!(date.hour(), 0);
     }
 
+    fn jpy_tick() -> Tick {
+        Tick::new_with_millis("USDJPY".to_string(), 1704067200000, 149.250, 149.262)
+    }
+
+    fn query_args(args: &[&str]) -> (OutputFormat, Option<usize>, Vec<Column>) {
+        let cli = Cli::try_parse_from(
+            ["backtestr", "query", "--symbol", "USDJPY"]
+                .iter()
+                .chain(args.iter()),
+        )
+        .unwrap();
+        match cli.command {
+            Commands::Query {
+                format,
+                digits,
+                columns,
+                ..
+            } => (format, digits, columns),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_query_digits_and_columns() {
+        let (_, digits, columns) = query_args(&["--digits", "3", "--columns", "symbol,mid,spread"]);
+        assert_eq!(digits, Some(3));
+        assert_eq!(columns, vec![Column::Symbol, Column::Mid, Column::Spread]);
+
+        let ticks = vec![jpy_tick()];
+
+        let csv = render_ticks(&ticks, &OutputFormat::Csv, digits, &columns);
+        assert_eq!(csv, "symbol,mid,spread\nUSDJPY,149.256,0.012");
+
+        let table = render_ticks(&ticks, &OutputFormat::Table, digits, &columns);
+        assert!(table.contains("Mid"));
+        assert!(table.contains("149.256"));
+        assert!(table.contains("0.012"));
+        assert!(!table.contains("Bid"));
+
+        let json = render_ticks(&ticks, &OutputFormat::Json, digits, &columns);
+        assert!(json.contains("\"symbol\": \"USDJPY\","));
+        assert!(json.contains("\"mid\": 149.256,"));
+        assert!(json.contains("\"spread\": 0.012\n"));
+    }
+
+    #[test]
+    fn test_query_default_columns() {
+        let (format, digits, columns) = query_args(&[]);
+        assert!(matches!(format, OutputFormat::Table));
+        assert_eq!(digits, None);
+        assert_eq!(columns.len(), 6);
+
+        // Without --digits, CSV and JSON keep full precision
+        let csv = render_ticks(&[jpy_tick()], &OutputFormat::Csv, digits, &columns);
+        assert_eq!(
+            csv,
+            "symbol,timestamp,bid,ask,bid_size,ask_size\nUSDJPY,1704067200000,149.25,149.262,0,0"
+        );
+        let json = render_ticks(&[jpy_tick()], &OutputFormat::Json, digits, &columns);
+        assert!(json.contains("\"ask\": 149.262,"));
+
+        // Tables still round to five places
+        let table = render_ticks(&[jpy_tick()], &OutputFormat::Table, digits, &columns);
+        assert!(table.contains("149.26200"));
+    }
+
+    #[test]
+    fn test_import_file_type_detection() {
+        assert!(is_parquet(Path::new("ticks/2024.parquet")));
+        assert!(is_parquet(Path::new("ticks.PQ")));
+        assert!(!is_parquet(Path::new("ticks.csv")));
+        assert!(!is_parquet(Path::new("parquet")));
+    }
+
+    #[test]
+    fn test_export_args() {
+        let cli = Cli::try_parse_from([
+            "backtestr",
+            "export",
+            "--symbol",
+            "EURUSD",
+            "--format",
+            "parquet",
+            "--output",
+            "out.parquet",
+            "--timeframe",
+            "1h",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Export {
+                format,
+                output,
+                timeframe,
+                from,
+                ..
+            } => {
+                assert_eq!(format, ExportFormat::Parquet);
+                assert_eq!(output, PathBuf::from("out.parquet"));
+                assert_eq!(timeframe, Some(Timeframe::H1));
+                assert!(from.is_none());
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_aggregate_requires_a_timeframe_choice() {
+        let parse = |args: &[&str]| {
+            Cli::try_parse_from(
+                ["backtestr", "aggregate", "--symbol", "EURUSD"]
+                    .iter()
+                    .chain(args.iter()),
+            )
+        };
+
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["--timeframe", "1h", "--all-timeframes"]).is_err());
+        assert!(parse(&["--all-timeframes"]).is_ok());
+        match parse(&["--timeframe", "5m"]).unwrap().command {
+            Commands::Aggregate { timeframe, .. } => assert_eq!(timeframe, Some(Timeframe::M5)),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_gaps_args() {
+        let cli = Cli::try_parse_from([
+            "backtestr",
+            "gaps",
+            "--symbol",
+            "EURUSD",
+            "--interval-ms",
+            "5000",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Gaps {
+                symbol,
+                interval_ms,
+                include_closed,
+                ..
+            } => {
+                assert_eq!(symbol, "EURUSD");
+                assert_eq!(interval_ms, 5000);
+                assert!(!include_closed);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_gaps_keep_friday_session_and_skip_weekend() {
+        let database = Database::new_memory().unwrap();
+        // Friday 2024-03-08 trades until 17:00 EST, 22:00 UTC
+        let ticks: Vec<Tick> = [
+            1709917200000, // Fri 17:00 UTC
+            1709935198000, // Fri 21:59:58 UTC
+            1710108005000, // Sun 22:00:05 UTC
+        ]
+        .iter()
+        .map(|&timestamp| Tick::new_with_millis("EURUSD".to_string(), timestamp, 1.0920, 1.0922))
+        .collect();
+        database.insert_ticks(&ticks).unwrap();
+
+        let (start, end) = parse_range(Some("2024-03-08"), Some("2024-03-11")).unwrap();
+        let gaps = find_gaps(&database, "EURUSD", start, end, 5000, false).unwrap();
+        assert_eq!(gaps, vec![(1709917200000, 1709935198000)]);
+
+        let all = find_gaps(&database, "EURUSD", start, end, 5000, true).unwrap();
+        assert_eq!(all.len(), 2);
+    }
+
+    #[test]
+    fn test_gaps_count_open_time_around_a_closure() {
+        let database = Database::new_memory().unwrap();
+        let ticks: Vec<Tick> = [
+            1709928000000, // Fri 2024-03-08 20:00 UTC, two hours before the close
+            1710108005000, // Sun 22:00:05 UTC, market still closed
+            1710540000000, // Sat 2024-03-16 22:00 UTC, a whole week later
+            1710541000000, // Sat 22:16:40 UTC
+        ]
+        .iter()
+        .map(|&timestamp| Tick::new_with_millis("EURUSD".to_string(), timestamp, 1.0920, 1.0922))
+        .collect();
+        database.insert_ticks(&ticks).unwrap();
+
+        let (start, end) = parse_range(Some("2024-03-08"), Some("2024-03-17")).unwrap();
+        let gaps = find_gaps(&database, "EURUSD", start, end, 5000, false).unwrap();
+        // The Friday session before the close and the trading week between
+        // two closed weekends are missing; the gap inside one weekend is not
+        assert_eq!(
+            gaps,
+            vec![
+                (1709928000000, 1710108005000),
+                (1710108005000, 1710540000000)
+            ]
+        );
+        // Two hours on Friday, then Monday 00:00 to Friday 17:00 New York
+        // time, which is on summer time by then
+        assert_eq!(missing_ms("EURUSD", &gaps, false), (2 + 113) * 3_600_000);
+        assert_eq!(
+            missing_ms("EURUSD", &gaps, true),
+            1710540000000 - 1709928000000
+        );
+    }
+
+    #[test]
+    fn test_profile_args_and_rendering() {
+        let cli = Cli::try_parse_from([
+            "backtestr",
+            "profile",
+            "--symbol",
+            "EURUSD",
+            "--timeframe",
+            "1h",
+            "--value-area",
+            "80",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Profile {
+                timeframe,
+                levels,
+                value_area,
+                width,
+                ..
+            } => {
+                assert_eq!(timeframe, Timeframe::H1);
+                assert_eq!(levels, 24);
+                assert_eq!(value_area, 80.0);
+                assert_eq!(width, 50);
+            }
+            _ => unreachable!(),
+        }
+
+        let mut profile = VolumeProfile::with_price_range(1.0, 1.3, 3);
+        for (level, volume) in profile.levels.iter_mut().zip([100, 400, 200]) {
+            level.volume = volume;
+        }
+        profile.update_poc();
+
+        let rendered = render_profile(&profile, 70.0, 2, 8);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "  POC: 1.15");
+        assert_eq!(lines[1], "  Value area (70%): 1.15 - 1.25");
+        // Highest price first; the lowest level falls outside the value area
+        assert_eq!(lines[2], "          1.25 |####    | 200");
+        assert_eq!(lines[3], "          1.15 |########| 400  <- POC");
+        assert_eq!(lines[4], "          1.05 |--      | 100");
+    }
+
     #[test]
     fn verify_cli() {
         use clap::CommandFactory;
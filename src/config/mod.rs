@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use backtestr_core::mtf::{MTFConfig, TickBufferPolicy};
 use serde::{Deserialize, Serialize};
 use std::env;
 use std::path::PathBuf;
@@ -31,6 +32,7 @@ pub struct DatabaseConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EngineConfig {
     pub tick_buffer_size: usize,
+    pub tick_buffer_policy: TickBufferPolicy,
     pub max_parallel_algorithms: usize,
     pub python_threads: usize,
 }
@@ -90,6 +92,16 @@ impl Config {
         Ok(config)
     }
 
+    /// MTF engine settings derived from this configuration
+    pub fn mtf_config(&self) -> MTFConfig {
+        MTFConfig {
+            tick_buffer_size: self.engine.tick_buffer_size,
+            tick_buffer_policy: self.engine.tick_buffer_policy,
+            check_tick_order: self.features.debug_mode,
+            ..Default::default()
+        }
+    }
+
     fn parse_environment() -> Result<Environment> {
         match env::var("NODE_ENV").as_deref() {
             Ok("production") => Ok(Environment::Production),
@@ -117,6 +129,11 @@ impl Config {
                 .unwrap_or_else(|_| "100000".to_string())
                 .parse()
                 .context("Invalid ENGINE_TICK_BUFFER_SIZE")?,
+            tick_buffer_policy: env::var("ENGINE_TICK_BUFFER_POLICY")
+                .unwrap_or_else(|_| "block".to_string())
+                .parse()
+                .map_err(anyhow::Error::msg)
+                .context("Invalid ENGINE_TICK_BUFFER_POLICY")?,
             max_parallel_algorithms: env::var("ENGINE_MAX_PARALLEL_ALGORITHMS")
                 .unwrap_or_else(|_| "4".to_string())
                 .parse()
@@ -189,13 +206,22 @@ impl Config {
             anyhow::bail!("IPC port must be >= 1024");
         }
 
+        self.engine.validate()
+    }
+}
+
+impl EngineConfig {
+    fn validate(&self) -> Result<()> {
         // Validate buffer sizes
-        if self.engine.tick_buffer_size == 0 {
+        if self.tick_buffer_size == 0 {
+            if self.tick_buffer_policy == TickBufferPolicy::Block {
+                anyhow::bail!("Tick buffer size must be > 0 with the Block policy");
+            }
             anyhow::bail!("Tick buffer size must be > 0");
         }
 
         // Validate thread counts
-        if self.engine.max_parallel_algorithms == 0 {
+        if self.max_parallel_algorithms == 0 {
             anyhow::bail!("Max parallel algorithms must be > 0");
         }
 
@@ -217,4 +243,22 @@ mod tests {
         let config = Config::load();
         assert!(config.is_ok());
     }
+
+    #[test]
+    fn test_zero_tick_buffer_with_block_rejected() {
+        let engine = EngineConfig {
+            tick_buffer_size: 0,
+            tick_buffer_policy: TickBufferPolicy::Block,
+            max_parallel_algorithms: 4,
+            python_threads: 2,
+        };
+        let err = engine.validate().unwrap_err();
+        assert!(err.to_string().contains("Block"));
+
+        let engine = EngineConfig {
+            tick_buffer_size: 1000,
+            ..engine
+        };
+        assert!(engine.validate().is_ok());
+    }
 }
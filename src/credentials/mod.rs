@@ -4,6 +4,17 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::env;
+#[cfg(feature = "keyring_store")]
+use std::sync::Mutex;
+
+/// Typed failures of credential store backends
+#[derive(Debug, thiserror::Error)]
+pub enum CredentialStoreError {
+    #[error("The {backend} credential store does not support listing credentials")]
+    ListNotSupported { backend: &'static str },
+    #[error("The {0} credential store is not compiled in")]
+    BackendUnavailable(&'static str),
+}
 
 /// Credential storage trait for different backends
 pub trait CredentialStore {
@@ -34,6 +45,10 @@ impl CredentialsManager {
         match store_type.as_str() {
             "env" => Ok(Box::new(EnvironmentStore::new())),
             "windows_credential_manager" => Ok(Box::new(WindowsCredentialStore::new())),
+            #[cfg(feature = "keyring_store")]
+            "keyring" => Ok(Box::new(KeyringCredentialStore::new())),
+            #[cfg(not(feature = "keyring_store"))]
+            "keyring" => Err(CredentialStoreError::BackendUnavailable("keyring").into()),
             _ => Ok(Box::new(EnvironmentStore::new())),
         }
     }
@@ -145,6 +160,89 @@ impl CredentialStore for WindowsCredentialStore {
     }
 }
 
+/// OS keychain store (macOS Keychain, Linux Secret Service)
+#[cfg(feature = "keyring_store")]
+pub struct KeyringCredentialStore {
+    service: String,
+    builder: Option<Box<keyring::CredentialBuilder>>,
+    // Entries are reused so every operation on a key hits the same credential
+    entries: Mutex<HashMap<String, keyring::Entry>>,
+}
+
+#[cfg(feature = "keyring_store")]
+impl KeyringCredentialStore {
+    pub const DEFAULT_SERVICE: &'static str = "backtestr-ai";
+
+    pub fn new() -> Self {
+        Self {
+            service: Self::DEFAULT_SERVICE.to_string(),
+            builder: None,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_service(mut self, service: &str) -> Self {
+        self.service = service.to_string();
+        self
+    }
+
+    /// Build entries with `builder` instead of the platform default
+    pub fn with_credential_builder(mut self, builder: Box<keyring::CredentialBuilder>) -> Self {
+        self.builder = Some(builder);
+        self
+    }
+
+    fn with_entry<T>(
+        &self,
+        key: &str,
+        f: impl FnOnce(&keyring::Entry) -> keyring::Result<T>,
+    ) -> Result<T> {
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Keyring entry cache poisoned"))?;
+
+        if !entries.contains_key(key) {
+            let entry = match &self.builder {
+                Some(builder) => {
+                    keyring::Entry::new_with_credential(builder.build(None, &self.service, key)?)
+                }
+                None => keyring::Entry::new(&self.service, key)?,
+            };
+            entries.insert(key.to_string(), entry);
+        }
+
+        Ok(f(&entries[key])?)
+    }
+}
+
+#[cfg(feature = "keyring_store")]
+impl CredentialStore for KeyringCredentialStore {
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        self.with_entry(key, |entry| match entry.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e),
+        })
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<()> {
+        self.with_entry(key, |entry| entry.set_password(value))
+    }
+
+    fn delete(&self, key: &str) -> Result<()> {
+        self.with_entry(key, |entry| match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e),
+        })
+    }
+
+    fn list(&self) -> Result<Vec<String>> {
+        // Keychains can't be enumerated portably
+        Err(CredentialStoreError::ListNotSupported { backend: "keyring" }.into())
+    }
+}
+
 /// Credential types used by the application
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BrokerCredentials {
@@ -232,4 +330,37 @@ mod tests {
         // Cleanup
         manager.delete_credential("TEST_CRED").unwrap();
     }
+
+    #[cfg(feature = "keyring_store")]
+    #[test]
+    fn test_keyring_store_round_trip() {
+        let store = KeyringCredentialStore::new()
+            .with_credential_builder(keyring::mock::default_credential_builder());
+
+        assert_eq!(store.get("BROKER_API_KEY").unwrap(), None);
+
+        store.set("BROKER_API_KEY", "secret").unwrap();
+        assert_eq!(
+            store.get("BROKER_API_KEY").unwrap(),
+            Some("secret".to_string())
+        );
+
+        store.delete("BROKER_API_KEY").unwrap();
+        assert_eq!(store.get("BROKER_API_KEY").unwrap(), None);
+        // Deleting a missing credential is not an error
+        store.delete("BROKER_API_KEY").unwrap();
+    }
+
+    #[cfg(feature = "keyring_store")]
+    #[test]
+    fn test_keyring_store_list_not_supported() {
+        let store = KeyringCredentialStore::new()
+            .with_credential_builder(keyring::mock::default_credential_builder());
+
+        let err = store.list().unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<CredentialStoreError>(),
+            Some(CredentialStoreError::ListNotSupported { backend: "keyring" })
+        ));
+    }
 }
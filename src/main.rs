@@ -1,7 +1,7 @@
 mod config;
 
-// Epic 2: Credentials (deferred)
-#[cfg(feature = "epic_2")]
+// Epic 2: Credentials (deferred); also built for the keyring store alone
+#[cfg(any(feature = "epic_2", feature = "keyring_store"))]
 mod credentials;
 
 use anyhow::Result;
@@ -16,6 +16,12 @@ fn main() -> Result<()> {
     let config = config::Config::load()?;
     info!("Configuration loaded: {:?}", config.environment);
 
+    let mtf_config = config.mtf_config();
+    info!(
+        "Tick buffer: {} ({:?}), tick order checks: {}",
+        mtf_config.tick_buffer_size, mtf_config.tick_buffer_policy, mtf_config.check_tick_order
+    );
+
     println!("BackTestr AI - Multi-Timeframe Forex Backtesting Platform");
     println!("Version: 0.1.0");
     println!("Environment: {:?}", config.environment);